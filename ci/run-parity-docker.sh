@@ -36,6 +36,9 @@ docker run --rm ${DOCKER_TTY_FLAGS:+${DOCKER_TTY_FLAGS[@]}} --platform "${PLATFO
 	-e CARGO_HOME=/cargo-home \
 	-e CARGO_TARGET_DIR=/cargo-target \
 	-e HEADLAMP_PARITY_DUMP_ROOT=/work/ci-dumps \
+	-e HEADLAMP_PARITY_BLESS \
+	-e HEADLAMP_PARITY_RETRIES \
+	-e HEADLAMP_PARITY_WORKTREE_METRICS \
 	-e RUSTC_WRAPPER=sccache \
 	-e SCCACHE_DIR=/sccache \
 	-e SCCACHE_CACHE_SIZE=20G \
@@ -12,6 +12,29 @@ const print = (payload) => {
   } catch {}
 };
 
+// Bridge protocol v2: NDJSON events on the fd headlamp passes via HEADLAMP_EVENTS_FD.
+// Without it (older headlamp, Windows) events fall back to v1 `print` lines on stderr.
+const BRIDGE_PROTOCOL_VERSION = 2;
+const eventsFd = (() => {
+  const raw = Number(process.env.HEADLAMP_EVENTS_FD);
+  return Number.isInteger(raw) && raw > 2 ? raw : null;
+})();
+let eventsFdBroken = false;
+const emit = (payload) => {
+  if (eventsFd === null || eventsFdBroken) return false;
+  try {
+    const line = JSON.stringify({ v: BRIDGE_PROTOCOL_VERSION, ...payload });
+    fs.writeSync(eventsFd, `${line}\n`);
+    return true;
+  } catch {
+    eventsFdBroken = true;
+    return false;
+  }
+};
+const emitOrPrint = (payload) => {
+  if (!emit(payload)) print(payload);
+};
+
 const isObject = (v) => typeof v === "object" && v !== null;
 const sanitizeError = (err) => {
   if (!isObject(err)) return err;
@@ -78,6 +101,12 @@ const sanitizeDetail = (d) => {
   return out;
 };
 
+const caseFullName = (info) =>
+  info?.fullName ||
+  [...((info && info.ancestorTitles) || []), info?.title]
+    .filter(Boolean)
+    .join(" ");
+
 class BridgeReporter {
   constructor(globalConfig, options) {
     this.out =
@@ -87,22 +116,27 @@ class BridgeReporter {
     this.buf = { startTime: Date.now(), testResults: [], aggregated: null };
   }
 
+  // Jest 29.6+ calls this as each test begins; older versions only report results, so the
+  // running count stays at zero there.
+  onTestCaseStart(test, testCaseStartInfo) {
+    try {
+      const testPath = test && (test.path || test.testPath);
+      const fullName = caseFullName(testCaseStartInfo);
+      if (testPath && fullName) {
+        emitOrPrint({ type: "caseStart", testPath, fullName });
+      }
+    } catch {}
+  }
+
   onTestCaseResult(test, testCaseResult) {
     try {
       const testPath =
         (test && (test.path || test.testPath)) || testCaseResult?.testFilePath;
-      const fullName =
-        testCaseResult?.fullName ||
-        [
-          ...((testCaseResult && testCaseResult.ancestorTitles) || []),
-          testCaseResult?.title,
-        ]
-          .filter(Boolean)
-          .join(" ");
+      const fullName = caseFullName(testCaseResult);
       const status = testCaseResult?.status;
       const duration = testCaseResult?.duration;
       if (testPath && fullName && status) {
-        print({
+        emitOrPrint({
           type: "caseComplete",
           testPath,
           fullName,
@@ -113,8 +147,20 @@ class BridgeReporter {
     } catch {}
   }
 
-  onRunStart() {
+  onRunStart(results) {
     this.buf.startTime = Date.now();
+    emit({
+      type: "runStart",
+      numTotalTestSuites:
+        results && typeof results.numTotalTestSuites === "number"
+          ? results.numTotalTestSuites
+          : null,
+    });
+  }
+
+  onTestFileStart(test) {
+    const testPath = test && (test.path || test.testPath);
+    if (testPath) emit({ type: "testFileStart", testPath });
   }
 
   onTestResult(_test, tr) {
@@ -173,12 +219,14 @@ class BridgeReporter {
       testResults: (tr.testResults || []).map(mapAssertion),
     });
     try {
-      print({
-        type: "suiteComplete",
+      const summary = {
         testPath: tr.testFilePath,
         numPassingTests: tr.numPassingTests,
         numFailingTests: tr.numFailingTests,
-      });
+      };
+      if (!emit({ type: "testFileComplete", ...summary })) {
+        print({ type: "suiteComplete", ...summary });
+      }
     } catch {}
   }
 
@@ -235,6 +283,7 @@ class BridgeReporter {
     };
     fs.mkdirSync(path.dirname(this.out), { recursive: true });
     fs.writeFileSync(this.out, JSON.stringify(this.buf), "utf8");
+    emit({ type: "runComplete" });
   }
 }
 
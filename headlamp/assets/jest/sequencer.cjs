@@ -0,0 +1,104 @@
+/* eslint-disable global-require */
+/* eslint-disable @typescript-eslint/no-require-imports */
+/* eslint-disable import/no-dynamic-require */
+
+// `--shuffle`: test files in the order of headlamp's seeded shuffle (splitmix64 + Fisher-Yates,
+// the same as headlamp's own), so a seed from the footer reproduces the run.
+
+const MASK = (1n << 64n) - 1n;
+
+const splitmix64 = (seed) => {
+  let state = BigInt.asUintN(64, seed);
+  return () => {
+    state = (state + 0x9e3779b97f4a7c15n) & MASK;
+    let z = state;
+    z = ((z ^ (z >> 30n)) * 0xbf58476d1ce4e5b9n) & MASK;
+    z = ((z ^ (z >> 27n)) * 0x94d049bb133111ebn) & MASK;
+    return z ^ (z >> 31n);
+  };
+};
+
+const shuffle = (items, seed) => {
+  const next = splitmix64(seed);
+  for (let index = items.length - 1; index > 0; index -= 1) {
+    const other = Number(next() % BigInt(index + 1));
+    [items[index], items[other]] = [items[other], items[index]];
+  }
+  return items;
+};
+
+const loadBaseSequencer = () => {
+  const tries = [
+    () => require.resolve("@jest/test-sequencer", { paths: [process.cwd()] }),
+    () =>
+      require.resolve("@jest/test-sequencer", {
+        paths: [require.resolve("jest", { paths: [process.cwd()] })],
+      }),
+    () =>
+      require.resolve("@jest/test-sequencer", {
+        paths: [require.resolve("@jest/core", { paths: [process.cwd()] })],
+      }),
+  ];
+  for (const attempt of tries) {
+    try {
+      const mod = require(attempt());
+      return mod.default || mod;
+    } catch {}
+  }
+  return class {
+    cacheResults() {}
+    allFailedTests(tests) {
+      return tests;
+    }
+    shard(tests) {
+      return tests;
+    }
+  };
+};
+
+const BaseSequencer = loadBaseSequencer();
+
+const realpath = (file) => {
+  try {
+    return require("node:fs").realpathSync(file);
+  } catch {
+    return file;
+  }
+};
+
+// `headlamp bisect-order`: test files in the order listed in HEADLAMP_TEST_ORDER's file.
+const listedOrder = () => {
+  const orderFile = process.env.HEADLAMP_TEST_ORDER;
+  if (!orderFile) return undefined;
+  try {
+    const listed = require("node:fs")
+      .readFileSync(orderFile, "utf8")
+      .split(/\r?\n/)
+      .map((line) => line.trim())
+      .filter(Boolean)
+      .map(realpath);
+    return new Map(listed.map((file, index) => [file, index]));
+  } catch {
+    return undefined;
+  }
+};
+
+class HeadlampShuffleSequencer extends BaseSequencer {
+  sort(tests) {
+    const rank = listedOrder();
+    if (rank) {
+      const at = (test) => rank.get(realpath(test.path)) ?? rank.size;
+      return [...tests].sort((a, b) => at(a) - at(b));
+    }
+    let seed;
+    try {
+      seed = BigInt(process.env.HEADLAMP_SHUFFLE_SEED || "");
+    } catch {
+      seed = undefined;
+    }
+    const ordered = [...tests].sort((a, b) => (a.path < b.path ? -1 : a.path > b.path ? 1 : 0));
+    return seed === undefined ? ordered : shuffle(ordered, seed);
+  }
+}
+
+module.exports = HeadlampShuffleSequencer;
@@ -0,0 +1,82 @@
+/* eslint-disable global-require */
+/* eslint-disable @typescript-eslint/no-require-imports */
+/* eslint-disable import/no-dynamic-require */
+
+// Long-lived jest process for `headlamp --watch`. Each stdin line is one run request
+// (`{"argv": [...], "env": {...}}`); jest itself stays loaded between runs, so a rerun skips
+// node startup, jest's module loading and config resolution. After every run a sentinel line
+// goes to both stdout and stderr so headlamp knows each stream is drained for that run.
+
+const path = require("node:path");
+const readline = require("node:readline");
+
+const MARKER = "[HEADLAMP-WORKER]";
+
+const send = (payload) => {
+  const line = `${MARKER} ${JSON.stringify(payload)}\n`;
+  process.stdout.write(line);
+  process.stderr.write(line);
+};
+
+const loadJest = () => {
+  const cwd = process.cwd();
+  const resolveFrom = (dir, id) => require.resolve(id, { paths: [dir] });
+  const jestDir = path.dirname(resolveFrom(cwd, "jest/package.json"));
+  const cliDir = path.dirname(resolveFrom(jestDir, "jest-cli/package.json"));
+  const cli = require(resolveFrom(jestDir, "jest-cli"));
+  const core = require(resolveFrom(cliDir, "@jest/core"));
+  if (typeof cli.buildArgv !== "function" || typeof core.runCLI !== "function") {
+    throw new Error("this jest version has no programmatic runCLI/buildArgv");
+  }
+  return { buildArgv: cli.buildArgv, runCLI: core.runCLI };
+};
+
+let jest;
+try {
+  jest = loadJest();
+} catch (error) {
+  send({ ready: false, error: String((error && error.message) || error) });
+  process.exit(0);
+}
+
+// The event pipe fd belongs to a single spawned run; the reporter falls back to stderr lines.
+delete process.env.HEADLAMP_EVENTS_FD;
+
+const runOnce = async (request) => {
+  for (const [key, value] of Object.entries(request.env || {})) {
+    process.env[key] = value;
+  }
+  try {
+    const argv = await jest.buildArgv(request.argv || []);
+    const projects =
+      Array.isArray(argv.projects) && argv.projects.length > 0
+        ? argv.projects
+        : [process.cwd()];
+    const { results } = await jest.runCLI(argv, projects);
+    return results && results.success ? 0 : 1;
+  } catch (error) {
+    process.stderr.write(`${(error && error.stack) || error}\n`);
+    return 1;
+  }
+};
+
+let queue = Promise.resolve();
+const lines = readline.createInterface({ input: process.stdin });
+lines.on("line", (line) => {
+  if (!line.trim()) return;
+  queue = queue.then(async () => {
+    let request;
+    try {
+      request = JSON.parse(line);
+    } catch {
+      send({ done: true, exitCode: 1 });
+      return;
+    }
+    send({ done: true, exitCode: await runOnce(request) });
+  });
+});
+lines.on("close", () => {
+  queue.then(() => process.exit(0));
+});
+
+send({ ready: true });
@@ -1,5 +1,7 @@
 import json
+import os
 import sys
+import time
 
 
 EVENT_PREFIX = "HEADLAMP_PYTEST_EVENT "
@@ -14,8 +16,153 @@ def _emit(payload):
     sys.__stdout__.flush()
 
 
+_MASK = (1 << 64) - 1
+
+
+def _splitmix64(seed):
+    state = seed & _MASK
+
+    def next_value():
+        nonlocal state
+        state = (state + 0x9E3779B97F4A7C15) & _MASK
+        z = state
+        z = ((z ^ (z >> 30)) * 0xBF58476D1CE4E5B9) & _MASK
+        z = ((z ^ (z >> 27)) * 0x94D049BB133111EB) & _MASK
+        return z ^ (z >> 31)
+
+    return next_value
+
+
+def _shuffle(items, seed):
+    # Same splitmix64 + Fisher-Yates as headlamp, so a seed reproduces across runners.
+    next_value = _splitmix64(seed)
+    for index in range(len(items) - 1, 0, -1):
+        other = next_value() % (index + 1)
+        items[index], items[other] = items[other], items[index]
+    return items
+
+
+def _item_file(item):
+    path = getattr(item, "path", None) or getattr(item, "fspath", "")
+    return os.path.realpath(str(path))
+
+
+def _listed_order(items):
+    # `headlamp bisect-order`: test files in the order listed in HEADLAMP_TEST_ORDER's file.
+    order_file = os.environ.get("HEADLAMP_TEST_ORDER", "")
+    if not order_file:
+        return False
+    try:
+        with open(order_file, encoding="utf-8") as handle:
+            listed = [os.path.realpath(line.strip()) for line in handle if line.strip()]
+    except OSError:
+        return False
+    rank = {path: index for index, path in enumerate(listed)}
+    items.sort(key=lambda item: rank.get(_item_file(item), len(rank)))
+    return True
+
+
+def pytest_collection_modifyitems(session, config, items):
+    if _listed_order(items):
+        return
+    # `--shuffle`: test files in the seed's order, each file's tests in their own order.
+    # pytest-randomly, when the user loads it, does the shuffling instead.
+    raw_seed = os.environ.get("HEADLAMP_SHUFFLE_SEED", "")
+    if not raw_seed.isdigit() or config.pluginmanager.hasplugin("randomly"):
+        return
+    by_file = {}
+    for item in items:
+        by_file.setdefault(item.nodeid.split("::", 1)[0], []).append(item)
+    files = _shuffle(sorted(by_file), int(raw_seed))
+    items[:] = [item for path in files for item in by_file[path]]
+
+
+_HTTP_CALLS = []
+_BODY_PREVIEW_CHARS = 400
+
+
+def _record_http(method, url, started, response=None, preview=True):
+    # Shaped like the jest bridge's httpResponse/httpAbort events, so headlamp renders them alike.
+    event = {
+        "type": "httpAbort" if response is None else "httpResponse",
+        "timestampMs": int(time.time() * 1000),
+        "method": str(method or "").upper(),
+        "url": str(url or ""),
+        "durationMs": int((time.monotonic() - started) * 1000),
+    }
+    if response is not None:
+        event["statusCode"] = getattr(response, "status_code", None)
+        event["contentType"] = response.headers.get("content-type", "")
+        if preview:
+            try:
+                event["bodyPreview"] = response.text[:_BODY_PREVIEW_CHARS]
+            except Exception:
+                pass
+    _HTTP_CALLS.append(event)
+
+
+def _wrap_send(original):
+    def send(self, request, *args, **kwargs):
+        started = time.monotonic()
+        try:
+            response = original(self, request, *args, **kwargs)
+        except Exception:
+            _record_http(request.method, request.url, started)
+            raise
+        _record_http(request.method, request.url, started, response, not kwargs.get("stream"))
+        return response
+
+    send._headlamp_http = True
+    return send
+
+
+def _wrap_async_send(original):
+    async def send(self, request, *args, **kwargs):
+        started = time.monotonic()
+        try:
+            response = await original(self, request, *args, **kwargs)
+        except Exception:
+            _record_http(request.method, request.url, started)
+            raise
+        _record_http(request.method, request.url, started, response, not kwargs.get("stream"))
+        return response
+
+    send._headlamp_http = True
+    return send
+
+
+def _patch(owner, wrap):
+    if owner is not None and not getattr(owner.send, "_headlamp_http", False):
+        owner.send = wrap(owner.send)
+
+
+def _patch_http_clients():
+    # Only clients the tests already imported; headlamp never imports them itself.
+    requests = sys.modules.get("requests")
+    if requests is not None:
+        _patch(getattr(requests, "Session", None), _wrap_send)
+    httpx = sys.modules.get("httpx")
+    if httpx is not None:
+        _patch(getattr(httpx, "Client", None), _wrap_send)
+        _patch(getattr(httpx, "AsyncClient", None), _wrap_async_send)
+
+
+def _skip_reason(report):
+    wasxfail = getattr(report, "wasxfail", None)
+    if wasxfail is not None:
+        return "xfail: " + wasxfail if wasxfail else "xfail"
+    longrepr = getattr(report, "longrepr", None)
+    if isinstance(longrepr, tuple) and len(longrepr) == 3:
+        reason = str(longrepr[2])
+        return reason[len("Skipped: "):] if reason.startswith("Skipped: ") else reason
+    return ""
+
+
 def pytest_runtest_logreport(report):
-    if getattr(report, "when", None) != "call":
+    when = getattr(report, "when", None)
+    # `@pytest.mark.skip` and `skipif` skip during setup; the test body never gets a call report.
+    skipped_in_setup = when == "setup" and getattr(report, "skipped", False)
+    if when != "call" and not skipped_in_setup:
         return
     payload = {
         "type": "case",
@@ -27,10 +174,16 @@ def pytest_runtest_logreport(report):
     }
     if payload["outcome"] == "failed":
         payload["longrepr"] = getattr(report, "longreprtext", "") or ""
+    if payload["outcome"] == "skipped":
+        payload["skipReason"] = _skip_reason(report)
+    if _HTTP_CALLS:
+        payload["http"] = list(_HTTP_CALLS)
     _emit(payload)
 
 
 def pytest_runtest_logstart(nodeid, location):
+    _HTTP_CALLS.clear()
+    _patch_http_clients()
     # Emit a lightweight "currently running" hint so headlamp can show per-test progress even in
     # quiet mode (-q). This is emitted before the test body runs.
     _emit(
@@ -0,0 +1,171 @@
+import json
+import os
+import sys
+import time
+import unittest
+
+
+EVENT_PREFIX = "HEADLAMP_PYTEST_EVENT "
+
+
+def _emit(payload):
+    try:
+        line = EVENT_PREFIX + json.dumps(payload, ensure_ascii=False)
+    except Exception as exc:
+        line = EVENT_PREFIX + json.dumps({"type": "error", "message": str(exc)})
+    sys.__stdout__.write(line + "\n")
+    sys.__stdout__.flush()
+
+
+def _rel_path(path):
+    try:
+        rel = os.path.relpath(path, os.getcwd())
+    except ValueError:
+        rel = path
+    return rel.replace(os.sep, "/")
+
+
+def _module_path(module_name):
+    module = sys.modules.get(module_name)
+    module_file = getattr(module, "__file__", None)
+    if module_file:
+        return _rel_path(module_file)
+    return module_name.replace(".", "/") + ".py"
+
+
+class _ImportFailure(unittest.TestCase):
+    def __init__(self, nodeid, exc):
+        super().__init__("runTest")
+        self._nodeid = nodeid
+        self._exc = exc
+
+    def id(self):
+        return self._nodeid
+
+    def runTest(self):
+        raise self._exc
+
+
+def _nodeid(test):
+    if isinstance(test, _ImportFailure):
+        return test.id()
+    # Map unittest ids ("pkg.test_mod.TestX.test_y") onto pytest-style nodeids
+    # ("pkg/test_mod.py::TestX::test_y") so the pytest adapter can group by file.
+    if isinstance(test, unittest.loader._FailedTest):
+        return _module_path(test._testMethodName) + "::" + test._testMethodName
+    test_id = test.id()
+    module_name = type(test).__module__
+    if not test_id.startswith(module_name + "."):
+        # _ErrorHolder for setUpClass/setUpModule failures: "setUpClass (pkg.test_mod.TestX)".
+        return test_id
+    rest = test_id[len(module_name) + 1 :]
+    return _module_path(module_name) + "::" + "::".join(rest.split("."))
+
+
+class HeadlampResult(unittest.TestResult):
+    def __init__(self):
+        super().__init__()
+        self.buffer = True
+        self._started_at = {}
+
+    def startTest(self, test):
+        super().startTest(test)
+        self._started_at[test.id()] = time.perf_counter()
+        _emit({"type": "case_start", "nodeid": _nodeid(test)})
+
+    def _captured(self, name):
+        buf = getattr(self, name, None)
+        return buf.getvalue() if buf is not None else ""
+
+    def _case(self, test, outcome, err=None, nodeid=None, skip_reason=None):
+        started = self._started_at.get(test.id())
+        payload = {
+            "type": "case",
+            "nodeid": nodeid or _nodeid(test),
+            "outcome": outcome,
+            "duration": (time.perf_counter() - started) if started else 0.0,
+            "stdout": self._captured("_stdout_buffer"),
+            "stderr": self._captured("_stderr_buffer"),
+        }
+        if outcome == "failed" and err is not None:
+            payload["longrepr"] = self._exc_info_to_string(err, test)
+        if skip_reason is not None:
+            payload["skipReason"] = skip_reason
+        _emit(payload)
+
+    def addSuccess(self, test):
+        super().addSuccess(test)
+        self._case(test, "passed")
+
+    def addFailure(self, test, err):
+        self._case(test, "failed", err)
+        super().addFailure(test, err)
+
+    def addError(self, test, err):
+        self._case(test, "failed", err)
+        super().addError(test, err)
+
+    def addSkip(self, test, reason):
+        super().addSkip(test, reason)
+        self._case(test, "skipped", skip_reason=reason)
+
+    def addExpectedFailure(self, test, err):
+        super().addExpectedFailure(test, err)
+        self._case(test, "passed")
+
+    def addUnexpectedSuccess(self, test):
+        super().addUnexpectedSuccess(test)
+        self._case(test, "failed")
+
+    def addSubTest(self, test, subtest, err):
+        if err is not None:
+            nodeid = _nodeid(test) + " " + subtest._subDescription()
+            self._case(test, "failed", err, nodeid=nodeid)
+        super().addSubTest(test, subtest, err)
+
+
+def _load_selection(loader, selection):
+    suite = unittest.TestSuite()
+    for token in selection:
+        path, _, member = token.partition("::")
+        module_name = _rel_path(path)[: -len(".py")].replace("/", ".")
+        name = ".".join([module_name] + [p for p in member.split("::") if p])
+        try:
+            suite.addTests(loader.loadTestsFromName(name))
+        except Exception as exc:
+            suite.addTest(_ImportFailure(_rel_path(path) + "::" + (member or module_name), exc))
+    return suite
+
+
+def _parse_args(argv):
+    # "-k PATTERN" keeps tests whose id contains it (or matches it, with "*"), like
+    # `python -m unittest -k`; "--failfast" stops at the first failure; the rest is the selection.
+    selection, patterns, failfast = [], [], False
+    tokens = iter(argv)
+    for token in tokens:
+        if token == "-k":
+            pattern = next(tokens, "")
+            patterns.append(pattern if "*" in pattern else "*" + pattern + "*")
+        elif token == "--failfast":
+            failfast = True
+        else:
+            selection.append(token)
+    return selection, patterns, failfast
+
+
+def main(argv):
+    selection, patterns, failfast = _parse_args(argv)
+    loader = unittest.TestLoader()
+    loader.testNamePatterns = patterns or None
+    if selection:
+        suite = _load_selection(loader, selection)
+    else:
+        suite = loader.discover(".", top_level_dir=".")
+    result = HeadlampResult()
+    result.failfast = failfast
+    suite.run(result)
+    return 0 if result.wasSuccessful() else 1
+
+
+if __name__ == "__main__":
+    sys.exit(main(sys.argv[1:]))
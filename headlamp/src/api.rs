@@ -0,0 +1,205 @@
+//! Running headlamp from another program (an editor extension, a bot) instead of spawning the CLI
+//! and scraping its stdout:
+//!
+//! ```no_run
+//! use headlamp::api::{RunRequest, run};
+//! use headlamp::runner::Runner;
+//!
+//! let outcome = run(&RunRequest::new("/path/to/repo")
+//!     .runner(Runner::Pytest)
+//!     .args(["--coverage", "tests/test_sum.py"]))?;
+//! for model in &outcome.models {
+//!     println!("{} failed", model.aggregated.num_failed_tests);
+//! }
+//! # Ok::<(), headlamp::api::ApiError>(())
+//! ```
+//!
+//! A run takes the CLI's flags and the repo's config and hooks, like `headlamp` would in
+//! `repo_root`. The runners print their live output and report to stdout, as the CLI does, unless
+//! the request is `quiet`; either way `rendered` is the same report built from the returned
+//! models. Each outcome holds its own run's results, but runners record into the current run and
+//! share process limits, so run one at a time.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::args::ParsedArgs;
+use crate::coverage::model::CoverageReport;
+use crate::process::{RunEnvironment, RunnerResources};
+use crate::recorded::RecordedResults;
+use crate::rerun::FailedTest;
+use crate::run::RunError;
+use crate::runner::{Runner, detect_runners, run_plans};
+use crate::test_model::TestRunModel;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("invalid arguments: {0}")]
+    InvalidArgs(String),
+
+    #[error(transparent)]
+    Run(#[from] RunError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunRequest {
+    pub repo_root: PathBuf,
+    /// Empty picks runners like the CLI does without `--runner`.
+    pub runners: Vec<Runner>,
+    /// CLI flags and paths, as after `headlamp`; runners go in `runners`, not `--runner`.
+    pub args: Vec<String>,
+    /// When non-empty, each runner is narrowed to these tests the way `--rerun-failed` narrows
+    /// it to a report's failures.
+    pub tests: Vec<FailedTest>,
+    /// Keeps the live output and report off stdout; the outcome still carries them.
+    pub quiet: bool,
+}
+
+impl RunRequest {
+    pub fn new(repo_root: impl Into<PathBuf>) -> Self {
+        Self {
+            repo_root: repo_root.into(),
+            runners: vec![],
+            args: vec![],
+            tests: vec![],
+            quiet: false,
+        }
+    }
+
+    pub fn runner(mut self, runner: Runner) -> Self {
+        self.runners.push(runner);
+        self
+    }
+
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn tests(mut self, tests: impl IntoIterator<Item = FailedTest>) -> Self {
+        self.tests.extend(tests);
+        self
+    }
+
+    pub fn quiet(mut self) -> Self {
+        self.quiet = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    /// What the CLI would exit with.
+    pub exit_code: i32,
+    /// Each runner's final model; a runner that failed to start contributes a failed suite.
+    pub models: Vec<TestRunModel>,
+    /// Each runner's coverage report, for `--coverage` runs.
+    pub coverage: Vec<CoverageReport>,
+    /// Totals over every runner.
+    pub results: RecordedResults,
+    /// Each runner invocation's peak memory, CPU time and process count.
+    pub resources: Vec<RunnerResources>,
+    /// The report for `models`, rendered as the CLI prints it.
+    pub rendered: String,
+}
+
+/// A flag the CLI runs around (watch loops, command listing, report replays) rather than through.
+fn cli_only_flag(args: &ParsedArgs) -> Option<&'static str> {
+    [
+        ("--watch", args.watch),
+        ("--print-commands", args.print_commands.is_some()),
+        ("--debug", args.debug.is_some()),
+        ("--rerun-failed", args.rerun_failed.is_some()),
+    ]
+    .into_iter()
+    .find_map(|(flag, is_set)| is_set.then_some(flag))
+}
+
+pub fn run(request: &RunRequest) -> Result<RunOutcome, ApiError> {
+    let repo_root = request.repo_root.as_path();
+    let initial = parse_args(repo_root, repo_root, &request.args).map_err(ApiError::InvalidArgs)?;
+    if let Some(flag) = cli_only_flag(&initial) {
+        return Err(ApiError::InvalidArgs(format!(
+            "{flag} is only supported by the CLI"
+        )));
+    }
+    let runners = match request.runners.as_slice() {
+        [] => detect_runners(repo_root, &initial),
+        runners => runners.to_vec(),
+    };
+    let parsed = if runners.iter().copied().any(Runner::is_rust) {
+        let argv = crate::args::expand_short_package_flag(&request.args);
+        parse_args(repo_root, repo_root, &argv).map_err(ApiError::InvalidArgs)?
+    } else {
+        initial
+    };
+    crate::notify::validate_notify_targets(&parsed.notify)?;
+    crate::format::report_out::configure_quiet(request.quiet);
+    let run_env = prepare_environment(repo_root, &parsed)?;
+    let plans = runners
+        .iter()
+        .map(|runner| {
+            let mut run_args = match request.tests.as_slice() {
+                [] => parsed.clone(),
+                tests => crate::rerun::rerun_args(&parsed, tests, runner.rerun_runner()),
+            };
+            run_args.runner_args =
+                crate::args::translate_runner_args(runner.rerun_runner(), &run_args.runner_args);
+            (*runner, runner.run_root(repo_root, &parsed), run_args)
+        })
+        .collect::<Vec<_>>();
+    let finished = run_plans(repo_root, &parsed, &run_env, &plans);
+    let exit_code = finished.exit_code;
+    let runs = finished.runs;
+    let ctx = crate::format::ctx::make_ctx_for_args(repo_root, &parsed, exit_code != 0);
+    let rendered = runs
+        .models
+        .iter()
+        .map(|model| {
+            crate::format::vitest::render_vitest_from_test_model(model, &ctx, parsed.only_failures)
+        })
+        .filter(|rendered| !rendered.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(RunOutcome {
+        exit_code,
+        models: runs.models,
+        coverage: runs.coverage,
+        resources: runs.resources,
+        results: finished.results,
+        rendered,
+    })
+}
+
+/// The CLI's process settings, minus logging (the embedding program owns that).
+fn prepare_environment(repo_root: &Path, args: &ParsedArgs) -> Result<RunEnvironment, RunError> {
+    crate::process::set_process_limits(crate::process::ProcessLimits::from_args(args));
+    crate::node_toolchain::set_node_override(args.node.as_ref().map(PathBuf::from));
+    crate::format::deterministic::configure(args.deterministic, repo_root);
+    crate::format::deterministic::configure_times(args.normalize_times);
+    crate::format::output_filter::configure(&args.output_filters, &args.output_collapse)
+        .map_err(|message| RunError::CommandFailed { message })?;
+    let run_env = RunEnvironment::from_args(repo_root, args)?;
+    if let Some(cache_dir) = run_env.cache_dir.as_ref() {
+        let _ = std::fs::create_dir_all(cache_dir);
+    }
+    run_env.publish();
+    Ok(run_env)
+}
+
+/// `argv` as the CLI reads it in `cwd`: presets expanded, passthrough checked, and the config
+/// nearest to what `argv` selects layered over `config_root`'s.
+pub fn parse_args(config_root: &Path, cwd: &Path, argv: &[String]) -> Result<ParsedArgs, String> {
+    let selection = crate::args::derive_args(&[], argv, false).selection_paths;
+    let anchor = crate::config::nearest::selection_anchor(cwd, &selection);
+    let cfg = crate::config::validate::load_headlamp_config_for(config_root, &anchor);
+    let argv = crate::args::expand_presets(cfg.presets.as_ref(), argv)
+        .and_then(|argv| crate::args::validate_runner_passthrough(&argv).map(|()| argv))?;
+    let cfg_tokens = crate::args::config_tokens(&cfg, &argv);
+    Ok(crate::args::derive_args(
+        &cfg_tokens,
+        &argv,
+        crate::format::terminal::is_output_terminal(),
+    ))
+}
@@ -1,14 +1,22 @@
+use super::cli_values::{
+    extend_comma_delimited, parse_bool_with_optional_value, parse_f64_value,
+    parse_optional_string_with_default, parse_string_value, parse_u32_value,
+};
+use super::helpers::parse_threshold_spec;
+
 #[derive(Debug, Clone, Default)]
 pub(super) struct HeadlampCli {
     pub(super) keep_artifacts: bool,
     pub(super) coverage: bool,
     pub(super) coverage_abort_on_failure: bool,
+    pub(super) coverage_native_thresholds: bool,
     pub(super) coverage_ui: Option<String>,
     pub(super) coverage_detail: Option<String>,
     pub(super) coverage_show_code: Option<bool>,
     pub(super) coverage_mode: Option<String>,
     pub(super) coverage_max_files: Option<u32>,
     pub(super) coverage_max_hotspots: Option<u32>,
+    pub(super) max_reported_failures: Option<u32>,
     pub(super) coverage_thresholds_lines: Option<f64>,
     pub(super) coverage_thresholds_functions: Option<f64>,
     pub(super) coverage_thresholds_branches: Option<f64>,
@@ -18,8 +26,27 @@ pub(super) struct HeadlampCli {
     pub(super) coverage_exclude: Vec<String>,
     pub(super) coverage_editor: Option<String>,
     pub(super) coverage_root: Option<String>,
+    pub(super) repo_root: Option<String>,
     pub(super) only_failures: bool,
     pub(super) show_logs: bool,
+    pub(super) full_stacks: bool,
+    pub(super) log_json: bool,
+    pub(super) deterministic: bool,
+    pub(super) normalize_times: bool,
+    pub(super) bail: bool,
+    pub(super) summary_line: bool,
+    pub(super) check_update: bool,
+    pub(super) show_skipped: bool,
+    pub(super) show_skipped_projects: bool,
+    pub(super) footer_breakdown: bool,
+    pub(super) fail_on_skipped: bool,
+    pub(super) fail_if_no_tests: bool,
+    pub(super) fail_on_untested_changes: bool,
+    pub(super) strict_duplicates: bool,
+    pub(super) print_commands: Option<String>,
+    pub(super) debug: Option<String>,
+    pub(super) resource_report: Option<String>,
+    pub(super) shuffle: Option<String>,
     pub(super) sequential: bool,
     pub(super) watch: bool,
     pub(super) watch_all: bool,
@@ -30,13 +57,56 @@ pub(super) struct HeadlampCli {
     pub(super) bootstrap_command: Option<String>,
     pub(super) changed: Option<String>,
     pub(super) changed_depth: Option<u32>,
+    pub(super) progress_heartbeat: Option<u32>,
     pub(super) coverage_compact: bool,
+    pub(super) max_memory: Option<String>,
+    pub(super) nice: Option<u32>,
+    pub(super) python: Option<String>,
+    pub(super) features: Option<String>,
+    pub(super) all_features: bool,
+    pub(super) no_default_features: bool,
+    pub(super) feature_matrix: Option<String>,
+    pub(super) packages: Vec<String>,
+    pub(super) exclude_packages: Vec<String>,
+    pub(super) target: Option<String>,
+    pub(super) target_runner: Option<String>,
+    pub(super) cargo_profile: Option<String>,
+    pub(super) nextest_profile: Option<String>,
+    pub(super) auto_install: bool,
+    pub(super) coverage_engine: Option<String>,
+    pub(super) coverage_provider: Option<String>,
+    pub(super) group_by: Option<String>,
+    pub(super) path_style: Option<String>,
+    pub(super) strip_path_prefixes: Vec<String>,
+    pub(super) projects: Vec<String>,
+    pub(super) ignore_projects: Vec<String>,
+    pub(super) jest_esm: Option<String>,
+    pub(super) typecheck: Option<String>,
+    pub(super) typecheck_command: Option<String>,
+    pub(super) node: Option<String>,
+    pub(super) hooks: crate::config::LifecycleHooks,
+    pub(super) wait_for: Vec<String>,
+    pub(super) wait_timeout: Option<String>,
+    pub(super) max_duration: Option<String>,
+    pub(super) env_vars: Vec<String>,
+    pub(super) env_files: Vec<String>,
+    pub(super) artifacts_dir: Option<String>,
+    pub(super) artifact_globs: Vec<String>,
+    pub(super) output_filters: Vec<String>,
+    pub(super) output_collapse: Vec<String>,
+    pub(super) badge_out: Option<String>,
+    pub(super) rerun_failed: Option<String>,
+    pub(super) remote: Option<String>,
+    pub(super) notify: Vec<String>,
+    pub(super) checks: Vec<String>,
+    pub(super) presets: Vec<String>,
+    pub(super) log_level: Option<String>,
     pub(super) dependency_language: Option<String>,
 }
 
 #[derive(Debug)]
 pub(super) struct HeadlampCliParseError {
-    message: String,
+    pub(super) message: String,
 }
 
 impl std::fmt::Display for HeadlampCliParseError {
@@ -67,7 +137,7 @@ impl HeadlampCli {
                 index += 1;
                 continue;
             };
-            let flag = normalize_flag_name(raw_flag);
+            let flag = super::flags::canonical_name(raw_flag);
             let (next_token_text, has_next) = tokens
                 .get(index + 1)
                 .map(|t| (t.as_str(), true))
@@ -95,10 +165,18 @@ fn apply_flag_to_cli(
     {
         return Ok(used_next);
     }
+    if let Some(used_next) = apply_switch_flag(parsed, flag, raw_value) {
+        return Ok(used_next);
+    }
     if let Some(used_next) = apply_string_flag(parsed, flag, raw_value, next_token_text, has_next)?
     {
         return Ok(used_next);
     }
+    if let Some(used_next) =
+        apply_repeatable_flag(parsed, flag, raw_value, next_token_text, has_next)?
+    {
+        return Ok(used_next);
+    }
     if let Some(used_next) = apply_u32_flag(parsed, flag, raw_value, next_token_text, has_next)? {
         return Ok(used_next);
     }
@@ -118,39 +196,41 @@ fn apply_bool_flag(
     next_token_text: &str,
     has_next: bool,
 ) -> Result<Option<usize>, HeadlampCliParseError> {
-    let (value, used_next) = match flag {
-        "keep-artifacts" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "coverage" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "coverage-abort-on-failure" => {
-            parse_bool_with_optional_value(raw_value, next_token_text, has_next)?
-        }
-        "only-failures" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "show-logs" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "sequential" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "watch" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "watch-all" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "ci" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "verbose" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "quiet" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
-        "no-cache" => parse_bool_with_optional_value(raw_value, next_token_text, has_next)?,
+    let field = match flag {
+        "keep-artifacts" => &mut parsed.keep_artifacts,
+        "coverage" => &mut parsed.coverage,
+        "coverage-abort-on-failure" => &mut parsed.coverage_abort_on_failure,
+        "coverage-native-thresholds" => &mut parsed.coverage_native_thresholds,
+        "only-failures" => &mut parsed.only_failures,
+        "show-logs" => &mut parsed.show_logs,
+        "full-stacks" => &mut parsed.full_stacks,
+        "log-json" => &mut parsed.log_json,
+        "deterministic" => &mut parsed.deterministic,
+        "normalize-times" => &mut parsed.normalize_times,
+        "bail" => &mut parsed.bail,
+        "summary-line" => &mut parsed.summary_line,
+        "check-update" => &mut parsed.check_update,
+        "show-skipped" => &mut parsed.show_skipped,
+        "show-skipped-projects" => &mut parsed.show_skipped_projects,
+        "footer-breakdown" => &mut parsed.footer_breakdown,
+        "fail-on-skipped" => &mut parsed.fail_on_skipped,
+        "fail-if-no-tests" => &mut parsed.fail_if_no_tests,
+        "fail-on-untested-changes" => &mut parsed.fail_on_untested_changes,
+        "strict-duplicates" => &mut parsed.strict_duplicates,
+        "all-features" => &mut parsed.all_features,
+        "auto-install" => &mut parsed.auto_install,
+        "no-default-features" => &mut parsed.no_default_features,
+        "sequential" => &mut parsed.sequential,
+        "watch" => &mut parsed.watch,
+        "watch-all" => &mut parsed.watch_all,
+        "ci" => &mut parsed.ci,
+        "verbose" => &mut parsed.verbose,
+        "quiet" => &mut parsed.quiet,
+        "no-cache" => &mut parsed.no_cache,
         _ => return Ok(None),
     };
-
-    match flag {
-        "keep-artifacts" => parsed.keep_artifacts = value,
-        "coverage" => parsed.coverage = value,
-        "coverage-abort-on-failure" => parsed.coverage_abort_on_failure = value,
-        "only-failures" => parsed.only_failures = value,
-        "show-logs" => parsed.show_logs = value,
-        "sequential" => parsed.sequential = value,
-        "watch" => parsed.watch = value,
-        "watch-all" => parsed.watch_all = value,
-        "ci" => parsed.ci = value,
-        "verbose" => parsed.verbose = value,
-        "quiet" => parsed.quiet = value,
-        "no-cache" => parsed.no_cache = value,
-        _ => {}
-    }
+    let (value, used_next) = parse_bool_with_optional_value(raw_value, next_token_text, has_next)?;
+    *field = value;
     Ok(Some(used_next))
 }
 
@@ -178,6 +258,25 @@ fn apply_bool_option_flag(
     Ok(Some(used_next))
 }
 
+/// Flags with an optional `=value` that never take the next token: `--debug src/a.ts` selects
+/// a file.
+fn apply_switch_flag(
+    parsed: &mut HeadlampCli,
+    flag: &str,
+    raw_value: Option<&str>,
+) -> Option<usize> {
+    let (field, default) = match flag {
+        "print-commands" => (&mut parsed.print_commands, "human"),
+        "debug" => (&mut parsed.debug, "auto"),
+        "resource-report" => (&mut parsed.resource_report, "human"),
+        "shuffle" => (&mut parsed.shuffle, "true"),
+        "typecheck" => (&mut parsed.typecheck, "gate"),
+        _ => return None,
+    };
+    *field = Some(raw_value.unwrap_or(default).to_string());
+    Some(0)
+}
+
 fn apply_string_flag(
     parsed: &mut HeadlampCli,
     flag: &str,
@@ -198,28 +297,131 @@ fn apply_string_flag(
         "coverage-mode" => parse_string_value(raw_value, next_token_text, has_next)?,
         "coverage-editor" => parse_string_value(raw_value, next_token_text, has_next)?,
         "coverage-root" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "repo-root" => parse_string_value(raw_value, next_token_text, has_next)?,
         "bootstrap-command" => parse_string_value(raw_value, next_token_text, has_next)?,
         "dependency-language" => parse_string_value(raw_value, next_token_text, has_next)?,
         "coverage-include" => parse_string_value(raw_value, next_token_text, has_next)?,
         "coverage-exclude" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "max-memory" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "python" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "features" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "feature-matrix" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "package" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "exclude" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "target" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "target-runner" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "cargo-profile" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "nextest-profile" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "coverage-engine" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "coverage-thresholds" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "coverage-provider" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "group-by" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "path-style" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "project" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "ignore-project" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "jest-esm" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "typecheck-command" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "node" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "wait-timeout" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "max-duration" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "artifacts-dir" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "badge-out" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "rerun-failed" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "remote" => parse_string_value(raw_value, next_token_text, has_next)?,
+        "log-level" => parse_string_value(raw_value, next_token_text, has_next)?,
         _ => return Ok(None),
     };
 
+    store_string_flag(parsed, flag, value);
+    Ok(Some(used_next))
+}
+
+fn store_string_flag(parsed: &mut HeadlampCli, flag: &str, value: String) {
     match flag {
         "coverage-ui" => parsed.coverage_ui = Some(value),
         "coverage-detail" => parsed.coverage_detail = Some(value),
         "coverage-mode" => parsed.coverage_mode = Some(value),
         "coverage-editor" => parsed.coverage_editor = Some(value),
         "coverage-root" => parsed.coverage_root = Some(value),
+        "repo-root" => parsed.repo_root = Some(value),
         "bootstrap-command" => parsed.bootstrap_command = Some(value),
         "dependency-language" => parsed.dependency_language = Some(value),
         "coverage-include" => extend_comma_delimited(&mut parsed.coverage_include, &value),
         "coverage-exclude" => extend_comma_delimited(&mut parsed.coverage_exclude, &value),
+        "max-memory" => parsed.max_memory = Some(value),
+        "python" => parsed.python = Some(value),
+        "features" => parsed.features = Some(value),
+        "feature-matrix" => parsed.feature_matrix = Some(value),
+        "package" => extend_comma_delimited(&mut parsed.packages, &value),
+        "exclude" => extend_comma_delimited(&mut parsed.exclude_packages, &value),
+        "target" => parsed.target = Some(value),
+        "target-runner" => parsed.target_runner = Some(value),
+        "cargo-profile" => parsed.cargo_profile = Some(value),
+        "nextest-profile" => parsed.nextest_profile = Some(value),
+        "coverage-engine" => parsed.coverage_engine = Some(value),
+        "coverage-thresholds" => apply_threshold_spec(parsed, &value),
+        "coverage-provider" => parsed.coverage_provider = Some(value),
+        "group-by" => parsed.group_by = Some(value),
+        "path-style" => parsed.path_style = Some(value),
+        "project" => extend_comma_delimited(&mut parsed.projects, &value),
+        "ignore-project" => extend_comma_delimited(&mut parsed.ignore_projects, &value),
+        "jest-esm" => parsed.jest_esm = Some(value),
+        "typecheck-command" => parsed.typecheck_command = Some(value),
+        "node" => parsed.node = Some(value),
+        "wait-timeout" => parsed.wait_timeout = Some(value),
+        "max-duration" => parsed.max_duration = Some(value),
+        "artifacts-dir" => parsed.artifacts_dir = Some(value),
+        "badge-out" => parsed.badge_out = Some(value),
+        "rerun-failed" => parsed.rerun_failed = Some(value),
+        "remote" => parsed.remote = Some(value),
+        "log-level" => parsed.log_level = Some(value),
         _ => {}
     }
+}
+
+/// Repeatable flags whose values are kept whole: hook commands may contain commas.
+fn apply_repeatable_flag(
+    parsed: &mut HeadlampCli,
+    flag: &str,
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+) -> Result<Option<usize>, HeadlampCliParseError> {
+    let commands = match flag {
+        "hook-pre-run" => &mut parsed.hooks.pre_run,
+        "hook-post-run" => &mut parsed.hooks.post_run,
+        "hook-pre-coverage" => &mut parsed.hooks.pre_coverage,
+        "hook-on-failure" => &mut parsed.hooks.on_failure,
+        "wait-for" => &mut parsed.wait_for,
+        "env" => &mut parsed.env_vars,
+        "env-file" => &mut parsed.env_files,
+        "artifact-glob" => &mut parsed.artifact_globs,
+        "output-filter" => &mut parsed.output_filters,
+        "output-collapse" => &mut parsed.output_collapse,
+        "strip-path-prefix" => &mut parsed.strip_path_prefixes,
+        "notify" => &mut parsed.notify,
+        "check" => &mut parsed.checks,
+        "preset" => &mut parsed.presets,
+        _ => return Ok(None),
+    };
+    let (value, used_next) = parse_string_value(raw_value, next_token_text, has_next)?;
+    commands.push(value);
     Ok(Some(used_next))
 }
 
+/// `--coverage-thresholds branches=80,lines=90` sets several thresholds at once.
+fn apply_threshold_spec(parsed: &mut HeadlampCli, spec: &str) {
+    for (metric, value) in parse_threshold_spec(spec) {
+        match metric.as_str() {
+            "lines" => parsed.coverage_thresholds_lines = Some(value),
+            "functions" => parsed.coverage_thresholds_functions = Some(value),
+            "branches" => parsed.coverage_thresholds_branches = Some(value),
+            "statements" => parsed.coverage_thresholds_statements = Some(value),
+            _ => {}
+        }
+    }
+}
+
 fn apply_u32_flag(
     parsed: &mut HeadlampCli,
     flag: &str,
@@ -231,6 +433,9 @@ fn apply_u32_flag(
         "changed-depth" => parse_u32_value(raw_value, next_token_text, has_next)?,
         "coverage-max-files" => parse_u32_value(raw_value, next_token_text, has_next)?,
         "coverage-max-hotspots" => parse_u32_value(raw_value, next_token_text, has_next)?,
+        "progress-heartbeat" => parse_u32_value(raw_value, next_token_text, has_next)?,
+        "nice" => parse_u32_value(raw_value, next_token_text, has_next)?,
+        "max-reported-failures" => parse_u32_value(raw_value, next_token_text, has_next)?,
         _ => return Ok(None),
     };
 
@@ -238,6 +443,9 @@ fn apply_u32_flag(
         "changed-depth" => parsed.changed_depth = Some(value),
         "coverage-max-files" => parsed.coverage_max_files = Some(value),
         "coverage-max-hotspots" => parsed.coverage_max_hotspots = Some(value),
+        "progress-heartbeat" => parsed.progress_heartbeat = Some(value),
+        "nice" => parsed.nice = Some(value),
+        "max-reported-failures" => parsed.max_reported_failures = Some(value),
         _ => {}
     }
     Ok(Some(used_next))
@@ -275,124 +483,3 @@ fn split_long_flag_token(token: &str) -> Option<(&str, Option<&str>)> {
     };
     Some((flag, Some(value)))
 }
-
-fn normalize_flag_name(flag: &str) -> &str {
-    match flag {
-        "keepArtifacts" => "keep-artifacts",
-        "coverage.abortOnFailure" => "coverage-abort-on-failure",
-        "coverageUi" => "coverage-ui",
-        "coverage.detail" => "coverage-detail",
-        "coverage.showCode" => "coverage-show-code",
-        "coverage.mode" => "coverage-mode",
-        "coverage.maxFiles" => "coverage-max-files",
-        "coverage.maxHotspots" => "coverage-max-hotspots",
-        "coverage.thresholds.lines" => "coverage-thresholds-lines",
-        "coverage.thresholds.functions" => "coverage-thresholds-functions",
-        "coverage.thresholds.branches" => "coverage-thresholds-branches",
-        "coverage.thresholds.statements" => "coverage-thresholds-statements",
-        "coverage.pageFit" => "coverage-page-fit",
-        "coverage.include" => "coverage-include",
-        "coverage.exclude" => "coverage-exclude",
-        "coverage.editor" => "coverage-editor",
-        "coverage.root" => "coverage-root",
-        "onlyFailures" => "only-failures",
-        "showLogs" => "show-logs",
-        "watchAll" => "watch-all",
-        "noCache" => "no-cache",
-        "bootstrapCommand" => "bootstrap-command",
-        "changed.depth" => "changed-depth",
-        "dependencyLanguage" => "dependency-language",
-        _ => flag,
-    }
-}
-
-fn parse_bool_text(text: &str) -> Option<bool> {
-    match text {
-        "true" => Some(true),
-        "false" => Some(false),
-        _ => None,
-    }
-}
-
-fn parse_bool_with_optional_value(
-    raw_value: Option<&str>,
-    next_token_text: &str,
-    has_next: bool,
-) -> Result<(bool, usize), HeadlampCliParseError> {
-    if let Some(value_text) = raw_value {
-        return parse_bool_text(value_text)
-            .map(|b| (b, 0))
-            .ok_or_else(|| HeadlampCliParseError {
-                message: format!("invalid bool value: {value_text}"),
-            });
-    }
-    if has_next {
-        if let Some(b) = parse_bool_text(next_token_text) {
-            return Ok((b, 1));
-        }
-    }
-    Ok((true, 0))
-}
-
-fn parse_string_value(
-    raw_value: Option<&str>,
-    next_token_text: &str,
-    has_next: bool,
-) -> Result<(String, usize), HeadlampCliParseError> {
-    if let Some(value_text) = raw_value {
-        return Ok((value_text.to_string(), 0));
-    }
-    if has_next && !next_token_text.starts_with("--") {
-        return Ok((next_token_text.to_string(), 1));
-    }
-    Err(HeadlampCliParseError {
-        message: "missing value".to_string(),
-    })
-}
-
-fn parse_optional_string_with_default(
-    raw_value: Option<&str>,
-    next_token_text: &str,
-    has_next: bool,
-    default_value: &str,
-) -> (String, usize) {
-    if let Some(value_text) = raw_value {
-        return (value_text.to_string(), 0);
-    }
-    if has_next && !next_token_text.starts_with("--") {
-        return (next_token_text.to_string(), 1);
-    }
-    (default_value.to_string(), 0)
-}
-
-fn parse_u32_value(
-    raw_value: Option<&str>,
-    next_token_text: &str,
-    has_next: bool,
-) -> Result<(u32, usize), HeadlampCliParseError> {
-    let (value_text, used_next) = parse_string_value(raw_value, next_token_text, has_next)?;
-    let value: u32 = value_text.parse().map_err(|_| HeadlampCliParseError {
-        message: format!("invalid u32 value: {value_text}"),
-    })?;
-    Ok((value, used_next))
-}
-
-fn parse_f64_value(
-    raw_value: Option<&str>,
-    next_token_text: &str,
-    has_next: bool,
-) -> Result<(f64, usize), HeadlampCliParseError> {
-    let (value_text, used_next) = parse_string_value(raw_value, next_token_text, has_next)?;
-    let value: f64 = value_text.parse().map_err(|_| HeadlampCliParseError {
-        message: format!("invalid f64 value: {value_text}"),
-    })?;
-    Ok((value, used_next))
-}
-
-fn extend_comma_delimited(out: &mut Vec<String>, value: &str) {
-    value
-        .split(',')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .for_each(|s| out.push(s));
-}
@@ -0,0 +1,92 @@
+use super::cli::HeadlampCliParseError;
+
+fn parse_bool_text(text: &str) -> Option<bool> {
+    match text {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+pub(super) fn parse_bool_with_optional_value(
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+) -> Result<(bool, usize), HeadlampCliParseError> {
+    if let Some(value_text) = raw_value {
+        return parse_bool_text(value_text)
+            .map(|b| (b, 0))
+            .ok_or_else(|| HeadlampCliParseError {
+                message: format!("invalid bool value: {value_text}"),
+            });
+    }
+    if has_next {
+        if let Some(b) = parse_bool_text(next_token_text) {
+            return Ok((b, 1));
+        }
+    }
+    Ok((true, 0))
+}
+
+pub(super) fn parse_string_value(
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+) -> Result<(String, usize), HeadlampCliParseError> {
+    if let Some(value_text) = raw_value {
+        return Ok((value_text.to_string(), 0));
+    }
+    if has_next && !next_token_text.starts_with("--") {
+        return Ok((next_token_text.to_string(), 1));
+    }
+    Err(HeadlampCliParseError {
+        message: "missing value".to_string(),
+    })
+}
+
+pub(super) fn parse_optional_string_with_default(
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+    default_value: &str,
+) -> (String, usize) {
+    if let Some(value_text) = raw_value {
+        return (value_text.to_string(), 0);
+    }
+    if has_next && !next_token_text.starts_with("--") {
+        return (next_token_text.to_string(), 1);
+    }
+    (default_value.to_string(), 0)
+}
+
+pub(super) fn parse_u32_value(
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+) -> Result<(u32, usize), HeadlampCliParseError> {
+    let (value_text, used_next) = parse_string_value(raw_value, next_token_text, has_next)?;
+    let value: u32 = value_text.parse().map_err(|_| HeadlampCliParseError {
+        message: format!("invalid u32 value: {value_text}"),
+    })?;
+    Ok((value, used_next))
+}
+
+pub(super) fn parse_f64_value(
+    raw_value: Option<&str>,
+    next_token_text: &str,
+    has_next: bool,
+) -> Result<(f64, usize), HeadlampCliParseError> {
+    let (value_text, used_next) = parse_string_value(raw_value, next_token_text, has_next)?;
+    let value: f64 = value_text.parse().map_err(|_| HeadlampCliParseError {
+        message: format!("invalid f64 value: {value_text}"),
+    })?;
+    Ok((value, used_next))
+}
+
+pub(super) fn extend_comma_delimited(out: &mut Vec<String>, value: &str) {
+    value
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .for_each(|s| out.push(s));
+}
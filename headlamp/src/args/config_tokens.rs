@@ -0,0 +1,431 @@
+use crate::config::{CoverageMode, CoverageUi, HeadlampConfig, LifecycleHooks, OutputFilterConfig};
+
+use super::helpers::{changed_mode_to_string, depth_for_mode, parse_changed_mode_string};
+
+pub fn config_tokens(cfg: &HeadlampConfig, argv: &[String]) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    append_basic_config_tokens(&mut tokens, cfg);
+    append_coverage_config_tokens(&mut tokens, cfg, argv);
+    append_changed_config_tokens(&mut tokens, cfg, argv);
+    tokens
+}
+
+fn append_basic_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig) {
+    trimmed(cfg.bootstrap_command.as_deref())
+        .into_iter()
+        .for_each(|cmd| tokens.push(format!("--bootstrap-command={cmd}")));
+    push_bool_flag(tokens, cfg.keep_artifacts == Some(true), "--keep-artifacts");
+    push_bool_flag(tokens, cfg.sequential == Some(true), "--sequential");
+    push_bool_flag(tokens, cfg.watch == Some(true), "--watch");
+    push_bool_flag(tokens, cfg.ci == Some(true), "--ci");
+    push_bool_flag(tokens, cfg.verbose == Some(true), "--verbose");
+    push_bool_flag(tokens, cfg.quiet == Some(true), "--quiet");
+    push_bool_flag(tokens, cfg.no_cache == Some(true), "--no-cache");
+    push_bool_flag(tokens, cfg.full_stacks == Some(true), "--full-stacks");
+    trimmed(cfg.log_level.as_deref())
+        .into_iter()
+        .for_each(|level| tokens.push(format!("--log-level={level}")));
+    push_bool_flag(tokens, cfg.log_json == Some(true), "--log-json");
+    push_bool_flag(tokens, cfg.deterministic == Some(true), "--deterministic");
+    push_bool_flag(
+        tokens,
+        cfg.normalize_times == Some(true),
+        "--normalize-times",
+    );
+    push_bool_flag(tokens, cfg.bail == Some(true), "--bail");
+    append_outcome_config_tokens(tokens, cfg);
+    trimmed(cfg.max_duration.as_deref())
+        .into_iter()
+        .for_each(|budget| tokens.push(format!("--max-duration={budget}")));
+    cfg.progress_heartbeat
+        .into_iter()
+        .for_each(|seconds| tokens.push(format!("--progress-heartbeat={seconds}")));
+    trimmed(cfg.max_memory.as_deref())
+        .into_iter()
+        .for_each(|size| tokens.push(format!("--max-memory={size}")));
+    cfg.nice
+        .into_iter()
+        .for_each(|increment| tokens.push(format!("--nice={increment}")));
+    trimmed(cfg.python.as_deref())
+        .into_iter()
+        .for_each(|path| tokens.push(format!("--python={path}")));
+    append_cargo_config_tokens(tokens, cfg);
+    cfg.projects
+        .iter()
+        .flatten()
+        .for_each(|project| tokens.push(format!("--project={project}")));
+    cfg.ignore_projects
+        .iter()
+        .flatten()
+        .for_each(|project| tokens.push(format!("--ignore-project={project}")));
+    append_hook_config_tokens(tokens, cfg.hooks.as_ref());
+    append_run_environment_config_tokens(tokens, cfg);
+    cfg.jest_esm
+        .into_iter()
+        .for_each(|mode| tokens.push(format!("--jest-esm={}", mode.as_str())));
+    cfg.typecheck
+        .into_iter()
+        .for_each(|mode| tokens.push(format!("--typecheck={}", mode.as_str())));
+    trimmed(cfg.typecheck_command.as_deref())
+        .into_iter()
+        .for_each(|command| tokens.push(format!("--typecheck-command={command}")));
+    trimmed(cfg.node.as_deref())
+        .into_iter()
+        .for_each(|path| tokens.push(format!("--node={path}")));
+    cfg.jest_args
+        .as_ref()
+        .filter(|a| !a.is_empty())
+        .into_iter()
+        .flat_map(|args| args.iter())
+        .cloned()
+        .for_each(|arg| tokens.push(arg));
+}
+
+/// What the end of a run reports and which outcomes fail it.
+fn append_outcome_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig) {
+    push_bool_flag(tokens, cfg.summary_line == Some(true), "--summary-line");
+    push_bool_flag(tokens, cfg.check_update == Some(true), "--check-update");
+    push_bool_flag(tokens, cfg.show_skipped == Some(true), "--show-skipped");
+    push_bool_flag(
+        tokens,
+        cfg.show_skipped_projects == Some(true),
+        "--show-skipped-projects",
+    );
+    push_bool_flag(
+        tokens,
+        cfg.footer_breakdown == Some(true),
+        "--footer-breakdown",
+    );
+    cfg.group_by
+        .into_iter()
+        .for_each(|group_by| tokens.push(format!("--group-by={}", group_by.as_str())));
+    cfg.path_style
+        .into_iter()
+        .for_each(|style| tokens.push(format!("--path-style={}", style.as_str())));
+    cfg.strip_path_prefixes
+        .iter()
+        .flatten()
+        .for_each(|glob| tokens.push(format!("--strip-path-prefix={glob}")));
+    cfg.max_reported_failures
+        .into_iter()
+        .for_each(|max| tokens.push(format!("--max-reported-failures={max}")));
+    push_bool_flag(
+        tokens,
+        cfg.fail_on_skipped == Some(true),
+        "--fail-on-skipped",
+    );
+    push_bool_flag(
+        tokens,
+        cfg.fail_if_no_tests == Some(true),
+        "--fail-if-no-tests",
+    );
+    push_bool_flag(
+        tokens,
+        cfg.fail_on_untested_changes == Some(true),
+        "--fail-on-untested-changes",
+    );
+    push_bool_flag(
+        tokens,
+        cfg.strict_duplicates == Some(true),
+        "--strict-duplicates",
+    );
+}
+
+fn append_cargo_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig) {
+    trimmed(cfg.features.as_deref())
+        .into_iter()
+        .for_each(|features| tokens.push(format!("--features={features}")));
+    push_bool_flag(tokens, cfg.all_features == Some(true), "--all-features");
+    push_bool_flag(
+        tokens,
+        cfg.no_default_features == Some(true),
+        "--no-default-features",
+    );
+    trimmed(cfg.feature_matrix.as_deref())
+        .into_iter()
+        .for_each(|matrix| tokens.push(format!("--feature-matrix={matrix}")));
+    cfg.packages
+        .iter()
+        .flatten()
+        .for_each(|package| tokens.push(format!("--package={package}")));
+    cfg.exclude_packages
+        .iter()
+        .flatten()
+        .for_each(|package| tokens.push(format!("--exclude={package}")));
+    trimmed(cfg.target.as_deref())
+        .into_iter()
+        .for_each(|triple| tokens.push(format!("--target={triple}")));
+    trimmed(cfg.target_runner.as_deref())
+        .into_iter()
+        .for_each(|runner| tokens.push(format!("--target-runner={runner}")));
+    trimmed(cfg.cargo_profile.as_deref())
+        .into_iter()
+        .for_each(|profile| tokens.push(format!("--cargo-profile={profile}")));
+    trimmed(cfg.nextest_profile.as_deref())
+        .into_iter()
+        .for_each(|profile| tokens.push(format!("--nextest-profile={profile}")));
+    push_bool_flag(tokens, cfg.auto_install == Some(true), "--auto-install");
+}
+
+/// Readiness, environment, artifact and notification settings.
+fn append_run_environment_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig) {
+    cfg.wait_for
+        .iter()
+        .flatten()
+        .for_each(|target| tokens.push(format!("--wait-for={target}")));
+    trimmed(cfg.wait_timeout.as_deref())
+        .into_iter()
+        .for_each(|timeout| tokens.push(format!("--wait-timeout={timeout}")));
+    trimmed(cfg.env_file.as_deref())
+        .into_iter()
+        .for_each(|file| tokens.push(format!("--env-file={file}")));
+    cfg.env
+        .iter()
+        .flatten()
+        .for_each(|(key, value)| tokens.push(format!("--env={key}={value}")));
+    trimmed(cfg.artifacts_dir.as_deref())
+        .into_iter()
+        .for_each(|dir| tokens.push(format!("--artifacts-dir={dir}")));
+    cfg.artifact_globs
+        .iter()
+        .flatten()
+        .for_each(|glob| tokens.push(format!("--artifact-glob={glob}")));
+    cfg.output_filters
+        .iter()
+        .flatten()
+        .for_each(|filter| tokens.push(output_filter_token(filter)));
+    trimmed(cfg.badge_out.as_deref())
+        .into_iter()
+        .for_each(|dir| tokens.push(format!("--badge-out={dir}")));
+    cfg.notify
+        .iter()
+        .flatten()
+        .for_each(|target| tokens.push(format!("--notify={target}")));
+    cfg.checks.iter().flatten().for_each(|check| {
+        tokens.push(format!(
+            "--check={}:{}",
+            check.format.as_str(),
+            check.command
+        ))
+    });
+}
+
+fn append_coverage_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig, argv: &[String]) {
+    let argv_has_coverage = argv
+        .iter()
+        .any(|t| t == "--coverage" || t.starts_with("--coverage="));
+    let coverage_always_on = matches!(
+        cfg.coverage,
+        Some(crate::config::CoverageConfig::Bool(true))
+    );
+    let coverage_obj = match cfg.coverage {
+        Some(crate::config::CoverageConfig::Obj(ref obj)) => Some(obj),
+        _ => cfg.coverage_section.as_ref(),
+    };
+
+    if coverage_always_on && !argv_has_coverage {
+        tokens.push("--coverage".to_string());
+    }
+    if !(coverage_always_on || argv_has_coverage) {
+        return;
+    }
+
+    append_coverage_behavior_tokens(tokens, cfg, coverage_obj);
+    append_coverage_threshold_tokens(tokens, coverage_obj);
+    append_coverage_detail_token(tokens, cfg);
+}
+
+fn append_coverage_behavior_tokens(
+    tokens: &mut Vec<String>,
+    cfg: &HeadlampConfig,
+    coverage_obj: Option<&crate::config::CoverageSection>,
+) {
+    let abort = coverage_obj
+        .and_then(|o| o.abort_on_failure)
+        .or(cfg.coverage_abort_on_failure);
+    abort.into_iter().for_each(|v| {
+        tokens.push(format!("--coverage-abort-on-failure={}", bool_str(v)));
+    });
+    coverage_obj
+        .and_then(|o| o.native_thresholds)
+        .into_iter()
+        .for_each(|v| {
+            tokens.push(format!("--coverage-native-thresholds={}", bool_str(v)));
+        });
+
+    let mode = coverage_obj.and_then(|o| o.mode).or(cfg.coverage_mode);
+    mode.into_iter()
+        .for_each(|m| tokens.push(format!("--coverage-mode={}", coverage_mode_str(m))));
+
+    let page_fit = coverage_obj
+        .and_then(|o| o.page_fit)
+        .or(cfg.coverage_page_fit);
+    page_fit
+        .into_iter()
+        .for_each(|v| tokens.push(format!("--coverage-page-fit={}", bool_str(v))));
+
+    cfg.coverage_ui
+        .into_iter()
+        .for_each(|ui| tokens.push(format!("--coverage-ui={}", coverage_ui_str(ui))));
+    cfg.coverage_engine
+        .into_iter()
+        .for_each(|engine| tokens.push(format!("--coverage-engine={}", engine.as_str())));
+    cfg.coverage_provider
+        .into_iter()
+        .for_each(|provider| tokens.push(format!("--coverage-provider={}", provider.as_str())));
+    trimmed(cfg.editor_cmd.as_deref())
+        .into_iter()
+        .for_each(|editor| tokens.push(format!("--coverage-editor={editor}")));
+    cfg.include
+        .as_ref()
+        .filter(|v| !v.is_empty())
+        .into_iter()
+        .for_each(|include| tokens.push(format!("--coverage-include={}", include.join(","))));
+    cfg.exclude
+        .as_ref()
+        .filter(|v| !v.is_empty())
+        .into_iter()
+        .for_each(|exclude| tokens.push(format!("--coverage-exclude={}", exclude.join(","))));
+    cfg.coverage_max_files
+        .into_iter()
+        .for_each(|max_files| tokens.push(format!("--coverage-max-files={max_files}")));
+    cfg.coverage_max_hotspots
+        .into_iter()
+        .for_each(|max_hotspots| tokens.push(format!("--coverage-max-hotspots={max_hotspots}")));
+    cfg.coverage_show_code
+        .into_iter()
+        .for_each(|show| tokens.push(format!("--coverage-show-code={}", bool_str(show))));
+}
+
+fn append_coverage_threshold_tokens(
+    tokens: &mut Vec<String>,
+    coverage_obj: Option<&crate::config::CoverageSection>,
+) {
+    let Some(thresholds) = coverage_obj.and_then(|o| o.thresholds.as_ref()) else {
+        return;
+    };
+    thresholds
+        .lines
+        .into_iter()
+        .for_each(|v| tokens.push(format!("--coverage-thresholds-lines={v}")));
+    thresholds.functions.into_iter().for_each(|v| {
+        tokens.push(format!("--coverage-thresholds-functions={v}"));
+    });
+    thresholds.branches.into_iter().for_each(|v| {
+        tokens.push(format!("--coverage-thresholds-branches={v}"));
+    });
+    thresholds.statements.into_iter().for_each(|v| {
+        tokens.push(format!("--coverage-thresholds-statements={v}"));
+    });
+}
+
+fn append_coverage_detail_token(tokens: &mut Vec<String>, cfg: &HeadlampConfig) {
+    let Some(detail) = cfg.coverage_detail.as_ref() else {
+        return;
+    };
+    match detail {
+        serde_json::Value::String(s) if s == "all" => {
+            tokens.push("--coverage-detail=all".to_string())
+        }
+        serde_json::Value::String(s) if s == "auto" => {
+            tokens.push("--coverage-detail=auto".to_string())
+        }
+        serde_json::Value::Number(n) if n.as_u64().is_some() => {
+            tokens.push(format!("--coverage-detail={}", n.as_u64().unwrap()))
+        }
+        _ => {}
+    }
+}
+
+fn append_changed_config_tokens(tokens: &mut Vec<String>, cfg: &HeadlampConfig, argv: &[String]) {
+    let changed_from_cli = argv
+        .iter()
+        .find_map(|t| t.strip_prefix("--changed=").map(|s| s.to_string()))
+        .or_else(|| {
+            argv.iter()
+                .position(|t| t == "--changed")
+                .and_then(|idx| argv.get(idx + 1).cloned())
+        });
+    let (changed_obj, changed_mode_config) = match cfg.changed {
+        Some(crate::config::ChangedConfig::Obj(ref obj)) => (Some(obj), None),
+        Some(crate::config::ChangedConfig::Mode(mode)) => (None, Some(mode)),
+        None => (cfg.changed_section.as_ref(), None),
+    };
+
+    let active_changed_mode = changed_from_cli
+        .as_deref()
+        .and_then(parse_changed_mode_string)
+        .or(changed_mode_config);
+    let Some(mode) = active_changed_mode else {
+        return;
+    };
+
+    let default_depth = changed_obj.and_then(|o| o.depth);
+    let override_depth = changed_obj.and_then(|o| depth_for_mode(o, mode));
+    override_depth
+        .or(default_depth)
+        .into_iter()
+        .for_each(|depth| tokens.push(format!("--changed-depth={depth}")));
+    if changed_from_cli.is_none() {
+        tokens.push(format!("--changed={}", changed_mode_to_string(mode)));
+    }
+}
+
+fn push_bool_flag(tokens: &mut Vec<String>, should_push: bool, flag: &'static str) {
+    if should_push {
+        tokens.push(flag.to_string());
+    }
+}
+
+fn trimmed(value: Option<&str>) -> Option<&str> {
+    value.map(|s| s.trim()).filter(|s| !s.is_empty())
+}
+
+fn bool_str(value: bool) -> &'static str {
+    if value { "true" } else { "false" }
+}
+
+fn coverage_mode_str(mode: CoverageMode) -> &'static str {
+    match mode {
+        CoverageMode::Compact => "compact",
+        CoverageMode::Full => "full",
+        CoverageMode::Auto => "auto",
+    }
+}
+
+fn coverage_ui_str(ui: CoverageUi) -> &'static str {
+    match ui {
+        CoverageUi::Jest => "jest",
+        CoverageUi::Both => "both",
+    }
+}
+
+fn append_hook_config_tokens(tokens: &mut Vec<String>, hooks: Option<&LifecycleHooks>) {
+    let Some(hooks) = hooks else {
+        return;
+    };
+    [
+        ("pre-run", &hooks.pre_run),
+        ("post-run", &hooks.post_run),
+        ("pre-coverage", &hooks.pre_coverage),
+        ("on-failure", &hooks.on_failure),
+    ]
+    .into_iter()
+    .flat_map(|(event, commands)| commands.iter().map(move |cmd| (event, cmd)))
+    .filter(|(_, cmd)| !cmd.trim().is_empty())
+    .for_each(|(event, cmd)| tokens.push(format!("--hook-{event}={cmd}")));
+}
+
+fn output_filter_token(filter: &OutputFilterConfig) -> String {
+    match filter {
+        OutputFilterConfig::Pattern(pattern)
+        | OutputFilterConfig::Rule {
+            pattern,
+            collapse: false,
+        } => format!("--output-filter={pattern}"),
+        OutputFilterConfig::Rule {
+            pattern,
+            collapse: true,
+        } => format!("--output-collapse={pattern}"),
+    }
+}
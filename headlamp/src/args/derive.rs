@@ -1,15 +1,22 @@
 use indexmap::IndexSet;
 
-use crate::config::{ChangedMode, CoverageMode, CoverageThresholds, CoverageUi};
+use crate::config::{
+    CoverageEngine, CoverageMode, CoverageProvider, CoverageThresholds, CoverageUi, JestEsm,
+    Typecheck,
+};
+use crate::readiness::parse_duration_ms;
 use crate::selection::dependency_language::DependencyLanguageId;
 
 use super::cli::HeadlampCli;
 use super::helpers::{
+    coverage_detail_final, coverage_mode_final, exclude_globs_final, include_globs_final,
     infer_glob_from_selection_path, is_path_like, is_test_like_token, parse_changed_mode_string,
-    parse_coverage_detail, parse_coverage_mode, parse_coverage_ui,
+    parse_coverage_detail, parse_coverage_engine, parse_coverage_mode, parse_coverage_provider,
+    parse_coverage_ui, parse_debug, parse_group_by, parse_jest_esm, parse_path_style,
+    parse_print_commands, parse_resource_report, parse_shuffle, parse_typecheck,
 };
 use super::tokens::split_headlamp_tokens;
-use super::types::{CoverageDetail, DEFAULT_EXCLUDE, DEFAULT_INCLUDE, ParsedArgs};
+use super::types::{CoverageDetail, ParsedArgs};
 
 pub fn derive_args(cfg_tokens: &[String], argv: &[String], is_tty: bool) -> ParsedArgs {
     let tokens = combined_tokens(cfg_tokens, argv);
@@ -18,37 +25,6 @@ pub fn derive_args(cfg_tokens: &[String], argv: &[String], is_tty: bool) -> Pars
     derive_args_from_parsed_cli(parsed_cli, passthrough, is_tty)
 }
 
-#[derive(Debug)]
-struct CommonArgs {
-    collect_coverage: bool,
-    coverage_abort_on_failure: bool,
-    only_failures: bool,
-    show_logs: bool,
-    sequential: bool,
-    ci: bool,
-    watch: bool,
-    verbose: bool,
-    quiet: bool,
-    no_cache: bool,
-    keep_artifacts: bool,
-    bootstrap_command: Option<String>,
-    coverage_ui: CoverageUi,
-    include_globs: Vec<String>,
-    exclude_globs: Vec<String>,
-    editor_cmd: Option<String>,
-    workspace_root: Option<String>,
-    coverage_thresholds: Option<CoverageThresholds>,
-    coverage_detail: Option<CoverageDetail>,
-    coverage_show_code: bool,
-    coverage_mode: CoverageMode,
-    coverage_max_files: Option<u32>,
-    coverage_max_hotspots: Option<u32>,
-    coverage_page_fit: bool,
-    changed: Option<ChangedMode>,
-    changed_depth: Option<u32>,
-    dependency_language: Option<DependencyLanguageId>,
-}
-
 #[derive(Debug)]
 struct SelectionParse {
     selection_specified: bool,
@@ -74,18 +50,56 @@ fn derive_args_from_parsed_cli(
     build_parsed_args(common, selection)
 }
 
-fn parse_common_flags(parsed_cli: &HeadlampCli, is_tty: bool) -> CommonArgs {
-    let ci = parsed_cli.ci;
-    CommonArgs {
+fn max_memory_bytes_from_cli(parsed_cli: &HeadlampCli) -> Option<u64> {
+    parsed_cli
+        .max_memory
+        .as_deref()
+        .and_then(crate::process::parse_memory_size)
+}
+
+fn parse_common_flags(parsed_cli: &HeadlampCli, is_tty: bool) -> ParsedArgs {
+    let debug = parsed_cli.debug.as_deref().and_then(parse_debug);
+    ParsedArgs {
         collect_coverage: parsed_cli.coverage,
         coverage_abort_on_failure: parsed_cli.coverage_abort_on_failure,
+        coverage_native_thresholds: parsed_cli.coverage_native_thresholds,
         only_failures: parsed_cli.only_failures,
         show_logs: parsed_cli.show_logs,
-        sequential: parsed_cli.sequential,
-        ci,
-        watch: !ci && (parsed_cli.watch || parsed_cli.watch_all),
+        full_stacks: parsed_cli.full_stacks,
+        log_json: parsed_cli.log_json,
+        deterministic: parsed_cli.deterministic,
+        normalize_times: parsed_cli.normalize_times,
+        bail: parsed_cli.bail,
+        summary_line: parsed_cli.summary_line,
+        check_update: parsed_cli.check_update,
+        show_skipped: parsed_cli.show_skipped,
+        show_skipped_projects: parsed_cli.show_skipped_projects,
+        footer_breakdown: parsed_cli.footer_breakdown,
+        group_by: parsed_cli
+            .group_by
+            .as_deref()
+            .map(parse_group_by)
+            .unwrap_or_default(),
+        fail_on_skipped: parsed_cli.fail_on_skipped,
+        fail_if_no_tests: parsed_cli.fail_if_no_tests,
+        fail_on_untested_changes: parsed_cli.fail_on_untested_changes,
+        strict_duplicates: parsed_cli.strict_duplicates,
+        print_commands: parsed_cli
+            .print_commands
+            .as_deref()
+            .map(parse_print_commands),
+        debug,
+        resource_report: parsed_cli
+            .resource_report
+            .as_deref()
+            .and_then(parse_resource_report),
+        shuffle_seed: parsed_cli.shuffle.as_deref().and_then(parse_shuffle),
+        // One process, one test at a time: a debugger attaches to a single runner.
+        sequential: parsed_cli.sequential || debug.is_some(),
+        ci: parsed_cli.ci,
+        watch: !parsed_cli.ci && (parsed_cli.watch || parsed_cli.watch_all),
         verbose: parsed_cli.verbose,
-        quiet: parsed_cli.quiet,
+        quiet: parsed_cli.quiet || parsed_cli.deterministic,
         no_cache: parsed_cli.no_cache,
         keep_artifacts: parsed_cli.keep_artifacts,
         bootstrap_command: parsed_cli.bootstrap_command.clone(),
@@ -94,6 +108,7 @@ fn parse_common_flags(parsed_cli: &HeadlampCli, is_tty: bool) -> CommonArgs {
         exclude_globs: parsed_cli.coverage_exclude.clone(),
         editor_cmd: parsed_cli.coverage_editor.clone(),
         workspace_root: parsed_cli.coverage_root.clone(),
+        repo_root: parsed_cli.repo_root.clone(),
         coverage_thresholds: coverage_thresholds_from_cli(parsed_cli),
         coverage_detail: parsed_cli
             .coverage_detail
@@ -102,17 +117,101 @@ fn parse_common_flags(parsed_cli: &HeadlampCli, is_tty: bool) -> CommonArgs {
         coverage_show_code: parsed_cli.coverage_show_code.unwrap_or(is_tty),
         coverage_mode: coverage_mode_from_cli(parsed_cli),
         coverage_max_files: parsed_cli.coverage_max_files,
+        max_reported_failures: parsed_cli.max_reported_failures,
         coverage_max_hotspots: parsed_cli.coverage_max_hotspots,
         coverage_page_fit: parsed_cli.coverage_page_fit.unwrap_or(is_tty),
+        ..parse_runtime_flags(parsed_cli)
+    }
+}
+
+/// Flags for the runners and the run around them; `parse_common_flags` adds the rest.
+fn parse_runtime_flags(parsed_cli: &HeadlampCli) -> ParsedArgs {
+    ParsedArgs {
         changed: parsed_cli
             .changed
             .as_deref()
             .and_then(parse_changed_mode_string),
         changed_depth: parsed_cli.changed_depth,
+        progress_heartbeat: parsed_cli.progress_heartbeat,
+        max_memory_bytes: max_memory_bytes_from_cli(parsed_cli),
+        nice: parsed_cli.nice,
+        python: parsed_cli.python.clone(),
+        features: features_from_cli(parsed_cli),
+        all_features: parsed_cli.all_features,
+        no_default_features: parsed_cli.no_default_features,
+        feature_matrix: feature_matrix_from_cli(parsed_cli),
+        packages: parsed_cli.packages.clone(),
+        exclude_packages: parsed_cli.exclude_packages.clone(),
+        target: parsed_cli.target.clone(),
+        target_runner: parsed_cli.target_runner.clone(),
+        cargo_profile: parsed_cli.cargo_profile.clone(),
+        nextest_profile: parsed_cli.nextest_profile.clone(),
+        auto_install: parsed_cli.auto_install,
+        coverage_engine: coverage_engine_from_cli(parsed_cli),
+        coverage_provider: coverage_provider_from_cli(parsed_cli),
+        projects: parsed_cli.projects.clone(),
+        ignore_projects: parsed_cli.ignore_projects.clone(),
+        jest_esm: jest_esm_from_cli(parsed_cli),
+        typecheck: parsed_cli
+            .typecheck
+            .as_deref()
+            .map_or(Typecheck::Off, parse_typecheck),
+        typecheck_command: parsed_cli.typecheck_command.clone(),
+        node: parsed_cli.node.clone(),
+        hooks: parsed_cli.hooks.clone(),
+        wait_for: parsed_cli.wait_for.clone(),
+        wait_timeout_ms: wait_timeout_ms_from_cli(parsed_cli),
+        max_duration_ms: parsed_cli
+            .max_duration
+            .as_deref()
+            .and_then(parse_duration_ms),
+        env_vars: parsed_cli.env_vars.clone(),
+        env_files: parsed_cli.env_files.clone(),
+        artifacts_dir: parsed_cli.artifacts_dir.clone(),
+        artifact_globs: parsed_cli.artifact_globs.clone(),
+        output_filters: parsed_cli.output_filters.clone(),
+        output_collapse: parsed_cli.output_collapse.clone(),
+        badge_out: parsed_cli.badge_out.clone(),
+        rerun_failed: parsed_cli.rerun_failed.clone(),
+        remote: parsed_cli.remote.clone(),
+        notify: parsed_cli.notify.clone(),
+        checks: parsed_cli.checks.clone(),
+        presets: parsed_cli.presets.clone(),
+        log_level: parsed_cli.log_level.clone(),
         dependency_language: dependency_language_from_cli(parsed_cli),
+        path_style: parsed_cli
+            .path_style
+            .as_deref()
+            .map(parse_path_style)
+            .unwrap_or_default(),
+        strip_path_prefixes: parsed_cli.strip_path_prefixes.clone(),
+        ..ParsedArgs::default()
     }
 }
 
+fn wait_timeout_ms_from_cli(parsed_cli: &HeadlampCli) -> Option<u64> {
+    parsed_cli
+        .wait_timeout
+        .as_deref()
+        .and_then(parse_duration_ms)
+}
+
+fn features_from_cli(parsed_cli: &HeadlampCli) -> Vec<String> {
+    parsed_cli
+        .features
+        .as_deref()
+        .map(crate::cargo::features::parse_feature_list)
+        .unwrap_or_default()
+}
+
+fn feature_matrix_from_cli(parsed_cli: &HeadlampCli) -> Vec<Vec<String>> {
+    parsed_cli
+        .feature_matrix
+        .as_deref()
+        .map(crate::cargo::features::parse_feature_matrix)
+        .unwrap_or_default()
+}
+
 fn coverage_ui_from_cli(parsed_cli: &HeadlampCli) -> CoverageUi {
     parsed_cli
         .coverage_ui
@@ -121,6 +220,30 @@ fn coverage_ui_from_cli(parsed_cli: &HeadlampCli) -> CoverageUi {
         .unwrap_or(CoverageUi::Both)
 }
 
+fn coverage_engine_from_cli(parsed_cli: &HeadlampCli) -> CoverageEngine {
+    parsed_cli
+        .coverage_engine
+        .as_deref()
+        .map(parse_coverage_engine)
+        .unwrap_or(CoverageEngine::Auto)
+}
+
+fn coverage_provider_from_cli(parsed_cli: &HeadlampCli) -> CoverageProvider {
+    parsed_cli
+        .coverage_provider
+        .as_deref()
+        .map(parse_coverage_provider)
+        .unwrap_or_default()
+}
+
+fn jest_esm_from_cli(parsed_cli: &HeadlampCli) -> JestEsm {
+    parsed_cli
+        .jest_esm
+        .as_deref()
+        .map(parse_jest_esm)
+        .unwrap_or_default()
+}
+
 fn dependency_language_from_cli(parsed_cli: &HeadlampCli) -> Option<DependencyLanguageId> {
     parsed_cli
         .dependency_language
@@ -153,54 +276,39 @@ fn coverage_thresholds_from_cli(parsed_cli: &HeadlampCli) -> Option<CoverageThre
     })
 }
 
-fn build_parsed_args(common: CommonArgs, selection: SelectionParse) -> ParsedArgs {
-    let (include_globs_final, exclude_globs_final) = globs_final(&common, &selection);
-    let (coverage_detail, coverage_mode) = coverage_defaults(
-        common.coverage_detail,
-        common.coverage_mode,
-        selection.selection_specified,
-    );
-
+fn build_parsed_args(common: ParsedArgs, selection: SelectionParse) -> ParsedArgs {
+    let (include_globs, exclude_globs, coverage_detail, coverage_mode) =
+        coverage_finals(&common, &selection);
     ParsedArgs {
         runner_args: selection.runner_args,
-        selection_paths: selection
-            .selection_paths
-            .into_iter()
-            .collect::<IndexSet<_>>()
-            .into_iter()
-            .collect::<Vec<_>>(),
+        selection_paths: dedup_selection_paths(selection.selection_paths),
         selection_specified: selection.selection_specified,
-        keep_artifacts: common.keep_artifacts,
-        watch: common.watch,
-        ci: common.ci,
-        verbose: common.verbose,
-        quiet: common.quiet,
-        no_cache: common.no_cache,
-        collect_coverage: common.collect_coverage,
-        coverage_ui: common.coverage_ui,
-        coverage_abort_on_failure: common.coverage_abort_on_failure,
+        include_globs,
+        exclude_globs,
         coverage_detail,
-        coverage_show_code: common.coverage_show_code,
         coverage_mode,
-        coverage_max_files: common.coverage_max_files,
-        coverage_max_hotspots: common.coverage_max_hotspots,
-        coverage_page_fit: common.coverage_page_fit,
-        coverage_thresholds: common.coverage_thresholds,
-        include_globs: include_globs_final,
-        exclude_globs: exclude_globs_final,
-        editor_cmd: common.editor_cmd,
-        workspace_root: common.workspace_root,
-        only_failures: common.only_failures,
-        show_logs: common.show_logs,
-        sequential: common.sequential,
-        bootstrap_command: common.bootstrap_command,
-        changed: common.changed,
-        changed_depth: common.changed_depth,
-        dependency_language: common.dependency_language,
+        ..common
     }
 }
 
-fn globs_final(common: &CommonArgs, selection: &SelectionParse) -> (Vec<String>, Vec<String>) {
+fn dedup_selection_paths(selection_paths: Vec<String>) -> Vec<String> {
+    selection_paths
+        .into_iter()
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>()
+}
+
+/// Include and exclude globs, coverage detail and coverage mode once the selection is known.
+fn coverage_finals(
+    common: &ParsedArgs,
+    selection: &SelectionParse,
+) -> (
+    Vec<String>,
+    Vec<String>,
+    Option<CoverageDetail>,
+    CoverageMode,
+) {
     let inferred_from_selection = selection
         .selection_paths
         .iter()
@@ -216,17 +324,12 @@ fn globs_final(common: &CommonArgs, selection: &SelectionParse) -> (Vec<String>,
         inferred_from_selection,
     );
     let exclude = exclude_globs_final(&common.exclude_globs);
-    (include, exclude)
-}
-
-fn coverage_defaults(
-    coverage_detail: Option<CoverageDetail>,
-    coverage_mode: CoverageMode,
-    selection_specified: bool,
-) -> (Option<CoverageDetail>, CoverageMode) {
+    let selected = selection.selection_specified;
     (
-        coverage_detail_final(coverage_detail, selection_specified),
-        coverage_mode_final(coverage_mode, selection_specified),
+        include,
+        exclude,
+        coverage_detail_final(common.coverage_detail, selected),
+        coverage_mode_final(common.coverage_mode, selected),
     )
 }
 
@@ -238,8 +341,9 @@ fn parse_selection_from_passthrough(
     let mut selection_paths: Vec<String> = vec![];
     let mut runner_args: Vec<String> = vec![];
     let mut pending_value_for_runner_flag: Option<&'static str> = None;
+    let mut passthrough = passthrough.into_iter();
 
-    for tok in passthrough {
+    for tok in passthrough.by_ref() {
         let (next_pending, handled) = handle_passthrough_token(&tok, pending_value_for_runner_flag);
         if handled {
             selection_specified = true;
@@ -254,7 +358,8 @@ fn parse_selection_from_passthrough(
             continue;
         }
         if tok == "--" {
-            continue;
+            // Everything after `--` is the runner's, verbatim.
+            break;
         }
         if is_path_like(&tok) || is_test_like_token(&tok) {
             selection_specified = true;
@@ -264,6 +369,8 @@ fn parse_selection_from_passthrough(
         }
     }
 
+    runner_args.extend(passthrough);
+
     SelectionParse {
         selection_specified,
         selection_paths,
@@ -289,57 +396,3 @@ fn handle_passthrough_token(
     }
     (None, false)
 }
-
-fn include_globs_final(
-    include_globs: &[String],
-    selection_looks_like_test_path: bool,
-    inferred_from_selection: Vec<String>,
-) -> Vec<String> {
-    if !include_globs.is_empty() {
-        return include_globs.to_vec();
-    }
-    if selection_looks_like_test_path {
-        return DEFAULT_INCLUDE
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
-    }
-    if !inferred_from_selection.is_empty() {
-        inferred_from_selection
-            .into_iter()
-            .collect::<IndexSet<_>>()
-            .into_iter()
-            .collect::<Vec<_>>()
-    } else {
-        DEFAULT_INCLUDE
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>()
-    }
-}
-
-fn exclude_globs_final(exclude_globs: &[String]) -> Vec<String> {
-    if !exclude_globs.is_empty() {
-        exclude_globs.to_vec()
-    } else {
-        DEFAULT_EXCLUDE
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>()
-    }
-}
-
-fn coverage_detail_final(
-    coverage_detail: Option<CoverageDetail>,
-    selection_specified: bool,
-) -> Option<CoverageDetail> {
-    coverage_detail.or_else(|| selection_specified.then_some(CoverageDetail::Auto))
-}
-
-fn coverage_mode_final(coverage_mode: CoverageMode, selection_specified: bool) -> CoverageMode {
-    if matches!(coverage_mode, CoverageMode::Auto) && selection_specified {
-        CoverageMode::Compact
-    } else {
-        coverage_mode
-    }
-}
@@ -0,0 +1,124 @@
+use super::{FlagSpec, boolean, switch, value};
+
+pub(super) const FLAGS: &[FlagSpec] = &[
+    boolean(
+        "--coverage",
+        "",
+        "Enable coverage collection (runner-specific)",
+    ),
+    value("--coverage-ui", "=jest|both", "Coverage output mode").aliases(&["--coverageUi"]),
+    boolean(
+        "--coverage-abort-on-failure",
+        "",
+        "Exit on test failures without printing coverage",
+    )
+    .aliases(&["--coverage.abortOnFailure"]),
+    boolean(
+        "--coverage-native-thresholds",
+        "[=true|false]",
+        "Also enforce thresholds from jest, coverage.py and Cargo.toml configs",
+    )
+    .aliases(&["--coverage.nativeThresholds"]),
+    value(
+        "--coverage-detail",
+        "=<all|auto|n>",
+        "Coverage detail level",
+    )
+    .aliases(&["--coverage.detail"]),
+    boolean(
+        "--coverage-show-code",
+        "[=true|false]",
+        "Show code under failing lines (default: true in TTY)",
+    )
+    .aliases(&["--coverage.showCode"]),
+    value(
+        "--coverage-mode",
+        "=<auto|full|compact>",
+        "Coverage UI mode",
+    )
+    .aliases(&["--coverage.mode"]),
+    switch(
+        "--coverage-compact",
+        "",
+        "Shorthand for --coverage-mode=compact",
+    )
+    .aliases(&["--coverage.compact"]),
+    value(
+        "--coverage-max-files",
+        "=<n>",
+        "Max files shown in coverage output",
+    )
+    .aliases(&["--coverage.maxFiles"]),
+    value(
+        "--coverage-max-hotspots",
+        "=<n>",
+        "Max hotspots shown in coverage output",
+    )
+    .aliases(&["--coverage.maxHotspots"]),
+    value(
+        "--coverage-thresholds",
+        "=<spec>",
+        "Thresholds as metric=percent pairs, e.g. branches=80,lines=90",
+    ),
+    value(
+        "--coverage-thresholds-lines",
+        "=<n>",
+        "Minimum line coverage threshold (0.0-1.0)",
+    )
+    .aliases(&["--coverage.thresholds.lines"]),
+    value(
+        "--coverage-thresholds-functions",
+        "=<n>",
+        "Minimum function coverage threshold (0.0-1.0)",
+    )
+    .aliases(&["--coverage.thresholds.functions"]),
+    value(
+        "--coverage-thresholds-branches",
+        "=<n>",
+        "Minimum branch coverage threshold (0.0-1.0)",
+    )
+    .aliases(&["--coverage.thresholds.branches"]),
+    value(
+        "--coverage-thresholds-statements",
+        "=<n>",
+        "Minimum statement coverage threshold (0.0-1.0)",
+    )
+    .aliases(&["--coverage.thresholds.statements"]),
+    boolean(
+        "--coverage-page-fit",
+        "[=true|false]",
+        "Fit coverage output to terminal width (default: true in TTY)",
+    )
+    .aliases(&["--coverage.pageFit"]),
+    value(
+        "--coverage-include",
+        "=<glob,...>",
+        "Include globs for coverage (comma-separated)",
+    )
+    .aliases(&["--coverage.include"]),
+    value(
+        "--coverage-exclude",
+        "=<glob,...>",
+        "Exclude globs for coverage (comma-separated)",
+    )
+    .aliases(&["--coverage.exclude"]),
+    value(
+        "--coverage-editor",
+        "=<cmd>",
+        "Editor command for file links",
+    )
+    .aliases(&["--coverage.editor"]),
+    value("--coverage-root", "=<path>", "Workspace root override").aliases(&["--coverage.root"]),
+    value(
+        "--coverage-engine",
+        "=<auto|instrument|llvm-cov>",
+        "Rust coverage backend (default: auto, the best available)",
+    )
+    .aliases(&["--coverageEngine"]),
+    value(
+        "--coverage-provider",
+        "=<babel|v8>",
+        "JS coverage source (default: babel; v8 skips instrumentation)",
+    )
+    .aliases(&["--coverageProvider"]),
+];
@@ -0,0 +1,146 @@
+//! Every headlamp flag in one table. Token splitting, legacy-name normalization and
+//! `headlamp help` all read it, so the help can't drift from what parses.
+
+mod coverage;
+mod output;
+mod run;
+mod runners;
+mod selection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagKind {
+    /// `--flag`, `--flag=false` or `--flag false`.
+    Bool,
+    /// `--flag value` or `--flag=value`.
+    Value,
+    /// `--flag` or `--flag=value`; never takes the next token.
+    Switch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    Selection,
+    Coverage,
+    Runners,
+    Output,
+    Run,
+}
+
+impl HelpTopic {
+    pub const ALL: [HelpTopic; 5] = [
+        HelpTopic::Selection,
+        HelpTopic::Coverage,
+        HelpTopic::Runners,
+        HelpTopic::Output,
+        HelpTopic::Run,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HelpTopic::Selection => "selection",
+            HelpTopic::Coverage => "coverage",
+            HelpTopic::Runners => "runners",
+            HelpTopic::Output => "output",
+            HelpTopic::Run => "run",
+        }
+    }
+
+    pub fn summary(self) -> &'static str {
+        match self {
+            HelpTopic::Selection => "Which tests run: --changed, packages, projects, reruns",
+            HelpTopic::Coverage => "Collecting coverage, thresholds and how the report looks",
+            HelpTopic::Runners => "Picking a runner and its toolchain: node, python, cargo",
+            HelpTopic::Output => "What gets printed, logged and kept after a run",
+            HelpTopic::Run => "Run control: bootstrap, env, time limits, hooks, checks",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        HelpTopic::ALL
+            .into_iter()
+            .find(|topic| topic.name() == name)
+    }
+
+    pub fn flags(self) -> &'static [FlagSpec] {
+        match self {
+            HelpTopic::Selection => selection::FLAGS,
+            HelpTopic::Coverage => coverage::FLAGS,
+            HelpTopic::Runners => runners::FLAGS,
+            HelpTopic::Output => output::FLAGS,
+            HelpTopic::Run => run::FLAGS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagSpec {
+    pub name: &'static str,
+    /// Legacy spellings (camelCase, dotted) that parse as `name`.
+    pub aliases: &'static [&'static str],
+    /// Shown in help only; the short form is expanded before parsing.
+    pub short: Option<&'static str>,
+    pub kind: FlagKind,
+    /// What help prints after the name, e.g. `=<n>` or `[=true|false]`.
+    pub value: &'static str,
+    pub help: &'static str,
+}
+
+impl FlagSpec {
+    const fn aliases(self, aliases: &'static [&'static str]) -> Self {
+        FlagSpec { aliases, ..self }
+    }
+
+    const fn short(self, short: &'static str) -> Self {
+        FlagSpec {
+            short: Some(short),
+            ..self
+        }
+    }
+
+    pub fn spellings(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name).chain(self.aliases.iter().copied())
+    }
+}
+
+const fn flag(
+    kind: FlagKind,
+    name: &'static str,
+    value: &'static str,
+    help: &'static str,
+) -> FlagSpec {
+    FlagSpec {
+        name,
+        aliases: &[],
+        short: None,
+        kind,
+        value,
+        help,
+    }
+}
+
+const fn boolean(name: &'static str, value: &'static str, help: &'static str) -> FlagSpec {
+    flag(FlagKind::Bool, name, value, help)
+}
+
+const fn value(name: &'static str, value: &'static str, help: &'static str) -> FlagSpec {
+    flag(FlagKind::Value, name, value, help)
+}
+
+const fn switch(name: &'static str, value: &'static str, help: &'static str) -> FlagSpec {
+    flag(FlagKind::Switch, name, value, help)
+}
+
+pub fn all_flags() -> impl Iterator<Item = &'static FlagSpec> {
+    HelpTopic::ALL.into_iter().flat_map(HelpTopic::flags)
+}
+
+/// The canonical name (without `--`) for `flag`, which may be a legacy spelling.
+pub(super) fn canonical_name(flag: &str) -> &str {
+    all_flags()
+        .find(|spec| {
+            spec.aliases
+                .iter()
+                .any(|alias| alias.strip_prefix("--") == Some(flag))
+        })
+        .map_or(flag, |spec| spec.name.trim_start_matches("--"))
+}
@@ -0,0 +1,171 @@
+use super::{FlagSpec, boolean, switch, value};
+
+pub(super) const FLAGS: &[FlagSpec] = &[
+    boolean(
+        "--only-failures",
+        "[=true|false]",
+        "Show only failing tests during live output",
+    )
+    .aliases(&["--onlyFailures"]),
+    boolean(
+        "--show-logs",
+        "[=true|false]",
+        "Show full logs under failing tests",
+    )
+    .aliases(&["--showLogs"]),
+    boolean(
+        "--full-stacks",
+        "[=true|false]",
+        "Show every stack frame (no node_modules/internal collapsing)",
+    )
+    .aliases(&["--fullStacks"]),
+    boolean(
+        "--show-skipped",
+        "[=true|false]",
+        "List skipped and todo tests with their reasons",
+    )
+    .aliases(&["--showSkipped"]),
+    boolean(
+        "--show-skipped-projects",
+        "[=true|false]",
+        "List jest projects and cargo packages skipped because no selected tests matched them",
+    )
+    .aliases(&["--showSkippedProjects"]),
+    boolean(
+        "--footer-breakdown",
+        "[=true|false]",
+        "Break the footer's totals down by jest project, cargo package and pytest rootdir",
+    )
+    .aliases(&["--footerBreakdown"]),
+    value(
+        "--group-by",
+        "=<file|suite|package>",
+        "Group results by test file (default), describe block/module, or owning package",
+    )
+    .aliases(&["--groupBy"]),
+    value(
+        "--path-style",
+        "=<relative|absolute|package-relative>",
+        "Show file paths relative to the repo root (default), absolute, or relative to their \
+         package",
+    )
+    .aliases(&["--pathStyle"]),
+    value(
+        "--strip-path-prefix",
+        "=<glob>",
+        "Cut a leading <glob> like packages/*/src/ from shown file paths (repeatable)",
+    )
+    .aliases(&["--stripPathPrefix"]),
+    value(
+        "--max-reported-failures",
+        "=<n>",
+        "Detail only the first <n> failures; the rest get one line each (full details stay in \
+         the run's reports)",
+    )
+    .aliases(&["--maxReportedFailures"]),
+    boolean("--verbose", "[=true|false]", "More Headlamp diagnostics"),
+    boolean(
+        "--quiet",
+        "[=true|false]",
+        "Quiet mode (disable live progress output)",
+    ),
+    value(
+        "--log-level",
+        "=<level>",
+        "Headlamp's own diagnostics: error, warn, info, debug or trace (default: warn; \
+         --verbose means debug)",
+    )
+    .aliases(&["--logLevel"]),
+    boolean(
+        "--log-json",
+        "[=true|false]",
+        "Write diagnostics as JSON lines; HEADLAMP_LOG=selection=debug,jest=trace filters by area",
+    )
+    .aliases(&["--logJson"]),
+    boolean(
+        "--deterministic",
+        "[=true|false]",
+        "Reproducible output: <time> for durations, relative paths, stable order, no ANSI \
+         unless FORCE_COLOR (implies --quiet)",
+    ),
+    boolean(
+        "--normalize-times",
+        "[=true|false]",
+        "Render every duration as <time> and zero the timings in written JSON reports",
+    )
+    .aliases(&["--normalizeTimes"]),
+    value(
+        "--progress-heartbeat",
+        "=<secs>",
+        "Non-TTY \"still running\" heartbeat interval (default: 30, 0 disables)",
+    )
+    .aliases(&["--progressHeartbeat"]),
+    boolean(
+        "--summary-line",
+        "[=true|false]",
+        "End with one line for scripts: headlamp: result=fail tests=.. failed=.. skipped=.. \
+         duration=..",
+    )
+    .aliases(&["--summaryLine"]),
+    boolean(
+        "--check-update",
+        "[=true|false]",
+        "After the run, say when a newer release is out (checked at most daily; see \
+         headlamp self-update)",
+    )
+    .aliases(&["--checkUpdate"]),
+    switch(
+        "--print-commands",
+        "[=json]",
+        "Print every runner command (argv, cwd, env) instead of running it",
+    )
+    .aliases(&["--printCommands"]),
+    switch(
+        "--resource-report",
+        "[=json]",
+        "After the run, print each runner's peak memory, CPU time and process count",
+    )
+    .aliases(&["--resourceReport"]),
+    value(
+        "--notify",
+        "=<target>",
+        "On completion notify desktop, slack:<webhook> or cmd:<script> with counts, duration \
+         and failures (repeatable)",
+    ),
+    boolean(
+        "--keep-artifacts",
+        "[=true|false]",
+        "Keep test artifacts after run (default: false)",
+    )
+    .aliases(&["--keepArtifacts"]),
+    value(
+        "--artifacts-dir",
+        "=<path>",
+        "Collect coverage, reports and matched files into <path> with an index.json",
+    )
+    .aliases(&["--artifactsDir"]),
+    value(
+        "--artifact-glob",
+        "=<glob>",
+        "Also collect repo files matching <glob> into --artifacts-dir (repeatable)",
+    )
+    .aliases(&["--artifactGlob"]),
+    value(
+        "--output-filter",
+        "=<regex>",
+        "Drop output lines matching <regex> (repeatable)",
+    )
+    .aliases(&["--outputFilter"]),
+    value(
+        "--output-collapse",
+        "=<regex>",
+        "Show only the first output line matching <regex> (repeatable)",
+    )
+    .aliases(&["--outputCollapse"]),
+    value(
+        "--badge-out",
+        "=<dir>",
+        "Write tests.svg (and coverage.svg with --coverage) badges into <dir> after each run",
+    )
+    .aliases(&["--badgeOut"]),
+];
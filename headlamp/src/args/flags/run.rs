@@ -0,0 +1,113 @@
+use super::{FlagSpec, boolean, value};
+
+pub(super) const FLAGS: &[FlagSpec] = &[
+    boolean(
+        "--ci",
+        "[=true|false]",
+        "CI mode (disable interactive UI and set CI=1)",
+    ),
+    boolean(
+        "--bail",
+        "[=true|false]",
+        "Stop at the first failure: in the runner, and skip the runners and checks after it",
+    ),
+    boolean(
+        "--fail-on-skipped",
+        "[=true|false]",
+        "Fail the run when any test was skipped",
+    )
+    .aliases(&["--failOnSkipped"]),
+    boolean(
+        "--strict-duplicates",
+        "[=true|false]",
+        "Fail the run on duplicate test names or suite paths, not just warn",
+    )
+    .aliases(&["--strictDuplicates"]),
+    boolean(
+        "--no-cache",
+        "[=true|false]",
+        "Disable Headlamp caches, including reused coverage (and runner caches when possible)",
+    )
+    .aliases(&["--noCache"]),
+    value(
+        "--repo-root",
+        "=<path>",
+        "Treat <path> as the repo root (config, caches, shown paths) instead of the git toplevel",
+    )
+    .aliases(&["--repoRoot"]),
+    value(
+        "--bootstrap-command",
+        " <cmd>",
+        "Run once before tests (npm script name or shell cmd)",
+    )
+    .aliases(&["--bootstrapCommand"]),
+    value(
+        "--wait-for",
+        "=<target>",
+        "After bootstrap, wait until tcp:host:port, http://host/path or unix:/path is ready \
+         (repeatable)",
+    )
+    .aliases(&["--waitFor"]),
+    value(
+        "--wait-timeout",
+        "=<duration>",
+        "How long --wait-for waits, e.g. 90s or 2m (default: 60s)",
+    )
+    .aliases(&["--waitTimeout"]),
+    value(
+        "--max-duration",
+        "=<duration>",
+        "Time budget for the whole run, e.g. 90s or 2m; past it the run is stopped and fails \
+         (exit 124)",
+    )
+    .aliases(&["--maxDuration"]),
+    value(
+        "--env",
+        " KEY=VALUE",
+        "Set a variable for the runner, bootstrap and hook processes (repeatable)",
+    ),
+    value(
+        "--env-file",
+        "=<path>",
+        "Load KEY=VALUE lines from a dotenv file, relative to the repo root (repeatable; \
+         --env wins)",
+    )
+    .aliases(&["--envFile"]),
+    value(
+        "--check",
+        "=<format>:<cmd>",
+        "Report eslint, ruff or clippy JSON findings as failed tests; {files} gets the \
+         --changed files (repeatable)",
+    ),
+    value(
+        "--preset",
+        "=<name>",
+        "Apply the config's presets.<name> flags before the command line's (repeatable)",
+    ),
+    value(
+        "--hook-pre-run",
+        "=<cmd>",
+        "Shell command before each run (repeatable; config: hooks.preRun)",
+    ),
+    value(
+        "--hook-pre-coverage",
+        "=<cmd>",
+        "Shell command before each --coverage run (repeatable)",
+    ),
+    value(
+        "--hook-on-failure",
+        "=<cmd>",
+        "Shell command after a failed run (repeatable)",
+    ),
+    value(
+        "--hook-post-run",
+        "=<cmd>",
+        "Shell command after each run, even a failed one (repeatable)",
+    ),
+    value(
+        "--remote",
+        "=<executor-cmd>",
+        "Experimental: select locally, hand the planned run to <executor-cmd> and render the \
+         results it returns",
+    ),
+];
@@ -0,0 +1,129 @@
+use super::{FlagSpec, boolean, switch, value};
+
+pub(super) const FLAGS: &[FlagSpec] = &[
+    value(
+        "--runner",
+        "=<runner>",
+        "jest, pytest, headlamp, cargo-nextest, cargo-test or plugin:<name> (default: jest; with \
+         --changed, picked from the changed files)",
+    ),
+    boolean(
+        "--sequential",
+        "[=true|false]",
+        "Serialize execution (e.g. jest --runInBand)",
+    ),
+    switch(
+        "--debug",
+        "[=pdb|gdb|lldb]",
+        "Run for a debugger: jest under node --inspect-brk, pytest under debugpy (else --pdb), \
+         Rust test binaries printed or launched under rust-gdb/rust-lldb",
+    )
+    .aliases(&["--inspect"]),
+    switch(
+        "--shuffle",
+        "[=<seed>]",
+        "Run test files in random order (jest sequencer, pytest plugin, Rust test binaries and \
+         nightly libtest); the seed is printed in the footer, pass it back to reproduce",
+    ),
+    boolean(
+        "--watch",
+        "[=true|false]",
+        "Re-run on file changes (polling watch)",
+    ),
+    boolean(
+        "--watch-all",
+        "[=true|false]",
+        "Watch everything (runner-specific)",
+    )
+    .aliases(&["--watchAll"]),
+    value(
+        "--max-memory",
+        "=<size>",
+        "Memory cap per runner subprocess, e.g. 2G or 512M (unix only)",
+    )
+    .aliases(&["--maxMemory"]),
+    value(
+        "--nice",
+        "=<n>",
+        "Lower runner subprocess CPU priority by n, 0-19 (unix only)",
+    ),
+    value(
+        "--python",
+        "=<path>",
+        "Python interpreter for pytest runs (default: project venv)",
+    ),
+    value(
+        "--features",
+        "=<a,b>",
+        "Cargo features to enable for Rust runs",
+    ),
+    boolean(
+        "--all-features",
+        "",
+        "Enable all Cargo features for Rust runs",
+    )
+    .aliases(&["--allFeatures"]),
+    boolean(
+        "--no-default-features",
+        "",
+        "Disable default Cargo features for Rust runs",
+    )
+    .aliases(&["--noDefaultFeatures"]),
+    value(
+        "--feature-matrix",
+        "=<a,b;c>",
+        "Run Rust tests once per feature set (';' separates sets)",
+    )
+    .aliases(&["--featureMatrix"]),
+    value(
+        "--target",
+        "=<triple>",
+        "Cross-compile Rust tests for the given target triple",
+    ),
+    value(
+        "--target-runner",
+        "=<cmd>",
+        "Wrapper for running --target test binaries (e.g. qemu-aarch64)",
+    )
+    .aliases(&["--targetRunner"]),
+    value(
+        "--cargo-profile",
+        "=<name>",
+        "Cargo profile for building Rust tests (e.g. release)",
+    )
+    .aliases(&["--cargoProfile"]),
+    value(
+        "--nextest-profile",
+        "=<name>",
+        "nextest config profile (.config/nextest.toml); its retries mark tests flaky",
+    )
+    .aliases(&["--nextestProfile"]),
+    boolean(
+        "--auto-install",
+        "",
+        "Install missing Rust tooling (cargo-nextest, llvm-tools) without prompting",
+    )
+    .aliases(&["--autoInstall"]),
+    value(
+        "--jest-esm",
+        "=<auto|on|off>",
+        "Run jest with node --experimental-vm-modules (default: auto, for ESM projects)",
+    )
+    .aliases(&["--jestEsm"]),
+    switch(
+        "--typecheck",
+        "[=gate|warn]",
+        "Run tsc --noEmit for the selected files' projects before jest (bare: gate)",
+    ),
+    value(
+        "--typecheck-command",
+        "=<cmd>",
+        "Replaces tsc --noEmit; gets -p tsconfig.json --pretty false",
+    )
+    .aliases(&["--typecheckCommand"]),
+    value(
+        "--node",
+        "=<path>",
+        "Node binary for jest runs (default: .nvmrc/volta pin, else PATH)",
+    ),
+];
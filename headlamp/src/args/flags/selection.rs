@@ -0,0 +1,62 @@
+use super::{FlagSpec, boolean, value};
+
+pub(super) const FLAGS: &[FlagSpec] = &[
+    value(
+        "--changed",
+        "[=<mode>]",
+        "Run the tests related to changed files: all (default), staged, unstaged, branch, \
+         lastCommit or lastRelease",
+    ),
+    value(
+        "--changed-depth",
+        "=<n>",
+        "Max dependency depth for changed selection",
+    )
+    .aliases(&["--changed.depth"]),
+    value(
+        "--dependency-language",
+        "=<tsjs|rust>",
+        "Dependency language for selection (where applicable)",
+    )
+    .aliases(&["--dependencyLanguage"]),
+    value(
+        "--package",
+        "=<name>",
+        "Workspace package(s) to test for Rust runs (repeatable)",
+    )
+    .short("-p"),
+    value(
+        "--exclude",
+        "=<name>",
+        "Workspace package(s) to skip for Rust runs (repeatable)",
+    ),
+    value(
+        "--project",
+        "=<name|path>",
+        "Jest project(s) to run, by displayName or config path (repeatable)",
+    ),
+    value(
+        "--ignore-project",
+        "=<name|path>",
+        "Jest project(s) to skip (repeatable)",
+    )
+    .aliases(&["--ignoreProject"]),
+    value(
+        "--rerun-failed",
+        "=<report.json>",
+        "Re-run only the tests that failed in a saved JSON report or --artifacts-dir index.json",
+    )
+    .aliases(&["--rerunFailed"]),
+    boolean(
+        "--fail-if-no-tests",
+        "[=true|false]",
+        "Exit 4, naming the selection inputs, when no test ran",
+    )
+    .aliases(&["--failIfNoTests"]),
+    boolean(
+        "--fail-on-untested-changes",
+        "[=true|false]",
+        "With --changed, fail the run when a changed source file has no related tests",
+    )
+    .aliases(&["--failOnUntestedChanges"]),
+];
@@ -2,20 +2,32 @@ use std::borrow::Cow;
 use std::path::Path;
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use indexmap::IndexSet;
 use std::sync::LazyLock;
 
-use crate::config::{ChangedMode, CoverageMode, CoverageUi};
+use crate::config::{
+    ChangedMode, CoverageEngine, CoverageMode, CoverageProvider, CoverageUi, GroupBy, JestEsm,
+    Typecheck,
+};
 
-use super::types::CoverageDetail;
+use crate::debug::Debugger;
+use crate::format::path_display::PathStyle;
+use crate::print_commands::PrintCommandsFormat;
+use crate::resource_report::ResourceReportFormat;
+
+use super::types::{CoverageDetail, DEFAULT_EXCLUDE, DEFAULT_INCLUDE};
 
 static TEST_LIKE_GLOBSET: LazyLock<GlobSet> = LazyLock::new(|| {
     let mut b = GlobSetBuilder::new();
-    ["**/tests/**", "**/*.{test,spec}.{ts,tsx,js,jsx}"]
-        .into_iter()
-        .filter_map(|g| Glob::new(g).ok())
-        .for_each(|g| {
-            b.add(g);
-        });
+    [
+        "**/tests/**",
+        "**/*.{test,spec}.{ts,tsx,js,jsx,mjs,cjs,mts,cts}",
+    ]
+    .into_iter()
+    .filter_map(|g| Glob::new(g).ok())
+    .for_each(|g| {
+        b.add(g);
+    });
     b.build().unwrap_or_else(|_| GlobSet::empty())
 });
 
@@ -26,6 +38,101 @@ pub(super) fn parse_coverage_ui(raw: &str) -> CoverageUi {
     }
 }
 
+pub(super) fn parse_coverage_provider(raw: &str) -> CoverageProvider {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "v8" => CoverageProvider::V8,
+        _ => CoverageProvider::Babel,
+    }
+}
+
+pub(super) fn parse_group_by(raw: &str) -> GroupBy {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "suite" | "describe" => GroupBy::Suite,
+        "package" => GroupBy::Package,
+        _ => GroupBy::File,
+    }
+}
+
+pub(super) fn parse_path_style(raw: &str) -> PathStyle {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "absolute" => PathStyle::Absolute,
+        "package-relative" | "package" => PathStyle::PackageRelative,
+        _ => PathStyle::Relative,
+    }
+}
+
+pub(super) fn parse_jest_esm(raw: &str) -> JestEsm {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "on" | "true" | "1" => JestEsm::On,
+        "off" | "false" | "0" => JestEsm::Off,
+        _ => JestEsm::Auto,
+    }
+}
+
+pub(super) fn parse_typecheck(raw: &str) -> Typecheck {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "gate" | "true" | "1" => Typecheck::Gate,
+        "warn" => Typecheck::Warn,
+        _ => Typecheck::Off,
+    }
+}
+
+pub(super) fn parse_debug(raw: &str) -> Option<Debugger> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "false" | "off" | "0" => None,
+        "pdb" => Some(Debugger::Pdb),
+        "gdb" => Some(Debugger::Gdb),
+        "lldb" => Some(Debugger::Lldb),
+        _ => Some(Debugger::Auto),
+    }
+}
+
+pub(super) fn parse_print_commands(raw: &str) -> PrintCommandsFormat {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "json" => PrintCommandsFormat::Json,
+        _ => PrintCommandsFormat::Human,
+    }
+}
+
+pub(super) fn parse_resource_report(raw: &str) -> Option<ResourceReportFormat> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "false" | "off" | "0" => None,
+        "json" => Some(ResourceReportFormat::Json),
+        _ => Some(ResourceReportFormat::Human),
+    }
+}
+
+/// A number is the seed to reproduce; a bare `--shuffle` picks a fresh one.
+pub(super) fn parse_shuffle(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    if let Ok(seed) = raw.parse::<u64>() {
+        return Some(seed);
+    }
+    match raw.to_ascii_lowercase().as_str() {
+        "false" | "off" => None,
+        _ => Some(crate::shuffle::random_seed()),
+    }
+}
+
+pub(super) fn parse_coverage_engine(raw: &str) -> CoverageEngine {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "instrument" => CoverageEngine::Instrument,
+        "llvm-cov" | "llvmcov" | "cargo-llvm-cov" => CoverageEngine::LlvmCov,
+        _ => CoverageEngine::Auto,
+    }
+}
+
+/// Splits `branches=80,lines=90` into lower-cased metric names and values.
+pub(super) fn parse_threshold_spec(spec: &str) -> Vec<(String, f64)> {
+    spec.split([',', ' '])
+        .filter_map(|pair| {
+            let (metric, value) = pair.split_once('=')?;
+            let value = value.trim().trim_end_matches('%').parse::<f64>().ok()?;
+            Some((metric.trim().to_ascii_lowercase(), value))
+        })
+        .collect()
+}
+
 pub(super) fn parse_coverage_detail(raw: &str) -> Option<CoverageDetail> {
     match raw.trim().to_ascii_lowercase().as_str() {
         "all" => Some(CoverageDetail::All),
@@ -130,3 +237,87 @@ pub(super) fn normalize_token_path_text(candidate: &str) -> Cow<'_, str> {
         Cow::Borrowed(candidate)
     }
 }
+
+/// Rewrites cargo's `-p <name>` / `-p<name>` into `--package=<name>`. Only applied for Rust
+/// runners, since `-p` means something else to pytest (plugins).
+pub fn expand_short_package_flag(argv: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    let mut index = 0usize;
+    while index < argv.len() {
+        let token = argv[index].as_str();
+        if token == "--" {
+            out.extend(argv[index..].iter().cloned());
+            break;
+        }
+        match (token, argv.get(index + 1)) {
+            ("-p", Some(name)) => {
+                out.push(format!("--package={name}"));
+                index += 2;
+                continue;
+            }
+            _ if token.starts_with("-p") && token.len() > 2 => {
+                out.push(format!("--package={}", &token[2..]));
+            }
+            _ => out.push(token.to_string()),
+        }
+        index += 1;
+    }
+    out
+}
+
+pub(super) fn include_globs_final(
+    include_globs: &[String],
+    selection_looks_like_test_path: bool,
+    inferred_from_selection: Vec<String>,
+) -> Vec<String> {
+    if !include_globs.is_empty() {
+        return include_globs.to_vec();
+    }
+    if selection_looks_like_test_path {
+        return DEFAULT_INCLUDE
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+    }
+    if !inferred_from_selection.is_empty() {
+        inferred_from_selection
+            .into_iter()
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+    } else {
+        DEFAULT_INCLUDE
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+    }
+}
+
+pub(super) fn exclude_globs_final(exclude_globs: &[String]) -> Vec<String> {
+    if !exclude_globs.is_empty() {
+        exclude_globs.to_vec()
+    } else {
+        DEFAULT_EXCLUDE
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+    }
+}
+
+pub(super) fn coverage_detail_final(
+    coverage_detail: Option<CoverageDetail>,
+    selection_specified: bool,
+) -> Option<CoverageDetail> {
+    coverage_detail.or_else(|| selection_specified.then_some(CoverageDetail::Auto))
+}
+
+pub(super) fn coverage_mode_final(
+    coverage_mode: CoverageMode,
+    selection_specified: bool,
+) -> CoverageMode {
+    if matches!(coverage_mode, CoverageMode::Auto) && selection_specified {
+        CoverageMode::Compact
+    } else {
+        coverage_mode
+    }
+}
@@ -1,11 +1,19 @@
 mod cli;
+mod cli_values;
+mod config_tokens;
 mod derive;
+mod flags;
 mod helpers;
+mod passthrough;
+mod presets;
 mod tokens;
 mod types;
 
+pub use config_tokens::config_tokens;
 pub use derive::derive_args;
-pub use tokens::config_tokens;
-#[cfg(test)]
-pub(crate) use tokens::split_headlamp_tokens;
+pub use flags::{FlagKind, FlagSpec, HelpTopic, all_flags};
+pub use helpers::expand_short_package_flag;
+pub use passthrough::{translate_runner_args, validate_runner_passthrough};
+pub use presets::expand_presets;
+pub use tokens::split_headlamp_tokens;
 pub use types::{CoverageDetail, DEFAULT_EXCLUDE, DEFAULT_INCLUDE, ParsedArgs};
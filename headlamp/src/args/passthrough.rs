@@ -0,0 +1,134 @@
+use crate::rerun::RerunRunner;
+
+use super::tokens::is_headlamp_flag;
+
+/// Test-name filters every runner accepts after `--`; each runner gets its own spelling.
+const NAME_FILTER_ALIASES: &[&str] = &["-t", "-k", "--grep", "--testNamePattern"];
+
+/// libtest flags that `cargo test` only understands after its own `--`.
+const LIBTEST_FLAGS: &[&str] = &[
+    "--nocapture",
+    "--no-capture",
+    "--show-output",
+    "--exact",
+    "--ignored",
+    "--include-ignored",
+];
+
+/// libtest flags that take a value.
+const LIBTEST_VALUE_FLAGS: &[&str] = &["--test-threads", "--skip"];
+
+/// `headlamp [headlamp flags] -- [runner args]`: everything after the first `--` goes to the
+/// runner as is, so a headlamp flag there would be silently lost. Past a second `--` the tokens
+/// belong to the test binary and are not checked.
+pub fn validate_runner_passthrough(argv: &[String]) -> Result<(), String> {
+    let Some(separator) = argv.iter().position(|token| token == "--") else {
+        return Ok(());
+    };
+    let runner_args = &argv[separator + 1..];
+    let end = runner_args
+        .iter()
+        .position(|token| token == "--")
+        .unwrap_or(runner_args.len());
+    match (0..end).find(|&index| is_headlamp_flag(runner_args, index)) {
+        Some(index) => Err(format!(
+            "`{}` is a headlamp flag but comes after `--`, which hands it to the runner; \
+             move it before `--`",
+            runner_args[index]
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Rewrites the common aliases in `runner_args` into what `runner` understands: a test-name
+/// filter given as `-t`, `-k`, `--grep` or `--testNamePattern` becomes jest's `-t`, pytest's
+/// `-k` or libtest's positional filter, and libtest flags move behind `cargo test`'s `--`.
+pub fn translate_runner_args(runner: RerunRunner, runner_args: &[String]) -> Vec<String> {
+    match runner {
+        RerunRunner::Jest | RerunRunner::Plugin => rewrite_name_filters(runner_args, Some("-t")),
+        RerunRunner::Pytest => rewrite_name_filters(runner_args, Some("-k")),
+        RerunRunner::Headlamp => rewrite_name_filters(runner_args, None),
+        RerunRunner::CargoTest | RerunRunner::CargoNextest => move_libtest_args(runner_args),
+    }
+}
+
+/// `(value, tokens spanned)` for a name filter at `tokens[i]`, in either the
+/// `-k value` or the `--grep=value` form.
+fn name_filter_at(tokens: &[String], i: usize) -> Option<(&str, usize)> {
+    let token = tokens[i].as_str();
+    NAME_FILTER_ALIASES.iter().find_map(|alias| {
+        if token == *alias {
+            return tokens.get(i + 1).map(|value| (value.as_str(), 2));
+        }
+        token
+            .strip_prefix(alias)
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(|value| (value, 1))
+    })
+}
+
+/// Each name filter becomes `spelling value`, or just `value` without a spelling.
+fn rewrite_name_filters(runner_args: &[String], spelling: Option<&str>) -> Vec<String> {
+    let mut out = vec![];
+    let mut i = 0;
+    while i < runner_args.len() {
+        match name_filter_at(runner_args, i) {
+            Some((value, span)) => {
+                out.extend(spelling.map(str::to_string));
+                out.push(value.to_string());
+                i += span;
+            }
+            None => {
+                out.push(runner_args[i].clone());
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn move_libtest_args(runner_args: &[String]) -> Vec<String> {
+    let separator = runner_args.iter().position(|token| token == "--");
+    let (cargo_part, binary_part) = match separator {
+        Some(index) => (&runner_args[..index], &runner_args[index + 1..]),
+        None => (runner_args, &[][..]),
+    };
+    let mut kept = vec![];
+    let mut moved = vec![];
+    let mut i = 0;
+    while i < cargo_part.len() {
+        let span = libtest_span(cargo_part, i);
+        if let Some((value, span)) = name_filter_at(cargo_part, i) {
+            moved.push(value.to_string());
+            i += span;
+        } else if span > 0 {
+            moved.extend(cargo_part[i..i + span].iter().cloned());
+            i += span;
+        } else {
+            kept.push(cargo_part[i].clone());
+            i += 1;
+        }
+    }
+    if separator.is_some() || !moved.is_empty() {
+        kept.push("--".to_string());
+    }
+    kept.extend(moved);
+    kept.extend(binary_part.iter().cloned());
+    kept
+}
+
+/// How many tokens a libtest flag at `tokens[i]` spans, 0 when it is not one.
+fn libtest_span(tokens: &[String], i: usize) -> usize {
+    let token = tokens[i].as_str();
+    if LIBTEST_FLAGS.contains(&token) {
+        return 1;
+    }
+    match LIBTEST_VALUE_FLAGS
+        .iter()
+        .find(|flag| token.starts_with(*flag))
+    {
+        Some(flag) if token == *flag => (tokens.len() - i).min(2),
+        Some(flag) if token[flag.len()..].starts_with('=') => 1,
+        _ => 0,
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use crate::config::schema::did_you_mean;
+
+/// `argv` with the flags of each `--preset <name>` it names, in order, placed in front of it so
+/// that flags given on the command line win. A preset may name other presets the same way.
+pub fn expand_presets(
+    presets: Option<&BTreeMap<String, Vec<String>>>,
+    argv: &[String],
+) -> Result<Vec<String>, String> {
+    let empty = BTreeMap::new();
+    let presets = presets.unwrap_or(&empty);
+    let mut expanded = vec![];
+    for name in preset_names(argv) {
+        expand_into(presets, &name, &mut vec![], &mut expanded)?;
+    }
+    expanded.extend(argv.iter().cloned());
+    Ok(expanded)
+}
+
+fn expand_into(
+    presets: &BTreeMap<String, Vec<String>>,
+    name: &str,
+    chain: &mut Vec<String>,
+    out: &mut Vec<String>,
+) -> Result<(), String> {
+    if chain.iter().any(|seen| seen == name) {
+        chain.push(name.to_string());
+        return Err(format!("preset cycle: {}", chain.join(" -> ")));
+    }
+    let Some(flags) = presets.get(name) else {
+        return Err(unknown_preset(presets, name));
+    };
+    chain.push(name.to_string());
+    let nested = preset_names(flags);
+    for inner in &nested {
+        expand_into(presets, inner, chain, out)?;
+    }
+    out.extend(without_preset_flags(flags));
+    chain.pop();
+    Ok(())
+}
+
+/// The names given to `--preset`, `--preset=<name>` or `--preset <name>`, before any `--`.
+fn preset_names(tokens: &[String]) -> Vec<String> {
+    let mut names = vec![];
+    let mut iter = tokens.iter().take_while(|token| *token != "--");
+    while let Some(token) = iter.next() {
+        if let Some(name) = token.strip_prefix("--preset=") {
+            names.push(name.to_string());
+        } else if token == "--preset" {
+            names.extend(iter.next().cloned());
+        }
+    }
+    names
+}
+
+fn without_preset_flags(tokens: &[String]) -> Vec<String> {
+    let mut kept = vec![];
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        if token == "--preset" {
+            iter.next();
+        } else if !token.starts_with("--preset=") {
+            kept.push(token.clone());
+        }
+    }
+    kept
+}
+
+fn unknown_preset(presets: &BTreeMap<String, Vec<String>>, name: &str) -> String {
+    let known = presets.keys().map(String::as_str);
+    match did_you_mean(name, known) {
+        Some(suggestion) => format!("unknown preset `{name}`; did you mean `{suggestion}`?"),
+        None if presets.is_empty() => {
+            format!("unknown preset `{name}`; the config defines no presets")
+        }
+        None => format!(
+            "unknown preset `{name}`; the config defines {}",
+            presets.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
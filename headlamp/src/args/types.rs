@@ -1,7 +1,13 @@
-use crate::config::{ChangedMode, CoverageMode, CoverageThresholds, CoverageUi};
+use crate::config::{
+    ChangedMode, CoverageEngine, CoverageMode, CoverageProvider, CoverageThresholds, CoverageUi,
+    GroupBy, JestEsm, LifecycleHooks, Typecheck,
+};
+use crate::debug::Debugger;
+use crate::print_commands::PrintCommandsFormat;
+use crate::resource_report::ResourceReportFormat;
 use crate::selection::dependency_language::DependencyLanguageId;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct ParsedArgs {
     pub runner_args: Vec<String>,
     pub selection_paths: Vec<String>,
@@ -17,6 +23,8 @@ pub struct ParsedArgs {
 
     pub collect_coverage: bool,
     pub coverage_ui: CoverageUi,
+    pub coverage_engine: CoverageEngine,
+    pub coverage_provider: CoverageProvider,
     pub coverage_abort_on_failure: bool,
     pub coverage_detail: Option<CoverageDetail>,
     pub coverage_show_code: bool,
@@ -25,19 +33,130 @@ pub struct ParsedArgs {
     pub coverage_max_hotspots: Option<u32>,
     pub coverage_page_fit: bool,
     pub coverage_thresholds: Option<CoverageThresholds>,
+    /// `--coverage-native-thresholds`: thresholds from the repo's jest, coverage.py and
+    /// Cargo.toml configs fill in the metrics `coverage_thresholds` leaves unset.
+    pub coverage_native_thresholds: bool,
     pub include_globs: Vec<String>,
     pub exclude_globs: Vec<String>,
     pub editor_cmd: Option<String>,
     pub workspace_root: Option<String>,
+    /// `--repo-root`: the repo root override; `config::resolve_repo_root` reads it from argv.
+    pub repo_root: Option<String>,
 
     pub only_failures: bool,
     pub show_logs: bool,
+    pub full_stacks: bool,
     pub sequential: bool,
     pub bootstrap_command: Option<String>,
 
     pub changed: Option<ChangedMode>,
     pub changed_depth: Option<u32>,
 
+    pub progress_heartbeat: Option<u32>,
+
+    pub max_memory_bytes: Option<u64>,
+    pub nice: Option<u32>,
+    pub python: Option<String>,
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    pub feature_matrix: Vec<Vec<String>>,
+    /// Set per run by `--feature-matrix`; suites are labeled with it.
+    pub feature_set_label: Option<String>,
+    /// Set per run by `coverage::scope`: the package dirs coverage is restricted to.
+    pub coverage_scope: Vec<String>,
+    pub packages: Vec<String>,
+    pub exclude_packages: Vec<String>,
+    pub target: Option<String>,
+    pub target_runner: Option<String>,
+    pub cargo_profile: Option<String>,
+    /// nextest's own `--profile` from `.config/nextest.toml` (retries, slow timeouts, overrides).
+    pub nextest_profile: Option<String>,
+    pub auto_install: bool,
+    pub projects: Vec<String>,
+    pub ignore_projects: Vec<String>,
+    pub jest_esm: JestEsm,
+    pub typecheck: Typecheck,
+    /// Replaces `tsc --noEmit`; run from each tsconfig's directory with `-p tsconfig.json`.
+    pub typecheck_command: Option<String>,
+    pub node: Option<String>,
+    pub hooks: LifecycleHooks,
+    pub wait_for: Vec<String>,
+    pub wait_timeout_ms: Option<u64>,
+    pub env_vars: Vec<String>,
+    pub env_files: Vec<String>,
+    /// `--artifacts-dir`: where the run's registered artifacts are collected, with an index.
+    pub artifacts_dir: Option<String>,
+    /// Extra files (e.g. failure screenshots) collected into `--artifacts-dir`.
+    pub artifact_globs: Vec<String>,
+    /// `--output-filter`: regexes whose matching passthrough and console lines are dropped.
+    pub output_filters: Vec<String>,
+    /// `--output-collapse`: regexes shown once per run, their repeats dropped.
+    pub output_collapse: Vec<String>,
+    /// `--badge-out`: where the tests and coverage SVG badges are written after each run.
+    pub badge_out: Option<String>,
+    /// `--rerun-failed`: a saved JSON report whose failed tests are the only ones run.
+    pub rerun_failed: Option<String>,
+    /// `--remote`: the executor command a planned run is handed to (see `remote`).
+    pub remote: Option<String>,
+    /// `--notify` targets told about the outcome when a run completes.
+    pub notify: Vec<String>,
+    /// `--check` specs, `<format>:<command>`, whose findings are reported as failed tests.
+    pub checks: Vec<String>,
+    /// `--preset` names from the config's `presets`, already expanded into the args.
+    pub presets: Vec<String>,
+    /// `--log-level`: the level of headlamp's own diagnostics on stderr (`HEADLAMP_LOG` wins).
+    pub log_level: Option<String>,
+    /// `--log-json`: diagnostics as one JSON object per line.
+    pub log_json: bool,
+    /// `--deterministic`: run times as `<time>`, paths relative to the cwd, stable ordering and
+    /// no ANSI unless `FORCE_COLOR` asks for it. Implies `--quiet`.
+    pub deterministic: bool,
+    /// `--normalize-times`: `<time>` for every rendered duration and zeroed timings in the JSON
+    /// reports headlamp writes; paths, colors and ordering are left alone.
+    pub normalize_times: bool,
+    /// `--bail`: stop at the first failure, in the runner and across runners.
+    pub bail: bool,
+    /// `--summary-line`: end with one greppable `headlamp: result=... tests=...` line on stdout.
+    pub summary_line: bool,
+    /// `--check-update`: after the run, note on stderr when a newer release is out (checked at
+    /// most once a day).
+    pub check_update: bool,
+    /// `--show-skipped`: list skipped and todo tests, with their reasons, before the footer.
+    pub show_skipped: bool,
+    /// `--show-skipped-projects`: list the jest projects and cargo packages the selection matched
+    /// no tests in.
+    pub show_skipped_projects: bool,
+    /// `--footer-breakdown`: per jest project / cargo package / pytest rootdir counts and time
+    /// under the footer.
+    pub footer_breakdown: bool,
+    /// `--group-by`: group rendered results by file, suite or package.
+    pub group_by: GroupBy,
+    /// `--path-style`: what shown file paths are relative to.
+    pub path_style: crate::format::path_display::PathStyle,
+    /// `--strip-path-prefix`: globs cut from the start of shown file paths.
+    pub strip_path_prefixes: Vec<String>,
+    /// `--max-reported-failures`: failures past the first `n` are listed one line each.
+    pub max_reported_failures: Option<u32>,
+    /// `--fail-on-skipped`: a run that skipped any test fails.
+    pub fail_on_skipped: bool,
+    /// `--fail-if-no-tests`: a run that selected no tests fails with `run::NO_TESTS_EXIT_CODE`.
+    pub fail_if_no_tests: bool,
+    /// `--fail-on-untested-changes`: a `--changed` run with a changed source file no test
+    /// exercises fails.
+    pub fail_on_untested_changes: bool,
+    /// `--strict-duplicates`: duplicate test names or suite paths fail the run, not just warn.
+    pub strict_duplicates: bool,
+    /// `--print-commands[=json]`: print the runner commands instead of running them.
+    pub print_commands: Option<PrintCommandsFormat>,
+    /// `--debug[=pdb|gdb|lldb]`: run the selection for a debugger (implies `--sequential`).
+    pub debug: Option<Debugger>,
+    /// `--resource-report[=json]`: print each runner's memory, CPU and process counts after the run.
+    pub resource_report: Option<ResourceReportFormat>,
+    /// `--shuffle[=seed]`: run test files in this seed's random order; shown in the footer.
+    pub shuffle_seed: Option<u64>,
+    /// `--max-duration`: the whole run's time budget; past it the run is stopped and fails.
+    pub max_duration_ms: Option<u64>,
     pub dependency_language: Option<DependencyLanguageId>,
 }
 
@@ -0,0 +1,119 @@
+//! `--badge-out <dir>`: shields-style SVG badges written after each run, for committing or
+//! publishing from CI. `tests.svg` shows the run's counts; `coverage.svg` the line coverage of
+//! a `--coverage` run (left untouched by runs without coverage).
+
+use std::path::Path;
+
+use crate::args::ParsedArgs;
+use crate::recorded::RecordedResults;
+
+const GREEN: &str = "#4c1";
+const RED: &str = "#e05d44";
+const GREY: &str = "#9f9f9f";
+
+/// Best-effort, like `--artifacts-dir`: a failed write only warns.
+pub fn write_badges_if_requested(repo_root: &Path, args: &ParsedArgs) {
+    let Some(dir) = args.badge_out.as_deref() else {
+        return;
+    };
+    let dir = repo_root.join(dir);
+    let results = crate::recorded::recorded_results();
+    let mut badges = vec![("tests.svg", tests_badge(&results))];
+    if let Some(badge) = coverage_badge(&results) {
+        badges.push(("coverage.svg", badge));
+    }
+    let written = std::fs::create_dir_all(&dir).and_then(|()| {
+        badges
+            .iter()
+            .try_for_each(|(name, svg)| std::fs::write(dir.join(name), svg))
+    });
+    if let Err(err) = written {
+        eprintln!(
+            "headlamp: failed to write badges into {}: {err}",
+            dir.display()
+        );
+    }
+}
+
+/// `tests | 3 failed, 40 passed, 2 skipped`: red with failures, green when everything passed,
+/// grey when nothing ran.
+pub fn tests_badge(results: &RecordedResults) -> String {
+    let counts = [
+        (results.failed, "failed"),
+        (results.passed, "passed"),
+        (results.skipped, "skipped"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect::<Vec<_>>();
+    let (value, color) = match (counts.is_empty(), results.failed > 0) {
+        (true, _) => ("no tests".to_string(), GREY),
+        (false, true) => (counts.join(", "), RED),
+        (false, false) => (counts.join(", "), GREEN),
+    };
+    render_badge("tests", &value, color)
+}
+
+/// `coverage | 84.2%`, colored by shields' usual coverage steps; `None` without coverage.
+pub fn coverage_badge(results: &RecordedResults) -> Option<String> {
+    let (covered, total) = results.coverage_lines?;
+    let pct = if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    };
+    Some(render_badge(
+        "coverage",
+        &format!("{pct:.1}%"),
+        coverage_color(pct),
+    ))
+}
+
+fn coverage_color(pct: f64) -> &'static str {
+    match pct {
+        pct if pct >= 90.0 => GREEN,
+        pct if pct >= 80.0 => "#97ca00",
+        pct if pct >= 70.0 => "#a4a61d",
+        pct if pct >= 60.0 => "#dfb317",
+        pct if pct >= 50.0 => "#fe7d37",
+        _ => RED,
+    }
+}
+
+/// A flat two-part badge. Text widths are estimated (Verdana 11px averages ~7px a character),
+/// which is what keeps this free of font metrics.
+pub fn render_badge(label: &str, value: &str, color: &str) -> String {
+    let segment_width = |text: &str| text.chars().count() as u32 * 7 + 10;
+    let label_width = segment_width(label);
+    let value_width = segment_width(value);
+    let width = label_width + value_width;
+    let (label, value) = (escape_xml(label), escape_xml(value));
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">"#,
+            r#"<title>{label}: {value}</title>"#,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+            r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+            r#"<text x="{label_x}" y="14">{label}</text><text x="{value_x}" y="14">{value}</text></g></svg>"#,
+            "\n"
+        ),
+        width = width,
+        label = label,
+        value = value,
+        label_width = label_width,
+        value_width = value_width,
+        color = color,
+        label_x = label_width / 2,
+        value_x = label_width + value_width / 2,
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
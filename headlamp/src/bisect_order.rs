@@ -0,0 +1,362 @@
+//! `headlamp bisect-order`: a test that fails in one order (say a `--shuffle` seed) but passes
+//! on its own was broken by a test that ran before it. One run in the failing order lists the
+//! tests ahead of it; bisection then re-runs halves of that list in front of the failing test,
+//! through the same per-runner filters as `--rerun-failed`, until the polluter is left.
+//!
+//! Re-runs keep the original order: `--sequential`, and a `HEADLAMP_TEST_ORDER` file listing the
+//! test files for the jest sequencer and the pytest plugin. Rust tests need neither, as libtest
+//! runs the tests of a binary by name.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexSet;
+
+use crate::api::{RunOutcome, RunRequest};
+use crate::rerun::FailedTest;
+use crate::runner::Runner;
+use crate::runner_detect::language_for_path;
+use crate::test_model::TestRunModel;
+
+const USAGE: &str =
+    "headlamp: usage: headlamp bisect-order [--test=<file>[::<name>]] [flags...] [paths...]";
+
+pub fn run_bisect_order_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "bisect-order" {
+        return None;
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = crate::config::find_repo_root(&cwd);
+    let options = match BisectOptions::parse(rest) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("headlamp: {message}");
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+    };
+    Some(match bisect_order(&repo_root, &options) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("headlamp: bisect-order: {message}");
+            2
+        }
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BisectOptions {
+    pub runner: Option<Runner>,
+    /// `<file>` or `<file>::<name>`; the first failure of the run when not given.
+    pub target: Option<String>,
+    /// Everything else, as for `headlamp`: the flags and paths that give the failing order.
+    pub args: Vec<String>,
+}
+
+impl BisectOptions {
+    pub fn parse(argv: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut tokens = argv.iter();
+        while let Some(token) = tokens.next() {
+            let (flag, inline) = match token.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (token.as_str(), None),
+            };
+            if flag != "--runner" && flag != "--test" {
+                options.args.push(token.clone());
+                continue;
+            }
+            let value = inline
+                .or_else(|| tokens.next().cloned())
+                .ok_or_else(|| format!("{flag} needs a value"))?;
+            if flag == "--test" {
+                options.target = Some(value);
+            } else {
+                let runner =
+                    Runner::parse(&value).ok_or_else(|| format!("unknown runner: {value}"))?;
+                options.runner = Some(runner);
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The smallest run of `candidates` (kept in order) in front of which the failure still
+/// reproduces: halves are tried in turn, and when neither half alone reproduces it the failure
+/// needs tests from both, so the current set is the answer. `reproduces` is never asked about
+/// the full set, which the caller has already seen fail.
+pub fn bisect<T: Clone>(candidates: &[T], mut reproduces: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut current = candidates.to_vec();
+    while current.len() > 1 {
+        let (front, back) = current.split_at(current.len() / 2);
+        current = if reproduces(front) {
+            front.to_vec()
+        } else if reproduces(back) {
+            back.to_vec()
+        } else {
+            break;
+        };
+    }
+    current
+}
+
+/// Every test of `models` that ran, and whether it failed. Suites come sorted by path, so this
+/// is not the order they ran in; see `run_order`.
+pub fn tests_of_run(repo_root: &Path, models: &[TestRunModel]) -> Vec<(FailedTest, bool)> {
+    models
+        .iter()
+        .flat_map(|model| &model.test_results)
+        .flat_map(|suite| {
+            let test_file_path = crate::rerun::localize_path(repo_root, &suite.test_file_path);
+            suite
+                .test_results
+                .iter()
+                .filter(|case| matches!(case.status.as_str(), "passed" | "failed"))
+                .map(move |case| {
+                    let test = FailedTest {
+                        test_file_path: test_file_path.clone(),
+                        full_name: Some(case.full_name.clone()),
+                    };
+                    (test, case.status == "failed")
+                })
+        })
+        .collect()
+}
+
+/// The order `runner` ran `tests` in: libtest runs a binary's tests by name (binaries are
+/// separate processes, so their order doesn't matter); the jest sequencer and the pytest plugin
+/// run files by path, or in `--shuffle`'s order, each file's tests in their own order.
+pub fn run_order(runner: Runner, tests: &[FailedTest], seed: Option<u64>) -> Vec<FailedTest> {
+    let mut ordered = tests.to_vec();
+    if runner.is_rust() {
+        ordered.sort_by(|left, right| left.full_name.cmp(&right.full_name));
+        return ordered;
+    }
+    let mut files = tests
+        .iter()
+        .map(|test| test.test_file_path.as_str())
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    files.sort();
+    if let Some(seed) = seed {
+        crate::shuffle::shuffle(&mut files, seed);
+    }
+    ordered.sort_by_key(|test| files.iter().position(|file| *file == test.test_file_path));
+    ordered
+}
+
+/// `<file>` matches any test of a file ending in it; `<file>::<name>` also needs the name.
+pub fn matches_target(test: &FailedTest, target: &str) -> bool {
+    let (file, name) = match target.split_once("::") {
+        Some((file, name)) => (file, Some(name)),
+        None => (target, None),
+    };
+    let file = file.trim_start_matches("./");
+    test.test_file_path.ends_with(file)
+        && name.is_none_or(|name| test.full_name.as_deref() == Some(name))
+}
+
+pub fn describe(test: &FailedTest) -> String {
+    match &test.full_name {
+        Some(name) => format!("{}::{name}", test.test_file_path),
+        None => test.test_file_path.clone(),
+    }
+}
+
+fn bisect_order(repo_root: &Path, options: &BisectOptions) -> Result<i32, String> {
+    let runner = pick_runner(repo_root, options)?;
+    let seed = shuffle_seed(&options.args)?;
+    let outcome = run(repo_root, runner, &options.args, &[])?;
+    let tests = tests_of_run(repo_root, &outcome.models);
+    let Some(target) = tests
+        .iter()
+        .find(|(test, failed)| {
+            *failed
+                && options
+                    .target
+                    .as_deref()
+                    .is_none_or(|target| matches_target(test, target))
+        })
+        .map(|(test, _)| test.clone())
+    else {
+        eprintln!("headlamp: bisect-order: no matching test failed");
+        return Ok(1);
+    };
+    let all = tests.into_iter().map(|(test, _)| test).collect::<Vec<_>>();
+    let ordered = run_order(runner, &all, seed);
+    let at = ordered.iter().position(|test| *test == target);
+    let earlier = ordered[..at.unwrap_or(0)].to_vec();
+    let mut bisection = Bisection::new(repo_root, runner, options, target)?;
+    eprintln!(
+        "headlamp: bisect-order: {} failed; re-running it after its {} earlier test(s)",
+        describe(&bisection.target),
+        earlier.len()
+    );
+    if !bisection.reproduces(&earlier)? {
+        eprintln!(
+            "headlamp: bisect-order: {} passes after the earlier tests run in order; pass the \
+             failing run's --shuffle=<seed>, or it is flaky",
+            describe(&bisection.target)
+        );
+        return Ok(1);
+    }
+    if bisection.reproduces(&[])? {
+        eprintln!(
+            "headlamp: bisect-order: {} also fails on its own; not order-dependent",
+            describe(&bisection.target)
+        );
+        return Ok(1);
+    }
+    let mut failure = None;
+    let polluters = bisect(&earlier, |subset| {
+        failure.is_none()
+            && bisection.reproduces(subset).unwrap_or_else(|message| {
+                failure = Some(message);
+                false
+            })
+    });
+    match failure {
+        Some(message) => Err(message),
+        None => {
+            report(&bisection.target, &polluters, bisection.runs);
+            Ok(0)
+        }
+    }
+}
+
+/// A bare `--shuffle` picks a new seed, which can't give the failing order back.
+fn shuffle_seed(args: &[String]) -> Result<Option<u64>, String> {
+    if args.iter().any(|arg| arg == "--shuffle") {
+        return Err("pass the failing run's seed: --shuffle=<seed>".to_string());
+    }
+    Ok(crate::args::derive_args(&[], args, false).shuffle_seed)
+}
+
+/// Re-runs of the target behind a subset of the earlier tests.
+struct Bisection<'a> {
+    repo_root: &'a Path,
+    runner: Runner,
+    options: &'a BisectOptions,
+    target: FailedTest,
+    order_dir: tempfile::TempDir,
+    runs: usize,
+}
+
+impl<'a> Bisection<'a> {
+    fn new(
+        repo_root: &'a Path,
+        runner: Runner,
+        options: &'a BisectOptions,
+        target: FailedTest,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            repo_root,
+            runner,
+            options,
+            target,
+            order_dir: tempfile::tempdir().map_err(|err| err.to_string())?,
+            runs: 0,
+        })
+    }
+
+    fn reproduces(&mut self, subset: &[FailedTest]) -> Result<bool, String> {
+        self.runs += 1;
+        eprintln!(
+            "headlamp: bisect-order: run {}: {} earlier test(s)",
+            self.runs,
+            subset.len()
+        );
+        let tests = subset
+            .iter()
+            .chain([&self.target])
+            .cloned()
+            .collect::<Vec<_>>();
+        let order_file = write_order_file(self.repo_root, self.order_dir.path(), &tests)?;
+        let args = reorder_args(&self.options.args, &order_file);
+        let outcome = run(self.repo_root, self.runner, &args, &tests)?;
+        Ok(tests_of_run(self.repo_root, &outcome.models)
+            .iter()
+            .any(|(test, failed)| *failed && *test == self.target))
+    }
+}
+
+fn pick_runner(repo_root: &Path, options: &BisectOptions) -> Result<Runner, String> {
+    let runner = options.runner.or_else(|| {
+        let target = options.target.as_deref()?;
+        let file = target.split_once("::").map_or(target, |(file, _)| file);
+        language_for_path(Path::new(file)).map(|language| Runner::for_language(repo_root, language))
+    });
+    match runner.unwrap_or(Runner::Jest) {
+        Runner::Plugin(name) => Err(format!(
+            "plugin:{name} has no way to keep a test order; use jest, pytest or a Rust runner"
+        )),
+        runner => Ok(runner),
+    }
+}
+
+fn run(
+    repo_root: &Path,
+    runner: Runner,
+    args: &[String],
+    tests: &[FailedTest],
+) -> Result<RunOutcome, String> {
+    let request = RunRequest::new(repo_root)
+        .runner(runner)
+        .args(args.iter().cloned())
+        .args(["--sequential"])
+        .tests(tests.iter().cloned());
+    crate::api::run(&request).map_err(|err| err.to_string())
+}
+
+/// The listed order replaces `--shuffle`'s, which would otherwise reorder a subset differently.
+fn reorder_args(args: &[String], order_file: &Path) -> Vec<String> {
+    args.iter()
+        .filter(|arg| arg.as_str() != "--shuffle" && !arg.starts_with("--shuffle="))
+        .cloned()
+        .chain([format!(
+            "--env={}={}",
+            crate::shuffle::ORDER_ENV,
+            order_file.display()
+        )])
+        .collect()
+}
+
+/// The test files of `tests`, absolute, in the order they first appear.
+fn write_order_file(repo_root: &Path, dir: &Path, tests: &[FailedTest]) -> Result<PathBuf, String> {
+    let files = tests
+        .iter()
+        .map(|test| repo_root.join(&test.test_file_path))
+        .collect::<IndexSet<_>>();
+    let path = dir.join("order.txt");
+    let mut file = std::fs::File::create(&path).map_err(|err| err.to_string())?;
+    files
+        .iter()
+        .try_for_each(|test_file| writeln!(file, "{}", test_file.display()))
+        .map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+fn report(target: &FailedTest, polluters: &[FailedTest], runs: usize) {
+    match polluters {
+        [] => eprintln!(
+            "headlamp: bisect-order: {} did not fail again after any earlier tests (flaky?)",
+            describe(target)
+        ),
+        [polluter] => println!(
+            "headlamp: bisect-order: {} makes {} fail ({runs} runs)",
+            describe(polluter),
+            describe(target)
+        ),
+        several => {
+            println!(
+                "headlamp: bisect-order: {} fails only after all of these together ({runs} runs):",
+                describe(target)
+            );
+            several
+                .iter()
+                .for_each(|polluter| println!("  {}", describe(polluter)));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use headlamp_core::format::cargo_build::CargoBuildDiagnostics;
 use headlamp_core::format::cargo_test::{CargoTestStreamEvent, CargoTestStreamParser};
 use headlamp_core::format::nextest::{NextestStreamParser, NextestStreamUpdate};
 
@@ -9,29 +10,35 @@ use crate::streaming::{OutputStream, StreamAction, StreamAdapter};
 #[derive(Debug)]
 pub(super) struct NextestAdapter {
     pub(super) only_failures: bool,
+    ci: bool,
     pub(super) parser: NextestStreamParser,
+    pub(super) build: CargoBuildDiagnostics,
 }
 
 impl NextestAdapter {
-    pub(super) fn new(repo_root: &Path, only_failures: bool) -> Self {
+    pub(super) fn new(repo_root: &Path, only_failures: bool, ci: bool) -> Self {
         Self {
             only_failures,
+            ci,
             parser: NextestStreamParser::new(repo_root),
+            build: CargoBuildDiagnostics::default(),
         }
     }
 
     fn actions_for_update(&self, update: &NextestStreamUpdate) -> Vec<StreamAction> {
+        let outcome = outcome_from_status(update.status.as_str());
         let should_print = !self.only_failures || update.status == "failed";
         if !should_print {
-            return vec![];
+            return vec![StreamAction::RecordOutcome(outcome)];
         }
         let line = render_finished_test_line(
-            outcome_from_status(update.status.as_str()),
+            outcome,
             update.duration,
             update.suite_path.as_str(),
             update.test_name.as_str(),
         );
         vec![
+            StreamAction::RecordOutcome(outcome),
             StreamAction::SetProgressLabel(update.suite_path.clone()),
             StreamAction::PrintStdout(line),
         ]
@@ -41,17 +48,21 @@ impl NextestAdapter {
 #[derive(Debug)]
 pub(super) struct CargoTestAdapter {
     pub(super) only_failures: bool,
+    ci: bool,
     pub(super) parser: CargoTestStreamParser,
+    pub(super) build: CargoBuildDiagnostics,
     last_pending_test_name: Option<String>,
     started_at_by_test: std::collections::BTreeMap<String, std::time::Instant>,
     current_suite_path: Option<String>,
 }
 
 impl CargoTestAdapter {
-    pub(super) fn new(repo_root: &Path, only_failures: bool) -> Self {
+    pub(super) fn new(repo_root: &Path, only_failures: bool, ci: bool) -> Self {
         Self {
             only_failures,
+            ci,
             parser: CargoTestStreamParser::new(repo_root),
+            build: CargoBuildDiagnostics::default(),
             last_pending_test_name: None,
             started_at_by_test: std::collections::BTreeMap::new(),
             current_suite_path: None,
@@ -70,8 +81,9 @@ impl CargoTestAdapter {
                 status,
                 duration,
             } => {
+                let outcome = outcome_from_status(status.as_str());
                 if self.only_failures && status != "failed" {
-                    return vec![];
+                    return vec![StreamAction::RecordOutcome(outcome)];
                 }
                 let duration = duration.or_else(|| {
                     self.started_at_by_test
@@ -79,12 +91,13 @@ impl CargoTestAdapter {
                         .map(|started_at| started_at.elapsed())
                 });
                 let line = render_finished_test_line(
-                    outcome_from_status(status.as_str()),
+                    outcome,
                     duration,
                     suite_path.as_str(),
                     test_name.as_str(),
                 );
                 vec![
+                    StreamAction::RecordOutcome(outcome),
                     StreamAction::SetProgressLabel(format!("{suite_path}::{test_name}")),
                     StreamAction::PrintStdout(line),
                 ]
@@ -134,17 +147,14 @@ impl CargoTestAdapter {
             .remove(name.as_str())
             .map(|t| t.elapsed());
         let status = if trimmed == "ok" { "passed" } else { "failed" };
+        let outcome = outcome_from_status(status);
         if self.only_failures && status != "failed" {
-            return vec![];
+            return vec![StreamAction::RecordOutcome(outcome)];
         }
         let suite_path = self.current_suite_path.clone().unwrap_or_default();
-        let line = render_finished_test_line(
-            outcome_from_status(status),
-            duration,
-            suite_path.as_str(),
-            name.as_str(),
-        );
+        let line = render_finished_test_line(outcome, duration, suite_path.as_str(), name.as_str());
         vec![
+            StreamAction::RecordOutcome(outcome),
             StreamAction::SetProgressLabel(format!("{suite_path}::{name}")),
             StreamAction::PrintStdout(line),
         ]
@@ -157,7 +167,10 @@ impl StreamAdapter for CargoTestAdapter {
     }
 
     fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
-        let is_ci_env = std::env::var_os("CI").is_some();
+        if self.build.push_line(line) {
+            return vec![];
+        }
+        let is_ci_env = self.ci;
         let is_tty_output = headlamp_core::format::terminal::is_output_terminal();
         let is_cargo_summary_error = line.starts_with("error: test failed, to rerun pass ")
             || line.starts_with("error: ") && line.contains(" target failed:");
@@ -195,7 +208,10 @@ impl StreamAdapter for NextestAdapter {
     }
 
     fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
-        let is_ci_env = std::env::var_os("CI").is_some();
+        if self.build.push_line(line) {
+            return vec![];
+        }
+        let is_ci_env = self.ci;
         let is_tty_output = headlamp_core::format::terminal::is_output_terminal();
         let is_nextest_summary_error = line.trim() == "error: test run failed";
         let has_useful_status = line.contains("Blocking waiting for file lock")
@@ -213,7 +229,10 @@ impl StreamAdapter for NextestAdapter {
                 StreamAction::PrintStderr(line.trim().to_string()),
             ]);
         }
+        let started_before = self.parser.started_tests();
         let update = self.parser.push_line(line);
+        let started = self.parser.started_tests() - started_before;
+        actions.extend(std::iter::repeat_n(StreamAction::TestStarted, started));
         actions.extend(
             update
                 .as_ref()
@@ -3,12 +3,16 @@ use std::path::Path;
 use duct::cmd as duct_cmd;
 
 use headlamp_core::args::ParsedArgs;
-use headlamp_core::coverage::istanbul_pretty::format_istanbul_pretty_from_lcov_report;
+use headlamp_core::config::CoverageUi;
+use headlamp_core::coverage::istanbul_pretty::{
+    format_istanbul_pretty_from_lcov_report, format_istanbul_text_from_report,
+};
 use headlamp_core::coverage::lcov::read_lcov_filtered_from_path;
 use headlamp_core::coverage::print::PrintOpts;
 
 use super::paths::headlamp_cargo_target_dir_for_duct;
 use crate::profile;
+use crate::session::artifacts::ArtifactKind;
 
 pub(super) fn has_cargo_nextest(
     repo_root: &Path,
@@ -29,24 +33,40 @@ pub(super) fn has_cargo_nextest(
         .is_some_and(|o| o.status.success())
 }
 
+/// `--coverage-ui=jest` prints istanbul's text reports, as a jest run would.
+fn format_coverage(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    report: headlamp_core::coverage::model::CoverageReport,
+    print_opts: &PrintOpts,
+) -> String {
+    if args.coverage_ui == CoverageUi::Jest {
+        return format_istanbul_text_from_report(repo_root, report, print_opts);
+    }
+    let _span = profile::span("format istanbul pretty (from lcov)");
+    format_istanbul_pretty_from_lcov_report(
+        repo_root,
+        report,
+        print_opts,
+        &[],
+        &args.include_globs,
+        &args.exclude_globs,
+        args.coverage_detail,
+    )
+}
+
 pub(crate) fn print_lcov(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
 ) -> bool {
-    let lcov_path = if args.keep_artifacts {
-        repo_root.join("coverage").join("lcov.info")
-    } else {
-        session.subdir("coverage").join("rust").join("lcov.info")
-    };
-    let llvm_cov_json_path = if args.keep_artifacts {
-        repo_root.join("coverage").join("coverage.json")
-    } else {
-        session
-            .subdir("coverage")
-            .join("rust")
-            .join("coverage.json")
-    };
+    let crate::rust_coverage::RustCoveragePaths {
+        lcov_path,
+        llvm_cov_json_path,
+        ..
+    } = crate::rust_coverage::rust_coverage_paths(args.keep_artifacts, repo_root, session);
+    session.register_artifact(ArtifactKind::Coverage, "rust", &lcov_path);
+    session.register_artifact(ArtifactKind::Coverage, "rust", &llvm_cov_json_path);
     let filtered = {
         let _span = profile::span("read lcov + glob filter");
         read_lcov_filtered_from_path(
@@ -72,6 +92,13 @@ pub(crate) fn print_lcov(
             None => filtered,
         }
     };
+    let filtered = {
+        let _span = profile::span("apply coverage exclusion pragmas + scope");
+        let filtered = crate::coverage::model::apply_exclusion_pragmas(filtered, repo_root);
+        crate::coverage::scope::restrict_report(filtered, repo_root, &args.coverage_scope)
+    };
+    crate::coverage::last_run::save_last_run_coverage(repo_root, "rust", &filtered);
+    crate::recorded::record_coverage(&filtered);
     let print_opts =
         PrintOpts::for_run(args, headlamp_core::format::terminal::is_output_terminal());
     let threshold_failure_lines = args.coverage_thresholds.as_ref().map(|thresholds| {
@@ -80,19 +107,9 @@ pub(crate) fn print_lcov(
             headlamp_core::coverage::thresholds::compute_totals_from_report(&filtered),
         )
     });
-    let pretty = {
-        let _span = profile::span("format istanbul pretty (from lcov)");
-        format_istanbul_pretty_from_lcov_report(
-            repo_root,
-            filtered,
-            &print_opts,
-            &[],
-            &args.include_globs,
-            &args.exclude_globs,
-            args.coverage_detail,
-        )
-    };
-    println!("{pretty}");
+    let pretty = format_coverage(repo_root, args, filtered, &print_opts);
+    crate::coverage::scope::print_scope_label(args);
+    crate::format::report_out::println(headlamp_core::format::deterministic::finalize(pretty));
     threshold_failure_lines.is_some_and(|lines| {
         if lines.is_empty() {
             return false;
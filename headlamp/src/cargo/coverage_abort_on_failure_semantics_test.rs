@@ -2,36 +2,9 @@ use headlamp_core::args::ParsedArgs;
 
 fn base_args() -> ParsedArgs {
     ParsedArgs {
-        runner_args: vec![],
-        selection_paths: vec![],
-        selection_specified: false,
-        keep_artifacts: false,
-        watch: false,
-        ci: false,
-        verbose: false,
-        quiet: false,
-        no_cache: false,
         collect_coverage: true,
-        coverage_ui: headlamp_core::config::CoverageUi::Both,
         coverage_abort_on_failure: true,
-        coverage_detail: None,
-        coverage_show_code: false,
-        coverage_mode: headlamp_core::config::CoverageMode::Auto,
-        coverage_max_files: None,
-        coverage_max_hotspots: None,
-        coverage_page_fit: false,
-        coverage_thresholds: None,
-        include_globs: vec![],
-        exclude_globs: vec![],
-        editor_cmd: None,
-        workspace_root: None,
-        only_failures: false,
-        show_logs: false,
-        sequential: false,
-        bootstrap_command: None,
-        changed: None,
-        changed_depth: None,
-        dependency_language: None,
+        ..ParsedArgs::default()
     }
 }
 
@@ -57,6 +30,7 @@ fn model_with_failed_tests(
             success: num_failed_tests == 0 && num_failed_suites == 0,
             run_time_ms: None,
         },
+        skipped_units: vec![],
     }
 }
 
@@ -0,0 +1,159 @@
+use std::path::Path;
+use std::time::Instant;
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::live_progress::{LiveProgress, live_progress_mode};
+use crate::run::RunError;
+use crate::streaming::run_streaming_capture_tail_merged;
+use crate::test_model::{TestRunModel, TestSuiteResult};
+
+const TARGET_SELECTION_FLAGS: [&str; 8] = [
+    "--test",
+    "--tests",
+    "--bin",
+    "--bins",
+    "--example",
+    "--examples",
+    "--bench",
+    "--lib",
+];
+
+#[derive(Debug)]
+pub(crate) struct DoctestPhaseOutput {
+    pub(crate) exit_code: i32,
+    pub(crate) suites: Vec<TestSuiteResult>,
+    pub(crate) elapsed_ms: u64,
+}
+
+/// Doctests never run under nextest or the headlamp runner (which executes prebuilt test
+/// binaries), so those runners follow up with `cargo test --doc`. Runs narrowed to specific
+/// targets (changed selection, `--test` passthrough) skip the phase.
+fn should_run_doctests(args: &ParsedArgs, extra_cargo_args: &[String]) -> bool {
+    let targets_selected = extra_cargo_args
+        .iter()
+        .chain(args.runner_args.iter())
+        .take_while(|token| token.as_str() != "--")
+        .any(|token| {
+            let flag = token
+                .split_once('=')
+                .map_or(token.as_str(), |(flag, _)| flag);
+            TARGET_SELECTION_FLAGS.contains(&flag)
+        });
+    !targets_selected
+}
+
+pub(crate) fn run_doctest_phase(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    extra_cargo_args: &[String],
+    filter: Option<&str>,
+) -> Result<Option<DoctestPhaseOutput>, RunError> {
+    if !should_run_doctests(args, extra_cargo_args) {
+        return Ok(None);
+    }
+    let has_doctests = super::selection::session_cargo_workspace(repo_root, session)
+        .is_none_or(|workspace| workspace.packages.iter().any(|p| p.has_doctests));
+    if !has_doctests {
+        return Ok(None);
+    }
+    let mode = live_progress_mode(
+        headlamp_core::format::terminal::is_output_terminal(),
+        args.ci,
+        args.quiet,
+    );
+    let live_progress = LiveProgress::start_with_args(1, mode, args);
+    let run_start = Instant::now();
+    let cmd = build_doctest_command(repo_root, args, session, extra_cargo_args, filter);
+    let mut adapter = super::adapters::CargoTestAdapter::new(
+        repo_root,
+        args.only_failures,
+        session.env().is_ci(),
+    );
+    let (exit_code, tail) = run_streaming_capture_tail_merged(
+        cmd,
+        &live_progress,
+        &mut adapter,
+        super::runner_output_ring(session, "cargo-doctest"),
+    )?;
+    live_progress.increment_done(1);
+    live_progress.finish();
+    let suites = adapter
+        .parser
+        .finalize()
+        .map(|model| model.test_results)
+        .unwrap_or_default();
+    if suites.is_empty() {
+        let no_library = tail
+            .lines()
+            .any(|line| line.contains("no library targets found"));
+        if no_library {
+            return Ok(None);
+        }
+        let empty = super::empty_test_run_model_for_exit_code(exit_code);
+        super::print_runner_tail_if_failed_without_tests(
+            repo_root,
+            args,
+            exit_code,
+            &empty,
+            &tail,
+            &adapter.build,
+        );
+    }
+    Ok(Some(DoctestPhaseOutput {
+        exit_code,
+        suites,
+        elapsed_ms: run_start.elapsed().as_millis() as u64,
+    }))
+}
+
+fn build_doctest_command(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    extra_cargo_args: &[String],
+    filter: Option<&str>,
+) -> std::process::Command {
+    let mut cmd = std::process::Command::new("cargo");
+    if super::paths::nightly_rustc_exists(repo_root) {
+        cmd.arg("+nightly");
+    }
+    let doc_cargo_args = std::iter::once("--doc".to_string())
+        .chain(extra_cargo_args.iter().cloned())
+        .collect::<Vec<_>>();
+    cmd.args(super::runner_args::build_cargo_test_args(
+        filter,
+        args,
+        &doc_cargo_args,
+    ));
+    cmd.current_dir(repo_root);
+    super::paths::apply_headlamp_cargo_target_dir(
+        &mut cmd,
+        args.keep_artifacts,
+        repo_root,
+        session,
+    );
+    super::target::apply_target_runner_env(&mut cmd, args);
+    cmd.env("RUST_BACKTRACE", "1");
+    cmd.env("RUST_LIB_BACKTRACE", "1");
+    session.env().apply(&mut cmd);
+    cmd
+}
+
+/// Appends doctest suites to a runner's model; the footer and failure counts are rebuilt from
+/// the combined suites.
+pub(crate) fn merge_doctest_phase(
+    model: TestRunModel,
+    doctests: DoctestPhaseOutput,
+) -> TestRunModel {
+    let run_time_ms = model
+        .aggregated
+        .run_time_ms
+        .map(|ms| ms.saturating_add(doctests.elapsed_ms));
+    let mut suites = model.test_results;
+    suites.extend(doctests.suites);
+    let mut merged = crate::format::unstructured_engine::build_test_run_model(suites);
+    merged.aggregated.run_time_ms = run_time_ms;
+    merged
+}
@@ -0,0 +1,89 @@
+use headlamp_core::args::ParsedArgs;
+
+use crate::test_model::TestRunModel;
+
+/// Cargo accepts features separated by commas or spaces (`--features "a,b c"`).
+pub fn parse_feature_list(raw: &str) -> Vec<String> {
+    raw.split([',', ' '])
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `a,b;c` runs twice: once with `a,b` and once with `c`. An empty set (`;c`) means the
+/// default features.
+pub fn parse_feature_matrix(raw: &str) -> Vec<Vec<String>> {
+    if raw.trim().is_empty() {
+        return vec![];
+    }
+    raw.split(';').map(parse_feature_list).collect()
+}
+
+pub(crate) fn cargo_feature_args(args: &ParsedArgs) -> Vec<String> {
+    let mut out = vec![];
+    if !args.features.is_empty() {
+        out.push(format!("--features={}", args.features.join(",")));
+    }
+    if args.all_features {
+        out.push("--all-features".to_string());
+    }
+    if args.no_default_features {
+        out.push("--no-default-features".to_string());
+    }
+    out
+}
+
+pub fn feature_set_label(args: &ParsedArgs) -> String {
+    let flags = cargo_feature_args(args);
+    if flags.is_empty() {
+        "default features".to_string()
+    } else {
+        flags.join(" ")
+    }
+}
+
+pub fn args_for_feature_set(args: &ParsedArgs, features: &[String]) -> ParsedArgs {
+    let mut out = ParsedArgs {
+        features: features.to_vec(),
+        feature_matrix: vec![],
+        ..args.clone()
+    };
+    out.feature_set_label = Some(feature_set_label(&out));
+    out
+}
+
+/// Suffixes every test name with the run's feature set so results from different matrix
+/// entries stay distinguishable (`tests::parse [--features=serde]`).
+pub(crate) fn label_model_with_feature_set(args: &ParsedArgs, model: &mut TestRunModel) {
+    let Some(label) = args.feature_set_label.as_deref() else {
+        return;
+    };
+    model
+        .test_results
+        .iter_mut()
+        .flat_map(|suite| suite.test_results.iter_mut())
+        .for_each(|case| {
+            case.title = format!("{} [{label}]", case.title);
+            case.full_name = format!("{} [{label}]", case.full_name);
+        });
+}
+
+/// Runs `run_once` per `--feature-matrix` entry and fails if any entry failed.
+pub fn run_feature_matrix(args: &ParsedArgs, mut run_once: impl FnMut(&ParsedArgs) -> i32) -> i32 {
+    let results = args
+        .feature_matrix
+        .iter()
+        .map(|features| {
+            let entry_args = args_for_feature_set(args, features);
+            let label = entry_args.feature_set_label.clone().unwrap_or_default();
+            crate::format::report_out::println(format!("headlamp: running with {label}"));
+            (label, run_once(&entry_args))
+        })
+        .collect::<Vec<_>>();
+    results.iter().for_each(|(label, exit_code)| {
+        let outcome = if *exit_code == 0 { "passed" } else { "failed" };
+        crate::format::report_out::println(format!("headlamp: feature matrix [{label}] {outcome}"));
+    });
+    i32::from(results.iter().any(|(_, exit_code)| *exit_code != 0))
+}
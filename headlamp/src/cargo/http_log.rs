@@ -0,0 +1,97 @@
+//! HTTP calls made by Rust tests. Headlamp points `HEADLAMP_HTTP_LOG` at a file for every Rust
+//! run; tests append one JSON line per call (see `headlamp::test_support`, behind the
+//! `test-support` feature), named by the test thread that made it. After the run each call is
+//! attached to its test as the event the jest bridge would have written, so the `HTTP:` card
+//! renders for Rust failures too.
+
+use std::path::PathBuf;
+
+use crate::process::RunEnvironment;
+use crate::session::RunSession;
+use crate::test_model::TestSuiteResult;
+
+/// Where Rust tests append their HTTP calls, one JSON object per line.
+pub const HTTP_LOG_ENV: &str = "HEADLAMP_HTTP_LOG";
+
+fn log_path(session: &RunSession) -> PathBuf {
+    session.subdir("http").join("rust.jsonl")
+}
+
+/// The session's environment plus `HEADLAMP_HTTP_LOG`, pointing at an emptied log so only this
+/// run's calls are attached.
+pub(crate) fn run_env_with_http_log(session: &RunSession) -> RunEnvironment {
+    let path = log_path(session);
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::remove_file(&path);
+    let mut env = session.env().clone();
+    env.vars.insert(
+        0,
+        (HTTP_LOG_ENV.to_string(), path.to_string_lossy().to_string()),
+    );
+    env
+}
+
+/// Adds each call logged under `run_env` to the console of the suite holding its test; calls
+/// whose test is not in `suites` are dropped.
+pub(crate) fn attach_http_events(suites: &mut [TestSuiteResult], run_env: &RunEnvironment) {
+    // The last assignment is the one the tests saw.
+    let Some((_, path)) = run_env
+        .vars
+        .iter()
+        .rev()
+        .find(|(key, _)| key == HTTP_LOG_ENV)
+    else {
+        return;
+    };
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return;
+    };
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|value| match value {
+            serde_json::Value::Object(event) => Some(event),
+            _ => None,
+        })
+        .for_each(|mut event| {
+            let test_name = event
+                .remove("testName")
+                .and_then(|name| name.as_str().map(str::to_string))
+                .unwrap_or_default();
+            attach_event(suites, event, &test_name);
+        });
+}
+
+fn attach_event(
+    suites: &mut [TestSuiteResult],
+    event: serde_json::Map<String, serde_json::Value>,
+    test_name: &str,
+) {
+    let Some((suite, full_name)) = suites.iter_mut().find_map(|suite| {
+        let full_name = suite
+            .test_results
+            .iter()
+            .find(|case| same_test(&case.full_name, test_name))?
+            .full_name
+            .clone();
+        Some((suite, full_name))
+    }) else {
+        return;
+    };
+    let entry = crate::format::bridge_console::http_bridge_console_entry(
+        event,
+        &suite.test_file_path,
+        &full_name,
+    );
+    suite.console.get_or_insert_with(Vec::new).push(entry);
+}
+
+/// libtest names a test's thread after its path from the crate root, which headlamp's names
+/// may extend or shorten by leading modules.
+fn same_test(full_name: &str, thread_name: &str) -> bool {
+    !thread_name.is_empty()
+        && (full_name == thread_name
+            || full_name.ends_with(&format!("::{thread_name}"))
+            || thread_name.ends_with(&format!("::{full_name}")))
+}
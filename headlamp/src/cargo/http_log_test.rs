@@ -0,0 +1,50 @@
+use crate::format::bridge_console::parse_bridge_console;
+use crate::session::RunSession;
+use crate::test_model::TestSuiteResult;
+use crate::test_support::model;
+
+use super::http_log::{HTTP_LOG_ENV, attach_http_events, run_env_with_http_log};
+
+fn suite(path: &str, full_name: &str) -> TestSuiteResult {
+    model::suite(path, vec![model::case(full_name, "failed")])
+}
+
+#[test]
+fn logged_http_calls_attach_to_the_test_named_by_their_thread() {
+    let session = RunSession::new(false).unwrap();
+    let run_env = run_env_with_http_log(&session);
+    let (_, log) = run_env
+        .vars
+        .iter()
+        .find(|(key, _)| key == HTTP_LOG_ENV)
+        .unwrap();
+    std::fs::write(
+        log,
+        [
+            r#"{"type":"httpResponse","timestampMs":5,"testName":"api::lists_users","method":"GET","url":"http://localhost/users","statusCode":500}"#,
+            r#"{"type":"httpAbort","timestampMs":6,"testName":"other::unknown","method":"GET","url":"http://localhost/x"}"#,
+            "not json",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let mut suites = vec![
+        suite("tests/health.rs", "health::ok"),
+        suite("tests/api.rs", "lists_users"),
+    ];
+    attach_http_events(&mut suites, &run_env);
+
+    assert!(suites[0].console.is_none());
+    let (http, _, _) = parse_bridge_console(suites[1].console.as_ref());
+    assert_eq!(http.len(), 1);
+    assert_eq!(http[0].status_code, Some(500));
+    assert_eq!(http[0].test_path.as_deref(), Some("tests/api.rs"));
+    assert_eq!(http[0].current_test_name.as_deref(), Some("lists_users"));
+
+    // The next run starts from an empty log.
+    run_env_with_http_log(&session);
+    let mut suites = vec![suite("tests/api.rs", "lists_users")];
+    attach_http_events(&mut suites, &run_env);
+    assert!(suites[0].console.is_none());
+}
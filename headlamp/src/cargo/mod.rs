@@ -2,13 +2,15 @@ use std::path::Path;
 use std::time::Instant;
 
 use headlamp_core::args::ParsedArgs;
-use headlamp_core::config::CoverageUi;
-use headlamp_core::format::ctx::make_ctx;
+use headlamp_core::format::cargo_build::{
+    CargoBuildDiagnostics, is_cargo_message, render_build_failed,
+};
+use headlamp_core::format::ctx::make_ctx_for_args;
 use headlamp_core::format::vitest::render_vitest_from_test_model;
 
 use crate::git::changed_files;
 use crate::live_progress::{LiveProgress, live_progress_mode};
-use crate::run::{RunError, run_bootstrap};
+use crate::run::{RunError, bootstrap_and_wait};
 use crate::streaming::run_streaming_capture_tail_merged;
 use crate::test_model::TestRunModel;
 
@@ -16,8 +18,14 @@ mod adapters;
 pub(crate) mod coverage;
 #[cfg(test)]
 mod coverage_abort_on_failure_semantics_test;
+pub(crate) mod doctest;
+pub mod features;
+pub mod http_log;
+#[cfg(test)]
+mod http_log_test;
 mod model_norm;
 mod nextest;
+pub mod nextest_config;
 pub(crate) mod paths;
 mod run_trace;
 mod runner_args;
@@ -26,9 +34,11 @@ mod runner_args_test;
 #[cfg(test)]
 mod rust_coverage_missing_test;
 pub(crate) mod selection;
+pub mod target;
 
 pub(crate) use model_norm::empty_test_run_model_for_exit_code;
 pub use nextest::run_cargo_nextest;
+pub(crate) use runner_args::libtest_args_for_binaries;
 
 fn apply_wall_clock_run_time_ms(
     mut model: headlamp_core::test_model::TestRunModel,
@@ -38,108 +48,63 @@ fn apply_wall_clock_run_time_ms(
     model
 }
 
-fn run_optional_bootstrap(repo_root: &Path, args: &ParsedArgs) -> Result<(), RunError> {
-    let Some(command) = args.bootstrap_command.as_deref() else {
-        return Ok(());
-    };
-    run_bootstrap(repo_root, command)
-}
-
 fn normalize_runner_exit_code(exit_code: i32) -> i32 {
     if exit_code == 0 { 0 } else { 1 }
 }
 
-struct RustCoverageContext {
-    toolchain: String,
-    enable_branch_coverage: bool,
-    paths: crate::rust_coverage::RustCoveragePaths,
-    llvm_profile_prefix: &'static str,
-}
-
-fn build_rust_coverage_context_if_enabled(
+/// Starts Rust coverage for the cargo-driven runners, whose builds share the headlamp target dir.
+fn start_rust_coverage(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     llvm_profile_prefix: &'static str,
-) -> Result<Option<RustCoverageContext>, RunError> {
-    if !crate::rust_coverage::should_collect_rust_coverage(args) {
-        return Ok(None);
-    }
-    let (toolchain, enable_branch_coverage) =
-        crate::rust_coverage::choose_llvm_tools_toolchain(repo_root);
-    crate::rust_coverage::ensure_llvm_tools_available(repo_root, toolchain.as_str())?;
-    let paths = crate::rust_coverage::rust_coverage_paths(args.keep_artifacts, repo_root, session);
-    let _ = std::fs::create_dir_all(&paths.profraw_dir);
-    crate::rust_coverage::purge_profile_artifacts(&paths.profraw_dir);
-    crate::rust_coverage::purge_profile_artifacts(
-        paths.profdata_path.parent().unwrap_or(repo_root),
-    );
-    Ok(Some(RustCoverageContext {
-        toolchain: toolchain.to_string(),
-        enable_branch_coverage,
-        paths,
+) -> Result<Option<crate::rust_coverage::RustCoverageRun>, RunError> {
+    let cargo_target_dir =
+        paths::headlamp_cargo_target_dir_for_duct(args.keep_artifacts, repo_root, session);
+    crate::rust_coverage::RustCoverageRun::start_if_enabled(
+        repo_root,
+        args,
+        session,
+        &cargo_target_dir,
         llvm_profile_prefix,
-    }))
+    )
 }
 
-fn build_instrumented_objects_for_rust_coverage(
+/// Builds the instrumented test binaries up front; llvm-cov needs them as objects for export.
+fn build_instrumented_objects(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     extra_cargo_args: &[String],
-    enable_branch_coverage: bool,
-    profraw_dir: &std::path::Path,
-    llvm_profile_prefix: &str,
+    coverage: Option<&crate::rust_coverage::RustCoverageRun>,
 ) -> Result<Vec<std::path::PathBuf>, RunError> {
-    let cargo_target_dir = crate::cargo::paths::headlamp_cargo_target_dir_for_duct(
-        args.keep_artifacts,
-        repo_root,
-        session,
-    );
-    let rustflags =
-        crate::rust_coverage::coverage_rustflags_with_branch_coverage(enable_branch_coverage);
-    let build_profile_prefix = format!("{llvm_profile_prefix}-build");
-    let build_profile_file =
-        crate::rust_coverage::llvm_profile_file_pattern(profraw_dir, build_profile_prefix.as_str());
-    let built =
-        crate::rust_runner::cargo_build::build_test_binaries_via_cargo_no_run_with_overrides(
-            repo_root,
-            args,
-            session,
-            extra_cargo_args,
-            &cargo_target_dir,
-            &rustflags,
-            Some(build_profile_file.as_os_str()),
-        )?;
-    crate::rust_coverage::purge_profile_artifacts(profraw_dir);
-    Ok(built.into_iter().map(|b| b.executable).collect::<Vec<_>>())
+    let Some(coverage) = coverage else {
+        return Ok(vec![]);
+    };
+    let built = coverage.build_instrumented_binaries(repo_root, args, session, extra_cargo_args)?;
+    Ok(built.into_iter().map(|b| b.executable).collect())
 }
 
-fn export_rust_coverage_reports(
-    repo_root: &Path,
-    ctx: &RustCoverageContext,
-    objects: &[std::path::PathBuf],
-) -> Result<(), RunError> {
-    crate::rust_coverage::merge_profraw_dir_to_profdata(
-        repo_root,
-        ctx.toolchain.as_str(),
-        &ctx.paths.profraw_dir,
-        &ctx.paths.profdata_path,
-    )?;
-    crate::rust_coverage::export_llvm_cov_reports(
-        repo_root,
-        ctx.toolchain.as_str(),
-        &ctx.paths.profdata_path,
-        objects,
-        &ctx.paths.lcov_path,
-        &ctx.paths.llvm_cov_json_path,
-    )
+const RUNNER_OUTPUT_HEAD_BYTES: usize = 8 * 1024;
+const RUNNER_OUTPUT_TAIL_BYTES: usize = 8 * 1024;
+
+/// Keeps the start and the end of a cargo invocation's output for the failure excerpt, and
+/// all of it in a log under the run's artifacts.
+pub(crate) fn runner_output_ring(
+    session: &crate::session::RunSession,
+    label: &str,
+) -> crate::streaming::RingBuffer {
+    crate::streaming::RingBuffer::with_head(RUNNER_OUTPUT_HEAD_BYTES, RUNNER_OUTPUT_TAIL_BYTES)
+        .with_full_log(&session.runner_log_path("rust", label))
 }
 
 fn print_runner_tail_if_failed_without_tests(
+    repo_root: &Path,
+    args: &ParsedArgs,
     exit_code: i32,
     model: &headlamp_core::test_model::TestRunModel,
     tail: &crate::streaming::RingBuffer,
+    build: &CargoBuildDiagnostics,
 ) {
     if exit_code == 0 {
         return;
@@ -147,14 +112,26 @@ fn print_runner_tail_if_failed_without_tests(
     if !model.test_results.is_empty() {
         return;
     }
-    let all = tail.lines().cloned().collect::<Vec<_>>();
-    let start = all.len().saturating_sub(60);
-    let last_lines = all[start..].iter().collect::<Vec<_>>();
-    if last_lines.is_empty() {
-        return;
+    if build.has_errors() {
+        let ctx = make_ctx_for_args(repo_root, args, false);
+        render_build_failed(build, &ctx)
+            .iter()
+            .for_each(|line| eprintln!("{line}"));
+    } else {
+        let excerpt = tail
+            .excerpt()
+            .into_iter()
+            .filter(|line| !is_cargo_message(line))
+            .collect::<Vec<_>>();
+        if excerpt.is_empty() {
+            return;
+        }
+        eprintln!("headlamp: runner failed before producing any test results; output:");
+        excerpt.iter().for_each(|line| eprintln!("{line}"));
+    }
+    if let Some(path) = tail.full_log_path() {
+        eprintln!("headlamp: full runner output: {}", path.display());
     }
-    eprintln!("headlamp: runner failed before producing any test results; last output:");
-    last_lines.into_iter().for_each(|line| eprintln!("{line}"));
 }
 
 pub fn run_cargo_test(
@@ -163,9 +140,9 @@ pub fn run_cargo_test(
     session: &crate::session::RunSession,
 ) -> Result<i32, RunError> {
     let started_at = Instant::now();
-    run_optional_bootstrap(repo_root, args)?;
+    bootstrap_and_wait(repo_root, args, session.env())?;
     let changed = changed_files_for_args(repo_root, args)?;
-    let selection = selection::derive_cargo_selection(repo_root, args, &changed);
+    let selection = selection::derive_cargo_selection(repo_root, args, session, &changed)?;
     if early_exit_for_zero_changed_selection_cargo_test(repo_root, args, session, &selection) {
         run_trace::trace_cargo_test_early_exit(
             repo_root,
@@ -176,34 +153,31 @@ pub fn run_cargo_test(
         );
         return Ok(0);
     }
-    let coverage_ctx =
-        build_rust_coverage_context_if_enabled(repo_root, args, session, "cargo-test")?;
-    let objects = coverage_ctx
-        .as_ref()
-        .map(|ctx| {
-            build_instrumented_objects_for_rust_coverage(
-                repo_root,
-                args,
-                session,
-                &selection.extra_cargo_args,
-                ctx.enable_branch_coverage,
-                &ctx.paths.profraw_dir,
-                ctx.llvm_profile_prefix,
-            )
-        })
-        .transpose()?
-        .unwrap_or_default();
+    let coverage = start_rust_coverage(repo_root, args, session, "cargo-test")?;
+    let objects = build_instrumented_objects(
+        repo_root,
+        args,
+        session,
+        &selection.extra_cargo_args,
+        coverage.as_ref(),
+    )?;
 
-    let run = run_cargo_test_streaming(
+    let mut run = run_cargo_test_streaming(
         repo_root,
         args,
         session,
         &selection.extra_cargo_args,
-        coverage_ctx
-            .as_ref()
-            .map(|ctx| (&ctx.paths, ctx.llvm_profile_prefix)),
+        coverage.as_ref(),
     )?;
-    print_runner_tail_if_failed_without_tests(run.exit_code, &run.model, &run.tail);
+    run.model.skipped_units = selection.skipped_units();
+    print_runner_tail_if_failed_without_tests(
+        repo_root,
+        args,
+        run.exit_code,
+        &run.model,
+        &run.tail,
+        &run.build,
+    );
     maybe_print_rendered_model(repo_root, args, run.exit_code, &run.model);
     if should_abort_coverage_after_run(args, &run.model) {
         return Ok(run_trace::normalize_and_trace_cargo_test_coverage_abort(
@@ -215,10 +189,15 @@ pub fn run_cargo_test(
             run.exit_code,
         ));
     }
-    if let Some(ctx) = coverage_ctx.as_ref() {
-        export_rust_coverage_reports(repo_root, ctx, &objects)?;
+    if let Some(coverage) = coverage.as_ref() {
+        coverage.export_reports(repo_root, &objects)?;
     }
-    let final_exit = maybe_print_lcov_and_adjust_exit(repo_root, args, session, run.exit_code);
+    let final_exit = crate::rust_coverage::print_coverage_and_adjust_exit(
+        repo_root,
+        args,
+        session,
+        run.exit_code,
+    );
     run_trace::trace_cargo_test_final_exit(
         repo_root,
         args,
@@ -246,23 +225,19 @@ fn early_exit_for_zero_changed_selection_cargo_test(
         .changed
         .map(selection::changed_mode_to_cli_string)
         .unwrap_or("all");
-    println!("headlamp: selected 0 tests (changed={changed_mode})");
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        false,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
-    let rendered = render_vitest_from_test_model(
-        &empty_test_run_model_for_exit_code(0),
-        &ctx,
-        args.only_failures,
-    );
+    crate::format::report_out::println(format!(
+        "headlamp: selected 0 tests (changed={changed_mode})"
+    ));
+    let ctx = make_ctx_for_args(repo_root, args, false);
+    let model = TestRunModel {
+        skipped_units: selection.skipped_units(),
+        ..empty_test_run_model_for_exit_code(0)
+    };
+    let rendered = render_vitest_from_test_model(&model, &ctx, args.only_failures);
     if !rendered.trim().is_empty() {
-        println!("{rendered}");
+        crate::format::report_out::println(&rendered);
     }
-    let _ = if args.collect_coverage && args.coverage_ui != CoverageUi::Jest {
+    let _ = if args.collect_coverage {
         coverage::print_lcov(repo_root, args, session)
     } else {
         false
@@ -275,6 +250,7 @@ struct CargoTestRunOutput {
     exit_code: i32,
     model: headlamp_core::test_model::TestRunModel,
     tail: crate::streaming::RingBuffer,
+    build: CargoBuildDiagnostics,
 }
 
 fn run_cargo_test_streaming(
@@ -282,16 +258,24 @@ fn run_cargo_test_streaming(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     extra_cargo_args: &[String],
-    coverage: Option<(&crate::rust_coverage::RustCoveragePaths, &'static str)>,
+    coverage: Option<&crate::rust_coverage::RustCoverageRun>,
 ) -> Result<CargoTestRunOutput, RunError> {
     let mode = live_progress_mode(
         headlamp_core::format::terminal::is_output_terminal(),
         args.ci,
         args.quiet,
     );
-    let live_progress = LiveProgress::start(1, mode);
+    let live_progress = LiveProgress::start_with_args(1, mode, args);
     let run_start = Instant::now();
-    let cmd = build_cargo_test_command(repo_root, args, session, extra_cargo_args, coverage);
+    let run_env = http_log::run_env_with_http_log(session);
+    let cmd = build_cargo_test_command(
+        repo_root,
+        args,
+        session,
+        extra_cargo_args,
+        coverage,
+        &run_env,
+    );
     headlamp_core::diagnostics_trace::maybe_write_run_trace(
         repo_root,
         "cargo-test",
@@ -302,9 +286,19 @@ fn run_cargo_test_streaming(
             "command": headlamp_core::diagnostics_trace::command_summary_json(&cmd),
         }),
     );
-    let mut adapter = adapters::CargoTestAdapter::new(repo_root, args.only_failures);
-    let (exit_code, tail) =
-        run_streaming_capture_tail_merged(cmd, &live_progress, &mut adapter, 1024 * 1024)?;
+    tracing::debug!(
+        target: "cargo",
+        command = %headlamp_core::diagnostics_trace::command_summary_json(&cmd),
+        "running cargo test"
+    );
+    let mut adapter =
+        adapters::CargoTestAdapter::new(repo_root, args.only_failures, session.env().is_ci());
+    let (exit_code, tail) = run_streaming_capture_tail_merged(
+        cmd,
+        &live_progress,
+        &mut adapter,
+        runner_output_ring(session, "cargo-test"),
+    )?;
     live_progress.increment_done(1);
     live_progress.finish();
     let model = adapter
@@ -313,11 +307,14 @@ fn run_cargo_test_streaming(
         .unwrap_or_else(|| empty_test_run_model_for_exit_code(exit_code));
     let elapsed_ms = run_start.elapsed().as_millis() as u64;
     let model = apply_wall_clock_run_time_ms(model, elapsed_ms);
-    let model = model_norm::normalize_cargo_test_model_by_panic_locations(repo_root, model);
+    let mut model = model_norm::normalize_cargo_test_model_by_panic_locations(repo_root, model);
+    http_log::attach_http_events(&mut model.test_results, &run_env);
+    features::label_model_with_feature_set(args, &mut model);
     Ok(CargoTestRunOutput {
         exit_code,
         model,
         tail,
+        build: adapter.build,
     })
 }
 
@@ -326,11 +323,12 @@ fn build_cargo_test_command(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     extra_cargo_args: &[String],
-    coverage: Option<(&crate::rust_coverage::RustCoveragePaths, &'static str)>,
+    coverage: Option<&crate::rust_coverage::RustCoverageRun>,
+    run_env: &crate::process::RunEnvironment,
 ) -> std::process::Command {
     let mut cmd = std::process::Command::new("cargo");
-    let use_nightly_rustc = crate::cargo::paths::nightly_rustc_exists(repo_root);
-    if use_nightly_rustc {
+    let nightly = crate::cargo::paths::nightly_rustc_exists(repo_root);
+    if nightly {
         cmd.arg("+nightly");
     }
     cmd.args(runner_args::build_cargo_test_args(
@@ -338,24 +336,20 @@ fn build_cargo_test_command(
         args,
         extra_cargo_args,
     ));
+    // libtest only shuffles under `-Z unstable-options`; stable toolchains keep their order.
+    if let Some(seed) = args.shuffle_seed.filter(|_| nightly) {
+        cmd.args(["-Z", "unstable-options"]);
+        cmd.args(crate::shuffle::libtest_seed_args(seed));
+    }
     cmd.current_dir(repo_root);
     paths::apply_headlamp_cargo_target_dir(&mut cmd, args.keep_artifacts, repo_root, session);
+    target::apply_target_runner_env(&mut cmd, args);
     cmd.env("RUST_BACKTRACE", "1");
     cmd.env("RUST_LIB_BACKTRACE", "1");
-    if let Some((paths, prefix)) = coverage {
-        let _ = std::fs::create_dir_all(&paths.profraw_dir);
-        let llvm_profile =
-            crate::rust_coverage::llvm_profile_file_pattern(&paths.profraw_dir, prefix);
-        cmd.env("LLVM_PROFILE_FILE", llvm_profile);
-        let existing = std::env::var("RUSTFLAGS").unwrap_or_default();
-        let enable_branch_coverage = use_nightly_rustc;
-        let rustflags = crate::rust_coverage::append_rustflags(
-            &existing,
-            &crate::rust_coverage::coverage_rustflags_with_branch_coverage(enable_branch_coverage),
-        );
-        cmd.env("RUSTFLAGS", rustflags);
-        cmd.env("CARGO_INCREMENTAL", "0");
+    if let Some(coverage) = coverage {
+        coverage.apply_to_command(&mut cmd);
     }
+    run_env.apply(&mut cmd);
     cmd
 }
 
@@ -393,23 +387,19 @@ fn early_exit_for_zero_changed_selection(
         .changed
         .map(selection::changed_mode_to_cli_string)
         .unwrap_or("all");
-    println!("headlamp: selected 0 tests (changed={changed_mode})");
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        false,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
-    let rendered = render_vitest_from_test_model(
-        &empty_test_run_model_for_exit_code(0),
-        &ctx,
-        args.only_failures,
-    );
+    crate::format::report_out::println(format!(
+        "headlamp: selected 0 tests (changed={changed_mode})"
+    ));
+    let ctx = make_ctx_for_args(repo_root, args, false);
+    let model = TestRunModel {
+        skipped_units: selection.skipped_units(),
+        ..empty_test_run_model_for_exit_code(0)
+    };
+    let rendered = render_vitest_from_test_model(&model, &ctx, args.only_failures);
     if !rendered.trim().is_empty() {
-        println!("{rendered}");
+        crate::format::report_out::println(&rendered);
     }
-    let thresholds_failed = if args.collect_coverage && args.coverage_ui != CoverageUi::Jest {
+    let thresholds_failed = if args.collect_coverage {
         coverage::print_lcov(repo_root, args, session)
     } else {
         false
@@ -422,56 +412,11 @@ fn maybe_print_rendered_model(
     exit_code: i32,
     model: &headlamp_core::test_model::TestRunModel,
 ) {
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        exit_code != 0,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
+    crate::recorded::record_failed_tests(model);
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_test_names(model));
+    let ctx = make_ctx_for_args(repo_root, args, exit_code != 0);
     let rendered = render_vitest_from_test_model(model, &ctx, args.only_failures);
     if !rendered.trim().is_empty() {
-        println!("{rendered}");
-    }
-}
-
-fn maybe_print_lcov_and_adjust_exit(
-    repo_root: &Path,
-    args: &ParsedArgs,
-    session: &crate::session::RunSession,
-    exit_code: i32,
-) -> i32 {
-    let coverage_requested = args.collect_coverage && args.coverage_ui != CoverageUi::Jest;
-    let thresholds_failed = if coverage_requested {
-        coverage::print_lcov(repo_root, args, session)
-    } else {
-        false
-    };
-    let normalized_exit_code = normalize_runner_exit_code(exit_code);
-    if coverage_requested && !thresholds_failed {
-        // `print_lcov` returns false both for "no thresholds failed" and for "could not print"
-        // (e.g., lcov missing). If coverage was explicitly requested, never fail silently:
-        // if we printed nothing due to missing coverage artifacts, fail with an actionable hint.
-        //
-        // Heuristic: if lcov is missing/unreadable, `print_lcov` returns false and there will be no
-        // thresholds output either. In that case, force a non-zero exit so users notice.
-        let expected_lcov_path = if args.keep_artifacts {
-            repo_root.join("coverage").join("lcov.info")
-        } else {
-            session.subdir("coverage").join("rust").join("lcov.info")
-        };
-        if !expected_lcov_path.exists() {
-            eprintln!(
-                "headlamp: coverage was requested but Rust lcov was not generated (missing {}). \
-Install `llvm-tools-preview` (rustup) and re-run.",
-                expected_lcov_path.to_string_lossy()
-            );
-            return 1;
-        }
-    }
-    if normalized_exit_code == 0 && thresholds_failed {
-        1
-    } else {
-        normalized_exit_code
+        crate::format::report_out::println(&rendered);
     }
 }
@@ -54,6 +54,7 @@ pub(crate) fn empty_test_run_model_for_exit_code(exit_code: i32) -> TestRunModel
             success,
             run_time_ms: Some(0),
         },
+        skipped_units: vec![],
     }
 }
 
@@ -81,6 +82,7 @@ pub(super) fn normalize_cargo_test_model_by_panic_locations(
         start_time: model.start_time,
         test_results: suites,
         aggregated,
+        skipped_units: vec![],
     }
 }
 
@@ -12,52 +12,54 @@ pub fn run_cargo_nextest(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
 ) -> Result<i32, RunError> {
-    super::run_optional_bootstrap(repo_root, args)?;
+    crate::run::bootstrap_and_wait(repo_root, args, session.env())?;
     let changed = super::changed_files_for_args(repo_root, args)?;
-    let selection = super::selection::derive_cargo_selection(repo_root, args, &changed);
+    let selection = super::selection::derive_cargo_selection(repo_root, args, session, &changed)?;
     if let Some(exit_code) =
         super::early_exit_for_zero_changed_selection(repo_root, args, session, &selection)
     {
         return Ok(exit_code);
     }
     ensure_cargo_nextest_is_available(repo_root, args, session)?;
-    let coverage_ctx =
-        super::build_rust_coverage_context_if_enabled(repo_root, args, session, "cargo-nextest")?;
-    let objects = coverage_ctx
-        .as_ref()
-        .map(|ctx| {
-            super::build_instrumented_objects_for_rust_coverage(
-                repo_root,
-                args,
-                session,
-                &selection.extra_cargo_args,
-                ctx.enable_branch_coverage,
-                &ctx.paths.profraw_dir,
-                ctx.llvm_profile_prefix,
-            )
-        })
-        .transpose()?
-        .unwrap_or_default();
+    let coverage = super::start_rust_coverage(repo_root, args, session, "cargo-nextest")?;
+    let objects = super::build_instrumented_objects(
+        repo_root,
+        args,
+        session,
+        &selection.extra_cargo_args,
+        coverage.as_ref(),
+    )?;
 
     let run = run_nextest_streaming(
         repo_root,
         args,
         session,
         &selection.extra_cargo_args,
-        coverage_ctx
-            .as_ref()
-            .map(|ctx| (&ctx.paths, ctx.llvm_profile_prefix)),
+        coverage.as_ref(),
     )?;
-    super::print_runner_tail_if_failed_without_tests(run.exit_code, &run.model, &run.tail);
+    let mut run = append_doctest_phase(repo_root, args, session, &selection.extra_cargo_args, run)?;
+    run.model.skipped_units = selection.skipped_units();
+    super::print_runner_tail_if_failed_without_tests(
+        repo_root,
+        args,
+        run.exit_code,
+        &run.model,
+        &run.tail,
+        &run.build,
+    );
     super::maybe_print_rendered_model(repo_root, args, run.exit_code, &run.model);
     if super::should_abort_coverage_after_run(args, &run.model) {
         return Ok(super::normalize_runner_exit_code(run.exit_code));
     }
-    if let Some(ctx) = coverage_ctx.as_ref() {
-        super::export_rust_coverage_reports(repo_root, ctx, &objects)?;
+    if let Some(coverage) = coverage.as_ref() {
+        coverage.export_reports(repo_root, &objects)?;
     }
-    let final_exit =
-        super::maybe_print_lcov_and_adjust_exit(repo_root, args, session, run.exit_code);
+    let final_exit = crate::rust_coverage::print_coverage_and_adjust_exit(
+        repo_root,
+        args,
+        session,
+        run.exit_code,
+    );
     Ok(final_exit)
 }
 
@@ -66,12 +68,58 @@ fn ensure_cargo_nextest_is_available(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
 ) -> Result<(), RunError> {
-    super::coverage::has_cargo_nextest(repo_root, args, session)
-        .then_some(())
-        .ok_or_else(|| RunError::MissingRunner {
-            runner: "cargo-nextest".to_string(),
-            hint: "expected `cargo nextest` to be installed and available".to_string(),
-        })
+    let recipe = crate::tool_install::InstallRecipe::cargo_nextest();
+    crate::tool_install::ensure_with_install(repo_root, args, &recipe, || {
+        super::coverage::has_cargo_nextest(repo_root, args, session)
+            .then_some(())
+            .ok_or_else(|| RunError::MissingRunner {
+                runner: "cargo-nextest".to_string(),
+                hint: format!(
+                    "expected `cargo nextest` to be installed and available; try `{}`",
+                    recipe.command_line()
+                ),
+            })
+    })
+}
+
+/// nextest skips doctests, so they run afterwards and land in the same model and footer.
+fn append_doctest_phase(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    extra_cargo_args: &[String],
+    run: NextestRunOutput,
+) -> Result<NextestRunOutput, RunError> {
+    let NextestRunOutput {
+        exit_code,
+        model,
+        tail,
+        build,
+    } = run;
+    let build_failed = exit_code != 0 && model.test_results.is_empty();
+    let doctests = if build_failed {
+        None
+    } else {
+        super::doctest::run_doctest_phase(repo_root, args, session, extra_cargo_args, None)?
+    };
+    let (exit_code, mut model) = match doctests {
+        Some(doctests) => (
+            if exit_code == 0 {
+                doctests.exit_code
+            } else {
+                exit_code
+            },
+            super::doctest::merge_doctest_phase(model, doctests),
+        ),
+        None => (exit_code, model),
+    };
+    super::features::label_model_with_feature_set(args, &mut model);
+    Ok(NextestRunOutput {
+        exit_code,
+        model,
+        tail,
+        build,
+    })
 }
 
 #[derive(Debug)]
@@ -79,6 +127,7 @@ struct NextestRunOutput {
     exit_code: i32,
     model: headlamp_core::test_model::TestRunModel,
     tail: crate::streaming::RingBuffer,
+    build: headlamp_core::format::cargo_build::CargoBuildDiagnostics,
 }
 
 fn run_nextest_streaming(
@@ -86,16 +135,24 @@ fn run_nextest_streaming(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     extra_cargo_args: &[String],
-    coverage: Option<(&crate::rust_coverage::RustCoveragePaths, &'static str)>,
+    coverage: Option<&crate::rust_coverage::RustCoverageRun>,
 ) -> Result<NextestRunOutput, RunError> {
     let mode = live_progress_mode(
         headlamp_core::format::terminal::is_output_terminal(),
         args.ci,
         args.quiet,
     );
-    let live_progress = LiveProgress::start(1, mode);
+    let live_progress = LiveProgress::start_with_args(1, mode, args);
     let run_start = Instant::now();
-    let cmd = build_nextest_command(repo_root, args, session, extra_cargo_args, coverage);
+    let run_env = super::http_log::run_env_with_http_log(session);
+    let cmd = build_nextest_command(
+        repo_root,
+        args,
+        session,
+        extra_cargo_args,
+        coverage,
+        &run_env,
+    );
     headlamp_core::diagnostics_trace::maybe_write_run_trace(
         repo_root,
         "cargo-nextest",
@@ -106,21 +163,33 @@ fn run_nextest_streaming(
             "command": headlamp_core::diagnostics_trace::command_summary_json(&cmd),
         }),
     );
-    let mut adapter = super::adapters::NextestAdapter::new(repo_root, args.only_failures);
-    let (exit_code, tail) =
-        run_streaming_capture_tail_merged(cmd, &live_progress, &mut adapter, 1024 * 1024)?;
+    tracing::debug!(
+        target: "cargo",
+        command = %headlamp_core::diagnostics_trace::command_summary_json(&cmd),
+        "running cargo nextest"
+    );
+    let mut adapter =
+        super::adapters::NextestAdapter::new(repo_root, args.only_failures, session.env().is_ci());
+    let (exit_code, tail) = run_streaming_capture_tail_merged(
+        cmd,
+        &live_progress,
+        &mut adapter,
+        super::runner_output_ring(session, "cargo-nextest"),
+    )?;
     live_progress.increment_done(1);
     live_progress.finish();
-    let super::adapters::NextestAdapter { parser, .. } = adapter;
+    let super::adapters::NextestAdapter { parser, build, .. } = adapter;
     let model = parser
         .finalize()
         .unwrap_or_else(|| super::empty_test_run_model_for_exit_code(exit_code));
     let elapsed_ms = run_start.elapsed().as_millis() as u64;
-    let model = super::apply_wall_clock_run_time_ms(model, elapsed_ms);
+    let mut model = super::apply_wall_clock_run_time_ms(model, elapsed_ms);
+    super::http_log::attach_http_events(&mut model.test_results, &run_env);
     Ok(NextestRunOutput {
         exit_code,
         model,
         tail,
+        build,
     })
 }
 
@@ -129,17 +198,20 @@ fn build_nextest_command(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
     extra_cargo_args: &[String],
-    coverage: Option<(&crate::rust_coverage::RustCoveragePaths, &'static str)>,
+    coverage: Option<&crate::rust_coverage::RustCoverageRun>,
+    run_env: &crate::process::RunEnvironment,
 ) -> std::process::Command {
     let mut cmd = std::process::Command::new("cargo");
-    let use_nightly_rustc = super::paths::nightly_rustc_exists(repo_root);
-    if use_nightly_rustc {
+    if super::paths::nightly_rustc_exists(repo_root) {
         cmd.arg("+nightly");
     }
+    let profile =
+        super::nextest_config::load_profile(repo_root, &super::nextest_config::profile_name(args));
     cmd.args(super::runner_args::build_nextest_run_args(
         None,
         args,
         extra_cargo_args,
+        profile.status_level(&args.runner_args),
     ));
     cmd.current_dir(repo_root);
     super::paths::apply_headlamp_cargo_target_dir(
@@ -148,22 +220,13 @@ fn build_nextest_command(
         repo_root,
         session,
     );
+    super::target::apply_target_runner_env(&mut cmd, args);
     cmd.env("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1");
     cmd.env("RUST_BACKTRACE", "1");
     cmd.env("RUST_LIB_BACKTRACE", "1");
-    if let Some((paths, prefix)) = coverage {
-        let _ = std::fs::create_dir_all(&paths.profraw_dir);
-        let llvm_profile =
-            crate::rust_coverage::llvm_profile_file_pattern(&paths.profraw_dir, prefix);
-        cmd.env("LLVM_PROFILE_FILE", llvm_profile);
-        let existing = std::env::var("RUSTFLAGS").unwrap_or_default();
-        let enable_branch_coverage = use_nightly_rustc;
-        let rustflags = crate::rust_coverage::append_rustflags(
-            &existing,
-            &crate::rust_coverage::coverage_rustflags_with_branch_coverage(enable_branch_coverage),
-        );
-        cmd.env("RUSTFLAGS", rustflags);
-        cmd.env("CARGO_INCREMENTAL", "0");
+    if let Some(coverage) = coverage {
+        coverage.apply_to_command(&mut cmd);
     }
+    run_env.apply(&mut cmd);
     cmd
 }
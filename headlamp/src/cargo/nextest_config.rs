@@ -0,0 +1,148 @@
+//! The repo's `.config/nextest.toml`, read for what it makes nextest do that headlamp has to
+//! report: retries (tests that pass on a later try are flaky) and slow timeouts that terminate
+//! tests. nextest still applies the profile itself; headlamp picks the profile (`--profile`) and
+//! asks for the status lines that say which tests were retried or timed out.
+
+use std::path::Path;
+
+use headlamp_core::args::ParsedArgs;
+use toml::Value;
+
+pub const CONFIG_PATH: &str = ".config/nextest.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlowTimeout {
+    pub period: String,
+    /// Periods after which nextest kills the test; without it a slow test only gets reported.
+    pub terminate_after: Option<u32>,
+}
+
+/// `[[profile.<name>.overrides]]`: settings for the tests matching `filter`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NextestOverride {
+    pub filter: Option<String>,
+    pub retries: Option<u32>,
+    pub slow_timeout: Option<SlowTimeout>,
+}
+
+/// A profile with `[profile.default]` layered under it, as nextest resolves custom profiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NextestProfile {
+    pub name: String,
+    pub retries: u32,
+    pub slow_timeout: Option<SlowTimeout>,
+    /// The profile's own overrides first, then the default profile's.
+    pub overrides: Vec<NextestOverride>,
+}
+
+impl NextestProfile {
+    /// Whether any test may be retried, by the profile or one of its overrides.
+    pub fn may_retry(&self) -> bool {
+        self.retries > 0 || self.overrides.iter().any(|o| o.retries.unwrap_or(0) > 0)
+    }
+
+    /// Whether any test may be killed for running too long.
+    pub fn may_time_out(&self) -> bool {
+        self.slow_timeout
+            .iter()
+            .chain(
+                self.overrides
+                    .iter()
+                    .filter_map(|o| o.slow_timeout.as_ref()),
+            )
+            .any(|timeout| timeout.terminate_after.is_some())
+    }
+
+    /// nextest's `--status-level`: `retry` lists every try and `fail` the timeouts, which the
+    /// stream parser reads; `none` otherwise, since the JSON events carry everything else.
+    pub fn status_level(&self, cargo_args: &[String]) -> &'static str {
+        let retries_flag = cargo_args
+            .iter()
+            .any(|t| t == "--retries" || t.starts_with("--retries="));
+        if retries_flag || self.may_retry() {
+            "retry"
+        } else if self.may_time_out() {
+            "fail"
+        } else {
+            "none"
+        }
+    }
+}
+
+/// `--nextest-profile`, else nextest's own `NEXTEST_PROFILE`, else `default`.
+pub fn profile_name(args: &ParsedArgs) -> String {
+    args.nextest_profile
+        .clone()
+        .or_else(|| std::env::var("NEXTEST_PROFILE").ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// A missing or unreadable config is nextest's defaults: no retries, no timeouts.
+pub fn load_profile(repo_root: &Path, name: &str) -> NextestProfile {
+    std::fs::read_to_string(repo_root.join(CONFIG_PATH))
+        .ok()
+        .map(|text| parse_profile(&text, name))
+        .unwrap_or_else(|| NextestProfile {
+            name: name.to_string(),
+            ..NextestProfile::default()
+        })
+}
+
+pub fn parse_profile(text: &str, name: &str) -> NextestProfile {
+    let config = text.parse::<toml::Table>().unwrap_or_default();
+    let profiles = config.get("profile").and_then(Value::as_table);
+    let default = profiles.and_then(|profiles| profiles.get("default"));
+    let named = (name != "default")
+        .then(|| profiles.and_then(|profiles| profiles.get(name)))
+        .flatten();
+    let layers = named.into_iter().chain(default).collect::<Vec<_>>();
+    NextestProfile {
+        name: name.to_string(),
+        retries: layers
+            .iter()
+            .find_map(|layer| layer.get("retries").and_then(retries_count))
+            .unwrap_or(0),
+        slow_timeout: layers
+            .iter()
+            .find_map(|layer| layer.get("slow-timeout").and_then(slow_timeout)),
+        overrides: layers
+            .iter()
+            .filter_map(|layer| layer.get("overrides").and_then(Value::as_array))
+            .flatten()
+            .map(|entry| NextestOverride {
+                filter: entry
+                    .get("filter")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                retries: entry.get("retries").and_then(retries_count),
+                slow_timeout: entry.get("slow-timeout").and_then(slow_timeout),
+            })
+            .collect(),
+    }
+}
+
+/// `retries = 2` or `retries = { backoff = "fixed", count = 2, ... }`.
+fn retries_count(value: &Value) -> Option<u32> {
+    value
+        .as_integer()
+        .or_else(|| value.get("count").and_then(Value::as_integer))
+        .and_then(|count| u32::try_from(count).ok())
+}
+
+/// `slow-timeout = "60s"` or `slow-timeout = { period = "60s", terminate-after = 2 }`.
+fn slow_timeout(value: &Value) -> Option<SlowTimeout> {
+    if let Some(period) = value.as_str() {
+        return Some(SlowTimeout {
+            period: period.to_string(),
+            terminate_after: None,
+        });
+    }
+    Some(SlowTimeout {
+        period: value.get("period")?.as_str()?.to_string(),
+        terminate_after: value
+            .get("terminate-after")
+            .and_then(Value::as_integer)
+            .and_then(|count| u32::try_from(count).ok()),
+    })
+}
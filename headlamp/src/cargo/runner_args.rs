@@ -1,9 +1,24 @@
 use headlamp_core::args::ParsedArgs;
 
+/// Build diagnostics as JSON with rustc's colored rendering, so a compile failure can be shown
+/// as a "Build failed" section rather than raw output.
+const CARGO_MESSAGE_FORMAT: &str = "json-diagnostic-rendered-ansi";
+
+fn overrides_flag(cargo_args: &[String], extra_cargo_args: &[String], flag: &str) -> bool {
+    let prefix = format!("{flag}=");
+    cargo_args
+        .iter()
+        .chain(extra_cargo_args)
+        .any(|token| token == flag || token.starts_with(&prefix))
+}
+
+/// `status_level` comes from the nextest profile: the status lines it adds (retries, timeouts)
+/// go to the stream parser with the JSON events.
 pub(super) fn build_nextest_run_args(
     filter: Option<&str>,
     args: &ParsedArgs,
     extra_cargo_args: &[String],
+    status_level: &str,
 ) -> Vec<String> {
     let (cargo_args, test_binary_args) = split_cargo_passthrough_args(&args.runner_args);
     let mut cmd_args: Vec<String> = vec!["nextest".to_string(), "run".to_string()];
@@ -19,10 +34,15 @@ pub(super) fn build_nextest_run_args(
         "--color".to_string(),
         "never".to_string(),
         "--status-level".to_string(),
-        "none".to_string(),
+        status_level.to_string(),
         "--final-status-level".to_string(),
         "none".to_string(),
-        "--no-fail-fast".to_string(),
+        if args.bail {
+            "--fail-fast"
+        } else {
+            "--no-fail-fast"
+        }
+        .to_string(),
         "--show-progress".to_string(),
         "none".to_string(),
         "--success-output".to_string(),
@@ -34,9 +54,9 @@ pub(super) fn build_nextest_run_args(
         "--message-format".to_string(),
         "libtest-json-plus".to_string(),
     ]);
-    if cargo_quiet {
-        cmd_args.push("--cargo-quiet".to_string());
-    }
+    cmd_args.extend(cargo_quiet.then(|| "--cargo-quiet".to_string()));
+    let profile = args.nextest_profile.iter();
+    cmd_args.extend(profile.flat_map(|name| ["--profile".to_string(), name.clone()]));
 
     let translated = translate_libtest_args_to_nextest(&test_binary_args);
     if args.sequential
@@ -48,7 +68,13 @@ pub(super) fn build_nextest_run_args(
         cmd_args.extend(["--test-threads".to_string(), n.to_string()]);
     }
 
-    cmd_args.extend(extra_cargo_args.iter().cloned());
+    if !overrides_flag(&cargo_args, extra_cargo_args, "--cargo-message-format") {
+        cmd_args.extend([
+            "--cargo-message-format".to_string(),
+            CARGO_MESSAGE_FORMAT.to_string(),
+        ]);
+    }
+    cmd_args.extend(extra_cargo_args.iter().map(|arg| nextest_cargo_arg(arg)));
     cmd_args.extend(cargo_args);
     if let Some(f) = filter.map(|s| s.trim()).filter(|s| !s.is_empty()) {
         cmd_args.push(f.to_string());
@@ -63,6 +89,13 @@ pub(super) fn build_nextest_run_args(
     cmd_args
 }
 
+/// nextest reserves `--profile` for its own config profiles.
+fn nextest_cargo_arg(arg: &str) -> String {
+    arg.strip_prefix("--profile=")
+        .map(|profile| format!("--cargo-profile={profile}"))
+        .unwrap_or_else(|| arg.to_string())
+}
+
 pub(super) fn build_cargo_test_args(
     filter: Option<&str>,
     args: &ParsedArgs,
@@ -74,11 +107,16 @@ pub(super) fn build_cargo_test_args(
         cmd_args.push(f.to_string());
     }
     cmd_args.extend(extra_cargo_args.iter().cloned());
-    if !cargo_args.iter().any(|t| t == "--no-fail-fast")
+    // cargo stops at the first failing test binary unless told not to.
+    if !args.bail
+        && !cargo_args.iter().any(|t| t == "--no-fail-fast")
         && !extra_cargo_args.iter().any(|t| t == "--no-fail-fast")
     {
         cmd_args.push("--no-fail-fast".to_string());
     }
+    if !overrides_flag(&cargo_args, extra_cargo_args, "--message-format") {
+        cmd_args.push(format!("--message-format={CARGO_MESSAGE_FORMAT}"));
+    }
     cmd_args.extend(cargo_args);
 
     let mut normalized_test_args: Vec<String> = vec!["--color".to_string(), "never".to_string()];
@@ -119,6 +157,46 @@ fn should_force_show_output(test_binary_args: &[String]) -> bool {
     should_force_nocapture(test_binary_args)
 }
 
+/// Cargo flags whose value is the next token, so it isn't read as `cargo test`'s name filter.
+const CARGO_VALUE_FLAGS: [&str; 16] = [
+    "-p",
+    "--package",
+    "--test",
+    "--bin",
+    "--example",
+    "--bench",
+    "-F",
+    "--features",
+    "--target",
+    "--target-dir",
+    "--profile",
+    "-j",
+    "--jobs",
+    "--manifest-path",
+    "--config",
+    "-Z",
+];
+
+/// `args` for running the test binaries directly: `cargo test`'s name filters plus what follows
+/// `--` reach libtest, while cargo's own flags (and nextest's) are dropped.
+pub(crate) fn libtest_args_for_binaries(args: &ParsedArgs) -> ParsedArgs {
+    let (cargo_args, test_binary_args) = split_cargo_passthrough_args(&args.runner_args);
+    let filters = cargo_args
+        .iter()
+        .enumerate()
+        .filter(|(index, token)| {
+            !token.starts_with('-')
+                && !index
+                    .checked_sub(1)
+                    .is_some_and(|prev| CARGO_VALUE_FLAGS.contains(&cargo_args[prev].as_str()))
+        })
+        .map(|(_, token)| token.clone());
+    ParsedArgs {
+        runner_args: filters.chain(test_binary_args).collect(),
+        ..args.clone()
+    }
+}
+
 fn split_cargo_passthrough_args(passthrough: &[String]) -> (Vec<String>, Vec<String>) {
     let sanitized = passthrough
         .iter()
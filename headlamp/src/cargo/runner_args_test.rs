@@ -14,7 +14,88 @@ fn with_env_var_removed<T>(key: &str, f: impl FnOnce() -> T) -> T {
 fn nextest_args_non_tty_non_ci_do_not_enable_cargo_quiet() {
     with_env_var_removed("CI", || {
         let parsed = derive_args(&[], &[], false);
-        let cmd_args = super::runner_args::build_nextest_run_args(None, &parsed, &[]);
+        let cmd_args = super::runner_args::build_nextest_run_args(None, &parsed, &[], "none");
         assert!(!cmd_args.iter().any(|t| t == "--cargo-quiet"));
     });
 }
+
+#[test]
+fn cargo_profile_is_spelled_per_runner() {
+    with_env_var_removed("CI", || {
+        let parsed = derive_args(&[], &["--cargo-profile=test-fast".to_string()], false);
+        let extra = super::target::cargo_profile_args(&parsed);
+        assert_eq!(extra, vec!["--profile=test-fast".to_string()]);
+        let cargo = super::runner_args::build_cargo_test_args(None, &parsed, &extra);
+        assert!(cargo.iter().any(|t| t == "--profile=test-fast"));
+        let nextest = super::runner_args::build_nextest_run_args(None, &parsed, &extra, "none");
+        assert!(nextest.iter().any(|t| t == "--cargo-profile=test-fast"));
+        assert!(!nextest.iter().any(|t| t == "--profile=test-fast"));
+    });
+}
+
+#[test]
+fn build_diagnostics_are_requested_as_json_unless_overridden() {
+    with_env_var_removed("CI", || {
+        let parsed = derive_args(&[], &[], false);
+        let cargo = super::runner_args::build_cargo_test_args(None, &parsed, &[]);
+        assert!(
+            cargo
+                .iter()
+                .any(|t| t == "--message-format=json-diagnostic-rendered-ansi")
+        );
+        let nextest = super::runner_args::build_nextest_run_args(None, &parsed, &[], "none");
+        let at = nextest
+            .iter()
+            .position(|t| t == "--cargo-message-format")
+            .expect("nextest cargo message format");
+        assert_eq!(nextest[at + 1], "json-diagnostic-rendered-ansi");
+
+        let parsed = derive_args(&[], &["--message-format=short".to_string()], false);
+        let cargo = super::runner_args::build_cargo_test_args(None, &parsed, &[]);
+        assert_eq!(
+            cargo
+                .iter()
+                .filter(|t| t.starts_with("--message-format"))
+                .collect::<Vec<_>>(),
+            vec!["--message-format=short"]
+        );
+    });
+}
+
+#[test]
+fn bail_lets_cargo_and_nextest_stop_at_the_first_failure() {
+    with_env_var_removed("CI", || {
+        let parsed = derive_args(&[], &[], false);
+        let cargo = super::runner_args::build_cargo_test_args(None, &parsed, &[]);
+        assert!(cargo.iter().any(|t| t == "--no-fail-fast"));
+
+        let parsed = derive_args(&[], &["--bail".to_string()], false);
+        let cargo = super::runner_args::build_cargo_test_args(None, &parsed, &[]);
+        assert!(!cargo.iter().any(|t| t == "--no-fail-fast"));
+        let nextest = super::runner_args::build_nextest_run_args(None, &parsed, &[], "none");
+        assert!(nextest.iter().any(|t| t == "--fail-fast"));
+        assert!(!nextest.iter().any(|t| t == "--no-fail-fast"));
+    });
+}
+
+#[test]
+fn debugged_binaries_get_name_filters_and_libtest_args_only() {
+    // The first `--` hands the rest to cargo; the second is cargo's own, before libtest's args.
+    let argv = [
+        "--",
+        "works",
+        "-p",
+        "api",
+        "--release",
+        "--",
+        "--exact",
+        "--ignored",
+    ]
+    .map(str::to_string);
+    let parsed = derive_args(&[], &argv, false);
+    let binary_args = super::libtest_args_for_binaries(&parsed);
+    assert_eq!(
+        binary_args.runner_args,
+        vec!["works", "--exact", "--ignored"]
+    );
+}
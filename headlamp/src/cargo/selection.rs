@@ -2,17 +2,174 @@ use std::path::{Path, PathBuf};
 
 use headlamp_core::args::ParsedArgs;
 use headlamp_core::config::ChangedMode;
+use headlamp_core::test_model::{SkippedUnit, SkippedUnitKind};
 
-use crate::cargo_select::{changed_rust_seeds, filter_rust_tests_by_seeds, list_rust_test_files};
+use crate::cargo_select::{
+    CargoWorkspace, cargo_metadata_json, changed_rust_seeds, filter_rust_tests_by_seeds,
+    list_rust_test_files, parse_cargo_metadata,
+};
+use crate::run::RunError;
 
 #[derive(Debug, Clone)]
 pub(crate) struct CargoSelection {
     pub(crate) extra_cargo_args: Vec<String>,
     pub(crate) changed_selection_attempted: bool,
     pub(crate) selected_test_count: Option<usize>,
+    /// Workspace packages `--changed` left out because no changed file affects them.
+    pub(crate) skipped_packages: Vec<String>,
+}
+
+impl CargoSelection {
+    pub(crate) fn skipped_units(&self) -> Vec<SkippedUnit> {
+        self.skipped_packages
+            .iter()
+            .map(|name| SkippedUnit {
+                kind: SkippedUnitKind::CargoPackage,
+                name: name.clone(),
+            })
+            .collect()
+    }
+}
+
+/// `cargo metadata` runs once per run; selection, the doctest phase and every feature-matrix
+/// entry share the result through the session.
+pub(crate) fn session_cargo_workspace(
+    repo_root: &Path,
+    session: &crate::session::RunSession,
+) -> Option<CargoWorkspace> {
+    let key = format!("cargo-metadata:{}", repo_root.to_string_lossy());
+    let json = session.memoized(&key, || cargo_metadata_json(repo_root))?;
+    parse_cargo_metadata(&json)
 }
 
 pub(crate) fn derive_cargo_selection(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    changed: &[PathBuf],
+) -> Result<CargoSelection, RunError> {
+    let needs_workspace = !args.packages.is_empty()
+        || !args.exclude_packages.is_empty()
+        || (!changed.is_empty() && args.selection_paths.is_empty());
+    let workspace = needs_workspace
+        .then(|| session_cargo_workspace(repo_root, session))
+        .flatten();
+    if let Some(workspace) = workspace.as_ref() {
+        validate_package_specs(workspace, args)?;
+    }
+    let mut selection = match workspace.as_ref() {
+        Some(workspace)
+            if workspace.is_multi_package()
+                && !changed.is_empty()
+                && args.selection_paths.is_empty() =>
+        {
+            derive_changed_package_selection(workspace, args, changed)
+        }
+        _ => {
+            let mut selection = derive_cargo_target_selection(repo_root, args, changed);
+            selection
+                .extra_cargo_args
+                .extend(package_args(&args.packages, &args.exclude_packages));
+            selection
+        }
+    };
+    selection
+        .extra_cargo_args
+        .extend(super::features::cargo_feature_args(args));
+    selection
+        .extra_cargo_args
+        .extend(super::target::cargo_target_args(args));
+    selection
+        .extra_cargo_args
+        .extend(super::target::cargo_profile_args(args));
+    Ok(selection)
+}
+
+fn validate_package_specs(workspace: &CargoWorkspace, args: &ParsedArgs) -> Result<(), RunError> {
+    let unknown = args
+        .packages
+        .iter()
+        .map(|spec| ("--package", spec))
+        .chain(args.exclude_packages.iter().map(|spec| ("--exclude", spec)))
+        .filter(|(_, spec)| workspace.matching_packages(spec).is_empty())
+        .map(|(flag, spec)| format!("{flag} {spec}"))
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let members = workspace
+        .packages
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(RunError::CommandFailed {
+        message: format!(
+            "no workspace package matches {} (members: {members})",
+            unknown.join(", ")
+        ),
+    })
+}
+
+/// `--exclude` only works together with `--workspace`, so explicit packages are filtered here
+/// instead of being passed alongside it.
+fn package_args(packages: &[String], excludes: &[String]) -> Vec<String> {
+    if packages.is_empty() {
+        return if excludes.is_empty() {
+            vec![]
+        } else {
+            std::iter::once("--workspace".to_string())
+                .chain(excludes.iter().map(|name| format!("--exclude={name}")))
+                .collect()
+        };
+    }
+    packages
+        .iter()
+        .filter(|name| !excludes.contains(name))
+        .map(|name| format!("--package={name}"))
+        .collect()
+}
+
+/// In multi-package workspaces, `--changed` tests the packages owning the changed files plus
+/// their reverse dependencies, narrowed by any explicit `--package`/`--exclude`.
+fn derive_changed_package_selection(
+    workspace: &CargoWorkspace,
+    args: &ParsedArgs,
+    changed: &[PathBuf],
+) -> CargoSelection {
+    let requested = args
+        .packages
+        .iter()
+        .flat_map(|spec| workspace.matching_packages(spec))
+        .collect::<Vec<_>>();
+    let excluded = args
+        .exclude_packages
+        .iter()
+        .flat_map(|spec| workspace.matching_packages(spec))
+        .collect::<Vec<_>>();
+    let in_scope = |name: &String| {
+        (requested.is_empty() || requested.contains(name)) && !excluded.contains(name)
+    };
+    let affected = workspace
+        .affected_packages(changed)
+        .into_iter()
+        .filter(in_scope)
+        .collect::<Vec<_>>();
+    let skipped_packages = workspace
+        .packages
+        .iter()
+        .map(|package| package.name.clone())
+        .filter(|name| in_scope(name) && !affected.contains(name))
+        .collect();
+    CargoSelection {
+        extra_cargo_args: package_args(&affected, &[]),
+        changed_selection_attempted: true,
+        selected_test_count: Some(affected.len()),
+        skipped_packages,
+    }
+}
+
+fn derive_cargo_target_selection(
     repo_root: &Path,
     args: &ParsedArgs,
     changed: &[PathBuf],
@@ -25,6 +182,7 @@ pub(crate) fn derive_cargo_selection(
         return CargoSelection {
             extra_cargo_args: vec![],
             changed_selection_attempted: false,
+            skipped_packages: vec![],
             selected_test_count: None,
         };
     }
@@ -34,6 +192,7 @@ pub(crate) fn derive_cargo_selection(
         return CargoSelection {
             extra_cargo_args: vec![],
             changed_selection_attempted: true,
+            skipped_packages: vec![],
             selected_test_count: None,
         };
     }
@@ -50,6 +209,7 @@ pub(crate) fn derive_cargo_selection(
     CargoSelection {
         extra_cargo_args: build_test_target_args(&test_targets),
         changed_selection_attempted: true,
+        skipped_packages: vec![],
         selected_test_count: if selected_count == 0 {
             None
         } else {
@@ -71,6 +231,7 @@ fn derive_selection_from_selection_paths(
         return CargoSelection {
             extra_cargo_args: vec![],
             changed_selection_attempted: false,
+            skipped_packages: vec![],
             selected_test_count: None,
         };
     }
@@ -85,6 +246,7 @@ fn derive_selection_from_selection_paths(
         return CargoSelection {
             extra_cargo_args: build_test_target_args(&direct_test_stems),
             changed_selection_attempted: false,
+            skipped_packages: vec![],
             selected_test_count: Some(direct_test_stems.len()),
         };
     }
@@ -93,6 +255,7 @@ fn derive_selection_from_selection_paths(
     CargoSelection {
         extra_cargo_args: build_test_target_args(&test_targets),
         changed_selection_attempted: false,
+        skipped_packages: vec![],
         selected_test_count: Some(test_targets.len()),
     }
 }
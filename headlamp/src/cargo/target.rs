@@ -0,0 +1,69 @@
+use headlamp_core::args::ParsedArgs;
+
+pub(crate) fn cargo_target_args(args: &ParsedArgs) -> Vec<String> {
+    target_triple(args)
+        .map(|triple| vec![format!("--target={triple}")])
+        .unwrap_or_default()
+}
+
+/// `cargo test --profile`; nextest spells it `--cargo-profile` (see `runner_args`).
+pub(crate) fn cargo_profile_args(args: &ParsedArgs) -> Vec<String> {
+    args.cargo_profile
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|profile| vec![format!("--profile={profile}")])
+        .unwrap_or_default()
+}
+
+fn target_triple(args: &ParsedArgs) -> Option<&str> {
+    args.target
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Cargo's per-target runner variable, e.g. `CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER`.
+pub fn target_runner_env_key(triple: &str) -> String {
+    let normalized = triple
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("CARGO_TARGET_{normalized}_RUNNER")
+}
+
+/// `cargo test` and nextest already honor the target runner through Cargo's own env var, so an
+/// explicit `--target-runner` is forwarded that way.
+pub(crate) fn apply_target_runner_env(cmd: &mut std::process::Command, args: &ParsedArgs) {
+    let Some(triple) = target_triple(args) else {
+        return;
+    };
+    if let Some(runner) = args
+        .target_runner
+        .as_deref()
+        .filter(|r| !r.trim().is_empty())
+    {
+        cmd.env(target_runner_env_key(triple), runner);
+    }
+}
+
+/// The wrapper command for executing cross-compiled test binaries directly: `--target-runner`
+/// first, then the runner Cargo itself would use from the environment.
+pub fn target_runner_command(args: &ParsedArgs) -> Option<Vec<String>> {
+    let triple = target_triple(args)?;
+    let raw = args
+        .target_runner
+        .clone()
+        .or_else(|| std::env::var(target_runner_env_key(triple)).ok())?;
+    let words = raw
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    (!words.is_empty()).then_some(words)
+}
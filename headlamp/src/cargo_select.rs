@@ -47,3 +47,146 @@ pub fn filter_rust_tests_by_seeds(tests: &[PathBuf], seeds: &[String]) -> Vec<Pa
         .cloned()
         .collect()
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub manifest_dir: PathBuf,
+    /// Workspace members this package depends on (any dependency kind, dev included).
+    pub workspace_deps: Vec<String>,
+    pub has_doctests: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CargoWorkspace {
+    pub packages: Vec<WorkspacePackage>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MetadataJson {
+    packages: Vec<MetadataPackageJson>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MetadataPackageJson {
+    name: String,
+    manifest_path: PathBuf,
+    #[serde(default)]
+    dependencies: Vec<MetadataDependencyJson>,
+    #[serde(default)]
+    targets: Vec<MetadataTargetJson>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MetadataTargetJson {
+    #[serde(default)]
+    doctest: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MetadataDependencyJson {
+    name: String,
+    #[serde(default)]
+    path: Option<PathBuf>,
+}
+
+/// Parses `cargo metadata --no-deps` output; only path dependencies on other members are kept.
+pub fn parse_cargo_metadata(json: &str) -> Option<CargoWorkspace> {
+    let metadata = serde_json::from_str::<MetadataJson>(json).ok()?;
+    let member_names = metadata
+        .packages
+        .iter()
+        .map(|p| p.name.clone())
+        .collect::<std::collections::HashSet<_>>();
+    let packages = metadata
+        .packages
+        .into_iter()
+        .map(|p| WorkspacePackage {
+            manifest_dir: p
+                .manifest_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            workspace_deps: p
+                .dependencies
+                .into_iter()
+                .filter(|d| d.path.is_some() && member_names.contains(&d.name))
+                .map(|d| d.name)
+                .collect(),
+            has_doctests: p.targets.iter().any(|t| t.doctest),
+            name: p.name,
+        })
+        .collect();
+    Some(CargoWorkspace { packages })
+}
+
+pub fn cargo_metadata_json(repo_root: &Path) -> Option<String> {
+    let output = std::process::Command::new("cargo")
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .current_dir(repo_root)
+        .output()
+        .ok()?;
+    output.status.success().then_some(())?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn load_cargo_workspace(repo_root: &Path) -> Option<CargoWorkspace> {
+    parse_cargo_metadata(&cargo_metadata_json(repo_root)?)
+}
+
+impl CargoWorkspace {
+    pub fn is_multi_package(&self) -> bool {
+        self.packages.len() > 1
+    }
+
+    /// Nested packages win over the workspace root package for files under their directory.
+    pub fn owning_package(&self, file: &Path) -> Option<&str> {
+        self.packages
+            .iter()
+            .filter(|p| file.starts_with(&p.manifest_dir))
+            .max_by_key(|p| p.manifest_dir.components().count())
+            .map(|p| p.name.as_str())
+    }
+
+    /// Packages owning a changed file, plus every member that (transitively) depends on one.
+    pub fn affected_packages(&self, changed: &[PathBuf]) -> Vec<String> {
+        let workspace_manifest_changed = changed.iter().any(|file| {
+            let name = file.file_name().and_then(|n| n.to_str());
+            name == Some("Cargo.lock")
+                || (name == Some("Cargo.toml") && self.owning_package(file).is_none())
+        });
+        if workspace_manifest_changed {
+            return self.packages.iter().map(|p| p.name.clone()).collect();
+        }
+        let mut affected = changed
+            .iter()
+            .filter_map(|file| self.owning_package(file))
+            .map(str::to_string)
+            .collect::<std::collections::BTreeSet<_>>();
+        loop {
+            let dependents = self
+                .packages
+                .iter()
+                .filter(|p| !affected.contains(&p.name))
+                .filter(|p| p.workspace_deps.iter().any(|d| affected.contains(d)))
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>();
+            if dependents.is_empty() {
+                break;
+            }
+            affected.extend(dependents);
+        }
+        affected.into_iter().collect()
+    }
+
+    /// Cargo accepts glob patterns for `--package`/`--exclude`, so names are matched the same way.
+    pub fn matching_packages(&self, spec: &str) -> Vec<String> {
+        let spec = spec.split('@').next().unwrap_or(spec);
+        let matcher = globset::Glob::new(spec).ok().map(|g| g.compile_matcher());
+        self.packages
+            .iter()
+            .filter(|p| p.name == spec || matcher.as_ref().is_some_and(|m| m.is_match(&p.name)))
+            .map(|p| p.name.clone())
+            .collect()
+    }
+}
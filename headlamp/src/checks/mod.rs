@@ -0,0 +1,222 @@
+//! Lint-as-tests: `--check` commands (eslint, ruff, clippy) whose JSON findings are reported as
+//! failed tests, in the same model and summary as the runners, so a PR check has one tool and
+//! one exit code.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+
+use crate::args::ParsedArgs;
+use crate::config::{CheckConfig, CheckFormat};
+use crate::format::ctx::make_ctx_for_args;
+use crate::format::vitest::render_vitest_from_test_model;
+use crate::process::RunEnvironment;
+use crate::run::RunError;
+use crate::test_model::{TestCaseResult, TestLocation, TestRunModel, TestSuiteResult};
+
+mod parse;
+
+pub use parse::{parse_clippy_messages, parse_eslint_json, parse_findings, parse_ruff_json};
+
+/// One finding from a check (or a type error from `--typecheck`), with `file` made absolute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub file: String,
+    pub line: i64,
+    pub column: i64,
+    /// The rule or error code, e.g. `no-unused-vars`, `F401` or `clippy::needless_return`.
+    pub code: String,
+    pub message: String,
+}
+
+/// `<format>:<command>`, as given to `--check`.
+pub fn parse_check_spec(spec: &str) -> Option<CheckConfig> {
+    let (format, command) = spec.split_once(':')?;
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    Some(CheckConfig {
+        format: CheckFormat::parse(format)?,
+        command: command.to_string(),
+    })
+}
+
+pub(crate) fn absolute_slash(cwd: &Path, file: &str) -> String {
+    let path = Path::new(file);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    };
+    absolute.to_slash_lossy().to_string()
+}
+
+/// The files a check looks at; `--changed` only hands it these.
+fn check_extensions(format: CheckFormat) -> &'static [&'static str] {
+    match format {
+        CheckFormat::Eslint => &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+        CheckFormat::Ruff => &["py", "pyi"],
+        CheckFormat::Clippy => &["rs"],
+    }
+}
+
+/// Findings as a run model: one failed suite per file, one failed case per finding, so they
+/// render like any other failure, code frame included. `tool` prefixes each case's full name.
+pub fn build_findings_model(tool: &str, findings: &[Finding]) -> TestRunModel {
+    crate::format::unstructured_engine::build_test_run_model(findings_suites(tool, findings))
+}
+
+fn findings_suites(tool: &str, findings: &[Finding]) -> Vec<TestSuiteResult> {
+    let mut by_file: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    findings
+        .iter()
+        .for_each(|finding| by_file.entry(&finding.file).or_default().push(finding));
+    by_file
+        .into_iter()
+        .map(|(file, findings)| TestSuiteResult {
+            test_file_path: file.to_string(),
+            status: "failed".to_string(),
+            timed_out: None,
+            failure_message: String::new(),
+            failure_details: None,
+            test_exec_error: None,
+            console: None,
+            test_results: findings
+                .into_iter()
+                .map(|finding| finding_case(tool, finding))
+                .collect(),
+        })
+        .collect()
+}
+
+fn finding_case(tool: &str, finding: &Finding) -> TestCaseResult {
+    let title = format!("{} ({}:{})", finding.code, finding.line, finding.column);
+    TestCaseResult {
+        full_name: format!("{tool} {title}"),
+        title,
+        status: "failed".to_string(),
+        timed_out: None,
+        duration: 0,
+        location: Some(TestLocation {
+            line: finding.line,
+            column: finding.column,
+        }),
+        failure_messages: vec![format!(
+            "{}\n    at {}:{}:{}",
+            finding.message, finding.file, finding.line, finding.column
+        )],
+        failure_details: None,
+        repro: None,
+        skip_reason: None,
+        attempts: None,
+    }
+}
+
+/// Runs every `--check` from `repo_root` and prints their findings as one run. Returns 1 when
+/// any check found something or failed to run.
+pub fn run_checks(repo_root: &Path, args: &ParsedArgs, run_env: &RunEnvironment) -> i32 {
+    if args.checks.is_empty() {
+        return 0;
+    }
+    let started_at = std::time::Instant::now();
+    let changed = match args
+        .changed
+        .map(|mode| crate::git::changed_files(repo_root, mode))
+        .transpose()
+    {
+        Ok(changed) => changed,
+        Err(err) => return render_check_error(repo_root, args, "changed", &err.to_string()),
+    };
+    let mut exit_code = 0;
+    let mut suites = vec![];
+    for spec in &args.checks {
+        let Some(check) = parse_check_spec(spec) else {
+            let message = format!("invalid --check {spec:?}: expected <eslint|ruff|clippy>:<cmd>");
+            exit_code = render_check_error(repo_root, args, "check", &message);
+            continue;
+        };
+        let tool = check.format.as_str();
+        match run_check(repo_root, &check, changed.as_deref(), run_env) {
+            Ok(findings) => suites.extend(findings_suites(tool, &findings)),
+            Err(err) => exit_code = render_check_error(repo_root, args, tool, &err.to_string()),
+        }
+    }
+    if suites.is_empty() {
+        return exit_code;
+    }
+    let mut model = crate::format::unstructured_engine::build_test_run_model(suites);
+    model.aggregated.run_time_ms = Some(started_at.elapsed().as_millis() as u64);
+    crate::recorded::record_failed_tests(&model);
+    let ctx = make_ctx_for_args(repo_root, args, true);
+    crate::format::report_out::println(render_vitest_from_test_model(&model, &ctx, false));
+    1
+}
+
+/// Runs one check. Under `--changed` it only sees the changed files it can lint (and is
+/// skipped when there are none); its findings are kept to those files too.
+fn run_check(
+    repo_root: &Path,
+    check: &CheckConfig,
+    changed: Option<&[PathBuf]>,
+    run_env: &RunEnvironment,
+) -> Result<Vec<Finding>, RunError> {
+    let relevant = changed.map(|changed| {
+        changed
+            .iter()
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| check_extensions(check.format).contains(&ext))
+            })
+            .map(|path| path.to_slash_lossy().to_string())
+            .collect::<Vec<_>>()
+    });
+    let file_args = match relevant.as_deref() {
+        Some([]) => return Ok(vec![]),
+        Some(files) => files
+            .iter()
+            .map(|file| crate::repro::shell_quote(file))
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => ".".to_string(),
+    };
+    let command = check.command.replace("{files}", &file_args);
+    let output = run_env
+        .apply_to_expression(crate::run::shell_expression(&command))
+        .dir(repo_root)
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .map_err(RunError::Io)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let findings = parse_findings(check.format, &stdout, repo_root)
+        .filter(|findings| output.status.success() || !findings.is_empty())
+        .ok_or_else(|| RunError::CommandFailed {
+            message: format!(
+                "{} check failed without reporting findings: {command}\n{}",
+                check.format.as_str(),
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ),
+        })?;
+    Ok(match relevant {
+        Some(files) => findings
+            .into_iter()
+            .filter(|finding| files.contains(&finding.file))
+            .collect(),
+        None => findings,
+    })
+}
+
+fn render_check_error(repo_root: &Path, args: &ParsedArgs, tool: &str, message: &str) -> i32 {
+    let ctx = make_ctx_for_args(repo_root, args, true);
+    let model = crate::format::infra_failure::build_infra_failure_test_run_model(
+        &format!("headlamp/check/{tool}"),
+        "Check failed to run",
+        message,
+    );
+    crate::format::report_out::println(render_vitest_from_test_model(&model, &ctx, true));
+    1
+}
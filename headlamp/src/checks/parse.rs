@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::config::CheckFormat;
+use crate::format::cargo_build::{is_cargo_message, is_summary_message, parse_compiler_message};
+
+use super::{Finding, absolute_slash};
+
+/// The findings in a check's stdout, or `None` when it is not the JSON `format` describes.
+pub fn parse_findings(format: CheckFormat, output: &str, cwd: &Path) -> Option<Vec<Finding>> {
+    match format {
+        CheckFormat::Eslint => parse_eslint_json(output, cwd),
+        CheckFormat::Ruff => parse_ruff_json(output, cwd),
+        CheckFormat::Clippy => Some(parse_clippy_messages(output, cwd)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintFile {
+    file_path: String,
+    #[serde(default)]
+    messages: Vec<EslintMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    rule_id: Option<String>,
+    severity: u8,
+    message: String,
+    line: Option<i64>,
+    column: Option<i64>,
+}
+
+/// `eslint --format json`. Only errors (severity 2) fail; warnings are eslint's own
+/// "allowed" level. Fatal parse errors have no rule and are reported as `eslint`.
+pub fn parse_eslint_json(output: &str, cwd: &Path) -> Option<Vec<Finding>> {
+    if output.trim().is_empty() {
+        return Some(vec![]);
+    }
+    let files = serde_json::from_str::<Vec<EslintFile>>(output.trim()).ok()?;
+    let findings = files.into_iter().flat_map(|file| {
+        let path = absolute_slash(cwd, &file.file_path);
+        file.messages
+            .into_iter()
+            .filter(|message| message.severity >= 2)
+            .map(move |message| Finding {
+                file: path.clone(),
+                line: message.line.unwrap_or(1),
+                column: message.column.unwrap_or(1),
+                code: message.rule_id.unwrap_or_else(|| "eslint".to_string()),
+                message: message.message,
+            })
+    });
+    Some(findings.collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct RuffDiagnostic {
+    code: Option<String>,
+    message: String,
+    filename: String,
+    location: Option<RuffLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuffLocation {
+    row: i64,
+    column: i64,
+}
+
+/// `ruff check --output-format json`. Syntax errors have no code and are reported as `ruff`.
+pub fn parse_ruff_json(output: &str, cwd: &Path) -> Option<Vec<Finding>> {
+    if output.trim().is_empty() {
+        return Some(vec![]);
+    }
+    let diagnostics = serde_json::from_str::<Vec<RuffDiagnostic>>(output.trim()).ok()?;
+    let findings = diagnostics.into_iter().map(|diagnostic| {
+        let (line, column) = diagnostic
+            .location
+            .map_or((1, 1), |location| (location.row, location.column));
+        Finding {
+            file: absolute_slash(cwd, &diagnostic.filename),
+            line,
+            column,
+            code: diagnostic.code.unwrap_or_else(|| "ruff".to_string()),
+            message: diagnostic.message,
+        }
+    });
+    Some(findings.collect())
+}
+
+/// `cargo clippy --message-format json`: every located warning and error, once each even when
+/// several targets compile the same file. Non-JSON lines are ignored.
+pub fn parse_clippy_messages(output: &str, cwd: &Path) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = vec![];
+    output
+        .lines()
+        .filter(|line| is_cargo_message(line))
+        .filter_map(parse_compiler_message)
+        .filter(|(level, diagnostic)| {
+            matches!(level.as_str(), "warning" | "error")
+                && !is_summary_message(&diagnostic.message)
+        })
+        .for_each(|(level, diagnostic)| {
+            let Some(file) = diagnostic.file else {
+                return;
+            };
+            let finding = Finding {
+                file: absolute_slash(cwd, &file),
+                line: diagnostic.line.unwrap_or(1),
+                column: diagnostic.column.unwrap_or(1),
+                code: diagnostic.code.unwrap_or(level),
+                message: diagnostic.message,
+            };
+            if !findings.contains(&finding) {
+                findings.push(finding);
+            }
+        });
+    findings
+}
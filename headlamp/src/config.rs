@@ -1,15 +1,22 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-use duct::cmd as duct_cmd;
 use serde::Deserialize;
-use serde_json::Value as JsonValue;
-use which::which;
 
 use crate::config_ts::load_headlamp_config_ts_oxc;
 use crate::error::HeadlampError;
 
+mod js_config;
 pub(crate) mod jsonish;
+pub mod nearest;
+mod repo_root;
+pub mod schema;
+mod toml_config;
+pub mod validate;
+
+use js_config::load_js_config;
+pub use repo_root::{canonical_or_given, find_repo_root, resolve_repo_root};
+use toml_config::{load_embedded_toml_config, load_toml_config_value, toml_source_for};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,18 +29,123 @@ pub enum ChangedMode {
     LastRelease,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum CoverageUi {
     Jest,
+    #[default]
     Both,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+/// Rust coverage backend. `Auto` prefers rustc instrumentation with rustup's llvm-tools and
+/// falls back to cargo-llvm-cov when only that is installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoverageEngine {
+    #[default]
+    Auto,
+    Instrument,
+    LlvmCov,
+}
+
+impl CoverageEngine {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Instrument => "instrument",
+            Self::LlvmCov => "llvm-cov",
+        }
+    }
+}
+
+/// Where JS coverage comes from: babel instrumentation, or V8's built-in counters, which
+/// leave the code untouched and are much faster on large TypeScript repos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoverageProvider {
+    #[default]
+    Babel,
+    V8,
+}
+
+impl CoverageProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Babel => "babel",
+            Self::V8 => "v8",
+        }
+    }
+}
+
+/// `--group-by`: what the rendered results are grouped under. `Suite` is the describe block
+/// (or module path) a test is declared in, `Package` the nearest directory with a manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupBy {
+    #[default]
+    File,
+    Suite,
+    Package,
+}
+
+impl GroupBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Suite => "suite",
+            Self::Package => "package",
+        }
+    }
+}
+
+/// Whether jest runs under node's `--experimental-vm-modules`, which jest needs to load test
+/// files as native ES modules. `Auto` turns it on for projects that look like ESM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JestEsm {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl JestEsm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::On => "on",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// The `--typecheck` phase before jest runs: `Gate` skips the tests when `tsc` reports errors,
+/// `Warn` shows the errors and runs the tests anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Typecheck {
+    #[default]
+    Off,
+    Gate,
+    Warn,
+}
+
+impl Typecheck {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Gate => "gate",
+            Self::Warn => "warn",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum CoverageMode {
     Compact,
     Full,
+    #[default]
     Auto,
 }
 
@@ -41,6 +153,7 @@ pub enum CoverageMode {
 #[serde(rename_all = "camelCase")]
 pub struct CoverageSection {
     pub abort_on_failure: Option<bool>,
+    pub native_thresholds: Option<bool>,
     pub mode: Option<CoverageMode>,
     pub page_fit: Option<bool>,
     pub thresholds: Option<CoverageThresholds>,
@@ -78,6 +191,67 @@ pub enum ChangedConfig {
     Obj(ChangedSection),
 }
 
+/// An `outputFilters` entry: a bare regex drops every matching line, `collapse` keeps the first.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OutputFilterConfig {
+    Pattern(String),
+    Rule {
+        pattern: String,
+        #[serde(default)]
+        collapse: bool,
+    },
+}
+
+/// A lint command whose findings are reported as failed tests (see `crate::checks`).
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct CheckConfig {
+    pub format: CheckFormat,
+    /// Shell command; `{files}` becomes the changed files under `--changed`, else `.`.
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckFormat {
+    Eslint,
+    Ruff,
+    Clippy,
+}
+
+impl CheckFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "eslint" => Some(Self::Eslint),
+            "ruff" => Some(Self::Ruff),
+            "clippy" => Some(Self::Clippy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eslint => "eslint",
+            Self::Ruff => "ruff",
+            Self::Clippy => "clippy",
+        }
+    }
+}
+
+/// Commands run around each headlamp run (see `crate::hooks`); every event takes a list.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LifecycleHooks {
+    #[serde(alias = "pre_run")]
+    pub pre_run: Vec<String>,
+    #[serde(alias = "post_run")]
+    pub post_run: Vec<String>,
+    #[serde(alias = "pre_coverage")]
+    pub pre_coverage: Vec<String>,
+    #[serde(alias = "on_failure")]
+    pub on_failure: Vec<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct HeadlampConfig {
@@ -96,9 +270,12 @@ pub struct HeadlampConfig {
 
     pub coverage: Option<CoverageConfig>,
     pub coverage_ui: Option<CoverageUi>,
+    pub coverage_engine: Option<CoverageEngine>,
+    pub coverage_provider: Option<CoverageProvider>,
     pub coverage_abort_on_failure: Option<bool>,
     pub only_failures: Option<bool>,
     pub show_logs: Option<bool>,
+    pub full_stacks: Option<bool>,
     pub include: Option<Vec<String>>,
     pub exclude: Option<Vec<String>>,
     pub editor_cmd: Option<String>,
@@ -112,24 +289,64 @@ pub struct HeadlampConfig {
 
     pub changed: Option<ChangedConfig>,
 
+    pub progress_heartbeat: Option<u32>,
+    pub max_memory: Option<String>,
+    pub nice: Option<u32>,
+    pub python: Option<String>,
+    pub features: Option<String>,
+    pub all_features: Option<bool>,
+    pub no_default_features: Option<bool>,
+    pub feature_matrix: Option<String>,
+    pub packages: Option<Vec<String>>,
+    pub exclude_packages: Option<Vec<String>>,
+    pub target: Option<String>,
+    pub target_runner: Option<String>,
+    pub cargo_profile: Option<String>,
+    pub nextest_profile: Option<String>,
+    pub auto_install: Option<bool>,
+    pub projects: Option<Vec<String>>,
+    pub ignore_projects: Option<Vec<String>>,
+    pub jest_esm: Option<JestEsm>,
+    pub typecheck: Option<Typecheck>,
+    pub typecheck_command: Option<String>,
+    pub node: Option<String>,
+    pub hooks: Option<LifecycleHooks>,
+    pub wait_for: Option<Vec<String>>,
+    pub wait_timeout: Option<String>,
+    pub env: Option<BTreeMap<String, String>>,
+    pub env_file: Option<String>,
+    pub artifacts_dir: Option<String>,
+    pub artifact_globs: Option<Vec<String>>,
+    pub output_filters: Option<Vec<OutputFilterConfig>>,
+    pub badge_out: Option<String>,
+    pub notify: Option<Vec<String>>,
+    pub checks: Option<Vec<CheckConfig>>,
+    pub presets: Option<BTreeMap<String, Vec<String>>>,
+    pub log_level: Option<String>,
+    pub log_json: Option<bool>,
+    pub deterministic: Option<bool>,
+    pub normalize_times: Option<bool>,
+    pub bail: Option<bool>,
+    pub summary_line: Option<bool>,
+    pub check_update: Option<bool>,
+    pub show_skipped: Option<bool>,
+    pub show_skipped_projects: Option<bool>,
+    pub footer_breakdown: Option<bool>,
+    pub group_by: Option<GroupBy>,
+    pub path_style: Option<crate::format::path_display::PathStyle>,
+    pub strip_path_prefixes: Option<Vec<String>>,
+    pub max_reported_failures: Option<u32>,
+    pub fail_on_skipped: Option<bool>,
+    pub fail_if_no_tests: Option<bool>,
+    pub fail_on_untested_changes: Option<bool>,
+    pub strict_duplicates: Option<bool>,
+    pub max_duration: Option<String>,
+    pub shard_launcher: Option<String>,
+
     pub coverage_section: Option<CoverageSection>,
     pub changed_section: Option<ChangedSection>,
 }
 
-pub fn find_repo_root(start: &Path) -> PathBuf {
-    let start_dir = start.parent().filter(|_| start.is_file()).unwrap_or(start);
-    let out = crate::git::git_command_in_repo(start_dir)
-        .args(["rev-parse", "--show-toplevel"])
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .and_then(|o| String::from_utf8(o.stdout).ok())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .map(PathBuf::from);
-    out.unwrap_or_else(|| start.to_path_buf())
-}
-
 pub fn discover_config_path(repo_root: &Path) -> Option<PathBuf> {
     let names = [
         "headlamp.toml",
@@ -168,6 +385,19 @@ pub fn load_headlamp_config(repo_root: &Path) -> Result<HeadlampConfig, Headlamp
 }
 
 pub fn load_headlamp_config_from_path(path: &Path) -> Result<HeadlampConfig, HeadlampError> {
+    let Some(value) = load_config_value(path)? else {
+        return Ok(HeadlampConfig::default());
+    };
+    serde_json::from_value::<HeadlampConfig>(value).map_err(|err| HeadlampError::ConfigParse {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// The config at `path` as a raw value, before it is deserialized (and validated, see
+/// `schema`). `None` when the file holds no headlamp config, like a `pyproject.toml` without a
+/// `[tool.headlamp]` table.
+pub fn load_config_value(path: &Path) -> Result<Option<serde_json::Value>, HeadlampError> {
     let ext = path
         .extension()
         .and_then(|x| x.to_str())
@@ -175,248 +405,36 @@ pub fn load_headlamp_config_from_path(path: &Path) -> Result<HeadlampConfig, Hea
         .to_ascii_lowercase();
 
     match ext.as_str() {
-        "json" | "json5" | "jsonc" => load_json_config(path),
-        "yaml" | "yml" => load_yaml_config(path),
-        "toml" => load_toml_config(path, TomlConfigSource::FullFile).map(|v| v.unwrap_or_default()),
-        "ts" => load_ts_config_oxc(path),
-        "js" | "mjs" | "cjs" => load_js_config(path),
-        _ => Ok(HeadlampConfig::default()),
+        "json" | "json5" | "jsonc" => load_json_config(path).map(Some),
+        "yaml" | "yml" => load_yaml_config(path).map(Some),
+        "toml" => load_toml_config_value(path, toml_source_for(path)),
+        "ts" => load_headlamp_config_ts_oxc(path).map(Some),
+        "js" | "mjs" | "cjs" => load_js_config(path).map(Some),
+        _ => Ok(None),
     }
 }
 
-fn load_json_config(path: &Path) -> Result<HeadlampConfig, HeadlampError> {
+fn load_json_config(path: &Path) -> Result<serde_json::Value, HeadlampError> {
     let raw = std::fs::read_to_string(path).map_err(|source| HeadlampError::Io {
         path: path.to_path_buf(),
         source,
     })?;
 
-    serde_json::from_str::<HeadlampConfig>(&raw)
-        .or_else(|_| jsonish::parse_jsonish_config(&raw))
+    serde_json::from_str::<serde_json::Value>(&raw)
+        .or_else(|_| jsonish::parse_jsonish_value(&raw))
         .map_err(|error| HeadlampError::ConfigParse {
             path: path.to_path_buf(),
             message: error.to_string(),
         })
 }
 
-fn load_yaml_config(path: &Path) -> Result<HeadlampConfig, HeadlampError> {
+fn load_yaml_config(path: &Path) -> Result<serde_json::Value, HeadlampError> {
     let raw = std::fs::read_to_string(path).map_err(|source| HeadlampError::Io {
         path: path.to_path_buf(),
         source,
     })?;
-    serde_yaml::from_str::<HeadlampConfig>(&raw).map_err(|err| HeadlampError::ConfigParse {
+    serde_yaml::from_str::<serde_json::Value>(&raw).map_err(|err| HeadlampError::ConfigParse {
         path: path.to_path_buf(),
         message: err.to_string(),
     })
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum TomlConfigSource {
-    FullFile,
-    PyProjectToolHeadlamp,
-    CargoPackageMetadataHeadlamp,
-}
-
-fn load_embedded_toml_config(repo_root: &Path) -> Result<HeadlampConfig, HeadlampError> {
-    let pyproject_path = repo_root.join("pyproject.toml");
-    if pyproject_path.exists() {
-        if let Some(config) =
-            load_toml_config(&pyproject_path, TomlConfigSource::PyProjectToolHeadlamp)?
-        {
-            return Ok(config);
-        }
-    }
-
-    let cargo_toml_path = repo_root.join("Cargo.toml");
-    if cargo_toml_path.exists() {
-        if let Some(config) = load_toml_config(
-            &cargo_toml_path,
-            TomlConfigSource::CargoPackageMetadataHeadlamp,
-        )? {
-            return Ok(config);
-        }
-    }
-
-    Ok(HeadlampConfig::default())
-}
-
-fn load_toml_config(
-    path: &Path,
-    source: TomlConfigSource,
-) -> Result<Option<HeadlampConfig>, HeadlampError> {
-    let raw = std::fs::read_to_string(path).map_err(|source| HeadlampError::Io {
-        path: path.to_path_buf(),
-        source,
-    })?;
-
-    let toml_value =
-        toml::from_str::<toml::Value>(&raw).map_err(|err| HeadlampError::ConfigParse {
-            path: path.to_path_buf(),
-            message: err.to_string(),
-        })?;
-
-    let maybe_table_value = match source {
-        TomlConfigSource::FullFile => Some(toml_value),
-        TomlConfigSource::PyProjectToolHeadlamp => extract_pyproject_tool_headlamp(&toml_value),
-        TomlConfigSource::CargoPackageMetadataHeadlamp => {
-            extract_cargo_package_metadata_headlamp(&toml_value)
-        }
-    };
-
-    let Some(table_value) = maybe_table_value else {
-        return Ok(None);
-    };
-
-    let json_value =
-        serde_json::to_value(table_value).map_err(|err| HeadlampError::ConfigParse {
-            path: path.to_path_buf(),
-            message: err.to_string(),
-        })?;
-    let normalized = normalize_toml_keys_to_camel_case(&json_value);
-
-    serde_json::from_value::<HeadlampConfig>(normalized)
-        .map(Some)
-        .map_err(|err| HeadlampError::ConfigParse {
-            path: path.to_path_buf(),
-            message: err.to_string(),
-        })
-}
-
-fn extract_pyproject_tool_headlamp(value: &toml::Value) -> Option<toml::Value> {
-    value
-        .as_table()
-        .and_then(|root| root.get("tool"))
-        .and_then(|tool| tool.as_table())
-        .and_then(|tool_table| tool_table.get("headlamp"))
-        .cloned()
-}
-
-fn extract_cargo_package_metadata_headlamp(value: &toml::Value) -> Option<toml::Value> {
-    value
-        .as_table()
-        .and_then(|root| root.get("package"))
-        .and_then(|package| package.as_table())
-        .and_then(|package_table| package_table.get("metadata"))
-        .and_then(|metadata| metadata.as_table())
-        .and_then(|metadata_table| metadata_table.get("headlamp"))
-        .cloned()
-}
-
-fn normalize_toml_keys_to_camel_case(value: &JsonValue) -> JsonValue {
-    match value {
-        JsonValue::Object(object) => {
-            let normalized_entries = object.iter().map(|(key, value)| {
-                let normalized_key = normalize_toml_key(key);
-                let normalized_value = normalize_toml_keys_to_camel_case(value);
-                (normalized_key, normalized_value)
-            });
-            JsonValue::Object(normalized_entries.collect())
-        }
-        JsonValue::Array(array) => JsonValue::Array(
-            array
-                .iter()
-                .map(normalize_toml_keys_to_camel_case)
-                .collect(),
-        ),
-        other => other.clone(),
-    }
-}
-
-fn normalize_toml_key(key: &str) -> String {
-    if !key.contains('_') {
-        return key.to_string();
-    }
-
-    let mut segments = key.split('_').filter(|segment| !segment.is_empty());
-    let Some(first_segment) = segments.next() else {
-        return key.to_string();
-    };
-
-    let mut normalized_key = String::from(first_segment);
-    for segment in segments {
-        let mut chars = segment.chars();
-        let Some(first_char) = chars.next() else {
-            continue;
-        };
-        normalized_key.push(first_char.to_ascii_uppercase());
-        normalized_key.extend(chars);
-    }
-    normalized_key
-}
-
-fn load_js_config(path: &Path) -> Result<HeadlampConfig, HeadlampError> {
-    let node = which_node().ok_or_else(|| HeadlampError::NodeMissing {
-        path: path.to_path_buf(),
-    })?;
-
-    let script = r#"
-import { pathToFileURL } from 'node:url';
-import { createRequire } from 'node:module';
-
-const p = process.argv[1];
-const url = pathToFileURL(p).href;
-
-let mod;
-try {
-  mod = await import(url);
-} catch (e) {
-  const require = createRequire(import.meta.url);
-  // Best-effort TS support (matches c12/jiti behavior when ts-node is present).
-  if (String(p).endsWith('.ts')) {
-    try { require('ts-node/register/transpile-only'); } catch {}
-    try { require('ts-node/register'); } catch {}
-    try { require('tsx/require'); } catch {}
-  }
-  mod = require(p);
-}
-
-const cfg = mod && (mod.default ?? mod);
-process.stdout.write(JSON.stringify(cfg ?? {}));
-"#;
-
-    let out = duct_cmd(
-        &node,
-        ["--input-type=module", "-e", script, &path.to_string_lossy()],
-    )
-    .stderr_capture()
-    .stdout_capture()
-    .unchecked()
-    .run()
-    .map_err(|e| HeadlampError::ConfigParse {
-        path: path.to_path_buf(),
-        message: e.to_string(),
-    })?;
-
-    if !out.status.success() {
-        let mut stderr = String::from_utf8_lossy(&out.stderr).to_string();
-        if stderr.trim().is_empty() {
-            stderr = format!("exit_code={:?}", out.status.code());
-        }
-        return Err(HeadlampError::NodeLoadFailed {
-            path: path.to_path_buf(),
-            stderr,
-        });
-    }
-
-    let stdout = String::from_utf8_lossy(&out.stdout);
-    serde_json::from_str::<HeadlampConfig>(&stdout).map_err(|err| HeadlampError::ConfigParse {
-        path: path.to_path_buf(),
-        message: err.to_string(),
-    })
-}
-
-fn load_ts_config_oxc(path: &Path) -> Result<HeadlampConfig, HeadlampError> {
-    let value = load_headlamp_config_ts_oxc(path)?;
-    serde_json::from_value::<HeadlampConfig>(value.clone()).map_err(|err| {
-        HeadlampError::ConfigParse {
-            path: path.to_path_buf(),
-            message: format!(
-                "{err} (ts_config_json={})",
-                serde_json::to_string(&value).unwrap_or_default()
-            ),
-        }
-    })
-}
-
-fn which_node() -> Option<PathBuf> {
-    which("node").ok()
-}
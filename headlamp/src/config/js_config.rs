@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use duct::cmd as duct_cmd;
+use which::which;
+
+use crate::error::HeadlampError;
+
+pub(super) fn load_js_config(path: &Path) -> Result<serde_json::Value, HeadlampError> {
+    let node = which_node().ok_or_else(|| HeadlampError::NodeMissing {
+        path: path.to_path_buf(),
+    })?;
+
+    let script = r#"
+import { pathToFileURL } from 'node:url';
+import { createRequire } from 'node:module';
+
+const p = process.argv[1];
+const url = pathToFileURL(p).href;
+
+let mod;
+try {
+  mod = await import(url);
+} catch (e) {
+  const require = createRequire(import.meta.url);
+  // Best-effort TS support (matches c12/jiti behavior when ts-node is present).
+  if (String(p).endsWith('.ts')) {
+    try { require('ts-node/register/transpile-only'); } catch {}
+    try { require('ts-node/register'); } catch {}
+    try { require('tsx/require'); } catch {}
+  }
+  mod = require(p);
+}
+
+const cfg = mod && (mod.default ?? mod);
+process.stdout.write(JSON.stringify(cfg ?? {}));
+"#;
+
+    let out = duct_cmd(
+        &node,
+        ["--input-type=module", "-e", script, &path.to_string_lossy()],
+    )
+    .stderr_capture()
+    .stdout_capture()
+    .unchecked()
+    .run()
+    .map_err(|e| HeadlampError::ConfigParse {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    if !out.status.success() {
+        let mut stderr = String::from_utf8_lossy(&out.stderr).to_string();
+        if stderr.trim().is_empty() {
+            stderr = format!("exit_code={:?}", out.status.code());
+        }
+        return Err(HeadlampError::NodeLoadFailed {
+            path: path.to_path_buf(),
+            stderr,
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    serde_json::from_str::<serde_json::Value>(&stdout).map_err(|err| HeadlampError::ConfigParse {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+fn which_node() -> Option<PathBuf> {
+    which("node").ok()
+}
@@ -1,4 +1,3 @@
-use super::HeadlampConfig;
 use serde::de::DeserializeOwned;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -7,10 +6,6 @@ enum ContextKind {
     Array,
 }
 
-pub(super) fn parse_jsonish_config(raw: &str) -> Result<HeadlampConfig, serde_json::Error> {
-    parse_jsonish::<HeadlampConfig>(raw)
-}
-
 pub(crate) fn parse_jsonish_value(raw: &str) -> Result<serde_json::Value, serde_json::Error> {
     parse_jsonish::<serde_json::Value>(raw)
 }
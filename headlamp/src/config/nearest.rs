@@ -0,0 +1,63 @@
+//! Nested `headlamp.config.*` files: a package's config overrides the repo root's for runs
+//! that select tests under that package, e.g. stricter coverage thresholds in `packages/core`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::discover_config_path;
+
+/// The directory a run is about: the deepest directory holding every selection path that
+/// exists on disk, else `cwd` (test-name patterns and globs do not move it).
+pub fn selection_anchor(cwd: &Path, selection_paths: &[String]) -> PathBuf {
+    let dirs = selection_paths
+        .iter()
+        .map(|selection| cwd.join(selection))
+        .filter(|path| path.exists())
+        .map(|path| match path.is_dir() {
+            true => path,
+            false => path.parent().map(Path::to_path_buf).unwrap_or(path),
+        })
+        .collect::<Vec<_>>();
+    let Some((first, rest)) = dirs.split_first() else {
+        return cwd.to_path_buf();
+    };
+    first
+        .ancestors()
+        .find(|candidate| rest.iter().all(|dir| dir.starts_with(candidate)))
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// Config files in the directories between `repo_root` (exclusive) and `anchor`, outermost
+/// first, so that merging them in order lets the nearest one win.
+pub fn nested_config_paths(repo_root: &Path, anchor: &Path) -> Vec<PathBuf> {
+    if !anchor.starts_with(repo_root) {
+        return vec![];
+    }
+    let mut paths = anchor
+        .ancestors()
+        .take_while(|dir| *dir != repo_root)
+        .filter_map(discover_config_path)
+        .collect::<Vec<_>>();
+    paths.reverse();
+    paths
+}
+
+/// Overlays `overlay` onto `base`: objects merge key by key, anything else (lists included)
+/// replaces the base value.
+pub fn merge_config_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_config_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
@@ -0,0 +1,50 @@
+//! The repo root every run is keyed on: config lookup, cache keys and shown paths. It is always
+//! canonical, so `cd`-ing in through a symlink (`/var` vs `/private/var` on macOS) or a `subst`
+//! drive lands on the same root as `cd`-ing in directly.
+
+use std::path::{Path, PathBuf};
+
+/// The git toplevel above `start` (or `start` itself outside a repo), canonicalized.
+pub fn find_repo_root(start: &Path) -> PathBuf {
+    let start_dir = start.parent().filter(|_| start.is_file()).unwrap_or(start);
+    let out = crate::git::git_command_in_repo(start_dir)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from);
+    canonical_or_given(&out.unwrap_or_else(|| start.to_path_buf()))
+}
+
+/// `--repo-root <path>` (relative to `cwd`) when `argv` has it, else [`find_repo_root`]. Read
+/// straight from argv because the config it locates is loaded before the args are parsed.
+pub fn resolve_repo_root(cwd: &Path, argv: &[String]) -> PathBuf {
+    repo_root_override(argv)
+        .map(|root| canonical_or_given(&cwd.join(root)))
+        .unwrap_or_else(|| find_repo_root(cwd))
+}
+
+fn repo_root_override(argv: &[String]) -> Option<String> {
+    let mut tokens = argv.iter().take_while(|token| token.as_str() != "--");
+    while let Some(token) = tokens.next() {
+        for flag in ["--repo-root", "--repoRoot"] {
+            let Some(rest) = token.strip_prefix(flag) else {
+                continue;
+            };
+            match rest.strip_prefix('=') {
+                Some(value) => return Some(value.to_string()),
+                None if rest.is_empty() => return tokens.next().cloned(),
+                None => {}
+            }
+        }
+    }
+    None
+}
+
+/// `dunce` keeps Windows paths free of the `\\?\` prefix `std` would add.
+pub fn canonical_or_given(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,443 @@
+//! The shape of headlamp's config, checked against the raw value every format loads to (JSON,
+//! YAML, TOML, JS/TS) so typos and wrong types are reported instead of silently turning into
+//! defaults.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    Bool,
+    String,
+    Number,
+    Integer,
+    Strings,
+    StringMap,
+    OneOf(&'static [&'static str]),
+    Object(&'static [Field]),
+    List(&'static Shape),
+    /// An object with keys the user picks, e.g. preset names.
+    Map(&'static Shape),
+    Either(&'static [Shape]),
+}
+
+type Field = (&'static str, Shape);
+
+const CHANGED_MODES: &[&str] = &[
+    "all",
+    "staged",
+    "unstaged",
+    "branch",
+    "lastCommit",
+    "lastRelease",
+];
+const COVERAGE_MODES: &[&str] = &["compact", "full", "auto"];
+
+const THRESHOLDS: &[Field] = &[
+    ("lines", Shape::Number),
+    ("functions", Shape::Number),
+    ("branches", Shape::Number),
+    ("statements", Shape::Number),
+];
+
+const COVERAGE_SECTION: &[Field] = &[
+    ("abortOnFailure", Shape::Bool),
+    ("nativeThresholds", Shape::Bool),
+    ("mode", Shape::OneOf(COVERAGE_MODES)),
+    ("pageFit", Shape::Bool),
+    ("thresholds", Shape::Object(THRESHOLDS)),
+];
+
+const MODE_DEPTH: Shape =
+    Shape::Either(&[Shape::Integer, Shape::Object(&[("depth", Shape::Integer)])]);
+
+const CHANGED_SECTION: &[Field] = &[
+    ("depth", Shape::Integer),
+    ("all", MODE_DEPTH),
+    ("staged", MODE_DEPTH),
+    ("unstaged", MODE_DEPTH),
+    ("branch", MODE_DEPTH),
+    ("lastCommit", MODE_DEPTH),
+    ("lastRelease", MODE_DEPTH),
+];
+
+const HOOKS: &[Field] = &[
+    ("preRun", Shape::Strings),
+    ("postRun", Shape::Strings),
+    ("preCoverage", Shape::Strings),
+    ("onFailure", Shape::Strings),
+    ("pre_run", Shape::Strings),
+    ("post_run", Shape::Strings),
+    ("pre_coverage", Shape::Strings),
+    ("on_failure", Shape::Strings),
+];
+
+const CHECK: &[Field] = &[
+    ("format", Shape::OneOf(&["eslint", "ruff", "clippy"])),
+    ("command", Shape::String),
+];
+
+const OUTPUT_FILTER: &[Field] = &[("pattern", Shape::String), ("collapse", Shape::Bool)];
+
+/// Every key `HeadlampConfig` reads, as spelled in the config.
+const CONFIG: &[Field] = &[
+    ("bootstrapCommand", Shape::String),
+    ("jestArgs", Shape::Strings),
+    ("vitestArgs", Shape::Strings),
+    ("sequential", Shape::Bool),
+    ("keepArtifacts", Shape::Bool),
+    ("watch", Shape::Bool),
+    ("ci", Shape::Bool),
+    ("verbose", Shape::Bool),
+    ("quiet", Shape::Bool),
+    ("noCache", Shape::Bool),
+    (
+        "coverage",
+        Shape::Either(&[Shape::Bool, Shape::Object(COVERAGE_SECTION)]),
+    ),
+    ("coverageUi", Shape::OneOf(&["jest", "both"])),
+    (
+        "coverageEngine",
+        Shape::OneOf(&["auto", "instrument", "llvm-cov"]),
+    ),
+    ("coverageProvider", Shape::OneOf(&["babel", "v8"])),
+    ("coverageAbortOnFailure", Shape::Bool),
+    ("onlyFailures", Shape::Bool),
+    ("showLogs", Shape::Bool),
+    ("fullStacks", Shape::Bool),
+    ("include", Shape::Strings),
+    ("exclude", Shape::Strings),
+    ("editorCmd", Shape::String),
+    ("workspaceRoot", Shape::String),
+    (
+        "coverageDetail",
+        Shape::Either(&[Shape::Integer, Shape::OneOf(&["all", "auto"])]),
+    ),
+    ("coverageShowCode", Shape::Bool),
+    ("coverageMode", Shape::OneOf(COVERAGE_MODES)),
+    ("coverageMaxFiles", Shape::Integer),
+    ("coverageMaxHotspots", Shape::Integer),
+    ("coveragePageFit", Shape::Bool),
+    (
+        "changed",
+        Shape::Either(&[Shape::OneOf(CHANGED_MODES), Shape::Object(CHANGED_SECTION)]),
+    ),
+    ("progressHeartbeat", Shape::Integer),
+    ("maxMemory", Shape::String),
+    ("nice", Shape::Integer),
+    ("python", Shape::String),
+    ("features", Shape::String),
+    ("allFeatures", Shape::Bool),
+    ("noDefaultFeatures", Shape::Bool),
+    ("featureMatrix", Shape::String),
+    ("packages", Shape::Strings),
+    ("excludePackages", Shape::Strings),
+    ("target", Shape::String),
+    ("targetRunner", Shape::String),
+    ("cargoProfile", Shape::String),
+    ("nextestProfile", Shape::String),
+    ("autoInstall", Shape::Bool),
+    ("projects", Shape::Strings),
+    ("ignoreProjects", Shape::Strings),
+    ("jestEsm", Shape::OneOf(&["auto", "on", "off"])),
+    ("typecheck", Shape::OneOf(&["off", "gate", "warn"])),
+    ("typecheckCommand", Shape::String),
+    ("node", Shape::String),
+    ("hooks", Shape::Object(HOOKS)),
+    ("waitFor", Shape::Strings),
+    ("waitTimeout", Shape::String),
+    ("env", Shape::StringMap),
+    ("envFile", Shape::String),
+    ("artifactsDir", Shape::String),
+    ("artifactGlobs", Shape::Strings),
+    (
+        "outputFilters",
+        Shape::List(&Shape::Either(&[
+            Shape::String,
+            Shape::Object(OUTPUT_FILTER),
+        ])),
+    ),
+    ("badgeOut", Shape::String),
+    ("notify", Shape::Strings),
+    ("checks", Shape::List(&Shape::Object(CHECK))),
+    ("presets", Shape::Map(&Shape::Strings)),
+    ("logLevel", Shape::String),
+    ("logJson", Shape::Bool),
+    ("deterministic", Shape::Bool),
+    ("normalizeTimes", Shape::Bool),
+    ("bail", Shape::Bool),
+    ("summaryLine", Shape::Bool),
+    ("checkUpdate", Shape::Bool),
+    ("showSkipped", Shape::Bool),
+    ("showSkippedProjects", Shape::Bool),
+    ("footerBreakdown", Shape::Bool),
+    ("groupBy", Shape::OneOf(&["file", "suite", "package"])),
+    (
+        "pathStyle",
+        Shape::OneOf(&["relative", "absolute", "package-relative"]),
+    ),
+    ("stripPathPrefixes", Shape::Strings),
+    ("maxReportedFailures", Shape::Integer),
+    ("failOnSkipped", Shape::Bool),
+    ("failIfNoTests", Shape::Bool),
+    ("failOnUntestedChanges", Shape::Bool),
+    ("strictDuplicates", Shape::Bool),
+    ("maxDuration", Shape::String),
+    ("shardLauncher", Shape::String),
+    ("coverageSection", Shape::Object(COVERAGE_SECTION)),
+    ("changedSection", Shape::Object(CHANGED_SECTION)),
+];
+
+/// One problem with a config, e.g. an unknown key or a value of the wrong type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Where in the config: `coverage.thresholds.lines`, `checks[0].format`.
+    pub path: String,
+    /// 1-based line in the config file, when the key could be found in its source.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Checks a loaded config against the schema. Returns its issues and the config without the
+/// offending entries, so one bad setting is dropped rather than the whole config.
+pub fn validate_config_value(value: &Value) -> (Vec<ConfigIssue>, Value) {
+    let mut issues = vec![];
+    let cleaned = check(value, Shape::Object(CONFIG), "", &mut issues)
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    (issues, cleaned)
+}
+
+fn check(value: &Value, shape: Shape, path: &str, issues: &mut Vec<ConfigIssue>) -> Option<Value> {
+    match (shape, value) {
+        (Shape::Bool, Value::Bool(_))
+        | (Shape::String, Value::String(_))
+        | (Shape::Number, Value::Number(_)) => Some(value.clone()),
+        (Shape::Integer, Value::Number(number)) if number.as_u64().is_some() => Some(value.clone()),
+        (Shape::Strings, Value::Array(items)) if items.iter().all(Value::is_string) => {
+            Some(value.clone())
+        }
+        (Shape::StringMap, Value::Object(map)) if map.values().all(Value::is_string) => {
+            Some(value.clone())
+        }
+        (Shape::OneOf(options), Value::String(text)) => {
+            check_one_of(options, text, path, issues).then(|| value.clone())
+        }
+        (Shape::Object(fields), Value::Object(map)) => {
+            Some(check_object(fields, map, path, issues))
+        }
+        (Shape::List(item), Value::Array(items)) => Some(Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    check(entry, *item, &format!("{path}[{index}]"), issues)
+                })
+                .collect(),
+        )),
+        (Shape::Map(item), Value::Object(map)) => Some(Value::Object(
+            map.iter()
+                .filter_map(|(key, entry)| {
+                    check(entry, *item, &format!("{path}.{key}"), issues)
+                        .map(|checked| (key.clone(), checked))
+                })
+                .collect(),
+        )),
+        (Shape::Either(shapes), _) => match shapes.iter().find(|alt| same_kind(**alt, value)) {
+            Some(alt) => check(value, *alt, path, issues),
+            None => mismatch(shape, value, path, issues),
+        },
+        _ => mismatch(shape, value, path, issues),
+    }
+}
+
+fn check_object(
+    fields: &[Field],
+    map: &serde_json::Map<String, Value>,
+    path: &str,
+    issues: &mut Vec<ConfigIssue>,
+) -> Value {
+    let mut cleaned = serde_json::Map::new();
+    // `$schema` and friends are for editors, not headlamp.
+    for (key, entry) in map.iter().filter(|(key, _)| !key.starts_with('$')) {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        let Some((_, shape)) = fields.iter().find(|(name, _)| name == key) else {
+            let known = fields.iter().map(|(name, _)| *name);
+            let message = match did_you_mean(key, known) {
+                Some(suggestion) => format!("unknown key; did you mean `{suggestion}`?"),
+                None => "unknown key".to_string(),
+            };
+            issues.push(issue(key_path, message));
+            continue;
+        };
+        // Every setting is optional, and `null` reads as unset.
+        let checked = if entry.is_null() {
+            Some(Value::Null)
+        } else {
+            check(entry, *shape, &key_path, issues)
+        };
+        if let Some(checked) = checked {
+            cleaned.insert(key.clone(), checked);
+        }
+    }
+    Value::Object(cleaned)
+}
+
+fn check_one_of(options: &[&str], text: &str, path: &str, issues: &mut Vec<ConfigIssue>) -> bool {
+    if options.contains(&text) {
+        return true;
+    }
+    let mut message = format!("expected one of {}, found {text:?}", options.join(", "));
+    if let Some(suggestion) = did_you_mean(text, options.iter().copied()) {
+        message.push_str(&format!("; did you mean {suggestion:?}?"));
+    }
+    issues.push(issue(path.to_string(), message));
+    false
+}
+
+fn mismatch(
+    shape: Shape,
+    value: &Value,
+    path: &str,
+    issues: &mut Vec<ConfigIssue>,
+) -> Option<Value> {
+    let message = format!(
+        "expected {}, found {}",
+        describe_shape(shape),
+        describe_value(value)
+    );
+    issues.push(issue(path.to_string(), message));
+    None
+}
+
+fn issue(path: String, message: String) -> ConfigIssue {
+    ConfigIssue {
+        path,
+        line: None,
+        message,
+    }
+}
+
+/// Whether `value` is the JSON kind `shape` wants, deciding which `Either` branch to check.
+fn same_kind(shape: Shape, value: &Value) -> bool {
+    match shape {
+        Shape::Bool => value.is_boolean(),
+        Shape::String | Shape::OneOf(_) => value.is_string(),
+        Shape::Number | Shape::Integer => value.is_number(),
+        Shape::Strings | Shape::List(_) => value.is_array(),
+        Shape::StringMap | Shape::Object(_) | Shape::Map(_) => value.is_object(),
+        Shape::Either(shapes) => shapes.iter().any(|alt| same_kind(*alt, value)),
+    }
+}
+
+fn describe_shape(shape: Shape) -> String {
+    match shape {
+        Shape::Bool => "true or false".to_string(),
+        Shape::String => "a string".to_string(),
+        Shape::Number => "a number".to_string(),
+        Shape::Integer => "a whole number".to_string(),
+        Shape::Strings => "a list of strings".to_string(),
+        Shape::StringMap => "an object of strings".to_string(),
+        Shape::OneOf(options) => format!("one of {}", options.join(", ")),
+        Shape::Object(_) => "an object".to_string(),
+        Shape::List(_) => "a list".to_string(),
+        Shape::Map(item) => format!("an object whose values are {}", describe_shape(*item)),
+        Shape::Either(shapes) => shapes
+            .iter()
+            .map(|alt| describe_shape(*alt))
+            .collect::<Vec<_>>()
+            .join(" or "),
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Number(number) => format!("the number {number}"),
+        Value::String(text) => format!("the string {text:?}"),
+        Value::Array(_) => "a list".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+/// The closest of `candidates` to a misspelled `key`, ignoring case, `_` and `-`.
+pub fn did_you_mean<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let wanted = fold_key(key);
+    let limit = if wanted.len() > 5 { 2 } else { 1 };
+    candidates
+        .map(|candidate| (edit_distance(&wanted, &fold_key(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn fold_key(key: &str) -> Vec<char> {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+static INDEX_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\d+\]").unwrap());
+
+/// The 1-based line of the key at `path` in a config's source text, found by searching for each
+/// segment in turn after the previous one. Works across JSON, YAML, TOML (whose keys may be
+/// snake_case) and JS/TS object literals; falls back to the deepest segment it found.
+pub fn locate_line(source: &str, path: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut found = None;
+    for segment in INDEX_RE.replace_all(path, "").split('.') {
+        let pattern = format!(
+            r#"(?m)(?:^|[\s{{,\[.])["']?(?:{}|{})["']?\s*(?:[:=\].])"#,
+            regex::escape(segment),
+            regex::escape(&snake_case(segment))
+        );
+        let Some(hit) = Regex::new(&pattern).ok()?.find_at(source, offset) else {
+            break;
+        };
+        offset = hit.end();
+        found = Some(source[..hit.end()].lines().count());
+    }
+    found
+}
+
+fn snake_case(key: &str) -> String {
+    key.chars().fold(String::new(), |mut out, c| {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+        out
+    })
+}
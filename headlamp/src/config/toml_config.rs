@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value as JsonValue;
+
+use super::HeadlampConfig;
+use crate::error::HeadlampError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TomlConfigSource {
+    FullFile,
+    PyProjectToolHeadlamp,
+    CargoPackageMetadataHeadlamp,
+}
+
+pub(super) fn load_embedded_toml_config(repo_root: &Path) -> Result<HeadlampConfig, HeadlampError> {
+    let Some(path) = embedded_config_path(repo_root)? else {
+        return Ok(HeadlampConfig::default());
+    };
+    let value = load_toml_config_value(&path, toml_source_for(&path))?.unwrap_or_default();
+    serde_json::from_value::<HeadlampConfig>(value).map_err(|err| HeadlampError::ConfigParse {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// The `pyproject.toml` or `Cargo.toml` in `repo_root` that embeds a headlamp table, in that
+/// order of preference.
+pub(super) fn embedded_config_path(repo_root: &Path) -> Result<Option<PathBuf>, HeadlampError> {
+    for name in ["pyproject.toml", "Cargo.toml"] {
+        let path = repo_root.join(name);
+        if path.exists() && load_toml_config_value(&path, toml_source_for(&path))?.is_some() {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+/// Which part of a TOML file is headlamp's, going by the file name.
+pub(super) fn toml_source_for(path: &Path) -> TomlConfigSource {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some("pyproject.toml") => TomlConfigSource::PyProjectToolHeadlamp,
+        Some("Cargo.toml") => TomlConfigSource::CargoPackageMetadataHeadlamp,
+        _ => TomlConfigSource::FullFile,
+    }
+}
+
+/// The headlamp table of a TOML file, keys normalized to the camelCase of the other formats.
+pub(super) fn load_toml_config_value(
+    path: &Path,
+    source: TomlConfigSource,
+) -> Result<Option<JsonValue>, HeadlampError> {
+    let raw = std::fs::read_to_string(path).map_err(|source| HeadlampError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let toml_value =
+        toml::from_str::<toml::Value>(&raw).map_err(|err| HeadlampError::ConfigParse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+
+    let maybe_table_value = match source {
+        TomlConfigSource::FullFile => Some(toml_value),
+        TomlConfigSource::PyProjectToolHeadlamp => extract_pyproject_tool_headlamp(&toml_value),
+        TomlConfigSource::CargoPackageMetadataHeadlamp => {
+            extract_cargo_package_metadata_headlamp(&toml_value)
+        }
+    };
+
+    let Some(table_value) = maybe_table_value else {
+        return Ok(None);
+    };
+
+    let json_value =
+        serde_json::to_value(table_value).map_err(|err| HeadlampError::ConfigParse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+    Ok(Some(normalize_toml_keys_to_camel_case(&json_value)))
+}
+
+fn extract_pyproject_tool_headlamp(value: &toml::Value) -> Option<toml::Value> {
+    value
+        .as_table()
+        .and_then(|root| root.get("tool"))
+        .and_then(|tool| tool.as_table())
+        .and_then(|tool_table| tool_table.get("headlamp"))
+        .cloned()
+}
+
+fn extract_cargo_package_metadata_headlamp(value: &toml::Value) -> Option<toml::Value> {
+    value
+        .as_table()
+        .and_then(|root| root.get("package"))
+        .and_then(|package| package.as_table())
+        .and_then(|package_table| package_table.get("metadata"))
+        .and_then(|metadata| metadata.as_table())
+        .and_then(|metadata_table| metadata_table.get("headlamp"))
+        .cloned()
+}
+
+fn normalize_toml_keys_to_camel_case(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(object) => {
+            let normalized_entries = object.iter().map(|(key, value)| {
+                let normalized_key = normalize_toml_key(key);
+                let normalized_value = normalize_toml_keys_to_camel_case(value);
+                (normalized_key, normalized_value)
+            });
+            JsonValue::Object(normalized_entries.collect())
+        }
+        JsonValue::Array(array) => JsonValue::Array(
+            array
+                .iter()
+                .map(normalize_toml_keys_to_camel_case)
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn normalize_toml_key(key: &str) -> String {
+    if !key.contains('_') {
+        return key.to_string();
+    }
+
+    let mut segments = key.split('_').filter(|segment| !segment.is_empty());
+    let Some(first_segment) = segments.next() else {
+        return key.to_string();
+    };
+
+    let mut normalized_key = String::from(first_segment);
+    for segment in segments {
+        let mut chars = segment.chars();
+        let Some(first_char) = chars.next() else {
+            continue;
+        };
+        normalized_key.push(first_char.to_ascii_uppercase());
+        normalized_key.extend(chars);
+    }
+    normalized_key
+}
@@ -0,0 +1,140 @@
+//! Config validation for runs (issues become warnings) and for `headlamp config validate`.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::Value;
+
+use super::nearest::{merge_config_values, nested_config_paths};
+use super::schema::{ConfigIssue, locate_line, validate_config_value};
+use super::{HeadlampConfig, discover_config_path, load_config_value, toml_config};
+use crate::error::HeadlampError;
+
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// The config file in effect for `repo_root`: a headlamp config file, else a `pyproject.toml`
+/// or `Cargo.toml` with a headlamp table.
+pub fn config_path(repo_root: &Path) -> Result<Option<PathBuf>, HeadlampError> {
+    match discover_config_path(repo_root) {
+        Some(path) => Ok(Some(path)),
+        None => toml_config::embedded_config_path(repo_root),
+    }
+}
+
+/// Loads the config at `path` and checks it. Returns the issues, with lines from the file's
+/// source, and the config without the invalid entries.
+pub fn validate_config_file(path: &Path) -> Result<(Vec<ConfigIssue>, Value), HeadlampError> {
+    let value = load_config_value(path)?.unwrap_or_else(|| Value::Object(Default::default()));
+    let (mut issues, cleaned) = validate_config_value(&value);
+    if let Ok(source) = std::fs::read_to_string(path) {
+        issues
+            .iter_mut()
+            .for_each(|issue| issue.line = locate_line(&source, &issue.path));
+    }
+    Ok((issues, cleaned))
+}
+
+/// `file:line: path: message`, the way compilers point at a problem.
+pub fn render_issue(file: &str, issue: &ConfigIssue) -> String {
+    match issue.line {
+        Some(line) => format!("{file}:{line}: {issue}"),
+        None => format!("{file}: {issue}"),
+    }
+}
+
+/// The config for a run. Invalid settings are dropped with a warning (printed once per
+/// process) and the rest still apply; a config that cannot be loaded at all is reported and
+/// replaced by the defaults.
+pub fn load_headlamp_config_checked(repo_root: &Path) -> HeadlampConfig {
+    load_headlamp_config_for(repo_root, repo_root)
+}
+
+/// Like [`load_headlamp_config_checked`], with the configs in directories between the repo
+/// root and `anchor` layered on top, nearest last. A nested config that cannot be loaded is
+/// reported and skipped.
+pub fn load_headlamp_config_for(repo_root: &Path, anchor: &Path) -> HeadlampConfig {
+    let report = !REPORTED.swap(true, Ordering::SeqCst);
+    let warn = |err: HeadlampError| {
+        report.then(|| eprintln!("headlamp: {err}; ignoring that config"));
+    };
+    let root = config_path(repo_root).unwrap_or_else(|err| {
+        warn(err);
+        None
+    });
+    let mut merged = Value::Object(Default::default());
+    for path in root
+        .into_iter()
+        .chain(nested_config_paths(repo_root, anchor))
+    {
+        match validate_config_file(&path) {
+            Ok((issues, cleaned)) => {
+                let file = display_path(repo_root, &path);
+                issues
+                    .iter()
+                    .filter(|_| report)
+                    .for_each(|issue| eprintln!("headlamp: {}", render_issue(&file, issue)));
+                merge_config_values(&mut merged, cleaned);
+            }
+            Err(err) => warn(err),
+        }
+    }
+    serde_json::from_value::<HeadlampConfig>(merged).unwrap_or_else(|err| {
+        report.then(|| eprintln!("headlamp: {err}; using the default config"));
+        HeadlampConfig::default()
+    })
+}
+
+/// `headlamp config validate [path]`. Returns `None` when `argv` is an ordinary test run.
+pub fn run_config_command(argv: &[String]) -> Option<i32> {
+    let [first, subcommand, rest @ ..] = argv else {
+        return None;
+    };
+    if first != "config" || subcommand != "validate" {
+        return None;
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = super::find_repo_root(&cwd);
+    let path = match rest {
+        [] => match config_path(&repo_root) {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                println!("headlamp: no config found in {}", repo_root.display());
+                return Some(0);
+            }
+            Err(err) => {
+                eprintln!("headlamp: {err}");
+                return Some(2);
+            }
+        },
+        [path] => cwd.join(path),
+        _ => {
+            eprintln!("headlamp: usage: headlamp config validate [path]");
+            return Some(2);
+        }
+    };
+    let file = display_path(&cwd, &path);
+    Some(match validate_config_file(&path) {
+        Ok((issues, _)) if issues.is_empty() => {
+            println!("headlamp: {file} is valid");
+            0
+        }
+        Ok((issues, _)) => {
+            issues
+                .iter()
+                .for_each(|issue| println!("{}", render_issue(&file, issue)));
+            println!("headlamp: {} problem(s) in {file}", issues.len());
+            1
+        }
+        Err(err) => {
+            eprintln!("headlamp: {err}");
+            2
+        }
+    })
+}
+
+fn display_path(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
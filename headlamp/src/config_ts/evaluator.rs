@@ -4,14 +4,15 @@ use std::sync::Arc;
 
 use oxc_ast::ast::{
     BindingPatternKind, Declaration, ExportDefaultDeclaration, ExportDefaultDeclarationKind,
-    ExportNamedDeclaration, ImportDeclaration, ImportDeclarationSpecifier, Program, Statement,
+    ExportNamedDeclaration, Function, ImportDeclaration, ImportDeclarationSpecifier, Program,
+    Statement,
 };
 use oxc_resolver::Resolver;
 
 use crate::error::HeadlampError;
 
 use super::types::{ImportBinding, ModuleCacheEntry};
-use super::utils::module_export_name_as_str;
+use super::utils::{config_label, module_export_name_as_str, module_exports_assignment};
 
 #[derive(Debug)]
 pub(super) struct ModuleEvaluator<'a> {
@@ -20,6 +21,7 @@ pub(super) struct ModuleEvaluator<'a> {
     pub(super) resolver: Resolver,
     pub(super) const_inits: HashMap<String, &'a oxc_ast::ast::Expression<'a>>,
     pub(super) imports: HashMap<String, ImportBinding>,
+    pub(super) function_decls: HashMap<String, &'a Function<'a>>,
 }
 
 impl<'a> ModuleEvaluator<'a> {
@@ -30,6 +32,7 @@ impl<'a> ModuleEvaluator<'a> {
             resolver,
             const_inits: HashMap::new(),
             imports: HashMap::new(),
+            function_decls: HashMap::new(),
         }
     }
 
@@ -51,6 +54,11 @@ impl<'a> ModuleEvaluator<'a> {
                 Statement::ExportNamedDeclaration(it) => {
                     self.eval_export_named(it, &mut exports, cache, stack)?;
                 }
+                Statement::ExpressionStatement(it) => {
+                    if let Some(right) = module_exports_assignment(&it.expression) {
+                        default_export = Some(self.eval_exported_config(right, cache, stack)?);
+                    }
+                }
                 _ => {}
             }
         }
@@ -66,6 +74,12 @@ impl<'a> ModuleEvaluator<'a> {
             match statement {
                 Statement::ImportDeclaration(it) => self.collect_import(it)?,
                 Statement::VariableDeclaration(var) => self.collect_var_decl(var),
+                Statement::FunctionDeclaration(func) => {
+                    if let Some(id) = &func.id {
+                        self.function_decls
+                            .insert(id.name.as_str().to_string(), func);
+                    }
+                }
                 Statement::ExportNamedDeclaration(it) => {
                     if let Some(Declaration::VariableDeclaration(var)) = &it.declaration {
                         self.collect_var_decl(var);
@@ -77,7 +91,7 @@ impl<'a> ModuleEvaluator<'a> {
         Ok(())
     }
 
-    fn collect_var_decl(&mut self, var: &'a oxc_ast::ast::VariableDeclaration<'a>) {
+    pub(super) fn collect_var_decl(&mut self, var: &'a oxc_ast::ast::VariableDeclaration<'a>) {
         for declarator in &var.declarations {
             let Some(init) = declarator.init.as_ref() else {
                 continue;
@@ -91,60 +105,67 @@ impl<'a> ModuleEvaluator<'a> {
     }
 
     fn collect_import(&mut self, it: &'a ImportDeclaration<'a>) -> Result<(), HeadlampError> {
-        let spec = it.source.value.as_str();
-        if !(spec.starts_with("./") || spec.starts_with("../")) {
-            return Err(
-                self.unsupported("only relative imports are supported in headlamp.config.ts")
-            );
+        if it.import_kind.is_type() {
+            return Ok(());
         }
-
-        let from_dir = self.path.parent().unwrap_or_else(|| Path::new("."));
-        let resolved = self
-            .resolver
-            .resolve(from_dir, spec)
-            .map_err(|e| HeadlampError::ConfigParse {
-                path: self.path.to_path_buf(),
-                message: format!("failed to resolve import {spec:?}: {e}"),
-            })?
-            .full_path();
-
+        let spec = it.source.value.as_str();
+        let from = self.resolve_import(spec)?;
         let Some(specifiers) = it.specifiers.as_ref() else {
             return Ok(());
         };
+        let external = || ImportBinding::External {
+            specifier: spec.to_string(),
+        };
 
         for s in specifiers {
-            match s {
+            let (local, binding) = match s {
                 ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
-                    let local = specifier.local.name.as_str().to_string();
-                    let imported = specifier.imported.name().to_string();
-                    self.imports.insert(
-                        local,
-                        ImportBinding::Named {
-                            from: resolved.clone(),
-                            export: imported,
-                        },
-                    );
+                    if specifier.import_kind.is_type() {
+                        continue;
+                    }
+                    let binding =
+                        from.clone()
+                            .map_or_else(&external, |from| ImportBinding::Named {
+                                from,
+                                export: specifier.imported.name().to_string(),
+                            });
+                    (specifier.local.name.as_str(), binding)
                 }
                 ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => {
-                    let local = specifier.local.name.as_str().to_string();
-                    self.imports.insert(
-                        local,
-                        ImportBinding::Default {
-                            from: resolved.clone(),
-                        },
-                    );
+                    let binding = from
+                        .clone()
+                        .map_or_else(&external, |from| ImportBinding::Default { from });
+                    (specifier.local.name.as_str(), binding)
                 }
-                _ => {
-                    return Err(
-                        self.unsupported("unsupported import specifier in headlamp.config.ts")
-                    );
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => {
+                    let binding = from
+                        .clone()
+                        .map_or_else(&external, |from| ImportBinding::Namespace { from });
+                    (specifier.local.name.as_str(), binding)
                 }
-            }
+            };
+            self.imports.insert(local.to_string(), binding);
         }
 
         Ok(())
     }
 
+    /// Relative specifiers resolve to a file on disk; bare package specifiers resolve to `None`
+    /// because packages are not evaluated.
+    pub(super) fn resolve_import(&self, spec: &str) -> Result<Option<PathBuf>, HeadlampError> {
+        if !(spec.starts_with("./") || spec.starts_with("../")) {
+            return Ok(None);
+        }
+        let from_dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        self.resolver
+            .resolve(from_dir, spec)
+            .map(|resolution| Some(resolution.full_path()))
+            .map_err(|e| HeadlampError::ConfigParse {
+                path: self.path.to_path_buf(),
+                message: format!("failed to resolve import {spec:?}: {e}"),
+            })
+    }
+
     fn eval_export_default(
         &mut self,
         it: &'a ExportDefaultDeclaration<'a>,
@@ -161,8 +182,16 @@ impl<'a> ModuleEvaluator<'a> {
         stack: &mut Vec<PathBuf>,
     ) -> Result<serde_json::Value, HeadlampError> {
         match kind {
-            ExportDefaultDeclarationKind::FunctionDeclaration(_)
-            | ExportDefaultDeclarationKind::ClassDeclaration(_)
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                self.eval_function(func, cache, stack)
+            }
+            ExportDefaultDeclarationKind::ArrowFunctionExpression(arrow) => {
+                self.eval_arrow_function(arrow, cache, stack)
+            }
+            ExportDefaultDeclarationKind::FunctionExpression(func) => {
+                self.eval_function(func, cache, stack)
+            }
+            ExportDefaultDeclarationKind::ClassDeclaration(_)
             | ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => {
                 Err(self.unsupported("unsupported export default declaration kind"))
             }
@@ -173,7 +202,7 @@ impl<'a> ModuleEvaluator<'a> {
                 self.eval_array_expression(arr, cache, stack)
             }
             ExportDefaultDeclarationKind::Identifier(ident) => {
-                self.eval_identifier_value(ident.name.as_str(), cache, stack)
+                self.eval_exported_identifier(ident.name.as_str(), cache, stack)
             }
             ExportDefaultDeclarationKind::CallExpression(call) => {
                 self.eval_call_expression(&call.callee, &call.arguments, cache, stack)
@@ -249,7 +278,7 @@ impl<'a> ModuleEvaluator<'a> {
     pub(super) fn unsupported(&self, message: &str) -> HeadlampError {
         HeadlampError::ConfigParse {
             path: self.path.to_path_buf(),
-            message: format!("headlamp.config.ts: {message}"),
+            message: format!("{}: {message}", config_label(self.path)),
         }
     }
 }
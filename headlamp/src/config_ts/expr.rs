@@ -10,7 +10,9 @@ use oxc_ast::ast::{
 use crate::error::HeadlampError;
 
 use super::evaluator::ModuleEvaluator;
-use super::types::{ImportBinding, ModuleCacheEntry};
+use super::imports::ModuleRequest;
+use super::operators::{is_process_env, is_static_call, is_truthy};
+use super::types::ModuleCacheEntry;
 
 impl<'a> ModuleEvaluator<'a> {
     pub(super) fn eval_identifier_value(
@@ -24,25 +26,7 @@ impl<'a> ModuleEvaluator<'a> {
         }
 
         if let Some(binding) = self.imports.get(name).cloned() {
-            let entry = super::load_module_exports(
-                match &binding {
-                    ImportBinding::Named { from, .. } => from,
-                    ImportBinding::Default { from } => from,
-                },
-                cache,
-                stack,
-            )?;
-            return match binding {
-                ImportBinding::Named { export, .. } => entry
-                    .exports
-                    .get(export.as_str())
-                    .cloned()
-                    .ok_or_else(|| self.unsupported("imported named export not found")),
-                ImportBinding::Default { .. } => entry
-                    .default_export
-                    .clone()
-                    .ok_or_else(|| self.unsupported("imported default export not found")),
-            };
+            return self.eval_import_binding(binding, cache, stack);
         }
 
         Err(self.unsupported("unknown identifier"))
@@ -55,17 +39,28 @@ impl<'a> ModuleEvaluator<'a> {
         cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
         stack: &mut Vec<PathBuf>,
     ) -> Result<serde_json::Value, HeadlampError> {
+        if is_static_call(callee, "JSON", "parse") {
+            return self.eval_json_parse(args, cache, stack);
+        }
         let Expression::Identifier(id) = callee else {
             return Err(self.unsupported("unsupported call callee"));
         };
-        let name = id.name.as_str();
-        if name != "defineConfig" {
-            return Err(self.unsupported("unsupported call expression"));
+        match id.name.as_str() {
+            "defineConfig" => {
+                let first = args
+                    .first()
+                    .ok_or_else(|| self.unsupported("defineConfig requires arg"))?;
+                self.eval_argument_to_value(first, cache, stack)
+            }
+            "require" => {
+                let source = args
+                    .first()
+                    .and_then(|arg| arg.as_expression())
+                    .ok_or_else(|| self.unsupported("require requires arg"))?;
+                self.eval_module_request(source, ModuleRequest::Require, cache, stack)
+            }
+            _ => Err(self.unsupported("unsupported call expression")),
         }
-        let first = args
-            .first()
-            .ok_or_else(|| self.unsupported("defineConfig requires arg"))?;
-        self.eval_argument_to_value(first, cache, stack)
     }
 
     pub(super) fn eval_argument_to_value(
@@ -114,7 +109,20 @@ impl<'a> ModuleEvaluator<'a> {
             Expression::CallExpression(call) => {
                 self.eval_call_expression(&call.callee, &call.arguments, cache, stack)
             }
-            _ => Err(self.unsupported("unsupported expression")),
+            Expression::AwaitExpression(it) => {
+                self.eval_expression_to_value(&it.argument, cache, stack)
+            }
+            Expression::ImportExpression(it) => {
+                self.eval_module_request(&it.source, ModuleRequest::Import, cache, stack)
+            }
+            Expression::StaticMemberExpression(member) if !is_process_env(&member.object) => {
+                let object = self.eval_expression_to_value(&member.object, cache, stack)?;
+                object
+                    .get(member.property.name.as_str())
+                    .cloned()
+                    .ok_or_else(|| self.unsupported("unknown property"))
+            }
+            _ => self.eval_operator_expression(expr, cache, stack),
         }
     }
 
@@ -134,6 +142,9 @@ impl<'a> ModuleEvaluator<'a> {
                         .ok_or_else(|| self.unsupported("unary minus expects number"))
                 }),
             UnaryOperator::UnaryPlus => self.eval_expression_to_value(arg, cache, stack),
+            UnaryOperator::LogicalNot => self
+                .eval_expression_to_value(arg, cache, stack)
+                .map(|v| serde_json::Value::Bool(!is_truthy(&v))),
             _ => Err(self.unsupported("unsupported unary operator")),
         }
     }
@@ -179,8 +190,11 @@ impl<'a> ModuleEvaluator<'a> {
         let mut out = Vec::new();
         for el in &arr.elements {
             match el {
-                ArrayExpressionElement::SpreadElement(_) => {
-                    return Err(self.unsupported("unsupported array spread"));
+                ArrayExpressionElement::SpreadElement(it) => {
+                    match self.eval_expression_to_value(&it.argument, cache, stack)? {
+                        serde_json::Value::Array(items) => out.extend(items),
+                        _ => return Err(self.unsupported("spread expects array")),
+                    }
                 }
                 ArrayExpressionElement::Elision(_) => out.push(serde_json::Value::Null),
                 _ => {
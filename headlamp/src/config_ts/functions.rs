@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use oxc_ast::ast::{ArrowFunctionExpression, Expression, Function, FunctionBody, Statement};
+
+use crate::error::HeadlampError;
+
+use super::evaluator::ModuleEvaluator;
+use super::types::ModuleCacheEntry;
+
+impl<'a> ModuleEvaluator<'a> {
+    /// An exported config may be a function (`export default async () => ({ ... })`); jest
+    /// calls it, awaiting the result when async, so the config is what the function returns.
+    pub(super) fn eval_exported_config(
+        &mut self,
+        expr: &'a Expression<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        match expr {
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.eval_arrow_function(arrow, cache, stack)
+            }
+            Expression::FunctionExpression(func) => self.eval_function(func, cache, stack),
+            Expression::Identifier(ident) => {
+                self.eval_exported_identifier(ident.name.as_str(), cache, stack)
+            }
+            Expression::CallExpression(call) => match (&call.callee, call.arguments.first()) {
+                (Expression::Identifier(id), Some(arg)) if id.name == "defineConfig" => {
+                    match arg.as_expression() {
+                        Some(inner) => self.eval_exported_config(inner, cache, stack),
+                        None => Err(self.unsupported("unsupported argument")),
+                    }
+                }
+                _ => self.eval_expression_to_value(expr, cache, stack),
+            },
+            Expression::TSAsExpression(it) => {
+                self.eval_exported_config(&it.expression, cache, stack)
+            }
+            Expression::TSSatisfiesExpression(it) => {
+                self.eval_exported_config(&it.expression, cache, stack)
+            }
+            Expression::ParenthesizedExpression(it) => {
+                self.eval_exported_config(&it.expression, cache, stack)
+            }
+            _ => self.eval_expression_to_value(expr, cache, stack),
+        }
+    }
+
+    pub(super) fn eval_exported_identifier(
+        &mut self,
+        name: &str,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        if let Some(func) = self.function_decls.get(name).copied() {
+            return self.eval_function(func, cache, stack);
+        }
+        match self.const_inits.get(name).copied() {
+            Some(init) => self.eval_exported_config(init, cache, stack),
+            None => self.eval_identifier_value(name, cache, stack),
+        }
+    }
+
+    pub(super) fn eval_arrow_function(
+        &mut self,
+        arrow: &'a ArrowFunctionExpression<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        if !arrow.expression {
+            return self.eval_function_body(&arrow.body, cache, stack);
+        }
+        match arrow.body.statements.first() {
+            Some(Statement::ExpressionStatement(it)) => {
+                self.eval_expression_to_value(&it.expression, cache, stack)
+            }
+            _ => Err(self.unsupported("unsupported arrow function body")),
+        }
+    }
+
+    pub(super) fn eval_function(
+        &mut self,
+        func: &'a Function<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        let body = func
+            .body
+            .as_ref()
+            .ok_or_else(|| self.unsupported("config function has no body"))?;
+        self.eval_function_body(body, cache, stack)
+    }
+
+    /// Straight-line bodies only: locals are bound like module-level consts, and the first
+    /// top-level `return` gives the value.
+    fn eval_function_body(
+        &mut self,
+        body: &'a FunctionBody<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        for statement in &body.statements {
+            match statement {
+                Statement::VariableDeclaration(var) => self.collect_var_decl(var),
+                Statement::ReturnStatement(ret) => {
+                    return match &ret.argument {
+                        Some(arg) => self.eval_expression_to_value(arg, cache, stack),
+                        None => Ok(serde_json::Value::Null),
+                    };
+                }
+                _ => {}
+            }
+        }
+        Err(self.unsupported("config function does not return a value"))
+    }
+}
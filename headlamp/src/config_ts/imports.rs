@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use oxc_ast::ast::Expression;
+
+use crate::error::HeadlampError;
+
+use super::evaluator::ModuleEvaluator;
+use super::types::{ImportBinding, ModuleCacheEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ModuleRequest {
+    /// `import('./x')`: resolves to the module namespace.
+    Import,
+    /// `require('./x')`: resolves to `module.exports`, or the namespace for an ES module.
+    Require,
+}
+
+impl<'a> ModuleEvaluator<'a> {
+    pub(super) fn eval_import_binding(
+        &mut self,
+        binding: ImportBinding,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        match binding {
+            ImportBinding::Named { from, export } => {
+                super::load_module_exports(&from, cache, stack)?
+                    .exports
+                    .get(export.as_str())
+                    .cloned()
+                    .ok_or_else(|| self.unsupported("imported named export not found"))
+            }
+            ImportBinding::Default { from } => super::load_module_exports(&from, cache, stack)?
+                .default_export
+                .clone()
+                .ok_or_else(|| self.unsupported("imported default export not found")),
+            ImportBinding::Namespace { from } => {
+                let entry = super::load_module_exports(&from, cache, stack)?;
+                Ok(namespace_value(&entry))
+            }
+            ImportBinding::External { specifier } => Err(self.unsupported(&format!(
+                "cannot evaluate import from package {specifier:?}"
+            ))),
+        }
+    }
+
+    pub(super) fn eval_module_request(
+        &mut self,
+        source: &'a Expression<'a>,
+        request: ModuleRequest,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<serde_json::Value, HeadlampError> {
+        let Expression::StringLiteral(lit) = source else {
+            return Err(self.unsupported("module specifier must be a string literal"));
+        };
+        let spec = lit.value.as_str();
+        let Some(from) = self.resolve_import(spec)? else {
+            return Err(self.unsupported(&format!("cannot evaluate import from package {spec:?}")));
+        };
+        let entry = super::load_module_exports(&from, cache, stack)?;
+        Ok(match (request, &entry.default_export) {
+            (ModuleRequest::Require, Some(value)) if entry.exports.is_empty() => value.clone(),
+            _ => namespace_value(&entry),
+        })
+    }
+}
+
+fn namespace_value(entry: &ModuleCacheEntry) -> serde_json::Value {
+    let mut out = entry
+        .exports
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<serde_json::Map<_, _>>();
+    if let Some(value) = &entry.default_export {
+        out.insert("default".to_string(), value.clone());
+    }
+    serde_json::Value::Object(out)
+}
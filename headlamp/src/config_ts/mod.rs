@@ -10,11 +10,21 @@ use crate::error::HeadlampError;
 
 mod evaluator;
 mod expr;
+mod functions;
+mod imports;
+mod operators;
 mod resolver;
 mod types;
 mod utils;
 
 pub fn load_headlamp_config_ts_oxc(path: &Path) -> Result<serde_json::Value, HeadlampError> {
+    load_config_module_oxc(path)
+}
+
+/// Evaluates a JS/TS config module (`headlamp.config.ts`, `jest.config.mjs`, ...) to the value
+/// it exports: the default export, or `module.exports` for CommonJS. An exported function is
+/// called, and awaited when async, the way jest loads its config.
+pub(crate) fn load_config_module_oxc(path: &Path) -> Result<serde_json::Value, HeadlampError> {
     let mut cache: HashMap<PathBuf, Arc<types::ModuleCacheEntry>> = HashMap::new();
     let mut stack: Vec<PathBuf> = vec![];
     let entry = load_module_exports(path, &mut cache, &mut stack)?;
@@ -23,7 +33,7 @@ pub fn load_headlamp_config_ts_oxc(path: &Path) -> Result<serde_json::Value, Hea
         .clone()
         .ok_or_else(|| HeadlampError::ConfigParse {
             path: path.to_path_buf(),
-            message: "headlamp.config.ts: missing default export".to_string(),
+            message: format!("{}: missing default export", utils::config_label(path)),
         })
 }
 
@@ -38,8 +48,11 @@ fn load_module_exports(
     }
     if stack.contains(&canonical) {
         return Err(HeadlampError::ConfigParse {
+            message: format!(
+                "{}: cyclic import in config evaluation",
+                utils::config_label(&canonical)
+            ),
             path: canonical,
-            message: "headlamp.config.ts: cyclic import in config evaluation".to_string(),
         });
     }
     stack.push(canonical.clone());
@@ -61,7 +74,10 @@ fn load_module_exports(
             .join("\n");
         return Err(HeadlampError::ConfigParse {
             path: canonical.clone(),
-            message: format!("headlamp.config.ts: parse errors\n{message}"),
+            message: format!(
+                "{}: parse errors\n{message}",
+                utils::config_label(&canonical)
+            ),
         });
     }
 
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use oxc_ast::ast::{Argument, BinaryOperator, Expression, LogicalOperator, TemplateLiteral};
+use serde_json::Value;
+
+use crate::error::HeadlampError;
+
+use super::evaluator::ModuleEvaluator;
+use super::types::ModuleCacheEntry;
+
+impl<'a> ModuleEvaluator<'a> {
+    /// Ternaries, `||` / `&&` / `??`, equality, template literals and `process.env` reads:
+    /// what a config needs to pick values per environment.
+    pub(super) fn eval_operator_expression(
+        &mut self,
+        expr: &'a Expression<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Value, HeadlampError> {
+        match expr {
+            Expression::ConditionalExpression(it) => {
+                let test = self.eval_expression_to_value(&it.test, cache, stack)?;
+                let branch = if is_truthy(&test) {
+                    &it.consequent
+                } else {
+                    &it.alternate
+                };
+                self.eval_expression_to_value(branch, cache, stack)
+            }
+            Expression::LogicalExpression(it) => {
+                let left = self.eval_expression_to_value(&it.left, cache, stack)?;
+                let keep_left = match it.operator {
+                    LogicalOperator::Or => is_truthy(&left),
+                    LogicalOperator::And => !is_truthy(&left),
+                    LogicalOperator::Coalesce => !left.is_null(),
+                };
+                match keep_left {
+                    true => Ok(left),
+                    false => self.eval_expression_to_value(&it.right, cache, stack),
+                }
+            }
+            Expression::BinaryExpression(it) => {
+                let negate = match it.operator {
+                    BinaryOperator::StrictEquality | BinaryOperator::Equality => false,
+                    BinaryOperator::StrictInequality | BinaryOperator::Inequality => true,
+                    _ => return Err(self.unsupported("unsupported binary operator")),
+                };
+                let left = self.eval_expression_to_value(&it.left, cache, stack)?;
+                let right = self.eval_expression_to_value(&it.right, cache, stack)?;
+                Ok(Value::Bool(loosely_equal(&left, &right) != negate))
+            }
+            Expression::TemplateLiteral(lit) => self.eval_template_literal(lit, cache, stack),
+            Expression::StaticMemberExpression(member) if is_process_env(&member.object) => {
+                Ok(env_value(member.property.name.as_str()))
+            }
+            Expression::ComputedMemberExpression(member) if is_process_env(&member.object) => {
+                match self.eval_expression_to_value(&member.expression, cache, stack)? {
+                    Value::String(name) => Ok(env_value(&name)),
+                    _ => Err(self.unsupported("process.env expects a string key")),
+                }
+            }
+            _ => Err(self.unsupported("unsupported expression")),
+        }
+    }
+
+    fn eval_template_literal(
+        &mut self,
+        lit: &'a TemplateLiteral<'a>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Value, HeadlampError> {
+        let mut out = String::new();
+        for (index, quasi) in lit.quasis.iter().enumerate() {
+            let cooked = quasi
+                .value
+                .cooked
+                .as_ref()
+                .ok_or_else(|| self.unsupported("unsupported template literal"))?;
+            out.push_str(cooked);
+            if let Some(expr) = lit.expressions.get(index) {
+                match self.eval_expression_to_value(expr, cache, stack)? {
+                    Value::String(text) => out.push_str(&text),
+                    Value::Array(_) | Value::Object(_) => {
+                        return Err(self.unsupported("template literal expects a primitive"));
+                    }
+                    other => out.push_str(&other.to_string()),
+                }
+            }
+        }
+        Ok(Value::String(out))
+    }
+
+    /// `JSON.parse("...")` on a string the evaluator can produce (a literal, a template).
+    pub(super) fn eval_json_parse(
+        &mut self,
+        args: &'a oxc_allocator::Vec<'a, Argument<'a>>,
+        cache: &mut HashMap<PathBuf, Arc<ModuleCacheEntry>>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Value, HeadlampError> {
+        let first = args
+            .first()
+            .ok_or_else(|| self.unsupported("JSON.parse requires arg"))?;
+        match self.eval_argument_to_value(first, cache, stack)? {
+            Value::String(text) => serde_json::from_str(&text)
+                .map_err(|err| self.unsupported(&format!("JSON.parse failed: {err}"))),
+            _ => Err(self.unsupported("JSON.parse expects a string")),
+        }
+    }
+}
+
+pub(super) fn is_static_call(callee: &Expression<'_>, object: &str, property: &str) -> bool {
+    matches!(
+        callee,
+        Expression::StaticMemberExpression(member)
+            if member.property.name == property
+                && matches!(&member.object, Expression::Identifier(id) if id.name == object)
+    )
+}
+
+pub(super) fn is_process_env(object: &Expression<'_>) -> bool {
+    matches!(
+        object,
+        Expression::StaticMemberExpression(member)
+            if member.property.name == "env"
+                && matches!(&member.object, Expression::Identifier(id) if id.name == "process")
+    )
+}
+
+/// An unset variable reads as `null`, standing in for `undefined`, so `??` and `||` fall back.
+fn env_value(name: &str) -> Value {
+    crate::process::env::run_var(name).map_or(Value::Null, Value::String)
+}
+
+pub(super) fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(flag) => *flag,
+        Value::Number(number) => number.as_f64().is_some_and(|n| n != 0.0 && !n.is_nan()),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// Numbers compare by value (`1 === 1.0`); everything else structurally.
+fn loosely_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
@@ -9,6 +9,18 @@ pub(super) struct ModuleCacheEntry {
 
 #[derive(Debug, Clone)]
 pub(super) enum ImportBinding {
-    Named { from: PathBuf, export: String },
-    Default { from: PathBuf },
+    Named {
+        from: PathBuf,
+        export: String,
+    },
+    Default {
+        from: PathBuf,
+    },
+    Namespace {
+        from: PathBuf,
+    },
+    /// A bare package import; only an error if the config actually reads it.
+    External {
+        specifier: String,
+    },
 }
@@ -1,4 +1,6 @@
-use oxc_ast::ast::ModuleExportName;
+use std::path::Path;
+
+use oxc_ast::ast::{AssignmentTarget, Expression, ModuleExportName};
 
 pub(super) fn module_export_name_as_str<'a>(name: &ModuleExportName<'a>) -> Option<&'a str> {
     match name {
@@ -24,3 +26,26 @@ pub(super) fn json_number_from_f64(value: f64) -> serde_json::Value {
         .map(serde_json::Value::Number)
         .unwrap_or(serde_json::Value::Null)
 }
+
+/// The file name used to prefix evaluation errors, e.g. `jest.config.ts`.
+pub(super) fn config_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string())
+}
+
+/// The right-hand side of a CommonJS `module.exports = <expr>` statement.
+pub(super) fn module_exports_assignment<'a>(
+    expr: &'a Expression<'a>,
+) -> Option<&'a Expression<'a>> {
+    let Expression::AssignmentExpression(assign) = expr else {
+        return None;
+    };
+    let AssignmentTarget::StaticMemberExpression(member) = &assign.left else {
+        return None;
+    };
+    let Expression::Identifier(object) = &member.object else {
+        return None;
+    };
+    (object.name == "module" && member.property.name == "exports").then_some(&assign.right)
+}
@@ -0,0 +1,160 @@
+//! Reuse of the last coverage report when nothing it depends on changed. The key hashes the
+//! repo's sources by path, size and mtime (the inputs of every build, so unchanged sources mean
+//! unchanged artifacts), the runner and the arguments that decide what runs and is measured: the
+//! selection, runner args, build options and coverage options. Output-only flags are left out.
+//! On a hit the runner runs without instrumentation and the cached report is printed, marked
+//! `(cached)`. `--no-cache` always re-instruments.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha1::Digest as _;
+
+use crate::args::ParsedArgs;
+use crate::config::CoverageUi;
+use crate::coverage::model::CoverageReport;
+use crate::runner::Runner;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCoverage {
+    key: String,
+    report: CoverageReport,
+}
+
+/// Only runs that print headlamp's own coverage table are cached.
+pub fn applies(args: &ParsedArgs) -> bool {
+    args.collect_coverage && !args.no_cache && args.coverage_ui != CoverageUi::Jest
+}
+
+pub fn cache_key(runner: Runner, repo_root: &Path, args: &ParsedArgs) -> String {
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(runner.label().as_bytes());
+    hasher.update(measured_inputs(args).as_bytes());
+    let mut sources = crate::watch::walk_watch_paths(repo_root)
+        .filter(|path| !is_coverage_output(path))
+        .collect::<Vec<_>>();
+    sources.sort();
+    sources.iter().for_each(|path| {
+        hasher.update(path.to_string_lossy().as_bytes());
+        if let Ok(meta) = std::fs::metadata(path) {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|since| since.as_nanos())
+                .unwrap_or(0);
+            hasher.update(meta.len().to_le_bytes());
+            hasher.update(modified.to_le_bytes());
+        }
+    });
+    hex::encode(hasher.finalize())
+}
+
+/// The arguments that change which tests run, how they are built or what coverage measures.
+fn measured_inputs(args: &ParsedArgs) -> String {
+    let selection = (
+        &args.selection_paths,
+        &args.changed,
+        args.changed_depth,
+        &args.rerun_failed,
+        args.bail,
+        &args.runner_args,
+    );
+    let build = (
+        (&args.projects, &args.ignore_projects),
+        (&args.packages, &args.exclude_packages),
+        (&args.features, args.all_features, args.no_default_features),
+        (&args.target, &args.cargo_profile, &args.nextest_profile),
+        (&args.python, &args.node, &args.env_vars, &args.env_files),
+    );
+    let coverage = (
+        args.coverage_engine,
+        args.coverage_provider,
+        &args.coverage_scope,
+        &args.include_globs,
+        &args.exclude_globs,
+    );
+    format!("{selection:?}{build:?}{coverage:?}")
+}
+
+/// Files the coverage run itself writes into the repo, which must not change the key.
+fn is_coverage_output(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    name.starts_with(".coverage")
+        || name.ends_with(".profraw")
+        || name.ends_with(".profdata")
+        || path.components().any(|component| {
+            matches!(
+                component.as_os_str().to_str(),
+                Some("__pycache__" | ".pytest_cache" | "htmlcov")
+            )
+        })
+}
+
+fn cache_path(cache_root: &Path, repo_root: &Path, runner: Runner) -> PathBuf {
+    cache_root
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("coverage-cache")
+        .join(format!("{}.json", runner.label()))
+}
+
+/// `cache_root` is the run's (see `RunEnvironment::cache_root`).
+pub fn load(
+    cache_root: &Path,
+    repo_root: &Path,
+    runner: Runner,
+    key: &str,
+) -> Option<CoverageReport> {
+    let bytes = std::fs::read(cache_path(cache_root, repo_root, runner)).ok()?;
+    let cached = serde_json::from_slice::<CachedCoverage>(&bytes).ok()?;
+    (cached.key == key).then_some(cached.report)
+}
+
+/// Best-effort, like the last-run coverage: a failed write only costs the next run a re-run.
+pub fn store(
+    cache_root: &Path,
+    repo_root: &Path,
+    runner: Runner,
+    key: &str,
+    report: CoverageReport,
+) {
+    let path = cache_path(cache_root, repo_root, runner);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let cached = CachedCoverage {
+        key: key.to_string(),
+        report,
+    };
+    if let Ok(json) = serde_json::to_vec(&cached) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Prints and records the cached report as the run's own would be; thresholds still apply.
+pub fn replay(
+    runner: Runner,
+    repo_root: &Path,
+    args: &ParsedArgs,
+    report: CoverageReport,
+    exit_code: i32,
+) -> i32 {
+    crate::coverage::last_run::save_last_run_coverage(repo_root, last_run_source(runner), &report);
+    crate::format::report_out::println(crate::format::ansi::dim(
+        "Coverage (cached): sources and test selection unchanged since the last coverage run",
+    ));
+    crate::coverage::print::print_run_coverage(repo_root, args, report, exit_code)
+}
+
+/// The last-run coverage source the runner itself saves under.
+fn last_run_source(runner: Runner) -> &'static str {
+    match runner {
+        Runner::Jest => "jest",
+        Runner::Pytest => "pytest",
+        Runner::Headlamp | Runner::CargoTest | Runner::CargoNextest => "rust",
+        Runner::Plugin(name) => name,
+    }
+}
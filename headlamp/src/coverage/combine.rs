@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::coverage::lcov::{merge_reports, read_lcov_file};
+use crate::coverage::model::{CoverageReport, FileCoverage, apply_exclusion_pragmas};
+use crate::coverage::print::{
+    PrintOpts, filter_report, render_report_text, should_render_hotspots,
+};
+use crate::coverage::thresholds::{
+    compute_totals_from_report, print_threshold_failure_summary, threshold_failure_lines,
+};
+
+/// `headlamp coverage combine [lcov...]`: one table and one threshold check over every runner's
+/// cached coverage plus any extra lcov files, e.g. a frontend and a backend in the same repo.
+pub fn run_coverage_combine(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    opts: &PrintOpts,
+    lcov_paths: &[PathBuf],
+) -> i32 {
+    let mut sources = super::last_run::load_last_run_sources(repo_root);
+    for path in lcov_paths {
+        match read_lcov_file(path) {
+            Ok(report) => sources.push((
+                label_for(path, repo_root),
+                resolve_relative_sources(report, &lcov_project_dir(path)),
+            )),
+            Err(err) => {
+                eprintln!("headlamp: {err}");
+                return 1;
+            }
+        }
+    }
+    if sources.is_empty() {
+        eprintln!(
+            "headlamp: no coverage to combine; run each runner with --coverage first or pass lcov files"
+        );
+        return 1;
+    }
+    let labels = sources
+        .iter()
+        .map(|(label, _)| label.as_str())
+        .collect::<Vec<_>>();
+    crate::format::report_out::println(format!("Combined coverage from: {}", labels.join(", ")));
+    let reports = sources
+        .into_iter()
+        .map(|(_, report)| report)
+        .collect::<Vec<_>>();
+    let combined = combine_reports(
+        repo_root,
+        &reports,
+        &args.include_globs,
+        &args.exclude_globs,
+    );
+    let include_hotspots = should_render_hotspots(args.coverage_detail);
+    crate::format::report_out::println(render_report_text(
+        &combined,
+        opts,
+        repo_root,
+        include_hotspots,
+    ));
+    let failures = args.coverage_thresholds.as_ref().map(|thresholds| {
+        threshold_failure_lines(thresholds, compute_totals_from_report(&combined))
+    });
+    match failures {
+        Some(lines) if !lines.is_empty() => {
+            print_threshold_failure_summary(&lines);
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Merges per-runner reports into one keyed by repo path; a file reported by several runners
+/// has its hits summed.
+pub fn combine_reports(
+    repo_root: &Path,
+    reports: &[CoverageReport],
+    includes: &[String],
+    excludes: &[String],
+) -> CoverageReport {
+    let combined = filter_report(
+        merge_reports(reports, repo_root),
+        repo_root,
+        includes,
+        excludes,
+    );
+    apply_exclusion_pragmas(combined, repo_root)
+}
+
+/// Relative `SF:` entries are relative to the project that wrote the lcov, which in a polyglot
+/// repo is usually a subdirectory rather than the repo root.
+pub fn resolve_relative_sources(report: CoverageReport, project_dir: &Path) -> CoverageReport {
+    let files = report
+        .files
+        .into_iter()
+        .map(|file| {
+            let candidate = project_dir.join(&file.path);
+            if Path::new(&file.path).is_relative() && candidate.exists() {
+                FileCoverage {
+                    path: candidate.to_string_lossy().to_string(),
+                    ..file
+                }
+            } else {
+                file
+            }
+        })
+        .collect();
+    CoverageReport { files }
+}
+
+/// `frontend/coverage/lcov.info` belongs to `frontend/`.
+fn lcov_project_dir(lcov_path: &Path) -> PathBuf {
+    let parent = lcov_path.parent().unwrap_or(Path::new("."));
+    match parent.file_name() {
+        Some(name) if name == "coverage" => parent.parent().unwrap_or(parent).to_path_buf(),
+        _ => parent.to_path_buf(),
+    }
+}
+
+fn label_for(path: &Path, repo_root: &Path) -> String {
+    path.strip_prefix(repo_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::coverage::print::PrintOpts;
+
+/// `headlamp coverage <show|combine|trend> ...` works from cached coverage instead of running
+/// tests. Returns `None` when `argv` is an ordinary test run.
+pub fn run_coverage_command(argv: &[String]) -> Option<i32> {
+    let [first, subcommand, rest @ ..] = argv else {
+        return None;
+    };
+    if first != "coverage" || !matches!(subcommand.as_str(), "show" | "combine" | "trend") {
+        return None;
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = crate::config::find_repo_root(&cwd);
+    if subcommand == "trend" {
+        return Some(super::trend::run_coverage_trend(&repo_root, rest));
+    }
+    let (flags, positionals) = split_positionals(rest);
+    let args = parsed_args_for_flags(&repo_root, &flags);
+    let opts = PrintOpts::for_run(&args, crate::format::terminal::is_output_terminal());
+    let positionals = positionals
+        .iter()
+        .map(|p| resolve_from_cwd(&cwd, p))
+        .collect::<Vec<_>>();
+    Some(match subcommand.as_str() {
+        "show" => match positionals.as_slice() {
+            [file] => super::show::run_coverage_show(&repo_root, file, &opts),
+            _ => {
+                eprintln!("headlamp: usage: headlamp coverage show <file>");
+                2
+            }
+        },
+        _ => {
+            let lcov_paths = positionals.iter().map(PathBuf::from).collect::<Vec<_>>();
+            super::combine::run_coverage_combine(&repo_root, &args, &opts, &lcov_paths)
+        }
+    })
+}
+
+fn split_positionals(tokens: &[String]) -> (Vec<String>, Vec<String>) {
+    let (flags, rest) = crate::args::split_headlamp_tokens(tokens);
+    let positionals = rest
+        .into_iter()
+        .filter(|t| t != "--" && !t.starts_with('-'))
+        .collect();
+    (flags, positionals)
+}
+
+fn parsed_args_for_flags(repo_root: &Path, flags: &[String]) -> ParsedArgs {
+    let cfg = crate::config::validate::load_headlamp_config_checked(repo_root);
+    let cfg_tokens = crate::args::config_tokens(&cfg, flags);
+    let args = crate::args::derive_args(
+        &cfg_tokens,
+        flags,
+        crate::format::terminal::is_output_terminal(),
+    );
+    crate::coverage::native_thresholds::with_native_thresholds(repo_root, &args)
+}
+
+fn resolve_from_cwd(cwd: &Path, raw: &str) -> String {
+    let from_cwd = cwd.join(raw);
+    if from_cwd.exists() {
+        from_cwd.to_string_lossy().to_string()
+    } else {
+        raw.to_string()
+    }
+}
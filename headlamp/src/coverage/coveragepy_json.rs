@@ -1,8 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 
 use serde_json::Value;
 
+use crate::coverage::model::{CoverageReport, FileCoverage};
+
 pub fn read_repo_coveragepy_json_statement_totals(
     repo_root: &Path,
 ) -> Option<BTreeMap<String, (u32, u32)>> {
@@ -56,3 +58,142 @@ pub fn parse_coveragepy_json_statement_totals(
         .collect::<BTreeMap<_, _>>();
     Ok(totals_by_path)
 }
+
+pub fn read_coveragepy_json_report_from_path(
+    repo_root: &Path,
+    json_path: &Path,
+) -> Option<CoverageReport> {
+    let raw = std::fs::read_to_string(json_path).ok()?;
+    parse_coveragepy_json_report(&raw, repo_root).ok()
+}
+
+/// The whole `coverage json` report as headlamp's model: lines from `executed_lines` /
+/// `missing_lines`, branch arcs (`--cov-branch`) grouped by the line they leave, and the
+/// `functions` section coverage.py 7.5+ writes.
+pub fn parse_coveragepy_json_report(
+    text: &str,
+    repo_root: &Path,
+) -> Result<CoverageReport, String> {
+    let root: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let files_obj = root
+        .get("files")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "missing files object".to_string())?;
+    let files = files_obj
+        .iter()
+        .map(|(path, record)| {
+            file_coverage(
+                crate::coverage::lcov::normalize_lcov_path(path, repo_root),
+                record,
+            )
+        })
+        .collect::<Vec<_>>();
+    Ok(CoverageReport { files })
+}
+
+fn file_coverage(path: String, record: &Value) -> FileCoverage {
+    let executed = line_numbers(record.get("executed_lines"));
+    let missing = line_numbers(record.get("missing_lines"));
+    let line_hits = executed
+        .iter()
+        .map(|line| (*line, 1))
+        .chain(missing.iter().map(|line| (*line, 0)))
+        .collect::<BTreeMap<u32, u32>>();
+    let summary = record.get("summary");
+    let summary_count = |key: &str| {
+        summary
+            .and_then(|summary| summary.get(key))
+            .and_then(Value::as_u64)
+            .map(|count| count.min(u64::from(u32::MAX)) as u32)
+    };
+    let (function_hits, function_map) = function_maps(record.get("functions"));
+    let (branch_hits, branch_map) = branch_maps(record);
+    FileCoverage {
+        path,
+        lines_total: line_hits.len() as u32,
+        lines_covered: line_hits.values().filter(|hits| **hits > 0).count() as u32,
+        statements_total: summary_count("num_statements"),
+        statements_covered: summary_count("covered_lines"),
+        statement_hits: None,
+        uncovered_lines: missing.into_iter().collect(),
+        line_hits,
+        function_hits,
+        function_map,
+        branch_hits,
+        branch_map,
+    }
+}
+
+fn line_numbers(value: Option<&Value>) -> BTreeSet<u32> {
+    value
+        .and_then(Value::as_array)
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(Value::as_u64)
+                .map(|line| line.min(u64::from(u32::MAX)) as u32)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Each source line with arcs is one branch point (`line:0`); its paths are the arcs out of it
+/// in destination order, hit when coverage.py saw them executed.
+fn branch_maps(record: &Value) -> (BTreeMap<String, Vec<u32>>, BTreeMap<String, u32>) {
+    let arcs = |key: &str, hit: u32| {
+        record
+            .get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|arc| {
+                let from = arc.get(0)?.as_i64()?;
+                let to = arc.get(1)?.as_i64()?;
+                (from > 0).then_some((from as u32, to, hit))
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut paths_by_line = BTreeMap::<u32, BTreeMap<i64, u32>>::new();
+    arcs("executed_branches", 1)
+        .into_iter()
+        .chain(arcs("missing_branches", 0))
+        .for_each(|(from, to, hit)| {
+            paths_by_line.entry(from).or_default().insert(to, hit);
+        });
+    paths_by_line.into_iter().fold(
+        (BTreeMap::new(), BTreeMap::new()),
+        |(mut hits, mut lines), (line, paths)| {
+            let id = format!("{line}:0");
+            hits.insert(id.clone(), paths.into_values().collect());
+            lines.insert(id, line);
+            (hits, lines)
+        },
+    )
+}
+
+/// Named functions only (`""` is the module body); a function counts as hit when any of its
+/// lines ran, and starts at its first measured line.
+fn function_maps(
+    functions: Option<&Value>,
+) -> (BTreeMap<String, u32>, BTreeMap<String, (String, u32)>) {
+    functions
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| !name.is_empty())
+        .filter_map(|(name, record)| {
+            let executed = line_numbers(record.get("executed_lines"));
+            let missing = line_numbers(record.get("missing_lines"));
+            let start = executed.iter().chain(missing.iter()).min().copied()?;
+            let hits = u32::from(!executed.is_empty());
+            Some((format!("{start}:{name}"), hits, (name.clone(), start)))
+        })
+        .fold(
+            (BTreeMap::new(), BTreeMap::new()),
+            |(mut hits, mut map), (id, hit, meta)| {
+                hits.insert(id.clone(), hit);
+                map.insert(id, meta);
+                (hits, map)
+            },
+        )
+}
@@ -1,4 +1,6 @@
-use crate::coverage::coveragepy_json::parse_coveragepy_json_statement_totals;
+use crate::coverage::coveragepy_json::{
+    parse_coveragepy_json_report, parse_coveragepy_json_statement_totals,
+};
 
 #[test]
 fn parse_coveragepy_json_statement_totals_uses_num_statements_and_covered_lines() {
@@ -21,3 +23,42 @@ fn parse_coveragepy_json_statement_totals_uses_num_statements_and_covered_lines(
     assert_eq!(total, 10);
     assert_eq!(covered, 7);
 }
+
+#[test]
+fn parse_coveragepy_json_report_maps_lines_branches_and_functions() {
+    let input = r#"
+{
+  "files": {
+    "src/a.py": {
+      "executed_lines": [1, 2, 3, 5],
+      "missing_lines": [4],
+      "summary": { "num_statements": 5, "covered_lines": 4 },
+      "executed_branches": [[2, 3], [3, -1]],
+      "missing_branches": [[2, 4]],
+      "functions": {
+        "f": { "executed_lines": [2, 3], "missing_lines": [4] },
+        "unused": { "executed_lines": [], "missing_lines": [7] },
+        "": { "executed_lines": [1, 5], "missing_lines": [] }
+      }
+    }
+  }
+}
+"#;
+
+    let report = parse_coveragepy_json_report(input, std::path::Path::new("/repo"))
+        .expect("should parse coverage.py json");
+    let file = &report.files[0];
+    assert_eq!(file.path, "/repo/src/a.py");
+    assert_eq!((file.lines_covered, file.lines_total), (4, 5));
+    assert_eq!(file.uncovered_lines, vec![4]);
+    assert_eq!(
+        (file.statements_covered, file.statements_total),
+        (Some(4), Some(5))
+    );
+    assert_eq!(file.branch_counts(), (2, 3));
+    assert_eq!(file.branch_hits.get("2:0"), Some(&vec![1, 0]));
+    assert_eq!(file.branch_map.get("3:0"), Some(&3));
+    assert_eq!(file.function_hits.get("2:f"), Some(&1));
+    assert_eq!(file.function_hits.get("7:unused"), Some(&0));
+    assert_eq!(file.function_hits.len(), 2);
+}
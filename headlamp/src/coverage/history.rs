@@ -0,0 +1,111 @@
+//! Coverage history: every saved coverage run adds one summary (line and branch totals per
+//! package) to `coverage-history.jsonl` in the headlamp cache, which `headlamp coverage trend`
+//! reads. Scoped runs only summarize the packages they covered, so per-package series stay
+//! comparable.
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::coverage::model::CoverageReport;
+
+/// Older summaries are dropped once the file holds this many.
+const MAX_SUMMARIES: usize = 2000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageTotals {
+    pub lines_covered: u32,
+    pub lines_total: u32,
+    pub branches_covered: u32,
+    pub branches_total: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The last-run source that produced it (`jest`, `pytest`, `rust`).
+    pub source: String,
+    pub commit: Option<String>,
+    /// Keyed by repo-relative package dir; `.` for files outside any package.
+    pub packages: BTreeMap<String, PackageTotals>,
+}
+
+/// Under `cache_root`, normally `fast_related::default_cache_root()`.
+pub fn history_path(cache_root: &Path, repo_root: &Path) -> PathBuf {
+    cache_root
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("coverage-history.jsonl")
+}
+
+pub fn summarize(
+    repo_root: &Path,
+    source: &str,
+    report: &CoverageReport,
+    timestamp: u64,
+) -> CoverageSummary {
+    let mut packages = BTreeMap::<String, PackageTotals>::new();
+    report.files.iter().for_each(|file| {
+        let package = crate::coverage::scope::package_dir(repo_root, Path::new(&file.path))
+            .unwrap_or_else(|| ".".to_string());
+        let totals = packages.entry(package).or_default();
+        let (branches_covered, branches_total) = file.branch_counts();
+        totals.lines_covered += file.lines_covered;
+        totals.lines_total += file.lines_total;
+        totals.branches_covered += branches_covered;
+        totals.branches_total += branches_total;
+    });
+    CoverageSummary {
+        timestamp,
+        source: source.to_string(),
+        commit: crate::git::head_commit(repo_root),
+        packages,
+    }
+}
+
+/// Best-effort, like the last-run coverage it is saved with.
+pub fn record_summary(cache_root: &Path, repo_root: &Path, summary: &CoverageSummary) {
+    let path = history_path(cache_root, repo_root);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Ok(line) = serde_json::to_string(summary) else {
+        return;
+    };
+    let existing = load_history(cache_root, repo_root);
+    if existing.len() >= MAX_SUMMARIES {
+        let kept = existing[existing.len() + 1 - MAX_SUMMARIES..]
+            .iter()
+            .filter_map(|summary| serde_json::to_string(summary).ok())
+            .chain(std::iter::once(line))
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        let _ = std::fs::write(path, kept);
+        return;
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+/// Oldest first; unreadable lines are skipped.
+pub fn load_history(cache_root: &Path, repo_root: &Path) -> Vec<CoverageSummary> {
+    std::fs::read_to_string(history_path(cache_root, repo_root))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+pub fn now_unix_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
@@ -20,6 +20,14 @@ struct IstanbulStatementLoc {
     start: Option<IstanbulLoc>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct IstanbulBranchLoc {
+    #[serde(default)]
+    line: Option<u64>,
+    #[serde(default)]
+    loc: Option<IstanbulStatementLoc>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct IstanbulFileRecord {
     #[serde(default)]
@@ -34,8 +42,17 @@ struct IstanbulFileRecord {
     #[serde(default)]
     #[serde(rename = "statementMap")]
     statement_map: Option<BTreeMap<String, IstanbulStatementLoc>>,
+
+    #[serde(default)]
+    b: Option<BTreeMap<String, Vec<u64>>>,
+
+    #[serde(default)]
+    #[serde(rename = "branchMap")]
+    branch_map: Option<BTreeMap<String, IstanbulBranchLoc>>,
 }
 
+type BranchData = (BTreeMap<String, Vec<u32>>, BTreeMap<String, u32>);
+
 pub fn read_istanbul_coverage_file(path: &Path) -> Result<CoverageReport, HeadlampError> {
     let raw = std::fs::read_to_string(path).map_err(|source| HeadlampError::Io {
         path: path.to_path_buf(),
@@ -69,6 +86,7 @@ pub fn read_istanbul_coverage_tree(root: &Path) -> Vec<(PathBuf, CoverageReport)
 pub fn merge_istanbul_reports(reports: &[CoverageReport], root: &Path) -> CoverageReport {
     let mut by_file: BTreeMap<String, BTreeMap<u32, u32>> = BTreeMap::new();
     let mut statement_hits_by_file: BTreeMap<String, HashMap<u64, u32>> = BTreeMap::new();
+    let mut branches_by_file: BTreeMap<String, BranchData> = BTreeMap::new();
     for report in reports {
         for file in &report.files {
             let abs = super::lcov::normalize_lcov_path(&file.path, root);
@@ -86,6 +104,7 @@ pub fn merge_istanbul_reports(reports: &[CoverageReport], root: &Path) -> Covera
                         .insert(*statement_id, prev.saturating_add(*statement_hit_count));
                 }
             }
+            merge_branch_data(branches_by_file.entry(abs.clone()).or_default(), file);
         }
     }
 
@@ -114,6 +133,7 @@ pub fn merge_istanbul_reports(reports: &[CoverageReport], root: &Path) -> Covera
                     let covered = statement_hits.values().filter(|h| **h > 0).count() as u32;
                     (Some(total), Some(covered), Some(statement_hits))
                 });
+            let (branch_hits, branch_map) = branches_by_file.remove(&path).unwrap_or_default();
             FileCoverage {
                 path,
                 lines_total: total,
@@ -125,8 +145,8 @@ pub fn merge_istanbul_reports(reports: &[CoverageReport], root: &Path) -> Covera
                 line_hits: hits,
                 function_hits: BTreeMap::new(),
                 function_map: BTreeMap::new(),
-                branch_hits: BTreeMap::new(),
-                branch_map: BTreeMap::new(),
+                branch_hits,
+                branch_map,
             }
         })
         .collect::<Vec<_>>();
@@ -148,6 +168,7 @@ pub fn parse_istanbul_coverage_text(text: &str) -> Result<CoverageReport, String
             .to_string();
         let line_hits = extract_line_hits(&file_record)?;
         let statement_hits = extract_statement_hits(&file_record);
+        let (branch_hits, branch_map) = extract_branches(&file_record);
         let (statements_total, statements_covered) = statement_hits
             .as_ref()
             .map(|hits| {
@@ -183,8 +204,8 @@ pub fn parse_istanbul_coverage_text(text: &str) -> Result<CoverageReport, String
             line_hits,
             function_hits: BTreeMap::new(),
             function_map: BTreeMap::new(),
-            branch_hits: BTreeMap::new(),
-            branch_map: BTreeMap::new(),
+            branch_hits,
+            branch_map,
         });
     }
 
@@ -239,3 +260,49 @@ fn extract_statement_hits(file_record: &IstanbulFileRecord) -> Option<HashMap<u6
             .collect::<HashMap<_, _>>()
     })
 }
+
+fn extract_branches(file_record: &IstanbulFileRecord) -> BranchData {
+    let branch_hits = file_record
+        .b
+        .iter()
+        .flatten()
+        .map(|(id, hits)| {
+            let hits = hits
+                .iter()
+                .map(|h| (*h).min(u64::from(u32::MAX)) as u32)
+                .collect::<Vec<_>>();
+            (id.clone(), hits)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let branch_map = file_record
+        .branch_map
+        .iter()
+        .flatten()
+        .filter_map(|(id, meta)| {
+            let line = meta.line.or_else(|| {
+                meta.loc
+                    .as_ref()
+                    .and_then(|loc| loc.start.as_ref())
+                    .and_then(|start| start.line)
+            })?;
+            (line > 0).then(|| (id.clone(), line as u32))
+        })
+        .collect::<BTreeMap<_, _>>();
+    (branch_hits, branch_map)
+}
+
+/// Branch ids are stable per file across istanbul reports, so path counts add element-wise.
+fn merge_branch_data(target: &mut BranchData, file: &FileCoverage) {
+    let (hits_by_id, lines_by_id) = target;
+    for (id, hits) in &file.branch_hits {
+        let existing = hits_by_id.entry(id.clone()).or_default();
+        existing.resize(existing.len().max(hits.len()), 0);
+        existing
+            .iter_mut()
+            .zip(hits)
+            .for_each(|(total, hit)| *total = total.saturating_add(*hit));
+    }
+    for (id, line) in &file.branch_map {
+        lines_by_id.entry(id.clone()).or_insert(*line);
+    }
+}
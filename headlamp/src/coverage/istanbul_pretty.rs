@@ -11,6 +11,7 @@ mod table;
 
 pub use api::format_istanbul_pretty;
 pub use api::format_istanbul_pretty_from_lcov_report;
+pub use api::format_istanbul_text_from_report;
 
 #[cfg(test)]
 mod istanbul_text_test;
@@ -186,7 +186,7 @@ pub(super) fn missed_functions(file: &FullFileCoverage) -> Vec<MissedFunction> {
             }
         })
         .collect();
-    out.sort_by(|a, b| a.line.cmp(&b.line));
+    out.sort_by_key(|a| a.line);
     out
 }
 
@@ -207,7 +207,7 @@ pub(super) fn missed_branches(file: &FullFileCoverage) -> Vec<MissedBranch> {
             })
         })
         .collect();
-    out.sort_by(|a, b| a.line.cmp(&b.line));
+    out.sort_by_key(|a| a.line);
     out
 }
 
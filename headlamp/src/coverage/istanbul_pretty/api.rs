@@ -2,6 +2,7 @@ use std::collections::BTreeSet;
 use std::path::Path;
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use path_slash::PathExt;
 
 use crate::coverage::model::CoverageReport;
 use crate::coverage::print::PrintOpts;
@@ -26,6 +27,7 @@ pub fn format_istanbul_pretty(
 ) -> Option<String> {
     let merged = read_and_merge_coverage_final_json(coverage_root, repo_root)?;
     Some(render_pretty_output(
+        repo_root,
         merged,
         print_opts,
         selection_paths_abs,
@@ -47,6 +49,7 @@ pub fn format_istanbul_pretty_from_lcov_report(
     let files = lcov_report_to_full_file_coverage(repo_root, report);
 
     render_pretty_output(
+        repo_root,
         files,
         print_opts,
         selection_paths_abs,
@@ -56,6 +59,37 @@ pub fn format_istanbul_pretty_from_lcov_report(
     )
 }
 
+/// `--coverage-ui=jest` for runners whose coverage headlamp reads itself: only istanbul's `text`
+/// table and `text-summary`, the same report jest's default reporters print.
+pub fn format_istanbul_text_from_report(
+    repo_root: &Path,
+    report: CoverageReport,
+    print_opts: &PrintOpts,
+) -> String {
+    let root = repo_root.to_slash_lossy();
+    let files = lcov_report_to_full_file_coverage(repo_root, report)
+        .into_iter()
+        .filter(|file| Path::new(&file.abs_path).is_relative() || file.rel_path != file.abs_path)
+        .map(|mut file| {
+            file.rel_path = print_opts.path_display.display(&file.rel_path, &root);
+            file
+        })
+        .collect::<Vec<_>>();
+    let summaries = files
+        .iter()
+        .map(super::analysis::file_summary)
+        .collect::<Vec<_>>();
+    let (report, totals) = render_istanbul_text_report_with_totals_from_summaries(
+        &files,
+        &summaries,
+        detect_columns().min(60),
+    );
+    format!(
+        "{report}\n\n{}",
+        render_istanbul_text_summary_from_totals(totals)
+    )
+}
+
 pub(super) fn lcov_report_to_full_file_coverage(
     repo_root: &Path,
     report: CoverageReport,
@@ -65,12 +99,9 @@ pub(super) fn lcov_report_to_full_file_coverage(
         .into_iter()
         .map(|file| {
             let abs_path = file.path.replace('\\', "/");
-            let rel_path = Path::new(&abs_path)
-                .strip_prefix(repo_root)
-                .ok()
-                .and_then(|p| p.to_str())
-                .unwrap_or(abs_path.as_str())
-                .replace('\\', "/");
+            let rel_path = crate::format::paths::strip_root(Path::new(&abs_path), repo_root)
+                .map(|rel| rel.to_slash_lossy().to_string())
+                .unwrap_or_else(|| abs_path.clone());
 
             // LCOV doesn't have Istanbul statement ranges; keep this empty and fall back to parsing
             // statement IDs (or line hits) when computing hotspots.
@@ -92,6 +123,7 @@ pub(super) fn lcov_report_to_full_file_coverage(
 }
 
 fn render_pretty_output(
+    repo_root: &Path,
     mut files: Vec<super::model::FullFileCoverage>,
     print_opts: &PrintOpts,
     selection_paths_abs: &[String],
@@ -142,6 +174,11 @@ fn render_pretty_output(
             .is_some_and(|s| s.is_match(&file.rel_path));
         included && !excluded
     });
+    let root = repo_root.to_slash_lossy();
+    for file in &mut files {
+        file.rel_path = print_opts.path_display.display(&file.rel_path, &root);
+    }
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
 
     let separator = crate::format::ansi::gray(&"─".repeat(sep_len));
     let per_file_layout = build_per_file_table_layout(total_width);
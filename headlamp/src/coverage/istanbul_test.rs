@@ -64,3 +64,30 @@ fn merge_istanbul_reports_merges_statement_hits_by_id_instead_of_double_counting
     assert_eq!(file.statements_total, Some(2));
     assert_eq!(file.statements_covered, Some(2));
 }
+
+#[test]
+fn istanbul_branch_hits_are_parsed_and_merged_per_path() {
+    let input = |hits: &str| {
+        format!(
+            r#"{{
+  "/repo/src/a.ts": {{
+    "path": "/repo/src/a.ts",
+    "s": {{ "0": 1 }},
+    "statementMap": {{ "0": {{ "start": {{ "line": 7 }} }} }},
+    "b": {{ "0": {hits} }},
+    "branchMap": {{ "0": {{ "line": 7, "loc": {{ "start": {{ "line": 7 }} }} }} }}
+  }}
+}}"#
+        )
+    };
+    let report_a = parse_istanbul_coverage_text(&input("[1, 0]")).expect("should parse");
+    let report_b = parse_istanbul_coverage_text(&input("[0, 0]")).expect("should parse");
+    assert_eq!(report_a.files[0].branch_counts(), (1, 2));
+    assert_eq!(report_b.files[0].uncovered_branch_lines(), vec![7]);
+
+    let merged = merge_istanbul_reports(&[report_a, report_b], std::path::Path::new("/repo"));
+    let file = &merged.files[0];
+    assert_eq!(file.branch_counts(), (1, 2));
+    assert_eq!(file.branch_pct(), Some(50.0));
+    assert_eq!(file.uncovered_branch_lines(), vec![7]);
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::coverage::model::CoverageReport;
+
+/// Where each runner's most recent merged coverage is cached, so `headlamp coverage show` and
+/// `headlamp coverage combine` can read it after the run. It lives in the headlamp cache (not
+/// the run session), so it survives ephemeral runs.
+pub fn last_run_dir(repo_root: &Path) -> PathBuf {
+    crate::fast_related::default_cache_root()
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("last-coverage")
+}
+
+/// `source` names the language side that produced the report (`jest`, `pytest`, `rust`); a
+/// later run of the same side replaces it, other sides are kept.
+pub fn last_run_lcov_path(repo_root: &Path, source: &str) -> PathBuf {
+    last_run_dir(repo_root).join(format!("{source}.lcov"))
+}
+
+/// Best-effort: a failed write only means the coverage commands have less to read later. The
+/// run's per-package summary is appended to the coverage history too.
+pub fn save_last_run_coverage(repo_root: &Path, source: &str, report: &CoverageReport) {
+    let path = last_run_lcov_path(repo_root, source);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, report_to_lcov(report));
+    let summary = crate::coverage::history::summarize(
+        repo_root,
+        source,
+        report,
+        crate::coverage::history::now_unix_seconds(),
+    );
+    let cache_root = crate::fast_related::default_cache_root();
+    crate::coverage::history::record_summary(&cache_root, repo_root, &summary);
+}
+
+/// Every cached source, sorted by name.
+pub fn load_last_run_sources(repo_root: &Path) -> Vec<(String, CoverageReport)> {
+    let Ok(entries) = std::fs::read_dir(last_run_dir(repo_root)) else {
+        return vec![];
+    };
+    let mut sources = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lcov"))
+        .filter_map(|path| {
+            let source = path.file_stem()?.to_string_lossy().to_string();
+            let report = crate::coverage::lcov::read_lcov_file(&path).ok()?;
+            Some((source, report))
+        })
+        .collect::<Vec<_>>();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    sources
+}
+
+/// All cached sources merged into one report.
+pub fn load_last_run_coverage(repo_root: &Path) -> Option<CoverageReport> {
+    let reports = load_last_run_sources(repo_root)
+        .into_iter()
+        .map(|(_, report)| report)
+        .collect::<Vec<_>>();
+    (!reports.is_empty()).then(|| crate::coverage::lcov::merge_reports(&reports, repo_root))
+}
+
+/// Serializes line and branch hits; statement and function detail is not needed to annotate
+/// source.
+pub fn report_to_lcov(report: &CoverageReport) -> String {
+    let mut out = String::from("TN:\n");
+    for file in &report.files {
+        let _ = writeln!(out, "SF:{}", file.path);
+        for (line, hits) in &file.line_hits {
+            let _ = writeln!(out, "DA:{line},{hits}");
+        }
+        let branches = file
+            .branch_hits
+            .iter()
+            .filter_map(|(id, hits)| Some((*file.branch_map.get(id)?, hits)));
+        for (block, (line, hits)) in branches.enumerate() {
+            for (branch, taken) in hits.iter().enumerate() {
+                let _ = writeln!(out, "BRDA:{line},{block},{branch},{taken}");
+            }
+        }
+        out.push_str("end_of_record\n");
+    }
+    out
+}
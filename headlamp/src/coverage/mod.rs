@@ -1,12 +1,23 @@
+pub mod cache;
+pub mod combine;
+pub mod command;
 pub mod coveragepy_json;
+pub mod history;
 pub mod istanbul;
 pub mod istanbul_pretty;
+pub mod last_run;
 pub mod lcov;
 pub mod llvm_cov_json;
 pub mod model;
+pub mod native_thresholds;
+pub mod pragmas;
 pub mod print;
+pub mod scope;
+pub mod show;
 pub mod statement_id;
 pub mod thresholds;
+pub mod trend;
+pub mod v8;
 
 #[cfg(test)]
 mod coveragepy_json_test;
@@ -17,4 +28,8 @@ mod lcov_test;
 #[cfg(test)]
 mod llvm_cov_json_test;
 #[cfg(test)]
+mod pragmas_test;
+#[cfg(test)]
 mod thresholds_test;
+#[cfg(test)]
+mod v8_test;
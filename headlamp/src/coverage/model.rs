@@ -1,12 +1,19 @@
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+use crate::coverage::pragmas::{excluded_lines, has_exclusion_markers};
+use crate::coverage::statement_id::statement_id_line;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CoverageReport {
     pub files: Vec<FileCoverage>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileCoverage {
     pub path: String,
     pub lines_total: u32,
@@ -53,6 +60,20 @@ impl CoverageReport {
     }
 }
 
+impl CoverageReport {
+    /// `(covered, total)` branch paths across all files.
+    pub fn branch_counts(&self) -> (u32, u32) {
+        self.files.iter().fold((0u32, 0u32), |(covered, total), f| {
+            let (c, t) = f.branch_counts();
+            (covered.saturating_add(c), total.saturating_add(t))
+        })
+    }
+
+    pub fn has_branch_data(&self) -> bool {
+        self.files.iter().any(|f| !f.branch_hits.is_empty())
+    }
+}
+
 impl FileCoverage {
     pub fn pct(&self) -> f64 {
         Totals {
@@ -61,6 +82,42 @@ impl FileCoverage {
         }
         .pct()
     }
+
+    /// `(covered, total)` branch paths; each entry in `branch_hits` holds one count per path.
+    pub fn branch_counts(&self) -> (u32, u32) {
+        self.branch_hits
+            .values()
+            .fold((0u32, 0u32), |(covered, total), hits| {
+                let taken = hits.iter().filter(|h| **h > 0).count() as u32;
+                (
+                    covered.saturating_add(taken),
+                    total.saturating_add(hits.len() as u32),
+                )
+            })
+    }
+
+    pub fn branch_pct(&self) -> Option<f64> {
+        let (covered, total) = self.branch_counts();
+        (total > 0).then(|| {
+            Totals {
+                lines_total: total,
+                lines_covered: covered,
+            }
+            .pct()
+        })
+    }
+
+    /// Lines with at least one branch path that never ran, in ascending order.
+    pub fn uncovered_branch_lines(&self) -> Vec<u32> {
+        let lines = self
+            .branch_hits
+            .iter()
+            .filter(|(_, hits)| hits.contains(&0))
+            .filter_map(|(id, _)| self.branch_map.get(id).copied())
+            .filter(|line| *line > 0)
+            .collect::<std::collections::BTreeSet<_>>();
+        lines.into_iter().collect()
+    }
 }
 
 pub fn apply_statement_totals_to_report(
@@ -108,3 +165,78 @@ pub fn apply_statement_hits_to_report(
         .collect::<Vec<_>>();
     CoverageReport { files }
 }
+
+/// Drops the lines, functions, branches and statements that in-source pragmas exclude
+/// (`istanbul ignore next`, `# pragma: no cover`, `#[coverage(off)]`, ...), so totals and
+/// thresholds match what the native tools report. Files that cannot be read are kept as is.
+pub fn apply_exclusion_pragmas(report: CoverageReport, root: &Path) -> CoverageReport {
+    let files = report
+        .files
+        .into_iter()
+        .map(|file| {
+            let source = std::fs::read_to_string(root.join(&file.path)).unwrap_or_default();
+            if !has_exclusion_markers(&source) {
+                return file;
+            }
+            without_lines(file, &excluded_lines(&source))
+        })
+        .collect::<Vec<_>>();
+    CoverageReport { files }
+}
+
+fn without_lines(file: FileCoverage, excluded: &BTreeSet<u32>) -> FileCoverage {
+    if excluded.is_empty() {
+        return file;
+    }
+    let kept = |line: &u32| !excluded.contains(line);
+    let line_hits = file
+        .line_hits
+        .into_iter()
+        .filter(|(line, _)| kept(line))
+        .collect::<BTreeMap<_, _>>();
+    let function_map = file
+        .function_map
+        .into_iter()
+        .filter(|(_, (_, line))| kept(line))
+        .collect::<BTreeMap<_, _>>();
+    let branch_map = file
+        .branch_map
+        .into_iter()
+        .filter(|(_, line)| kept(line))
+        .collect::<BTreeMap<_, _>>();
+    let statement_hits = file.statement_hits.map(|hits| {
+        hits.into_iter()
+            .filter(|(id, _)| kept(&statement_id_line(*id)))
+            .collect::<HashMap<_, _>>()
+    });
+    let statement_counts = statement_hits.as_ref().map(|hits| {
+        let covered = hits.values().filter(|hit| **hit > 0).count() as u32;
+        (hits.len() as u32, covered)
+    });
+    FileCoverage {
+        lines_total: line_hits.len() as u32,
+        lines_covered: line_hits.values().filter(|hit| **hit > 0).count() as u32,
+        statements_total: statement_counts
+            .map(|(total, _)| total)
+            .or(file.statements_total),
+        statements_covered: statement_counts
+            .map(|(_, covered)| covered)
+            .or(file.statements_covered),
+        uncovered_lines: file.uncovered_lines.into_iter().filter(kept).collect(),
+        function_hits: file
+            .function_hits
+            .into_iter()
+            .filter(|(id, _)| function_map.contains_key(id))
+            .collect(),
+        branch_hits: file
+            .branch_hits
+            .into_iter()
+            .filter(|(id, _)| branch_map.contains_key(id))
+            .collect(),
+        line_hits,
+        function_map,
+        branch_map,
+        statement_hits,
+        ..file
+    }
+}
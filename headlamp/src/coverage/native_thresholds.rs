@@ -0,0 +1,135 @@
+//! `--coverage-native-thresholds`: the coverage thresholds a repo already declares for its own
+//! tools — jest's `coverageThreshold.global`, coverage.py's `fail_under` under
+//! `[tool.coverage.report]` in pyproject.toml, and `coverage.thresholds` under
+//! `[package.metadata.headlamp]` (or `[workspace.metadata.headlamp]`) in Cargo.toml — enforced
+//! alongside headlamp's own, so a team keeps them in one place.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::args::ParsedArgs;
+use crate::config::CoverageThresholds;
+
+/// `args` with the native thresholds filling in every metric headlamp's own leave unset; unchanged
+/// when the flag is off or the repo declares none.
+pub fn with_native_thresholds(repo_root: &Path, args: &ParsedArgs) -> ParsedArgs {
+    let mut merged = args.clone();
+    if args.coverage_native_thresholds {
+        merged.coverage_thresholds = merge_thresholds(
+            args.coverage_thresholds.as_ref(),
+            native_thresholds(repo_root),
+        );
+    }
+    merged
+}
+
+/// Every native source in `repo_root`, the strictest value winning per metric.
+pub fn native_thresholds(repo_root: &Path) -> Option<CoverageThresholds> {
+    [
+        jest_thresholds(repo_root),
+        coveragepy_thresholds(repo_root),
+        cargo_thresholds(repo_root),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(|a, b| CoverageThresholds {
+        lines: strictest(a.lines, b.lines),
+        functions: strictest(a.functions, b.functions),
+        branches: strictest(a.branches, b.branches),
+        statements: strictest(a.statements, b.statements),
+    })
+}
+
+/// Headlamp's own value wins for each metric it sets.
+pub fn merge_thresholds(
+    own: Option<&CoverageThresholds>,
+    native: Option<CoverageThresholds>,
+) -> Option<CoverageThresholds> {
+    match (own, native) {
+        (Some(own), Some(native)) => Some(CoverageThresholds {
+            lines: own.lines.or(native.lines),
+            functions: own.functions.or(native.functions),
+            branches: own.branches.or(native.branches),
+            statements: own.statements.or(native.statements),
+        }),
+        (own, native) => own.cloned().or(native),
+    }
+}
+
+fn strictest(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// `package.json`'s `jest` key, else the root jest config. Negative values (jest's "at most N
+/// uncovered") have no percentage to compare against and are skipped.
+fn jest_thresholds(repo_root: &Path) -> Option<CoverageThresholds> {
+    let from_package_json = std::fs::read_to_string(repo_root.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|package| package.get("jest").cloned());
+    let config = from_package_json.or_else(|| {
+        crate::jest_config::list_all_jest_configs(repo_root)
+            .first()
+            .and_then(|path| crate::jest_config::load_jest_config_value(path))
+    })?;
+    let global = config.get("coverageThreshold")?.get("global")?;
+    let metric = |name: &str| {
+        global
+            .get(name)
+            .and_then(Value::as_f64)
+            .filter(|value| *value >= 0.0)
+    };
+    thresholds_if_any(CoverageThresholds {
+        lines: metric("lines"),
+        functions: metric("functions"),
+        branches: metric("branches"),
+        statements: metric("statements"),
+    })
+}
+
+/// coverage.py's `fail_under` is a floor on its total, which is statement coverage.
+fn coveragepy_thresholds(repo_root: &Path) -> Option<CoverageThresholds> {
+    let pyproject = read_toml(&repo_root.join("pyproject.toml"))?;
+    let fail_under = pyproject
+        .get("tool")?
+        .get("coverage")?
+        .get("report")?
+        .get("fail_under")?;
+    let statements = fail_under
+        .as_float()
+        .or_else(|| fail_under.as_integer().map(|value| value as f64))?;
+    thresholds_if_any(CoverageThresholds {
+        statements: Some(statements),
+        ..CoverageThresholds::default()
+    })
+}
+
+fn cargo_thresholds(repo_root: &Path) -> Option<CoverageThresholds> {
+    let manifest = read_toml(&repo_root.join("Cargo.toml"))?;
+    ["package", "workspace"].into_iter().find_map(|section| {
+        let thresholds = manifest
+            .get(section)?
+            .get("metadata")?
+            .get("headlamp")?
+            .get("coverage")?
+            .get("thresholds")?;
+        serde_json::to_value(thresholds)
+            .ok()
+            .and_then(|value| serde_json::from_value::<CoverageThresholds>(value).ok())
+            .and_then(thresholds_if_any)
+    })
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str::<toml::Value>(&text).ok())
+}
+
+fn thresholds_if_any(thresholds: CoverageThresholds) -> Option<CoverageThresholds> {
+    (thresholds != CoverageThresholds::default()).then_some(thresholds)
+}
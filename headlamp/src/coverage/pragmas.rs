@@ -0,0 +1,152 @@
+//! In-source coverage exclusions, found by scanning the source of each covered file:
+//!
+//! - `/* istanbul ignore next */` (and `c8`/`v8` spellings): the next statement or block;
+//!   `istanbul ignore file` the whole file; `c8 ignore start` .. `c8 ignore stop` a range.
+//! - `# pragma: no cover`: the line, or the whole block when the line opens one (`def f():`).
+//! - `#[coverage(off)]`: the item it is attached to.
+//! - `// coverage: off` .. `// coverage: on` (or `#` comments): a range.
+//!
+//! The scan is line based and does not parse the language; it is what lets headlamp's tables
+//! agree with the native tools on lcov/llvm-cov data that ignores these markers.
+
+use std::collections::BTreeSet;
+
+const MARKERS: &[&str] = &[
+    "istanbul ignore",
+    "c8 ignore",
+    "v8 ignore",
+    "pragma: no cover",
+    "coverage(off)",
+    "coverage: off",
+];
+
+/// Cheap pre-check so files without any marker are never scanned line by line.
+pub fn has_exclusion_markers(source: &str) -> bool {
+    MARKERS.iter().any(|marker| source.contains(marker))
+}
+
+/// 1-based line numbers excluded from coverage.
+pub fn excluded_lines(source: &str) -> BTreeSet<u32> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut excluded = BTreeSet::new();
+    let mut range_start: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate() {
+        if is_file_ignore(line) {
+            return (1..=lines.len() as u32).collect();
+        }
+        if is_range_start(line) {
+            range_start.get_or_insert(index);
+        } else if is_range_stop(line) {
+            if let Some(start) = range_start.take() {
+                excluded.extend(line_numbers(start, index));
+            }
+        } else if is_next_ignore(line) || line.trim_start().starts_with("#[coverage(off)]") {
+            excluded.extend(next_item(&lines, index));
+        } else if line.contains("pragma: no cover") {
+            excluded.extend(python_block(&lines, index));
+        }
+    }
+    if let Some(start) = range_start {
+        excluded.extend(line_numbers(start, lines.len() - 1));
+    }
+    excluded
+}
+
+fn line_numbers(first: usize, last: usize) -> impl Iterator<Item = u32> {
+    (first..=last).map(|index| index as u32 + 1)
+}
+
+fn ignore_comment<'a>(line: &'a str, tools: &[&str]) -> Option<&'a str> {
+    tools.iter().find_map(|tool| {
+        let (_, rest) = line.split_once(&format!("{tool} ignore "))?;
+        rest.split_whitespace().next()
+    })
+}
+
+fn is_file_ignore(line: &str) -> bool {
+    ignore_comment(line, &["istanbul", "c8", "v8"]).is_some_and(|what| what == "file")
+}
+
+fn is_next_ignore(line: &str) -> bool {
+    ignore_comment(line, &["istanbul", "c8", "v8"]).is_some_and(|what| what == "next")
+}
+
+fn is_range_start(line: &str) -> bool {
+    ignore_comment(line, &["c8", "v8"]).is_some_and(|what| what == "start")
+        || is_coverage_comment(line, "off")
+}
+
+fn is_range_stop(line: &str) -> bool {
+    ignore_comment(line, &["c8", "v8"]).is_some_and(|what| what == "stop")
+        || is_coverage_comment(line, "on")
+}
+
+fn is_coverage_comment(line: &str, state: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["//", "#"].iter().any(|comment| {
+        trimmed
+            .strip_prefix(comment)
+            .map(|rest| rest.trim())
+            .is_some_and(|rest| rest == format!("coverage: {state}"))
+    })
+}
+
+/// The marker line and the statement after it: code on the marker line itself
+/// (`/* istanbul ignore next */ function f() {`), else the next line that is not blank, a
+/// comment or an attribute, through the end of its `{ .. }` block.
+fn next_item(lines: &[&str], marker: usize) -> Vec<u32> {
+    let code_on_marker = lines[marker]
+        .rsplit_once("*/")
+        .is_some_and(|(_, after)| !after.trim().is_empty());
+    let start = if code_on_marker {
+        Some(marker)
+    } else {
+        (marker + 1..lines.len()).find(|index| {
+            let trimmed = lines[*index].trim();
+            !trimmed.is_empty()
+                && !trimmed.starts_with("//")
+                && !trimmed.starts_with("/*")
+                && !trimmed.starts_with('*')
+                && !trimmed.starts_with("#[")
+        })
+    };
+    let Some(start) = start else {
+        return vec![marker as u32 + 1];
+    };
+    let mut depth = 0i64;
+    let mut opened = false;
+    let end = (start..lines.len())
+        .find(|index| {
+            lines[*index].chars().for_each(|ch| match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                _ => {}
+            });
+            depth <= 0 && (opened || !lines[*index].trim_end().ends_with(['(', ',', '=']))
+        })
+        .unwrap_or(lines.len() - 1);
+    line_numbers(marker, end).collect()
+}
+
+/// The pragma's line, plus the indented block under it when the code before the comment ends
+/// with `:`.
+fn python_block(lines: &[&str], marker: usize) -> Vec<u32> {
+    let line = lines[marker];
+    let code = line.split('#').next().unwrap_or("").trim_end();
+    if !code.ends_with(':') {
+        return vec![marker as u32 + 1];
+    }
+    let indent = |text: &str| text.len() - text.trim_start().len();
+    let base = indent(line);
+    let end = (marker + 1..lines.len())
+        .take_while(|index| {
+            let text = lines[*index];
+            text.trim().is_empty() || indent(text) > base
+        })
+        .last()
+        .unwrap_or(marker);
+    line_numbers(marker, end).collect()
+}
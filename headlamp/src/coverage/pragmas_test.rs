@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use crate::coverage::model::{CoverageReport, FileCoverage, apply_exclusion_pragmas};
+use crate::coverage::pragmas::{excluded_lines, has_exclusion_markers};
+
+fn lines(source: &str) -> Vec<u32> {
+    excluded_lines(source).into_iter().collect()
+}
+
+#[test]
+fn istanbul_ignore_next_excludes_the_following_block() {
+    let source = "\
+const a = 1;
+/* istanbul ignore next */
+function debugOnly() {
+  if (x) {
+    log();
+  }
+}
+const b = 2;
+";
+    assert_eq!(lines(source), vec![2, 3, 4, 5, 6, 7]);
+    assert_eq!(
+        lines("/* c8 ignore next */ const fallback = compute(\n  a,\n  b);\nconst c = 3;\n"),
+        vec![1, 2, 3]
+    );
+}
+
+#[test]
+fn ignore_file_and_start_stop_ranges() {
+    assert_eq!(
+        lines("/* istanbul ignore file */\na();\nb();\n"),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        lines("a();\n/* c8 ignore start */\nb();\n/* c8 ignore stop */\nc();\n"),
+        vec![2, 3, 4]
+    );
+    assert_eq!(
+        lines("fn a() {}\n// coverage: off\nfn b() {}\n// coverage: on\nfn c() {}\n"),
+        vec![2, 3, 4]
+    );
+}
+
+#[test]
+fn pragma_no_cover_excludes_a_line_or_the_block_it_opens() {
+    let source = "\
+def kept():
+    return 1
+
+def debug():  # pragma: no cover
+    print('x')
+
+    return 2
+value = compute()  # pragma: no cover
+other = 1
+";
+    assert_eq!(lines(source), vec![4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn coverage_off_attribute_excludes_the_item_after_other_attributes() {
+    let source = "\
+fn kept() {}
+
+#[coverage(off)]
+#[inline]
+fn excluded() {
+    body();
+}
+fn also_kept() {}
+";
+    assert_eq!(lines(source), vec![3, 4, 5, 6, 7]);
+    assert!(!has_exclusion_markers("fn plain() {}\n"));
+}
+
+#[test]
+fn exclusion_pragmas_drop_lines_functions_and_branches_from_the_report() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::write(
+        root.path().join("lib.js"),
+        "run();\n/* istanbul ignore next */\nfunction debug() {\n  log();\n}\n",
+    )
+    .unwrap();
+    let file = FileCoverage {
+        path: "lib.js".to_string(),
+        lines_total: 3,
+        lines_covered: 1,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines: vec![3, 4],
+        line_hits: BTreeMap::from([(1, 1), (3, 0), (4, 0)]),
+        function_hits: BTreeMap::from([("3:debug".to_string(), 0)]),
+        function_map: BTreeMap::from([("3:debug".to_string(), ("debug".to_string(), 3))]),
+        branch_hits: BTreeMap::from([("4:0".to_string(), vec![0, 0])]),
+        branch_map: BTreeMap::from([("4:0".to_string(), 4)]),
+    };
+    let report = apply_exclusion_pragmas(CoverageReport { files: vec![file] }, root.path());
+    let file = &report.files[0];
+    assert_eq!((file.lines_total, file.lines_covered), (1, 1));
+    assert!(file.uncovered_lines.is_empty());
+    assert!(file.function_hits.is_empty());
+    assert_eq!(file.branch_counts(), (0, 0));
+}
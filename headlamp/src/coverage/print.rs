@@ -6,6 +6,7 @@ use path_slash::PathExt;
 use crate::args::CoverageDetail;
 use crate::args::ParsedArgs;
 use crate::coverage::model::{CoverageReport, FileCoverage};
+use crate::format::path_display::PathDisplay;
 
 #[derive(Debug, Clone)]
 pub struct PrintOpts {
@@ -14,6 +15,8 @@ pub struct PrintOpts {
     pub page_fit: bool,
     pub tty: bool,
     pub editor_cmd: Option<String>,
+    /// `--path-style` / `--strip-path-prefix`, for the file column.
+    pub path_display: PathDisplay,
 }
 
 pub fn filter_report(
@@ -54,14 +57,32 @@ pub fn format_compact(report: &CoverageReport, opts: &PrintOpts, root: &Path) ->
     });
     let files = apply_max_files(files, opts.max_files);
 
-    let header = format!("{:<6}  {:<8}  {}", "%Lines", "Uncov", "File");
+    let with_branches = report.has_branch_data();
+    let header = if with_branches {
+        format!(
+            "{:<6}  {:<7}  {:<8}  {}",
+            "%Lines", "%Branch", "Uncov", "File"
+        )
+    } else {
+        format!("{:<6}  {:<8}  {}", "%Lines", "Uncov", "File")
+    };
     let mut lines = vec![header];
 
     for file in files {
-        let rel = path_rel_posix(&file.path, root);
+        let rel = display_path(&file.path, root, opts);
         let pct = file.pct();
         let uncov = file.lines_total.saturating_sub(file.lines_covered);
-        lines.push(format!("{:>5.1}%  {:>8}  {}", pct, uncov, rel));
+        if with_branches {
+            let branch_pct = file
+                .branch_pct()
+                .map_or_else(|| "-".to_string(), |p| format!("{p:.1}%"));
+            lines.push(format!(
+                "{:>5.1}%  {:>7}  {:>8}  {}",
+                pct, branch_pct, uncov, rel
+            ));
+        } else {
+            lines.push(format!("{:>5.1}%  {:>8}  {}", pct, uncov, rel));
+        }
     }
 
     lines.join("\n")
@@ -80,24 +101,33 @@ pub fn format_hotspots(report: &CoverageReport, opts: &PrintOpts, root: &Path) -
     let files = apply_max_files(files, opts.max_files);
 
     for file in files {
-        let rel = path_rel_posix(&file.path, root);
+        let rel = display_path(&file.path, root, opts);
         let uncovered = file
             .uncovered_lines
             .iter()
             .copied()
             .take(max)
             .collect::<Vec<_>>();
-        if uncovered.is_empty() {
-            continue;
+        if !uncovered.is_empty() {
+            let joined = uncovered
+                .into_iter()
+                .map(|ln| format_line_link(&file.path, ln, opts))
+                .collect::<Vec<_>>()
+                .join(", ");
+            out.push(format!("{rel}: {joined}"));
         }
-        let mut line = format!("{rel}: ");
-        let joined = uncovered
+        let branch_locations = file
+            .uncovered_branch_lines()
             .into_iter()
-            .map(|ln| format_line_link(&file.path, ln, opts))
-            .collect::<Vec<_>>()
-            .join(", ");
-        line.push_str(&joined);
-        out.push(line);
+            .take(max)
+            .map(|ln| format!("{rel}:{}", format_line_link(&file.path, ln, opts)))
+            .collect::<Vec<_>>();
+        if !branch_locations.is_empty() {
+            out.push(format!(
+                "  uncovered branches: {}",
+                branch_locations.join(", ")
+            ));
+        }
     }
 
     out.join("\n")
@@ -105,11 +135,23 @@ pub fn format_hotspots(report: &CoverageReport, opts: &PrintOpts, root: &Path) -
 
 pub fn format_summary(report: &CoverageReport) -> String {
     let totals = report.totals();
-    format!(
+    let lines = format!(
         "Lines: {:.1}% ({}/{})",
         totals.pct(),
         totals.lines_covered,
         totals.lines_total
+    );
+    if !report.has_branch_data() {
+        return lines;
+    }
+    let (covered, total) = report.branch_counts();
+    let branches = crate::coverage::model::Totals {
+        lines_total: total,
+        lines_covered: covered,
+    };
+    format!(
+        "{lines}  Branches: {:.1}% ({covered}/{total})",
+        branches.pct()
     )
 }
 
@@ -137,10 +179,43 @@ impl PrintOpts {
             page_fit: args.coverage_page_fit,
             tty: is_tty,
             editor_cmd: args.editor_cmd.clone(),
+            path_display: PathDisplay::for_args(args),
         }
     }
 }
 
+/// Records and prints `report` as a run's own coverage; a passing run whose coverage misses a
+/// threshold fails.
+pub fn print_run_coverage(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    report: CoverageReport,
+    exit_code: i32,
+) -> i32 {
+    crate::recorded::record_coverage(&report);
+    let print_opts = PrintOpts::for_run(args, crate::format::terminal::is_output_terminal());
+    let pretty = crate::coverage::istanbul_pretty::format_istanbul_pretty_from_lcov_report(
+        repo_root,
+        report.clone(),
+        &print_opts,
+        &[],
+        &args.include_globs,
+        &args.exclude_globs,
+        args.coverage_detail,
+    );
+    crate::coverage::scope::print_scope_label(args);
+    crate::format::report_out::println(crate::format::deterministic::finalize(pretty));
+    let thresholds_failed = crate::coverage::thresholds::compare_thresholds_and_print_if_needed(
+        args.coverage_thresholds.as_ref(),
+        Some(&report),
+    );
+    if exit_code == 0 && thresholds_failed {
+        1
+    } else {
+        exit_code
+    }
+}
+
 pub fn should_render_hotspots(detail: Option<CoverageDetail>) -> bool {
     detail.is_some_and(|d| d != CoverageDetail::Auto)
 }
@@ -167,17 +242,18 @@ fn build_globset(globs: &[String]) -> Option<GlobSet> {
     builder.build().ok()
 }
 
+fn display_path(abs_or_rel: &str, root: &Path, opts: &PrintOpts) -> String {
+    opts.path_display
+        .display(&path_rel_posix(abs_or_rel, root), &root.to_slash_lossy())
+}
+
 fn path_rel_posix(abs_or_rel: &str, root: &Path) -> String {
-    let p = Path::new(abs_or_rel);
-    let rel = p
-        .strip_prefix(root)
-        .ok()
-        .and_then(|x| x.to_str())
-        .unwrap_or(abs_or_rel);
-    Path::new(rel).to_slash_lossy().to_string()
+    crate::format::paths::strip_root(Path::new(abs_or_rel), root)
+        .map(|rel| rel.to_slash_lossy().to_string())
+        .unwrap_or_else(|| Path::new(abs_or_rel).to_slash_lossy().to_string())
 }
 
-fn format_line_link(file: &str, line: u32, opts: &PrintOpts) -> String {
+pub(super) fn format_line_link(file: &str, line: u32, opts: &PrintOpts) -> String {
     let label = format!("{line}");
     let Some(cmd) = opts
         .editor_cmd
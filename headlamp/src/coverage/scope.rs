@@ -0,0 +1,104 @@
+//! Coverage scoped to the code a run selected. With `--changed` or explicit selection paths,
+//! coverage is collected only for the packages those files belong to (the nearest
+//! `package.json`, `Cargo.toml`, `pyproject.toml` or `setup.py` above each file): jest gets
+//! `--collectCoverageFrom`, coverage.py `--cov=<package>`, and every report is restricted to
+//! those packages before it is printed, under a `Coverage scoped to:` line. A file outside any
+//! package, or in the root package, means the whole repo is in scope and nothing is scoped.
+
+use std::path::{Path, PathBuf};
+
+use crate::args::ParsedArgs;
+use crate::coverage::model::CoverageReport;
+
+const MANIFESTS: &[&str] = &["package.json", "Cargo.toml", "pyproject.toml", "setup.py"];
+
+/// `args` with `coverage_scope` set for this run; unchanged when coverage is off, nothing is
+/// selected, or the selection reaches the root package.
+pub fn scoped_args(repo_root: &Path, args: &ParsedArgs) -> ParsedArgs {
+    let mut scoped = args.clone();
+    if args.collect_coverage && args.coverage_scope.is_empty() {
+        scoped.coverage_scope = coverage_scope(repo_root, &selected_files(repo_root, args));
+    }
+    scoped
+}
+
+fn selected_files(repo_root: &Path, args: &ParsedArgs) -> Vec<PathBuf> {
+    let explicit = args
+        .selection_paths
+        .iter()
+        .filter(|_| args.selection_specified)
+        .map(|path| repo_root.join(path))
+        .filter(|path| path.exists());
+    let changed = args
+        .changed
+        .and_then(|mode| crate::git::changed_files(repo_root, mode).ok())
+        .unwrap_or_default();
+    explicit.chain(changed).collect()
+}
+
+/// The repo-relative package directories owning `files`, sorted; empty when any file belongs to
+/// no package below the root.
+pub fn coverage_scope(repo_root: &Path, files: &[PathBuf]) -> Vec<String> {
+    let mut packages = Vec::with_capacity(files.len());
+    for file in files {
+        match package_dir(repo_root, file) {
+            Some(dir) => packages.push(dir),
+            None => return vec![],
+        }
+    }
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+/// The repo-relative directory of the nearest manifest above `file`, below the root.
+pub fn package_dir(repo_root: &Path, file: &Path) -> Option<String> {
+    let file = repo_root.join(file);
+    let dir = file
+        .ancestors()
+        .skip(usize::from(!file.is_dir()))
+        .take_while(|dir| dir.starts_with(repo_root) && *dir != repo_root)
+        .find(|dir| MANIFESTS.iter().any(|name| dir.join(name).is_file()))?;
+    let rel = dir.strip_prefix(repo_root).ok()?;
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
+
+/// Drops the files outside the scoped packages; the report is unchanged when nothing is scoped.
+pub fn restrict_report(
+    report: CoverageReport,
+    repo_root: &Path,
+    scope: &[String],
+) -> CoverageReport {
+    if scope.is_empty() {
+        return report;
+    }
+    let in_scope = |path: &str| {
+        let path = repo_root.join(path);
+        scope
+            .iter()
+            .any(|dir| path.starts_with(repo_root.join(dir)))
+    };
+    CoverageReport {
+        files: report
+            .files
+            .into_iter()
+            .filter(|file| in_scope(&file.path))
+            .collect(),
+    }
+}
+
+/// `./<package>/**` globs for jest's `--collectCoverageFrom`.
+pub fn collect_coverage_from_globs(scope: &[String]) -> Vec<String> {
+    scope.iter().map(|dir| format!("./{dir}/**")).collect()
+}
+
+/// Printed above a scoped coverage table so partial numbers are not read as the repo's.
+pub fn print_scope_label(args: &ParsedArgs) {
+    if args.coverage_scope.is_empty() {
+        return;
+    }
+    crate::format::report_out::println(crate::format::ansi::dim(&format!(
+        "Coverage scoped to: {} (run without a selection for full coverage)",
+        args.coverage_scope.join(", ")
+    )));
+}
@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use path_slash::PathExt;
+
+use crate::coverage::model::{CoverageReport, FileCoverage};
+use crate::coverage::print::PrintOpts;
+use crate::format::ansi;
+
+/// `headlamp coverage show <file>`: prints one file's source annotated with hit counts from the
+/// last run's cached coverage.
+pub fn run_coverage_show(repo_root: &Path, file_arg: &str, opts: &PrintOpts) -> i32 {
+    let Some(report) = super::last_run::load_last_run_coverage(repo_root) else {
+        eprintln!(
+            "headlamp: no cached coverage for {}; run headlamp with --coverage first",
+            repo_root.to_string_lossy()
+        );
+        return 1;
+    };
+    match render_file_show(&report, file_arg, repo_root, opts) {
+        Ok(text) => {
+            println!("{text}");
+            0
+        }
+        Err(message) => {
+            eprintln!("headlamp: {message}");
+            1
+        }
+    }
+}
+
+pub fn render_file_show(
+    report: &CoverageReport,
+    file_arg: &str,
+    repo_root: &Path,
+    opts: &PrintOpts,
+) -> Result<String, String> {
+    let file = find_file(report, file_arg, repo_root)?;
+    let source = std::fs::read_to_string(&file.path)
+        .map_err(|err| format!("cannot read {}: {err}", file.path))?;
+    let rel = rel_label(&file.path, repo_root);
+    let mut out = vec![
+        ansi_if(opts.tty, ansi::bold, &rel),
+        format!(
+            "Lines: {:.1}% ({}/{})",
+            file.pct(),
+            file.lines_covered,
+            file.lines_total
+        ),
+    ];
+    let ranges = uncovered_ranges(&file.uncovered_lines);
+    if !ranges.is_empty() {
+        let links = ranges
+            .iter()
+            .map(|(start, end)| format_range(file, *start, *end, opts))
+            .collect::<Vec<_>>();
+        out.push(format!("Uncovered: {}", links.join(", ")));
+    }
+    let width = source.lines().count().to_string().len();
+    out.extend(source.lines().enumerate().map(|(index, text)| {
+        let line = index as u32 + 1;
+        annotate_line(file, line, text, width, opts.tty)
+    }));
+    Ok(out.join("\n"))
+}
+
+/// Accepts an absolute path, a path relative to the repo root, or a unique path suffix.
+fn find_file<'a>(
+    report: &'a CoverageReport,
+    file_arg: &str,
+    repo_root: &Path,
+) -> Result<&'a FileCoverage, String> {
+    let wanted = Path::new(file_arg)
+        .to_slash_lossy()
+        .trim_start_matches("./")
+        .to_string();
+    let abs = repo_root.join(&wanted).to_slash_lossy().to_string();
+    if let Some(exact) = report
+        .files
+        .iter()
+        .find(|f| f.path == wanted || f.path == abs)
+    {
+        return Ok(exact);
+    }
+    let suffix = format!("/{wanted}");
+    let matches = report
+        .files
+        .iter()
+        .filter(|f| f.path.ends_with(&suffix))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [only] => Ok(only),
+        [] => Err(format!("{file_arg} is not in the last run's coverage")),
+        many => Err(format!(
+            "{file_arg} matches several covered files: {}",
+            many.iter()
+                .map(|f| rel_label(&f.path, repo_root))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn annotate_line(file: &FileCoverage, line: u32, text: &str, width: usize, tty: bool) -> String {
+    let hits = file.line_hits.get(&line).copied();
+    let count = hits.map(|h| format!("{h}x")).unwrap_or_default();
+    let row = format!("{line:>width$} {count:>6} | {text}");
+    match hits {
+        Some(0) => ansi_if(tty, ansi::red, &format!("{row}  <- uncovered")),
+        _ => row,
+    }
+}
+
+/// Collapses sorted line numbers into inclusive `(start, end)` runs.
+pub fn uncovered_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
+    lines.iter().fold(Vec::new(), |mut ranges, line| {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *line => *end = *line,
+            _ => ranges.push((*line, *line)),
+        }
+        ranges
+    })
+}
+
+fn format_range(file: &FileCoverage, start: u32, end: u32, opts: &PrintOpts) -> String {
+    let start_link = super::print::format_line_link(&file.path, start, opts);
+    if start == end {
+        start_link
+    } else {
+        format!("{start_link}-{end}")
+    }
+}
+
+fn rel_label(path: &str, repo_root: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(repo_root)
+        .map(|rel| rel.to_slash_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn ansi_if(tty: bool, paint: fn(&str) -> String, text: &str) -> String {
+    if tty { paint(text) } else { text.to_string() }
+}
@@ -44,19 +44,7 @@ pub fn compute_totals_from_report(report: &CoverageReport) -> CoverageTotals {
         .map(|f| f.function_hits.values().filter(|h| **h > 0).count() as u32)
         .sum::<u32>();
 
-    let (branches_total, branches_covered) =
-        report
-            .files
-            .iter()
-            .fold((0u32, 0u32), |(total, covered), f| {
-                let (t, c) = f.branch_hits.values().fold((0u32, 0u32), |acc, hits| {
-                    let (t0, c0) = acc;
-                    let t1 = t0.saturating_add(hits.len() as u32);
-                    let c1 = c0.saturating_add(hits.iter().filter(|h| **h > 0).count() as u32);
-                    (t1, c1)
-                });
-                (total.saturating_add(t), covered.saturating_add(c))
-            });
+    let (branches_covered, branches_total) = report.branch_counts();
 
     let (statements_total, statements_covered) =
         report
@@ -120,9 +108,11 @@ pub fn threshold_failure_lines(
 }
 
 pub fn print_threshold_failure_summary(lines: &IndexSet<String>) {
-    println!();
-    println!("Coverage thresholds not met");
-    lines.iter().for_each(|line| println!(" {line}"));
+    crate::format::report_out::println("");
+    crate::format::report_out::println("Coverage thresholds not met");
+    lines
+        .iter()
+        .for_each(|line| crate::format::report_out::println(format!(" {line}")));
 }
 
 pub fn compare_thresholds_and_print_if_needed(
@@ -0,0 +1,247 @@
+//! `headlamp coverage trend [--since 30d] [--export <file.json|file.csv>]`: line and branch
+//! coverage per package over the recorded history (see `coverage::history`), as a sparkline
+//! with the first and latest percentage of the window.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::coverage::history::{CoverageSummary, PackageTotals};
+use crate::format::{ansi, colors};
+
+/// Points drawn per sparkline; older runs in the window still count for the first percentage.
+const SPARKLINE_POINTS: usize = 30;
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TrendOptions {
+    pub since_seconds: Option<u64>,
+    pub export: Option<String>,
+}
+
+/// One package in one run, as exported.
+#[derive(Debug, Serialize)]
+struct TrendRow<'a> {
+    timestamp: u64,
+    source: &'a str,
+    commit: Option<&'a str>,
+    package: &'a str,
+    lines_covered: u32,
+    lines_total: u32,
+    branches_covered: u32,
+    branches_total: u32,
+}
+
+pub fn parse_trend_options(tokens: &[String]) -> Result<TrendOptions, String> {
+    let mut options = TrendOptions::default();
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        let (flag, inline) = match token.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (token.as_str(), None),
+        };
+        let mut value = || inline.clone().or_else(|| tokens.next().cloned());
+        match flag {
+            "--since" => {
+                let raw = value().unwrap_or_default();
+                let seconds = parse_duration_seconds(&raw)
+                    .ok_or_else(|| format!("invalid --since value {raw:?} (try 30d, 12h or 2w)"))?;
+                options.since_seconds = Some(seconds);
+            }
+            "--export" => options.export = value().filter(|path| !path.is_empty()),
+            other => return Err(format!("unknown coverage trend argument {other:?}")),
+        }
+    }
+    Ok(options)
+}
+
+/// `30d`, `12h`, `2w`, or a bare number of days.
+pub fn parse_duration_seconds(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    let (count, unit) = match raw.char_indices().last()? {
+        (index, unit) if unit.is_ascii_alphabetic() => (&raw[..index], unit),
+        _ => (raw, 'd'),
+    };
+    let count = count.parse::<u64>().ok()?;
+    let unit_seconds = match unit.to_ascii_lowercase() {
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 604_800,
+        _ => return None,
+    };
+    Some(count * unit_seconds)
+}
+
+pub fn run_coverage_trend(repo_root: &Path, tokens: &[String]) -> i32 {
+    let options = match parse_trend_options(tokens) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("headlamp: {message}");
+            return 2;
+        }
+    };
+    let now = crate::coverage::history::now_unix_seconds();
+    let cache_root = crate::fast_related::default_cache_root();
+    let summaries = crate::coverage::history::load_history(&cache_root, repo_root)
+        .into_iter()
+        .filter(|summary| {
+            options
+                .since_seconds
+                .is_none_or(|since| summary.timestamp + since >= now)
+        })
+        .collect::<Vec<_>>();
+    if summaries.is_empty() {
+        eprintln!("headlamp: no coverage history in this window; runs with --coverage record it");
+        return 1;
+    }
+    println!("{}", render_trend(&summaries));
+    match options.export.as_deref() {
+        Some(path) => export_trend(&repo_root.join(path), &summaries),
+        None => 0,
+    }
+}
+
+/// A header, then per package: `api  Lines ▁▃▅█ 71.2% → 78.4% (+7.2)  Branches …`.
+pub fn render_trend(summaries: &[CoverageSummary]) -> String {
+    let mut series = std::collections::BTreeMap::<&str, Vec<&PackageTotals>>::new();
+    summaries.iter().for_each(|summary| {
+        summary
+            .packages
+            .iter()
+            .for_each(|(package, totals)| series.entry(package).or_default().push(totals));
+    });
+    let width = series.keys().map(|name| name.len()).max().unwrap_or(0);
+    let header = ansi::bold(&format!(
+        "Coverage trend: {} run{}",
+        summaries.len(),
+        if summaries.len() == 1 { "" } else { "s" }
+    ));
+    let rows = series.into_iter().map(|(package, points)| {
+        let lines = points
+            .iter()
+            .map(|totals| pct(totals.lines_covered, totals.lines_total))
+            .collect::<Vec<_>>();
+        let branches = points
+            .iter()
+            .filter(|totals| totals.branches_total > 0)
+            .map(|totals| pct(totals.branches_covered, totals.branches_total))
+            .collect::<Vec<_>>();
+        let mut row = format!("{package:<width$}  Lines {}", render_series(&lines));
+        if !branches.is_empty() {
+            row.push_str(&format!("  Branches {}", render_series(&branches)));
+        }
+        row
+    });
+    std::iter::once(header)
+        .chain(rows)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn pct(covered: u32, total: u32) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        f64::from(covered) * 100.0 / f64::from(total)
+    }
+}
+
+/// `▁▃▅█ 71.2% → 78.4% (+7.2)`; a single point is just its percentage.
+fn render_series(values: &[f64]) -> String {
+    let (Some(first), Some(last)) = (values.first(), values.last()) else {
+        return String::new();
+    };
+    let shown = &values[values.len().saturating_sub(SPARKLINE_POINTS)..];
+    if values.len() == 1 {
+        return format!("{} {last:.1}%", sparkline(shown));
+    }
+    let delta = last - first;
+    let delta_text = format!("({delta:+.1})");
+    let delta_text = if delta > 0.05 {
+        colors::success(&delta_text)
+    } else if delta < -0.05 {
+        colors::failure(&delta_text)
+    } else {
+        ansi::dim(&delta_text)
+    };
+    format!("{} {first:.1}% → {last:.1}% {delta_text}", sparkline(shown))
+}
+
+/// Scaled between the series' own low and high, so small drifts stay visible.
+pub fn sparkline(values: &[f64]) -> String {
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if (high - low).abs() < f64::EPSILON {
+                return SPARK_CHARS[SPARK_CHARS.len() / 2];
+            }
+            let level = ((value - low) / (high - low) * (SPARK_CHARS.len() - 1) as f64).round();
+            SPARK_CHARS[level as usize]
+        })
+        .collect()
+}
+
+fn trend_rows(summaries: &[CoverageSummary]) -> Vec<TrendRow<'_>> {
+    summaries
+        .iter()
+        .flat_map(|summary| {
+            summary.packages.iter().map(|(package, totals)| TrendRow {
+                timestamp: summary.timestamp,
+                source: &summary.source,
+                commit: summary.commit.as_deref(),
+                package,
+                lines_covered: totals.lines_covered,
+                lines_total: totals.lines_total,
+                branches_covered: totals.branches_covered,
+                branches_total: totals.branches_total,
+            })
+        })
+        .collect()
+}
+
+/// One row per package per run; CSV for a `.csv` path, JSON otherwise.
+pub fn export_trend(path: &Path, summaries: &[CoverageSummary]) -> i32 {
+    let rows = trend_rows(summaries);
+    let body = if path.extension().is_some_and(|ext| ext == "csv") {
+        trend_csv(&rows)
+    } else {
+        serde_json::to_string_pretty(&rows).unwrap_or_default()
+    };
+    match std::fs::write(path, body) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("headlamp: failed to write {}: {err}", path.display());
+            1
+        }
+    }
+}
+
+fn trend_csv(rows: &[TrendRow<'_>]) -> String {
+    let quote = |text: &str| {
+        if text.contains([',', '"', '\n']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    };
+    let header =
+        "timestamp,source,commit,package,lines_covered,lines_total,branches_covered,branches_total";
+    std::iter::once(header.to_string())
+        .chain(rows.iter().map(|row| {
+            format!(
+                "{},{},{},{},{},{},{},{}",
+                row.timestamp,
+                quote(row.source),
+                quote(row.commit.unwrap_or("")),
+                quote(row.package),
+                row.lines_covered,
+                row.lines_total,
+                row.branches_covered,
+                row.branches_total
+            )
+        }))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
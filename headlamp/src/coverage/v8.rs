@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::coverage::model::{CoverageReport, FileCoverage};
+
+/// One `coverage-*.json` file written by Node under `NODE_V8_COVERAGE`.
+#[derive(Debug, Clone, Deserialize)]
+struct V8Dump {
+    #[serde(default)]
+    result: Vec<V8ScriptCoverage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct V8ScriptCoverage {
+    url: String,
+    #[serde(default)]
+    functions: Vec<V8FunctionCoverage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V8FunctionCoverage {
+    #[serde(default)]
+    function_name: String,
+    #[serde(default)]
+    ranges: Vec<V8Range>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V8Range {
+    start_offset: usize,
+    end_offset: usize,
+    count: u32,
+}
+
+/// Reads every dump in `dir` and keeps scripts that are files under `repo_root` (outside
+/// `node_modules`). Hits from separate processes are summed per line.
+pub fn read_v8_coverage_dir(dir: &Path, repo_root: &Path) -> Option<CoverageReport> {
+    let mut entries = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    entries.sort();
+    let mut by_file: BTreeMap<PathBuf, FileCoverage> = BTreeMap::new();
+    for dump_path in entries {
+        let Some(dump) = std::fs::read_to_string(&dump_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<V8Dump>(&raw).ok())
+        else {
+            continue;
+        };
+        for script in dump.result {
+            let Some(path) = script_path_in_repo(&script.url, repo_root) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let file = script_to_file_coverage(&path, &source, &script.functions);
+            by_file
+                .entry(path)
+                .and_modify(|existing| merge_file_coverage(existing, &file))
+                .or_insert(file);
+        }
+    }
+    (!by_file.is_empty()).then(|| CoverageReport {
+        files: by_file.into_values().collect(),
+    })
+}
+
+/// Node reports `file://` URLs; scripts compiled through `vm` (as jest does) report plain paths.
+/// Only plain JS is kept: offsets for transpiled sources refer to the emitted code, which is
+/// not what is on disk, so those files come from jest's own source-mapped report instead.
+fn script_path_in_repo(url: &str, repo_root: &Path) -> Option<PathBuf> {
+    let path = PathBuf::from(url.strip_prefix("file://").unwrap_or(url));
+    let is_plain_js = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "js" | "mjs" | "cjs"));
+    let in_repo =
+        path.starts_with(repo_root) && !path.components().any(|c| c.as_os_str() == "node_modules");
+    (is_plain_js && in_repo).then_some(path)
+}
+
+/// A line's count is the count of the innermost range holding its first non-blank character;
+/// V8 nests block ranges inside their function's range. Blank lines are not instrumented.
+pub fn v8_line_hits(source: &str, ranges: &[(usize, usize, u32)]) -> BTreeMap<u32, u32> {
+    let mut hits = BTreeMap::new();
+    let mut offset = 0usize;
+    for (index, line) in source.split('\n').enumerate() {
+        let leading = line.len() - line.trim_start().len();
+        let first_char_offset = offset + utf16_len(&line[..leading]);
+        offset += utf16_len(line) + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        let innermost = ranges
+            .iter()
+            .filter(|(start, end, _)| *start <= first_char_offset && first_char_offset < *end)
+            .min_by_key(|(start, end, _)| end - start);
+        if let Some((_, _, count)) = innermost {
+            hits.insert(index as u32 + 1, *count);
+        }
+    }
+    hits
+}
+
+/// V8 offsets count UTF-16 code units, not bytes.
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+fn script_to_file_coverage(
+    path: &Path,
+    source: &str,
+    functions: &[V8FunctionCoverage],
+) -> FileCoverage {
+    let ranges = functions
+        .iter()
+        .flat_map(|f| f.ranges.iter())
+        .map(|r| (r.start_offset, r.end_offset, r.count))
+        .collect::<Vec<_>>();
+    let line_hits = v8_line_hits(source, &ranges);
+    let line_starts = line_start_offsets(source);
+    let mut function_hits = BTreeMap::new();
+    let mut function_map = BTreeMap::new();
+    for function in functions.iter().filter(|f| !f.function_name.is_empty()) {
+        let Some(range) = function.ranges.first() else {
+            continue;
+        };
+        let line = line_starts.partition_point(|start| *start <= range.start_offset) as u32;
+        let id = format!("{line}:{}", function.function_name);
+        function_hits.insert(id.clone(), range.count);
+        function_map.insert(id, (function.function_name.clone(), line));
+    }
+    build_file_coverage(
+        path.to_string_lossy().to_string(),
+        line_hits,
+        function_hits,
+        function_map,
+    )
+}
+
+fn line_start_offsets(source: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut offset = 0usize;
+    for line in source.split('\n') {
+        offset += utf16_len(line) + 1;
+        starts.push(offset);
+    }
+    starts.pop();
+    starts
+}
+
+fn build_file_coverage(
+    path: String,
+    line_hits: BTreeMap<u32, u32>,
+    function_hits: BTreeMap<String, u32>,
+    function_map: BTreeMap<String, (String, u32)>,
+) -> FileCoverage {
+    let uncovered_lines = line_hits
+        .iter()
+        .filter(|(_, hits)| **hits == 0)
+        .map(|(line, _)| *line)
+        .collect::<Vec<_>>();
+    FileCoverage {
+        path,
+        lines_total: line_hits.len() as u32,
+        lines_covered: line_hits.values().filter(|h| **h > 0).count() as u32,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines,
+        line_hits,
+        function_hits,
+        function_map,
+        branch_hits: BTreeMap::new(),
+        branch_map: BTreeMap::new(),
+    }
+}
+
+fn merge_file_coverage(existing: &mut FileCoverage, other: &FileCoverage) {
+    let mut line_hits = existing.line_hits.clone();
+    for (line, hits) in &other.line_hits {
+        let entry = line_hits.entry(*line).or_insert(0);
+        *entry = entry.saturating_add(*hits);
+    }
+    let mut function_hits = existing.function_hits.clone();
+    for (id, hits) in &other.function_hits {
+        let entry = function_hits.entry(id.clone()).or_insert(0);
+        *entry = entry.saturating_add(*hits);
+    }
+    let mut function_map = existing.function_map.clone();
+    function_map.extend(other.function_map.clone());
+    *existing = build_file_coverage(
+        existing.path.clone(),
+        line_hits,
+        function_hits,
+        function_map,
+    );
+}
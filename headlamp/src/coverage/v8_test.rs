@@ -0,0 +1,70 @@
+use crate::coverage::v8::{read_v8_coverage_dir, v8_line_hits};
+
+#[test]
+fn v8_line_hits_use_the_innermost_range_and_skip_blank_lines() {
+    let source = "function f(a) {\n  if (a) {\n    return 1;\n  }\n\n  return 2;\n}\nf(0);\n";
+    let if_block_start = source.find("{\n    return").unwrap();
+    let if_block_end = source.find("  }\n").unwrap() + 3;
+    let ranges = [
+        (0, source.len(), 1),
+        (0, source.find("\nf(0)").unwrap(), 1),
+        (if_block_start, if_block_end, 0),
+    ];
+    let hits = v8_line_hits(source, &ranges);
+    assert_eq!(hits.get(&1), Some(&1));
+    assert_eq!(hits.get(&3), Some(&0));
+    assert_eq!(hits.get(&5), None);
+    assert_eq!(hits.get(&6), Some(&1));
+    assert_eq!(hits.get(&8), Some(&1));
+}
+
+#[test]
+fn v8_offsets_are_counted_in_utf16_units() {
+    let source = "const s = '\u{1F600}';\nskipped();\n";
+    let second_line_utf16 = source.split('\n').next().unwrap().encode_utf16().count() + 1;
+    let ranges = [(0, 100, 1), (second_line_utf16, second_line_utf16 + 10, 0)];
+    let hits = v8_line_hits(source, &ranges);
+    assert_eq!(hits.get(&1), Some(&1));
+    assert_eq!(hits.get(&2), Some(&0));
+}
+
+#[test]
+fn read_v8_coverage_dir_sums_processes_and_keeps_repo_js_only() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo = temp.path().join("repo");
+    let dumps = temp.path().join("v8");
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    std::fs::create_dir_all(&dumps).unwrap();
+    let source = "function f() {\n  return 1;\n}\n";
+    std::fs::write(repo.join("src/a.js"), source).unwrap();
+    std::fs::write(repo.join("src/b.ts"), "export {};\n").unwrap();
+    let dump = |count: u32| {
+        serde_json::json!({
+            "result": [
+                {
+                    "url": format!("file://{}", repo.join("src/a.js").display()),
+                    "functions": [
+                        { "functionName": "", "ranges": [{ "startOffset": 0, "endOffset": source.len(), "count": 1 }] },
+                        { "functionName": "f", "ranges": [{ "startOffset": 0, "endOffset": 27, "count": count }] }
+                    ]
+                },
+                {
+                    "url": format!("file://{}", repo.join("src/b.ts").display()),
+                    "functions": [{ "functionName": "", "ranges": [{ "startOffset": 0, "endOffset": 10, "count": 1 }] }]
+                },
+                { "url": "node:internal/main", "functions": [] }
+            ]
+        })
+        .to_string()
+    };
+    std::fs::write(dumps.join("coverage-1.json"), dump(0)).unwrap();
+    std::fs::write(dumps.join("coverage-2.json"), dump(2)).unwrap();
+
+    let report = read_v8_coverage_dir(&dumps, &repo).expect("report");
+    assert_eq!(report.files.len(), 1);
+    let file = &report.files[0];
+    assert!(file.path.ends_with("src/a.js"));
+    assert_eq!(file.line_hits.get(&2), Some(&2));
+    assert_eq!(file.function_hits.get("1:f"), Some(&2));
+    assert_eq!(file.lines_covered, file.lines_total);
+}
@@ -0,0 +1,116 @@
+//! `--debug`: run the selected tests so a debugger can attach, instead of reconstructing the
+//! runner invocation by hand. Jest keeps its normal (rendered) run with node's inspector waiting
+//! for a client; pytest and Rust debugger sessions run attached to the terminal.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::pytest::PythonTestRunner;
+use crate::repro::shell_quote;
+use crate::run::RunError;
+
+/// Where `node --inspect-brk` listens for jest runs.
+pub const NODE_INSPECT_ADDRESS: &str = "127.0.0.1:9229";
+/// Where `python -m debugpy --listen` waits for pytest runs.
+pub const DEBUGPY_ADDRESS: &str = "127.0.0.1:5678";
+/// Jest's `--testTimeout` while debugging, so a paused test isn't failed as timed out.
+pub const JEST_DEBUG_TEST_TIMEOUT_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Debugger {
+    /// Each runner's usual route: node's inspector, debugpy (else pdb), or printing the Rust
+    /// test binary commands.
+    Auto,
+    Pdb,
+    Gdb,
+    Lldb,
+}
+
+/// `NODE_OPTIONS` with the inspector added, keeping whatever the user (or `--jest-esm`) set.
+pub fn node_options_with_inspect(existing: Option<&str>) -> String {
+    let flag = format!("--inspect-brk={NODE_INSPECT_ADDRESS}");
+    match existing
+        .map(str::trim)
+        .filter(|options| !options.is_empty())
+    {
+        Some(options) => format!("{options} {flag}"),
+        None => flag,
+    }
+}
+
+pub fn announce_node_inspector() {
+    eprintln!(
+        "headlamp: debugger: node waits on {NODE_INSPECT_ADDRESS}; attach from chrome://inspect \
+         or your editor's \"Attach to Node\""
+    );
+}
+
+/// The program and args for a debugged Python run, from the usual `cmd_args` (which start with
+/// `-m pytest` whenever there is an interpreter). debugpy needs that interpreter; without it
+/// pytest gets `--pdb`, while the unittest fallback has no post-mortem hook and runs as is.
+pub(crate) fn python_invocation(
+    debugger: Debugger,
+    runner: &PythonTestRunner,
+    cmd_args: Vec<String>,
+) -> (String, Vec<String>) {
+    let debugpy_python = runner
+        .python()
+        .filter(|python| debugger != Debugger::Pdb && can_import_debugpy(python));
+    if let Some(python) = debugpy_python {
+        eprintln!(
+            "headlamp: debugger: debugpy waits on {DEBUGPY_ADDRESS}; attach with your editor's \
+             Python \"Remote Attach\""
+        );
+        let debugpy_args = [
+            "-m",
+            "debugpy",
+            "--listen",
+            DEBUGPY_ADDRESS,
+            "--wait-for-client",
+        ]
+        .into_iter()
+        .map(str::to_string);
+        return (
+            python.to_string_lossy().to_string(),
+            debugpy_args.chain(cmd_args).collect(),
+        );
+    }
+    let mut cmd_args = cmd_args;
+    if !runner.is_unittest() && !cmd_args.iter().any(|arg| arg == "--pdb") {
+        cmd_args.push("--pdb".to_string());
+    }
+    (runner.program(), cmd_args)
+}
+
+fn can_import_debugpy(python: &Path) -> bool {
+    Command::new(python)
+        .args(["-c", "import debugpy"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// The command line as a user would paste it.
+pub fn display_command(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| shell_quote(&part.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs `command` in the foreground with the terminal's stdio, leaving Ctrl-C to the debugger.
+pub fn run_attached(mut command: Command) -> Result<i32, RunError> {
+    eprintln!("headlamp: debugging: {}", display_command(&command));
+    let status = crate::interrupt::while_attached(|| {
+        command
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+    })
+    .map_err(RunError::SpawnFailed)?;
+    Ok(status.code().unwrap_or(1))
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use crate::args::ParsedArgs;
+use crate::recorded::RecordedResults;
+use crate::test_model::TestRunModel;
+
+/// Results that collide once merged into one model, so the merged counts cannot be trusted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Duplicate {
+    /// `count` results for `full_name` within one suite (e.g. `test.each` without `%s`).
+    TestName {
+        suite_path: String,
+        full_name: String,
+        count: usize,
+    },
+    /// The same suite path reported by `count` projects of one run.
+    SuitePath { suite_path: String, count: usize },
+}
+
+impl std::fmt::Display for Duplicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TestName {
+                suite_path,
+                full_name,
+                count,
+            } => write!(f, "{count} tests named {full_name:?} in {suite_path}"),
+            Self::SuitePath { suite_path, count } => {
+                write!(f, "{suite_path} was reported by {count} projects")
+            }
+        }
+    }
+}
+
+/// Full test names that appear more than once within the same suite of `model`.
+pub fn duplicate_test_names(model: &TestRunModel) -> Vec<Duplicate> {
+    model
+        .test_results
+        .iter()
+        .flat_map(|suite| {
+            counts(
+                suite
+                    .test_results
+                    .iter()
+                    .map(|case| case.full_name.as_str()),
+            )
+            .into_iter()
+            .map(|(full_name, count)| Duplicate::TestName {
+                suite_path: suite.test_file_path.clone(),
+                full_name: full_name.to_string(),
+                count,
+            })
+        })
+        .collect()
+}
+
+/// Suite paths reported by more than one of `projects`, before they are merged into one model.
+pub fn duplicate_suite_paths(projects: &[TestRunModel]) -> Vec<Duplicate> {
+    let paths = projects.iter().flat_map(|model| {
+        let mut own = model
+            .test_results
+            .iter()
+            .map(|suite| suite.test_file_path.as_str())
+            .collect::<Vec<_>>();
+        own.sort_unstable();
+        own.dedup();
+        own
+    });
+    counts(paths)
+        .into_iter()
+        .map(|(suite_path, count)| Duplicate::SuitePath {
+            suite_path: suite_path.to_string(),
+            count,
+        })
+        .collect()
+}
+
+fn counts<'a>(items: impl Iterator<Item = &'a str>) -> Vec<(&'a str, usize)> {
+    let mut by_item = BTreeMap::<&str, usize>::new();
+    items.for_each(|item| *by_item.entry(item).or_default() += 1);
+    by_item
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .collect()
+}
+
+/// Warns about every recorded duplicate; under `--strict-duplicates` a passing run that had any
+/// exits 1 instead.
+pub fn report_duplicates(args: &ParsedArgs, results: &RecordedResults, code: i32) -> i32 {
+    results
+        .duplicates
+        .iter()
+        .for_each(|duplicate| eprintln!("headlamp: warning: duplicate results: {duplicate}"));
+    if !args.strict_duplicates || results.duplicates.is_empty() || code != 0 {
+        return code;
+    }
+    eprintln!(
+        "headlamp: {} duplicate(s) and --strict-duplicates is set",
+        results.duplicates.len()
+    );
+    1
+}
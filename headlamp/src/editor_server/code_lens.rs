@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+use serde::Serialize;
+
+use super::symbols::{TestSymbol, test_symbols};
+
+/// What an editor needs to place "Run | Debug" lenses in one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestsInFile {
+    /// Repo-relative, with slashes.
+    pub file: String,
+    pub tests: Vec<TestSymbol>,
+    /// Repo-relative test files that import this one, as `--changed` selection would pick them.
+    pub related_tests: Vec<String>,
+}
+
+/// The tests defined in `path` (absolute, or relative to `repo_root`) and the test files that
+/// cover it, from static discovery only: nothing here starts a runner.
+pub fn tests_in_file(repo_root: &Path, path: &Path, source: &str) -> TestsInFile {
+    let abs = repo_root.join(path);
+    let file = relative_slash(repo_root, &abs);
+    let related_tests = crate::selection::related_files::related_test_files(repo_root, &abs)
+        .into_iter()
+        .map(|test| relative_slash(repo_root, &test))
+        .filter(|test| *test != file)
+        .collect();
+    TestsInFile {
+        tests: test_symbols(Path::new(&file), source),
+        file,
+        related_tests,
+    }
+}
+
+/// `headlamp tests-in-file <file>`: `TestsInFile` as JSON on stdout.
+pub fn run_tests_in_file_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "tests-in-file" {
+        return None;
+    }
+    let [file] = rest else {
+        eprintln!("headlamp: usage: headlamp tests-in-file <file>");
+        return Some(2);
+    };
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = crate::config::find_repo_root(&cwd);
+    let path = cwd.join(file);
+    let source = match std::fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("headlamp: {file}: {err}");
+            return Some(2);
+        }
+    };
+    let found = tests_in_file(&repo_root, &path, &source);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&found).unwrap_or_default()
+    );
+    Some(0)
+}
+
+fn relative_slash(repo_root: &Path, path: &Path) -> String {
+    path.strip_prefix(repo_root)
+        .unwrap_or(path)
+        .to_slash_lossy()
+        .to_string()
+}
@@ -0,0 +1,332 @@
+//! `headlamp editor-server`: JSON-RPC 2.0 over stdio with LSP's `Content-Length` framing, for
+//! editor integrations. Lines are 0-based, like LSP positions; `file` is a path (absolute or
+//! repo-relative) or a `uri` (`file://...`), with an optional `text` for unsaved buffers.
+//!
+//! - `initialize` `{rootPath?, initializationOptions?: {args?: [..], runner?: "plugin:<name>"}}`
+//! - `headlamp/listTests` `{file}` -> `{tests: [TestSymbol]}`, for inline decorations
+//! - `headlamp/resolveTest` `{file, line}` -> the innermost `TestSymbol` at `line`, or null
+//! - `headlamp/testsInFile` `{file}` -> `TestsInFile`, the tests plus related test files, for
+//!   code lenses (also `headlamp tests-in-file <file>`)
+//! - `headlamp/runTestAtCursor` `{file, line}` and `headlamp/runRelated` `{file}` run tests;
+//!   the result (`{exitCode, passed, failed, skipped}`) follows a `headlamp/runStarted`
+//!   notification and one `headlamp/testResults` notification per test file
+//! - `shutdown`, then the `exit` notification
+//!
+//! Runs go through `api::run` as quiet runs, so stdout carries only messages.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+use serde_json::{Value, json};
+
+use crate::api::RunRequest;
+use crate::rerun::FailedTest;
+use crate::runner::Runner;
+use crate::runner_detect::{DetectedLanguage, language_for_path};
+use crate::test_model::TestSuiteResult;
+
+pub mod code_lens;
+pub mod framing;
+pub mod symbols;
+
+pub use code_lens::{TestsInFile, run_tests_in_file_command, tests_in_file};
+pub use framing::{read_message, write_message};
+pub use symbols::{SymbolKind, TestSymbol, symbol_at_line, test_symbols};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// `headlamp editor-server`; `None` when `argv` is another command.
+pub fn run_editor_server_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "editor-server" {
+        return None;
+    }
+    if !rest.is_empty() {
+        eprintln!("headlamp: usage: headlamp editor-server");
+        return Some(2);
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut server = EditorServer::new(crate::config::find_repo_root(&cwd));
+    let served = server.serve(&mut std::io::stdin().lock(), &mut std::io::stdout());
+    Some(served.unwrap_or_else(|err| {
+        eprintln!("headlamp: editor-server: {err}");
+        1
+    }))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EditorServer {
+    repo_root: PathBuf,
+    /// Flags every run gets, from `initializationOptions.args`.
+    run_args: Vec<String>,
+    /// `initializationOptions.runner`, used instead of picking a runner by language.
+    runner: Option<Runner>,
+    shutdown_requested: bool,
+}
+
+impl EditorServer {
+    pub fn new(repo_root: PathBuf) -> Self {
+        Self {
+            repo_root,
+            run_args: vec![],
+            runner: None,
+            shutdown_requested: false,
+        }
+    }
+
+    /// Serves until `exit`; exits 0 when a `shutdown` came first, as LSP servers do.
+    pub fn serve(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> std::io::Result<i32> {
+        while let Some(message) = read_message(input)? {
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            if method == "exit" {
+                return Ok(if self.shutdown_requested { 0 } else { 1 });
+            }
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            let result = self.handle(&method, &params, output);
+            let Some(id) = message.get("id").cloned() else {
+                continue;
+            };
+            let response = match result {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err(err) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": err.code, "message": err.message},
+                }),
+            };
+            write_message(output, &response)?;
+        }
+        Ok(1)
+    }
+
+    fn handle(
+        &mut self,
+        method: &str,
+        params: &Value,
+        output: &mut impl Write,
+    ) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => self.initialize(params),
+            "initialized" => Ok(Value::Null),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "headlamp/listTests" => {
+                let (_, symbols) = self.symbols_for(params)?;
+                Ok(json!({ "tests": symbols }))
+            }
+            "headlamp/resolveTest" => {
+                let (_, symbols) = self.symbols_for(params)?;
+                Ok(json!(symbol_at_line(&symbols, line_param(params)?)))
+            }
+            "headlamp/testsInFile" => {
+                let (path, _) = self.file_param(params)?;
+                let source = self.source_for(params)?;
+                Ok(json!(tests_in_file(&self.repo_root, &path, &source)))
+            }
+            "headlamp/runTestAtCursor" => self.run_test_at_cursor(params, output),
+            "headlamp/runRelated" => {
+                let (path, relative) = self.file_param(params)?;
+                let request = RunRequest::new(&self.repo_root)
+                    .runner(self.runner_for(&path)?)
+                    .args(self.run_args.iter().cloned().chain([relative.clone()]));
+                run(request, json!({ "file": relative }), output)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Result<Value, RpcError> {
+        if let Some(root) = params["rootPath"].as_str().or_else(|| {
+            params["rootUri"]
+                .as_str()
+                .map(|uri| uri.trim_start_matches("file://"))
+        }) {
+            self.repo_root = PathBuf::from(root);
+        }
+        let options = &params["initializationOptions"];
+        self.run_args = options["args"]
+            .as_array()
+            .map(|args| {
+                args.iter()
+                    .filter_map(|arg| arg.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.runner =
+            match options["runner"].as_str() {
+                Some(raw) => Some(Runner::parse(raw).ok_or_else(|| {
+                    RpcError::new(INVALID_PARAMS, format!("unknown runner: {raw}"))
+                })?),
+                None => None,
+            };
+        Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "serverInfo": {"name": "headlamp", "version": env!("CARGO_PKG_VERSION")},
+            "capabilities": {"methods": [
+                "headlamp/listTests",
+                "headlamp/resolveTest",
+                "headlamp/testsInFile",
+                "headlamp/runTestAtCursor",
+                "headlamp/runRelated",
+            ]},
+        }))
+    }
+
+    fn run_test_at_cursor(
+        &self,
+        params: &Value,
+        output: &mut impl Write,
+    ) -> Result<Value, RpcError> {
+        let line = line_param(params)?;
+        let (path, symbols) = self.symbols_for(params)?;
+        let (_, relative) = self.file_param(params)?;
+        let symbol = symbol_at_line(&symbols, line).cloned();
+        let request = RunRequest::new(&self.repo_root)
+            .runner(self.runner_for(&path)?)
+            .args(self.run_args.iter().cloned());
+        let is_js = language_for_path(&path) == Some(DetectedLanguage::JavaScript);
+        let request = match &symbol {
+            // jest's `-t` matches whole names, so a `describe` selects by prefix.
+            Some(suite) if suite.kind == SymbolKind::Suite && is_js => request.args([
+                relative.clone(),
+                "--".to_string(),
+                "-t".to_string(),
+                format!("^{}( |$)", regex::escape(&suite.full_name)),
+            ]),
+            _ => request.tests([FailedTest {
+                test_file_path: relative.clone(),
+                full_name: symbol.as_ref().map(|symbol| symbol.full_name.clone()),
+            }]),
+        };
+        run(request, json!({ "file": relative, "test": symbol }), output)
+    }
+
+    fn runner_for(&self, path: &Path) -> Result<Runner, RpcError> {
+        if let Some(runner) = self.runner {
+            return Ok(runner);
+        }
+        language_for_path(path)
+            .map(|language| Runner::for_language(&self.repo_root, language))
+            .ok_or_else(|| {
+                RpcError::new(INVALID_PARAMS, format!("no runner for {}", path.display()))
+            })
+    }
+
+    /// The absolute path and the repo-relative (slash) path of the request's file.
+    fn file_param(&self, params: &Value) -> Result<(PathBuf, String), RpcError> {
+        let raw = params["file"]
+            .as_str()
+            .or_else(|| {
+                params["uri"]
+                    .as_str()
+                    .map(|uri| uri.trim_start_matches("file://"))
+            })
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing `file` or `uri`"))?;
+        let path = self.repo_root.join(raw);
+        let relative = path
+            .strip_prefix(&self.repo_root)
+            .unwrap_or(&path)
+            .to_slash_lossy()
+            .to_string();
+        Ok((path, relative))
+    }
+
+    fn symbols_for(&self, params: &Value) -> Result<(PathBuf, Vec<TestSymbol>), RpcError> {
+        let (path, relative) = self.file_param(params)?;
+        let symbols = test_symbols(Path::new(&relative), &self.source_for(params)?);
+        Ok((path, symbols))
+    }
+
+    /// The editor's unsaved `text` when sent, else the file on disk.
+    fn source_for(&self, params: &Value) -> Result<String, RpcError> {
+        if let Some(text) = params["text"].as_str() {
+            return Ok(text.to_string());
+        }
+        let (path, relative) = self.file_param(params)?;
+        std::fs::read_to_string(&path)
+            .map_err(|err| RpcError::new(INVALID_PARAMS, format!("{relative}: {err}")))
+    }
+}
+
+fn line_param(params: &Value) -> Result<usize, RpcError> {
+    params["line"]
+        .as_u64()
+        .map(|line| line as usize)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing `line`"))
+}
+
+fn run(request: RunRequest, target: Value, output: &mut impl Write) -> Result<Value, RpcError> {
+    let internal = |err: &dyn std::fmt::Display| RpcError::new(INTERNAL_ERROR, err.to_string());
+    notify(output, "headlamp/runStarted", target).map_err(|err| internal(&err))?;
+    let outcome = crate::api::run(&request.quiet()).map_err(|err| internal(&err))?;
+    for suite in outcome.models.iter().flat_map(|model| &model.test_results) {
+        notify(output, "headlamp/testResults", suite_results(suite))
+            .map_err(|err| internal(&err))?;
+    }
+    Ok(json!({
+        "exitCode": outcome.exit_code,
+        "passed": outcome.results.passed,
+        "failed": outcome.results.failed,
+        "skipped": outcome.results.skipped,
+    }))
+}
+
+fn notify(output: &mut impl Write, method: &str, params: Value) -> std::io::Result<()> {
+    write_message(
+        output,
+        &json!({"jsonrpc": "2.0", "method": method, "params": params}),
+    )
+}
+
+/// One file's results, with 0-based lines where the runner reported a location.
+fn suite_results(suite: &TestSuiteResult) -> Value {
+    let tests = suite
+        .test_results
+        .iter()
+        .map(|case| {
+            json!({
+                "name": case.title,
+                "fullName": case.full_name,
+                "status": case.status,
+                "durationMs": case.duration,
+                "line": case.location.as_ref().map(|location| (location.line - 1).max(0)),
+                "failureMessages": case.failure_messages,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "file": suite.test_file_path,
+        "status": suite.status,
+        "failureMessage": suite.failure_message,
+        "tests": tests,
+    })
+}
@@ -0,0 +1,239 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Argument, CallExpression, Expression};
+use oxc_ast_visit::{Visit, walk};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use path_slash::PathExt;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::runner_detect::{DetectedLanguage, language_for_path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SymbolKind {
+    /// A jest `describe` or a pytest `Test*` class.
+    Suite,
+    Test,
+}
+
+/// A test (or group of tests) in a file, with 0-based inclusive lines like LSP ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSymbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    /// The name the runner reports and filters by: jest's `describe it` title path, pytest's
+    /// `Class::test`, libtest's `module::test`.
+    pub full_name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// The tests in `source`, read as the file at `path` (repo-relative for Rust module paths).
+pub fn test_symbols(path: &Path, source: &str) -> Vec<TestSymbol> {
+    match language_for_path(path) {
+        Some(DetectedLanguage::JavaScript) => js_symbols(path, source),
+        Some(DetectedLanguage::Python) => python_symbols(source),
+        Some(DetectedLanguage::Rust) => rust_symbols(path, source),
+        None => vec![],
+    }
+}
+
+/// The innermost symbol spanning `line`.
+pub fn symbol_at_line(symbols: &[TestSymbol], line: usize) -> Option<&TestSymbol> {
+    symbols
+        .iter()
+        .filter(|symbol| (symbol.start_line..=symbol.end_line).contains(&line))
+        .min_by_key(|symbol| symbol.end_line - symbol.start_line)
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())]
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count()
+}
+
+fn js_symbols(path: &Path, source: &str) -> Vec<TestSymbol> {
+    let source_type = SourceType::from_path(path).unwrap_or_default();
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source, source_type).parse();
+    let mut collector = JestCallCollector {
+        source,
+        titles: vec![],
+        symbols: vec![],
+    };
+    collector.visit_program(&ret.program);
+    collector.symbols
+}
+
+struct JestCallCollector<'s> {
+    source: &'s str,
+    titles: Vec<String>,
+    symbols: Vec<TestSymbol>,
+}
+
+impl JestCallCollector<'_> {
+    fn kind_of(callee: &Expression<'_>) -> Option<SymbolKind> {
+        let base = match callee {
+            Expression::Identifier(ident) => ident.name.as_str(),
+            Expression::StaticMemberExpression(member) => match &member.object {
+                Expression::Identifier(ident) => ident.name.as_str(),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match base {
+            "describe" | "fdescribe" | "xdescribe" => Some(SymbolKind::Suite),
+            "it" | "test" | "fit" | "xit" | "xtest" => Some(SymbolKind::Test),
+            _ => None,
+        }
+    }
+
+    fn title(argument: Option<&Argument<'_>>) -> Option<String> {
+        match argument? {
+            Argument::StringLiteral(lit) => Some(lit.value.to_string()),
+            Argument::TemplateLiteral(lit) if lit.expressions.is_empty() => lit
+                .quasis
+                .iter()
+                .map(|quasi| quasi.value.cooked.as_ref().map(|cooked| cooked.as_str()))
+                .collect::<Option<String>>(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Visit<'a> for JestCallCollector<'_> {
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        let found = Self::kind_of(&it.callee).zip(Self::title(it.arguments.first()));
+        let Some((kind, name)) = found else {
+            walk::walk_call_expression(self, it);
+            return;
+        };
+        let full_name = self
+            .titles
+            .iter()
+            .chain(std::iter::once(&name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.symbols.push(TestSymbol {
+            kind,
+            name: name.clone(),
+            full_name,
+            start_line: line_of(self.source, it.span.start as usize),
+            end_line: line_of(self.source, it.span.end as usize),
+        });
+        self.titles.push(name);
+        walk::walk_call_expression(self, it);
+        self.titles.pop();
+    }
+}
+
+static PY_DEF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<indent>[ \t]*)(?:(?P<class>class)|(?:async[ \t]+)?def)[ \t]+(?P<name>\w+)")
+        .unwrap()
+});
+
+/// pytest collects `test*` functions at module level or in `Test*` classes (which may nest).
+fn python_symbols(source: &str) -> Vec<TestSymbol> {
+    struct Frame {
+        indent: usize,
+        symbol: Option<usize>,
+        is_test_class: bool,
+    }
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut symbols: Vec<TestSymbol> = vec![];
+    let mut frames: Vec<Frame> = vec![];
+    let mut last_code_line = 0usize;
+    for (line_index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        while frames.last().is_some_and(|frame| frame.indent >= indent) {
+            if let Some(index) = frames.pop().and_then(|frame| frame.symbol) {
+                symbols[index].end_line = last_code_line;
+            }
+        }
+        last_code_line = line_index;
+        let Some(captures) = PY_DEF_RE.captures(line) else {
+            continue;
+        };
+        let name = captures["name"].to_string();
+        let is_class = captures.name("class").is_some();
+        let in_test_scope = frames.iter().all(|frame| frame.is_test_class);
+        let kind = match (is_class, in_test_scope) {
+            (true, true) if name.starts_with("Test") => Some(SymbolKind::Suite),
+            (false, true) if name.starts_with("test") => Some(SymbolKind::Test),
+            _ => None,
+        };
+        let symbol = kind.map(|kind| {
+            let full_name = frames
+                .iter()
+                .filter_map(|frame| frame.symbol.map(|index| symbols[index].name.as_str()))
+                .chain(std::iter::once(name.as_str()))
+                .collect::<Vec<_>>()
+                .join("::");
+            symbols.push(TestSymbol {
+                kind,
+                name: name.clone(),
+                full_name,
+                start_line: line_index,
+                end_line: line_index,
+            });
+            symbols.len() - 1
+        });
+        frames.push(Frame {
+            indent,
+            symbol,
+            is_test_class: kind == Some(SymbolKind::Suite),
+        });
+    }
+    for index in frames.into_iter().filter_map(|frame| frame.symbol) {
+        symbols[index].end_line = last_code_line;
+    }
+    symbols
+}
+
+fn rust_symbols(path: &Path, source: &str) -> Vec<TestSymbol> {
+    let module = rust_module_path(path);
+    crate::rust_parse::find_test_fns(source)
+        .into_iter()
+        .map(|test_fn| TestSymbol {
+            kind: SymbolKind::Test,
+            full_name: match module.as_str() {
+                "" => test_fn.path.clone(),
+                module => format!("{module}::{}", test_fn.path),
+            },
+            name: test_fn.name,
+            start_line: line_of(source, test_fn.start),
+            end_line: line_of(source, test_fn.end),
+        })
+        .collect()
+}
+
+/// The module a file under `src/` declares (`src/a/b.rs` is `a::b`); test names are relative to
+/// the crate, while integration tests and crate roots add nothing.
+fn rust_module_path(path: &Path) -> String {
+    let slashed = path.to_slash_lossy();
+    let Some((_, in_src)) = slashed.rsplit_once("src/") else {
+        return String::new();
+    };
+    let mut segments = in_src
+        .trim_end_matches(".rs")
+        .split('/')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if segments.last().is_some_and(|last| last == "mod")
+        || (segments.len() == 1 && matches!(segments[0].as_str(), "lib" | "main"))
+    {
+        segments.pop();
+    }
+    segments.join("::")
+}
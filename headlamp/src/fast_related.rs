@@ -13,8 +13,8 @@ use crate::process::run_command_capture_with_timeout;
 use crate::run::RunError;
 
 pub const DEFAULT_TEST_GLOBS: [&str; 2] = [
-    "**/*.{test,spec}.{ts,tsx,js,jsx}",
-    "tests/**/*.{ts,tsx,js,jsx}",
+    "**/*.{test,spec}.{ts,tsx,js,jsx,mjs,cjs,mts,cts}",
+    "tests/**/*.{ts,tsx,js,jsx,mjs,cjs,mts,cts}",
 ];
 
 pub const FAST_RELATED_TIMEOUT: Duration = Duration::from_millis(1500);
@@ -148,6 +148,26 @@ pub fn parse_bridge_console(
     (http, assertions, console_list)
 }
 
+/// An HTTP event recorded outside jest (the pytest plugin, Rust's `test_support`) as the console
+/// entry the jest bridge would have written, so it renders the same way. `event` carries the
+/// bridge event fields (`type`, `timestampMs`, `method`, ...); the test it belongs to is added.
+pub fn http_bridge_console_entry(
+    mut event: serde_json::Map<String, serde_json::Value>,
+    test_path: &str,
+    test_name: &str,
+) -> crate::format::bridge::BridgeConsoleEntry {
+    event.insert("testPath".to_string(), test_path.into());
+    event.insert("currentTestName".to_string(), test_name.into());
+    crate::format::bridge::BridgeConsoleEntry {
+        message: Some(serde_json::Value::String(format!(
+            "[JEST-BRIDGE-EVENT] {}",
+            serde_json::Value::Object(event)
+        ))),
+        type_name: Some("log".to_string()),
+        origin: None,
+    }
+}
+
 fn push_plain_console_entry(
     console_list: &mut Vec<ConsoleEntry>,
     entry: &crate::format::bridge::BridgeConsoleEntry,
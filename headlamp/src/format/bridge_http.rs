@@ -1,6 +1,11 @@
 use crate::format::ansi;
 use crate::format::bridge_console::{AssertionEvt, HttpEvent};
 use crate::format::time::format_duration;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static PYTEST_STATUS_ASSERT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bassert (\d{3}) == (\d{3})\b").unwrap());
 
 const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
 
@@ -408,10 +413,20 @@ fn is_http_status_number(value: Option<i64>) -> bool {
     value.is_some_and(|n| (100..=599).contains(&n))
 }
 
+/// `(expected, received)` from jest's `Expected:`/`Received:`, `assert_eq!`'s `right:`/`left:`
+/// or pytest's `assert 500 == 200`.
 fn infer_http_numbers_from_text(text: &str) -> Option<(Option<i64>, Option<i64>)> {
-    let expected = find_three_digit_after_label(text, "Expected:")?;
-    let received = find_three_digit_after_label(text, "Received:")?;
-    Some((Some(expected), Some(received)))
+    let labelled = |expected_label: &str, received_label: &str| {
+        let expected = find_three_digit_after_label(text, expected_label)?;
+        let received = find_three_digit_after_label(text, received_label)?;
+        Some((Some(expected), Some(received)))
+    };
+    labelled("Expected:", "Received:")
+        .or_else(|| labelled("right:", "left:"))
+        .or_else(|| {
+            let caps = PYTEST_STATUS_ASSERT_RE.captures(text)?;
+            Some((caps[2].parse().ok(), caps[1].parse().ok()))
+        })
 }
 
 fn find_three_digit_after_label(text: &str, label: &str) -> Option<i64> {
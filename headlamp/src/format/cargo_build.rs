@@ -0,0 +1,192 @@
+use serde::Deserialize;
+
+use crate::format::ctx::Ctx;
+use crate::format::{ansi, colors, fns, paths, stacks};
+
+/// rustc diagnostics collected from cargo's `--message-format=json-diagnostic-rendered-ansi`
+/// stream, so a build that fails before any test runs can be rendered as a "Build failed"
+/// section instead of raw output.
+#[derive(Debug, Default)]
+pub struct CargoBuildDiagnostics {
+    errors: Vec<BuildDiagnostic>,
+    warnings: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildDiagnostic {
+    pub code: Option<String>,
+    pub message: String,
+    /// Primary span, relative to the workspace root as cargo reports it.
+    pub file: Option<String>,
+    pub line: Option<i64>,
+    pub column: Option<i64>,
+    /// rustc's own rendering, ANSI colored.
+    pub rendered: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    level: String,
+    code: Option<RustcCode>,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: i64,
+    column_start: i64,
+    is_primary: bool,
+}
+
+/// Cargo's JSON messages always lead with `reason`; libtest and nextest JSON never do.
+pub fn is_cargo_message(line: &str) -> bool {
+    line.trim_start().starts_with("{\"reason\":")
+}
+
+/// The rustc diagnostic carried by a cargo `compiler-message` line, with its level
+/// (`error`, `warning`, ...). Clippy's lints arrive the same way.
+pub fn parse_compiler_message(line: &str) -> Option<(String, BuildDiagnostic)> {
+    let message = serde_json::from_str::<CargoMessage>(line).ok()?;
+    let diagnostic = message
+        .message
+        .filter(|_| message.reason == "compiler-message")?;
+    let primary = diagnostic.spans.iter().find(|span| span.is_primary);
+    let parsed = BuildDiagnostic {
+        code: diagnostic.code.map(|code| code.code),
+        file: primary.map(|span| span.file_name.clone()),
+        line: primary.map(|span| span.line_start),
+        column: primary.map(|span| span.column_start),
+        message: diagnostic.message,
+        rendered: diagnostic.rendered.unwrap_or_default(),
+    };
+    Some((diagnostic.level, parsed))
+}
+
+/// rustc's closing "aborting due to ..." and "N warnings emitted" lines, which only count the
+/// diagnostics before them.
+pub fn is_summary_message(message: &str) -> bool {
+    message.starts_with("aborting due to")
+        || message.ends_with("warning emitted")
+        || message.ends_with("warnings emitted")
+}
+
+impl CargoBuildDiagnostics {
+    /// Takes `line` if it is a cargo JSON message; anything else is left for the test output
+    /// parsers.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        if !is_cargo_message(line) {
+            return false;
+        }
+        let Some((level, diagnostic)) = parse_compiler_message(line) else {
+            return true;
+        };
+        if is_summary_message(&diagnostic.message) {
+            return true;
+        }
+        match level.as_str() {
+            // The same error is reported once per target that compiles the file (lib, lib test).
+            "error" | "error: internal compiler error" if !self.errors.contains(&diagnostic) => {
+                self.errors.push(diagnostic)
+            }
+            "warning" => self.warnings += 1,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn errors(&self) -> &[BuildDiagnostic] {
+        &self.errors
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.warnings
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+pub fn render_build_failed(diagnostics: &CargoBuildDiagnostics, ctx: &Ctx) -> Vec<String> {
+    let title = ansi::bold(&colors::failure("Build failed"));
+    let mut lines = vec![fns::draw_rule(ctx.width, Some(&title)), String::new()];
+    diagnostics.errors.iter().for_each(|error| {
+        lines.extend(render_error(error, ctx));
+        lines.push(String::new());
+    });
+    lines.push(format!(
+        "{title} {}",
+        ansi::dim(&format!(
+            "{}, {}",
+            plural(diagnostics.errors.len(), "error"),
+            plural(diagnostics.warnings, "warning")
+        ))
+    ));
+    lines
+}
+
+fn render_error(error: &BuildDiagnostic, ctx: &Ctx) -> Vec<String> {
+    let label = match error.code.as_deref() {
+        Some(code) => format!("error[{code}]"),
+        None => "error".to_string(),
+    };
+    let mut lines = vec![format!(
+        "  {} {}",
+        colors::failure(&format!("✕ {label}:")),
+        ansi::bold(&error.message)
+    )];
+    if let (Some(file), Some(line)) = (error.file.as_deref(), error.line) {
+        let absolute = if std::path::Path::new(file).is_absolute() {
+            file.to_string()
+        } else {
+            format!("{}/{file}", ctx.cwd)
+        };
+        let location = format!("{file}:{line}:{}", error.column.unwrap_or(1));
+        let href = paths::preferred_editor_href(&absolute, Some(line), ctx.editor_cmd.as_deref());
+        lines.push(format!(
+            "    {} {}",
+            ansi::dim("at"),
+            ansi::osc8(&location, &href)
+        ));
+    }
+    lines.extend(snippet_lines(&error.rendered).map(|line| format!("  {line}")));
+    lines
+}
+
+/// rustc's rendering minus the header and `-->` location we already show.
+fn snippet_lines(rendered: &str) -> impl Iterator<Item = &str> {
+    let all = rendered.trim_end().lines().collect::<Vec<_>>();
+    let skip = all
+        .iter()
+        .position(|line| {
+            stacks::strip_ansi_simple(line)
+                .trim_start()
+                .starts_with("--> ")
+        })
+        .map(|index| index + 1)
+        .unwrap_or(1);
+    all.into_iter().skip(skip)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::format::cargo_test::parse_test_line_extended;
+use crate::format::unstructured_engine::{ParsedTestLine, UnstructuredStreamEvent};
+use crate::test_model::{TestCaseResult, TestLocation, TestSuiteResult};
+
+/// A rustdoc test name such as `src/lib.rs - Foo::bar (line 10)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctestName {
+    pub source_path: String,
+    pub item: String,
+    pub line: i64,
+}
+
+impl DoctestName {
+    pub fn title(&self) -> String {
+        if self.item.is_empty() {
+            format!("(line {})", self.line)
+        } else {
+            format!("{} (line {})", self.item, self.line)
+        }
+    }
+}
+
+pub fn parse_doctest_name(name: &str) -> Option<DoctestName> {
+    static NAME_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(.+?\.rs) - (.*?)\s*\(line (\d+)\)").unwrap());
+    let caps = NAME_RE.captures(name.trim())?;
+    Some(DoctestName {
+        source_path: caps.get(1)?.as_str().to_string(),
+        item: caps.get(2)?.as_str().trim().to_string(),
+        line: caps.get(3)?.as_str().parse().ok()?,
+    })
+}
+
+/// Cargo announces the rustdoc phase with `Doc-tests <crate>` instead of `Running <binary>`.
+pub fn is_doctest_header(line: &str) -> bool {
+    let stripped = if line.contains('\u{1b}') {
+        Cow::Owned(String::from_utf8_lossy(&strip_ansi_escapes::strip(line.as_bytes())).to_string())
+    } else {
+        Cow::Borrowed(line)
+    };
+    stripped.trim().starts_with("Doc-tests ")
+}
+
+#[derive(Debug, Clone)]
+struct DoctestCase {
+    rustdoc_name: String,
+    suite_path: String,
+    result: TestCaseResult,
+}
+
+/// Parses the pretty output of `cargo test --doc`. Unlike libtest binaries, a single doctest
+/// run spans many source files, so suites are keyed by the file each doc comment lives in.
+#[derive(Debug, Clone)]
+pub struct DoctestStreamParser {
+    repo_root: PathBuf,
+    cases: Vec<DoctestCase>,
+    failures_by_name: BTreeMap<String, Vec<String>>,
+    active_failure: Option<String>,
+    in_backtrace: bool,
+}
+
+impl DoctestStreamParser {
+    pub fn new(repo_root: &Path) -> Self {
+        Self {
+            repo_root: repo_root.to_path_buf(),
+            cases: vec![],
+            failures_by_name: BTreeMap::new(),
+            active_failure: None,
+            in_backtrace: false,
+        }
+    }
+
+    pub fn push_line(&mut self, line: &str) -> Vec<UnstructuredStreamEvent> {
+        if let Some(ParsedTestLine::Completed {
+            name,
+            status,
+            duration,
+            ..
+        }) = parse_test_line_extended(line)
+            && let Some(parsed) = parse_doctest_name(&name)
+        {
+            return vec![self.push_case(name, parsed, status, duration)];
+        }
+        let trimmed = line.trim();
+        if let Some(name) = failure_section_name(trimmed) {
+            self.active_failure = Some(name.to_string());
+            self.in_backtrace = false;
+            return vec![];
+        }
+        if trimmed == "failures:" || trimmed.starts_with("test result:") {
+            self.active_failure = None;
+            return vec![];
+        }
+        if let Some(name) = self.active_failure.clone() {
+            self.push_failure_line(&name, line);
+        }
+        vec![]
+    }
+
+    fn push_case(
+        &mut self,
+        rustdoc_name: String,
+        name: DoctestName,
+        status: String,
+        duration: Option<std::time::Duration>,
+    ) -> UnstructuredStreamEvent {
+        let suite_path = self.absolutize(&name.source_path);
+        let title = name.title();
+        let repro = Some(crate::repro::cargo_doctest(&rustdoc_name));
+        self.cases.push(DoctestCase {
+            rustdoc_name,
+            suite_path: suite_path.clone(),
+            result: TestCaseResult {
+                title: title.clone(),
+                full_name: title.clone(),
+                status: status.clone(),
+                timed_out: None,
+                duration: duration.map(|d| d.as_millis() as u64).unwrap_or(0),
+                location: Some(TestLocation {
+                    line: name.line,
+                    column: 1,
+                }),
+                failure_messages: vec![],
+                failure_details: None,
+                repro,
+                skip_reason: None,
+                attempts: None,
+            },
+        });
+        UnstructuredStreamEvent::TestFinished {
+            suite_path,
+            test_name: title,
+            status,
+            duration,
+        }
+    }
+
+    fn push_failure_line(&mut self, name: &str, line: &str) {
+        let trimmed = line.trim();
+        if trimmed == "stack backtrace:" {
+            self.in_backtrace = true;
+            return;
+        }
+        if self.in_backtrace && (line.starts_with(' ') || trimmed.starts_with("note: ")) {
+            return;
+        }
+        self.in_backtrace = false;
+        if is_failure_noise_line(trimmed) {
+            return;
+        }
+        self.failures_by_name
+            .entry(name.to_string())
+            .or_default()
+            .push(line.trim_end().to_string());
+    }
+
+    fn absolutize(&self, source_path: &str) -> String {
+        let joined = self.repo_root.join(source_path);
+        if joined.exists() {
+            return joined.to_string_lossy().to_string();
+        }
+        crate::format::failure_diagnostics::resolve_existing_path_best_effort(
+            &self.repo_root.to_string_lossy(),
+            source_path,
+        )
+        .unwrap_or_else(|| joined.to_string_lossy().to_string())
+    }
+
+    pub fn finalize(self) -> Vec<TestSuiteResult> {
+        let Self {
+            cases,
+            failures_by_name,
+            ..
+        } = self;
+        let mut suites: Vec<TestSuiteResult> = vec![];
+        for mut case in cases {
+            if case.result.status == "failed"
+                && let Some(lines) = failures_by_name.get(&case.rustdoc_name)
+            {
+                case.result.failure_messages = vec![lines.join("\n")];
+            }
+            let suite = match suites
+                .iter_mut()
+                .find(|s| s.test_file_path == case.suite_path)
+            {
+                Some(suite) => suite,
+                None => {
+                    suites.push(empty_suite(case.suite_path.clone()));
+                    suites.last_mut().expect("just pushed")
+                }
+            };
+            if case.result.status == "failed" {
+                suite.status = "failed".to_string();
+            }
+            suite.test_results.push(case.result);
+        }
+        suites
+    }
+}
+
+fn failure_section_name(trimmed: &str) -> Option<&str> {
+    trimmed
+        .strip_prefix("---- ")?
+        .strip_suffix(" ----")?
+        .trim()
+        .strip_suffix(" stdout")
+        .map(str::trim)
+}
+
+fn is_failure_noise_line(trimmed: &str) -> bool {
+    trimmed.is_empty()
+        || trimmed == "stderr:"
+        || trimmed == "stdout:"
+        || trimmed.starts_with("Test executable failed")
+        || trimmed.starts_with("note: run with `RUST_BACKTRACE=")
+}
+
+fn empty_suite(test_file_path: String) -> TestSuiteResult {
+    TestSuiteResult {
+        test_file_path,
+        status: "passed".to_string(),
+        timed_out: None,
+        failure_message: String::new(),
+        failure_details: None,
+        test_exec_error: None,
+        console: None,
+        test_results: vec![],
+    }
+}
+
+pub fn parse_doctest_output(repo_root: &Path, combined_output: &str) -> Vec<TestSuiteResult> {
+    let mut parser = DoctestStreamParser::new(repo_root);
+    combined_output.lines().for_each(|line| {
+        let _ = parser.push_line(line);
+    });
+    parser.finalize()
+}
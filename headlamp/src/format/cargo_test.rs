@@ -2,8 +2,10 @@ use std::borrow::Cow;
 use std::path::Path;
 use std::time::Duration;
 
+use crate::format::cargo_doctest::{DoctestStreamParser, is_doctest_header};
 use crate::format::unstructured_engine::{
     ParsedTestLine, UnstructuredDialect, UnstructuredStreamEvent, UnstructuredStreamParser,
+    build_test_run_model,
 };
 use crate::test_model::TestRunModel;
 
@@ -59,26 +61,58 @@ impl UnstructuredDialect for CargoTestDialect {
     fn should_keep_as_console_line(&self, line: &str) -> bool {
         should_keep_as_console_line(line)
     }
+
+    fn repro_command(&self, suite_source_path: &str, test_name: &str) -> Option<String> {
+        Some(crate::repro::cargo_test(suite_source_path, test_name))
+    }
 }
 
+/// Libtest output goes through the unstructured engine; everything after a `Doc-tests` header
+/// goes to the doctest parser so doc comments get their own suites instead of being appended to
+/// whichever test binary ran last.
 #[derive(Debug, Clone)]
 pub struct CargoTestStreamParser {
     inner: UnstructuredStreamParser<CargoTestDialect>,
+    doctests: DoctestStreamParser,
+    in_doctests: bool,
 }
 
 impl CargoTestStreamParser {
     pub fn new(repo_root: &Path) -> Self {
         Self {
             inner: UnstructuredStreamParser::new_default(repo_root),
+            doctests: DoctestStreamParser::new(repo_root),
+            in_doctests: false,
         }
     }
 
     pub fn push_line(&mut self, line: &str) -> Vec<CargoTestStreamEvent> {
-        self.inner.push_line(line)
+        if is_doctest_header(line) {
+            self.in_doctests = true;
+            return vec![];
+        }
+        if self.in_doctests && parse_suite_header_source_path(line).is_some() {
+            self.in_doctests = false;
+        }
+        if self.in_doctests {
+            self.doctests.push_line(line)
+        } else {
+            self.inner.push_line(line)
+        }
     }
 
     pub fn finalize(self) -> Option<TestRunModel> {
-        self.inner.finalize()
+        let doctest_suites = self.doctests.finalize();
+        if doctest_suites.is_empty() {
+            return self.inner.finalize();
+        }
+        let mut suites = self
+            .inner
+            .finalize()
+            .map(|model| model.test_results)
+            .unwrap_or_default();
+        suites.extend(doctest_suites);
+        Some(build_test_run_model(suites))
     }
 }
 
@@ -108,7 +142,7 @@ fn parse_suite_header_source_path(line: &str) -> Option<String> {
     (!cleaned.is_empty()).then(|| cleaned.to_string())
 }
 
-fn parse_test_line_extended(line: &str) -> Option<ParsedTestLine> {
+pub(crate) fn parse_test_line_extended(line: &str) -> Option<ParsedTestLine> {
     let trimmed = line.trim();
     if !trimmed.starts_with("test ") {
         return None;
@@ -122,6 +156,7 @@ fn parse_test_line_extended(line: &str) -> Option<ParsedTestLine> {
             name: name.to_string(),
             status: "passed".to_string(),
             duration,
+            skip_reason: None,
         });
     }
     if status_word == "FAILED" {
@@ -129,13 +164,15 @@ fn parse_test_line_extended(line: &str) -> Option<ParsedTestLine> {
             name: name.to_string(),
             status: "failed".to_string(),
             duration,
+            skip_reason: None,
         });
     }
-    if status_word == "ignored" {
+    if let Some(skip_reason) = parse_ignored(rest_trimmed) {
         return Some(ParsedTestLine::Completed {
             name: name.to_string(),
             status: "pending".to_string(),
             duration,
+            skip_reason,
         });
     }
     Some(ParsedTestLine::Pending {
@@ -144,6 +181,16 @@ fn parse_test_line_extended(line: &str) -> Option<ParsedTestLine> {
     })
 }
 
+/// `ignored`, or `ignored, <reason>` for `#[ignore = "<reason>"]`.
+fn parse_ignored(rest: &str) -> Option<Option<String>> {
+    let after = rest.strip_prefix("ignored")?;
+    if after.is_empty() {
+        return Some(None);
+    }
+    let reason = after.strip_prefix(',')?.trim();
+    Some((!reason.is_empty()).then(|| reason.to_string()))
+}
+
 fn split_status_and_report_time(rest: &str) -> (&str, Option<Duration>) {
     let status_word = rest.split_whitespace().next().unwrap_or(rest).trim();
     let duration = parse_report_time_suffix(rest);
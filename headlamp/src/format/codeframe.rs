@@ -122,6 +122,48 @@ pub struct Loc {
     pub column: Option<i64>,
 }
 
+/// Where a Rust or pytest failure happened, read from its message: the `panicked at` location,
+/// or the deepest `path.py:12: ` / `File "path.py", line 12` frame of a longrepr. Only files in
+/// the project (below `cwd`, outside `site-packages`) count, so a frame inside a library never
+/// replaces the test's own line.
+pub fn failure_message_location(message_lines: &[String], cwd: &str) -> Option<Loc> {
+    static PY_PATH_LINE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"^\s*([^\s:"]+\.py):(\d+):(?:\s|$)"#).unwrap());
+    static PY_FILE_LINE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"^\s*File\s+"([^"]+\.py)",\s+line\s+(\d+)"#).unwrap());
+    message_lines
+        .iter()
+        .flat_map(|line| {
+            line.lines()
+                .map(stacks::strip_ansi_simple)
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|simple| {
+            if let Some((file, line, column)) =
+                crate::format::failure_diagnostics::parse_rust_panic_location(&simple)
+            {
+                return Some((file, line, Some(column)));
+            }
+            let caps = PY_PATH_LINE_RE
+                .captures(&simple)
+                .or_else(|| PY_FILE_LINE_RE.captures(&simple))?;
+            let line = caps.get(2)?.as_str().parse::<i64>().ok()?;
+            Some((caps.get(1)?.as_str().to_string(), line, None))
+        })
+        .filter_map(|(file, line, column)| {
+            let resolved =
+                crate::format::failure_diagnostics::resolve_existing_path_best_effort(cwd, &file)?;
+            let in_project =
+                resolved.starts_with(cwd) && !resolved.contains("/site-packages/") && line > 0;
+            in_project.then_some(Loc {
+                file: resolved,
+                line,
+                column,
+            })
+        })
+        .next_back()
+}
+
 pub fn build_code_frame_section(
     message_lines: &[String],
     show_stacks: bool,
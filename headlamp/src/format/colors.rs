@@ -30,6 +30,10 @@ pub fn bg_failure(text: &str) -> String {
     bg_color_hex("#ff2323", text)
 }
 
+pub fn bg_skip(text: &str) -> String {
+    bg_color_hex("#eab308", text)
+}
+
 pub fn bg_run(text: &str) -> String {
     bg_color_hex("#3b82f6", text)
 }
@@ -3,6 +3,10 @@ use std::path::Path;
 use path_slash::PathExt;
 use regex::Regex;
 
+use crate::args::ParsedArgs;
+use crate::config::GroupBy;
+use crate::format::path_display::PathDisplay;
+
 #[derive(Debug, Clone)]
 pub struct Ctx {
     pub cwd: String,
@@ -11,6 +15,33 @@ pub struct Ctx {
     pub show_logs: bool,
     pub project_hint: Regex,
     pub editor_cmd: Option<String>,
+    pub full_stacks: bool,
+    pub show_skipped: bool,
+    pub show_skipped_projects: bool,
+    pub footer_breakdown: bool,
+    /// `--shuffle`'s seed, printed under the footer's time.
+    pub shuffle_seed: Option<u64>,
+    pub group_by: GroupBy,
+    /// `--max-reported-failures`: failures rendered in full before the rest become an index.
+    pub max_reported_failures: Option<usize>,
+    /// `--path-style` / `--strip-path-prefix`: how suite paths are shown.
+    pub path_display: PathDisplay,
+    /// Where the failing suites' log files are, for the `logs:` line under their failure block.
+    pub suite_log_dir: Option<String>,
+}
+
+impl Ctx {
+    pub fn stack_frame_limit(&self, default_limit: usize) -> usize {
+        if self.full_stacks {
+            usize::MAX
+        } else {
+            default_limit
+        }
+    }
+
+    pub fn is_shown_stack_frame(&self, line: &str) -> bool {
+        self.full_stacks || self.project_hint.is_match(line)
+    }
 }
 
 pub fn make_ctx(
@@ -34,6 +65,36 @@ pub fn make_ctx(
         show_logs,
         project_hint: hint,
         editor_cmd,
+        full_stacks: false,
+        show_skipped: false,
+        show_skipped_projects: false,
+        footer_breakdown: false,
+        shuffle_seed: None,
+        group_by: GroupBy::File,
+        max_reported_failures: None,
+        path_display: PathDisplay::default(),
+        suite_log_dir: None,
+    }
+}
+
+pub fn make_ctx_for_args(cwd: &Path, args: &ParsedArgs, show_stacks: bool) -> Ctx {
+    Ctx {
+        full_stacks: args.full_stacks,
+        show_skipped: args.show_skipped,
+        show_skipped_projects: args.show_skipped_projects,
+        footer_breakdown: args.footer_breakdown,
+        shuffle_seed: args.shuffle_seed,
+        group_by: args.group_by,
+        max_reported_failures: args.max_reported_failures.map(|max| max as usize),
+        path_display: PathDisplay::for_args(args),
+        suite_log_dir: Some(crate::format::suite_log::shown_suite_logs_dir(args)),
+        ..make_ctx(
+            cwd,
+            None,
+            show_stacks,
+            args.show_logs,
+            args.editor_cmd.clone(),
+        )
     }
 }
 
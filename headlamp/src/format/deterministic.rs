@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+use crate::test_model::TestRunModel;
+
+/// Set while `--deterministic` is on: the root absolute paths are rewritten relative to.
+static DETERMINISTIC_ROOT: LazyLock<RwLock<Option<String>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Set while `--normalize-times` is on.
+static NORMALIZE_TIMES: AtomicBool = AtomicBool::new(false);
+
+pub const TIME_PLACEHOLDER: &str = "<time>";
+
+/// `--deterministic`: output of two runs over the same tree compares equal byte for byte.
+pub fn configure(enabled: bool, root: &Path) {
+    let root = enabled.then(|| {
+        let canonical = dunce::canonicalize(root).unwrap_or_else(|_| PathBuf::from(root));
+        canonical
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_end_matches('/')
+            .to_string()
+    });
+    if let Ok(mut guard) = DETERMINISTIC_ROOT.write() {
+        *guard = root;
+    }
+}
+
+pub fn is_enabled() -> bool {
+    DETERMINISTIC_ROOT
+        .read()
+        .ok()
+        .is_some_and(|guard| guard.is_some())
+}
+
+/// `--normalize-times`: just the timing half of `--deterministic`.
+pub fn configure_times(enabled: bool) {
+    NORMALIZE_TIMES.store(enabled, Ordering::Relaxed);
+}
+
+/// Durations render as `<time>` and written reports carry zeroed timings.
+pub fn times_normalized() -> bool {
+    NORMALIZE_TIMES.load(Ordering::Relaxed) || is_enabled()
+}
+
+/// Zeroes the start times, the run time and every test's duration; a no-op unless times are
+/// normalized.
+pub fn normalize_model_times(model: &mut TestRunModel) {
+    if !times_normalized() {
+        return;
+    }
+    model.start_time = 0;
+    model.aggregated.start_time = 0;
+    model.aggregated.run_time_ms = model.aggregated.run_time_ms.map(|_| 0);
+    model
+        .test_results
+        .iter_mut()
+        .flat_map(|suite| suite.test_results.iter_mut())
+        .for_each(|case| case.duration = 0);
+}
+
+/// Rewrites a rendered block for deterministic mode; a no-op otherwise. Paths under the root
+/// become relative and ANSI is dropped unless `FORCE_COLOR` asks for it.
+pub fn finalize(text: String) -> String {
+    let Some(root) = DETERMINISTIC_ROOT
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    else {
+        return text;
+    };
+    let relative = relativize(&text, &root);
+    if color_forced() {
+        relative
+    } else {
+        super::stacks::strip_ansi_simple(&relative)
+    }
+}
+
+fn relativize(text: &str, root: &str) -> String {
+    if root.is_empty() || root == "/" {
+        return text.to_string();
+    }
+    text.replace(&format!("{root}/"), "").replace(root, ".")
+}
+
+fn color_forced() -> bool {
+    std::env::var("FORCE_COLOR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .is_some_and(|value| !value.is_empty() && value != "0")
+}
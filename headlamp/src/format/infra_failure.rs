@@ -24,6 +24,9 @@ pub fn build_infra_failure_test_run_model(
                 location: None,
                 failure_messages: vec![failure_message.to_string()],
                 failure_details: None,
+                repro: None,
+                skip_reason: None,
+                attempts: None,
             }],
         }],
         aggregated: TestRunAggregated {
@@ -41,5 +44,6 @@ pub fn build_infra_failure_test_run_model(
             success: false,
             run_time_ms: Some(0),
         },
+        skipped_units: vec![],
     }
 }
@@ -23,6 +23,8 @@ enum LibtestJsonEvent {
         name: String,
         exec_time: Option<f64>,
         stdout: Option<String>,
+        /// The reason of an `#[ignore = "..."]` test.
+        message: Option<String>,
     },
     #[serde(other)]
     Other,
@@ -33,6 +35,7 @@ pub struct LibtestJsonStreamParser {
     repo_root: PathBuf,
     suite_source_path: String,
     tests_by_name: BTreeMap<String, TestCaseResult>,
+    started_tests: usize,
 }
 
 impl LibtestJsonStreamParser {
@@ -41,6 +44,7 @@ impl LibtestJsonStreamParser {
             repo_root: repo_root.to_path_buf(),
             suite_source_path: suite_source_path.to_string(),
             tests_by_name: BTreeMap::new(),
+            started_tests: 0,
         }
     }
 
@@ -59,11 +63,17 @@ impl LibtestJsonStreamParser {
                 name,
                 exec_time,
                 stdout,
-            } => self.handle_test_event(event, name, exec_time, stdout),
+                message,
+            } => self.handle_test_event(event, name, exec_time, stdout, message),
             LibtestJsonEvent::Other => None,
         }
     }
 
+    /// `started` events so far; the adapter turns the difference into running-test counts.
+    pub fn started_tests(&self) -> usize {
+        self.started_tests
+    }
+
     pub fn finalize(self) -> Option<TestRunModel> {
         let test_file_path = self.repo_root.join(&self.suite_source_path);
         let test_file_path = test_file_path.to_string_lossy().to_string();
@@ -104,6 +114,7 @@ impl LibtestJsonStreamParser {
                 success: failed == 0,
                 run_time_ms: None,
             },
+            skipped_units: vec![],
         })
     }
 
@@ -113,7 +124,12 @@ impl LibtestJsonStreamParser {
         name: String,
         exec_time: Option<f64>,
         stdout: Option<String>,
+        message: Option<String>,
     ) -> Option<LibtestJsonStreamUpdate> {
+        if event == "started" {
+            self.started_tests += 1;
+            return None;
+        }
         let status = match event.as_str() {
             "ok" => "passed",
             "failed" => "failed",
@@ -139,9 +155,13 @@ impl LibtestJsonStreamParser {
                     location: None,
                     failure_messages: vec![],
                     failure_details: None,
+                    repro: Some(crate::repro::cargo_test(&self.suite_source_path, &name)),
+                    skip_reason: None,
+                    attempts: None,
                 });
 
         test_case.status = status.clone();
+        test_case.skip_reason = message.filter(|reason| event == "ignored" && !reason.is_empty());
         test_case.duration = duration_ms;
 
         if test_case.status == "failed" {
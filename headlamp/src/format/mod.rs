@@ -2,20 +2,30 @@ pub mod ansi;
 pub mod bridge;
 pub mod bridge_console;
 pub mod bridge_http;
+pub mod cargo_build;
+pub mod cargo_doctest;
 pub mod cargo_test;
 pub mod codeframe;
 pub mod colors;
 pub mod console;
 pub mod ctx;
 pub mod details;
+pub mod deterministic;
 pub mod failure_diagnostics;
 pub mod fns;
 pub mod infra_failure;
 pub mod libtest_json;
 pub mod nextest;
+pub mod nextest_status;
+pub mod output_filter;
+pub mod path_display;
 pub mod paths;
 pub mod raw_jest;
+pub mod report_out;
+pub mod rust_test_location;
+pub mod source_map;
 pub mod stacks;
+pub mod suite_log;
 pub mod terminal;
 pub mod time;
 pub mod unstructured_engine;
@@ -59,6 +59,8 @@ enum NextestEvent {
         name: String,
         exec_time: Option<f64>,
         stdout: Option<String>,
+        /// The reason of an `#[ignore = "..."]` test.
+        message: Option<String>,
     },
 }
 
@@ -75,6 +77,10 @@ pub struct NextestStreamParser {
     suites_by_key: BTreeMap<SuiteKey, SuiteAcc>,
     kind_by_crate_and_binary: BTreeMap<(String, String), String>,
     loose_log_lines: Vec<String>,
+    /// From the status lines, by binary id and test name: the most tries seen, and whether nextest
+    /// killed the test for running too long.
+    tries: BTreeMap<(String, String), (u32, bool)>,
+    started_tests: usize,
 }
 
 impl NextestStreamParser {
@@ -84,11 +90,22 @@ impl NextestStreamParser {
             suites_by_key: BTreeMap::new(),
             kind_by_crate_and_binary: BTreeMap::new(),
             loose_log_lines: vec![],
+            tries: BTreeMap::new(),
+            started_tests: 0,
         }
     }
 
     pub fn push_line(&mut self, line: &str) -> Option<NextestStreamUpdate> {
         let trimmed = line.trim();
+        if let Some(status) = super::nextest_status::parse_status_line(trimmed) {
+            let entry = self
+                .tries
+                .entry((status.binary_id.clone(), status.test_name.clone()))
+                .or_insert((1, false));
+            entry.0 = entry.0.max(status.attempt.unwrap_or(1));
+            entry.1 |= status.timed_out();
+            return None;
+        }
         let event = parse_nextest_event(trimmed, &mut self.loose_log_lines)?;
         match event {
             NextestEvent::Suite {
@@ -110,10 +127,16 @@ impl NextestStreamParser {
                 name,
                 exec_time,
                 stdout,
-            } => self.handle_test_event(event, name, exec_time, stdout),
+                message,
+            } => self.handle_test_event(event, name, exec_time, stdout, message),
         }
     }
 
+    /// `started` events so far; the adapter turns the difference into running-test counts.
+    pub fn started_tests(&self) -> usize {
+        self.started_tests
+    }
+
     pub fn finalize(mut self) -> Option<TestRunModel> {
         if !self.loose_log_lines.is_empty()
             && let Some((_key, first_suite)) = self.suites_by_key.iter_mut().next()
@@ -126,9 +149,11 @@ impl NextestStreamParser {
                     origin: Some("cargo-nextest".to_string()),
                 }));
         };
+        let tries = self.tries;
         let suites = self
             .suites_by_key
             .into_values()
+            .map(|suite| apply_tries(suite, &tries))
             .map(|suite| finalize_suite(&self.repo_root, suite))
             .filter(|suite| !suite.test_results.is_empty())
             .collect::<Vec<_>>();
@@ -186,7 +211,12 @@ impl NextestStreamParser {
         name: String,
         exec_time: Option<f64>,
         stdout: Option<String>,
+        message: Option<String>,
     ) -> Option<NextestStreamUpdate> {
+        if event == "started" {
+            self.started_tests += 1;
+            return None;
+        }
         if !matches!(event.as_str(), "ok" | "failed" | "ignored") {
             return None;
         }
@@ -209,8 +239,9 @@ impl NextestStreamParser {
         let mut test_case = suite
             .tests
             .remove(&display_name)
-            .unwrap_or_else(|| empty_test_case(&display_name, duration_ms));
+            .unwrap_or_else(|| empty_test_case(&name, &display_name, duration_ms));
         test_case.status = status.to_string();
+        test_case.skip_reason = message.filter(|reason| event == "ignored" && !reason.is_empty());
         test_case.duration = duration_ms;
         update_failure_messages(&mut test_case, stdout.as_deref());
         update_location_if_matches_suite(&mut test_case, stdout.as_deref(), &suite_path);
@@ -241,7 +272,9 @@ fn duration_ms_from_exec_time(exec_time: Option<f64>) -> u64 {
         .unwrap_or(0)
 }
 
-fn empty_test_case(display_name: &str, duration_ms: u64) -> TestCaseResult {
+/// `full_name` is nextest's `binary_id$test::path`.
+fn empty_test_case(full_name: &str, display_name: &str, duration_ms: u64) -> TestCaseResult {
+    let binary_id = full_name.split_once('$').map_or(full_name, |(id, _)| id);
     TestCaseResult {
         title: display_name.to_string(),
         full_name: display_name.to_string(),
@@ -251,6 +284,9 @@ fn empty_test_case(display_name: &str, duration_ms: u64) -> TestCaseResult {
         location: None,
         failure_messages: vec![],
         failure_details: None,
+        repro: Some(crate::repro::nextest(binary_id, display_name)),
+        skip_reason: None,
+        attempts: None,
     }
 }
 
@@ -346,11 +382,32 @@ fn suite_key_from_test_name(
     })
 }
 
+/// A unit-test binary's id is the bare crate name.
+fn apply_tries(mut suite: SuiteAcc, tries: &BTreeMap<(String, String), (u32, bool)>) -> SuiteAcc {
+    let binary_ids = [
+        format!("{}::{}", suite.key.crate_name, suite.key.test_binary),
+        suite.key.crate_name.clone(),
+    ];
+    for (name, test_case) in suite.tests.iter_mut() {
+        let found = binary_ids
+            .iter()
+            .find_map(|id| tries.get(&(id.clone(), name.clone())));
+        if let Some((attempts, timed_out)) = found {
+            test_case.attempts = (*attempts > 1).then_some(*attempts);
+            if *timed_out {
+                test_case.timed_out = Some(true);
+            }
+        }
+    }
+    suite
+}
+
 fn finalize_suite(repo_root: &Path, suite: SuiteAcc) -> TestSuiteResult {
-    let tests = suite.tests.into_values().collect::<Vec<_>>();
+    let mut tests = suite.tests.into_values().collect::<Vec<_>>();
     let failed = tests.iter().filter(|t| t.status == "failed").count() as u64;
     let status = if failed > 0 { "failed" } else { "passed" }.to_string();
     let test_file_path = suite_display_path(repo_root, &suite.key);
+    crate::format::rust_test_location::fill_missing_test_fn_locations(&test_file_path, &mut tests);
     TestSuiteResult {
         test_file_path,
         status,
@@ -432,5 +489,6 @@ fn build_run_model(suites: Vec<TestSuiteResult>) -> TestRunModel {
             success: failed_suites == 0 && failed_tests == 0,
             run_time_ms: None,
         },
+        skipped_units: vec![],
     }
 }
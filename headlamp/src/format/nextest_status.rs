@@ -0,0 +1,47 @@
+//! nextest's human status lines, which it prints next to the JSON events when a profile retries
+//! or times out tests (`--status-level retry`/`fail`):
+//!
+//! ```text
+//!    TRY 1 FAIL [   0.004s] my-crate::integration tests::flaky
+//!   RETRY 2/3 [         ] my-crate::integration tests::flaky
+//!    TRY 2 PASS [   0.003s] my-crate::integration tests::flaky
+//!      TIMEOUT [  60.002s] my-crate tests::hangs
+//! ```
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextestStatusLine {
+    /// `<crate>` for unit tests, `<crate>::<binary>` otherwise.
+    pub binary_id: String,
+    pub test_name: String,
+    pub status: String,
+    /// The try this line reports; `None` for a test's only try.
+    pub attempt: Option<u32>,
+}
+
+impl NextestStatusLine {
+    pub fn timed_out(&self) -> bool {
+        self.status == "TIMEOUT"
+    }
+}
+
+static STATUS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:TRY (\d+) )?(PASS|FAIL|TIMEOUT|SIGSEGV|SIGABRT|SIGBUS|SIGILL|ABORT|LEAK|LEAK-FAIL|FLAKY|RETRY|DELAY|SLOW)(?: (\d+)/\d+)? +\[[^\]]*\] +(\S+) +(.+)$",
+    )
+    .unwrap()
+});
+
+pub fn parse_status_line(line: &str) -> Option<NextestStatusLine> {
+    let caps = STATUS_RE.captures(line.trim())?;
+    let number = |index: usize| caps.get(index).and_then(|m| m.as_str().parse::<u32>().ok());
+    Some(NextestStatusLine {
+        binary_id: caps[4].to_string(),
+        test_name: caps[5].trim().to_string(),
+        status: caps[2].to_string(),
+        attempt: number(1).or_else(|| number(3)),
+    })
+}
@@ -0,0 +1,109 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+use regex::Regex;
+
+use super::console::ConsoleEntry;
+use super::stacks::strip_ansi_simple;
+
+/// `--output-filter` and `--output-collapse`: lines known to be noise (jsdom navigation warnings,
+/// cargo future-incompat notes) dropped from passthrough and captured console output.
+#[derive(Debug, Default)]
+struct OutputFilters {
+    drop: Vec<Regex>,
+    /// Each pattern's first match in a run is kept; the rest are dropped.
+    collapse: Vec<(Regex, AtomicBool)>,
+}
+
+static FILTERS: LazyLock<RwLock<OutputFilters>> =
+    LazyLock::new(|| RwLock::new(OutputFilters::default()));
+
+static SUPPRESSED: AtomicUsize = AtomicUsize::new(0);
+
+/// Compiles the patterns; the first invalid one is the error.
+pub fn configure(drop: &[String], collapse: &[String]) -> Result<(), String> {
+    let compile = |pattern: &String| {
+        Regex::new(pattern).map_err(|err| format!("invalid output filter {pattern:?}: {err}"))
+    };
+    let filters = OutputFilters {
+        drop: drop.iter().map(compile).collect::<Result<_, _>>()?,
+        collapse: collapse
+            .iter()
+            .map(|pattern| compile(pattern).map(|regex| (regex, AtomicBool::new(false))))
+            .collect::<Result<_, _>>()?,
+    };
+    if let Ok(mut guard) = FILTERS.write() {
+        *guard = filters;
+    }
+    start_run();
+    Ok(())
+}
+
+/// Zeroes the suppressed count and lets every collapsed pattern show once more.
+pub fn start_run() {
+    SUPPRESSED.store(0, Ordering::Relaxed);
+    if let Ok(guard) = FILTERS.read() {
+        guard
+            .collapse
+            .iter()
+            .for_each(|(_, seen)| seen.store(false, Ordering::Relaxed));
+    }
+}
+
+/// Whether `line` (ANSI ignored) is filtered out; each suppressed line is counted.
+pub fn suppresses(line: &str) -> bool {
+    let Ok(guard) = FILTERS.read() else {
+        return false;
+    };
+    if guard.drop.is_empty() && guard.collapse.is_empty() {
+        return false;
+    }
+    let plain = strip_ansi_simple(line);
+    let suppressed = guard.drop.iter().any(|regex| regex.is_match(&plain))
+        || guard
+            .collapse
+            .iter()
+            .find(|(regex, _)| regex.is_match(&plain))
+            .is_some_and(|(_, seen)| seen.swap(true, Ordering::Relaxed));
+    if suppressed {
+        SUPPRESSED.fetch_add(1, Ordering::Relaxed);
+    }
+    suppressed
+}
+
+/// A suite's captured console without its filtered entries; a collapsed pattern keeps its first
+/// entry in the suite. Each dropped entry is counted.
+pub fn filter_console(entries: Vec<ConsoleEntry>) -> Vec<ConsoleEntry> {
+    let Ok(guard) = FILTERS.read() else {
+        return entries;
+    };
+    if guard.drop.is_empty() && guard.collapse.is_empty() {
+        return entries;
+    }
+    let mut collapsed = vec![false; guard.collapse.len()];
+    let before = entries.len();
+    let kept = entries
+        .into_iter()
+        .filter(|entry| {
+            let plain = strip_ansi_simple(entry.message.as_deref().unwrap_or_default());
+            if guard.drop.iter().any(|regex| regex.is_match(&plain)) {
+                return false;
+            }
+            match guard
+                .collapse
+                .iter()
+                .position(|(regex, _)| regex.is_match(&plain))
+            {
+                Some(index) => !std::mem::replace(&mut collapsed[index], true),
+                None => true,
+            }
+        })
+        .collect::<Vec<_>>();
+    SUPPRESSED.fetch_add(before - kept.len(), Ordering::Relaxed);
+    kept
+}
+
+/// Lines suppressed since the last `start_run`.
+pub fn suppressed_count() -> usize {
+    SUPPRESSED.load(Ordering::Relaxed)
+}
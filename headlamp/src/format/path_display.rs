@@ -0,0 +1,94 @@
+//! How rendered file paths read: `--path-style` picks what they are relative to, and each
+//! `--strip-path-prefix` glob (`packages/*/src/`) is cut from their start. Suite overview
+//! lines, failure headers and the coverage tables all go through [`PathDisplay::display`].
+
+use std::path::Path;
+
+use path_slash::PathExt;
+use regex::Regex;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathStyle {
+    /// Relative to the repo root.
+    #[default]
+    Relative,
+    Absolute,
+    /// Relative to the owning package (the nearest directory with a manifest).
+    PackageRelative,
+}
+
+impl PathStyle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Relative => "relative",
+            Self::Absolute => "absolute",
+            Self::PackageRelative => "package-relative",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathDisplay {
+    pub style: PathStyle,
+    strip_prefixes: Vec<Regex>,
+}
+
+impl PathDisplay {
+    /// Globs that fail to compile are dropped; `*` matches within one path segment, `**` across.
+    pub fn new(style: PathStyle, strip_prefixes: &[String]) -> Self {
+        Self {
+            style,
+            strip_prefixes: strip_prefixes
+                .iter()
+                .filter_map(|glob| prefix_regex(glob))
+                .collect(),
+        }
+    }
+
+    pub fn for_args(args: &crate::args::ParsedArgs) -> Self {
+        Self::new(args.path_style, &args.strip_path_prefixes)
+    }
+
+    /// `path` (absolute, or relative to `root`) the way it should be shown.
+    pub fn display(&self, path: &str, root: &str) -> String {
+        let path = Path::new(path).to_slash_lossy().to_string();
+        let rel = crate::format::paths::strip_root(Path::new(&path), Path::new(root))
+            .map(|rel| rel.to_slash_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let rel = rel.as_str();
+        let styled = match self.style {
+            PathStyle::Relative => rel.to_string(),
+            PathStyle::Absolute if Path::new(rel).is_relative() => {
+                format!("{}/{rel}", root.trim_end_matches('/'))
+            }
+            PathStyle::Absolute => rel.to_string(),
+            PathStyle::PackageRelative => package_relative(rel, root),
+        };
+        self.strip_prefixes
+            .iter()
+            .find_map(|prefix| {
+                let matched = prefix.find(&styled)?;
+                let rest = &styled[matched.end()..];
+                (!rest.is_empty()).then(|| rest.to_string())
+            })
+            .unwrap_or(styled)
+    }
+}
+
+fn package_relative(rel: &str, root: &str) -> String {
+    if Path::new(rel).is_absolute() {
+        return rel.to_string();
+    }
+    crate::coverage::scope::package_dir(Path::new(root), Path::new(rel))
+        .and_then(|dir| rel.strip_prefix(&format!("{dir}/")).map(str::to_string))
+        .unwrap_or_else(|| rel.to_string())
+}
+
+fn prefix_regex(glob: &str) -> Option<Regex> {
+    let pattern = regex::escape(glob.trim_start_matches("./"))
+        .replace(r"\*\*", ".*")
+        .replace(r"\*", "[^/]*");
+    Regex::new(&format!("^{pattern}")).ok()
+}
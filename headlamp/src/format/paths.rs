@@ -47,3 +47,17 @@ pub fn linkify_padded(
         " ".repeat(pad)
     )
 }
+
+/// `path` relative to `root`, also when only one of them was reached through a symlink; `None`
+/// for relative paths and paths outside the root.
+pub fn strip_root(path: &Path, root: &Path) -> Option<PathBuf> {
+    if let Ok(rel) = path.strip_prefix(root) {
+        return Some(rel.to_path_buf());
+    }
+    if path.is_relative() {
+        return None;
+    }
+    let path = crate::config::canonical_or_given(path);
+    let root = crate::config::canonical_or_given(root);
+    path.strip_prefix(&root).ok().map(Path::to_path_buf)
+}
@@ -200,7 +200,7 @@ fn render_failure_block(acc: &mut RenderChunksAcc, ctx: &Ctx, title: &str, lines
         colors::failure("×"),
         ansi::white(&header_text)
     ));
-    let collapsed = stacks::collapse_stacks(lines);
+    let collapsed = stacks::clean_stacks(lines, ctx.full_stacks);
     let deepest = fns::deepest_project_loc_resolved(&collapsed, &ctx.project_hint, &ctx.cwd).map(
         |(file, line, _)| codeframe::Loc {
             file,
@@ -272,8 +272,8 @@ fn push_stack_section(out: &mut Vec<String>, ctx: &Ctx, collapsed: &[String]) {
         .iter()
         .map(|ln| stacks::strip_ansi_simple(ln))
         .filter(|ln| stacks::is_stack_line(ln))
-        .filter(|ln| ctx.project_hint.is_match(ln))
-        .take(6)
+        .filter(|ln| ctx.is_shown_stack_frame(ln))
+        .take(ctx.stack_frame_limit(6))
         .collect::<Vec<_>>();
     if stack_lines.is_empty() {
         return;
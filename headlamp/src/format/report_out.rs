@@ -0,0 +1,31 @@
+//! Where a run's stdout goes: the live progress and the report. A quiet run (`api::run` with
+//! `RunRequest::quiet`) sends both nowhere and leaves the embedding program's stdout alone.
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Set as each run starts; the CLI never turns it on.
+pub fn configure_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `println!` for the report, dropped in a quiet run.
+pub fn println(text: impl std::fmt::Display) {
+    if !is_quiet() {
+        println!("{text}");
+    }
+}
+
+/// The process's stdout, or a sink in a quiet run.
+pub fn stdout() -> Box<dyn Write> {
+    if is_quiet() {
+        Box::new(std::io::sink())
+    } else {
+        Box::new(std::io::stdout())
+    }
+}
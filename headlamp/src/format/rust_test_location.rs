@@ -0,0 +1,38 @@
+//! A failure anchor for Rust tests whose output carries no usable `panicked at` location
+//! (stripped or captured output, a panic inside another thread or a helper crate): the test's
+//! own `fn` in its suite file, found by parsing the source rather than trusting a backtrace.
+
+use crate::test_model::{TestCaseResult, TestLocation};
+
+/// Sets `location` on each failed test of the suite at `suite_path` that has none, when its
+/// name (`tests::adds`, relative to the suite file) names a `#[test]` fn in that file.
+pub fn fill_missing_test_fn_locations(suite_path: &str, tests: &mut [TestCaseResult]) {
+    let is_missing = |test: &TestCaseResult| test.status == "failed" && test.location.is_none();
+    if !tests.iter().any(is_missing) {
+        return;
+    }
+    let Ok(source) = std::fs::read_to_string(suite_path) else {
+        return;
+    };
+    let test_fns = crate::rust_parse::find_test_fns(&source);
+    for test in tests.iter_mut().filter(|test| is_missing(test)) {
+        test.location = test_fns
+            .iter()
+            .find(|test_fn| test_fn.path == test.full_name)
+            .and_then(|test_fn| fn_keyword_location(&source, test_fn));
+    }
+}
+
+/// The `fn` of the item rather than its first attribute, so the anchor lands on the signature.
+fn fn_keyword_location(
+    source: &str,
+    test_fn: &crate::rust_parse::RustTestFn,
+) -> Option<TestLocation> {
+    let item = source.get(test_fn.start..test_fn.end)?;
+    let needle = format!("fn {}", test_fn.name);
+    let offset = test_fn.start + item.find(&needle).unwrap_or(0);
+    let before = &source[..offset];
+    let line = before.matches('\n').count() as i64 + 1;
+    let column = (before.len() - before.rfind('\n').map_or(0, |i| i + 1)) as i64 + 1;
+    Some(TestLocation { line, column })
+}
@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use dashmap::DashMap;
+use regex::Regex;
+use serde::Deserialize;
+
+static SOURCE_MAP_CACHE: LazyLock<DashMap<PathBuf, Option<Arc<SourceMap>>>> =
+    LazyLock::new(DashMap::new);
+
+static FRAME_LOC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:file://)?(?P<path>(?:[A-Za-z]:)?[^\s():]+\.[cm]?jsx?):(?P<line>\d+):(?P<col>\d+)",
+    )
+    .unwrap()
+});
+
+static SOURCE_MAPPING_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"//[#@]\s*sourceMappingURL=(\S+)\s*$").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginalLoc {
+    pub source: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    generated_column: u32,
+    source_index: u32,
+    original_line: u32,
+    original_column: u32,
+}
+
+#[derive(Debug)]
+pub struct SourceMap {
+    sources: Vec<String>,
+    lines: Vec<Vec<Segment>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    #[serde(default)]
+    source_root: Option<String>,
+    #[serde(default)]
+    sources: Vec<String>,
+    #[serde(default)]
+    mappings: String,
+}
+
+impl SourceMap {
+    pub fn parse(json: &str, map_dir: &Path) -> Option<Self> {
+        let raw = serde_json::from_str::<RawSourceMap>(json).ok()?;
+        let root = raw.source_root.unwrap_or_default();
+        let sources = raw
+            .sources
+            .iter()
+            .map(|source| resolve_source_path(map_dir, &root, source))
+            .collect::<Vec<_>>();
+        let lines = decode_mappings(&raw.mappings)?;
+        Some(Self { sources, lines })
+    }
+
+    /// Looks up a 1-based generated position and returns the 1-based original position.
+    pub fn lookup(&self, line: u32, column: u32) -> Option<OriginalLoc> {
+        let segments = self.lines.get(line.checked_sub(1)? as usize)?;
+        let column0 = column.saturating_sub(1);
+        let segment = segments
+            .iter()
+            .take_while(|segment| segment.generated_column <= column0)
+            .last()
+            .or_else(|| segments.first())?;
+        Some(OriginalLoc {
+            source: self.sources.get(segment.source_index as usize)?.clone(),
+            line: segment.original_line + 1,
+            column: segment.original_column + 1,
+        })
+    }
+}
+
+fn resolve_source_path(map_dir: &Path, source_root: &str, source: &str) -> String {
+    let stripped = source
+        .strip_prefix("webpack://")
+        .map(|rest| {
+            rest.trim_start_matches(|c: char| c != '/')
+                .trim_start_matches('/')
+        })
+        .unwrap_or(source);
+    let joined = map_dir.join(source_root).join(stripped);
+    dunce::canonicalize(&joined)
+        .unwrap_or(joined)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn decode_mappings(mappings: &str) -> Option<Vec<Vec<Segment>>> {
+    let mut state = [0i64; 4];
+    let mut lines: Vec<Vec<Segment>> = vec![];
+    for line_text in mappings.split(';') {
+        let mut generated_column = 0i64;
+        let mut segments: Vec<Segment> = vec![];
+        for segment_text in line_text.split(',').filter(|s| !s.is_empty()) {
+            let fields = decode_vlq_fields(segment_text)?;
+            generated_column += *fields.first()?;
+            if fields.len() < 4 {
+                continue;
+            }
+            fields[1..4]
+                .iter()
+                .enumerate()
+                .for_each(|(index, delta)| state[index] += delta);
+            segments.push(Segment {
+                generated_column: u32::try_from(generated_column).ok()?,
+                source_index: u32::try_from(state[0]).ok()?,
+                original_line: u32::try_from(state[1]).ok()?,
+                original_column: u32::try_from(state[2]).ok()?,
+            });
+        }
+        segments.sort_by_key(|segment| segment.generated_column);
+        lines.push(segments);
+    }
+    Some(lines)
+}
+
+fn decode_vlq_fields(text: &str) -> Option<Vec<i64>> {
+    let mut out: Vec<i64> = vec![];
+    let mut value = 0i64;
+    let mut shift = 0u32;
+    for byte in text.bytes() {
+        let digit = base64_digit(byte)? as i64;
+        value += (digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+            continue;
+        }
+        let magnitude = value >> 1;
+        out.push(if value & 1 == 1 {
+            -magnitude
+        } else {
+            magnitude
+        });
+        value = 0;
+        shift = 0;
+    }
+    Some(out)
+}
+
+fn base64_digit(byte: u8) -> Option<u8> {
+    Some(match byte {
+        b'A'..=b'Z' => byte - b'A',
+        b'a'..=b'z' => byte - b'a' + 26,
+        b'0'..=b'9' => byte - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    })
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let digits = text
+        .bytes()
+        .filter(|b| *b != b'=')
+        .map(base64_digit)
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        digits
+            .chunks(4)
+            .flat_map(|chunk| {
+                let bits = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |acc, (i, d)| acc | (u32::from(*d) << (18 - 6 * i)));
+                let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+                bytes.into_iter().take(chunk.len().saturating_sub(1))
+            })
+            .collect(),
+    )
+}
+
+fn load_source_map_for(generated: &Path) -> Option<Arc<SourceMap>> {
+    if let Some(cached) = SOURCE_MAP_CACHE.get(generated) {
+        return cached.clone();
+    }
+    let loaded = read_source_map_for(generated).map(Arc::new);
+    SOURCE_MAP_CACHE.insert(generated.to_path_buf(), loaded.clone());
+    loaded
+}
+
+fn read_source_map_for(generated: &Path) -> Option<SourceMap> {
+    let dir = generated.parent()?;
+    let sibling = PathBuf::from(format!("{}.map", generated.to_string_lossy()));
+    if let Ok(json) = std::fs::read_to_string(&sibling) {
+        return SourceMap::parse(&json, dir);
+    }
+    let contents = std::fs::read_to_string(generated).ok()?;
+    let url = contents
+        .lines()
+        .rev()
+        .find_map(|line| SOURCE_MAPPING_URL_RE.captures(line.trim()))
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())?;
+    if let Some((_, payload)) = url
+        .strip_prefix("data:application/json")
+        .and_then(|rest| rest.split_once("base64,"))
+    {
+        let json = String::from_utf8(decode_base64(payload)?).ok()?;
+        return SourceMap::parse(&json, dir);
+    }
+    let map_path = dir.join(url);
+    let json = std::fs::read_to_string(&map_path).ok()?;
+    SourceMap::parse(&json, map_path.parent().unwrap_or(dir))
+}
+
+/// Rewrites the first `file.js:line:col` location in a stack frame to its original source
+/// location when a source map exists next to (or is referenced by) the generated file.
+pub fn remap_stack_line(line: &str) -> String {
+    match remap_stack_location(line) {
+        Some((generated, original)) => line.replacen(&generated, &original, 1),
+        None => line.to_string(),
+    }
+}
+
+/// The first `file.js:line:col` location in a stack frame, as written, and the original source
+/// location it maps to; `None` when the frame has no location or no source map covers it.
+pub fn remap_stack_location(line: &str) -> Option<(String, String)> {
+    let caps = FRAME_LOC_RE.captures(line)?;
+    let whole = caps.get(0)?;
+    let path = caps.name("path")?;
+    let line_no = caps.name("line")?.as_str().parse::<u32>().ok()?;
+    let col_no = caps.name("col")?.as_str().parse::<u32>().ok()?;
+    let original = load_source_map_for(Path::new(path.as_str()))?.lookup(line_no, col_no)?;
+    Some((
+        whole.as_str().to_string(),
+        format!("{}:{}:{}", original.source, original.line, original.column),
+    ))
+}
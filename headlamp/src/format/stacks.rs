@@ -1,7 +1,7 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::format::ansi;
+use crate::format::{ansi, source_map};
 
 pub fn strip_ansi_simple(text: &str) -> String {
     String::from_utf8_lossy(&strip_ansi_escapes::strip(text.as_bytes())).to_string()
@@ -52,7 +52,20 @@ pub fn first_test_location(lines: &[String], project_hint: &Regex) -> Option<Str
     None
 }
 
-pub fn collapse_stacks(lines: &[String]) -> Vec<String> {
+pub fn is_noisy_stack_line(line: &str) -> bool {
+    static NOISY_FRAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"node_modules/|\bat node:|\(node:|\(internal/|webpack/bootstrap|webpack/runtime|webpack:///webpack|@babel/runtime|regenerator-runtime",
+        )
+        .unwrap()
+    });
+    NOISY_FRAME_RE.is_match(line)
+}
+
+/// Cleans stack frames for display: source-maps transpiled locations back to their
+/// original sources and, unless `full_stacks` is set, collapses runtime/vendor frames
+/// into a single "… N stack frames hidden" line.
+pub fn clean_stacks(lines: &[String], full_stacks: bool) -> Vec<String> {
     let mut out: Vec<String> = vec![];
     let mut hidden = 0usize;
     let flush = |out: &mut Vec<String>, hidden: &mut usize| {
@@ -68,19 +81,34 @@ pub fn collapse_stacks(lines: &[String]) -> Vec<String> {
 
     for raw in lines {
         let ln = strip_ansi_simple(raw);
-        if is_stack_line(&ln) {
-            let noisy = ln.contains("node_modules/") || ln.contains(" at node:");
-            if noisy {
-                hidden += 1;
-                continue;
-            }
-            flush(&mut out, &mut hidden);
-            out.push(raw.clone());
-        } else {
+        if !is_stack_line(&ln) {
             flush(&mut out, &mut hidden);
             out.push(raw.clone());
+            continue;
+        }
+        if !full_stacks && is_noisy_stack_line(&ln) {
+            hidden += 1;
+            continue;
         }
+        flush(&mut out, &mut hidden);
+        out.push(restyled_remap(raw, &ln));
     }
     flush(&mut out, &mut hidden);
     out
 }
+
+/// `raw` with its frame location remapped in place, keeping the frame's styling; the plain
+/// remapped frame when styling splits the location.
+fn restyled_remap(raw: &str, plain: &str) -> String {
+    match source_map::remap_stack_location(plain) {
+        Some((generated, original)) if raw.contains(&generated) => {
+            raw.replacen(&generated, &original, 1)
+        }
+        Some((generated, original)) => plain.replacen(&generated, &original, 1),
+        None => raw.to_string(),
+    }
+}
+
+pub fn collapse_stacks(lines: &[String]) -> Vec<String> {
+    clean_stacks(lines, false)
+}
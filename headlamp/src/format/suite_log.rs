@@ -0,0 +1,96 @@
+//! Per-suite log files: a failing suite's captured console output, written in full next to the
+//! runner logs and referenced from its failure block (`logs: …/src__api__user.test.ts.log`),
+//! since the inline console section is truncated.
+
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+
+use crate::args::ParsedArgs;
+use crate::format::bridge_console::parse_bridge_console;
+use crate::test_model::{TestRunModel, TestSuiteResult};
+
+/// Under the shared `headlamp` temp dir, like the runner logs, so the paths printed during the
+/// run still exist after it.
+pub fn suite_logs_dir() -> PathBuf {
+    std::env::temp_dir()
+        .join("headlamp")
+        .join("logs")
+        .join("suites")
+}
+
+/// Where the failure block says the logs are: inside `--artifacts-dir`, which they are collected
+/// into as `logs/suites/`, else `suite_logs_dir`.
+pub fn shown_suite_logs_dir(args: &ParsedArgs) -> String {
+    match args.artifacts_dir.as_deref() {
+        Some(dir) => Path::new(dir)
+            .join("logs")
+            .join("suites")
+            .to_slash_lossy()
+            .to_string(),
+        None => suite_logs_dir().to_string_lossy().to_string(),
+    }
+}
+
+/// `src/api/user.test.ts` → `src__api__user.test.ts.log`, from the path relative to `root`.
+pub fn suite_log_file_name(test_file_path: &str, root: &Path) -> String {
+    let path = Path::new(test_file_path);
+    let relative = crate::format::paths::strip_root(path, root).unwrap_or(path.to_path_buf());
+    let name = relative
+        .to_slash_lossy()
+        .trim_start_matches('/')
+        .replace('/', "__")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{name}.log")
+}
+
+/// `None` for suites that passed or captured nothing: only failures get a log.
+pub fn suite_log_contents(suite: &TestSuiteResult) -> Option<String> {
+    let failed =
+        suite.status == "failed" || suite.test_results.iter().any(|t| t.status == "failed");
+    let (_, _, entries) = parse_bridge_console(suite.console.as_ref());
+    if !failed || entries.is_empty() {
+        return None;
+    }
+    let lines = entries.iter().map(|entry| {
+        let type_name = entry.type_name.as_deref().unwrap_or("log").to_lowercase();
+        let message =
+            crate::format::stacks::strip_ansi_simple(entry.message.as_deref().unwrap_or(""));
+        match entry.origin.as_deref().filter(|origin| !origin.is_empty()) {
+            Some(origin) => format!("{type_name}: {message} ({origin})"),
+            None => format!("{type_name}: {message}"),
+        }
+    });
+    Some(
+        std::iter::once(format!("# {}", suite.test_file_path))
+            .chain(lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n",
+    )
+}
+
+/// Writes every failing suite's log into `dir`; the paths written, for registering as artifacts.
+/// A suite whose log cannot be written just has none.
+pub fn write_suite_logs(dir: &Path, root: &Path, models: &[TestRunModel]) -> Vec<PathBuf> {
+    models
+        .iter()
+        .flat_map(|model| &model.test_results)
+        .filter_map(|suite| {
+            let contents = suite_log_contents(suite)?;
+            let path = dir.join(suite_log_file_name(&suite.test_file_path, root));
+            std::fs::create_dir_all(dir)
+                .and_then(|()| std::fs::write(&path, contents))
+                .ok()
+                .map(|()| path)
+        })
+        .collect()
+}
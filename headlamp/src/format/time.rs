@@ -49,6 +49,9 @@ pub fn format_duration_at_least(duration: Duration, min_unit: TimeUnit) -> Strin
 }
 
 pub fn format_duration_with(duration: Duration, options: PrettyDurationOptions) -> String {
+    if super::deterministic::times_normalized() {
+        return super::deterministic::TIME_PLACEHOLDER.to_string();
+    }
     if duration.is_zero() {
         return format_less_than_one(options.min_unit.unwrap_or(TimeUnit::Nanosecond));
     }
@@ -30,6 +30,7 @@ pub enum ParsedTestLine {
         name: String,
         status: String,
         duration: Option<std::time::Duration>,
+        skip_reason: Option<String>,
     },
     Pending {
         name: String,
@@ -61,6 +62,11 @@ pub trait UnstructuredDialect {
     fn is_output_section_header(&self, line: &str) -> Option<String>;
 
     fn should_keep_as_console_line(&self, line: &str) -> bool;
+
+    /// Builds `TestCaseResult::repro` for a test in the suite at `suite_source_path`.
+    fn repro_command(&self, _suite_source_path: &str, _test_name: &str) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -136,6 +142,7 @@ impl<D: UnstructuredDialect> UnstructuredStreamParser<D> {
                     name,
                     status,
                     duration,
+                    ..
                 } => {
                     state.active_output_test_name = None;
                     let abs_suite_path =
@@ -213,6 +220,9 @@ fn parse_suite_block<D: UnstructuredDialect>(
     let mut acc = parse_suite_lines(dialect, &block.lines);
     apply_failure_messages(&mut acc.tests, &acc.failures_by_name);
     apply_failure_locations(repo_root, &block.source_path, &mut acc.tests);
+    acc.tests.iter_mut().for_each(|test| {
+        test.repro = dialect.repro_command(&block.source_path, &test.full_name);
+    });
     let suite_failed = acc.tests.iter().any(|t| t.status == "failed");
 
     TestSuiteResult {
@@ -287,10 +297,12 @@ fn apply_parsed_test_line<D: UnstructuredDialect>(
             name,
             status,
             duration,
+            skip_reason,
         } => {
             acc.last_pending_test_index = None;
             let mut test_case = empty_test_case(name, status);
             test_case.duration = duration.map(|d| d.as_millis() as u64).unwrap_or(0);
+            test_case.skip_reason = skip_reason;
             acc.tests.push(test_case);
         }
         ParsedTestLine::Pending {
@@ -347,6 +359,9 @@ fn empty_test_case(full_name: String, status: String) -> TestCaseResult {
         location: None,
         failure_messages: vec![],
         failure_details: None,
+        repro: None,
+        skip_reason: None,
+        attempts: None,
     }
 }
 
@@ -426,7 +441,7 @@ fn apply_failure_locations(
     });
 }
 
-fn build_test_run_model(suites: Vec<TestSuiteResult>) -> TestRunModel {
+pub(crate) fn build_test_run_model(suites: Vec<TestSuiteResult>) -> TestRunModel {
     let aggregated = suites.iter().fold(
         TestRunAggregated {
             num_total_test_suites: 0,
@@ -475,5 +490,6 @@ fn build_test_run_model(suites: Vec<TestSuiteResult>) -> TestRunModel {
         start_time: 0,
         test_results: suites,
         aggregated,
+        skipped_units: vec![],
     }
 }
@@ -31,7 +31,7 @@ pub(super) fn render_failed_assertion(
             .or(file.failure_details.as_ref()),
     );
     let messages_array = merge_msg_lines(&primary_block, &detail_msgs);
-    let merged_for_stack = merged_for_stack_for_failed_assertion(&messages_array, &stacks);
+    let merged_for_stack = merged_for_stack_for_failed_assertion(&messages_array, &stacks, ctx);
     let deepest = crate::format::fns::deepest_project_loc_resolved(
         &merged_for_stack,
         &ctx.project_hint,
@@ -59,11 +59,25 @@ pub(super) fn render_failed_assertion(
         http_sorted,
     );
     out.extend(build_console_section(console_list, ctx.show_logs));
+    out.extend(repro_lines(assertion));
     out.push(draw_fail_line(ctx.width));
     out.push(String::new());
     out
 }
 
+fn repro_lines(assertion: &crate::test_model::TestCaseResult) -> Vec<String> {
+    assertion
+        .repro
+        .as_deref()
+        .map(|command| {
+            vec![
+                format!("{}{command}", ansi::dim("    Reproduce: ")),
+                String::new(),
+            ]
+        })
+        .unwrap_or_default()
+}
+
 fn failed_assertion_prelude_lines(
     ctx: &Ctx,
     header: &str,
@@ -102,7 +116,7 @@ fn maybe_push_failed_assertion_stack_sections(
     if !ctx.show_stacks {
         return;
     }
-    let synth = synth_loc_for_failed_assertion(file, assertion, deepest);
+    let synth = synth_loc_for_failed_assertion(file, assertion, deepest, messages_array, ctx);
     out.extend(build_code_frame_section(
         messages_array,
         ctx.show_stacks,
@@ -153,13 +167,15 @@ fn primary_block_for_failed_assertion(
 fn merged_for_stack_for_failed_assertion(
     messages_array: &[String],
     stacks: &[String],
+    ctx: &Ctx,
 ) -> Vec<String> {
-    crate::format::stacks::collapse_stacks(
+    crate::format::stacks::clean_stacks(
         &messages_array
             .iter()
             .chain(stacks.iter())
             .cloned()
             .collect::<Vec<_>>(),
+        ctx.full_stacks,
     )
 }
 
@@ -182,15 +198,24 @@ fn synth_loc_for_failed_assertion(
     file: &crate::test_model::TestSuiteResult,
     assertion: &crate::test_model::TestCaseResult,
     deepest: Option<&(String, i64, i64)>,
+    messages_array: &[String],
+    ctx: &Ctx,
 ) -> Option<Loc> {
-    assertion
-        .location
-        .as_ref()
-        .filter(|loc| loc.line > 0)
-        .map(|loc| Loc {
-            file: file.test_file_path.replace('\\', "/"),
-            line: loc.line,
-            column: None,
+    let test_file = file.test_file_path.replace('\\', "/");
+    crate::format::codeframe::failure_message_location(messages_array, &ctx.cwd)
+        .or_else(|| {
+            assertion
+                .location
+                .as_ref()
+                .filter(|loc| loc.line > 0)
+                .map(|loc| Loc {
+                    file: crate::format::failure_diagnostics::resolve_existing_path_best_effort(
+                        &ctx.cwd, &test_file,
+                    )
+                    .unwrap_or(test_file),
+                    line: loc.line,
+                    column: None,
+                })
         })
         .or_else(|| {
             deepest.map(|(file, line, _)| Loc {
@@ -262,8 +287,8 @@ fn build_stack_preview(merged_for_stack: &[String], ctx: &Ctx) -> Vec<String> {
         .iter()
         .map(|ln| crate::format::stacks::strip_ansi_simple(ln))
         .filter(|ln| crate::format::stacks::is_stack_line(ln))
-        .filter(|ln| ctx.project_hint.is_match(ln))
-        .take(2)
+        .filter(|ln| ctx.is_shown_stack_frame(ln))
+        .take(ctx.stack_frame_limit(2))
         .map(|ln| {
             format!(
                 "      {}",
@@ -332,7 +357,7 @@ fn render_stack_tail_like_legacy(merged_for_stack: &[String], ctx: &Ctx) -> Vec<
     let tail = only_stack
         .into_iter()
         .rev()
-        .take(4)
+        .take(ctx.stack_frame_limit(4))
         .collect::<Vec<_>>()
         .into_iter()
         .rev()
@@ -79,6 +79,10 @@ fn inline_synth_loc(
     stacks: &[String],
     ctx: &Ctx,
 ) -> Option<Loc> {
+    if let Some(loc) = crate::format::codeframe::failure_message_location(messages_array, &ctx.cwd)
+    {
+        return Some(loc);
+    }
     let stack_loc = messages_array
         .iter()
         .chain(stacks.iter())
@@ -127,7 +131,7 @@ fn render_ts_style_assertion_failure(
         out.push(ansi::dim("    Stack:"));
         stacks
             .iter()
-            .take(6)
+            .take(ctx.stack_frame_limit(6))
             .for_each(|ln| out.push(format!("            {}", ln.trim_start())));
         out.push(String::new());
     }
@@ -0,0 +1,132 @@
+//! `--footer-breakdown`: the footer's totals split by the unit each test file belongs to — its
+//! jest project, cargo package or pytest rootdir — with each unit's counts and time, so a
+//! monorepo log shows at a glance whose tests failed and where the time went.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::format::ansi;
+use crate::format::colors;
+use crate::format::ctx::Ctx;
+use crate::format::time::format_duration;
+use crate::test_model::TestSuiteResult;
+
+const JEST_CONFIGS: [&str; 5] = [
+    "jest.config.js",
+    "jest.config.ts",
+    "jest.config.mjs",
+    "jest.config.cjs",
+    "jest.config.json",
+];
+const PYTEST_CONFIGS: [&str; 4] = ["pytest.ini", "pyproject.toml", "tox.ini", "setup.cfg"];
+
+#[derive(Debug, Default)]
+struct UnitTotals {
+    failed: usize,
+    passed: usize,
+    skipped: usize,
+    total: usize,
+    duration_ms: u64,
+}
+
+/// `By unit` and a line per unit, sorted by kind then name.
+pub(super) fn render_breakdown(suites: &[&TestSuiteResult], ctx: &Ctx) -> Vec<String> {
+    let root = Path::new(&ctx.cwd);
+    let mut units = BTreeMap::<(&'static str, String), UnitTotals>::new();
+    suites.iter().for_each(|suite| {
+        let totals = units
+            .entry(unit_of(&root.join(&suite.test_file_path), root))
+            .or_default();
+        suite.test_results.iter().for_each(|test| {
+            totals.total += 1;
+            totals.duration_ms += test.duration;
+            match test.status.as_str() {
+                "failed" => totals.failed += 1,
+                "passed" => totals.passed += 1,
+                "pending" | "todo" => totals.skipped += 1,
+                _ => {}
+            }
+        });
+        if suite.status == "failed" && suite.test_results.is_empty() {
+            totals.failed += 1;
+        }
+    });
+    if units.is_empty() {
+        return vec![];
+    }
+    let width = units
+        .keys()
+        .map(|(kind, name)| kind.len() + name.len() + 1)
+        .max()
+        .unwrap_or(0);
+    std::iter::once(String::new())
+        .chain(std::iter::once(ansi::bold("By unit")))
+        .chain(units.iter().map(|((kind, name), totals)| {
+            let padding = " ".repeat(width - (kind.len() + name.len() + 1));
+            format!(
+                "  {} {}{padding}  {} {}  {}",
+                ansi::dim(kind),
+                ansi::bold(name),
+                unit_counts(totals),
+                ansi::dim(&format!("({})", totals.total)),
+                format_duration(std::time::Duration::from_millis(totals.duration_ms))
+            )
+        }))
+        .collect()
+}
+
+fn unit_counts(totals: &UnitTotals) -> String {
+    [
+        (totals.failed > 0).then(|| colors::failure(&format!("{} failed", totals.failed))),
+        (totals.passed > 0).then(|| colors::success(&format!("{} passed", totals.passed))),
+        (totals.skipped > 0).then(|| colors::skip(&format!("{} skipped", totals.skipped))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(&ansi::dim(" | "))
+}
+
+/// Found from the file's ancestors (within `root`): the nearest jest config for JS/TS, the
+/// nearest `Cargo.toml` naming a package for Rust, and for Python the rootdir pytest would pick,
+/// the nearest dir holding one of its config files. `.` when nothing claims the file.
+fn unit_of(test_file: &Path, root: &Path) -> (&'static str, String) {
+    let nearest = |names: &[&str]| {
+        test_file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| {
+                names
+                    .iter()
+                    .map(|name| dir.join(name))
+                    .find(|path| path.is_file())
+            })
+    };
+    let relative = |path: &Path| {
+        path.strip_prefix(root)
+            .ok()
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .filter(|rel| !rel.is_empty())
+            .unwrap_or_else(|| ".".to_string())
+    };
+    match test_file.extension().and_then(|ext| ext.to_str()) {
+        Some("rs") => {
+            let name = test_file
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(root))
+                .filter(|dir| dir.join("Cargo.toml").is_file())
+                .find_map(|dir| super::grouping::manifest_package_name(&dir.join("Cargo.toml")));
+            ("cargo package", name.unwrap_or_else(|| ".".to_string()))
+        }
+        Some("py") => {
+            let dir = nearest(&PYTEST_CONFIGS).and_then(|config| config.parent().map(relative));
+            ("pytest rootdir", dir.unwrap_or_else(|| ".".to_string()))
+        }
+        _ => {
+            let config = nearest(&JEST_CONFIGS).map(|config| relative(&config));
+            ("jest project", config.unwrap_or_else(|| ".".to_string()))
+        }
+    }
+}
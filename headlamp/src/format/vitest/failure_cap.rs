@@ -0,0 +1,71 @@
+//! `--max-reported-failures=<n>`: the first `n` failures (in render order) get their detailed
+//! blocks; each later one is listed on one line after the results. The model itself is not
+//! trimmed, so reports and artifacts still carry every failure in full.
+
+use std::cell::{Cell, RefCell};
+
+use crate::format::ansi;
+use crate::format::colors;
+use crate::format::ctx::Ctx;
+
+#[derive(Debug)]
+pub(super) struct FailureBudget {
+    max: Option<usize>,
+    detailed: Cell<usize>,
+    overflow: RefCell<Vec<String>>,
+}
+
+impl FailureBudget {
+    pub(super) fn new(ctx: &Ctx) -> Self {
+        Self {
+            max: ctx.max_reported_failures,
+            detailed: Cell::new(0),
+            overflow: RefCell::new(vec![]),
+        }
+    }
+
+    /// Whether another failure still gets its detailed block.
+    pub(super) fn has_room(&self) -> bool {
+        self.max.is_none_or(|max| self.detailed.get() < max)
+    }
+
+    /// Takes a detailed slot for the failure of `name` in `rel_path` (`None`: the file itself),
+    /// or lists it in the index when none is left.
+    pub(super) fn admit(&self, rel_path: &str, name: Option<&str>) -> bool {
+        if self.has_room() {
+            self.detailed.set(self.detailed.get() + 1);
+            return true;
+        }
+        let label = match name {
+            Some(name) => format!(
+                "{} {}",
+                ansi::white(rel_path),
+                ansi::dim(&format!("› {name}"))
+            ),
+            None => ansi::white(rel_path),
+        };
+        self.overflow
+            .borrow_mut()
+            .push(format!("  {} {label}", colors::failure("×")));
+        false
+    }
+
+    /// `… 140 more failures (--max-reported-failures=20):` and one line per failure; empty
+    /// when every failure was detailed.
+    pub(super) fn render_index(&self) -> Vec<String> {
+        let overflow = self.overflow.borrow();
+        if overflow.is_empty() {
+            return vec![];
+        }
+        let count = overflow.len();
+        let header = format!(
+            "… {count} more failure{} (--max-reported-failures={}):",
+            if count == 1 { "" } else { "s" },
+            self.max.unwrap_or_default()
+        );
+        std::iter::once(colors::failure(&header))
+            .chain(overflow.iter().cloned())
+            .chain(std::iter::once(String::new()))
+            .collect()
+    }
+}
@@ -28,7 +28,7 @@ pub(super) fn render_file_level_failure(
             out.push(ansi::dim("    Stack:"));
             stacks
                 .iter()
-                .take(6)
+                .take(ctx.stack_frame_limit(6))
                 .for_each(|ln| out.push(format!("      {ln}")));
             out.push(String::new());
         }
@@ -1,14 +1,21 @@
+use std::time::Duration;
+
+use crate::args::ParsedArgs;
 use crate::format::ansi;
 use crate::format::colors;
 use crate::format::ctx::Ctx;
 use crate::format::fns::draw_rule;
 use crate::format::stacks;
 use crate::format::time::format_duration;
+use crate::recorded::RecordedResults;
 use crate::test_model::{TestRunAggregated, TestRunModel};
 
+use super::grouping::SuiteGroup;
+
 pub(super) fn render_footer(
     data: &TestRunModel,
     suites: &[&crate::test_model::TestSuiteResult],
+    groups: Option<&[SuiteGroup]>,
     ctx: &Ctx,
     only_failures: bool,
 ) -> Vec<String> {
@@ -20,7 +27,12 @@ pub(super) fn render_footer(
             filtered_agg.num_failed_tests
         };
     let timed_out_count = filtered_agg.num_timed_out_tests.unwrap_or(0);
-    let footer = vitest_footer(&filtered_agg, only_failures);
+    let flaky = flaky_tests(suites);
+    let footer = vitest_footer(&filtered_agg, only_failures, ctx.shuffle_seed, flaky.len());
+    let footer = match groups {
+        Some(groups) => with_groups_line(&footer, groups),
+        None => footer,
+    };
 
     let mut out: Vec<String> = vec![
         draw_rule(
@@ -32,6 +44,16 @@ pub(super) fn render_footer(
         String::new(),
         footer,
     ];
+    out.extend(flaky.iter().map(|(name, attempts)| {
+        format!(
+            "  {} {name} {}",
+            colors::warn("flaky"),
+            ansi::dim(&format!("(passed on try {attempts})"))
+        )
+    }));
+    if ctx.footer_breakdown {
+        out.extend(super::breakdown::render_breakdown(suites, ctx));
+    }
 
     if timed_out_count > 0 {
         out.push(String::new());
@@ -45,6 +67,40 @@ pub(super) fn render_footer(
     out
 }
 
+/// `headlamp: result=fail tests=184 failed=3 skipped=2 duration=93.4s coverage_lines=81.2%`:
+/// the whole run on one line for scripts, `coverage_lines` only when coverage was collected.
+pub fn summary_line(results: &RecordedResults, exit_code: i32, duration: Duration) -> String {
+    let result = if exit_code == 0 { "pass" } else { "fail" };
+    let tests = results.passed + results.failed + results.skipped;
+    let duration = if crate::format::deterministic::times_normalized() {
+        crate::format::deterministic::TIME_PLACEHOLDER.to_string()
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    };
+    let coverage = results.coverage_lines.map(|(covered, total)| {
+        let pct = if total == 0 {
+            100.0
+        } else {
+            covered as f64 * 100.0 / total as f64
+        };
+        format!(" coverage_lines={pct:.1}%")
+    });
+    format!(
+        "headlamp: result={result} tests={tests} failed={} skipped={} duration={duration}{}",
+        results.failed,
+        results.skipped,
+        coverage.unwrap_or_default()
+    )
+}
+
+/// `--summary-line`: printed to stdout after everything else, whatever the verbosity.
+pub fn print_summary_line_if_requested(args: &ParsedArgs, exit_code: i32, duration: Duration) {
+    if args.summary_line {
+        let results = crate::recorded::recorded_results();
+        crate::format::report_out::println(summary_line(&results, exit_code, duration));
+    }
+}
+
 fn aggregated_from_suites(
     suites: &[&crate::test_model::TestSuiteResult],
     run_time_ms: Option<u64>,
@@ -102,7 +158,25 @@ fn aggregated_from_suites(
     }
 }
 
-fn vitest_footer(agg: &TestRunAggregated, only_failures: bool) -> String {
+/// Tests that passed only after the runner retried them (nextest `retries`).
+fn flaky_tests(suites: &[&crate::test_model::TestSuiteResult]) -> Vec<(String, u32)> {
+    suites
+        .iter()
+        .flat_map(|suite| suite.test_results.iter())
+        .filter(|test| test.status == "passed")
+        .filter_map(|test| {
+            let attempts = test.attempts.filter(|attempts| *attempts > 1)?;
+            Some((test.full_name.clone(), attempts))
+        })
+        .collect()
+}
+
+fn vitest_footer(
+    agg: &TestRunAggregated,
+    only_failures: bool,
+    seed: Option<u64>,
+    flaky: usize,
+) -> String {
     let _ = only_failures;
 
     let files = vec![
@@ -123,6 +197,7 @@ fn vitest_footer(agg: &TestRunAggregated, only_failures: bool) -> String {
             .then(|| colors::failure(&format!("{} failed", agg.num_failed_tests))),
         (agg.num_passed_tests > 0)
             .then(|| colors::success(&format!("{} passed", agg.num_passed_tests))),
+        (flaky > 0).then(|| colors::warn(&format!("{flaky} flaky"))),
         (agg.num_pending_tests > 0)
             .then(|| colors::skip(&format!("{} skipped", agg.num_pending_tests))),
         (agg.num_todo_tests > 0).then(|| colors::todo(&format!("{} todo", agg.num_todo_tests))),
@@ -135,14 +210,7 @@ fn vitest_footer(agg: &TestRunAggregated, only_failures: bool) -> String {
     .collect::<Vec<_>>()
     .join(&ansi::dim(" | "));
 
-    let time_ms = agg.run_time_ms.unwrap_or_else(|| {
-        let now_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|duration| duration.as_millis() as u64)
-            .unwrap_or(agg.start_time);
-        now_ms.saturating_sub(agg.start_time)
-    });
-    let time = format_duration(std::time::Duration::from_millis(time_ms));
+    let time = run_time(agg);
 
     let footer = [
         format!(
@@ -159,11 +227,55 @@ fn vitest_footer(agg: &TestRunAggregated, only_failures: bool) -> String {
         ),
         format!("{}      {}", ansi::bold("Time"), time),
     ]
+    .into_iter()
+    .chain(seed.map(|seed| {
+        format!(
+            "{}      {} {}",
+            ansi::bold("Seed"),
+            seed,
+            ansi::dim(&format!("(--shuffle={seed} to reproduce)"))
+        )
+    }))
+    .collect::<Vec<_>>()
     .join("\n");
 
     drop_blank_line_before_time_line(&footer)
 }
 
+/// `Groups    1 failed | 4 passed (5)` under the `Test Files` line, for `--group-by`.
+fn with_groups_line(footer: &str, groups: &[SuiteGroup]) -> String {
+    let failed = groups.iter().filter(|group| group.failed()).count();
+    let passed = groups.len() - failed;
+    let counts = [
+        (failed > 0).then(|| colors::failure(&format!("{failed} failed"))),
+        (passed > 0).then(|| colors::success(&format!("{passed} passed"))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(&ansi::dim(" | "));
+    let line = format!(
+        "{}    {} {}",
+        ansi::bold("Groups"),
+        counts,
+        ansi::dim(&format!("({})", groups.len()))
+    );
+    let mut lines = footer.lines().map(str::to_string).collect::<Vec<_>>();
+    lines.insert(1.min(lines.len()), line);
+    lines.join("\n")
+}
+
+fn run_time(agg: &TestRunAggregated) -> String {
+    let time_ms = agg.run_time_ms.unwrap_or_else(|| {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(agg.start_time);
+        now_ms.saturating_sub(agg.start_time)
+    });
+    format_duration(std::time::Duration::from_millis(time_ms))
+}
+
 fn drop_blank_line_before_time_line(text: &str) -> String {
     let lines = text.lines().collect::<Vec<_>>();
     let kept = lines
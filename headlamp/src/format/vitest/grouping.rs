@@ -0,0 +1,186 @@
+//! `--group-by=suite|package`: results gathered under the describe block (or module path) a
+//! test is declared in, or under the package that owns its file, instead of file by file.
+//! Each group still renders its files as usual; a file with tests in several suites shows up
+//! once per suite, with just those tests.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::config::GroupBy;
+use crate::format::ansi;
+use crate::format::colors;
+use crate::format::ctx::Ctx;
+use crate::test_model::{TestCaseResult, TestSuiteResult};
+
+#[derive(Debug)]
+pub(super) struct SuiteGroup {
+    pub(super) name: String,
+    pub(super) suites: Vec<TestSuiteResult>,
+}
+
+impl SuiteGroup {
+    fn tests(&self) -> impl Iterator<Item = &TestCaseResult> {
+        self.suites
+            .iter()
+            .flat_map(|suite| suite.test_results.iter())
+    }
+
+    pub(super) fn failed(&self) -> bool {
+        self.suites.iter().any(|suite| suite.status == "failed")
+            || self.tests().any(|test| test.status == "failed")
+    }
+}
+
+/// `None` for the default file-by-file rendering.
+pub(super) fn group_suites(suites: &[&TestSuiteResult], ctx: &Ctx) -> Option<Vec<SuiteGroup>> {
+    let mut groups = BTreeMap::<String, Vec<TestSuiteResult>>::new();
+    match ctx.group_by {
+        GroupBy::File => return None,
+        GroupBy::Suite => suites.iter().for_each(|suite| {
+            split_by_suite_name(suite, ctx)
+                .into_iter()
+                .for_each(|(name, piece)| groups.entry(name).or_default().push(piece));
+        }),
+        GroupBy::Package => suites.iter().for_each(|suite| {
+            let name = package_name(Path::new(&suite.test_file_path), Path::new(&ctx.cwd));
+            groups.entry(name).or_default().push((*suite).clone());
+        }),
+    }
+    Some(
+        groups
+            .into_iter()
+            .map(|(name, suites)| SuiteGroup { name, suites })
+            .collect(),
+    )
+}
+
+/// `▸ payments 1 failed | 12 passed (13)`
+pub(super) fn render_group_header(group: &SuiteGroup) -> String {
+    let count = |status: &str| group.tests().filter(|test| test.status == status).count();
+    let counts = [
+        (
+            count("failed"),
+            colors::failure as fn(&str) -> String,
+            "failed",
+        ),
+        (count("passed"), colors::success, "passed"),
+        (count("pending"), colors::skip, "skipped"),
+        (count("todo"), colors::todo, "todo"),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(count, paint, label)| paint(&format!("{count} {label}")))
+    .collect::<Vec<_>>()
+    .join(&ansi::dim(" | "));
+    format!(
+        "{} {} {counts} {}",
+        ansi::dim("▸"),
+        ansi::bold(&group.name),
+        ansi::dim(&format!("({})", group.tests().count()))
+    )
+}
+
+/// The suite a test belongs to: what its full name adds before its title (jest's describe
+/// blocks), else the module path of a `a::b::test` name; tests with neither are grouped under
+/// their file.
+fn suite_name(test: &TestCaseResult) -> Option<String> {
+    let full_name = test.full_name.trim();
+    let title = test.title.trim();
+    let from_title = full_name
+        .strip_suffix(title)
+        .filter(|_| !title.is_empty())
+        .map(|prefix| prefix.trim_end_matches([' ', ':', '›', '>']).trim());
+    from_title
+        .filter(|prefix| !prefix.is_empty())
+        .or_else(|| full_name.rsplit_once("::").map(|(module, _)| module.trim()))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// One copy of the file per suite. A file-level failure (an `afterAll` error, say) stays with
+/// the first copy so it renders once.
+fn split_by_suite_name(suite: &TestSuiteResult, ctx: &Ctx) -> Vec<(String, TestSuiteResult)> {
+    let mut by_name = BTreeMap::<String, Vec<TestCaseResult>>::new();
+    suite.test_results.iter().for_each(|test| {
+        let name = suite_name(test).unwrap_or_else(|| relative(&suite.test_file_path, ctx));
+        by_name.entry(name).or_default().push(test.clone());
+    });
+    by_name
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, tests))| {
+            let keeps_file_failure = index == 0 && !suite.failure_message.trim().is_empty();
+            let piece = TestSuiteResult {
+                status: if keeps_file_failure {
+                    suite.status.clone()
+                } else {
+                    piece_status(&tests).to_string()
+                },
+                failure_message: if keeps_file_failure {
+                    suite.failure_message.clone()
+                } else {
+                    String::new()
+                },
+                console: suite.console.clone().filter(|_| index == 0),
+                test_results: tests,
+                ..suite.clone()
+            };
+            (name, piece)
+        })
+        .collect()
+}
+
+fn piece_status(tests: &[TestCaseResult]) -> &'static str {
+    if tests.iter().any(|test| test.status == "failed") {
+        "failed"
+    } else {
+        "passed"
+    }
+}
+
+fn relative(path: &str, ctx: &Ctx) -> String {
+    ctx.path_display.display(path, &ctx.cwd)
+}
+
+/// The `name` from the nearest `package.json`, `Cargo.toml` or `pyproject.toml` above the
+/// file (within `root`), else that manifest's directory; `.` when no manifest is found.
+fn package_name(test_file: &Path, root: &Path) -> String {
+    let test_file = root.join(test_file);
+    let manifest = test_file
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(root))
+        .find_map(|dir| {
+            ["package.json", "Cargo.toml", "pyproject.toml"]
+                .into_iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())
+        });
+    let Some(manifest) = manifest else {
+        return ".".to_string();
+    };
+    manifest_package_name(&manifest).unwrap_or_else(|| {
+        let dir = manifest.parent().unwrap_or(root);
+        dir.strip_prefix(root)
+            .ok()
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .filter(|rel| !rel.is_empty())
+            .unwrap_or_else(|| ".".to_string())
+    })
+}
+
+pub(super) fn manifest_package_name(manifest: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(manifest).ok()?;
+    let name = if manifest.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str::<serde_json::Value>(&text)
+            .ok()?
+            .get("name")?
+            .as_str()?
+            .to_string()
+    } else {
+        let table = text.parse::<toml::Table>().ok()?;
+        let section = table.get("package").or_else(|| table.get("project"))?;
+        section.get("name")?.as_str()?.to_string()
+    };
+    Some(name).filter(|name| !name.trim().is_empty())
+}
@@ -1,15 +1,23 @@
+use crate::format::ansi;
 use crate::format::bridge_console::parse_bridge_console;
 use crate::format::ctx::Ctx;
 use crate::format::fns::{build_file_badge_line, build_per_file_overview, render_run_line};
 use crate::test_model::TestRunModel;
 use path_slash::PathExt;
 use regex::Regex;
+use std::path::Path;
 use std::sync::LazyLock;
 
 mod assertions;
+mod breakdown;
 mod console;
+mod failure_cap;
 mod file_failure;
 mod footer;
+mod grouping;
+mod skipped;
+
+pub use footer::{print_summary_line_if_requested, summary_line};
 
 static CODE_FRAME_LINE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\s*(>?\s*\d+\s*\|)").unwrap());
@@ -19,18 +27,44 @@ pub fn render_vitest_from_test_model(
     ctx: &Ctx,
     only_failures: bool,
 ) -> String {
+    if crate::print_commands::is_active() {
+        return String::new();
+    }
     let mut lines: Vec<String> = vec![];
     render_run_header(&mut lines, ctx, only_failures);
     let suites = sorted_suites(data)
         .into_iter()
         .filter(|suite| !suite.test_results.is_empty())
         .collect::<Vec<_>>();
-    suites
-        .iter()
-        .copied()
-        .for_each(|suite| render_suite(&mut lines, suite, ctx, only_failures));
-    lines.extend(footer::render_footer(data, &suites, ctx, only_failures));
-    lines.join("\n")
+    let groups = grouping::group_suites(&suites, ctx);
+    let budget = failure_cap::FailureBudget::new(ctx);
+    match &groups {
+        None => suites
+            .iter()
+            .copied()
+            .for_each(|suite| render_suite(&mut lines, suite, ctx, only_failures, &budget)),
+        Some(groups) => groups
+            .iter()
+            .for_each(|group| render_group(&mut lines, group, ctx, only_failures, &budget)),
+    }
+    lines.extend(budget.render_index());
+    if ctx.show_skipped {
+        lines.extend(skipped::render_skipped_section(&suites, ctx));
+    }
+    if ctx.show_skipped_projects {
+        lines.extend(skipped::render_skipped_units_section(
+            &data.skipped_units,
+            ctx,
+        ));
+    }
+    lines.extend(footer::render_footer(
+        data,
+        &suites,
+        groups.as_deref(),
+        ctx,
+        only_failures,
+    ));
+    crate::format::deterministic::finalize(lines.join("\n"))
 }
 
 #[derive(Debug)]
@@ -63,23 +97,44 @@ fn sorted_suites(data: &TestRunModel) -> Vec<&crate::test_model::TestSuiteResult
     suites
 }
 
+fn render_group(
+    lines: &mut Vec<String>,
+    group: &grouping::SuiteGroup,
+    ctx: &Ctx,
+    only_failures: bool,
+    budget: &failure_cap::FailureBudget,
+) {
+    if only_failures && !group.failed() {
+        return;
+    }
+    lines.push(grouping::render_group_header(group));
+    group
+        .suites
+        .iter()
+        .for_each(|suite| render_suite(lines, suite, ctx, only_failures, budget));
+    lines.push(String::new());
+}
+
 fn render_suite(
     lines: &mut Vec<String>,
     suite: &crate::test_model::TestSuiteResult,
     ctx: &Ctx,
     only_failures: bool,
+    budget: &failure_cap::FailureBudget,
 ) {
     let suite_ctx = build_suite_render_ctx(suite, ctx, only_failures);
     maybe_render_per_file_overview(lines, suite, &suite_ctx.rel_path, only_failures);
     maybe_render_file_badge_and_console(lines, &suite_ctx, ctx, only_failures);
-    maybe_render_inline_failed_assertion(
-        lines,
-        suite,
-        &suite_ctx.assertions_sorted,
-        ctx,
-        only_failures,
-    );
-    if suite_ctx.has_suite_failure {
+    if budget.has_room() {
+        maybe_render_inline_failed_assertion(
+            lines,
+            suite,
+            &suite_ctx.assertions_sorted,
+            ctx,
+            only_failures,
+        );
+    }
+    if suite_ctx.has_suite_failure && budget.admit(&suite_ctx.rel_path, None) {
         lines.extend(file_failure::render_file_level_failure(
             suite,
             ctx,
@@ -90,6 +145,7 @@ fn render_suite(
         .assertions_sorted
         .iter()
         .filter(|assertion| assertion.status == "failed")
+        .filter(|assertion| budget.admit(&suite_ctx.rel_path, Some(&assertion.full_name)))
         .for_each(|assertion| {
             lines.extend(assertions::render_failed_assertion(
                 &suite_ctx.rel_path,
@@ -101,6 +157,18 @@ fn render_suite(
                 &suite_ctx.http_sorted,
             ));
         });
+    if let Some(line) = suite_log_line(suite, ctx) {
+        lines.push(line);
+    }
+}
+
+/// `logs: <dir>/<file>` for a failing suite whose console output was written to a log file.
+fn suite_log_line(suite: &crate::test_model::TestSuiteResult, ctx: &Ctx) -> Option<String> {
+    let dir = ctx.suite_log_dir.as_deref()?;
+    crate::format::suite_log::suite_log_contents(suite)?;
+    let name =
+        crate::format::suite_log::suite_log_file_name(&suite.test_file_path, Path::new(&ctx.cwd));
+    Some(format!("    {}", ansi::dim(&format!("logs: {dir}/{name}"))))
 }
 
 fn build_suite_render_ctx<'a>(
@@ -121,6 +189,7 @@ fn build_suite_render_ctx<'a>(
         failed_count
     };
     let (http, assertion_events, console_list) = parse_bridge_console(suite.console.as_ref());
+    let console_list = crate::format::output_filter::filter_console(console_list);
     let console_list = match (only_failures, badge_count > 0) {
         (true, true) => console::filter_console_to_failed_tests(suite, console_list),
         _ => console_list,
@@ -144,13 +213,7 @@ fn build_suite_render_ctx<'a>(
 }
 
 fn relativize_suite_path(abs_or_rel: &str, ctx: &Ctx) -> String {
-    let file_path = std::path::Path::new(abs_or_rel)
-        .to_slash_lossy()
-        .to_string();
-    file_path
-        .strip_prefix(&format!("{}/", ctx.cwd))
-        .unwrap_or(file_path.as_str())
-        .to_string()
+    ctx.path_display.display(abs_or_rel, &ctx.cwd)
 }
 
 fn has_suite_failure(suite: &crate::test_model::TestSuiteResult, failed_assertions: usize) -> bool {
@@ -167,11 +230,14 @@ fn maybe_render_per_file_overview(
     if only_failures {
         return;
     }
-    let assertions = suite
+    let mut assertions = suite
         .test_results
         .iter()
         .map(|a| (a.full_name.clone(), a.status.clone()))
         .collect::<Vec<_>>();
+    if crate::format::deterministic::is_enabled() {
+        assertions.sort_by(|left, right| left.0.cmp(&right.0));
+    }
     lines.extend(build_per_file_overview(rel, &assertions));
 }
 
@@ -0,0 +1,90 @@
+use crate::format::ansi;
+use crate::format::colors;
+use crate::format::ctx::Ctx;
+use crate::format::fns::draw_rule;
+use crate::test_model::{SkippedUnit, TestSuiteResult};
+
+/// `--show-skipped`: every skipped and todo test, one per line with its reason when the runner
+/// gave one. Empty when nothing was skipped.
+pub(super) fn render_skipped_section(suites: &[&TestSuiteResult], ctx: &Ctx) -> Vec<String> {
+    let entries = suites
+        .iter()
+        .flat_map(|suite| {
+            let rel_path = super::relativize_suite_path(&suite.test_file_path, ctx);
+            let mut skipped = suite
+                .test_results
+                .iter()
+                .filter(|test| test.status == "pending" || test.status == "todo")
+                .collect::<Vec<_>>();
+            skipped.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+            skipped
+                .into_iter()
+                .map(move |test| render_skipped_line(&rel_path, test))
+        })
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![
+        draw_rule(
+            ctx.width,
+            Some(&colors::bg_skip(&ansi::white(&format!(
+                " Skipped Tests {} ",
+                entries.len()
+            )))),
+        ),
+        String::new(),
+    ];
+    out.extend(entries);
+    out.push(String::new());
+    out
+}
+
+fn render_skipped_line(rel_path: &str, test: &crate::test_model::TestCaseResult) -> String {
+    let (marker, tag) = if test.status == "todo" {
+        (colors::todo("☐"), colors::todo("[todo]"))
+    } else {
+        (colors::skip("↓"), colors::skip("[skipped]"))
+    };
+    let name = format!(
+        "{} {} {}",
+        ansi::magenta(rel_path),
+        ansi::dim(">"),
+        test.full_name
+    );
+    match test.skip_reason.as_deref() {
+        Some(reason) => format!(
+            "  {marker} {name} {tag} {}",
+            ansi::dim(&format!("— {reason}"))
+        ),
+        None => format!("  {marker} {name} {tag}"),
+    }
+}
+
+/// `--show-skipped-projects`: the jest projects and cargo packages the selection matched no
+/// tests in, which therefore never ran. Empty when every unit ran.
+pub(super) fn render_skipped_units_section(units: &[SkippedUnit], ctx: &Ctx) -> Vec<String> {
+    if units.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![
+        draw_rule(
+            ctx.width,
+            Some(&colors::bg_skip(&ansi::white(&format!(
+                " Skipped Projects (no matching tests) {} ",
+                units.len()
+            )))),
+        ),
+        String::new(),
+    ];
+    out.extend(units.iter().map(|unit| {
+        format!(
+            "  {} {} {}",
+            colors::skip("↓"),
+            ansi::magenta(&unit.name),
+            colors::skip(&format!("[{}]", unit.kind.label()))
+        )
+    }));
+    out.push(String::new());
+    out
+}
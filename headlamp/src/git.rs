@@ -214,6 +214,14 @@ fn git_stdout_lines(repo_root: &Path, args: &[&str]) -> Result<Vec<String>, RunE
         .collect())
 }
 
+/// The short hash of `HEAD`; `None` outside a repo or before the first commit.
+pub fn head_commit(repo_root: &Path) -> Option<String> {
+    git_has_head(repo_root)
+        .then(|| git_stdout_trimmed(repo_root, &["rev-parse", "--short", "HEAD"]).ok())
+        .flatten()
+        .filter(|oid| !oid.is_empty())
+}
+
 fn git_stdout_trimmed(repo_root: &Path, args: &[&str]) -> Result<String, RunError> {
     git_stdout_lines(repo_root, args).map(|lines| lines.into_iter().next().unwrap_or_default())
 }
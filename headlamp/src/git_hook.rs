@@ -0,0 +1,181 @@
+//! `headlamp hook install [pre-commit|pre-push]`: writes a git hook that runs headlamp on what
+//! changed, failing fast and within a time budget, instead of glue maintained per repo.
+
+use std::path::{Path, PathBuf};
+
+use crate::repro::shell_quote;
+use crate::run::RunError;
+
+/// First line after the shebang; a hook carrying it is ours to overwrite.
+const MARKER: &str = "# Installed by `headlamp hook install`; run it again to update this hook.";
+
+/// The budget written into hooks unless `--max-duration` is passed to `hook install`.
+const DEFAULT_MAX_DURATION: &str = "2m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHookKind {
+    PreCommit,
+    PrePush,
+}
+
+impl GitHookKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreCommit => "pre-commit",
+            Self::PrePush => "pre-push",
+        }
+    }
+
+    /// A commit tests what is staged; a push tests everything on the branch.
+    fn changed_mode(self) -> &'static str {
+        match self {
+            Self::PreCommit => "staged",
+            Self::PrePush => "branch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookInstall {
+    pub kind: GitHookKind,
+    /// Replace a hook headlamp did not write.
+    pub force: bool,
+    /// Extra headlamp flags for the hook, e.g. `--runner=jest`; they win over the defaults.
+    pub extra_args: Vec<String>,
+}
+
+/// `headlamp hook install ...`. Returns `None` when `argv` is an ordinary test run.
+pub fn run_hook_command(argv: &[String]) -> Option<i32> {
+    let [first, subcommand, rest @ ..] = argv else {
+        return None;
+    };
+    if first != "hook" || subcommand != "install" {
+        return None;
+    }
+    let install = match parse_install_args(rest) {
+        Ok(install) => install,
+        Err(message) => {
+            eprintln!("headlamp: {message}");
+            eprintln!(
+                "headlamp: usage: headlamp hook install [pre-commit|pre-push] [--force] [flags...]"
+            );
+            return Some(2);
+        }
+    };
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = crate::config::find_repo_root(&cwd);
+    Some(match install_hook(&repo_root, &install) {
+        Ok(path) => {
+            println!(
+                "headlamp: installed the {} hook at {}",
+                install.kind.as_str(),
+                path.display()
+            );
+            0
+        }
+        Err(err) => {
+            eprintln!("headlamp: {err}");
+            1
+        }
+    })
+}
+
+pub fn parse_install_args(args: &[String]) -> Result<HookInstall, String> {
+    let mut kind = None;
+    let mut force = false;
+    let mut extra_args = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--force" => force = true,
+            "pre-commit" | "pre-push" if kind.is_none() => {
+                kind = Some(if arg == "pre-commit" {
+                    GitHookKind::PreCommit
+                } else {
+                    GitHookKind::PrePush
+                });
+            }
+            flag if flag.starts_with('-') => extra_args.push(arg.clone()),
+            other => {
+                return Err(format!(
+                    "unknown hook {other:?}; expected pre-commit or pre-push"
+                ));
+            }
+        }
+    }
+    Ok(HookInstall {
+        kind: kind.unwrap_or(GitHookKind::PreCommit),
+        force,
+        extra_args,
+    })
+}
+
+/// The hook itself: headlamp from PATH when it is there (it moves with upgrades), else the
+/// binary that installed the hook. Runners are detected when the hook runs.
+pub fn hook_script(install: &HookInstall, headlamp_bin: &str) -> String {
+    let flags = [
+        format!("--changed={}", install.kind.changed_mode()),
+        "--bail".to_string(),
+        "--quiet".to_string(),
+        format!("--max-duration={DEFAULT_MAX_DURATION}"),
+    ]
+    .into_iter()
+    .chain(install.extra_args.iter().map(|arg| shell_quote(arg)))
+    .collect::<Vec<_>>()
+    .join(" ");
+    format!(
+        "#!/bin/sh\n{MARKER}\nif command -v headlamp >/dev/null 2>&1; then\n  HEADLAMP=headlamp\nelse\n  HEADLAMP={}\nfi\nexec \"$HEADLAMP\" {flags}\n",
+        shell_quote(headlamp_bin)
+    )
+}
+
+/// Writes the hook into the repo's hooks directory (honoring `core.hooksPath`) and returns its
+/// path. An existing hook is only replaced if headlamp wrote it or `force` is set.
+pub fn install_hook(repo_root: &Path, install: &HookInstall) -> Result<PathBuf, RunError> {
+    let hooks_dir = hooks_dir(repo_root)?;
+    let path = hooks_dir.join(install.kind.as_str());
+    let existing = std::fs::read_to_string(&path).ok();
+    if existing.is_some_and(|script| !script.contains(MARKER)) && !install.force {
+        return Err(RunError::CommandFailed {
+            message: format!(
+                "{} exists and was not written by headlamp; pass --force to replace it",
+                path.display()
+            ),
+        });
+    }
+    let headlamp_bin = std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "headlamp".to_string());
+    std::fs::create_dir_all(&hooks_dir).map_err(RunError::Io)?;
+    std::fs::write(&path, hook_script(install, &headlamp_bin)).map_err(RunError::Io)?;
+    make_executable(&path)?;
+    Ok(path)
+}
+
+fn hooks_dir(repo_root: &Path) -> Result<PathBuf, RunError> {
+    let output = crate::git::git_command_in_repo(repo_root)
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .map_err(RunError::Io)?;
+    if !output.status.success() {
+        return Err(RunError::CommandFailed {
+            message: format!("{} is not inside a git repository", repo_root.display()),
+        });
+    }
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(if dir.is_absolute() {
+        dir
+    } else {
+        repo_root.join(dir)
+    })
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), RunError> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).map_err(RunError::Io)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), RunError> {
+    Ok(())
+}
@@ -1,49 +1,144 @@
-pub fn help_text() -> &'static str {
-    r#"headlamp
-
-Usage:
-  headlamp [--runner=<jest|pytest|headlamp|cargo-nextest|cargo-test>] [--coverage] [--changed[=<mode>]] [args...]
-
-Flags:
-  -h, --help                                Print help
-  -V, --version                             Print version
-  --runner <runner>                         Select runner (default: jest)
-  --coverage                                Enable coverage collection (runner-specific)
-  --coverage-ui=jest|both                   Coverage output mode
-  --coverage-abort-on-failure               Exit on test failures without printing coverage
-  --coverage-detail=<all|auto|n>            Coverage detail level
-  --coverage-show-code[=true|false]         Show code under failing lines (default: true in TTY)
-  --coverage-mode=<auto|full|compact>       Coverage UI mode
-  --coverage-compact                        Shorthand for --coverage-mode=compact
-  --coverage-max-files=<n>                  Max files shown in coverage output
-  --coverage-max-hotspots=<n>               Max hotspots shown in coverage output
-  --coverage-thresholds-lines=<n>           Minimum line coverage threshold (0.0-1.0)
-  --coverage-thresholds-functions=<n>       Minimum function coverage threshold (0.0-1.0)
-  --coverage-thresholds-branches=<n>        Minimum branch coverage threshold (0.0-1.0)
-  --coverage-thresholds-statements=<n>      Minimum statement coverage threshold (0.0-1.0)
-  --coverage-page-fit[=true|false]          Fit coverage output to terminal width (default: true in TTY)
-  --coverage-include=<glob,...>             Include globs for coverage (comma-separated)
-  --coverage-exclude=<glob,...>             Exclude globs for coverage (comma-separated)
-  --coverage-editor=<cmd>                   Editor command for file links
-  --coverage-root=<path>                    Workspace root override
-  --only-failures[=true|false]              Show only failing tests during live output
-  --show-logs[=true|false]                  Show full logs under failing tests
-  --sequential[=true|false]                 Serialize execution (e.g. jest --runInBand)
-  --watch[=true|false]                      Re-run on file changes (polling watch)
-  --watch-all[=true|false]                  Watch everything (runner-specific)
-  --ci[=true|false]                         CI mode (disable interactive UI and set CI=1)
-  --verbose[=true|false]                    More Headlamp diagnostics
-  --quiet[=true|false]                      Quiet mode (disable live progress output)
-  --no-cache[=true|false]                   Disable Headlamp caches (and runner caches when possible)
-  --keep-artifacts[=true|false]             Keep test artifacts after run (default: false)
-  --bootstrap-command <cmd>                 Run once before tests (npm script name or shell cmd)
-  --changed[=all|staged|unstaged|branch|lastCommit|lastRelease]
-  --changed-depth=<n>                       Max dependency depth for changed selection
-  --dependency-language=<tsjs|rust>         Dependency language for selection (where applicable)
-  --dependencyLanguage=<tsjs|rust>          Legacy alias for --dependency-language
-
-Notes:
-  Unknown args are forwarded to the runner.
-  Most flags also accept legacy spellings (camelCase and prior dotted coverage/changing flags).
-"#
+//! `--help` prints a short overview; `headlamp help <topic>` lists a topic's flags. Both render
+//! from the flag table the parser uses.
+
+use crate::args::{FlagSpec, HelpTopic, all_flags};
+
+const USAGE: &str = "Usage:
+  headlamp [flags...] [paths...] [-- runner args...]
+  headlamp help <topic>   Every flag of a topic: selection, coverage, runners, output, run
+  headlamp coverage show <file> [--editor-cmd=<cmd>]   Annotate a file with the last run's coverage
+  headlamp coverage combine [lcov...] [--coverage-thresholds=<spec>]   One report across runners
+  headlamp coverage trend [--since=30d] [--export=<file.json|file.csv>]   Line/branch coverage per package over recorded runs
+  headlamp config validate [path]   Check the config for unknown keys and wrong types
+  headlamp editor-server   JSON-RPC over stdio for editors: tests at a cursor, related tests
+  headlamp tests-in-file <file>   JSON: the tests defined in <file> and the tests covering it
+  headlamp owners <source-file> [--json]   The test files exercising a file and the jest project / cargo package owning each
+  headlamp bisect-order [--test=<file>[::<name>]] [flags...]   Find the earlier test that makes an order-dependent test fail
+  headlamp orchestrate --shards=<n> [--launcher=<cmd>] [flags...] [paths...]   Split the tests into timed shards, run them side by side, merge the reports
+  headlamp self-update [--check]   Replace this binary with the latest release (sha256-checked against corruption, not signed)
+  headlamp hook install [pre-commit|pre-push] [--force] [flags...]   Git hook running --changed=staged (pre-push: branch) --bail --quiet --max-duration=2m";
+
+/// Flags worth seeing before picking a topic.
+const OVERVIEW_FLAGS: &[&str] = &[
+    "--runner",
+    "--changed",
+    "--coverage",
+    "--watch",
+    "--bail",
+    "--only-failures",
+    "--ci",
+];
+
+pub fn help_text() -> String {
+    let topics = HelpTopic::ALL
+        .into_iter()
+        .map(|topic| format!("  {:<12}{}", topic.name(), topic.summary()));
+    let common = OVERVIEW_FLAGS
+        .iter()
+        .filter_map(|name| all_flags().find(|spec| spec.name == *name))
+        .map(render_flag);
+    [
+        "headlamp".to_string(),
+        String::new(),
+        USAGE.to_string(),
+        String::new(),
+        "Topics (headlamp help <topic>):".to_string(),
+    ]
+    .into_iter()
+    .chain(topics)
+    .chain([String::new(), "Common flags:".to_string()])
+    .chain([
+        render_row("-h, --help", "Print help"),
+        render_row("-V, --version", "Print version"),
+    ])
+    .chain(common)
+    .chain([
+        String::new(),
+        "Notes:".to_string(),
+        "  Everything after `--`, and any unknown arg, goes to the runner.".to_string(),
+        "  Most flags also accept legacy spellings (camelCase and prior dotted coverage/changing \
+         flags)."
+            .to_string(),
+    ])
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+pub fn topic_help_text(topic: HelpTopic) -> String {
+    let notes = topic_notes(topic);
+    [
+        format!("headlamp help {}: {}", topic.name(), topic.summary()),
+        String::new(),
+        "Flags:".to_string(),
+    ]
+    .into_iter()
+    .chain(topic.flags().iter().map(render_flag))
+    .chain((!notes.is_empty()).then(|| format!("\nNotes:\n{notes}")))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn topic_notes(topic: HelpTopic) -> &'static str {
+    match topic {
+        HelpTopic::Runners => {
+            "  Everything after `--` goes to the runner; -t/-k/--grep name filters and libtest \
+             flags are translated per runner.\n  Unknown args are forwarded to the runner.\n  \
+             plugin:<name> runs .headlamp/plugins/<name>.json (or a dir in HEADLAMP_PLUGIN_PATH, \
+             or headlamp-runner-<name> on PATH) and reads its NDJSON events."
+        }
+        HelpTopic::Run => {
+            "  Hooks get RUN_ID; on-failure and post-run also get EXIT_CODE and FAILED_TESTS (a \
+             file of failing tests)."
+        }
+        HelpTopic::Selection | HelpTopic::Coverage | HelpTopic::Output => "",
+    }
+}
+
+fn render_flag(spec: &FlagSpec) -> String {
+    let label = match spec.short {
+        Some(short) => format!("{short}, {}{}", spec.name, spec.value),
+        None => format!("{}{}", spec.name, spec.value),
+    };
+    render_row(&label, spec.help)
+}
+
+fn render_row(label: &str, help: &str) -> String {
+    format!("  {label:<41} {help}").trim_end().to_string()
+}
+
+/// `-h`/`--help` and `-V`/`--version` anywhere before `--`; `None` when neither is given.
+pub fn run_help_flags(argv: &[String]) -> Option<i32> {
+    let flag = argv
+        .iter()
+        .take_while(|token| token.as_str() != "--")
+        .find(|token| matches!(token.as_str(), "-h" | "--help" | "-V" | "--version"))?;
+    match flag.as_str() {
+        "-h" | "--help" => println!("{}", help_text()),
+        _ => println!("{}", env!("CARGO_PKG_VERSION")),
+    }
+    Some(0)
+}
+
+/// `headlamp help [topic]`; `None` when `argv` is not a help command.
+pub fn run_help_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "help" {
+        return None;
+    }
+    match rest {
+        [] => println!("{}", help_text()),
+        [name] => match HelpTopic::parse(name) {
+            Some(topic) => println!("{}", topic_help_text(topic)),
+            None => {
+                let names = HelpTopic::ALL.map(HelpTopic::name).join(", ");
+                eprintln!("headlamp: unknown help topic: {name} (topics: {names})");
+                return Some(2);
+            }
+        },
+        _ => {
+            eprintln!("headlamp: usage: headlamp help [topic]");
+            return Some(2);
+        }
+    }
+    Some(0)
 }
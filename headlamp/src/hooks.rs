@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use crate::args::ParsedArgs;
+use crate::process::RunEnvironment;
+use crate::run::RunError;
+
+/// Wraps one headlamp run (every runner, one watch iteration) in the configured hooks:
+/// `pre_run`, then `pre_coverage` for `--coverage` runs, the run itself, `on_failure` when it
+/// failed and finally `post_run`, which runs even when an earlier hook failed so it can tear
+/// down whatever `pre_run` started. Every hook gets `RUN_ID`; the last two also get `EXIT_CODE`
+/// and `FAILED_TESTS` (a file of failing tests), on top of the runners' `RunEnvironment`.
+pub fn run_with_hooks(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    run: impl FnOnce() -> i32,
+) -> i32 {
+    let hooks = &args.hooks;
+    let run_id = new_run_id();
+    let mut env = run_env.pairs();
+    env.push(("RUN_ID".to_string(), run_id));
+    let pre_coverage = if args.collect_coverage {
+        hooks.pre_coverage.as_slice()
+    } else {
+        &[]
+    };
+    let prepared = run_hook(repo_root, "pre_run", &hooks.pre_run, &env)
+        .and_then(|()| run_hook(repo_root, "pre_coverage", pre_coverage, &env));
+    let exit_code = match prepared {
+        Ok(()) => run(),
+        Err(err) => {
+            eprintln!("headlamp: {err}");
+            1
+        }
+    };
+    if hooks.on_failure.is_empty() && hooks.post_run.is_empty() {
+        return exit_code;
+    }
+    env.push(("EXIT_CODE".to_string(), exit_code.to_string()));
+    env.push((
+        "FAILED_TESTS".to_string(),
+        write_failed_tests_file(repo_root),
+    ));
+    if exit_code != 0 {
+        report_hook_failure(run_hook(repo_root, "on_failure", &hooks.on_failure, &env));
+    }
+    report_hook_failure(run_hook(repo_root, "post_run", &hooks.post_run, &env));
+    exit_code
+}
+
+/// Runs `commands` in order through the shell, stopping at the first failure. Hook output goes
+/// to stderr so it never mixes into the test report on stdout.
+pub fn run_hook(
+    repo_root: &Path,
+    hook: &str,
+    commands: &[String],
+    env: &[(String, String)],
+) -> Result<(), RunError> {
+    for command in commands.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        let expression = env.iter().fold(
+            crate::run::shell_expression(command)
+                .dir(repo_root)
+                .stdout_to_stderr()
+                .unchecked(),
+            |expression, (key, value)| expression.env(key, value),
+        );
+        let output = expression
+            .run()
+            .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
+        if !output.status.success() {
+            return Err(RunError::HookFailed {
+                hook: hook.to_string(),
+                command: command.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Hook failures after the run only warn; the tests' exit code stands.
+fn report_hook_failure(result: Result<(), RunError>) {
+    if let Err(err) = result {
+        eprintln!("headlamp: {err}");
+    }
+}
+
+fn new_run_id() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+    format!("{millis}-{}", std::process::id())
+}
+
+/// Lives in the headlamp cache next to the last-run coverage, so it outlasts the run session.
+fn failed_tests_path(repo_root: &Path) -> PathBuf {
+    crate::fast_related::default_cache_root()
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("hooks")
+        .join("failed-tests.txt")
+}
+
+fn write_failed_tests_file(repo_root: &Path) -> String {
+    let path = failed_tests_path(repo_root);
+    let lines = crate::recorded::recorded_results()
+        .failed_tests
+        .iter()
+        .map(|(file, name)| match name {
+            Some(name) => format!("{file}\t{name}\n"),
+            None => format!("{file}\n"),
+        })
+        .collect::<String>();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&path, lines);
+    path.to_string_lossy().to_string()
+}
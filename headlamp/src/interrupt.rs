@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::live_progress::TestOutcome;
+
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Exit code when `--max-duration` runs out, as coreutils `timeout` uses.
+pub const MAX_DURATION_EXIT_CODE: i32 = 124;
+
+const TERMINATE_GRACE: Duration = Duration::from_secs(2);
+
+static ACTIVE_PROGRESS: LazyLock<Mutex<Option<crate::live_progress::InterruptHandle>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Why the run is being stopped and its exit code, until a thread claims the stop.
+static PENDING_STOP: Mutex<Option<(String, i32)>> = Mutex::new(None);
+
+/// Set while a `--debug` session owns the terminal; Ctrl-C then belongs to the debugger.
+static ATTACHED: AtomicBool = AtomicBool::new(false);
+
+static PASSED: AtomicUsize = AtomicUsize::new(0);
+static FAILED: AtomicUsize = AtomicUsize::new(0);
+static SKIPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// What had finished by the time the run was interrupted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartialSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub done_units: usize,
+    pub total_units: usize,
+}
+
+pub fn render_partial_summary(summary: &PartialSummary) -> String {
+    render_stopped_summary("interrupted", summary)
+}
+
+/// `reason` says why the run stopped early, e.g. "interrupted".
+pub fn render_stopped_summary(reason: &str, summary: &PartialSummary) -> String {
+    let tests = format!(
+        "{} passed, {} failed, {} skipped",
+        summary.passed, summary.failed, summary.skipped
+    );
+    if summary.total_units == 0 {
+        return format!("headlamp: {reason} ({tests})");
+    }
+    format!(
+        "headlamp: {reason} after {}/{} units ({tests})",
+        summary.done_units.min(summary.total_units),
+        summary.total_units
+    )
+}
+
+/// Installs the SIGINT/SIGTERM handler. Safe to call more than once; only the first call wins.
+pub fn install_handler() {
+    let _ = ctrlc::set_handler(handle_interrupt);
+}
+
+/// Starts the `--max-duration` watchdog: once the budget is spent the run is stopped like an
+/// interrupt and exits with `MAX_DURATION_EXIT_CODE`. Watch mode has no end, so no budget.
+///
+/// The watchdog only raises the interrupt flag and tears the runners down; the thread waiting
+/// on the runner prints the summary and exits (see `park_if_interrupted`), so the summary cannot
+/// interleave with output that thread is still writing. When no runner is in flight the
+/// watchdog stops the run itself after the teardown grace.
+pub fn arm_max_duration(args: &crate::args::ParsedArgs) {
+    let Some(budget_ms) = args.max_duration_ms.filter(|_| !args.watch) else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let budget = Duration::from_millis(budget_ms);
+        std::thread::sleep(budget);
+        let reason = format!(
+            "exceeded --max-duration of {}",
+            crate::format::time::format_duration(budget)
+        );
+        request_stop(reason, MAX_DURATION_EXIT_CODE);
+        std::thread::sleep(TERMINATE_GRACE);
+        finish_pending_stop();
+    });
+}
+
+fn request_stop(reason: String, exit_code: i32) {
+    if let Ok(mut pending) = PENDING_STOP.lock() {
+        *pending = Some((reason, exit_code));
+    }
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    crate::process::group::terminate_registered_groups(TERMINATE_GRACE);
+}
+
+/// Stops the run on the calling thread if a stop is pending and no other thread claimed it.
+fn finish_pending_stop() {
+    let pending = PENDING_STOP.lock().ok().and_then(|mut guard| guard.take());
+    if let Some((reason, exit_code)) = pending {
+        stop_run(&reason, exit_code);
+    }
+}
+
+/// Runs `run` with the interrupt handler standing down, for a child attached to the terminal.
+pub(crate) fn while_attached<T>(run: impl FnOnce() -> T) -> T {
+    ATTACHED.store(true, Ordering::SeqCst);
+    let out = run();
+    ATTACHED.store(false, Ordering::SeqCst);
+    out
+}
+
+fn handle_interrupt() {
+    if ATTACHED.load(Ordering::SeqCst) {
+        return;
+    }
+    stop_run("interrupted", INTERRUPTED_EXIT_CODE);
+}
+
+/// Tears the runners down, prints what had finished and exits with `exit_code`.
+fn stop_run(reason: &str, exit_code: i32) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    crate::process::group::terminate_registered_groups(TERMINATE_GRACE);
+    let handle = ACTIVE_PROGRESS.lock().ok().and_then(|guard| guard.clone());
+    let (done_units, total_units) = handle.as_ref().map(|h| h.units()).unwrap_or((0, 0));
+    // Keep the progress write lock held until exit so no runner output lands after the summary.
+    let _frame_guard = handle.as_ref().and_then(|h| h.restore_terminal());
+    let summary = PartialSummary {
+        passed: PASSED.load(Ordering::SeqCst),
+        failed: FAILED.load(Ordering::SeqCst),
+        skipped: SKIPPED.load(Ordering::SeqCst),
+        done_units,
+        total_units,
+    };
+    crate::format::report_out::println(render_stopped_summary(reason, &summary));
+    std::process::exit(exit_code);
+}
+
+/// Called once a runner child has exited. If that exit was caused by teardown, finishes a pending
+/// `--max-duration` stop on this thread, or blocks so the caller cannot render a (misleading)
+/// full summary before the handler exits the process.
+pub(crate) fn park_if_interrupted() {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        finish_pending_stop();
+    }
+    while INTERRUPTED.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+pub fn reset_partial_summary() {
+    [&PASSED, &FAILED, &SKIPPED]
+        .into_iter()
+        .for_each(|counter| counter.store(0, Ordering::SeqCst));
+}
+
+pub fn record_test_outcome(outcome: TestOutcome) {
+    let counter = match outcome {
+        TestOutcome::Pass => &PASSED,
+        TestOutcome::Fail => &FAILED,
+        TestOutcome::Skip => &SKIPPED,
+        TestOutcome::Unknown => return,
+    };
+    counter.fetch_add(1, Ordering::SeqCst);
+}
+
+pub(crate) fn set_active_progress(handle: Option<crate::live_progress::InterruptHandle>) {
+    if let Ok(mut guard) = ACTIVE_PROGRESS.lock() {
+        *guard = handle;
+    }
+}
+
+pub(crate) fn clear_active_progress_if(
+    is_current: impl Fn(&crate::live_progress::InterruptHandle) -> bool,
+) {
+    if let Ok(mut guard) = ACTIVE_PROGRESS.lock() {
+        if guard.as_ref().is_some_and(is_current) {
+            *guard = None;
+        }
+    }
+}
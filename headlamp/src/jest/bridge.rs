@@ -15,6 +15,26 @@ pub(super) fn config_token(repo_root: &Path, cfg: &Path) -> String {
         .unwrap_or_else(|| cfg.to_slash_lossy().to_string())
 }
 
+/// Records, per test, the `npx jest` command that re-runs just that test under this project's
+/// config.
+pub(super) fn with_repro_commands(
+    mut bridge: TestRunModel,
+    repo_root: &Path,
+    cfg_token: &str,
+) -> TestRunModel {
+    let config = Some(cfg_token).filter(|token| !token.is_empty());
+    for suite in &mut bridge.test_results {
+        let test_path = Path::new(&suite.test_file_path)
+            .strip_prefix(repo_root)
+            .map(|rel| rel.to_slash_lossy().to_string())
+            .unwrap_or_else(|_| suite.test_file_path.clone());
+        for case in &mut suite.test_results {
+            case.repro = Some(crate::repro::jest(config, &test_path, &case.full_name));
+        }
+    }
+    bridge
+}
+
 pub(super) fn merge_bridge_json(
     items: &[TestRunModel],
     rank_by_abs_path: &BTreeMap<String, i64>,
@@ -78,6 +98,7 @@ pub(super) fn merge_bridge_json(
         start_time,
         test_results,
         aggregated,
+        skipped_units: vec![],
     })
 }
 
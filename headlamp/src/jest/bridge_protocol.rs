@@ -0,0 +1,54 @@
+use serde::Deserialize;
+
+/// Version of the NDJSON event stream the bundled reporter writes to `HEADLAMP_EVENTS_FD`.
+/// Reporters that predate it (v1) only print `[JEST-BRIDGE-EVENT]` lines on stderr and write
+/// the final `JEST_BRIDGE_OUT` JSON, which is still read either way.
+pub(super) const BRIDGE_PROTOCOL_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(super) enum BridgeEvent {
+    #[serde(rename_all = "camelCase")]
+    RunStart {
+        num_total_test_suites: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    TestFileStart {
+        test_path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CaseStart {
+        test_path: String,
+        full_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CaseComplete {
+        test_path: String,
+        full_name: String,
+        status: String,
+        #[serde(rename = "duration")]
+        duration_ms: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    TestFileComplete {
+        test_path: String,
+        num_passing_tests: Option<u64>,
+        num_failing_tests: Option<u64>,
+    },
+    RunComplete,
+}
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    v: u32,
+}
+
+/// Parses one NDJSON line from the event pipe; lines from other protocol versions are ignored.
+pub(super) fn parse_bridge_event_line(line: &str) -> Option<BridgeEvent> {
+    let trimmed = line.trim();
+    let envelope = serde_json::from_str::<Envelope>(trimmed).ok()?;
+    if envelope.v != BRIDGE_PROTOCOL_VERSION {
+        return None;
+    }
+    serde_json::from_str::<BridgeEvent>(trimmed).ok()
+}
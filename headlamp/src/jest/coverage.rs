@@ -7,13 +7,17 @@ use headlamp_core::args::ParsedArgs;
 use headlamp_core::coverage::istanbul::{merge_istanbul_reports, read_istanbul_coverage_tree};
 use headlamp_core::coverage::istanbul_pretty::format_istanbul_pretty;
 use headlamp_core::coverage::lcov::{merge_reports, read_lcov_file, resolve_lcov_paths_to_root};
-use headlamp_core::coverage::model::{CoverageReport, apply_statement_totals_to_report};
+use headlamp_core::coverage::model::{
+    CoverageReport, apply_exclusion_pragmas, apply_statement_totals_to_report,
+};
 use headlamp_core::coverage::print::{
     PrintOpts, filter_report, render_report_text, should_render_hotspots,
 };
 use headlamp_core::coverage::thresholds::compare_thresholds_and_print_if_needed;
+use headlamp_core::coverage::v8::read_v8_coverage_dir;
 use indexmap::IndexSet;
 
+use crate::jest_projects::JestProject;
 use crate::run::RunError;
 
 pub(super) fn write_asset(path: &Path, bytes: &[u8]) -> Result<PathBuf, RunError> {
@@ -57,17 +61,21 @@ pub(crate) fn build_jest_threshold_report(
     }
 }
 
-pub(super) fn coverage_dir_for_config_in_root(cfg_path: &Path, coverage_root: &Path) -> PathBuf {
+/// Each run unit writes to its own directory, keyed by its repo-relative label, so projects
+/// that share a config file name (or a root config) do not overwrite each other's reports.
+pub(super) fn coverage_dir_for_project_in_root(
+    project: &JestProject,
+    repo_root: &Path,
+    coverage_root: &Path,
+) -> PathBuf {
     coverage_root
         .join("jest")
-        .join(coverage_dir_suffix_for_config(cfg_path))
+        .join(coverage_dir_suffix_for_project(project, repo_root))
 }
 
-fn coverage_dir_suffix_for_config(cfg_path: &Path) -> String {
-    let base = cfg_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("default");
+fn coverage_dir_suffix_for_project(project: &JestProject, repo_root: &Path) -> String {
+    let label = project.label(repo_root);
+    let base = if label.is_empty() { "default" } else { &label };
     base.chars()
         .map(|c| {
             if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
@@ -176,13 +184,15 @@ fn titlecase_first(text: &str) -> String {
 }
 
 fn print_coverage_threshold_failure_summary(lines: &IndexSet<String>) {
-    println!();
-    println!("Coverage thresholds not met");
+    crate::format::report_out::println("");
+    crate::format::report_out::println("Coverage thresholds not met");
     if lines.is_empty() {
-        println!(" See tables above and jest coverageThreshold.");
+        crate::format::report_out::println(" See tables above and jest coverageThreshold.");
         return;
     }
-    lines.iter().for_each(|line| println!(" {line}"));
+    lines
+        .iter()
+        .for_each(|line| crate::format::report_out::println(format!(" {line}")));
 }
 
 pub(crate) fn should_print_coverage_threshold_failure_summary(
@@ -207,6 +217,12 @@ struct CoverageInputs {
     resolved_for_fallback_render: Option<CoverageReport>,
 }
 
+/// Raw `NODE_V8_COVERAGE` dumps for `--coverage-provider=v8`; they fill in plain JS that
+/// jest's own report does not cover (e.g. scripts run in child node processes).
+pub(super) fn v8_dump_dir(coverage_root: &Path) -> PathBuf {
+    coverage_root.join("v8")
+}
+
 fn collect_coverage_inputs(repo_root: &Path, coverage_root: &Path) -> CoverageInputs {
     let jest_cov_dir = coverage_root.join("jest");
     let json_tree = read_istanbul_coverage_tree(&jest_cov_dir);
@@ -216,6 +232,8 @@ fn collect_coverage_inputs(repo_root: &Path, coverage_root: &Path) -> CoverageIn
         .collect::<Vec<_>>();
     let merged_json =
         (!json_reports.is_empty()).then(|| merge_istanbul_reports(&json_reports, repo_root));
+    let merged_json = with_v8_dumps(merged_json, repo_root, coverage_root)
+        .map(|report| apply_exclusion_pragmas(report, repo_root));
 
     let lcov_candidates = collect_lcov_candidates(coverage_root, &jest_cov_dir);
     let reports = lcov_candidates
@@ -223,9 +241,15 @@ fn collect_coverage_inputs(repo_root: &Path, coverage_root: &Path) -> CoverageIn
         .filter(|path| path.exists())
         .filter_map(|path| read_lcov_file(path).ok())
         .collect::<Vec<_>>();
+    tracing::debug!(
+        target: "coverage",
+        istanbul_reports = json_reports.len(),
+        lcov_reports = reports.len(),
+        "collected jest coverage"
+    );
     let resolved_lcov = (!reports.is_empty()).then(|| {
         let merged = merge_reports(&reports, repo_root);
-        resolve_lcov_paths_to_root(merged, repo_root)
+        apply_exclusion_pragmas(resolve_lcov_paths_to_root(merged, repo_root), repo_root)
     });
 
     let threshold_report = build_jest_threshold_report(resolved_lcov.clone(), merged_json.clone());
@@ -238,6 +262,32 @@ fn collect_coverage_inputs(repo_root: &Path, coverage_root: &Path) -> CoverageIn
     }
 }
 
+fn with_v8_dumps(
+    merged_json: Option<CoverageReport>,
+    repo_root: &Path,
+    coverage_root: &Path,
+) -> Option<CoverageReport> {
+    let Some(v8_report) = read_v8_coverage_dir(&v8_dump_dir(coverage_root), repo_root) else {
+        return merged_json;
+    };
+    let Some(mut merged) = merged_json else {
+        return Some(v8_report);
+    };
+    let known = merged
+        .files
+        .iter()
+        .map(|f| f.path.clone())
+        .collect::<std::collections::BTreeSet<_>>();
+    merged.files.extend(
+        v8_report
+            .files
+            .into_iter()
+            .filter(|f| !known.contains(&f.path)),
+    );
+    merged.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(merged)
+}
+
 fn collect_lcov_candidates(coverage_root: &Path, jest_cov_dir: &Path) -> Vec<PathBuf> {
     let mut lcov_candidates: Vec<PathBuf> = vec![coverage_root.join("lcov.info")];
     if jest_cov_dir.exists() {
@@ -267,6 +317,7 @@ fn maybe_print_coverage(
 
     let print_opts =
         PrintOpts::for_run(args, headlamp_core::format::terminal::is_output_terminal());
+    headlamp_core::coverage::scope::print_scope_label(args);
 
     if let Some(pretty) = format_istanbul_pretty(
         repo_root,
@@ -277,7 +328,7 @@ fn maybe_print_coverage(
         &args.exclude_globs,
         args.coverage_detail,
     ) {
-        println!("{pretty}");
+        crate::format::report_out::println(headlamp_core::format::deterministic::finalize(pretty));
         return;
     }
 
@@ -292,10 +343,12 @@ fn maybe_print_coverage(
         &args.exclude_globs,
     );
     let include_hotspots = should_render_hotspots(args.coverage_detail);
-    println!(
-        "{}",
-        render_report_text(&filtered, &print_opts, repo_root, include_hotspots)
-    );
+    crate::format::report_out::println(render_report_text(
+        &filtered,
+        &print_opts,
+        repo_root,
+        include_hotspots,
+    ));
 }
 
 fn apply_thresholds_and_exit_code(
@@ -325,6 +378,10 @@ pub(super) fn collect_and_print_coverage(args: CollectCoverageArgs<'_>) -> Resul
     } = args;
 
     let inputs = collect_coverage_inputs(repo_root, coverage_root);
+    if let Some(report) = inputs.resolved_for_fallback_render.as_ref() {
+        crate::coverage::last_run::save_last_run_coverage(repo_root, "jest", report);
+        crate::recorded::record_coverage(report);
+    }
     maybe_print_coverage(repo_root, args, selection_paths_abs, &inputs);
     let final_exit = apply_thresholds_and_exit_code(
         args,
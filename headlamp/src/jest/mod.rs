@@ -2,26 +2,29 @@ use std::path::{Path, PathBuf};
 
 use path_slash::PathExt;
 
-use indexmap::IndexSet;
-
 #[cfg(test)]
 use crate::coverage::model::CoverageReport;
 use headlamp_core::args::ParsedArgs;
-use headlamp_core::format::ctx::make_ctx;
-use headlamp_core::format::vitest::render_vitest_from_test_model;
 use headlamp_core::selection::dependency_language::DependencyLanguageId;
 use headlamp_core::selection::relevance::augment_rank_with_priority_paths;
 
-use crate::jest_config::list_all_jest_configs;
+use crate::jest_config::{filter_jest_projects, list_all_jest_configs};
 use crate::jest_discovery::{args_for_discovery, jest_bin};
+use crate::jest_projects::{JestProject, expand_jest_projects};
 use crate::live_progress::live_progress_mode;
-use crate::run::{RunError, run_bootstrap};
+use crate::run::RunError;
+use crate::session::RunSession;
+use crate::session::artifacts::ArtifactKind;
 
 mod bridge;
+mod bridge_protocol;
 mod coverage;
+mod output;
 mod project_run;
 mod selection;
+mod shuffle;
 mod streaming;
+mod worker;
 
 #[cfg(test)]
 pub(crate) fn build_jest_threshold_report(
@@ -31,10 +34,24 @@ pub(crate) fn build_jest_threshold_report(
     coverage::build_jest_threshold_report(resolved_lcov, merged_json)
 }
 
+#[cfg(test)]
+pub(crate) fn jest_streaming_actions_for_lines(
+    repo_root: &Path,
+    only_failures: bool,
+    lines: &[(crate::streaming::OutputStream, &str)],
+) -> Vec<crate::streaming::StreamAction> {
+    use crate::streaming::StreamAdapter;
+    let mut adapter = streaming::JestStreamingAdapter::new(repo_root, false, only_failures);
+    lines
+        .iter()
+        .flat_map(|(stream, line)| adapter.on_line(*stream, line))
+        .collect()
+}
+
 #[cfg(test)]
 pub(crate) fn should_print_coverage_threshold_failure_summary(
     exit_code: i32,
-    coverage_failure_lines: &IndexSet<String>,
+    coverage_failure_lines: &indexmap::IndexSet<String>,
 ) -> bool {
     coverage::should_print_coverage_threshold_failure_summary(exit_code, coverage_failure_lines)
 }
@@ -42,22 +59,12 @@ pub(crate) fn should_print_coverage_threshold_failure_summary(
 const JEST_REPORTER_BYTES: &[u8] = include_bytes!("../../assets/jest/reporter.cjs");
 const JEST_SETUP_BYTES: &[u8] = include_bytes!("../../assets/jest/setup.cjs");
 
-#[derive(Debug)]
-struct AggregatedProjectRuns {
-    exit_code: i32,
-    bridges: Vec<headlamp_core::test_model::TestRunModel>,
-    captured_stdout: Vec<String>,
-    captured_stderr: Vec<String>,
-    coverage_failure_lines: IndexSet<String>,
-    raw_output_all: Vec<String>,
-}
-
 #[derive(Debug)]
 struct JestRunContext {
     jest_bin: PathBuf,
     selection_paths_abs: Vec<String>,
     discovery_args: Vec<String>,
-    project_configs: Vec<PathBuf>,
+    project_configs: Vec<JestProject>,
     related_selection: headlamp_core::selection::related_tests::RelatedTestSelection,
     directness_rank: std::collections::BTreeMap<String, i64>,
     out_json_base: PathBuf,
@@ -72,11 +79,11 @@ fn build_jest_run_context(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
 ) -> Result<JestRunContext, RunError> {
-    run_bootstrap_if_configured(repo_root, args)?;
+    crate::run::bootstrap_and_wait(repo_root, args, session.env())?;
     let jest_bin = ensure_jest_bin_exists(repo_root)?;
     let selection_paths_abs = selection::selection_paths_abs(repo_root, args)?;
     let discovery_args = args_for_discovery(&args.runner_args);
-    let project_configs = project_configs_for_repo_root(repo_root);
+    let project_configs = project_configs_for_repo_root(repo_root, args)?;
     let selection_exclude_globs = selection::exclude_globs_for_selection(&args.exclude_globs);
     let selection_is_tests_only = selection_is_tests_only(&selection_paths_abs);
     let production_seeds = production_seeds_abs(&selection_paths_abs);
@@ -86,17 +93,17 @@ fn build_jest_run_context(
         selection_is_tests_only,
         &production_seeds,
     );
-    let dependency_language = args
-        .dependency_language
-        .unwrap_or(DependencyLanguageId::TsJs);
     let related_selection =
         selection::compute_related_selection(selection::ComputeRelatedSelectionArgs {
             repo_root,
             args,
             project_configs: &project_configs,
             jest_bin: &jest_bin,
+            run_env: session.env(),
             discovery_args: &discovery_args,
-            dependency_language,
+            dependency_language: args
+                .dependency_language
+                .unwrap_or(DependencyLanguageId::TsJs),
             selection_key: selection_key.as_deref(),
             selection_is_tests_only,
             selection_paths_abs: &selection_paths_abs,
@@ -117,6 +124,7 @@ fn build_jest_run_context(
     } else {
         session.subdir("coverage")
     };
+    let _ = std::fs::remove_dir_all(coverage::v8_dump_dir(&coverage_root));
     let name_pattern_only_for_discovery =
         bridge::should_skip_run_tests_by_path_for_name_pattern_only(args, &selection_paths_abs);
     let base_cmd_args =
@@ -186,11 +194,20 @@ pub fn run_jest(
 ) -> Result<i32, RunError> {
     let started_at = std::time::Instant::now();
     let ctx = build_jest_run_context(repo_root, args, session)?;
+    let typecheck_scope = crate::typecheck::jest_typecheck_scope(
+        &ctx.selection_paths_abs,
+        &ctx.related_selection.selected_test_paths_abs,
+        &ctx.project_configs,
+    );
+    if crate::typecheck::run_typecheck_phase(repo_root, args, session, &typecheck_scope)? {
+        return Ok(1);
+    }
     let per_project_results = project_run::run_projects(project_run::RunProjectsArgs {
         repo_root,
         args,
         project_configs: &ctx.project_configs,
         jest_bin: &ctx.jest_bin,
+        run_env: session.env(),
         discovery_args: &ctx.discovery_args,
         related_selection: &ctx.related_selection.selected_test_paths_abs,
         base_cmd_args: &ctx.base_cmd_args,
@@ -200,8 +217,9 @@ pub fn run_jest(
         coverage_root: &ctx.coverage_root,
         mode: ctx.mode,
     })?;
-    let aggregated = aggregate_project_runs(per_project_results);
-    print_jest_run_output(repo_root, args, &ctx.directness_rank, &aggregated);
+    register_jest_artifacts(args, session, &ctx);
+    let aggregated = output::aggregate_project_runs(per_project_results);
+    output::print_jest_run_output(repo_root, args, &ctx.directness_rank, &aggregated);
     let exit = maybe_collect_coverage(
         repo_root,
         &ctx.coverage_root,
@@ -214,14 +232,15 @@ pub fn run_jest(
     Ok(exit)
 }
 
-fn run_bootstrap_if_configured(repo_root: &Path, args: &ParsedArgs) -> Result<(), RunError> {
-    args.bootstrap_command
-        .as_ref()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|cmd| run_bootstrap(repo_root, cmd))
-        .transpose()?;
-    Ok(())
+/// One bridge JSON per project (see `run_project`), plus the coverage root for `--coverage`.
+fn register_jest_artifacts(args: &ParsedArgs, session: &RunSession, ctx: &JestRunContext) {
+    for index in 0..ctx.project_configs.len() {
+        let out_json = ctx.out_json_base.with_extension(format!("{index}.json"));
+        session.register_artifact(ArtifactKind::Report, "jest", out_json);
+    }
+    if args.collect_coverage {
+        session.register_artifact(ArtifactKind::Coverage, "jest", &ctx.coverage_root);
+    }
 }
 
 fn ensure_jest_bin_exists(repo_root: &Path) -> Result<PathBuf, RunError> {
@@ -235,13 +254,18 @@ fn ensure_jest_bin_exists(repo_root: &Path) -> Result<PathBuf, RunError> {
         })
 }
 
-fn project_configs_for_repo_root(repo_root: &Path) -> Vec<PathBuf> {
+fn project_configs_for_repo_root(
+    repo_root: &Path,
+    args: &ParsedArgs,
+) -> Result<Vec<JestProject>, RunError> {
     let discovered = list_all_jest_configs(repo_root);
-    if discovered.is_empty() {
-        vec![repo_root.to_path_buf()]
+    let configs = if discovered.is_empty() {
+        vec![JestProject::from(repo_root.to_path_buf())]
     } else {
-        discovered
-    }
+        expand_jest_projects(discovered)
+    };
+    filter_jest_projects(configs, repo_root, &args.projects, &args.ignore_projects)
+        .map_err(|message| RunError::CommandFailed { message })
 }
 
 fn selection_is_tests_only(selection_paths_abs: &[String]) -> bool {
@@ -334,126 +358,12 @@ fn build_base_cmd_args(
     }
 }
 
-fn aggregate_project_runs(
-    per_project_results: Vec<project_run::ProjectRunOutput>,
-) -> AggregatedProjectRuns {
-    per_project_results.into_iter().fold(
-        AggregatedProjectRuns {
-            exit_code: 0,
-            bridges: vec![],
-            captured_stdout: vec![],
-            captured_stderr: vec![],
-            coverage_failure_lines: IndexSet::new(),
-            raw_output_all: vec![],
-        },
-        |mut acc, result| {
-            acc.exit_code = acc.exit_code.max(result.exit_code);
-            acc.captured_stdout.extend(result.captured_stdout);
-            acc.captured_stderr.extend(result.captured_stderr);
-            result.coverage_failure_lines.into_iter().for_each(|ln| {
-                acc.coverage_failure_lines.insert(ln);
-            });
-            acc.raw_output_all.push(result.raw_output);
-            result.bridge.into_iter().for_each(|bridge| {
-                acc.bridges.push(bridge);
-            });
-            acc
-        },
-    )
-}
-
-fn print_jest_run_output(
-    repo_root: &Path,
-    args: &ParsedArgs,
-    directness_rank: &std::collections::BTreeMap<String, i64>,
-    aggregated: &AggregatedProjectRuns,
-) {
-    let combined_raw = aggregated.raw_output_all.join("\n");
-    match bridge::merge_bridge_json(&aggregated.bridges, directness_rank) {
-        Some(merged) => {
-            print_from_merged_bridge(
-                repo_root,
-                args,
-                &merged,
-                &combined_raw,
-                aggregated.exit_code,
-            );
-        }
-        None => {
-            print_from_raw_output(repo_root, args, &combined_raw, aggregated);
-        }
-    }
-}
-
-fn print_from_merged_bridge(
-    repo_root: &Path,
-    args: &ParsedArgs,
-    merged: &headlamp_core::test_model::TestRunModel,
-    combined_raw: &str,
-    exit_code: i32,
-) {
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        exit_code != 0,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
-    let pretty = render_vitest_from_test_model(merged, &ctx, args.only_failures);
-    let maybe_merged_text = if !args.only_failures && bridge::looks_sparse(&pretty) {
-        let raw_also = headlamp_core::format::raw_jest::format_jest_output_vitest(
-            combined_raw,
-            &ctx,
-            args.only_failures,
-        );
-        Some(bridge::merge_sparse_bridge_and_raw(&pretty, &raw_also))
-    } else {
-        None
-    };
-    let final_text = maybe_merged_text.as_deref().unwrap_or(&pretty);
-    if !final_text.trim().is_empty() {
-        println!("{final_text}");
-    }
-}
-
-fn print_from_raw_output(
-    repo_root: &Path,
-    args: &ParsedArgs,
-    combined_raw: &str,
-    aggregated: &AggregatedProjectRuns,
-) {
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        combined_raw.contains("FAIL"),
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
-    let formatted = headlamp_core::format::raw_jest::format_jest_output_vitest(
-        combined_raw,
-        &ctx,
-        args.only_failures,
-    );
-    if !formatted.trim().is_empty() {
-        println!("{formatted}");
-    } else {
-        aggregated
-            .captured_stdout
-            .iter()
-            .for_each(|line| println!("{line}"));
-        aggregated
-            .captured_stderr
-            .iter()
-            .for_each(|line| eprintln!("{line}"));
-    }
-}
-
 fn maybe_collect_coverage(
     repo_root: &Path,
     coverage_root: &Path,
     args: &ParsedArgs,
     selection_paths_abs: &[String],
-    aggregated: &AggregatedProjectRuns,
+    aggregated: &output::AggregatedProjectRuns,
 ) -> Result<i32, RunError> {
     if !args.collect_coverage {
         return Ok(aggregated.exit_code);
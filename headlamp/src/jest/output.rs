@@ -0,0 +1,155 @@
+//! Folding the per-project runs into one and printing it: the merged bridge model when any
+//! project produced one, else jest's own output.
+
+use std::path::Path;
+
+use indexmap::IndexSet;
+
+use headlamp_core::args::ParsedArgs;
+use headlamp_core::format::ctx::make_ctx_for_args;
+use headlamp_core::format::vitest::render_vitest_from_test_model;
+use headlamp_core::test_model::{SkippedUnit, SkippedUnitKind, TestRunModel};
+
+use super::{bridge, project_run};
+
+#[derive(Debug)]
+pub(super) struct AggregatedProjectRuns {
+    pub(super) exit_code: i32,
+    bridges: Vec<TestRunModel>,
+    captured_stdout: Vec<String>,
+    captured_stderr: Vec<String>,
+    pub(super) coverage_failure_lines: IndexSet<String>,
+    raw_output_all: Vec<String>,
+    skipped_units: Vec<SkippedUnit>,
+}
+
+pub(super) fn aggregate_project_runs(
+    per_project_results: Vec<project_run::ProjectRunOutput>,
+) -> AggregatedProjectRuns {
+    per_project_results.into_iter().fold(
+        AggregatedProjectRuns {
+            exit_code: 0,
+            bridges: vec![],
+            captured_stdout: vec![],
+            captured_stderr: vec![],
+            coverage_failure_lines: IndexSet::new(),
+            raw_output_all: vec![],
+            skipped_units: vec![],
+        },
+        |mut acc, result| {
+            acc.exit_code = acc.exit_code.max(result.exit_code);
+            acc.captured_stdout.extend(result.captured_stdout);
+            acc.captured_stderr.extend(result.captured_stderr);
+            result.coverage_failure_lines.into_iter().for_each(|ln| {
+                acc.coverage_failure_lines.insert(ln);
+            });
+            acc.raw_output_all.push(result.raw_output);
+            result.skipped.into_iter().for_each(|name| {
+                acc.skipped_units.push(SkippedUnit {
+                    kind: SkippedUnitKind::JestProject,
+                    name,
+                });
+            });
+            result.bridge.into_iter().for_each(|bridge| {
+                acc.bridges.push(bridge);
+            });
+            acc
+        },
+    )
+}
+
+pub(super) fn print_jest_run_output(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    directness_rank: &std::collections::BTreeMap<String, i64>,
+    aggregated: &AggregatedProjectRuns,
+) {
+    let combined_raw = aggregated.raw_output_all.join("\n");
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_suite_paths(
+        &aggregated.bridges,
+    ));
+    let merged = bridge::merge_bridge_json(&aggregated.bridges, directness_rank)
+        .or_else(|| only_skipped_projects(args, aggregated, &combined_raw));
+    match merged {
+        Some(mut merged) => {
+            merged.skipped_units = aggregated.skipped_units.clone();
+            print_from_merged_bridge(
+                repo_root,
+                args,
+                &merged,
+                &combined_raw,
+                aggregated.exit_code,
+            );
+        }
+        None => {
+            print_from_raw_output(repo_root, args, &combined_raw, aggregated);
+        }
+    }
+}
+
+/// With `--show-skipped-projects`, a run whose projects were all skipped still gets a summary
+/// naming them rather than printing nothing.
+fn only_skipped_projects(
+    args: &ParsedArgs,
+    aggregated: &AggregatedProjectRuns,
+    combined_raw: &str,
+) -> Option<TestRunModel> {
+    (args.show_skipped_projects
+        && !aggregated.skipped_units.is_empty()
+        && combined_raw.trim().is_empty())
+    .then(|| crate::cargo::empty_test_run_model_for_exit_code(aggregated.exit_code))
+}
+
+fn print_from_merged_bridge(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    merged: &TestRunModel,
+    combined_raw: &str,
+    exit_code: i32,
+) {
+    crate::recorded::record_failed_tests(merged);
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_test_names(merged));
+    let ctx = make_ctx_for_args(repo_root, args, exit_code != 0);
+    let pretty = render_vitest_from_test_model(merged, &ctx, args.only_failures);
+    let maybe_merged_text = if !args.only_failures && bridge::looks_sparse(&pretty) {
+        let raw_also = headlamp_core::format::raw_jest::format_jest_output_vitest(
+            combined_raw,
+            &ctx,
+            args.only_failures,
+        );
+        Some(bridge::merge_sparse_bridge_and_raw(&pretty, &raw_also))
+    } else {
+        None
+    };
+    let final_text =
+        headlamp_core::format::deterministic::finalize(maybe_merged_text.unwrap_or(pretty));
+    if !final_text.trim().is_empty() {
+        crate::format::report_out::println(&final_text);
+    }
+}
+
+fn print_from_raw_output(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    combined_raw: &str,
+    aggregated: &AggregatedProjectRuns,
+) {
+    let ctx = make_ctx_for_args(repo_root, args, combined_raw.contains("FAIL"));
+    let formatted = headlamp_core::format::raw_jest::format_jest_output_vitest(
+        combined_raw,
+        &ctx,
+        args.only_failures,
+    );
+    if !formatted.trim().is_empty() {
+        crate::format::report_out::println(&formatted);
+    } else {
+        aggregated
+            .captured_stdout
+            .iter()
+            .for_each(crate::format::report_out::println);
+        aggregated
+            .captured_stderr
+            .iter()
+            .for_each(|line| eprintln!("{line}"));
+    }
+}
@@ -1,29 +1,35 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use headlamp_core::args::ParsedArgs;
+use headlamp_core::config::CoverageProvider;
 use headlamp_core::test_model::TestRunModel;
 
 use crate::jest_discovery::{
     JEST_LIST_TESTS_TIMEOUT, discover_jest_list_tests_cached_with_timeout,
 };
+use crate::jest_esm::{needs_vm_modules, node_options_with_vm_modules};
 use crate::jest_ownership::filter_candidates_for_project;
+use crate::jest_projects::JestProject;
 use crate::live_progress::{LiveProgress, LiveProgressMode};
 use crate::parallel_stride::run_parallel_stride;
+use crate::process::RunEnvironment;
 use crate::run::RunError;
-use crate::streaming::run_streaming_capture_tail;
+use crate::streaming::{UnitTagged, run_streaming_capture_tail_with_events};
 
-use super::bridge::{config_token, filter_bridge_for_name_pattern_only};
+use super::bridge::{config_token, filter_bridge_for_name_pattern_only, with_repro_commands};
 use super::coverage::{
-    collect_coverage_from_args, coverage_dir_for_config_in_root,
+    collect_coverage_from_args, coverage_dir_for_project_in_root,
     ensure_watchman_disabled_by_default, extract_coverage_failure_lines,
 };
 use super::streaming::merge_console_entries_into_bridge_json;
+use super::worker::{WorkerKey, WorkerRun, reuses_workers, run_in_worker};
 
 #[derive(Debug)]
 struct RunProjectContext<'a> {
     repo_root: &'a Path,
     args: &'a ParsedArgs,
     jest_bin: &'a Path,
+    run_env: &'a RunEnvironment,
     discovery_args: &'a [String],
     related_selection: &'a [String],
     base_cmd_args: &'a [String],
@@ -31,6 +37,8 @@ struct RunProjectContext<'a> {
     name_pattern_only_for_discovery: bool,
     out_json_base: &'a Path,
     coverage_root: &'a Path,
+    /// `--show-logs` with projects running side by side: printed lines carry the project's tag.
+    tag_units: bool,
 }
 
 #[derive(Debug)]
@@ -41,14 +49,17 @@ pub(super) struct ProjectRunOutput {
     pub(super) captured_stderr: Vec<String>,
     pub(super) coverage_failure_lines: Vec<String>,
     pub(super) raw_output: String,
+    /// The project's label when the selection matched none of its tests, so it never ran.
+    pub(super) skipped: Option<String>,
 }
 
 #[derive(Debug)]
 pub(super) struct RunProjectsArgs<'a> {
     pub(super) repo_root: &'a Path,
     pub(super) args: &'a ParsedArgs,
-    pub(super) project_configs: &'a [PathBuf],
+    pub(super) project_configs: &'a [JestProject],
     pub(super) jest_bin: &'a Path,
+    pub(super) run_env: &'a RunEnvironment,
     pub(super) discovery_args: &'a [String],
     pub(super) related_selection: &'a [String],
     pub(super) base_cmd_args: &'a [String],
@@ -65,6 +76,7 @@ pub(super) fn run_projects(args: RunProjectsArgs<'_>) -> Result<Vec<ProjectRunOu
         args,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         related_selection,
         base_cmd_args,
@@ -76,11 +88,13 @@ pub(super) fn run_projects(args: RunProjectsArgs<'_>) -> Result<Vec<ProjectRunOu
     } = args;
 
     let stride = if args.sequential { 1 } else { 3 };
-    let live_progress = LiveProgress::start(project_configs.len(), mode);
+    let live_progress = LiveProgress::start_with_args(project_configs.len(), mode, args);
+    live_progress.load_unit_timings(repo_root);
     let ctx = RunProjectContext {
         repo_root,
         args,
         jest_bin,
+        run_env,
         discovery_args,
         related_selection,
         base_cmd_args,
@@ -88,9 +102,10 @@ pub(super) fn run_projects(args: RunProjectsArgs<'_>) -> Result<Vec<ProjectRunOu
         name_pattern_only_for_discovery,
         out_json_base,
         coverage_root,
+        tag_units: args.show_logs && stride > 1 && project_configs.len() > 1,
     };
-    let per_project_results = run_parallel_stride(project_configs, stride, |cfg_path, index| {
-        run_project_for_config(&ctx, &live_progress, cfg_path, index)
+    let per_project_results = run_parallel_stride(project_configs, stride, |project, index| {
+        run_project_for_config(&ctx, &live_progress, project, index)
     })?;
     live_progress.finish();
     Ok(per_project_results)
@@ -99,30 +114,65 @@ pub(super) fn run_projects(args: RunProjectsArgs<'_>) -> Result<Vec<ProjectRunOu
 fn run_project_for_config(
     ctx: &RunProjectContext<'_>,
     live_progress: &LiveProgress,
-    cfg_path: &Path,
+    project: &JestProject,
     index: usize,
 ) -> Result<ProjectRunOutput, RunError> {
-    let cfg_token = config_token(ctx.repo_root, cfg_path);
-    live_progress.set_current_label(cfg_token.clone());
-    let tests_for_project = tests_for_project(ctx, cfg_path, &cfg_token)?;
+    live_progress.start_unit(index, &project.label(ctx.repo_root));
+    // The lane closes on failure too, so a project that errored is not left "running".
+    let output = run_started_project(ctx, live_progress, project, index);
+    live_progress.finish_unit(index);
+    output
+}
+
+fn run_started_project(
+    ctx: &RunProjectContext<'_>,
+    live_progress: &LiveProgress,
+    project: &JestProject,
+    index: usize,
+) -> Result<ProjectRunOutput, RunError> {
+    let cfg_token = config_token(ctx.repo_root, &project.config);
+    let unit_label = project.label(ctx.repo_root);
+    let tests_for_project = tests_for_project(ctx, project, &cfg_token)?;
+    tracing::debug!(
+        target: "selection",
+        project = %unit_label,
+        tests = tests_for_project.len(),
+        "jest project selection"
+    );
     if should_skip_project(
         ctx.selection_paths_abs,
         &tests_for_project,
         ctx.name_pattern_only_for_discovery,
     ) {
-        live_progress.increment_done(1);
-        return Ok(empty_project_output());
+        return Ok(skipped_project_output(unit_label));
     }
     let out_json = ctx.out_json_base.with_extension(format!("{index}.json"));
-    let cmd_args = build_cmd_args(ctx, cfg_path, &cfg_token, &tests_for_project);
-    let run = execute_jest_for_project(ctx, live_progress, &out_json, cmd_args)?;
+    let cmd_args = build_cmd_args(ctx, project, &cfg_token, &tests_for_project)?;
+    tracing::debug!(target: "jest", project = %unit_label, args = ?cmd_args, "running jest");
+    let vm_modules = needs_vm_modules(
+        ctx.args.jest_esm,
+        ctx.repo_root,
+        project,
+        &tests_for_project,
+    );
+    let run = execute_jest_for_project(
+        ctx,
+        live_progress,
+        &unit_label,
+        &out_json,
+        cmd_args,
+        vm_modules,
+    )?;
     Ok(ProjectRunOutput {
         exit_code: run.exit_code,
-        bridge: run.bridge,
+        bridge: run
+            .bridge
+            .map(|bridge| with_repro_commands(bridge, ctx.repo_root, &cfg_token)),
         captured_stdout: run.captured_stdout,
         captured_stderr: run.captured_stderr,
         coverage_failure_lines: run.coverage_failure_lines,
         raw_output: run.raw_output,
+        skipped: None,
     })
 }
 
@@ -138,24 +188,25 @@ struct ProjectExecution {
 
 fn tests_for_project(
     ctx: &RunProjectContext<'_>,
-    cfg_path: &Path,
+    project: &JestProject,
     cfg_token: &str,
 ) -> Result<Vec<String>, RunError> {
     if ctx.selection_paths_abs.is_empty() {
-        return list_all_tests_for_project(ctx, cfg_path, cfg_token);
+        return list_all_tests_for_project(ctx, project, cfg_token);
     }
     filter_candidates_for_project(
         ctx.repo_root,
         ctx.jest_bin,
+        ctx.run_env,
         ctx.discovery_args,
-        cfg_path,
+        project,
         ctx.related_selection,
     )
 }
 
 fn list_all_tests_for_project(
     ctx: &RunProjectContext<'_>,
-    cfg_path: &Path,
+    project: &JestProject,
     cfg_token: &str,
 ) -> Result<Vec<String>, RunError> {
     if ctx.name_pattern_only_for_discovery {
@@ -163,9 +214,11 @@ fn list_all_tests_for_project(
     }
     let mut list_args = ctx.discovery_args.to_vec();
     list_args.extend(["--config".to_string(), cfg_token.to_string()]);
+    list_args.extend(project.select_args());
     discover_jest_list_tests_cached_with_timeout(
-        cfg_path.parent().unwrap_or(ctx.repo_root),
+        project.discovery_cwd(ctx.repo_root),
         ctx.jest_bin,
+        ctx.run_env,
         &list_args,
         ctx.args.no_cache,
         JEST_LIST_TESTS_TIMEOUT,
@@ -181,7 +234,7 @@ fn should_skip_project(
         && (!selection_paths_abs.is_empty() || !name_pattern_only_for_discovery)
 }
 
-fn empty_project_output() -> ProjectRunOutput {
+fn skipped_project_output(unit_label: String) -> ProjectRunOutput {
     ProjectRunOutput {
         exit_code: 0,
         bridge: None,
@@ -189,26 +242,34 @@ fn empty_project_output() -> ProjectRunOutput {
         captured_stderr: vec![],
         coverage_failure_lines: vec![],
         raw_output: String::new(),
+        skipped: Some(unit_label),
     }
 }
 
 fn build_cmd_args(
     ctx: &RunProjectContext<'_>,
-    cfg_path: &Path,
+    project: &JestProject,
     cfg_token: &str,
     tests_for_project: &[String],
-) -> Vec<String> {
+) -> Result<Vec<String>, RunError> {
     let mut cmd_args = ctx.base_cmd_args.to_vec();
     cmd_args.extend(["--config".to_string(), cfg_token.to_string()]);
+    cmd_args.extend(project.select_args());
     cmd_args.extend(ctx.args.runner_args.iter().cloned());
     ensure_watchman_disabled_by_default(&mut cmd_args);
     append_cache_and_execution_flags(&mut cmd_args, ctx.args);
-    append_coverage_flags(&mut cmd_args, cfg_path, ctx);
+    super::shuffle::append_sequencer_args(&mut cmd_args, jest_asset_dir(ctx), ctx.args)?;
+    append_coverage_flags(&mut cmd_args, project, ctx);
     ctx.args
         .show_logs
         .then(|| cmd_args.push("--no-silent".to_string()));
     append_test_selection_args(&mut cmd_args, ctx, tests_for_project);
-    cmd_args
+    Ok(cmd_args)
+}
+
+/// Where `write_jest_assets` put the reporter and setup file.
+fn jest_asset_dir<'a>(ctx: &RunProjectContext<'a>) -> &'a Path {
+    ctx.out_json_base.parent().unwrap_or(ctx.repo_root)
 }
 
 fn append_cache_and_execution_flags(cmd_args: &mut Vec<String>, args: &ParsedArgs) {
@@ -218,9 +279,26 @@ fn append_cache_and_execution_flags(cmd_args: &mut Vec<String>, args: &ParsedArg
     if args.sequential {
         cmd_args.push("--runInBand".to_string());
     }
+    if args.debug.is_some() && !cmd_args.iter().any(|t| t.starts_with("--testTimeout")) {
+        cmd_args.push(format!(
+            "--testTimeout={}",
+            crate::debug::JEST_DEBUG_TEST_TIMEOUT_MS
+        ));
+    }
+    if args.bail
+        && !cmd_args
+            .iter()
+            .any(|t| t == "--bail" || t.starts_with("--bail="))
+    {
+        cmd_args.push("--bail".to_string());
+    }
 }
 
-fn append_coverage_flags(cmd_args: &mut Vec<String>, cfg_path: &Path, ctx: &RunProjectContext<'_>) {
+fn append_coverage_flags(
+    cmd_args: &mut Vec<String>,
+    project: &JestProject,
+    ctx: &RunProjectContext<'_>,
+) {
     if !ctx.args.collect_coverage {
         return;
     }
@@ -228,10 +306,13 @@ fn append_coverage_flags(cmd_args: &mut Vec<String>, cfg_path: &Path, ctx: &RunP
         .iter()
         .any(|t| t == "--coverage" || t.starts_with("--coverage="));
     (!has_coverage_arg).then(|| {
+        cmd_args.push("--coverage".to_string());
+        cmd_args.push(format!(
+            "--coverageProvider={}",
+            ctx.args.coverage_provider.as_str()
+        ));
         cmd_args.extend(
             [
-                "--coverage",
-                "--coverageProvider=babel",
                 "--coverageReporters=lcov",
                 "--coverageReporters=json",
                 "--coverageReporters=text-summary",
@@ -242,13 +323,23 @@ fn append_coverage_flags(cmd_args: &mut Vec<String>, cfg_path: &Path, ctx: &RunP
     });
     cmd_args.push(format!(
         "--coverageDirectory={}",
-        coverage_dir_for_config_in_root(cfg_path, ctx.coverage_root).to_string_lossy()
+        coverage_dir_for_project_in_root(project, ctx.repo_root, ctx.coverage_root)
+            .to_string_lossy()
     ));
-    cmd_args.extend(collect_coverage_from_args(
+    let collect_from = collect_coverage_from_args(
         ctx.repo_root,
         ctx.selection_paths_abs,
         &ctx.args.selection_paths,
-    ));
+    );
+    if collect_from.is_empty() {
+        headlamp_core::coverage::scope::collect_coverage_from_globs(&ctx.args.coverage_scope)
+            .into_iter()
+            .for_each(|glob| {
+                cmd_args.push("--collectCoverageFrom".to_string());
+                cmd_args.push(glob);
+            });
+    }
+    cmd_args.extend(collect_from);
 }
 
 fn append_test_selection_args(
@@ -268,21 +359,64 @@ fn append_test_selection_args(
 fn execute_jest_for_project(
     ctx: &RunProjectContext<'_>,
     live_progress: &LiveProgress,
+    unit_label: &str,
     out_json: &Path,
     cmd_args: Vec<String>,
+    vm_modules: bool,
 ) -> Result<ProjectExecution, RunError> {
     let emit_raw_lines = ctx.args.ci;
-    let mut command = std::process::Command::new(ctx.jest_bin);
+    let tag = ctx.tag_units.then(|| unit_tag(unit_label));
+    if reuses_workers(ctx.args, ctx.jest_bin) {
+        let mut adapter = super::streaming::JestStreamingAdapter::new(
+            ctx.repo_root,
+            emit_raw_lines,
+            ctx.args.only_failures,
+        );
+        let run = WorkerRun {
+            key: WorkerKey {
+                repo_root: ctx.repo_root.to_path_buf(),
+                unit: unit_label.to_string(),
+                vm_modules,
+            },
+            jest_bin: ctx.jest_bin,
+            run_env: ctx.run_env,
+            asset_dir: jest_asset_dir(ctx),
+            cmd_args: &cmd_args,
+            out_json,
+        };
+        let mut tagged = UnitTagged::new(&mut adapter, tag.as_deref());
+        if let Some(exit_code) = run_in_worker(run, live_progress, &mut tagged)? {
+            return build_project_execution(
+                exit_code,
+                ctx.name_pattern_only_for_discovery,
+                out_json,
+                adapter,
+            );
+        }
+    }
+    let mut command = crate::node_toolchain::jest_command(ctx.jest_bin);
     command
         .args(cmd_args)
         .current_dir(ctx.repo_root)
         .env("NODE_ENV", "test")
         .env("FORCE_COLOR", "3")
         .env("JEST_BRIDGE_OUT", out_json.to_string_lossy().to_string());
-    let mut adapter =
-        super::streaming::JestStreamingAdapter::new(emit_raw_lines, ctx.args.only_failures);
+    ctx.run_env.apply(&mut command);
+    apply_node_options(&mut command, ctx.args, vm_modules);
+    if ctx.args.collect_coverage && ctx.args.coverage_provider == CoverageProvider::V8 {
+        command.env(
+            "NODE_V8_COVERAGE",
+            super::coverage::v8_dump_dir(ctx.coverage_root),
+        );
+    }
+    let mut adapter = super::streaming::JestStreamingAdapter::new(
+        ctx.repo_root,
+        emit_raw_lines,
+        ctx.args.only_failures,
+    );
+    let mut tagged = UnitTagged::new(&mut adapter, tag.as_deref());
     let (exit_code, _tail) =
-        run_streaming_capture_tail(command, live_progress, &mut adapter, 1024 * 1024)?;
+        run_streaming_capture_tail_with_events(command, live_progress, &mut tagged, 1024 * 1024)?;
     build_project_execution(
         exit_code,
         ctx.name_pattern_only_for_discovery,
@@ -291,6 +425,38 @@ fn execute_jest_for_project(
     )
 }
 
+/// The `displayName` a project was selected by, else its config's directory (`root` for the
+/// repo root's own config).
+fn unit_tag(unit_label: &str) -> String {
+    if let Some((_, name)) = unit_label.split_once('#') {
+        return name.to_string();
+    }
+    Path::new(unit_label)
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "root".to_string())
+}
+
+/// `--experimental-vm-modules` and `--debug`'s inspector go through `NODE_OPTIONS`, on top of
+/// the user's own.
+fn apply_node_options(command: &mut std::process::Command, args: &ParsedArgs, vm_modules: bool) {
+    if !vm_modules && args.debug.is_none() {
+        return;
+    }
+    let mut node_options = std::env::var("NODE_OPTIONS").ok();
+    if vm_modules {
+        node_options = Some(node_options_with_vm_modules(node_options.as_deref()));
+    }
+    if args.debug.is_some() {
+        node_options = Some(crate::debug::node_options_with_inspect(
+            node_options.as_deref(),
+        ));
+        crate::debug::announce_node_inspector();
+    }
+    command.env("NODE_OPTIONS", node_options.unwrap_or_default());
+}
+
 fn build_project_execution(
     exit_code: i32,
     name_pattern_only_for_discovery: bool,
@@ -1,5 +1,5 @@
 use std::collections::BTreeMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use path_slash::PathExt;
 
@@ -19,6 +19,8 @@ use crate::git::changed_files;
 use crate::jest_discovery::{
     JEST_LIST_TESTS_TIMEOUT, discover_jest_list_tests_cached_with_timeout,
 };
+use crate::jest_projects::JestProject;
+use crate::process::RunEnvironment;
 use crate::run::RunError;
 
 mod utils;
@@ -79,8 +81,9 @@ pub(super) fn looks_like_test_path(candidate_path: &str) -> bool {
 pub(super) struct ComputeRelatedSelectionArgs<'a> {
     pub(super) repo_root: &'a Path,
     pub(super) args: &'a ParsedArgs,
-    pub(super) project_configs: &'a [PathBuf],
+    pub(super) project_configs: &'a [JestProject],
     pub(super) jest_bin: &'a Path,
+    pub(super) run_env: &'a RunEnvironment,
     pub(super) discovery_args: &'a [String],
     pub(super) dependency_language: DependencyLanguageId,
     pub(super) selection_key: Option<&'a str>,
@@ -121,6 +124,7 @@ pub(super) fn compute_related_selection(
             dependency_language: compute_args.dependency_language,
             project_configs: compute_args.project_configs,
             jest_bin: compute_args.jest_bin,
+            run_env: compute_args.run_env,
             discovery_args: compute_args.discovery_args,
             production_seeds_abs: compute_args.production_seeds_abs,
             selection_exclude_globs: compute_args.selection_exclude_globs,
@@ -150,8 +154,9 @@ fn empty_related_selection() -> RelatedTestSelection {
 struct ComputeRelatedFromFastTestsArgs<'a> {
     repo_root: &'a Path,
     dependency_language: DependencyLanguageId,
-    project_configs: &'a [PathBuf],
+    project_configs: &'a [JestProject],
     jest_bin: &'a Path,
+    run_env: &'a RunEnvironment,
     discovery_args: &'a [String],
     production_seeds_abs: &'a [String],
     selection_exclude_globs: &'a [String],
@@ -169,6 +174,7 @@ fn compute_related_selection_from_fast_tests(
         dependency_language,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         production_seeds_abs,
         selection_exclude_globs,
@@ -184,6 +190,7 @@ fn compute_related_selection_from_fast_tests(
             dependency_language,
             project_configs,
             jest_bin,
+            run_env,
             discovery_args,
             production_seeds_abs,
             selection_exclude_globs,
@@ -246,8 +253,9 @@ fn related_selection_without_transitive_refine(
 struct TransitiveRefineSelectionArgs<'a> {
     repo_root: &'a Path,
     dependency_language: DependencyLanguageId,
-    project_configs: &'a [PathBuf],
+    project_configs: &'a [JestProject],
     jest_bin: &'a Path,
+    run_env: &'a RunEnvironment,
     discovery_args: &'a [String],
     production_seeds_abs: &'a [String],
     selection_exclude_globs: &'a [String],
@@ -264,6 +272,7 @@ fn related_selection_with_transitive_refine(
         dependency_language,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         production_seeds_abs,
         selection_exclude_globs,
@@ -288,6 +297,7 @@ fn related_selection_with_transitive_refine(
         dependency_language,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         production_seeds_abs,
         candidate_tests_abs,
@@ -326,8 +336,9 @@ fn augment_with_http_tests(
 struct RefineByTransitiveSeedScanArgs<'a> {
     repo_root: &'a Path,
     dependency_language: DependencyLanguageId,
-    project_configs: &'a [PathBuf],
+    project_configs: &'a [JestProject],
     jest_bin: &'a Path,
+    run_env: &'a RunEnvironment,
     discovery_args: &'a [String],
     production_seeds_abs: &'a [String],
     candidate_tests_abs: Vec<String>,
@@ -343,6 +354,7 @@ fn refine_by_transitive_seed_scan(
         dependency_language,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         production_seeds_abs,
         candidate_tests_abs,
@@ -360,6 +372,7 @@ fn refine_by_transitive_seed_scan(
         repo_root,
         project_configs,
         jest_bin,
+        run_env,
         discovery_args,
         no_cache,
     });
@@ -388,46 +401,36 @@ fn refine_by_transitive_seed_scan(
 #[derive(Debug)]
 struct DiscoverAllTestsArgs<'a> {
     repo_root: &'a Path,
-    project_configs: &'a [PathBuf],
+    project_configs: &'a [JestProject],
     jest_bin: &'a Path,
+    run_env: &'a RunEnvironment,
     discovery_args: &'a [String],
     no_cache: bool,
 }
 
 fn discover_tests_for_config(
-    repo_root: &Path,
-    cfg_path: &Path,
-    jest_bin: &Path,
-    discovery_args: &[String],
-    no_cache: bool,
+    args: &DiscoverAllTestsArgs<'_>,
+    project: &JestProject,
 ) -> Option<Vec<String>> {
-    let cfg_token = config_token(repo_root, cfg_path);
-    let mut list_args = discovery_args.to_vec();
+    let cfg_token = config_token(args.repo_root, &project.config);
+    let mut list_args = args.discovery_args.to_vec();
     list_args.extend(["--config".to_string(), cfg_token]);
+    list_args.extend(project.select_args());
     discover_jest_list_tests_cached_with_timeout(
-        cfg_path.parent().unwrap_or(repo_root),
-        jest_bin,
+        project.discovery_cwd(args.repo_root),
+        args.jest_bin,
+        args.run_env,
         &list_args,
-        no_cache,
+        args.no_cache,
         JEST_LIST_TESTS_TIMEOUT,
     )
     .ok()
 }
 
 fn discover_all_tests_for_transitive_scan(args: DiscoverAllTestsArgs<'_>) -> Vec<String> {
-    let DiscoverAllTestsArgs {
-        repo_root,
-        project_configs,
-        jest_bin,
-        discovery_args,
-        no_cache,
-    } = args;
-
-    project_configs
+    args.project_configs
         .iter()
-        .filter_map(|cfg_path| {
-            discover_tests_for_config(repo_root, cfg_path, jest_bin, discovery_args, no_cache)
-        })
+        .filter_map(|project| discover_tests_for_config(&args, project))
         .flatten()
         .collect::<IndexSet<_>>()
         .into_iter()
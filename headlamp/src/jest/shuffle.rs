@@ -0,0 +1,37 @@
+//! `--shuffle` for jest: a custom `--testSequencer` that orders test files with headlamp's seeded
+//! shuffle, reading the seed from the run environment (or, for `headlamp bisect-order`, the
+//! listed order).
+
+use std::path::Path;
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::run::RunError;
+
+const JEST_SEQUENCER_BYTES: &[u8] = include_bytes!("../../assets/jest/sequencer.cjs");
+
+/// Writes the sequencer next to the reporter and setup file; a `--testSequencer` of the user's
+/// own is left alone.
+pub(super) fn append_sequencer_args(
+    cmd_args: &mut Vec<String>,
+    asset_dir: &Path,
+    args: &ParsedArgs,
+) -> Result<(), RunError> {
+    let user_sequencer = cmd_args
+        .iter()
+        .any(|t| t == "--testSequencer" || t.starts_with("--testSequencer="));
+    let listed_order = args
+        .env_vars
+        .iter()
+        .any(|entry| entry.starts_with(&format!("{}=", crate::shuffle::ORDER_ENV)));
+    if (args.shuffle_seed.is_none() && !listed_order) || user_sequencer {
+        return Ok(());
+    }
+    let sequencer_path =
+        super::coverage::write_asset(&asset_dir.join("sequencer.cjs"), JEST_SEQUENCER_BYTES)?;
+    cmd_args.extend([
+        "--testSequencer".to_string(),
+        sequencer_path.to_string_lossy().to_string(),
+    ]);
+    Ok(())
+}
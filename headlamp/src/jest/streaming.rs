@@ -1,10 +1,13 @@
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use headlamp_core::test_model::{TestConsoleEntry, TestRunModel};
 
 use crate::live_progress::{outcome_from_status, render_finished_test_line};
 use crate::streaming::{OutputStream, StreamAction, StreamAdapter};
 
+use super::bridge_protocol::{BridgeEvent, parse_bridge_event_line};
+
 #[derive(Debug)]
 pub(super) struct JestStreamingAdapter {
     pub(super) emit_raw_lines: bool,
@@ -12,23 +15,109 @@ pub(super) struct JestStreamingAdapter {
     pub(super) captured_stdout: Vec<String>,
     pub(super) captured_stderr: Vec<String>,
     pub(super) extra_bridge_entries_by_test_path: BTreeMap<String, Vec<TestConsoleEntry>>,
+    repo_root: PathBuf,
+    saw_event_stream: bool,
 }
 
 impl JestStreamingAdapter {
-    pub(super) fn new(emit_raw_lines: bool, only_failures: bool) -> Self {
+    pub(super) fn new(repo_root: &Path, emit_raw_lines: bool, only_failures: bool) -> Self {
         Self {
             emit_raw_lines,
             only_failures,
             captured_stdout: vec![],
             captured_stderr: vec![],
             extra_bridge_entries_by_test_path: BTreeMap::new(),
+            repo_root: repo_root.to_path_buf(),
+            saw_event_stream: false,
+        }
+    }
+
+    fn display_path(&self, test_path: &str) -> String {
+        Path::new(test_path)
+            .strip_prefix(&self.repo_root)
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| test_path.to_string())
+    }
+
+    fn actions_for_event_stream_line(&mut self, line: &str) -> Vec<StreamAction> {
+        let Some(event) = parse_bridge_event_line(line) else {
+            return vec![];
+        };
+        self.saw_event_stream = true;
+        match event {
+            BridgeEvent::TestFileStart { test_path } => {
+                vec![StreamAction::SetProgressLabel(
+                    self.display_path(&test_path),
+                )]
+            }
+            BridgeEvent::CaseStart {
+                test_path,
+                full_name,
+            } => self.actions_for_case_start(&test_path.replace('\\', "/"), full_name.trim()),
+            BridgeEvent::CaseComplete {
+                test_path,
+                full_name,
+                status,
+                duration_ms,
+            } => self.actions_for_case_complete(
+                &test_path.replace('\\', "/"),
+                full_name.trim(),
+                status.trim(),
+                duration_ms,
+            ),
+            BridgeEvent::RunStart { .. }
+            | BridgeEvent::TestFileComplete { .. }
+            | BridgeEvent::RunComplete => vec![],
         }
     }
 
+    /// The frame counts passes and failures from the outcomes, so the label only names the test.
+    fn progress_label(&self, test_path: &str, full_name: &str) -> String {
+        format!("{} › {full_name}", self.display_path(test_path))
+    }
+
+    fn actions_for_case_start(&self, test_path: &str, full_name: &str) -> Vec<StreamAction> {
+        if full_name.is_empty() {
+            return vec![];
+        }
+        vec![
+            StreamAction::TestStarted,
+            StreamAction::SetProgressLabel(self.progress_label(test_path, full_name)),
+        ]
+    }
+
+    fn actions_for_case_complete(
+        &self,
+        test_path: &str,
+        full_name: &str,
+        status: &str,
+        duration_ms: Option<u64>,
+    ) -> Vec<StreamAction> {
+        if full_name.is_empty() || status.is_empty() {
+            return vec![];
+        }
+        let outcome = outcome_from_status(status);
+        let label = self.progress_label(test_path, full_name);
+        if self.only_failures && !status.eq_ignore_ascii_case("failed") {
+            return vec![
+                StreamAction::RecordOutcome(outcome),
+                StreamAction::SetProgressLabel(label),
+            ];
+        }
+        let duration = duration_ms.map(std::time::Duration::from_millis);
+        let line = render_finished_test_line(outcome, duration, test_path, full_name);
+        vec![
+            StreamAction::RecordOutcome(outcome),
+            StreamAction::SetProgressLabel(label),
+            StreamAction::PrintStdout(line),
+        ]
+    }
+
     fn push_non_event_line(&mut self, stream: OutputStream, line: &str) {
         match stream {
             OutputStream::Stdout => self.captured_stdout.push(line.to_string()),
             OutputStream::Stderr => self.captured_stderr.push(line.to_string()),
+            OutputStream::Events => {}
         }
     }
 
@@ -54,39 +143,22 @@ impl JestStreamingAdapter {
                     origin: None,
                 });
         }
-        let Some(event) = event else {
-            return vec![];
-        };
-        if event.type_name != "caseComplete" {
-            return vec![];
-        }
-        let Some(full_name) = event
-            .full_name
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-        else {
-            return vec![];
-        };
-        let Some(status) = event
-            .status
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-        else {
+        // v1 reporters only report results this way; once the event stream is live it is
+        // authoritative, so never count a test twice.
+        let Some(event) = event.filter(|_| !self.saw_event_stream) else {
             return vec![];
         };
-        if self.only_failures && !status.eq_ignore_ascii_case("failed") {
-            return vec![];
+        let full_name = event.full_name.as_deref().unwrap_or("").trim();
+        match event.type_name.as_str() {
+            "caseStart" => self.actions_for_case_start(&test_path, full_name),
+            "caseComplete" => self.actions_for_case_complete(
+                &test_path,
+                full_name,
+                event.status.as_deref().unwrap_or("").trim(),
+                event.duration_ms,
+            ),
+            _ => vec![],
         }
-        let duration = event.duration_ms.map(std::time::Duration::from_millis);
-        let line = render_finished_test_line(
-            outcome_from_status(status),
-            duration,
-            test_path.as_str(),
-            full_name,
-        );
-        vec![StreamAction::PrintStdout(line)]
     }
 }
 
@@ -96,6 +168,9 @@ impl StreamAdapter for JestStreamingAdapter {
     }
 
     fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        if stream == OutputStream::Events {
+            return self.actions_for_event_stream_line(line);
+        }
         if line.starts_with("[JEST-BRIDGE-EVENT] ") {
             return self.actions_for_bridge_event_line(line);
         }
@@ -108,6 +183,7 @@ impl StreamAdapter for JestStreamingAdapter {
         match stream {
             OutputStream::Stdout => vec![StreamAction::PrintStdout(line.to_string())],
             OutputStream::Stderr => vec![StreamAction::PrintStderr(line.to_string())],
+            OutputStream::Events => vec![],
         }
     }
 }
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::live_progress::LiveProgress;
+use crate::process::{ChildGroupGuard, RunEnvironment, isolate_process_group};
+use crate::run::RunError;
+use crate::streaming::{OutputStream, StreamAdapter, dispatch_line, spawn_lines_thread};
+
+const JEST_WORKER_BYTES: &[u8] = include_bytes!("../../assets/jest/worker.cjs");
+
+const WORKER_MARKER: &str = "[HEADLAMP-WORKER] ";
+
+/// Loading jest (and a TS/ESM config) in a cold node can be slow; past this the worker is
+/// abandoned and jest is spawned the usual way.
+const WORKER_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Idle workers between watch iterations. A run takes its worker out of the map for the
+/// duration, so parallel projects never share one.
+static IDLE_WORKERS: LazyLock<Mutex<HashMap<WorkerKey, JestWorker>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// One warm jest per run unit; the node flags a worker was started with cannot change later.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct WorkerKey {
+    pub(super) repo_root: PathBuf,
+    pub(super) unit: String,
+    pub(super) vm_modules: bool,
+}
+
+#[derive(Debug)]
+struct JestWorker {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<(OutputStream, String)>,
+    _group: ChildGroupGuard,
+}
+
+impl Drop for JestWorker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct WorkerRun<'a> {
+    pub(super) key: WorkerKey,
+    pub(super) jest_bin: &'a Path,
+    pub(super) run_env: &'a RunEnvironment,
+    pub(super) asset_dir: &'a Path,
+    pub(super) cmd_args: &'a [String],
+    pub(super) out_json: &'a Path,
+}
+
+/// Watch reruns go through a warm worker. Coverage runs still get a fresh jest per run (V8
+/// coverage is gathered per process), as does Yarn PnP, whose loader only `yarn` sets up.
+pub(super) fn reuses_workers(args: &ParsedArgs, jest_bin: &Path) -> bool {
+    args.watch
+        && !args.collect_coverage
+        && args.debug.is_none()
+        && jest_bin.file_name().is_some_and(|name| name != ".pnp.cjs")
+}
+
+/// Runs jest in the unit's warm worker, starting one when there is none yet. `Ok(None)` means no
+/// worker is available (e.g. a jest without the programmatic API) and the caller should spawn
+/// jest itself.
+pub(super) fn run_in_worker(
+    run: WorkerRun<'_>,
+    progress: &LiveProgress,
+    adapter: &mut dyn StreamAdapter,
+) -> Result<Option<i32>, RunError> {
+    let request = serde_json::json!({
+        "argv": run.cmd_args,
+        "env": { "JEST_BRIDGE_OUT": run.out_json.to_string_lossy() },
+    })
+    .to_string();
+    let Some(mut worker) = worker_for_request(&run, &request)? else {
+        return Ok(None);
+    };
+    if let Some(label) = adapter.on_start() {
+        progress.set_current_label(label);
+    }
+    let exit_code = stream_until_done(&worker.lines, progress, adapter);
+    crate::interrupt::park_if_interrupted();
+    match exit_code {
+        Some(code) => {
+            if let Ok(mut idle) = IDLE_WORKERS.lock() {
+                idle.insert(run.key, worker);
+            }
+            Ok(Some(code))
+        }
+        // The worker died mid-run; what it printed has already been streamed.
+        None => {
+            let status = worker.child.wait().map_err(RunError::WaitFailed)?;
+            Ok(Some(status.code().unwrap_or(1)))
+        }
+    }
+}
+
+/// The unit's idle worker when it still accepts requests, else a freshly started one.
+fn worker_for_request(run: &WorkerRun<'_>, request: &str) -> Result<Option<JestWorker>, RunError> {
+    let idle = IDLE_WORKERS
+        .lock()
+        .ok()
+        .and_then(|mut idle| idle.remove(&run.key));
+    if let Some(mut worker) = idle
+        && send_request(&mut worker, request)
+    {
+        return Ok(Some(worker));
+    }
+    let Some(mut worker) = start_worker(run)? else {
+        return Ok(None);
+    };
+    Ok(send_request(&mut worker, request).then_some(worker))
+}
+
+fn send_request(worker: &mut JestWorker, request: &str) -> bool {
+    writeln!(worker.stdin, "{request}")
+        .and_then(|()| worker.stdin.flush())
+        .is_ok()
+}
+
+fn start_worker(run: &WorkerRun<'_>) -> Result<Option<JestWorker>, RunError> {
+    let script =
+        super::coverage::write_asset(&run.asset_dir.join("worker.cjs"), JEST_WORKER_BYTES)?;
+    let (stdout_reader, stdout_writer) = os_pipe::pipe().map_err(RunError::SpawnFailed)?;
+    let (stderr_reader, stderr_writer) = os_pipe::pipe().map_err(RunError::SpawnFailed)?;
+    let mut command = crate::node_toolchain::node_command_for(run.jest_bin);
+    command
+        .arg(&script)
+        .current_dir(&run.key.repo_root)
+        .env("NODE_ENV", "test")
+        .env("FORCE_COLOR", "3")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(stdout_writer))
+        .stderr(Stdio::from(stderr_writer));
+    if run.key.vm_modules {
+        let existing = std::env::var("NODE_OPTIONS").ok();
+        command.env(
+            "NODE_OPTIONS",
+            crate::jest_esm::node_options_with_vm_modules(existing.as_deref()),
+        );
+    }
+    run.run_env.apply(&mut command);
+    isolate_process_group(&mut command);
+    let Ok(mut child) = command.spawn() else {
+        return Ok(None);
+    };
+    drop(command);
+    let group = ChildGroupGuard::register(&child);
+    let Some(stdin) = child.stdin.take() else {
+        return Ok(None);
+    };
+    let (tx, lines) = mpsc::channel();
+    spawn_lines_thread(stdout_reader, tx.clone(), OutputStream::Stdout);
+    spawn_lines_thread(stderr_reader, tx, OutputStream::Stderr);
+    let worker = JestWorker {
+        child,
+        stdin,
+        lines,
+        _group: group,
+    };
+    Ok(wait_until_ready(&worker.lines).then_some(worker))
+}
+
+/// The worker announces itself on both streams; only stdout's copy is awaited, and the stderr
+/// one is skipped as a marker line during the first run.
+fn wait_until_ready(lines: &Receiver<(OutputStream, String)>) -> bool {
+    let deadline = Instant::now() + WORKER_READY_TIMEOUT;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match lines.recv_timeout(remaining) {
+            Ok((OutputStream::Stdout, line)) => {
+                if let Some(message) = worker_message(&line) {
+                    return message.get("ready").and_then(|v| v.as_bool()) == Some(true);
+                }
+            }
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return false,
+        }
+    }
+}
+
+/// Streams one run's output; the run is over once the `done` marker arrived on both stdout and
+/// stderr. `None` when the worker exits first.
+fn stream_until_done(
+    lines: &Receiver<(OutputStream, String)>,
+    progress: &LiveProgress,
+    adapter: &mut dyn StreamAdapter,
+) -> Option<i32> {
+    let (mut stdout_done, mut stderr_done) = (false, false);
+    let mut exit_code = 1;
+    while !(stdout_done && stderr_done) {
+        let (stream, line) = lines.recv().ok()?;
+        let Some(message) = worker_message(&line) else {
+            dispatch_line(progress, adapter, stream, &line);
+            continue;
+        };
+        if message.get("done").and_then(|v| v.as_bool()) != Some(true) {
+            continue;
+        }
+        if let Some(code) = message.get("exitCode").and_then(|v| v.as_i64()) {
+            exit_code = i32::try_from(code).unwrap_or(1);
+        }
+        match stream {
+            OutputStream::Stdout => stdout_done = true,
+            _ => stderr_done = true,
+        }
+    }
+    Some(exit_code)
+}
+
+fn worker_message(line: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(line.strip_prefix(WORKER_MARKER)?).ok()
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use headlamp_core::jest::jest_streaming_actions_for_lines;
+use headlamp_core::live_progress::TestOutcome;
+use headlamp_core::streaming::{OutputStream, StreamAction};
+
+fn labels(actions: &[StreamAction]) -> Vec<String> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            StreamAction::SetProgressLabel(label) => Some(label.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn outcomes(actions: &[StreamAction]) -> Vec<TestOutcome> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            StreamAction::RecordOutcome(outcome) => Some(*outcome),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn v2_event_stream_labels_progress_with_the_current_test() {
+    let actions = jest_streaming_actions_for_lines(
+        Path::new("/repo"),
+        true,
+        &[
+            (
+                OutputStream::Events,
+                r#"{"v":2,"type":"testFileStart","testPath":"/repo/src/a.test.ts"}"#,
+            ),
+            (
+                OutputStream::Events,
+                r#"{"v":2,"type":"caseStart","testPath":"/repo/src/a.test.ts","fullName":"adds"}"#,
+            ),
+            (
+                OutputStream::Events,
+                r#"{"v":2,"type":"caseComplete","testPath":"/repo/src/a.test.ts","fullName":"adds","status":"failed","duration":4}"#,
+            ),
+            (
+                OutputStream::Events,
+                r#"{"v":2,"type":"caseComplete","testPath":"/repo/src/a.test.ts","fullName":"subtracts","status":"passed"}"#,
+            ),
+        ],
+    );
+    assert_eq!(
+        labels(&actions),
+        vec![
+            "src/a.test.ts".to_string(),
+            "src/a.test.ts › adds".to_string(),
+            "src/a.test.ts › adds".to_string(),
+            "src/a.test.ts › subtracts".to_string(),
+        ]
+    );
+    let started = actions
+        .iter()
+        .filter(|action| matches!(action, StreamAction::TestStarted))
+        .count();
+    assert_eq!(started, 1);
+    assert_eq!(
+        outcomes(&actions),
+        vec![TestOutcome::Fail, TestOutcome::Pass]
+    );
+}
+
+#[test]
+fn v1_stderr_case_events_are_used_until_the_event_stream_is_live() {
+    let v1_start = r#"[JEST-BRIDGE-EVENT] {"type":"caseStart","testPath":"/repo/a.test.js","fullName":"works"}"#;
+    let v1 = r#"[JEST-BRIDGE-EVENT] {"type":"caseComplete","testPath":"/repo/a.test.js","fullName":"works","status":"passed"}"#;
+    let v2 = r#"{"v":2,"type":"caseComplete","testPath":"/repo/a.test.js","fullName":"works","status":"passed"}"#;
+    let v1_only = jest_streaming_actions_for_lines(
+        Path::new("/repo"),
+        false,
+        &[(OutputStream::Stderr, v1_start), (OutputStream::Stderr, v1)],
+    );
+    assert!(matches!(v1_only[0], StreamAction::TestStarted));
+    assert_eq!(labels(&v1_only), vec!["a.test.js › works"; 2]);
+    assert_eq!(outcomes(&v1_only), vec![TestOutcome::Pass]);
+
+    let both = jest_streaming_actions_for_lines(
+        Path::new("/repo"),
+        false,
+        &[(OutputStream::Events, v2), (OutputStream::Stderr, v1)],
+    );
+    assert_eq!(outcomes(&both), vec![TestOutcome::Pass]);
+}
+
+#[test]
+fn event_stream_ignores_other_protocol_versions() {
+    let actions = jest_streaming_actions_for_lines(
+        Path::new("/repo"),
+        false,
+        &[(
+            OutputStream::Events,
+            r#"{"v":3,"type":"caseComplete","testPath":"/repo/a.test.js","fullName":"works","status":"passed"}"#,
+        )],
+    );
+    assert!(actions.is_empty());
+}
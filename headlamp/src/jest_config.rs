@@ -1,12 +1,17 @@
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
 use path_slash::PathExt;
+use regex::Regex;
 
-const CANDIDATE_FILENAMES: [&str; 6] = [
+use crate::jest_projects::JestProject;
+
+const CANDIDATE_FILENAMES: [&str; 7] = [
     "jest.config.cjs",
     "jest.config.js",
     "jest.config.mjs",
     "jest.config.ts",
+    "jest.config.cts",
     "jest.ts.config.js",
     "jest.ts.config.cjs",
 ];
@@ -39,3 +44,100 @@ pub fn append_config_arg_if_missing(args: &[String], repo_root: &Path) -> Vec<St
         .chain(["--config".to_string(), config_token])
         .collect()
 }
+
+/// The config a jest config file exports, evaluated without running node: CommonJS and ES
+/// modules, TypeScript, `defineConfig(...)`, and (async) config functions. `None` when the file
+/// does more than the evaluator understands; callers fall back to reading the source text.
+pub fn load_jest_config_value(config_path: &Path) -> Option<serde_json::Value> {
+    crate::config_ts::load_config_module_oxc(config_path)
+        .ok()
+        .filter(serde_json::Value::is_object)
+}
+
+/// A config's `displayName`, either `displayName: 'web'` or `displayName: { name: 'web' }`.
+pub fn jest_display_name(config_path: &Path) -> Option<String> {
+    let evaluated = load_jest_config_value(config_path).and_then(|config| {
+        let display_name = config.get("displayName")?;
+        display_name
+            .as_str()
+            .or_else(|| display_name.get("name")?.as_str())
+            .map(str::to_string)
+    });
+    if evaluated.is_some() {
+        return evaluated;
+    }
+    static DISPLAY_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"displayName['"]?\s*:\s*(?:\{[^}]*?name['"]?\s*:\s*)?['"`]([^'"`]+)['"`]"#)
+            .unwrap()
+    });
+    let text = std::fs::read_to_string(config_path).ok()?;
+    DISPLAY_NAME_RE
+        .captures(&text)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// `selector` names a project by `displayName`, by config path, or by the config's directory
+/// (all relative to the repo root).
+pub fn jest_project_matches(config_path: &Path, repo_root: &Path, selector: &str) -> bool {
+    let selector = selector
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    let rel = |path: &Path| {
+        path.strip_prefix(repo_root)
+            .unwrap_or(path)
+            .to_slash_lossy()
+            .to_string()
+    };
+    let config_rel = rel(config_path);
+    let dir = if config_path.is_dir() {
+        config_path
+    } else {
+        config_path.parent().unwrap_or(repo_root)
+    };
+    let dir_rel = rel(dir);
+    selector == config_rel
+        || (!selector.is_empty() && selector == dir_rel)
+        || (selector == "." && dir_rel.is_empty())
+        || jest_display_name(config_path).is_some_and(|name| name == selector)
+}
+
+/// Applies `--project` / `--ignore-project`; an include list that matches nothing is an error
+/// rather than a silently empty run. A project selected out of a `projects` array also matches
+/// its own `displayName`.
+pub fn filter_jest_projects(
+    projects: Vec<JestProject>,
+    repo_root: &Path,
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<JestProject>, String> {
+    let matches_any = |project: &JestProject, selectors: &[String]| {
+        selectors.iter().any(|selector| {
+            project.select.as_deref() == Some(selector.trim())
+                || jest_project_matches(&project.config, repo_root, selector)
+        })
+    };
+    let available = projects.clone();
+    let selected = projects
+        .into_iter()
+        .filter(|project| include.is_empty() || matches_any(project, include))
+        .filter(|project| !matches_any(project, exclude))
+        .collect::<Vec<_>>();
+    if selected.is_empty() && !include.is_empty() {
+        let names = available
+            .iter()
+            .map(|project| {
+                project
+                    .display_name()
+                    .unwrap_or_else(|| project.label(repo_root))
+            })
+            .collect::<Vec<_>>();
+        return Err(format!(
+            "no jest project matches --project {}; available: {}",
+            include.join(","),
+            names.join(", ")
+        ));
+    }
+    Ok(selected)
+}
@@ -1,5 +1,4 @@
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::Duration;
 
 use path_slash::PathExt;
@@ -8,7 +7,7 @@ use tempfile::NamedTempFile;
 use crate::fast_related::FAST_RELATED_TIMEOUT;
 use crate::fast_related::{DEFAULT_TEST_GLOBS, cached_related, find_related_tests_fast};
 use crate::jest_config::append_config_arg_if_missing;
-use crate::process::run_command_capture_with_timeout;
+use crate::process::{RunEnvironment, run_command_capture_with_timeout};
 use crate::run::RunError;
 use sha1::{Digest, Sha1};
 
@@ -64,14 +63,22 @@ pub fn args_for_discovery(jest_args: &[String]) -> Vec<String> {
 pub fn discover_jest_list_tests(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
 ) -> Result<Vec<String>, RunError> {
-    discover_jest_list_tests_with_timeout(repo_root, jest_bin, jest_args, JEST_LIST_TESTS_TIMEOUT)
+    discover_jest_list_tests_with_timeout(
+        repo_root,
+        jest_bin,
+        run_env,
+        jest_args,
+        JEST_LIST_TESTS_TIMEOUT,
+    )
 }
 
 pub fn discover_jest_list_tests_with_timeout(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
     timeout: Duration,
 ) -> Result<Vec<String>, RunError> {
@@ -86,12 +93,13 @@ pub fn discover_jest_list_tests_with_timeout(
             .collect::<Vec<_>>()
             .join(" ")
     );
-    let mut command = Command::new(jest_bin);
+    let mut command = crate::node_toolchain::jest_command(jest_bin);
     command
         .args(&args)
         .current_dir(repo_root)
         .env("CI", "1")
         .env("NODE_ENV", "test");
+    run_env.apply(&mut command);
     let out = run_command_capture_with_timeout(command, display_command, timeout)?;
     if !out.status.success() {
         let exit_code = out.status.code().unwrap_or(1);
@@ -124,12 +132,13 @@ pub fn discover_jest_list_tests_with_timeout(
 pub fn discover_jest_list_tests_cached_with_timeout(
     cwd: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
     no_cache: bool,
     timeout: Duration,
 ) -> Result<Vec<String>, RunError> {
     if no_cache {
-        return discover_jest_list_tests_with_timeout(cwd, jest_bin, jest_args, timeout);
+        return discover_jest_list_tests_with_timeout(cwd, jest_bin, run_env, jest_args, timeout);
     }
     let cache_root = crate::fast_related::default_cache_root();
     let repo_key = crate::fast_related::stable_repo_key_hash_12(cwd);
@@ -156,16 +165,17 @@ pub fn discover_jest_list_tests_cached_with_timeout(
         return Ok(hit.clone());
     };
 
-    let listed = match discover_jest_list_tests_with_timeout(cwd, jest_bin, jest_args, timeout) {
-        Ok(v) => Ok(v),
-        Err(RunError::TimedOut { .. }) => {
-            // Don't silently treat a timeout as "no tests" and definitely don't cache it.
-            // Under load, a single transient timeout would otherwise produce non-deterministic
-            // results (and can poison the cache). Retry once to keep behavior stable.
-            discover_jest_list_tests_with_timeout(cwd, jest_bin, jest_args, timeout)
-        }
-        Err(e) => Err(e),
-    }?;
+    let listed =
+        match discover_jest_list_tests_with_timeout(cwd, jest_bin, run_env, jest_args, timeout) {
+            Ok(v) => Ok(v),
+            Err(RunError::TimedOut { .. }) => {
+                // Don't silently treat a timeout as "no tests" and definitely don't cache it.
+                // Under load, a single transient timeout would otherwise produce non-deterministic
+                // results (and can poison the cache). Retry once to keep behavior stable.
+                discover_jest_list_tests_with_timeout(cwd, jest_bin, run_env, jest_args, timeout)
+            }
+            Err(e) => Err(e),
+        }?;
 
     let mut uniq = listed;
     uniq.sort();
@@ -186,16 +196,29 @@ pub fn discover_jest_list_tests_cached_with_timeout(
     Ok(uniq)
 }
 
+/// What `--listTests` falls back to when it times out: the tests that import the changed
+/// production files, found by the fast related-tests scan.
+#[derive(Debug, Clone, Copy)]
+pub struct RelatedTestsFallback<'a> {
+    pub production_paths_abs: &'a [String],
+    pub exclude_globs: &'a [String],
+    pub no_cache: bool,
+}
+
 pub fn discover_jest_list_tests_resilient_with_timeout(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
-    related_production_paths_abs: &[String],
-    exclude_globs: &[String],
-    no_cache: bool,
+    fallback: RelatedTestsFallback<'_>,
     timeout: Duration,
 ) -> Result<Vec<String>, RunError> {
-    match discover_jest_list_tests_with_timeout(repo_root, jest_bin, jest_args, timeout) {
+    let RelatedTestsFallback {
+        production_paths_abs: related_production_paths_abs,
+        exclude_globs,
+        no_cache,
+    } = fallback;
+    match discover_jest_list_tests_with_timeout(repo_root, jest_bin, run_env, jest_args, timeout) {
         Ok(listed) => Ok(listed),
         Err(RunError::TimedOut { .. }) if related_production_paths_abs.is_empty() => Ok(vec![]),
         Err(RunError::TimedOut { .. }) => {
@@ -228,6 +251,7 @@ pub fn discover_jest_list_tests_resilient_with_timeout(
 pub fn discover_jest_list_tests_for_project(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
     cfg_token: &str,
     cwd: &Path,
@@ -235,6 +259,7 @@ pub fn discover_jest_list_tests_for_project(
     discover_jest_list_tests_for_project_with_timeout(
         repo_root,
         jest_bin,
+        run_env,
         jest_args,
         cfg_token,
         cwd,
@@ -245,51 +270,26 @@ pub fn discover_jest_list_tests_for_project(
 pub fn discover_jest_list_tests_for_project_with_timeout(
     _repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
     cfg_token: &str,
     cwd: &Path,
     timeout: Duration,
 ) -> Result<Vec<String>, RunError> {
-    let mut args = jest_args.to_vec();
-    args.extend(["--config".to_string(), cfg_token.to_string()]);
-    args.push("--listTests".to_string());
-    let display_command = format!(
-        "{} {}",
-        jest_bin.to_string_lossy(),
-        args.iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>()
-            .join(" ")
-    );
-    let mut command = Command::new(jest_bin);
-    command
-        .args(&args)
-        .current_dir(cwd)
-        .env("CI", "1")
-        .env("NODE_ENV", "test");
-    let out = run_command_capture_with_timeout(command, display_command, timeout)?;
-    if !out.status.success() {
-        let exit_code = out.status.code().unwrap_or(1);
-        let stderr_text = String::from_utf8_lossy(&out.stderr);
-        let message = if stderr_text.trim().is_empty() {
-            format!("exit {exit_code}")
-        } else {
-            stderr_text.to_string()
-        };
-        return Err(RunError::CommandFailed { message });
-    }
-    let text = String::from_utf8_lossy(&out.stdout);
-    Ok(text
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(|l| std::path::Path::new(l).to_slash_lossy().to_string())
-        .collect())
+    discover_jest_list_tests_for_project_with_patterns_with_timeout(
+        jest_bin,
+        run_env,
+        jest_args,
+        cfg_token,
+        cwd,
+        &[],
+        timeout,
+    )
 }
 
 pub fn discover_jest_list_tests_for_project_with_patterns_with_timeout(
-    _repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     jest_args: &[String],
     cfg_token: &str,
     cwd: &Path,
@@ -309,12 +309,13 @@ pub fn discover_jest_list_tests_for_project_with_patterns_with_timeout(
             .collect::<Vec<_>>()
             .join(" ")
     );
-    let mut command = Command::new(jest_bin);
+    let mut command = crate::node_toolchain::jest_command(jest_bin);
     command
         .args(&args)
         .current_dir(cwd)
         .env("CI", "1")
         .env("NODE_ENV", "test");
+    run_env.apply(&mut command);
     let out = run_command_capture_with_timeout(command, display_command, timeout)?;
     if !out.status.success() {
         let exit_code = out.status.code().unwrap_or(1);
@@ -336,10 +337,12 @@ pub fn discover_jest_list_tests_for_project_with_patterns_with_timeout(
 }
 
 pub fn jest_bin(repo_root: &Path) -> PathBuf {
-    repo_root
-        .join("node_modules")
-        .join(".bin")
-        .join(if cfg!(windows) { "jest.cmd" } else { "jest" })
+    crate::node_toolchain::resolve_jest_bin(repo_root).unwrap_or_else(|| {
+        repo_root
+            .join("node_modules")
+            .join(".bin")
+            .join(if cfg!(windows) { "jest.cmd" } else { "jest" })
+    })
 }
 
 fn read_json_map(path: &Path) -> Option<std::collections::BTreeMap<String, Vec<String>>> {
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use headlamp_core::config::JestEsm;
+
+use crate::jest_config::load_jest_config_value;
+use crate::jest_projects::JestProject;
+
+const VM_MODULES_FLAG: &str = "--experimental-vm-modules";
+
+/// Jest only loads test files as native ES modules when node runs with
+/// `--experimental-vm-modules`; without it an ESM project fails with "Cannot use import
+/// statement outside a module" even though plain `jest` works through the project's own
+/// `NODE_OPTIONS` script.
+pub fn needs_vm_modules(
+    mode: JestEsm,
+    repo_root: &Path,
+    project: &JestProject,
+    tests: &[String],
+) -> bool {
+    match mode {
+        JestEsm::On => true,
+        JestEsm::Off => false,
+        JestEsm::Auto => is_esm_project(repo_root, project, tests),
+    }
+}
+
+/// A project is treated as ESM when its package (or the repo's) is `"type": "module"`, its
+/// config is an ES module file or sets `extensionsToTreatAsEsm`, or it runs `.mjs` tests.
+pub fn is_esm_project(repo_root: &Path, project: &JestProject, tests: &[String]) -> bool {
+    let config_dir = if project.config.is_dir() {
+        project.config.as_path()
+    } else {
+        project.config.parent().unwrap_or(repo_root)
+    };
+    let config_is_esm_file = project
+        .config
+        .extension()
+        .is_some_and(|ext| ext == "mjs" || ext == "mts");
+    let treats_extensions_as_esm = || {
+        project.config.is_file()
+            && load_jest_config_value(&project.config)
+                .and_then(|config| config.get("extensionsToTreatAsEsm")?.as_array().cloned())
+                .is_some_and(|extensions| !extensions.is_empty())
+    };
+    package_type_is_module(config_dir)
+        || package_type_is_module(repo_root)
+        || config_is_esm_file
+        || tests.iter().any(|test| test.ends_with(".mjs"))
+        || treats_extensions_as_esm()
+}
+
+fn package_type_is_module(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .is_some_and(|package| package.get("type").and_then(|t| t.as_str()) == Some("module"))
+}
+
+/// Appends the flag to any `NODE_OPTIONS` the user already has, without repeating it.
+pub fn node_options_with_vm_modules(existing: Option<&str>) -> String {
+    match existing
+        .map(str::trim)
+        .filter(|options| !options.is_empty())
+    {
+        Some(options) if options.split_whitespace().any(|o| o == VM_MODULES_FLAG) => {
+            options.to_string()
+        }
+        Some(options) => format!("{options} {VM_MODULES_FLAG}"),
+        None => VM_MODULES_FLAG.to_string(),
+    }
+}
@@ -6,6 +6,8 @@ use crate::jest_discovery::{
     JEST_LIST_TESTS_TIMEOUT, discover_jest_list_tests_for_project,
     discover_jest_list_tests_for_project_with_patterns_with_timeout,
 };
+use crate::jest_projects::JestProject;
+use crate::process::RunEnvironment;
 use crate::run::RunError;
 
 fn relative_patterns_for_candidates(config_dir: &Path, candidates_abs: &[String]) -> Vec<String> {
@@ -26,19 +28,19 @@ fn relative_patterns_for_candidates(config_dir: &Path, candidates_abs: &[String]
 }
 
 fn discover_list_tests_with_patterns_best_effort(
-    repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     discovery_args: &[String],
     cfg_token: &str,
-    config_dir: &Path,
+    cwd: &Path,
     relative_patterns: &[String],
 ) -> Vec<String> {
     match discover_jest_list_tests_for_project_with_patterns_with_timeout(
-        repo_root,
         jest_bin,
+        run_env,
         discovery_args,
         cfg_token,
-        config_dir,
+        cwd,
         relative_patterns,
         JEST_LIST_TESTS_TIMEOUT,
     ) {
@@ -51,16 +53,18 @@ fn discover_list_tests_with_patterns_best_effort(
 fn discover_all_tests_in_project_best_effort(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     discovery_args: &[String],
     cfg_token: &str,
-    config_dir: &Path,
+    cwd: &Path,
 ) -> Vec<String> {
     match discover_jest_list_tests_for_project(
         repo_root,
         jest_bin,
+        run_env,
         discovery_args,
         cfg_token,
-        config_dir,
+        cwd,
     ) {
         Ok(v) => v,
         Err(RunError::TimedOut { .. }) => vec![],
@@ -102,23 +106,26 @@ fn absolute_candidates_from_relative(
 pub fn filter_candidates_for_project(
     repo_root: &Path,
     jest_bin: &Path,
+    run_env: &RunEnvironment,
     discovery_args: &[String],
-    config_path: &Path,
+    project: &JestProject,
     candidates_abs: &[String],
 ) -> Result<Vec<String>, RunError> {
     if candidates_abs.is_empty() {
         return Ok(vec![]);
     }
 
-    let config_dir = config_path.parent().unwrap_or(repo_root);
-    let cfg_token = config_token(repo_root, config_path);
+    let config_dir = project.config.parent().unwrap_or(repo_root);
+    let cwd = project.discovery_cwd(repo_root);
+    let cfg_token = config_token(repo_root, &project.config);
+    let discovery_args = [discovery_args, &project.select_args()].concat();
     let relative_patterns = relative_patterns_for_candidates(config_dir, candidates_abs);
     let attempt_norm = discover_list_tests_with_patterns_best_effort(
-        repo_root,
         jest_bin,
-        discovery_args,
+        run_env,
+        &discovery_args,
         &cfg_token,
-        config_dir,
+        cwd,
         &relative_patterns,
     )
     .into_iter()
@@ -131,9 +138,10 @@ pub fn filter_candidates_for_project(
     let all_in_project = discover_all_tests_in_project_best_effort(
         repo_root,
         jest_bin,
-        discovery_args,
+        run_env,
+        &discovery_args,
         &cfg_token,
-        config_dir,
+        cwd,
     )
     .into_iter()
     .map(|p| p.replace('\\', "/"))
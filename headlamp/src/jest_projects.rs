@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+use globset::GlobBuilder;
+use ignore::WalkBuilder;
+use path_slash::PathExt;
+
+use crate::jest_config::{jest_display_name, list_all_jest_configs, load_jest_config_value};
+
+/// One jest run unit: a config file, optionally narrowed with `--selectProjects` to one entry
+/// of that config's `projects` array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JestProject {
+    pub config: PathBuf,
+    pub select: Option<String>,
+}
+
+impl From<PathBuf> for JestProject {
+    fn from(config: PathBuf) -> Self {
+        Self {
+            config,
+            select: None,
+        }
+    }
+}
+
+impl JestProject {
+    pub fn selected(config: PathBuf, display_name: String) -> Self {
+        Self {
+            config,
+            select: Some(display_name),
+        }
+    }
+
+    /// Extra jest args that narrow the config to this unit's project.
+    pub fn select_args(&self) -> Vec<String> {
+        self.select
+            .iter()
+            .flat_map(|name| ["--selectProjects".to_string(), name.clone()])
+            .collect()
+    }
+
+    pub fn display_name(&self) -> Option<String> {
+        self.select
+            .clone()
+            .or_else(|| jest_display_name(&self.config))
+    }
+
+    /// Repo-relative config path, plus `#<displayName>` for a selected project.
+    pub fn label(&self, repo_root: &Path) -> String {
+        let config = self
+            .config
+            .strip_prefix(repo_root)
+            .unwrap_or(&self.config)
+            .to_slash_lossy()
+            .to_string();
+        match &self.select {
+            Some(name) => format!("{config}#{name}"),
+            None => config,
+        }
+    }
+
+    /// Directory `jest --listTests` runs in. Config tokens are repo-relative, so a config nested
+    /// in a package is listed from the repo root rather than from its own directory.
+    pub fn discovery_cwd<'a>(&'a self, repo_root: &'a Path) -> &'a Path {
+        if self.config.is_file() && self.config.starts_with(repo_root) {
+            return repo_root;
+        }
+        self.config.parent().unwrap_or(repo_root)
+    }
+}
+
+/// Replaces each config that has a `projects` array with one unit per project: path and glob
+/// entries run their own config, inline objects are selected from the root config by
+/// `displayName`. A config whose projects cannot all be resolved that way stays a single unit,
+/// which is what jest itself would run.
+pub fn expand_jest_projects(configs: Vec<PathBuf>) -> Vec<JestProject> {
+    configs
+        .into_iter()
+        .flat_map(|config| {
+            expand_config_projects(&config).unwrap_or_else(|| vec![JestProject::from(config)])
+        })
+        .collect()
+}
+
+fn expand_config_projects(config: &Path) -> Option<Vec<JestProject>> {
+    if !config.is_file() {
+        return None;
+    }
+    let value = load_jest_config_value(config)?;
+    let entries = value.get("projects")?.as_array()?;
+    let config_dir = config.parent()?;
+    let root_dir = value
+        .get("rootDir")
+        .and_then(|dir| dir.as_str())
+        .map_or_else(|| config_dir.to_path_buf(), |dir| config_dir.join(dir));
+    let mut units = Vec::new();
+    for entry in entries {
+        match entry {
+            serde_json::Value::String(pattern) => {
+                units.extend(resolve_project_pattern(&root_dir, pattern)?);
+            }
+            serde_json::Value::Object(project) => {
+                units.push(JestProject::selected(
+                    config.to_path_buf(),
+                    inline_display_name(project)?,
+                ));
+            }
+            _ => return None,
+        }
+    }
+    let lists_itself = units
+        .iter()
+        .any(|unit| unit.select.is_none() && same_file(&unit.config, config));
+    (!units.is_empty() && !lists_itself).then_some(units)
+}
+
+fn inline_display_name(project: &serde_json::Map<String, serde_json::Value>) -> Option<String> {
+    let display_name = project.get("displayName")?;
+    display_name
+        .as_str()
+        .or_else(|| display_name.get("name")?.as_str())
+        .map(str::to_string)
+}
+
+/// A `projects` string is a path or glob (relative to `rootDir`) naming project directories or
+/// config files.
+fn resolve_project_pattern(root_dir: &Path, pattern: &str) -> Option<Vec<JestProject>> {
+    if pattern.starts_with('!') {
+        return None;
+    }
+    let root = root_dir.to_slash_lossy().to_string();
+    let pattern = pattern.replace("<rootDir>", &root);
+    let relative = pattern
+        .strip_prefix(&root)
+        .unwrap_or(&pattern)
+        .trim_start_matches('/')
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string();
+    let paths = if relative.contains(['*', '?', '[', '{']) {
+        glob_under(root_dir, &relative)?
+    } else {
+        vec![root_dir.join(&relative)]
+    };
+    paths
+        .into_iter()
+        .map(|path| project_config_for(&path))
+        .collect()
+}
+
+fn glob_under(root_dir: &Path, relative_glob: &str) -> Option<Vec<PathBuf>> {
+    let matcher = GlobBuilder::new(relative_glob)
+        .literal_separator(true)
+        .build()
+        .ok()?
+        .compile_matcher();
+    let mut paths = WalkBuilder::new(root_dir)
+        .filter_entry(|entry| entry.file_name() != "node_modules")
+        .build()
+        .map_while(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.strip_prefix(root_dir)
+                .is_ok_and(|rel| !rel.as_os_str().is_empty() && matcher.is_match(rel))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    Some(paths)
+}
+
+/// A project directory runs its own jest config, or its `package.json` (jest reads the `jest`
+/// key) when it has none.
+fn project_config_for(path: &Path) -> Option<JestProject> {
+    if path.is_file() {
+        return Some(JestProject::from(path.to_path_buf()));
+    }
+    if !path.is_dir() {
+        return None;
+    }
+    list_all_jest_configs(path)
+        .into_iter()
+        .next()
+        .or_else(|| Some(path.join("package.json")).filter(|p| p.is_file()))
+        .map(JestProject::from)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (dunce::canonicalize(a), dunce::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
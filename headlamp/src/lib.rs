@@ -1,38 +1,73 @@
 extern crate self as headlamp_core;
 
+pub mod badges;
 pub mod cargo;
 pub mod cargo_select;
+pub mod checks;
 pub mod fast_related;
 pub mod git;
+pub mod git_hook;
+pub mod hooks;
+pub mod interrupt;
 pub mod jest;
+#[cfg(test)]
+mod jest_bridge_protocol_test;
 pub mod jest_config;
 #[cfg(test)]
 mod jest_coverage_test;
 pub mod jest_discovery;
+pub mod jest_esm;
 pub mod jest_ownership;
+pub mod jest_projects;
 #[cfg(test)]
 mod jest_threshold_test;
 pub mod live_progress;
 #[cfg(test)]
 mod live_progress_test;
+pub mod log;
+pub mod node_toolchain;
+pub mod notify;
+pub mod orchestrate;
+pub mod owners;
 pub mod parallel_stride;
+pub mod plugin;
+pub mod print_commands;
 pub mod process;
 pub mod pytest;
 pub mod pytest_select;
+pub mod python_env;
 pub(crate) mod pythonpath;
+pub mod readiness;
+pub mod recorded;
+pub mod remote;
+pub mod repro;
+pub mod rerun;
+pub mod resource_report;
 pub mod run;
+pub mod runner;
+pub mod runner_detect;
 mod seed_match;
+pub mod self_update;
 pub mod session;
+pub mod shuffle;
 pub mod streaming;
+pub mod tool_install;
+pub mod typecheck;
+pub mod untested_changes;
 pub mod watch;
 
 pub mod rust_runner;
 
+pub mod api;
 pub mod args;
+pub mod bisect_order;
 pub mod config;
 mod config_ts;
 pub mod coverage;
+pub mod debug;
 pub mod diagnostics_trace;
+pub mod duplicates;
+pub mod editor_server;
 pub mod error;
 pub mod format;
 pub mod help;
@@ -42,6 +77,8 @@ pub(crate) mod rust_coverage;
 pub(crate) mod rust_parse;
 pub mod selection;
 pub mod test_model;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 #[cfg(test)]
 mod args_test;
@@ -56,10 +93,14 @@ mod pytest_artifacts_test;
 #[cfg(test)]
 mod pytest_coverage_test;
 #[cfg(test)]
+mod pytest_django_test;
+#[cfg(test)]
 mod pytest_location_test;
 #[cfg(test)]
 mod pytest_timing_test;
 #[cfg(test)]
+mod pytest_unittest_test;
+#[cfg(test)]
 mod pythonpath_test;
 
 pub fn core_version() -> &'static str {
@@ -0,0 +1,63 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// When set, every frame or plain line the ticker draws is also appended to this file as one
+/// JSON object per line, so a harness can assert on frames without capturing a TTY.
+pub const FRAMES_FILE_ENV: &str = "HEADLAMP_PROGRESS_FRAMES_FILE";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedFrame {
+    /// Time since the progress started, by the progress clock.
+    pub elapsed_ms: u64,
+    pub columns: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FrameSink {
+    path: Option<PathBuf>,
+}
+
+impl FrameSink {
+    pub(super) fn from_env() -> Self {
+        Self {
+            path: std::env::var_os(FRAMES_FILE_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    pub(super) fn record(&self, elapsed: Duration, columns: usize, text: &str) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        let frame = CapturedFrame {
+            elapsed_ms: elapsed.as_millis() as u64,
+            columns,
+            text: text.to_string(),
+        };
+        let Ok(json) = serde_json::to_string(&frame) else {
+            return;
+        };
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            let _ = writeln!(file, "{json}");
+        }
+    }
+}
+
+/// Frames written through [`FRAMES_FILE_ENV`], in the order they were drawn. Unreadable lines
+/// (e.g. one cut short by an interrupt) are skipped.
+pub fn read_captured_frames(path: &Path) -> Vec<CapturedFrame> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CapturedFrame>(line).ok())
+        .collect()
+}
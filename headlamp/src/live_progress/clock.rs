@@ -0,0 +1,41 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Where live progress reads the time from: the system clock, or a fake one that only moves
+/// when a test advances it.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressClock {
+    fake_now: Option<Arc<Mutex<Instant>>>,
+}
+
+impl ProgressClock {
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    pub fn fake() -> Self {
+        Self {
+            fake_now: Some(Arc::new(Mutex::new(Instant::now()))),
+        }
+    }
+
+    /// Moves a fake clock forward; the system clock ignores this.
+    pub fn advance(&self, by: Duration) {
+        if let Some(now) = self.fake_now.as_ref() {
+            if let Ok(mut guard) = now.lock() {
+                *guard += by;
+            }
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.fake_now
+            .as_ref()
+            .and_then(|now| now.lock().ok().map(|guard| *guard))
+            .unwrap_or_else(Instant::now)
+    }
+
+    pub fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
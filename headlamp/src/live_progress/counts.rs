@@ -0,0 +1,44 @@
+use super::TestOutcome;
+
+/// Per-test counters for the frame, fed by the stream adapters as results arrive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub running: usize,
+}
+
+impl TestCounts {
+    pub fn started(&mut self) {
+        self.running += 1;
+    }
+
+    /// A runner that never says a test started (skipped tests, most runners) leaves `running`
+    /// at zero rather than below it.
+    pub fn finished(&mut self, outcome: TestOutcome) {
+        let counter = match outcome {
+            TestOutcome::Pass => &mut self.passed,
+            TestOutcome::Fail => &mut self.failed,
+            TestOutcome::Skip => &mut self.skipped,
+            TestOutcome::Unknown => return,
+        };
+        *counter += 1;
+        self.running = self.running.saturating_sub(1);
+    }
+
+    /// `412 passed, 2 failed, 7 running`; empty until the first test starts or finishes.
+    pub fn summary(&self) -> String {
+        [
+            (self.passed, "passed"),
+            (self.failed, "failed"),
+            (self.skipped, "skipped"),
+            (self.running, "running"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{count} {label}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
@@ -6,6 +6,8 @@ use unicode_width::UnicodeWidthChar;
 
 use crate::format::time::{TimeUnit, format_duration_at_least};
 
+use super::lanes::LaneView;
+
 pub fn render_run_frame(
     current_label: &str,
     done_units: usize,
@@ -23,6 +25,8 @@ pub fn render_run_frame(
         elapsed_seconds,
         idle_seconds,
         recent,
+        counts: "",
+        lanes: &[],
         columns: terminal_columns(),
     })
 }
@@ -35,6 +39,9 @@ pub struct RenderRunFrameArgs<'a> {
     pub elapsed_seconds: u64,
     pub idle_seconds: u64,
     pub recent: &'a str,
+    /// `TestCounts::summary`, shown after the label.
+    pub counts: &'a str,
+    pub lanes: &'a [LaneView],
     pub columns: usize,
 }
 
@@ -44,10 +51,18 @@ pub fn render_run_frame_with_columns(args: RenderRunFrameArgs<'_>) -> String {
     let elapsed =
         format_duration_at_least(Duration::from_secs(args.elapsed_seconds), TimeUnit::Second);
     let mut lines = Vec::new();
-    lines.push(format!(
+    let header = format!(
         "RUN [{spinner} +{elapsed}] ({}/{}) {}",
-        args.done_units, args.total_units, args.current_label
-    ));
+        args.done_units,
+        args.total_units,
+        label_with_counts(&header_label(args.current_label, args.lanes), args.counts)
+    );
+    lines.push(header.trim_end().to_string());
+    if args.lanes.len() > 1 {
+        args.lanes.iter().for_each(|lane| {
+            lines.push(format!("  {spinner} {} {}", lane.label, lane_timing(lane)));
+        });
+    }
     let recent = args.recent.trim();
     if recent.is_empty() {
         let idle =
@@ -59,6 +74,63 @@ pub fn render_run_frame_with_columns(args: RenderRunFrameArgs<'_>) -> String {
     hard_wrap_lines_to_terminal_width(&lines, args.columns)
 }
 
+fn header_label(current_label: &str, lanes: &[LaneView]) -> String {
+    match lanes {
+        [] => current_label.to_string(),
+        [only] => {
+            let label = if current_label.trim().is_empty() {
+                only.label.as_str()
+            } else {
+                current_label
+            };
+            format!("{label} {}", lane_timing(only))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Renders lane timing as `(+45s, eta ~15s)`; units that outlive their historical duration
+/// are flagged as `overdue` so stuck lanes stand out.
+pub(super) fn lane_timing(lane: &LaneView) -> String {
+    let elapsed =
+        format_duration_at_least(Duration::from_secs(lane.elapsed_seconds), TimeUnit::Second);
+    match lane.eta_seconds {
+        None => format!("(+{elapsed})"),
+        Some(remaining) if remaining >= 0 => {
+            let eta =
+                format_duration_at_least(Duration::from_secs(remaining as u64), TimeUnit::Second);
+            format!("(+{elapsed}, eta ~{eta})")
+        }
+        Some(remaining) => {
+            let over = format_duration_at_least(
+                Duration::from_secs(remaining.unsigned_abs()),
+                TimeUnit::Second,
+            );
+            format!("(+{elapsed}, overdue +{over})")
+        }
+    }
+}
+
+/// `cargo nextest · 412 passed, 2 failed, 7 running`.
+pub(super) fn label_with_counts(label: &str, counts: &str) -> String {
+    match (label.trim().is_empty(), counts.is_empty()) {
+        (_, true) => label.to_string(),
+        (true, false) => counts.to_string(),
+        (false, false) => format!("{label} · {counts}"),
+    }
+}
+
+pub(super) fn plain_label(current_label: &str, lanes: &[LaneView]) -> String {
+    if lanes.len() <= 1 {
+        return header_label(current_label, lanes);
+    }
+    lanes
+        .iter()
+        .map(|lane| format!("{} {}", lane.label, lane_timing(lane)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub(super) fn render_plain_line(
     current_label: &str,
     done_units: usize,
@@ -160,8 +232,8 @@ pub(super) fn clear_previous_frame(lines: usize) {
     if lines == 0 {
         return;
     }
-    let _ = std::io::stdout().write_all("\u{1b}[2K\r".as_bytes());
+    let _ = crate::format::report_out::stdout().write_all("\u{1b}[2K\r".as_bytes());
     for _ in 1..lines {
-        let _ = std::io::stdout().write_all("\u{1b}[1A\u{1b}[2K\r".as_bytes());
+        let _ = crate::format::report_out::stdout().write_all("\u{1b}[1A\u{1b}[2K\r".as_bytes());
     }
 }
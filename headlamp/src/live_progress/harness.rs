@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use super::capture::CapturedFrame;
+use super::clock::ProgressClock;
+use super::{LiveProgress, LiveProgressMode};
+
+/// Drives a [`LiveProgress`] on a fake clock with no ticker thread and no terminal, capturing
+/// frames at chosen widths.
+///
+/// ```
+/// use std::time::Duration;
+/// use headlamp::live_progress::ProgressHarness;
+///
+/// let mut harness = ProgressHarness::new(2);
+/// harness.progress().start_unit(0, "math.test.ts");
+/// harness.capture(80);
+/// harness.advance(Duration::from_secs(3));
+/// harness.progress().finish_unit(0);
+/// harness.capture(80);
+/// harness.assert_transitions(&["(0/2) math.test.ts (+<1s)", "(1/2) math.test.ts"]);
+/// ```
+pub struct ProgressHarness {
+    progress: LiveProgress,
+    clock: ProgressClock,
+    frames: Vec<CapturedFrame>,
+}
+
+impl ProgressHarness {
+    pub fn new(total_units: usize) -> Self {
+        let clock = ProgressClock::fake();
+        Self {
+            progress: LiveProgress::with_clock(
+                total_units,
+                LiveProgressMode::Interactive,
+                clock.clone(),
+            ),
+            clock,
+            frames: vec![],
+        }
+    }
+
+    pub fn progress(&self) -> &LiveProgress {
+        &self.progress
+    }
+
+    /// Moves the fake clock forward by `by`, stepping the spinner once per ticker interval.
+    pub fn advance(&mut self, by: Duration) {
+        let ticks = by.as_millis() / 120;
+        (0..ticks).for_each(|_| self.progress.tick_spinner());
+        self.clock.advance(by);
+    }
+
+    pub fn capture(&mut self, columns: usize) -> String {
+        let text = self.progress.render_frame(columns);
+        self.push(columns, text)
+    }
+
+    pub fn capture_plain(&mut self, columns: usize) -> String {
+        let text = self.progress.render_plain_line(columns);
+        self.push(columns, text)
+    }
+
+    pub fn frames(&self) -> &[CapturedFrame] {
+        &self.frames
+    }
+
+    /// Asserts each fragment shows up in a later captured frame than the fragment before it.
+    #[track_caller]
+    pub fn assert_transitions(&self, fragments: &[&str]) {
+        let mut from = 0;
+        for fragment in fragments {
+            match self.frames[from..]
+                .iter()
+                .position(|frame| frame.text.contains(fragment))
+            {
+                Some(offset) => from += offset + 1,
+                None => panic!(
+                    "no frame from #{from} on contains {fragment:?}; captured frames:\n{}",
+                    self.frames
+                        .iter()
+                        .enumerate()
+                        .map(|(index, frame)| format!("#{index}:\n{}", frame.text))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ),
+            }
+        }
+    }
+
+    fn push(&mut self, columns: usize, text: String) -> String {
+        self.frames.push(CapturedFrame {
+            elapsed_ms: self.clock.since(self.progress.started_at).as_millis() as u64,
+            columns,
+            text: text.clone(),
+        });
+        text
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::args::ParsedArgs;
+use crate::format::time::{TimeUnit, format_duration_at_least};
+
+use super::{LiveProgress, LiveProgressMode};
+
+pub const DEFAULT_HEARTBEAT_SECONDS: u32 = 30;
+
+/// Single-line status emitted periodically in non-TTY plain mode so CI logs (and CI
+/// no-output watchdogs) can tell headlamp is still making progress.
+pub fn render_heartbeat_line(
+    label: &str,
+    elapsed_seconds: u64,
+    done_units: usize,
+    total_units: usize,
+) -> String {
+    let elapsed = format_duration_at_least(Duration::from_secs(elapsed_seconds), TimeUnit::Second);
+    format!(
+        "still running: {} ({elapsed} elapsed, {done_units}/{} done)",
+        label.trim(),
+        total_units.max(1)
+    )
+}
+
+impl LiveProgress {
+    pub fn start_with_args(total_units: usize, mode: LiveProgressMode, args: &ParsedArgs) -> Self {
+        let live_progress = Self::start(total_units, mode);
+        live_progress
+            .set_heartbeat_seconds(args.progress_heartbeat.unwrap_or(DEFAULT_HEARTBEAT_SECONDS));
+        live_progress
+    }
+
+    /// `0` disables the heartbeat.
+    pub fn set_heartbeat_seconds(&self, seconds: u32) {
+        self.heartbeat_seconds
+            .store(u64::from(seconds), Ordering::SeqCst);
+    }
+}
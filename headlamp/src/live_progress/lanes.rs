@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use indexmap::IndexMap;
+
+/// One in-flight unit of work (jest project, cargo test binary, pytest batch) as rendered in
+/// the live progress frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneView {
+    pub label: String,
+    pub elapsed_seconds: u64,
+    pub eta_seconds: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Lanes {
+    /// Keyed by unit index: two units may share a label (same display name).
+    in_flight: IndexMap<usize, (String, Instant)>,
+    history_ms: BTreeMap<String, u64>,
+    history_path: Option<PathBuf>,
+}
+
+impl Lanes {
+    pub(super) fn load_history(&mut self, repo_root: &Path) {
+        let path = unit_timings_path(repo_root);
+        self.history_ms = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<BTreeMap<String, u64>>(&raw).ok())
+            .unwrap_or_default();
+        self.history_path = Some(path);
+    }
+
+    pub(super) fn start(&mut self, index: usize, label: String, now: Instant) {
+        self.in_flight.insert(index, (label, now));
+    }
+
+    pub(super) fn finish(&mut self, index: usize, now: Instant) {
+        let Some((label, started_at)) = self.in_flight.shift_remove(&index) else {
+            return;
+        };
+        let observed_ms = now.saturating_duration_since(started_at).as_millis() as u64;
+        let smoothed_ms = self
+            .history_ms
+            .get(&label)
+            .map(|previous| (previous + observed_ms) / 2)
+            .unwrap_or(observed_ms);
+        self.history_ms.insert(label, smoothed_ms);
+    }
+
+    pub(super) fn views(&self, now: Instant) -> Vec<LaneView> {
+        self.in_flight
+            .values()
+            .map(|(label, started_at)| {
+                let elapsed = now.saturating_duration_since(*started_at);
+                LaneView {
+                    label: label.clone(),
+                    elapsed_seconds: elapsed.as_secs(),
+                    eta_seconds: self.history_ms.get(label).map(|expected_ms| {
+                        (*expected_ms as i64 - elapsed.as_millis() as i64).div_euclid(1000)
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    pub(super) fn save_history(&self) {
+        let Some(path) = self.history_path.as_ref() else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(&self.history_ms) else {
+            return;
+        };
+        if path
+            .parent()
+            .is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+        {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+fn unit_timings_path(repo_root: &Path) -> PathBuf {
+    crate::fast_related::default_cache_root()
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("unit-timings.json")
+}
+
+impl super::LiveProgress {
+    /// Seeds per-unit ETAs from timings recorded by previous runs in this repo; the updated
+    /// timings are written back on `finish`.
+    pub fn load_unit_timings(&self, repo_root: &Path) {
+        if let Ok(mut guard) = self.lanes.lock() {
+            guard.load_history(repo_root);
+        }
+    }
+
+    /// Opens the lane of unit `index` (its position among the run's units).
+    pub fn start_unit(&self, index: usize, label: &str) {
+        if let Ok(mut guard) = self.lanes.lock() {
+            guard.start(index, label.to_string(), self.clock.now());
+        }
+        self.set_current_label(label.to_string());
+    }
+
+    pub fn finish_unit(&self, index: usize) {
+        if let Ok(mut guard) = self.lanes.lock() {
+            guard.finish(index, self.clock.now());
+        }
+        self.increment_done(1);
+    }
+}
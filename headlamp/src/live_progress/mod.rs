@@ -1,13 +1,29 @@
+mod capture;
 mod classify;
+mod clock;
+mod counts;
 mod frame;
+mod harness;
+mod heartbeat;
+mod lanes;
+mod teardown;
 mod test_line;
 mod ticker;
+mod vt;
 
+pub use capture::{CapturedFrame, FRAMES_FILE_ENV, read_captured_frames};
 pub use classify::classify_runner_line_for_progress;
+pub use clock::ProgressClock;
+pub use counts::TestCounts;
 pub use frame::{
     RenderRunFrameArgs, frame_physical_line_count, render_run_frame, render_run_frame_with_columns,
 };
+pub use harness::ProgressHarness;
+pub use heartbeat::{DEFAULT_HEARTBEAT_SECONDS, render_heartbeat_line};
+pub use lanes::LaneView;
+pub use teardown::InterruptHandle;
 pub use test_line::{TestOutcome, outcome_from_status, render_finished_test_line};
+pub use vt::enable_vt_processing;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LiveProgressMode {
@@ -21,14 +37,19 @@ pub struct LiveProgress {
     pub(super) stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
     pub(super) done_units: std::sync::Arc<std::sync::atomic::AtomicUsize>,
     pub(super) current_label: std::sync::Arc<std::sync::Mutex<String>>,
+    pub(super) test_counts: std::sync::Arc<std::sync::Mutex<counts::TestCounts>>,
+    pub(super) lanes: std::sync::Arc<std::sync::Mutex<lanes::Lanes>>,
     pub(super) last_event_at: std::sync::Arc<std::sync::Mutex<std::time::Instant>>,
     pub(super) last_runner_stdout_hint: std::sync::Arc<std::sync::Mutex<Option<String>>>,
     pub(super) last_runner_stderr_hint: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+    pub(super) heartbeat_seconds: std::sync::Arc<std::sync::atomic::AtomicU64>,
     pub(super) spinner_index: std::sync::Arc<std::sync::atomic::AtomicUsize>,
     pub(super) last_frame_lines: std::sync::Arc<std::sync::atomic::AtomicUsize>,
     pub(super) write_lock: std::sync::Arc<std::sync::Mutex<()>>,
     pub(super) started_at: std::time::Instant,
     pub(super) total_units: usize,
+    pub(super) clock: clock::ProgressClock,
+    pub(super) frame_sink: capture::FrameSink,
     pub(super) ticker: Option<std::thread::JoinHandle<()>>,
 }
 
@@ -50,7 +71,12 @@ pub fn live_progress_mode_with_env_ci(
     LiveProgressMode::Plain
 }
 
+/// Like [`live_progress_mode_with_env_ci`], but falls back to `Plain` on a console that cannot
+/// draw the interactive frame (a Windows console without VT processing).
 pub fn live_progress_mode(stdout_is_tty: bool, ci: bool, quiet: bool) -> LiveProgressMode {
     let env_ci = std::env::var("CI").ok().is_some();
-    live_progress_mode_with_env_ci(stdout_is_tty, ci, env_ci, quiet)
+    match live_progress_mode_with_env_ci(stdout_is_tty, ci, env_ci, quiet) {
+        LiveProgressMode::Interactive if !enable_vt_processing() => LiveProgressMode::Plain,
+        mode => mode,
+    }
 }
@@ -0,0 +1,54 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::LiveProgress;
+
+/// The slice of live progress state the interrupt handler needs: unit counts for the partial
+/// summary, and enough of the frame bookkeeping to wipe the frame it left on screen.
+#[derive(Debug, Clone)]
+pub struct InterruptHandle {
+    stop: Arc<AtomicBool>,
+    done_units: Arc<AtomicUsize>,
+    total_units: usize,
+    last_frame_lines: Arc<AtomicUsize>,
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl InterruptHandle {
+    pub fn units(&self) -> (usize, usize) {
+        (self.done_units.load(Ordering::SeqCst), self.total_units)
+    }
+
+    /// Stops the ticker and clears the last frame. The returned guard holds the progress write
+    /// lock so nothing else draws over the terminal afterwards.
+    pub fn restore_terminal(&self) -> Option<MutexGuard<'_, ()>> {
+        self.stop.store(true, Ordering::SeqCst);
+        let guard = self.write_lock.lock().ok()?;
+        super::frame::clear_previous_frame(self.last_frame_lines.swap(0, Ordering::SeqCst));
+        let _ = crate::format::report_out::stdout().flush();
+        Some(guard)
+    }
+}
+
+impl LiveProgress {
+    pub(super) fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            stop: Arc::clone(&self.stop),
+            done_units: Arc::clone(&self.done_units),
+            total_units: self.total_units,
+            last_frame_lines: Arc::clone(&self.last_frame_lines),
+            write_lock: Arc::clone(&self.write_lock),
+        }
+    }
+
+    pub(super) fn register_for_interrupt(&self) {
+        crate::interrupt::set_active_progress(Some(self.interrupt_handle()));
+    }
+
+    pub(super) fn unregister_for_interrupt(&self) {
+        crate::interrupt::clear_active_progress_if(|handle| {
+            Arc::ptr_eq(&handle.done_units, &self.done_units)
+        });
+    }
+}
@@ -2,25 +2,32 @@ use std::io::IsTerminal;
 use std::io::Write;
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
 use super::LiveProgress;
 use super::LiveProgressMode;
+use super::capture::FrameSink;
+use super::clock::ProgressClock;
 
 #[derive(Debug, Clone)]
 struct TickerShared {
     stop: Arc<AtomicBool>,
     done_units: Arc<AtomicUsize>,
     current_label: Arc<Mutex<String>>,
+    test_counts: Arc<Mutex<super::counts::TestCounts>>,
+    lanes: Arc<Mutex<super::lanes::Lanes>>,
     last_event_at: Arc<Mutex<Instant>>,
     last_runner_stdout_hint: Arc<Mutex<Option<String>>>,
     last_runner_stderr_hint: Arc<Mutex<Option<String>>>,
+    heartbeat_seconds: Arc<AtomicU64>,
     spinner_index: Arc<AtomicUsize>,
     last_frame_lines: Arc<AtomicUsize>,
     write_lock: Arc<Mutex<()>>,
     started_at: Instant,
     total_units: usize,
+    clock: ProgressClock,
+    frame_sink: FrameSink,
 }
 
 #[derive(Debug, Clone)]
@@ -31,32 +38,9 @@ struct PlainTickerShared {
 
 impl LiveProgress {
     pub fn start(total_units: usize, mode: LiveProgressMode) -> Self {
-        let started_at = Instant::now();
-        let stop = Arc::new(AtomicBool::new(false));
-        let done_units = Arc::new(AtomicUsize::new(0));
-        let current_label = Arc::new(Mutex::new(String::new()));
-        let last_event_at = Arc::new(Mutex::new(Instant::now()));
-        let last_runner_stdout_hint = Arc::new(Mutex::new(None));
-        let last_runner_stderr_hint = Arc::new(Mutex::new(None));
-        let spinner_index = Arc::new(AtomicUsize::new(0));
-        let last_frame_lines = Arc::new(AtomicUsize::new(0));
-        let write_lock = Arc::new(Mutex::new(()));
-
-        let shared = TickerShared {
-            stop: Arc::clone(&stop),
-            done_units: Arc::clone(&done_units),
-            current_label: Arc::clone(&current_label),
-            last_event_at: Arc::clone(&last_event_at),
-            last_runner_stdout_hint: Arc::clone(&last_runner_stdout_hint),
-            last_runner_stderr_hint: Arc::clone(&last_runner_stderr_hint),
-            spinner_index: Arc::clone(&spinner_index),
-            last_frame_lines: Arc::clone(&last_frame_lines),
-            write_lock: Arc::clone(&write_lock),
-            started_at,
-            total_units,
-        };
-
-        let ticker = match mode {
+        let mut progress = Self::with_clock(total_units, mode, ProgressClock::system());
+        let shared = progress.ticker_shared();
+        progress.ticker = match mode {
             LiveProgressMode::Off => None,
             LiveProgressMode::Interactive => Some(spawn_interactive_ticker(shared)),
             LiveProgressMode::Plain => Some(spawn_plain_ticker(PlainTickerShared {
@@ -64,24 +48,77 @@ impl LiveProgress {
                 stdout_is_tty: std::io::stdout().is_terminal(),
             })),
         };
+        progress.register_for_interrupt();
+        progress
+    }
 
+    /// Progress state without a ticker thread; frames are only rendered when asked for.
+    pub(super) fn with_clock(
+        total_units: usize,
+        mode: LiveProgressMode,
+        clock: ProgressClock,
+    ) -> Self {
+        let now = clock.now();
         Self {
             mode,
-            stop,
-            done_units,
-            current_label,
-            last_event_at,
-            last_runner_stdout_hint,
-            last_runner_stderr_hint,
-            spinner_index,
-            last_frame_lines,
-            write_lock,
-            started_at,
+            stop: Arc::new(AtomicBool::new(false)),
+            done_units: Arc::new(AtomicUsize::new(0)),
+            current_label: Arc::new(Mutex::new(String::new())),
+            test_counts: Arc::new(Mutex::new(super::counts::TestCounts::default())),
+            lanes: Arc::new(Mutex::new(super::lanes::Lanes::default())),
+            last_event_at: Arc::new(Mutex::new(now)),
+            last_runner_stdout_hint: Arc::new(Mutex::new(None)),
+            last_runner_stderr_hint: Arc::new(Mutex::new(None)),
+            heartbeat_seconds: Arc::new(AtomicU64::new(u64::from(
+                super::DEFAULT_HEARTBEAT_SECONDS,
+            ))),
+            spinner_index: Arc::new(AtomicUsize::new(0)),
+            last_frame_lines: Arc::new(AtomicUsize::new(0)),
+            write_lock: Arc::new(Mutex::new(())),
+            started_at: now,
             total_units,
-            ticker,
+            clock,
+            frame_sink: FrameSink::from_env(),
+            ticker: None,
         }
     }
 
+    fn ticker_shared(&self) -> TickerShared {
+        TickerShared {
+            stop: Arc::clone(&self.stop),
+            done_units: Arc::clone(&self.done_units),
+            current_label: Arc::clone(&self.current_label),
+            test_counts: Arc::clone(&self.test_counts),
+            lanes: Arc::clone(&self.lanes),
+            last_event_at: Arc::clone(&self.last_event_at),
+            last_runner_stdout_hint: Arc::clone(&self.last_runner_stdout_hint),
+            last_runner_stderr_hint: Arc::clone(&self.last_runner_stderr_hint),
+            heartbeat_seconds: Arc::clone(&self.heartbeat_seconds),
+            spinner_index: Arc::clone(&self.spinner_index),
+            last_frame_lines: Arc::clone(&self.last_frame_lines),
+            write_lock: Arc::clone(&self.write_lock),
+            started_at: self.started_at,
+            total_units: self.total_units,
+            clock: self.clock.clone(),
+            frame_sink: self.frame_sink.clone(),
+        }
+    }
+
+    /// The interactive frame as the ticker would draw it now, hard-wrapped to `columns`.
+    pub fn render_frame(&self, columns: usize) -> String {
+        interactive_frame(&self.ticker_shared(), columns)
+    }
+
+    /// The plain-mode status line as the ticker would draw it now, hard-wrapped to `columns`.
+    pub fn render_plain_line(&self, columns: usize) -> String {
+        plain_line(&self.ticker_shared(), columns)
+    }
+
+    /// Advances the spinner one step, as each interactive tick does.
+    pub fn tick_spinner(&self) {
+        self.spinner_index.fetch_add(1, Ordering::SeqCst);
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.mode != LiveProgressMode::Off
     }
@@ -92,11 +129,29 @@ impl LiveProgress {
         }
         if self.mode != LiveProgressMode::Off {
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
     }
 
+    /// A test began running, for the frame's `running` counter.
+    pub fn record_test_started(&self) {
+        if let Ok(mut guard) = self.test_counts.lock() {
+            guard.started();
+        }
+    }
+
+    /// A test finished, for the frame's pass/fail counters.
+    pub fn record_test_outcome(&self, outcome: super::TestOutcome) {
+        if let Ok(mut guard) = self.test_counts.lock() {
+            guard.finished(outcome);
+        }
+    }
+
+    pub fn test_counts(&self) -> super::counts::TestCounts {
+        locked_clone(&self.test_counts).unwrap_or_default()
+    }
+
     pub fn record_runner_stdout_line(&self, line: &str) {
         let Some(hint) = super::classify::classify_runner_line_for_progress(line) else {
             return;
@@ -106,7 +161,7 @@ impl LiveProgress {
         }
         if self.mode != LiveProgressMode::Off {
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
     }
@@ -120,7 +175,7 @@ impl LiveProgress {
         }
         if self.mode != LiveProgressMode::Off {
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
     }
@@ -129,7 +184,7 @@ impl LiveProgress {
         if self.mode != LiveProgressMode::Off {
             self.done_units.fetch_add(delta, Ordering::SeqCst);
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
     }
@@ -137,7 +192,7 @@ impl LiveProgress {
     pub fn println_stdout(&self, line: &str) {
         if self.mode != LiveProgressMode::Off {
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
         if let Ok(_guard) = self.write_lock.lock() {
@@ -147,9 +202,9 @@ impl LiveProgress {
                 super::frame::clear_previous_frame(prev_lines);
                 self.last_frame_lines.store(0, Ordering::SeqCst);
             }
-            let _ = std::io::stdout().write_all(line.as_bytes());
-            let _ = std::io::stdout().write_all("\n".as_bytes());
-            let _ = std::io::stdout().flush();
+            let _ = crate::format::report_out::stdout().write_all(line.as_bytes());
+            let _ = crate::format::report_out::stdout().write_all("\n".as_bytes());
+            let _ = crate::format::report_out::stdout().flush();
             if self.mode == LiveProgressMode::Interactive && is_tty {
                 self.redraw_interactive_frame();
             }
@@ -159,7 +214,7 @@ impl LiveProgress {
     pub fn eprintln_stderr(&self, line: &str) {
         if self.mode != LiveProgressMode::Off {
             if let Ok(mut guard) = self.last_event_at.lock() {
-                *guard = Instant::now();
+                *guard = self.clock.now();
             }
         }
         if let Ok(_guard) = self.write_lock.lock() {
@@ -168,7 +223,7 @@ impl LiveProgress {
                 let prev_lines = self.last_frame_lines.load(Ordering::SeqCst);
                 super::frame::clear_previous_frame(prev_lines);
                 self.last_frame_lines.store(0, Ordering::SeqCst);
-                let _ = std::io::stdout().flush();
+                let _ = crate::format::report_out::stdout().flush();
             }
             let _ = std::io::stderr().write_all(line.as_bytes());
             let _ = std::io::stderr().write_all("\n".as_bytes());
@@ -180,6 +235,10 @@ impl LiveProgress {
     }
 
     pub fn finish(mut self) {
+        self.unregister_for_interrupt();
+        if let Ok(guard) = self.lanes.lock() {
+            guard.save_history();
+        }
         if self.mode == LiveProgressMode::Off {
             return;
         }
@@ -192,7 +251,7 @@ impl LiveProgress {
                 let prev_lines = self.last_frame_lines.load(Ordering::SeqCst);
                 super::frame::clear_previous_frame(prev_lines);
                 self.last_frame_lines.store(0, Ordering::SeqCst);
-                let _ = std::io::stdout().flush();
+                let _ = crate::format::report_out::stdout().flush();
             }
         }
     }
@@ -200,43 +259,12 @@ impl LiveProgress {
 
 impl LiveProgress {
     fn redraw_interactive_frame(&self) {
-        let done = self.done_units.load(Ordering::SeqCst);
-        let label = self
-            .current_label
-            .lock()
-            .ok()
-            .map(|g| g.clone())
-            .unwrap_or_default();
-        let elapsed_seconds = self.started_at.elapsed().as_secs();
-        let idle_seconds = self
-            .last_event_at
-            .lock()
-            .ok()
-            .map(|t| t.elapsed().as_secs())
-            .unwrap_or(elapsed_seconds);
         let columns = super::frame::terminal_columns();
-        let recent = super::classify::recent_summary(
-            self.last_runner_stdout_hint
-                .lock()
-                .ok()
-                .and_then(|g| g.clone()),
-            self.last_runner_stderr_hint
-                .lock()
-                .ok()
-                .and_then(|g| g.clone()),
-        );
-        let frame = super::frame::render_run_frame_with_columns(super::frame::RenderRunFrameArgs {
-            current_label: &label,
-            done_units: done,
-            total_units: self.total_units.max(1),
-            spinner_index: self.spinner_index.load(Ordering::SeqCst),
-            elapsed_seconds,
-            idle_seconds,
-            recent: &recent,
-            columns,
-        });
-        let _ = std::io::stdout().write_all(frame.as_bytes());
-        let _ = std::io::stdout().flush();
+        let frame = self.render_frame(columns);
+        self.frame_sink
+            .record(self.clock.since(self.started_at), columns, &frame);
+        let _ = crate::format::report_out::stdout().write_all(frame.as_bytes());
+        let _ = crate::format::report_out::stdout().flush();
         self.last_frame_lines.store(
             super::frame::frame_physical_line_count(&frame, columns),
             Ordering::SeqCst,
@@ -255,8 +283,13 @@ fn spawn_interactive_ticker(shared: TickerShared) -> std::thread::JoinHandle<()>
 
 fn spawn_plain_ticker(shared: PlainTickerShared) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
+        let mut last_heartbeat_at = shared.shared.clock.now();
         while !shared.shared.stop.load(Ordering::SeqCst) {
-            plain_tick(&shared);
+            if shared.stdout_is_tty {
+                plain_tick(&shared);
+            } else {
+                heartbeat_tick(&shared.shared, &mut last_heartbeat_at);
+            }
             std::thread::sleep(Duration::from_secs(2));
         }
     })
@@ -264,65 +297,108 @@ fn spawn_plain_ticker(shared: PlainTickerShared) -> std::thread::JoinHandle<()>
 
 fn interactive_tick(shared: &TickerShared) {
     shared.spinner_index.fetch_add(1, Ordering::SeqCst);
-    let done = shared.done_units.load(Ordering::SeqCst);
+    let columns = super::frame::terminal_columns();
+    let frame = interactive_frame(shared, columns);
+    write_frame(shared, &frame, columns);
+}
+
+fn interactive_frame(shared: &TickerShared, columns: usize) -> String {
     let label = locked_clone(&shared.current_label).unwrap_or_default();
     let (elapsed_seconds, idle_seconds) = elapsed_and_idle_seconds(shared);
-    let columns = super::frame::terminal_columns();
     let recent = super::classify::recent_summary(
         locked_clone(&shared.last_runner_stdout_hint).flatten(),
         locked_clone(&shared.last_runner_stderr_hint).flatten(),
     );
-    let frame = super::frame::render_run_frame_with_columns(super::frame::RenderRunFrameArgs {
+    let lanes = lane_views(shared);
+    let counts = locked_clone(&shared.test_counts)
+        .unwrap_or_default()
+        .summary();
+    super::frame::render_run_frame_with_columns(super::frame::RenderRunFrameArgs {
         current_label: &label,
-        done_units: done,
+        counts: &counts,
+        done_units: shared.done_units.load(Ordering::SeqCst),
         total_units: shared.total_units.max(1),
         spinner_index: shared.spinner_index.load(Ordering::SeqCst),
         elapsed_seconds,
         idle_seconds,
         recent: &recent,
+        lanes: &lanes,
         columns,
-    });
-    write_frame(shared, &frame, columns);
+    })
 }
 
 fn plain_tick(shared: &PlainTickerShared) {
-    let done = shared.shared.done_units.load(Ordering::SeqCst);
     let label = locked_clone(&shared.shared.current_label).unwrap_or_default();
     if label.trim().is_empty() {
         return;
     }
-    let (elapsed_seconds, idle_seconds) = elapsed_and_idle_seconds(&shared.shared);
-    // In TTY environments, avoid redrawing too aggressively (this stabilizes snapshots and
-    // keeps the output readable). In non-TTY environments, keep emitting progress even if the
-    // runner is chatty (otherwise we can end up printing nothing).
-    if shared.stdout_is_tty && idle_seconds < 5 {
+    let (_, idle_seconds) = elapsed_and_idle_seconds(&shared.shared);
+    // Avoid redrawing too aggressively (this stabilizes snapshots and keeps the output
+    // readable). Non-TTY output is covered by the periodic heartbeat instead.
+    if idle_seconds < 5 {
         std::thread::sleep(Duration::from_secs(2));
         return;
     }
     let columns = super::frame::terminal_columns();
+    let line = plain_line(&shared.shared, columns);
+    write_plain_line(shared, &line, columns);
+}
+
+fn plain_line(shared: &TickerShared, columns: usize) -> String {
+    let label = locked_clone(&shared.current_label).unwrap_or_default();
+    let (elapsed_seconds, idle_seconds) = elapsed_and_idle_seconds(shared);
     let recent = super::classify::recent_summary(
-        locked_clone(&shared.shared.last_runner_stdout_hint).flatten(),
-        locked_clone(&shared.shared.last_runner_stderr_hint).flatten(),
+        locked_clone(&shared.last_runner_stdout_hint).flatten(),
+        locked_clone(&shared.last_runner_stderr_hint).flatten(),
     );
-    let line = super::frame::render_plain_line(
-        &label,
-        done,
-        shared.shared.total_units,
+    super::frame::render_plain_line(
+        &with_counts(
+            super::frame::plain_label(&label, &lane_views(shared)),
+            shared,
+        ),
+        shared.done_units.load(Ordering::SeqCst),
+        shared.total_units,
         elapsed_seconds,
         idle_seconds,
         &recent,
         columns,
+    )
+}
+
+fn heartbeat_tick(shared: &TickerShared, last_heartbeat_at: &mut Instant) {
+    let interval_seconds = shared.heartbeat_seconds.load(Ordering::SeqCst);
+    if interval_seconds == 0 || shared.clock.since(*last_heartbeat_at).as_secs() < interval_seconds
+    {
+        return;
+    }
+    let label = locked_clone(&shared.current_label).unwrap_or_default();
+    if label.trim().is_empty() {
+        return;
+    }
+    *last_heartbeat_at = shared.clock.now();
+    let line = super::render_heartbeat_line(
+        &with_counts(
+            super::frame::plain_label(&label, &lane_views(shared)),
+            shared,
+        ),
+        shared.clock.since(shared.started_at).as_secs(),
+        shared.done_units.load(Ordering::SeqCst),
+        shared.total_units,
     );
-    write_plain_line(shared, &line, columns);
+    if let Ok(_guard) = shared.write_lock.lock() {
+        let _ = crate::format::report_out::stdout().write_all(line.as_bytes());
+        let _ = crate::format::report_out::stdout().write_all("\n".as_bytes());
+        let _ = crate::format::report_out::stdout().flush();
+    }
 }
 
 fn elapsed_and_idle_seconds(shared: &TickerShared) -> (u64, u64) {
-    let elapsed_seconds = shared.started_at.elapsed().as_secs();
+    let elapsed_seconds = shared.clock.since(shared.started_at).as_secs();
     let idle_seconds = shared
         .last_event_at
         .lock()
         .ok()
-        .map(|t| t.elapsed().as_secs())
+        .map(|t| shared.clock.since(*t).as_secs())
         .unwrap_or(elapsed_seconds);
     (elapsed_seconds, idle_seconds)
 }
@@ -331,8 +407,11 @@ fn write_frame(shared: &TickerShared, frame: &str, columns: usize) {
     if let Ok(_guard) = shared.write_lock.lock() {
         let prev_lines = shared.last_frame_lines.load(Ordering::SeqCst);
         super::frame::clear_previous_frame(prev_lines);
-        let _ = std::io::stdout().write_all(frame.as_bytes());
-        let _ = std::io::stdout().flush();
+        shared
+            .frame_sink
+            .record(shared.clock.since(shared.started_at), columns, frame);
+        let _ = crate::format::report_out::stdout().write_all(frame.as_bytes());
+        let _ = crate::format::report_out::stdout().flush();
         shared.last_frame_lines.store(
             super::frame::frame_physical_line_count(frame, columns),
             Ordering::SeqCst,
@@ -342,22 +421,38 @@ fn write_frame(shared: &TickerShared, frame: &str, columns: usize) {
 
 fn write_plain_line(shared: &PlainTickerShared, line: &str, columns: usize) {
     if let Ok(_guard) = shared.shared.write_lock.lock() {
-        if shared.stdout_is_tty {
-            let prev_lines = shared.shared.last_frame_lines.load(Ordering::SeqCst);
-            super::frame::clear_previous_frame(prev_lines);
-            let _ = std::io::stdout().write_all(line.as_bytes());
-            shared.shared.last_frame_lines.store(
-                super::frame::frame_physical_line_count(line, columns),
-                Ordering::SeqCst,
-            );
-        } else {
-            let _ = std::io::stdout().write_all(line.as_bytes());
-            let _ = std::io::stdout().write_all("\n".as_bytes());
-        }
-        let _ = std::io::stdout().flush();
+        let prev_lines = shared.shared.last_frame_lines.load(Ordering::SeqCst);
+        super::frame::clear_previous_frame(prev_lines);
+        shared.shared.frame_sink.record(
+            shared.shared.clock.since(shared.shared.started_at),
+            columns,
+            line,
+        );
+        let _ = crate::format::report_out::stdout().write_all(line.as_bytes());
+        shared.shared.last_frame_lines.store(
+            super::frame::frame_physical_line_count(line, columns),
+            Ordering::SeqCst,
+        );
+        let _ = crate::format::report_out::stdout().flush();
     }
 }
 
+fn lane_views(shared: &TickerShared) -> Vec<super::lanes::LaneView> {
+    let now = shared.clock.now();
+    shared
+        .lanes
+        .lock()
+        .map(|guard| guard.views(now))
+        .unwrap_or_default()
+}
+
+fn with_counts(label: String, shared: &TickerShared) -> String {
+    let counts = locked_clone(&shared.test_counts)
+        .unwrap_or_default()
+        .summary();
+    super::frame::label_with_counts(&label, &counts)
+}
+
 fn locked_clone<T: Clone>(value: &Mutex<T>) -> Option<T> {
     value.lock().ok().map(|g| g.clone())
 }
@@ -0,0 +1,36 @@
+/// Whether stdout understands the cursor movement and colors of the interactive frame.
+///
+/// Unix terminals always do. Windows consoles only do once virtual terminal processing is on,
+/// so the first call switches it on for stdout and stderr and remembers whether stdout took it.
+#[cfg(windows)]
+pub fn enable_vt_processing() -> bool {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::System::Console::{
+        ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_ERROR_HANDLE, STD_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    fn enable_for(std_handle: STD_HANDLE) -> bool {
+        use windows_sys::Win32::System::Console::{GetConsoleMode, GetStdHandle, SetConsoleMode};
+        // SAFETY: the handle comes straight from GetStdHandle and `mode` outlives both calls.
+        unsafe {
+            let handle = GetStdHandle(std_handle);
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                return false;
+            }
+            mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+        }
+    }
+
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        let _ = enable_for(STD_ERROR_HANDLE);
+        enable_for(STD_OUTPUT_HANDLE)
+    })
+}
+
+#[cfg(not(windows))]
+pub fn enable_vt_processing() -> bool {
+    true
+}
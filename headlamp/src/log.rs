@@ -0,0 +1,69 @@
+//! Headlamp's own diagnostics, as `tracing` events on stderr. Events name an area as their
+//! target (`selection`, `jest`, `pytest`, `cargo`, `coverage`, `watch`, `run`, `profile`) so
+//! `HEADLAMP_LOG=selection=debug,jest=trace` can pick areas without knowing module paths.
+
+use std::io::IsTerminal;
+
+use tracing_subscriber::EnvFilter;
+
+use crate::args::ParsedArgs;
+use crate::run::RunError;
+
+pub const LOG_ENV: &str = "HEADLAMP_LOG";
+
+const DEFAULT_LEVEL: &str = "warn";
+
+const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// The `EnvFilter` directives for a run: `HEADLAMP_LOG` when set, else `--log-level`, else
+/// `debug` under `--verbose`, else `warn`. `HEADLAMP_PROFILE` keeps its timings visible.
+pub fn filter_directives(args: &ParsedArgs, env_filter: Option<&str>, profile: bool) -> String {
+    let base = env_filter
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            args.log_level
+                .as_deref()
+                .map(|l| l.trim().to_ascii_lowercase())
+        })
+        .unwrap_or_else(|| if args.verbose { "debug" } else { DEFAULT_LEVEL }.to_string());
+    if profile {
+        format!("{base},profile=info")
+    } else {
+        base
+    }
+}
+
+/// Installs the stderr subscriber; only the first call in a process takes effect. An unknown
+/// `--log-level` is an error, while a bad `HEADLAMP_LOG` falls back to the default with a warning.
+pub fn init(args: &ParsedArgs) -> Result<(), RunError> {
+    if let Some(level) = args.log_level.as_deref()
+        && !LEVELS.contains(&level.trim().to_ascii_lowercase().as_str())
+    {
+        return Err(RunError::CommandFailed {
+            message: format!(
+                "invalid --log-level: {level} (expected one of {})",
+                LEVELS.join(", ")
+            ),
+        });
+    }
+    let env_filter = std::env::var(LOG_ENV).ok();
+    let directives = filter_directives(args, env_filter.as_deref(), crate::profile::enabled());
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|err| {
+        eprintln!("headlamp: ignoring invalid log filter {directives:?}: {err}");
+        EnvFilter::new(DEFAULT_LEVEL)
+    });
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    let _ = if args.log_json {
+        builder.json().try_init()
+    } else {
+        builder
+            .with_ansi(std::io::stderr().is_terminal())
+            .without_time()
+            .try_init()
+    };
+    Ok(())
+}
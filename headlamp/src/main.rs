@@ -1,13 +1,6 @@
 use std::io::IsTerminal;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Runner {
-    Jest,
-    Pytest,
-    Headlamp,
-    CargoTest,
-    CargoNextest,
-}
+use headlamp::runner::{RunPlan, Runner};
 
 fn base_flag(t: &str) -> &str {
     t.split_once('=').map(|(k, _)| k).unwrap_or(t)
@@ -53,194 +46,211 @@ fn main() {
         }
     }
     let argv0 = std::env::args().skip(1).collect::<Vec<_>>();
-    match early_exit_before_double_dash(&argv0) {
-        Some(EarlyExit::Help) => {
-            print_help();
-            return;
-        }
-        Some(EarlyExit::Version) => {
-            print_version();
-            return;
-        }
-        None => {}
-    };
-    let (runner, argv) = extract_runner(&argv0);
+    if let Some(code) = headlamp::help::run_help_flags(&argv0)
+        .or_else(|| headlamp::help::run_help_command(&argv0))
+        .or_else(|| headlamp::self_update::run_self_update_command(&argv0))
+        .or_else(|| headlamp::coverage::command::run_coverage_command(&argv0))
+        .or_else(|| headlamp::git_hook::run_hook_command(&argv0))
+        .or_else(|| headlamp::config::validate::run_config_command(&argv0))
+        .or_else(|| headlamp::editor_server::run_editor_server_command(&argv0))
+        .or_else(|| headlamp::editor_server::run_tests_in_file_command(&argv0))
+        .or_else(|| headlamp::owners::run_owners_command(&argv0))
+        .or_else(|| headlamp::bisect_order::run_bisect_order_command(&argv0))
+        .or_else(|| headlamp::orchestrate::run_orchestrate_command(&argv0))
+    {
+        std::process::exit(code);
+    }
+    headlamp::interrupt::install_handler();
+    let (explicit_runner, argv) = extract_runner(&argv0);
     let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-    let config_root = headlamp::config::find_repo_root(&cwd);
-    let parsed = build_parsed_args(&config_root, &argv);
-    let run_root = resolve_run_root(runner, &cwd, &parsed);
-    apply_ci_env(&parsed);
+    let config_root = headlamp::config::resolve_repo_root(&cwd, &argv);
+    let (run_roots, parsed) = resolve_runs(explicit_runner, argv, &cwd, &config_root);
+    apply_process_settings(&parsed, &config_root);
+    headlamp::interrupt::arm_max_duration(&parsed);
+    let run_env = build_run_environment(&config_root, &parsed);
     validate_watch_ci(&parsed);
-    maybe_print_verbose_startup(runner, &run_root, &parsed);
-    let user_cache_dir_was_set = std::env::var_os("HEADLAMP_CACHE_DIR").is_some();
-    let mut run_once_closure = || run_once(runner, &run_root, &parsed, user_cache_dir_was_set);
+    validate_notify(&parsed);
+    run_roots
+        .iter()
+        .for_each(|(runner, run_root, run_args)| log_startup(*runner, run_root, run_args));
+    if let Some(format) = parsed.print_commands {
+        std::process::exit(headlamp::print_commands::plan_and_print(format, || {
+            run_roots.iter().for_each(|(runner, run_root, run_args)| {
+                headlamp::runner::run_once(*runner, run_root, run_args, &run_env);
+            })
+        }));
+    }
+    let mut run_once_closure = || run_all(&config_root, &parsed, &run_env, &run_roots);
+    let watch_root = match run_roots.as_slice() {
+        [(_, only_root, _)] => only_root.clone(),
+        _ => config_root.clone(),
+    };
     let code = if parsed.watch {
-        {
-            headlamp::watch::run_polling_watch_loop(
-                &run_root,
-                std::time::Duration::from_millis(800),
-                parsed.verbose,
-                &mut run_once_closure,
-            )
-        }
+        headlamp::watch::run_polling_watch_loop(
+            &watch_root,
+            std::time::Duration::from_millis(800),
+            &mut run_once_closure,
+        )
     } else {
         run_once_closure()
     };
+    headlamp::self_update::print_update_notice_if_requested(&parsed);
     std::process::exit(code);
 }
 
-fn resolve_run_root(
-    runner: Runner,
-    cwd: &std::path::Path,
+/// One headlamp run, then `--notify`, the summary line and the other after-run reports.
+fn run_all(
+    config_root: &std::path::Path,
     parsed: &headlamp::args::ParsedArgs,
-) -> std::path::PathBuf {
-    let workspace_override = parsed
-        .workspace_root
-        .as_ref()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(std::path::PathBuf::from)
-        .map(|p| if p.is_absolute() { p } else { cwd.join(p) });
-
-    if let Some(p) = workspace_override {
-        return p;
-    }
-
-    match runner {
-        Runner::Pytest => headlamp::project::markers::find_pyproject_toml_root(cwd)
-            .unwrap_or_else(|| cwd.to_path_buf()),
-        _ => headlamp::config::find_repo_root(cwd),
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum EarlyExit {
-    Help,
-    Version,
+    run_env: &headlamp::process::RunEnvironment,
+    run_roots: &[RunPlan],
+) -> i32 {
+    let started = std::time::Instant::now();
+    let code = match parsed.remote.as_deref() {
+        Some(executor) => {
+            headlamp::remote::run_remote(executor, config_root, parsed, run_env, run_roots)
+        }
+        None => headlamp::runner::run_plans(config_root, parsed, run_env, run_roots).exit_code,
+    };
+    headlamp::notify::notify_run_completed(config_root, parsed, run_env, code, started.elapsed());
+    headlamp::format::vitest::print_summary_line_if_requested(parsed, code, started.elapsed());
+    headlamp::resource_report::print_resource_report_if_requested(parsed);
+    headlamp::badges::write_badges_if_requested(config_root, parsed);
+    code
 }
 
-fn early_exit_before_double_dash(argv: &[String]) -> Option<EarlyExit> {
-    argv.iter()
-        .take_while(|t| t.as_str() != "--")
-        .find_map(|t| {
-            Some(match t.as_str() {
-                "-h" | "--help" => EarlyExit::Help,
-                "-V" | "--version" => EarlyExit::Version,
-                _ => return None,
-            })
+/// The runners to execute with their run roots and own args (which differ only under
+/// `--rerun-failed`), plus the args every one of them shares.
+fn resolve_runs(
+    explicit_runner: Option<Runner>,
+    argv: Vec<String>,
+    cwd: &std::path::Path,
+    config_root: &std::path::Path,
+) -> (Vec<RunPlan>, headlamp::args::ParsedArgs) {
+    let initial = build_parsed_args(config_root, &argv);
+    let rerun = load_rerun_failed(config_root, &initial);
+    let runners = match (explicit_runner, rerun.as_deref()) {
+        (Some(runner), _) => vec![runner],
+        (None, Some(failed)) => headlamp::rerun::failed_languages(failed)
+            .into_iter()
+            .map(|language| Runner::for_language(config_root, language))
+            .collect(),
+        (None, None) => headlamp::runner::detect_runners(config_root, &initial),
+    };
+    let argv = if runners.iter().copied().any(Runner::is_rust) {
+        headlamp::args::expand_short_package_flag(&argv)
+    } else {
+        argv
+    };
+    let parsed = build_parsed_args(config_root, &argv);
+    let run_roots = runners
+        .iter()
+        .map(|runner| {
+            let mut run_args = match rerun.as_deref() {
+                Some(failed) => headlamp::rerun::rerun_args(&parsed, failed, runner.rerun_runner()),
+                None => parsed.clone(),
+            };
+            run_args.runner_args =
+                headlamp::args::translate_runner_args(runner.rerun_runner(), &run_args.runner_args);
+            (*runner, runner.run_root(cwd, &parsed), run_args)
         })
+        .collect::<Vec<_>>();
+    (run_roots, parsed)
 }
 
-fn print_version() {
-    println!("{}", env!("CARGO_PKG_VERSION"));
+/// A report without failures has nothing to replay, so headlamp exits successfully.
+fn load_rerun_failed(
+    config_root: &std::path::Path,
+    parsed: &headlamp::args::ParsedArgs,
+) -> Option<Vec<headlamp::rerun::FailedTest>> {
+    let report = parsed.rerun_failed.as_deref()?;
+    match headlamp::rerun::load_failed_tests(config_root, &config_root.join(report)) {
+        Ok(failed) if failed.is_empty() => {
+            eprintln!("headlamp: no failed tests in {report}");
+            std::process::exit(0);
+        }
+        Ok(failed) => Some(failed),
+        Err(err) => {
+            eprintln!("headlamp: --rerun-failed {report}: {err}");
+            std::process::exit(2);
+        }
+    }
 }
 
 fn build_parsed_args(repo_root: &std::path::Path, argv: &[String]) -> headlamp::args::ParsedArgs {
-    let cfg = headlamp::config::load_headlamp_config(repo_root).unwrap_or_default();
-    let cfg_tokens = headlamp::args::config_tokens(&cfg, argv);
-    headlamp::args::derive_args(
-        &cfg_tokens,
-        argv,
-        headlamp::format::terminal::is_output_terminal(),
-    )
+    let cwd = std::env::current_dir().unwrap_or_else(|_| repo_root.to_path_buf());
+    headlamp::api::parse_args(repo_root, &cwd, argv).unwrap_or_else(|err| {
+        eprintln!("headlamp: {err}");
+        std::process::exit(2);
+    })
 }
 
-fn apply_ci_env(parsed: &headlamp::args::ParsedArgs) {
-    if parsed.ci {
-        unsafe { std::env::set_var("CI", "1") };
+/// Settings every runner subprocess picks up, fixed once before the first run.
+fn apply_process_settings(parsed: &headlamp::args::ParsedArgs, config_root: &std::path::Path) {
+    if let Err(err) = headlamp::log::init(parsed) {
+        eprintln!("headlamp: {err}");
+        std::process::exit(2);
     }
-}
-
-fn validate_watch_ci(parsed: &headlamp::args::ParsedArgs) {
-    if parsed.watch && parsed.ci {
-        eprintln!("headlamp: --watch is not allowed with --ci");
+    headlamp::process::set_process_limits(headlamp::process::ProcessLimits::from_args(parsed));
+    headlamp::node_toolchain::set_node_override(parsed.node.as_ref().map(std::path::PathBuf::from));
+    headlamp::format::deterministic::configure(parsed.deterministic, config_root);
+    headlamp::format::deterministic::configure_times(parsed.normalize_times);
+    if let Err(err) =
+        headlamp::format::output_filter::configure(&parsed.output_filters, &parsed.output_collapse)
+    {
+        eprintln!("headlamp: {err}");
         std::process::exit(2);
     }
 }
 
-fn maybe_print_verbose_startup(
-    runner: Runner,
-    repo_root: &std::path::Path,
+/// `--ci`, the cache dir and `--env` reach the runners through their commands, never through
+/// headlamp's own environment.
+fn build_run_environment(
+    config_root: &std::path::Path,
     parsed: &headlamp::args::ParsedArgs,
-) {
-    if !parsed.verbose {
-        return;
+) -> headlamp::process::RunEnvironment {
+    match headlamp::process::RunEnvironment::from_args(config_root, parsed) {
+        Ok(run_env) => {
+            if let Some(cache_dir) = run_env.cache_dir.as_ref() {
+                let _ = std::fs::create_dir_all(cache_dir);
+            }
+            run_env.publish();
+            run_env
+        }
+        Err(err) => {
+            eprintln!("headlamp: {err}");
+            std::process::exit(2);
+        }
     }
-    eprintln!(
-        "headlamp: runner={runner:?} repo_root={} watch={} ci={} no_cache={}",
-        repo_root.to_string_lossy(),
-        parsed.watch,
-        parsed.ci,
-        parsed.no_cache
-    );
 }
 
-fn run_once(
-    runner: Runner,
-    repo_root: &std::path::Path,
-    parsed: &headlamp::args::ParsedArgs,
-    user_cache_dir_was_set: bool,
-) -> i32 {
-    let session = match headlamp::session::RunSession::new(parsed.keep_artifacts) {
-        Ok(session) => session,
-        Err(err) => return render_run_error(repo_root, parsed, runner, err),
-    };
-    if !parsed.keep_artifacts && !user_cache_dir_was_set {
-        let cache_dir = headlamp::fast_related::default_cache_root();
-        let _ = std::fs::create_dir_all(&cache_dir);
-        unsafe { std::env::set_var("HEADLAMP_CACHE_DIR", cache_dir) };
-    }
-    match runner {
-        Runner::Jest => headlamp::jest::run_jest(repo_root, parsed, &session)
-            .unwrap_or_else(|err| render_run_error(repo_root, parsed, runner, err)),
-        Runner::Pytest => headlamp::pytest::run_pytest(repo_root, parsed, &session)
-            .unwrap_or_else(|err| render_run_error(repo_root, parsed, runner, err)),
-        Runner::Headlamp => headlamp::rust_runner::run_headlamp_rust(repo_root, parsed, &session)
-            .unwrap_or_else(|err| render_run_error(repo_root, parsed, runner, err)),
-        Runner::CargoTest => headlamp::cargo::run_cargo_test(repo_root, parsed, &session)
-            .unwrap_or_else(|err| render_run_error(repo_root, parsed, runner, err)),
-        Runner::CargoNextest => headlamp::cargo::run_cargo_nextest(repo_root, parsed, &session)
-            .unwrap_or_else(|err| render_run_error(repo_root, parsed, runner, err)),
+fn validate_watch_ci(parsed: &headlamp::args::ParsedArgs) {
+    if parsed.watch && parsed.ci {
+        eprintln!("headlamp: --watch is not allowed with --ci");
+        std::process::exit(2);
     }
 }
 
-fn runner_label(runner: Runner) -> &'static str {
-    match runner {
-        Runner::Jest => "jest",
-        Runner::Pytest => "pytest",
-        Runner::Headlamp => "headlamp",
-        Runner::CargoTest => "cargo-test",
-        Runner::CargoNextest => "cargo-nextest",
+fn validate_notify(parsed: &headlamp::args::ParsedArgs) {
+    if let Err(err) = headlamp::notify::validate_notify_targets(&parsed.notify) {
+        eprintln!("headlamp: {err}");
+        std::process::exit(2);
     }
 }
 
-fn render_run_error(
-    repo_root: &std::path::Path,
-    parsed: &headlamp::args::ParsedArgs,
-    runner: Runner,
-    err: headlamp::run::RunError,
-) -> i32 {
-    let ctx = headlamp::format::ctx::make_ctx(
-        repo_root,
-        None,
-        true,
-        parsed.show_logs,
-        parsed.editor_cmd.clone(),
+fn log_startup(runner: Runner, repo_root: &std::path::Path, parsed: &headlamp::args::ParsedArgs) {
+    tracing::debug!(
+        target: "run",
+        runner = ?runner,
+        repo_root = %repo_root.display(),
+        watch = parsed.watch,
+        ci = parsed.ci,
+        no_cache = parsed.no_cache,
+        "starting runner"
     );
-    let suite_path = format!("headlamp/{}", runner_label(runner));
-    let model = headlamp::format::infra_failure::build_infra_failure_test_run_model(
-        suite_path.as_str(),
-        "Test suite failed to run",
-        &err.to_string(),
-    );
-    let rendered = headlamp::format::vitest::render_vitest_from_test_model(&model, &ctx, true);
-    if !rendered.trim().is_empty() {
-        println!("{rendered}");
-    }
-    1
 }
 
-fn extract_runner(argv: &[String]) -> (Runner, Vec<String>) {
+fn extract_runner(argv: &[String]) -> (Option<Runner>, Vec<String>) {
     let mut out: Vec<String> = vec![];
     let mut runner: Option<Runner> = None;
 
@@ -253,7 +263,7 @@ fn extract_runner(argv: &[String]) -> (Runner, Vec<String>) {
                 .map(|(_, v)| v)
                 .or_else(|| argv.get(i + 1).map(|s| s.as_str()));
             if let Some(v) = v {
-                runner = parse_runner(v).or_else(|| {
+                runner = Runner::parse(v).or_else(|| {
                     eprintln!("headlamp: unknown runner: {v}");
                     eprintln!();
                     print_help();
@@ -267,18 +277,7 @@ fn extract_runner(argv: &[String]) -> (Runner, Vec<String>) {
         i += 1;
     }
 
-    (runner.unwrap_or(Runner::Jest), out)
-}
-
-fn parse_runner(raw: &str) -> Option<Runner> {
-    Some(match raw.trim().to_ascii_lowercase().as_str() {
-        "jest" => Runner::Jest,
-        "pytest" => Runner::Pytest,
-        "headlamp" => Runner::Headlamp,
-        "cargo-nextest" => Runner::CargoNextest,
-        "cargo-test" => Runner::CargoTest,
-        _ => return None,
-    })
+    (runner, out)
 }
 
 fn print_help() {
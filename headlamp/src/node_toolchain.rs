@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{LazyLock, RwLock};
+
+static NODE_OVERRIDE: LazyLock<RwLock<Option<PathBuf>>> = LazyLock::new(|| RwLock::new(None));
+
+/// `--node <path>`: the node binary every jest subprocess runs under, ahead of any `.nvmrc` or
+/// volta pin.
+pub fn set_node_override(node: Option<PathBuf>) {
+    if let Ok(mut guard) = NODE_OVERRIDE.write() {
+        *guard = node;
+    }
+}
+
+fn current_node_override() -> Option<PathBuf> {
+    NODE_OVERRIDE.read().ok().and_then(|guard| guard.clone())
+}
+
+/// Finds jest for `repo_root`. Workspace layouts (pnpm, yarn and npm workspaces) may only link
+/// `node_modules/.bin/jest` in the workspace root or leave just the package's own entry script,
+/// so each ancestor directory is tried in turn. Yarn Plug'n'Play has no `node_modules`; its
+/// `.pnp.cjs` stands in as the entry and jest is started through `yarn`.
+pub fn resolve_jest_bin(repo_root: &Path) -> Option<PathBuf> {
+    let bin_name = if cfg!(windows) { "jest.cmd" } else { "jest" };
+    repo_root.ancestors().find_map(|dir| {
+        let node_modules = dir.join("node_modules");
+        [
+            node_modules.join(".bin").join(bin_name),
+            node_modules.join("jest").join("bin").join("jest.js"),
+            node_modules.join("jest-cli").join("bin").join("jest.js"),
+            dir.join(".pnp.cjs"),
+        ]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+    })
+}
+
+/// The command that starts jest from `jest_bin` (as returned by [`resolve_jest_bin`]) under the
+/// repo's pinned node: `--node` first, then volta's `package.json` pin when volta is installed,
+/// then an installed nvm version matching `.nvmrc` / `.node-version`.
+pub fn jest_command(jest_bin: &Path) -> Command {
+    let project_dir = project_dir_for(jest_bin);
+    let node_override = current_node_override();
+    let (program, args) = entry_program_and_args(jest_bin, &project_dir, node_override.clone());
+    pinned_command(&project_dir, node_override, program, args)
+}
+
+/// Plain `node`, pinned the same way as [`jest_command`] for the project that owns `jest_bin`;
+/// used for headlamp's own node scripts.
+pub fn node_command_for(jest_bin: &Path) -> Command {
+    let project_dir = project_dir_for(jest_bin);
+    let node_override = current_node_override();
+    let program = node_override
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("node"));
+    pinned_command(&project_dir, node_override, program, vec![])
+}
+
+fn pinned_command(
+    project_dir: &Path,
+    node_override: Option<PathBuf>,
+    program: PathBuf,
+    mut args: Vec<String>,
+) -> Command {
+    let pinned_node_dir = match &node_override {
+        Some(node) => node.parent().map(Path::to_path_buf),
+        None => nvm_pinned_bin_dir(project_dir),
+    };
+    let volta_node = node_override
+        .is_none()
+        .then(|| volta_pinned_node(project_dir))
+        .flatten()
+        .filter(|_| which::which("volta").is_ok());
+    let mut command = match volta_node {
+        Some(version) => {
+            let mut volta_args = vec!["run".to_string(), "--node".to_string(), version];
+            volta_args.push(program.to_string_lossy().to_string());
+            volta_args.append(&mut args);
+            args = volta_args;
+            Command::new("volta")
+        }
+        None => Command::new(&program),
+    };
+    command.args(args);
+    if let Some(dir) = pinned_node_dir {
+        prepend_to_path(&mut command, &dir);
+    }
+    command
+}
+
+fn entry_program_and_args(
+    jest_bin: &Path,
+    project_dir: &Path,
+    node_override: Option<PathBuf>,
+) -> (PathBuf, Vec<String>) {
+    if jest_bin.file_name().is_some_and(|name| name == ".pnp.cjs") {
+        let uses_corepack = package_manager_field(project_dir)
+            .is_some_and(|pm| pm.starts_with("yarn"))
+            && which::which("corepack").is_ok();
+        return if uses_corepack {
+            (
+                PathBuf::from("corepack"),
+                vec!["yarn".to_string(), "jest".to_string()],
+            )
+        } else {
+            (PathBuf::from("yarn"), vec!["jest".to_string()])
+        };
+    }
+    let is_script = jest_bin
+        .extension()
+        .is_some_and(|ext| ext == "js" || ext == "cjs" || ext == "mjs");
+    if is_script {
+        let node = node_override.unwrap_or_else(|| PathBuf::from("node"));
+        return (node, vec![jest_bin.to_string_lossy().to_string()]);
+    }
+    (jest_bin.to_path_buf(), vec![])
+}
+
+/// The directory that owns `node_modules` (or `.pnp.cjs`) for a jest entry.
+fn project_dir_for(jest_bin: &Path) -> PathBuf {
+    jest_bin
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "node_modules"))
+        .and_then(Path::parent)
+        .or_else(|| jest_bin.parent())
+        .unwrap_or(Path::new("."))
+        .to_path_buf()
+}
+
+fn read_package_json(dir: &Path) -> Option<serde_json::Value> {
+    let raw = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn package_manager_field(project_dir: &Path) -> Option<String> {
+    project_dir.ancestors().find_map(|dir| {
+        read_package_json(dir)?
+            .get("packageManager")?
+            .as_str()
+            .map(str::to_string)
+    })
+}
+
+/// `"volta": { "node": "20.11.0" }`, from the nearest `package.json` that pins it (volta lets
+/// workspace packages `extends` the root pin, so ancestors are checked too).
+pub fn volta_pinned_node(project_dir: &Path) -> Option<String> {
+    project_dir.ancestors().find_map(|dir| {
+        read_package_json(dir)?
+            .get("volta")?
+            .get("node")?
+            .as_str()
+            .map(str::to_string)
+    })
+}
+
+fn nvm_pinned_bin_dir(project_dir: &Path) -> Option<PathBuf> {
+    let spec = project_dir.ancestors().find_map(|dir| {
+        [".nvmrc", ".node-version"]
+            .into_iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    })?;
+    let nvm_dir = std::env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".nvm")))?;
+    nvm_node_bin_dir(&nvm_dir, &spec)
+}
+
+/// The `bin` dir of the newest node under `<nvm_dir>/versions/node` matching `spec` (`v20`,
+/// `20.11`, `20.11.0`). Aliases such as `lts/*` are left to whatever node is on `PATH`.
+pub fn nvm_node_bin_dir(nvm_dir: &Path, spec: &str) -> Option<PathBuf> {
+    let wanted = spec.trim().trim_start_matches('v');
+    if wanted.is_empty() || !wanted.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let versions_dir = nvm_dir.join("versions").join("node");
+    std::fs::read_dir(&versions_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let version = semver::Version::parse(name.trim_start_matches('v')).ok()?;
+            let text = version.to_string();
+            (text == wanted || text.starts_with(&format!("{wanted}.")))
+                .then(|| (version, entry.path().join("bin")))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, bin)| bin)
+}
+
+fn prepend_to_path(command: &mut Command, dir: &Path) {
+    let current = std::env::var_os("PATH").unwrap_or_default();
+    let paths = std::iter::once(dir.to_path_buf()).chain(std::env::split_paths(&current));
+    if let Ok(joined) = std::env::join_paths(paths) {
+        command.env("PATH", joined);
+    }
+}
@@ -0,0 +1,232 @@
+use std::path::Path;
+use std::time::Duration;
+
+use duct::cmd as duct_cmd;
+use serde::Serialize;
+
+use crate::args::ParsedArgs;
+use crate::format::time::format_duration;
+use crate::process::RunEnvironment;
+use crate::recorded::RecordedResults;
+use crate::run::RunError;
+
+/// Failures listed in desktop and Slack messages; the JSON payload carries all of them.
+const MAX_LISTED_FAILURES: usize = 10;
+
+const SLACK_TIMEOUT_SECS: &str = "10";
+
+/// Where `--notify` reports a finished run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyTarget {
+    /// `desktop`: `osascript` on macOS, `notify-send` elsewhere.
+    Desktop,
+    /// `slack:<webhook url>`: an incoming-webhook message, posted with `curl`.
+    Slack(String),
+    /// `cmd:<script>`: a shell command that reads the JSON payload on stdin.
+    Command(String),
+}
+
+pub fn parse_notify_target(spec: &str) -> Option<NotifyTarget> {
+    let spec = spec.trim();
+    if spec == "desktop" {
+        return Some(NotifyTarget::Desktop);
+    }
+    if let Some(webhook) = spec.strip_prefix("slack:") {
+        let webhook = webhook.trim();
+        return webhook
+            .starts_with("https://")
+            .then(|| NotifyTarget::Slack(webhook.to_string()));
+    }
+    let command = spec.strip_prefix("cmd:")?.trim();
+    (!command.is_empty()).then(|| NotifyTarget::Command(command.to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NotifyPayload {
+    pub status: &'static str,
+    pub exit_code: i32,
+    pub passed: u64,
+    pub failed: u64,
+    pub skipped: u64,
+    pub duration_ms: u64,
+    pub failed_tests: Vec<NotifiedFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NotifiedFailure {
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl NotifyPayload {
+    pub fn new(results: &RecordedResults, exit_code: i32, duration: Duration) -> Self {
+        Self {
+            status: if exit_code == 0 { "passed" } else { "failed" },
+            exit_code,
+            passed: results.passed,
+            failed: results.failed,
+            skipped: results.skipped,
+            duration_ms: duration.as_millis() as u64,
+            failed_tests: results
+                .failed_tests
+                .iter()
+                .map(|(file, name)| NotifiedFailure {
+                    file: file.clone(),
+                    name: name.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// `headlamp failed: 2 failed, 40 passed, 1 skipped in 1m 3s`
+    pub fn headline(&self) -> String {
+        format!(
+            "headlamp {}: {} failed, {} passed, {} skipped in {}",
+            self.status,
+            self.failed,
+            self.passed,
+            self.skipped,
+            format_duration(Duration::from_millis(self.duration_ms))
+        )
+    }
+
+    /// The headline, then one line per failure (up to `MAX_LISTED_FAILURES`).
+    pub fn message(&self) -> String {
+        let listed =
+            self.failed_tests
+                .iter()
+                .take(MAX_LISTED_FAILURES)
+                .map(|failure| match &failure.name {
+                    Some(name) => format!("✗ {} > {name}", failure.file),
+                    None => format!("✗ {}", failure.file),
+                });
+        let hidden = self.failed_tests.len().saturating_sub(MAX_LISTED_FAILURES);
+        let more = (hidden > 0).then(|| format!("…and {hidden} more"));
+        std::iter::once(self.headline())
+            .chain(listed)
+            .chain(more)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Specs are checked before the run starts so a typo does not surface only after a long run.
+pub fn validate_notify_targets(specs: &[String]) -> Result<(), RunError> {
+    match specs
+        .iter()
+        .find(|spec| parse_notify_target(spec).is_none())
+    {
+        Some(spec) => Err(RunError::CommandFailed {
+            message: format!(
+                "invalid --notify target: {spec} (expected desktop, slack:<https webhook> or cmd:<script>)"
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Sends the results recorded for the run that just ended to every `--notify` target. A target
+/// that fails only warns; the run's exit code stands.
+pub fn notify_run_completed(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    exit_code: i32,
+    duration: Duration,
+) {
+    if args.notify.is_empty() {
+        return;
+    }
+    let payload = NotifyPayload::new(&crate::recorded::recorded_results(), exit_code, duration);
+    for spec in &args.notify {
+        let Some(target) = parse_notify_target(spec) else {
+            continue;
+        };
+        if let Err(err) = send(repo_root, &target, &payload, run_env) {
+            eprintln!("headlamp: --notify={spec} failed: {err}");
+        }
+    }
+}
+
+fn send(
+    repo_root: &Path,
+    target: &NotifyTarget,
+    payload: &NotifyPayload,
+    run_env: &RunEnvironment,
+) -> Result<(), RunError> {
+    let json = serde_json::to_vec(payload)
+        .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
+    let expression = match target {
+        NotifyTarget::Desktop => desktop_expression(payload)?,
+        NotifyTarget::Slack(webhook) => {
+            let body = serde_json::json!({ "text": payload.message() }).to_string();
+            duct_cmd(
+                "curl",
+                [
+                    "-sS",
+                    "--fail",
+                    "--max-time",
+                    SLACK_TIMEOUT_SECS,
+                    "-H",
+                    "Content-Type: application/json",
+                    "-K",
+                    "-",
+                ],
+            )
+            .stdin_bytes(slack_curl_config(webhook, &body))
+            .stdout_null()
+        }
+        NotifyTarget::Command(command) => run_env.pairs().into_iter().fold(
+            crate::run::shell_expression(command)
+                .dir(repo_root)
+                .stdin_bytes(json),
+            |expression, (key, value)| expression.env(key, value),
+        ),
+    };
+    let output = expression
+        .stdout_to_stderr()
+        .unchecked()
+        .run()
+        .map_err(RunError::SpawnFailed)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(RunError::CommandFailed {
+            message: format!("exited with {}", output.status),
+        })
+    }
+}
+
+fn desktop_expression(payload: &NotifyPayload) -> Result<duct::Expression, RunError> {
+    let body = payload.headline();
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title \"headlamp\"",
+            double_quoted(&body)
+        );
+        return Ok(duct_cmd("osascript", ["-e", script.as_str()]));
+    }
+    if which::which("notify-send").is_err() {
+        return Err(RunError::MissingRunner {
+            runner: "notify-send".to_string(),
+            hint: "install libnotify to use --notify=desktop".to_string(),
+        });
+    }
+    Ok(duct_cmd("notify-send", ["headlamp", body.as_str()]))
+}
+
+/// The `curl -K -` config that posts `body` to `webhook`. The webhook URL is a secret, so it goes
+/// through stdin rather than argv, where any user could read it with `ps`.
+pub fn slack_curl_config(webhook: &str, body: &str) -> String {
+    format!(
+        "url = {}\ndata-binary = {}\n",
+        double_quoted(webhook),
+        double_quoted(body)
+    )
+}
+
+/// AppleScript and curl config strings escape alike.
+fn double_quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
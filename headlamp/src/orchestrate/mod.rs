@@ -0,0 +1,455 @@
+//! `headlamp orchestrate --shards=N`: one CI job runs the whole suite as N headlamp invocations.
+//! The selected test files are split by their recorded timings (see [`shards`]), each shard runs
+//! as a child `headlamp` with `--artifacts-dir`, and the children's reports are read back into
+//! one summary, followed by each shard's wall time.
+//!
+//! Children run on this machine, or through a launcher (`--launcher=<cmd>`, or `shardLauncher`
+//! in the config): a shell command run once per shard and runner with
+//!
+//! - `HEADLAMP_SHARD_INDEX` / `HEADLAMP_SHARD_TOTAL`: the 1-based shard and the shard count;
+//! - `HEADLAMP_SHARD_ARGS`: the shell-quoted args for `headlamp`;
+//! - `HEADLAMP_SHARD_OUT`: the artifacts dir those args name, which must hold the results when
+//!   the launcher exits.
+//!
+//! A shard runs one child per runner its files need, one after another. Child output (coverage
+//! tables included) goes to `shard-<n>.log` next to the reports.
+
+pub mod shards;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::runner::Runner;
+use crate::runner_detect::{DetectedLanguage, language_for_path};
+use crate::session::run_dirs::{new_run_dir, prune_run_dirs};
+use crate::test_model::TestRunModel;
+
+pub use shards::{FileTimings, Shard, discover_test_files, split_into_shards};
+
+const USAGE: &str = "headlamp: usage: headlamp orchestrate --shards=<n> [--launcher=<cmd>] \
+                     [--runner=<name>] [flags...] [paths...]";
+
+pub fn run_orchestrate_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "orchestrate" {
+        return None;
+    }
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let repo_root = crate::config::resolve_repo_root(&cwd, rest);
+    let mut options = match OrchestrateOptions::parse(&cwd, rest) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("headlamp: {message}");
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+    };
+    if options.launcher.is_none() {
+        options.launcher =
+            crate::config::validate::load_headlamp_config_checked(&repo_root).shard_launcher;
+    }
+    let cache_root = crate::fast_related::default_cache_root();
+    Some(orchestrate(&repo_root, &cache_root, &options))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrchestrateOptions {
+    pub shards: usize,
+    /// Runs each shard instead of spawning `headlamp` here.
+    pub launcher: Option<String>,
+    /// Every shard runs only this runner's files; by default each file's language picks one.
+    pub runner: Option<Runner>,
+    /// Files and dirs to take the test files from; the repo root when empty.
+    pub paths: Vec<PathBuf>,
+    /// Everything else, passed to every child.
+    pub args: Vec<String>,
+    /// After `--`, passed to every child's runner.
+    pub passthrough: Vec<String>,
+}
+
+impl OrchestrateOptions {
+    /// Tokens that name an existing file or dir (relative to `cwd`) are the selection.
+    pub fn parse(cwd: &Path, argv: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut tokens = argv.iter();
+        while let Some(token) = tokens.next() {
+            if token == "--" {
+                options.passthrough = tokens.by_ref().cloned().collect();
+                break;
+            }
+            let (flag, inline) = match token.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (token.as_str(), None),
+            };
+            if !matches!(flag, "--shards" | "--launcher" | "--runner") {
+                match cwd.join(token) {
+                    path if !token.starts_with('-') && path.exists() => options.paths.push(path),
+                    _ => options.args.push(token.clone()),
+                }
+                continue;
+            }
+            let value = inline
+                .or_else(|| tokens.next().cloned())
+                .ok_or_else(|| format!("{flag} needs a value"))?;
+            match flag {
+                "--shards" => {
+                    options.shards = value
+                        .parse()
+                        .ok()
+                        .filter(|shards| *shards > 0)
+                        .ok_or_else(|| format!("--shards needs a positive count: {value}"))?;
+                }
+                "--launcher" => options.launcher = Some(value),
+                _ => {
+                    let runner =
+                        Runner::parse(&value).ok_or_else(|| format!("unknown runner: {value}"))?;
+                    options.runner = Some(runner);
+                }
+            }
+        }
+        if options.shards == 0 {
+            return Err("--shards is required".to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// What one shard ran and how long it took.
+#[derive(Debug, Clone)]
+pub struct ShardRun {
+    pub index: usize,
+    pub shard: Shard,
+    pub exit_code: i32,
+    pub elapsed: Duration,
+    pub models: Vec<TestRunModel>,
+    pub log: PathBuf,
+}
+
+/// Splits, runs the shards in parallel, prints the merged report and per-shard timings, and
+/// records the files' timings for the next split, all under `cache_root`. Older runs' dirs are
+/// pruned unless `--keep-artifacts` is among the forwarded flags. The worst shard's exit code
+/// wins.
+pub fn orchestrate(repo_root: &Path, cache_root: &Path, options: &OrchestrateOptions) -> i32 {
+    let files = discover_test_files(repo_root, &options.paths)
+        .into_iter()
+        .filter(|file| runner_for_file(repo_root, options, file).is_some())
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        eprintln!("headlamp: orchestrate: no test files selected");
+        return 0;
+    }
+    let mut timings = FileTimings::load(cache_root, repo_root);
+    let split = split_into_shards(&files, &timings, options.shards);
+    let run_dir = new_run_dir(cache_root, repo_root, "orchestrate");
+    let started = Instant::now();
+    let runs = std::thread::scope(|scope| {
+        let handles = split
+            .into_iter()
+            .enumerate()
+            .filter(|(_, shard)| !shard.files.is_empty())
+            .map(|(index, shard)| {
+                let run_dir = run_dir.as_path();
+                let planned = shard.clone();
+                let handle =
+                    scope.spawn(move || run_shard(repo_root, options, run_dir, index, shard));
+                (index, planned, handle)
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|(index, shard, handle)| {
+                handle.join().unwrap_or_else(|_| {
+                    eprintln!("headlamp: orchestrate: shard {} panicked", index + 1);
+                    ShardRun {
+                        index,
+                        shard,
+                        exit_code: 1,
+                        elapsed: started.elapsed(),
+                        models: vec![],
+                        log: shard_log(&run_dir, index),
+                    }
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    let models = runs
+        .iter()
+        .flat_map(|run| run.models.iter().cloned())
+        .collect::<Vec<_>>();
+    print_merged_report(repo_root, options, &models);
+    println!(
+        "{}",
+        render_shard_timings(&runs, options.shards, started.elapsed())
+    );
+    timings.record(repo_root, &models);
+    timings.save(cache_root, repo_root);
+    if !crate::args::derive_args(&[], &options.args, false).keep_artifacts {
+        prune_run_dirs(&run_dir);
+    }
+    crate::run::worst_exit_code(runs.iter().map(|run| run.exit_code), false)
+}
+
+/// `--runner` when given (its language's files only), else the runner for the file's language.
+fn runner_for_file(repo_root: &Path, options: &OrchestrateOptions, file: &str) -> Option<Runner> {
+    let language = language_for_path(Path::new(file))?;
+    let Some(runner) = options.runner else {
+        return Some(Runner::for_language(repo_root, language));
+    };
+    let matches = match runner {
+        Runner::Jest => language == DetectedLanguage::JavaScript,
+        Runner::Pytest => language == DetectedLanguage::Python,
+        Runner::Headlamp | Runner::CargoTest | Runner::CargoNextest => {
+            language == DetectedLanguage::Rust
+        }
+        Runner::Plugin(_) => true,
+    };
+    matches.then_some(runner)
+}
+
+fn run_shard(
+    repo_root: &Path,
+    options: &OrchestrateOptions,
+    run_dir: &Path,
+    index: usize,
+    shard: Shard,
+) -> ShardRun {
+    let started = Instant::now();
+    let log = shard_log(run_dir, index);
+    let mut by_runner: Vec<(Runner, Vec<String>)> = vec![];
+    for file in &shard.files {
+        let Some(runner) = runner_for_file(repo_root, options, file) else {
+            continue;
+        };
+        match by_runner.iter_mut().find(|(known, _)| *known == runner) {
+            Some((_, files)) => files.push(file.clone()),
+            None => by_runner.push((runner, vec![file.clone()])),
+        }
+    }
+    let mut codes = vec![];
+    let mut models = vec![];
+    for (runner, files) in by_runner {
+        let out = run_dir
+            .join(format!("shard-{}", index + 1))
+            .join(runner_flag(runner).replace(':', "-"));
+        let child = ShardChild {
+            index,
+            total: options.shards,
+            args: child_args(options, runner, &files, &out),
+            out,
+        };
+        let code = child.run(repo_root, options.launcher.as_deref(), &log);
+        match crate::rerun::load_report_models(&child.out) {
+            Ok(read) if !read.is_empty() => {
+                let failed = read.iter().any(|model| !model.aggregated.success);
+                codes.push(if code == 0 { i32::from(failed) } else { code });
+                models.extend(read);
+            }
+            _ => {
+                eprintln!(
+                    "headlamp: orchestrate: shard {} ({}) left no reports; see {}",
+                    index + 1,
+                    runner.label(),
+                    log.display()
+                );
+                codes.push(if code == 0 { 1 } else { code });
+            }
+        }
+    }
+    ShardRun {
+        index,
+        shard,
+        exit_code: crate::run::worst_exit_code(codes.into_iter(), false),
+        elapsed: started.elapsed(),
+        models,
+        log,
+    }
+}
+
+fn shard_log(run_dir: &Path, index: usize) -> PathBuf {
+    run_dir.join(format!("shard-{}.log", index + 1))
+}
+
+fn runner_flag(runner: Runner) -> String {
+    match runner {
+        Runner::Plugin(name) => format!("plugin:{name}"),
+        runner => runner.label().to_string(),
+    }
+}
+
+/// The args of one child `headlamp`: its runner, the shared flags, where to leave the reports,
+/// then its files and the runner passthrough.
+pub fn child_args(
+    options: &OrchestrateOptions,
+    runner: Runner,
+    files: &[String],
+    out: &Path,
+) -> Vec<String> {
+    let passthrough = match options.passthrough.as_slice() {
+        [] => vec![],
+        rest => std::iter::once("--".to_string())
+            .chain(rest.iter().cloned())
+            .collect(),
+    };
+    [format!("--runner={}", runner_flag(runner))]
+        .into_iter()
+        .chain(options.args.iter().cloned())
+        .chain([format!("--artifacts-dir={}", out.to_string_lossy())])
+        .chain(files.iter().cloned())
+        .chain(passthrough)
+        .collect()
+}
+
+struct ShardChild {
+    index: usize,
+    total: usize,
+    args: Vec<String>,
+    out: PathBuf,
+}
+
+impl ShardChild {
+    /// Output is appended to `log`; a child that cannot start counts as exit code 1.
+    fn run(&self, repo_root: &Path, launcher: Option<&str>, log: &Path) -> i32 {
+        let expression = match launcher {
+            Some(launcher) => {
+                let quoted = self
+                    .args
+                    .iter()
+                    .map(|arg| crate::repro::shell_quote(arg))
+                    .collect::<Vec<_>>();
+                crate::run::shell_expression(launcher)
+                    .env("HEADLAMP_SHARD_ARGS", quoted.join(" "))
+                    .env("HEADLAMP_SHARD_OUT", &self.out)
+            }
+            None => match std::env::current_exe() {
+                Ok(exe) => duct::cmd(exe, &self.args),
+                Err(err) => {
+                    eprintln!("headlamp: orchestrate: cannot find the headlamp binary: {err}");
+                    return 1;
+                }
+            },
+        };
+        let opened = log
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log)
+            });
+        let file = match opened {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("headlamp: orchestrate: {}: {err}", log.display());
+                return 1;
+            }
+        };
+        let result = expression
+            .dir(repo_root)
+            .env("HEADLAMP_SHARD_INDEX", (self.index + 1).to_string())
+            .env("HEADLAMP_SHARD_TOTAL", self.total.to_string())
+            .stderr_to_stdout()
+            .stdout_file(file)
+            .unchecked()
+            .run();
+        match result {
+            Ok(output) => output.status.code().unwrap_or(1),
+            Err(err) => {
+                eprintln!("headlamp: orchestrate: shard {}: {err}", self.index + 1);
+                1
+            }
+        }
+    }
+}
+
+/// One report over every shard's suites, in path order.
+pub fn merge_models(models: &[TestRunModel]) -> Option<TestRunModel> {
+    let first = models.first()?;
+    let mut merged = TestRunModel {
+        start_time: models.iter().map(|model| model.start_time).min()?,
+        test_results: models
+            .iter()
+            .flat_map(|model| model.test_results.iter().cloned())
+            .collect(),
+        aggregated: first.aggregated.clone(),
+        // A shard skips the projects whose files went to other shards, which says nothing
+        // about the whole run.
+        skipped_units: vec![],
+    };
+    merged
+        .test_results
+        .sort_by(|left, right| left.test_file_path.cmp(&right.test_file_path));
+    let aggregated = &mut merged.aggregated;
+    for model in &models[1..] {
+        let other = &model.aggregated;
+        aggregated.num_total_test_suites += other.num_total_test_suites;
+        aggregated.num_passed_test_suites += other.num_passed_test_suites;
+        aggregated.num_failed_test_suites += other.num_failed_test_suites;
+        aggregated.num_total_tests += other.num_total_tests;
+        aggregated.num_passed_tests += other.num_passed_tests;
+        aggregated.num_failed_tests += other.num_failed_tests;
+        aggregated.num_pending_tests += other.num_pending_tests;
+        aggregated.num_todo_tests += other.num_todo_tests;
+        aggregated.num_timed_out_tests =
+            sum_optional(aggregated.num_timed_out_tests, other.num_timed_out_tests);
+        aggregated.num_timed_out_test_suites = sum_optional(
+            aggregated.num_timed_out_test_suites,
+            other.num_timed_out_test_suites,
+        );
+        aggregated.start_time = aggregated.start_time.min(other.start_time);
+        aggregated.success &= other.success;
+        // Shards run side by side, so the run took as long as the slowest.
+        aggregated.run_time_ms = aggregated.run_time_ms.max(other.run_time_ms);
+    }
+    Some(merged)
+}
+
+fn sum_optional(left: Option<u64>, right: Option<u64>) -> Option<u64> {
+    match (left, right) {
+        (None, None) => None,
+        (left, right) => Some(left.unwrap_or(0) + right.unwrap_or(0)),
+    }
+}
+
+/// Rendered with the display flags the children were given (`--only-failures`, `--path-style`).
+fn print_merged_report(repo_root: &Path, options: &OrchestrateOptions, models: &[TestRunModel]) {
+    let Some(merged) = merge_models(models) else {
+        return;
+    };
+    crate::recorded::record_failed_tests(&merged);
+    let args = crate::args::derive_args(
+        &[],
+        &options.args,
+        crate::format::terminal::is_output_terminal(),
+    );
+    let ctx = crate::format::ctx::make_ctx_for_args(repo_root, &args, !merged.aggregated.success);
+    println!(
+        "{}",
+        crate::format::vitest::render_vitest_from_test_model(&merged, &ctx, args.only_failures)
+    );
+}
+
+/// A line per shard: its files, wall time against the estimate it was split by, and its log.
+pub fn render_shard_timings(runs: &[ShardRun], total: usize, wall: Duration) -> String {
+    let format = crate::format::time::format_duration;
+    let header = format!("Shards: {total} in {}", format(wall));
+    let lines = runs.iter().map(|run| {
+        let status = if run.exit_code == 0 {
+            "passed"
+        } else {
+            "failed"
+        };
+        format!(
+            "  shard {}/{total}  {} file(s)  {} (est. {})  {status}  {}",
+            run.index + 1,
+            run.shard.files.len(),
+            format(run.elapsed),
+            format(Duration::from_millis(run.shard.estimated_ms)),
+            run.log.display()
+        )
+    });
+    std::iter::once(header)
+        .chain(lines)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
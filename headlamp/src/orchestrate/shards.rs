@@ -0,0 +1,161 @@
+//! Which test files a shard runs: the selected files, split by the time each took in earlier
+//! orchestrated runs so the shards finish close together.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+
+use crate::runner_detect::is_test_file;
+use crate::test_model::TestRunModel;
+
+/// What a file without a recorded timing is assumed to take when nothing has been recorded yet.
+const DEFAULT_FILE_MS: u64 = 1_000;
+
+const SKIPPED_DIRS: [&str; 6] = [
+    "node_modules",
+    ".venv",
+    "venv",
+    "site-packages",
+    "target",
+    ".git",
+];
+
+/// Test files below `roots` (the repo root when empty), repo-relative and sorted: JS/TS
+/// `*.test.*` / `*.spec.*` and files under `__tests__`, Python `test_*.py` / `*_test.py`, and the
+/// `.rs` files of Rust `tests/` dirs. Explicit files are kept as given.
+pub fn discover_test_files(repo_root: &Path, roots: &[PathBuf]) -> Vec<String> {
+    let roots = match roots {
+        [] => vec![repo_root.to_path_buf()],
+        roots => roots.to_vec(),
+    };
+    let mut files = roots
+        .iter()
+        .flat_map(|root| {
+            if root.is_file() {
+                return vec![root.clone()];
+            }
+            walk_test_files(root)
+        })
+        .map(|path| {
+            let rel = crate::format::paths::strip_root(&path, repo_root).unwrap_or(path);
+            rel.to_slash_lossy().to_string()
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn walk_test_files(root: &Path) -> Vec<PathBuf> {
+    ignore::WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .filter_entry(|dent| {
+            !SKIPPED_DIRS
+                .iter()
+                .any(|dir| dent.file_name().to_string_lossy() == *dir)
+        })
+        .build()
+        .map_while(Result::ok)
+        .filter(|dent| dent.file_type().is_some_and(|t| t.is_file()))
+        .map(|dent| dent.into_path())
+        .filter(|path| is_test_file(path))
+        .collect()
+}
+
+/// Milliseconds per repo-relative test file, kept in the repo's cache between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileTimings {
+    pub ms_by_file: BTreeMap<String, u64>,
+}
+
+impl FileTimings {
+    pub fn load(cache_root: &Path, repo_root: &Path) -> Self {
+        let ms_by_file = std::fs::read_to_string(timings_path(cache_root, repo_root))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self { ms_by_file }
+    }
+
+    /// Failures only lose the history; the run itself is unaffected.
+    pub fn save(&self, cache_root: &Path, repo_root: &Path) {
+        let path = timings_path(cache_root, repo_root);
+        let Ok(json) = serde_json::to_string(&self.ms_by_file) else {
+            return;
+        };
+        if path
+            .parent()
+            .is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+        {
+            let _ = std::fs::write(path, json);
+        }
+    }
+
+    /// Each suite's time is the sum of its tests' durations; files that did not run keep theirs.
+    pub fn record(&mut self, repo_root: &Path, models: &[TestRunModel]) {
+        models
+            .iter()
+            .flat_map(|model| &model.test_results)
+            .for_each(|suite| {
+                let file = crate::rerun::localize_path(repo_root, &suite.test_file_path);
+                let ms = suite.test_results.iter().map(|case| case.duration).sum();
+                self.ms_by_file.insert(file, ms);
+            });
+    }
+
+    /// The recorded time, else the mean of the recorded files (or a second when there are none).
+    pub fn estimate(&self, file: &str) -> u64 {
+        self.ms_by_file.get(file).copied().unwrap_or_else(|| {
+            let count = self.ms_by_file.len() as u64;
+            match count {
+                0 => DEFAULT_FILE_MS,
+                _ => self.ms_by_file.values().sum::<u64>() / count,
+            }
+        })
+    }
+}
+
+fn timings_path(cache_root: &Path, repo_root: &Path) -> PathBuf {
+    cache_root
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join("shard-timings.json")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Shard {
+    pub files: Vec<String>,
+    /// The sum of the files' estimates.
+    pub estimated_ms: u64,
+}
+
+/// Longest file first onto the least loaded shard (the lowest index on ties), so the split is
+/// the same for the same files and timings. Each shard's files come back sorted; shards past
+/// the number of files stay empty.
+pub fn split_into_shards(files: &[String], timings: &FileTimings, count: usize) -> Vec<Shard> {
+    let mut shards = vec![Shard::default(); count.max(1)];
+    let mut by_time = files
+        .iter()
+        .map(|file| (timings.estimate(file), file))
+        .collect::<Vec<_>>();
+    by_time.sort_by(|(left_ms, left), (right_ms, right)| {
+        right_ms.cmp(left_ms).then_with(|| left.cmp(right))
+    });
+    for (ms, file) in by_time {
+        let Some(lightest) = shards
+            .iter_mut()
+            .enumerate()
+            .min_by_key(|(index, shard)| (shard.estimated_ms, *index))
+            .map(|(_, shard)| shard)
+        else {
+            break;
+        };
+        lightest.files.push(file.clone());
+        lightest.estimated_ms += ms;
+    }
+    shards.iter_mut().for_each(|shard| shard.files.sort());
+    shards
+}
@@ -0,0 +1,206 @@
+//! `headlamp owners <source-file>`: the test files that exercise a source file, from the same
+//! related-tests selection `--changed` uses, and the unit that owns each of them: the jest
+//! project it runs in, its cargo package, or its Python package.
+
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+use serde::Serialize;
+
+use crate::jest_projects::JestProject;
+use crate::runner_detect::{DetectedLanguage, language_for_path};
+
+const USAGE: &str = "headlamp: usage: headlamp owners <source-file> [--json]";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceOwners {
+    /// Repo-relative, with slashes.
+    pub file: String,
+    pub tests: Vec<OwnedTest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedTest {
+    /// Repo-relative, with slashes.
+    pub file: String,
+    /// `None` when no project, package or manifest claims the file.
+    pub owner: Option<TestOwner>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestOwner {
+    pub kind: OwnerKind,
+    /// A jest project's label (`<config>[#<displayName>]`), a cargo package name, or a Python
+    /// package's repo-relative dir.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OwnerKind {
+    JestProject,
+    CargoPackage,
+    PythonPackage,
+}
+
+impl OwnerKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::JestProject => "jest project",
+            Self::CargoPackage => "cargo package",
+            Self::PythonPackage => "python package",
+        }
+    }
+}
+
+pub fn run_owners_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "owners" {
+        return None;
+    }
+    let json = rest.iter().any(|arg| arg == "--json");
+    let [file] = rest
+        .iter()
+        .filter(|arg| *arg != "--json")
+        .collect::<Vec<_>>()[..]
+    else {
+        eprintln!("{USAGE}");
+        return Some(2);
+    };
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let path = cwd.join(file);
+    if !path.is_file() {
+        eprintln!("headlamp: {file}: no such file");
+        return Some(2);
+    }
+    let repo_root = crate::config::find_repo_root(&cwd);
+    let found = owners(&repo_root, &path);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&found).unwrap_or_default()
+        );
+    } else {
+        println!("{}", render_owners(&found));
+    }
+    Some(0)
+}
+
+/// Static only, like `tests-in-file`: the import graph and the manifests, no runner.
+pub fn owners(repo_root: &Path, path: &Path) -> SourceOwners {
+    let abs = repo_root.join(path);
+    let file = relative_slash(repo_root, &abs);
+    let mut resolver = OwnerResolver::new(repo_root);
+    let tests = crate::selection::related_files::related_test_files(repo_root, &abs)
+        .into_iter()
+        .map(|test| OwnedTest {
+            file: relative_slash(repo_root, &test),
+            owner: resolver.owner_of(&test),
+        })
+        .filter(|test| test.file != file)
+        .collect();
+    SourceOwners { file, tests }
+}
+
+pub fn render_owners(found: &SourceOwners) -> String {
+    if found.tests.is_empty() {
+        return format!("{}: no test files exercise it", found.file);
+    }
+    let width = found
+        .tests
+        .iter()
+        .map(|test| test.file.len())
+        .max()
+        .unwrap_or(0);
+    let lines = found.tests.iter().map(|test| match &test.owner {
+        Some(owner) => format!(
+            "  {:<width$}  {}: {}",
+            test.file,
+            owner.kind.label(),
+            owner.name
+        ),
+        None => format!("  {}", test.file),
+    });
+    std::iter::once(format!(
+        "{}: {} test file(s)",
+        found.file,
+        found.tests.len()
+    ))
+    .chain(lines)
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Loads the jest projects and the cargo workspace the first time a test needs them.
+struct OwnerResolver<'a> {
+    repo_root: &'a Path,
+    jest_projects: Option<Vec<JestProject>>,
+    cargo_workspace: Option<Option<crate::cargo_select::CargoWorkspace>>,
+}
+
+impl<'a> OwnerResolver<'a> {
+    fn new(repo_root: &'a Path) -> Self {
+        Self {
+            repo_root,
+            jest_projects: None,
+            cargo_workspace: None,
+        }
+    }
+
+    fn owner_of(&mut self, test: &Path) -> Option<TestOwner> {
+        let (kind, name) = match language_for_path(test)? {
+            DetectedLanguage::JavaScript => (OwnerKind::JestProject, self.jest_project(test)?),
+            DetectedLanguage::Rust => (OwnerKind::CargoPackage, self.cargo_package(test)?),
+            DetectedLanguage::Python => (
+                OwnerKind::PythonPackage,
+                crate::coverage::scope::package_dir(self.repo_root, test)?,
+            ),
+        };
+        Some(TestOwner { kind, name })
+    }
+
+    /// The project whose config sits deepest above the test; projects selected by
+    /// `displayName` from one config cannot be told apart statically, so that config is named.
+    fn jest_project(&mut self, test: &Path) -> Option<String> {
+        let repo_root = self.repo_root;
+        let projects = self.jest_projects.get_or_insert_with(|| {
+            crate::jest_projects::expand_jest_projects(crate::jest_config::list_all_jest_configs(
+                repo_root,
+            ))
+        });
+        let dir_of = |project: &JestProject| project.config.parent().map(Path::to_path_buf);
+        let deepest = projects
+            .iter()
+            .filter_map(&dir_of)
+            .filter(|dir| test.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())?;
+        let owning = projects
+            .iter()
+            .filter(|project| dir_of(project).as_ref() == Some(&deepest))
+            .collect::<Vec<_>>();
+        Some(match owning.as_slice() {
+            [only] => only.label(repo_root),
+            [first, ..] => JestProject::from(first.config.clone()).label(repo_root),
+            [] => return None,
+        })
+    }
+
+    fn cargo_package(&mut self, test: &Path) -> Option<String> {
+        let repo_root = self.repo_root;
+        self.cargo_workspace
+            .get_or_insert_with(|| crate::cargo_select::load_cargo_workspace(repo_root))
+            .as_ref()?
+            .owning_package(test)
+            .map(str::to_string)
+    }
+}
+
+fn relative_slash(repo_root: &Path, path: &Path) -> String {
+    crate::format::paths::strip_root(path, repo_root)
+        .unwrap_or_else(|| path.to_path_buf())
+        .to_slash_lossy()
+        .to_string()
+}
@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::PROTOCOL_VERSION;
+use crate::run::RunError;
+
+/// Where a repo keeps its plugin manifests, relative to the repo root.
+pub const PLUGIN_DIR: &str = ".headlamp/plugins";
+/// More manifest dirs, separated like `PATH`, searched after the repo's own.
+pub const PLUGIN_PATH_ENV: &str = "HEADLAMP_PLUGIN_PATH";
+/// Without a manifest, `plugin:<name>` runs `headlamp-runner-<name>` from `PATH`.
+pub const EXECUTABLE_PREFIX: &str = "headlamp-runner-";
+
+/// `<name>.json` in a plugin dir:
+/// `{ "name": "gradle", "protocol": 1, "command": ["./gradlew", "-q", "headlampTest"] }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub protocol: u32,
+    pub command: Vec<String>,
+    /// The manifest's directory; a relative program path in `command` resolves against it.
+    #[serde(skip)]
+    pub dir: Option<PathBuf>,
+}
+
+impl PluginManifest {
+    pub fn program(&self) -> PathBuf {
+        let program = PathBuf::from(&self.command[0]);
+        let is_path = self.command[0].contains(['/', '\\']);
+        match &self.dir {
+            Some(dir) if is_path && program.is_relative() => dir.join(program),
+            _ => program,
+        }
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.command[1..]
+    }
+}
+
+pub fn plugin_search_dirs(repo_root: &Path) -> Vec<PathBuf> {
+    std::iter::once(repo_root.join(PLUGIN_DIR))
+        .chain(
+            std::env::var_os(PLUGIN_PATH_ENV)
+                .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+                .unwrap_or_default(),
+        )
+        .collect()
+}
+
+/// The first `<name>.json` in the plugin dirs, else `headlamp-runner-<name>` on `PATH`.
+pub fn discover_plugin(repo_root: &Path, name: &str) -> Result<PluginManifest, RunError> {
+    let dirs = plugin_search_dirs(repo_root);
+    if let Some(path) = dirs
+        .iter()
+        .map(|dir| dir.join(format!("{name}.json")))
+        .find(|path| path.is_file())
+    {
+        return load_manifest(&path);
+    }
+    let executable = format!("{EXECUTABLE_PREFIX}{name}");
+    match which::which(&executable) {
+        Ok(path) => Ok(PluginManifest {
+            name: name.to_string(),
+            protocol: PROTOCOL_VERSION,
+            command: vec![path.to_string_lossy().to_string()],
+            dir: None,
+        }),
+        Err(_) => Err(RunError::MissingRunner {
+            runner: format!("plugin:{name}"),
+            hint: format!(
+                "no {name}.json in {} and no {executable} on PATH",
+                dirs.iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }),
+    }
+}
+
+pub fn load_manifest(path: &Path) -> Result<PluginManifest, RunError> {
+    let invalid = |message: String| RunError::CommandFailed {
+        message: format!("plugin manifest {}: {message}", path.display()),
+    };
+    let raw = std::fs::read_to_string(path).map_err(RunError::Io)?;
+    let mut manifest =
+        serde_json::from_str::<PluginManifest>(&raw).map_err(|err| invalid(err.to_string()))?;
+    if manifest.command.is_empty() {
+        return Err(invalid("`command` is empty".to_string()));
+    }
+    if manifest.protocol != PROTOCOL_VERSION {
+        return Err(invalid(format!(
+            "speaks protocol {}; this headlamp speaks {PROTOCOL_VERSION}",
+            manifest.protocol
+        )));
+    }
+    manifest.dir = path.parent().map(Path::to_path_buf);
+    Ok(manifest)
+}
@@ -0,0 +1,103 @@
+//! Custom runners as subprocesses: `--runner=plugin:<name>`.
+//!
+//! A plugin is found by its manifest (see `manifest`) and run from the repo root with
+//! `HEADLAMP_PLUGIN_PROTOCOL` and `HEADLAMP_PLUGIN_REQUEST` (a `PluginRequest` as JSON) set. It
+//! answers with NDJSON `PluginEvent`s: on the fd named by `HEADLAMP_EVENTS_FD` where that is set,
+//! else as lines on stdout. The first event is `{"event":"hello","protocol":1}`; after that
+//! `progress`/`print` drive the live output and `testCase`/`suite`/`run` build the run's
+//! `TestRunModel`, which headlamp renders like any other runner's. The plugin's exit code is the
+//! run's, except a zero exit with failed tests exits 1.
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::args::ParsedArgs;
+use crate::format::ctx::make_ctx_for_args;
+use crate::format::vitest::render_vitest_from_test_model;
+use crate::live_progress::{LiveProgress, live_progress_mode};
+use crate::run::RunError;
+use crate::streaming::run_streaming_capture_tail_with_events;
+
+pub mod manifest;
+pub mod protocol;
+
+pub use manifest::{PluginManifest, discover_plugin, load_manifest};
+pub use protocol::{PluginAdapter, PluginEvent, PluginRequest, parse_event};
+
+/// Bumped when an event or request field changes meaning; plugins must say the same in `hello`.
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const PROTOCOL_ENV: &str = "HEADLAMP_PLUGIN_PROTOCOL";
+pub const REQUEST_ENV: &str = "HEADLAMP_PLUGIN_REQUEST";
+
+pub fn run_plugin(
+    repo_root: &Path,
+    name: &str,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+) -> Result<i32, RunError> {
+    let started_at = std::time::Instant::now();
+    let started_at_unix_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    crate::run::bootstrap_and_wait(repo_root, args, session.env())?;
+    let manifest = discover_plugin(repo_root, name)?;
+    let command = plugin_command(repo_root, &manifest, args, session)?;
+    let label = format!("plugin:{name}");
+    let mode = live_progress_mode(
+        headlamp_core::format::terminal::is_output_terminal(),
+        args.ci,
+        args.quiet,
+    );
+    let live_progress = LiveProgress::start_with_args(1, mode, args);
+    live_progress.start_unit(0, &label);
+    let mut adapter = PluginAdapter::new(name, args.only_failures);
+    let (exit_code, _tail) =
+        run_streaming_capture_tail_with_events(command, &live_progress, &mut adapter, 1024 * 1024)?;
+    live_progress.finish_unit(0);
+    live_progress.finish();
+    if crate::print_commands::is_active() {
+        return Ok(0);
+    }
+    let mut model = adapter.finish(exit_code)?;
+    crate::pytest::apply_run_timing_to_model(
+        &mut model,
+        started_at_unix_ms,
+        started_at.elapsed().as_millis() as u64,
+    );
+    let failed =
+        model.aggregated.num_failed_tests > 0 || model.aggregated.num_failed_test_suites > 0;
+    let exit_code = if exit_code == 0 && failed {
+        1
+    } else {
+        exit_code
+    };
+    crate::recorded::record_failed_tests(&model);
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_test_names(&model));
+    let ctx = make_ctx_for_args(repo_root, args, exit_code != 0);
+    let rendered = render_vitest_from_test_model(&model, &ctx, args.only_failures);
+    (!rendered.trim().is_empty()).then(|| crate::format::report_out::println(&rendered));
+    Ok(exit_code)
+}
+
+fn plugin_command(
+    repo_root: &Path,
+    manifest: &PluginManifest,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+) -> Result<Command, RunError> {
+    let request =
+        serde_json::to_string(&PluginRequest::from_args(repo_root, args)).map_err(|err| {
+            RunError::CommandFailed {
+                message: format!("plugin request: {err}"),
+            }
+        })?;
+    let mut command = Command::new(manifest.program());
+    command
+        .args(manifest.args())
+        .current_dir(repo_root)
+        .env(PROTOCOL_ENV, PROTOCOL_VERSION.to_string())
+        .env(REQUEST_ENV, request);
+    session.env().apply(&mut command);
+    Ok(command)
+}
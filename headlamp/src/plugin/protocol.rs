@@ -0,0 +1,242 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::PROTOCOL_VERSION;
+use crate::args::ParsedArgs;
+use crate::live_progress::{outcome_from_status, render_finished_test_line};
+use crate::run::RunError;
+use crate::streaming::{OutputStream, StreamAction, StreamAdapter};
+use crate::test_model::{TestCaseResult, TestRunModel, TestSuiteResult};
+
+/// Lines of plugin output kept for the error when a plugin fails before its `hello`.
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// What headlamp asks of the plugin, as JSON in `REQUEST_ENV`. Test-name filters arrive in
+/// `runnerArgs` as `-t <regex>`, like jest's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRequest {
+    pub protocol: u32,
+    pub repo_root: String,
+    pub selection_paths: Vec<String>,
+    pub runner_args: Vec<String>,
+    pub collect_coverage: bool,
+    pub bail: bool,
+    pub ci: bool,
+    pub show_logs: bool,
+}
+
+impl PluginRequest {
+    pub fn from_args(repo_root: &Path, args: &ParsedArgs) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            repo_root: repo_root.to_string_lossy().to_string(),
+            selection_paths: args.selection_paths.clone(),
+            runner_args: args.runner_args.clone(),
+            collect_coverage: args.collect_coverage,
+            bail: args.bail,
+            ci: args.ci,
+            show_logs: args.show_logs,
+        }
+    }
+}
+
+/// One NDJSON line from a plugin, on the event pipe or (where there is none) on stdout. The
+/// events mirror `StreamAction` and the pieces of a `TestRunModel`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum PluginEvent {
+    /// Must come first: the protocol version the plugin speaks.
+    Hello { protocol: u32 },
+    /// `StreamAction::SetProgressLabel`.
+    Progress { label: String },
+    /// `StreamAction::PrintStdout`, or `PrintStderr` with `stderr: true`.
+    Print {
+        text: String,
+        #[serde(default)]
+        stderr: bool,
+    },
+    /// A finished test: recorded for interrupts and shown in the live output.
+    #[serde(rename_all = "camelCase")]
+    TestCase {
+        test_path: String,
+        result: TestCaseResult,
+    },
+    /// A finished file; replaces that file's `testCase` events in the model.
+    Suite { suite: TestSuiteResult },
+    /// The whole run; replaces every `suite` and `testCase` event.
+    Run { model: TestRunModel },
+}
+
+pub fn parse_event(line: &str) -> Option<PluginEvent> {
+    let line = line.trim();
+    line.starts_with('{')
+        .then(|| serde_json::from_str::<PluginEvent>(line).ok())?
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handshake {
+    Pending,
+    Done,
+    Failed,
+}
+
+pub struct PluginAdapter {
+    name: String,
+    only_failures: bool,
+    handshake: Handshake,
+    protocol_error: Option<String>,
+    cases: IndexMap<String, Vec<TestCaseResult>>,
+    suites: IndexMap<String, TestSuiteResult>,
+    model: Option<TestRunModel>,
+    output: Vec<String>,
+}
+
+impl PluginAdapter {
+    pub fn new(name: &str, only_failures: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            only_failures,
+            handshake: Handshake::Pending,
+            protocol_error: None,
+            cases: IndexMap::new(),
+            suites: IndexMap::new(),
+            model: None,
+            output: vec![],
+        }
+    }
+
+    fn fail_handshake(&mut self, message: String) -> Vec<StreamAction> {
+        self.handshake = Handshake::Failed;
+        self.protocol_error = Some(message);
+        vec![]
+    }
+
+    fn on_event(&mut self, event: PluginEvent) -> Vec<StreamAction> {
+        match (self.handshake, event) {
+            (Handshake::Failed, _) => vec![],
+            (Handshake::Pending, PluginEvent::Hello { protocol })
+                if protocol == PROTOCOL_VERSION =>
+            {
+                self.handshake = Handshake::Done;
+                vec![]
+            }
+            (Handshake::Pending, PluginEvent::Hello { protocol }) => self.fail_handshake(format!(
+                "speaks protocol {protocol}; this headlamp speaks {PROTOCOL_VERSION}"
+            )),
+            (Handshake::Pending, _) => {
+                self.fail_handshake("sent an event before its hello".to_string())
+            }
+            (Handshake::Done, PluginEvent::Hello { .. }) => vec![],
+            (Handshake::Done, PluginEvent::Progress { label }) => {
+                vec![StreamAction::SetProgressLabel(label)]
+            }
+            (
+                Handshake::Done,
+                PluginEvent::Print {
+                    text,
+                    stderr: false,
+                },
+            ) => {
+                vec![StreamAction::PrintStdout(text)]
+            }
+            (Handshake::Done, PluginEvent::Print { text, stderr: true }) => {
+                vec![StreamAction::PrintStderr(text)]
+            }
+            (Handshake::Done, PluginEvent::TestCase { test_path, result }) => {
+                self.on_test_case(test_path, result)
+            }
+            (Handshake::Done, PluginEvent::Suite { suite }) => {
+                self.suites.insert(suite.test_file_path.clone(), suite);
+                vec![]
+            }
+            (Handshake::Done, PluginEvent::Run { model }) => {
+                self.model = Some(model);
+                vec![]
+            }
+        }
+    }
+
+    fn on_test_case(&mut self, test_path: String, result: TestCaseResult) -> Vec<StreamAction> {
+        let outcome = outcome_from_status(&result.status);
+        let mut actions = vec![StreamAction::RecordOutcome(outcome)];
+        if !self.only_failures || result.status == "failed" {
+            let duration = std::time::Duration::from_millis(result.duration);
+            actions.push(StreamAction::PrintStdout(render_finished_test_line(
+                outcome,
+                Some(duration),
+                &test_path,
+                &result.full_name,
+            )));
+        }
+        self.cases.entry(test_path).or_default().push(result);
+        actions
+    }
+
+    /// The run's model: the plugin's `run` event if it sent one, else its suites plus a suite
+    /// per file that only had `testCase` events.
+    pub fn finish(self, exit_code: i32) -> Result<TestRunModel, RunError> {
+        let label = format!("plugin:{}", self.name);
+        if let Some(message) = self.protocol_error {
+            return Err(RunError::CommandFailed {
+                message: format!("{label} {message}"),
+            });
+        }
+        if self.handshake == Handshake::Pending {
+            let tail =
+                self.output[self.output.len().saturating_sub(OUTPUT_TAIL_LINES)..].join("\n");
+            return Err(RunError::CommandFailed {
+                message: format!("{label} exited with {exit_code} before its hello event\n{tail}"),
+            });
+        }
+        if let Some(model) = self.model {
+            return Ok(model);
+        }
+        let mut suites = self.suites;
+        for (test_path, cases) in self.cases {
+            suites
+                .entry(test_path.clone())
+                .or_insert_with(|| suite_from_cases(test_path, cases));
+        }
+        Ok(crate::rust_runner::build_run_model(
+            suites.into_values().collect(),
+            0,
+        ))
+    }
+}
+
+fn suite_from_cases(test_file_path: String, test_results: Vec<TestCaseResult>) -> TestSuiteResult {
+    let failed = test_results.iter().any(|case| case.status == "failed");
+    TestSuiteResult {
+        test_file_path,
+        status: if failed { "failed" } else { "passed" }.to_string(),
+        timed_out: None,
+        failure_message: String::new(),
+        failure_details: None,
+        test_exec_error: None,
+        console: None,
+        test_results,
+    }
+}
+
+impl StreamAdapter for PluginAdapter {
+    fn on_start(&mut self) -> Option<String> {
+        Some(format!("plugin:{}", self.name))
+    }
+
+    fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        let event = match stream {
+            OutputStream::Events | OutputStream::Stdout => parse_event(line),
+            OutputStream::Stderr => None,
+        };
+        match event {
+            Some(event) => self.on_event(event),
+            None => {
+                self.output.push(line.to_string());
+                vec![]
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `--print-commands`: discovery and selection run as usual, but every runner command is
+//! recorded instead of spawned, then printed. Discovery steps headlamp needs to plan the rest
+//! (e.g. `cargo test --no-run` to find the test binaries) still run and are listed too.
+
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintCommandsFormat {
+    Human,
+    Json,
+}
+
+/// One command as headlamp would run it. `env` holds only what headlamp sets on top of its own
+/// environment; `None` means the variable is removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    pub env: BTreeMap<String, Option<String>>,
+}
+
+/// `Some` while a `--print-commands` run is planning.
+static PLANNED: LazyLock<Mutex<Option<Vec<PlannedCommand>>>> = LazyLock::new(Mutex::default);
+
+pub fn is_active() -> bool {
+    PLANNED.lock().is_ok_and(|guard| guard.is_some())
+}
+
+pub fn planned_command(command: &Command) -> PlannedCommand {
+    let lossy = |text: &std::ffi::OsStr| text.to_string_lossy().to_string();
+    PlannedCommand {
+        program: lossy(command.get_program()),
+        args: command.get_args().map(lossy).collect(),
+        cwd: command.get_current_dir().map(|dir| lossy(dir.as_os_str())),
+        env: command
+            .get_envs()
+            .map(|(key, value)| (lossy(key), value.map(lossy)))
+            .collect(),
+    }
+}
+
+/// Records `command` while planning. Returns true when the caller must not spawn it.
+pub fn intercept(command: &Command) -> bool {
+    record(command)
+}
+
+/// Records a discovery command that runs even while planning.
+pub(crate) fn note(command: &Command) {
+    record(command);
+}
+
+fn record(command: &Command) -> bool {
+    let Ok(mut guard) = PLANNED.lock() else {
+        return false;
+    };
+    match guard.as_mut() {
+        Some(planned) => {
+            planned.push(planned_command(command));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Runs `plan` with planning on and prints what it would have run. Exits 0: nothing ran.
+pub fn plan_and_print(format: PrintCommandsFormat, plan: impl FnOnce()) -> i32 {
+    let planned = plan_commands(plan);
+    println!("{}", render_planned(&planned, format));
+    0
+}
+
+/// Runs `plan` with planning on and returns what it would have run.
+pub fn plan_commands(plan: impl FnOnce()) -> Vec<PlannedCommand> {
+    if let Ok(mut guard) = PLANNED.lock() {
+        *guard = Some(vec![]);
+    }
+    plan();
+    PLANNED
+        .lock()
+        .ok()
+        .and_then(|mut guard| guard.take())
+        .unwrap_or_default()
+}
+
+pub fn render_planned(planned: &[PlannedCommand], format: PrintCommandsFormat) -> String {
+    match format {
+        PrintCommandsFormat::Json => {
+            serde_json::to_string_pretty(&serde_json::json!({ "commands": planned }))
+                .unwrap_or_default()
+        }
+        PrintCommandsFormat::Human if planned.is_empty() => {
+            "headlamp: no runner commands would run".to_string()
+        }
+        PrintCommandsFormat::Human => planned
+            .iter()
+            .map(render_human)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn render_human(command: &PlannedCommand) -> String {
+    let quote = crate::repro::shell_quote;
+    let argv = std::iter::once(&command.program)
+        .chain(&command.args)
+        .map(|arg| quote(arg))
+        .collect::<Vec<_>>();
+    let mut lines = vec![format!("$ {}", argv.join(" "))];
+    if let Some(cwd) = &command.cwd {
+        lines.push(format!("    cwd: {cwd}"));
+    }
+    let env = command
+        .env
+        .iter()
+        .map(|(key, value)| match value {
+            Some(value) => format!("{key}={}", quote(value)),
+            None => format!("-{key}"),
+        })
+        .collect::<Vec<_>>();
+    if !env.is_empty() {
+        lines.push(format!("    env: {}", env.join(" ")));
+    }
+    lines.join("\n")
+}
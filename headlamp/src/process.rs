@@ -2,7 +2,16 @@ use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use crate::run::RunError;
-use wait_timeout::ChildExt;
+
+pub mod env;
+pub mod group;
+pub mod limits;
+pub mod usage;
+
+pub use env::RunEnvironment;
+pub use group::{ChildGroupGuard, isolate_process_group};
+pub use limits::{ProcessLimits, parse_memory_size, set_process_limits};
+pub use usage::{ChildUsage, ResourceUsage, RunnerResources, UsageScope};
 
 #[derive(Debug)]
 pub struct CapturedProcessOutput {
@@ -64,15 +73,22 @@ pub fn run_command_capture_with_timeout(
     timeout: Duration,
 ) -> Result<CapturedProcessOutput, RunError> {
     command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    isolate_process_group(&mut command);
     let mut child = command.spawn().map_err(RunError::SpawnFailed)?;
+    let group = ChildGroupGuard::register(&child);
+    let mut usage = ChildUsage::watch(&child);
 
     let stdout_receiver = spawn_capture_receiver(child.stdout.take());
     let stderr_receiver = spawn_capture_receiver(child.stderr.take());
 
-    let maybe_status = ChildExt::wait_timeout(&mut child, timeout).map_err(RunError::WaitFailed)?;
+    let maybe_status = usage
+        .wait_timeout(&mut child, timeout)
+        .map_err(RunError::WaitFailed)?;
+    crate::interrupt::park_if_interrupted();
+    group.kill_remaining();
     let Some(status) = maybe_status else {
         let _ = child.kill();
-        let _ = child.wait();
+        let _ = usage.wait(&mut child);
         let deadline = drain_after_exit_deadline(Instant::now());
         let _ = drain_receiver_until_deadline(stdout_receiver, deadline);
         let _ = drain_receiver_until_deadline(stderr_receiver, deadline);
@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{LazyLock, RwLock};
+
+use crate::args::ParsedArgs;
+use crate::run::RunError;
+
+static PUBLISHED: LazyLock<RwLock<Vec<(String, String)>>> = LazyLock::new(RwLock::default);
+
+/// The environment layered onto every runner subprocess, built once per headlamp invocation
+/// instead of mutating headlamp's own environment: `CI=1` for `--ci`, the shared
+/// `HEADLAMP_CACHE_DIR`, the `--shuffle` seed, then the `--env-file` / `--env` variables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunEnvironment {
+    pub ci: bool,
+    pub cache_dir: Option<PathBuf>,
+    /// Read by the jest sequencer and the pytest plugin to order test files.
+    pub shuffle_seed: Option<u64>,
+    pub vars: Vec<(String, String)>,
+}
+
+impl RunEnvironment {
+    /// Without `--keep-artifacts` the runners share the default cache dir unless the user already
+    /// chose one with `HEADLAMP_CACHE_DIR`.
+    pub fn from_args(base_dir: &Path, args: &ParsedArgs) -> Result<Self, RunError> {
+        let cache_dir = std::env::var_os("HEADLAMP_CACHE_DIR")
+            .map(PathBuf::from)
+            .or_else(|| (!args.keep_artifacts).then(crate::fast_related::default_cache_root));
+        Ok(Self {
+            ci: args.ci,
+            cache_dir,
+            shuffle_seed: args.shuffle_seed,
+            vars: load_runner_env(base_dir, args)?,
+        })
+    }
+
+    /// Where headlamp keeps what outlives a run (coverage cache, history): the runners' cache dir,
+    /// or the default one when `--keep-artifacts` left them without.
+    pub fn cache_root(&self) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .unwrap_or_else(crate::fast_related::default_cache_root)
+    }
+
+    /// `--ci`, or a CI system that exported `CI` to headlamp itself.
+    pub fn is_ci(&self) -> bool {
+        self.ci || std::env::var_os("CI").is_some()
+    }
+
+    /// Layers in order, so a user-supplied variable wins over the ones headlamp sets.
+    pub fn pairs(&self) -> Vec<(String, String)> {
+        let ci = self.ci.then(|| ("CI".to_string(), "1".to_string()));
+        let cache_dir = self.cache_dir.as_ref().map(|dir| {
+            (
+                "HEADLAMP_CACHE_DIR".to_string(),
+                dir.to_string_lossy().to_string(),
+            )
+        });
+        let shuffle_seed = self
+            .shuffle_seed
+            .map(|seed| (crate::shuffle::SEED_ENV.to_string(), seed.to_string()));
+        ci.into_iter()
+            .chain(cache_dir)
+            .chain(shuffle_seed)
+            .chain(self.vars.iter().cloned())
+            .collect()
+    }
+
+    /// Call after the builder's own `env` calls so the user's variables take precedence.
+    pub fn apply(&self, command: &mut Command) {
+        command.envs(self.pairs());
+    }
+
+    /// Makes these variables what in-process readers (`process.env.X` in an evaluated jest
+    /// config) see, matching what the runner will see. Each run publishes its own, replacing the
+    /// previous run's.
+    pub fn publish(&self) {
+        if let Ok(mut guard) = PUBLISHED.write() {
+            *guard = self.pairs();
+        }
+    }
+
+    pub fn apply_to_expression(&self, expression: duct::Expression) -> duct::Expression {
+        self.pairs()
+            .into_iter()
+            .fold(expression, |expression, (key, value)| {
+                expression.env(key, value)
+            })
+    }
+}
+
+/// A variable as a runner would see it: the published run environment, then headlamp's own.
+pub fn run_var(key: &str) -> Option<String> {
+    PUBLISHED
+        .read()
+        .ok()
+        .and_then(|pairs| {
+            pairs
+                .iter()
+                .rev()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.clone())
+        })
+        .or_else(|| std::env::var(key).ok())
+}
+
+/// Reads the `--env-file`s (relative to `base_dir`, in order) and then the `--env KEY=VALUE`
+/// entries; a later definition of a key replaces an earlier one.
+fn load_runner_env(base_dir: &Path, args: &ParsedArgs) -> Result<Vec<(String, String)>, RunError> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    let mut set = |key: String, value: String| match vars.iter_mut().find(|(k, _)| *k == key) {
+        Some(existing) => existing.1 = value,
+        None => vars.push((key, value)),
+    };
+    for file in &args.env_files {
+        let path = base_dir.join(file);
+        let text = std::fs::read_to_string(&path).map_err(|err| RunError::CommandFailed {
+            message: format!("failed to read --env-file {}: {err}", path.display()),
+        })?;
+        let parsed = parse_dotenv(&text).map_err(|line| RunError::CommandFailed {
+            message: format!("invalid line in {}: {line}", path.display()),
+        })?;
+        parsed.into_iter().for_each(|(key, value)| set(key, value));
+    }
+    for entry in &args.env_vars {
+        let (key, value) = parse_env_assignment(entry).ok_or_else(|| RunError::CommandFailed {
+            message: format!("invalid --env (expected KEY=VALUE): {entry}"),
+        })?;
+        set(key, value);
+    }
+    Ok(vars)
+}
+
+pub fn parse_env_assignment(entry: &str) -> Option<(String, String)> {
+    let (key, value) = entry.split_once('=')?;
+    let key = key.trim();
+    is_valid_key(key).then(|| (key.to_string(), value.to_string()))
+}
+
+/// dotenv syntax: `KEY=VALUE` lines with optional `export `, `#` comments, and single- or
+/// double-quoted values (double quotes understand `\n`, `\t`, `\"` and `\\`). No `${VAR}`
+/// expansion. Returns the offending line on error.
+pub fn parse_dotenv(text: &str) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let assignment = line.strip_prefix("export ").unwrap_or(line);
+            let (key, raw_value) = assignment
+                .split_once('=')
+                .filter(|(key, _)| is_valid_key(key.trim()))
+                .ok_or_else(|| line.to_string())?;
+            let value = dotenv_value(raw_value.trim()).ok_or_else(|| line.to_string())?;
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn dotenv_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.rfind('\'').map(|end| rest[..end].to_string());
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        return unescape_double_quoted(rest);
+    }
+    let value = raw.find(" #").map_or(raw, |comment| &raw[..comment]);
+    Some(value.trim_end().to_string())
+}
+
+fn unescape_double_quoted(rest: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                other => out.push(other),
+            },
+            other => out.push(other),
+        }
+    }
+    None
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
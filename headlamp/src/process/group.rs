@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+use std::process::{Child, Command};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+static CHILD_GROUPS: LazyLock<Mutex<BTreeSet<u32>>> = LazyLock::new(|| Mutex::new(BTreeSet::new()));
+
+/// Starts the child in its own process group (with the configured resource limits) so that
+/// teardown can signal every process it forks: jest workers, test binaries spawned by cargo,
+/// pytest-xdist workers, servers forked by the tests themselves.
+pub fn isolate_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    super::limits::apply_process_limits(command);
+}
+
+/// Keeps a spawned child's process group registered for interrupt teardown while alive.
+#[derive(Debug)]
+pub struct ChildGroupGuard {
+    pid: u32,
+}
+
+impl ChildGroupGuard {
+    pub fn register(child: &Child) -> Self {
+        let pid = child.id();
+        if let Ok(mut guard) = CHILD_GROUPS.lock() {
+            guard.insert(pid);
+        }
+        Self { pid }
+    }
+
+    /// Kills whatever is left in the group once the runner itself has exited or timed out.
+    pub fn kill_remaining(&self) {
+        signal_group(self.pid, GroupSignal::Kill);
+    }
+}
+
+impl Drop for ChildGroupGuard {
+    fn drop(&mut self) {
+        if let Ok(mut guard) = CHILD_GROUPS.lock() {
+            guard.remove(&self.pid);
+        }
+    }
+}
+
+/// Sends SIGTERM to every registered group, waits up to `grace` for them to exit, then SIGKILLs
+/// whatever is still around.
+pub fn terminate_registered_groups(grace: Duration) {
+    let groups = CHILD_GROUPS
+        .lock()
+        .map(|guard| guard.iter().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+    groups
+        .iter()
+        .for_each(|pid| signal_group(*pid, GroupSignal::Terminate));
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline && groups.iter().any(|pid| group_is_alive(*pid)) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    groups
+        .iter()
+        .for_each(|pid| signal_group(*pid, GroupSignal::Kill));
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GroupSignal {
+    Terminate,
+    Kill,
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: GroupSignal) {
+    let signal = match signal {
+        GroupSignal::Terminate => libc::SIGTERM,
+        GroupSignal::Kill => libc::SIGKILL,
+    };
+    if let Ok(pgid) = libc::pid_t::try_from(pid) {
+        unsafe { libc::killpg(pgid, signal) };
+    }
+}
+
+/// A group whose leader (the runner) has exited counts as done; the SIGKILL that follows takes
+/// any stragglers. `killpg` alone cannot tell, since it succeeds while the leader is an unreaped
+/// zombie.
+#[cfg(unix)]
+fn group_is_alive(pid: u32) -> bool {
+    let Ok(pgid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    match leader_has_exited(pgid) {
+        Some(exited) => !exited,
+        None => (unsafe { libc::killpg(pgid, 0) }) == 0,
+    }
+}
+
+/// Peeks at the leader without reaping it, since its owner still waits on it; `None` when it is
+/// not ours to wait on (already reaped).
+#[cfg(unix)]
+fn leader_has_exited(pid: libc::pid_t) -> Option<bool> {
+    let id = libc::id_t::try_from(pid).ok()?;
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let flags = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+    if unsafe { libc::waitid(libc::P_PID, id, &mut info, flags) } != 0 {
+        return None;
+    }
+    Some(unsafe { info.si_pid() } != 0)
+}
+
+// Windows delivers console control events to every process attached to the console, so the
+// runner tree already receives Ctrl-C on its own.
+#[cfg(not(unix))]
+fn signal_group(_pid: u32, _signal: GroupSignal) {}
+
+#[cfg(not(unix))]
+fn group_is_alive(_pid: u32) -> bool {
+    false
+}
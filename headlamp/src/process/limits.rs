@@ -0,0 +1,101 @@
+use std::process::Command;
+use std::sync::{LazyLock, RwLock};
+
+use crate::args::ParsedArgs;
+
+static PROCESS_LIMITS: LazyLock<RwLock<ProcessLimits>> =
+    LazyLock::new(|| RwLock::new(ProcessLimits::default()));
+
+/// Resource limits applied to every runner subprocess (unix only; ignored elsewhere).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessLimits {
+    /// Per-process cap on the data segment (`RLIMIT_DATA`).
+    pub max_memory_bytes: Option<u64>,
+    /// Niceness increment applied in the child before exec.
+    pub nice: Option<u32>,
+}
+
+impl ProcessLimits {
+    pub fn from_args(args: &ParsedArgs) -> Self {
+        Self {
+            max_memory_bytes: args.max_memory_bytes,
+            nice: args.nice,
+        }
+    }
+}
+
+pub fn set_process_limits(limits: ProcessLimits) {
+    if let Ok(mut guard) = PROCESS_LIMITS.write() {
+        *guard = limits;
+    }
+}
+
+fn current_process_limits() -> ProcessLimits {
+    PROCESS_LIMITS
+        .read()
+        .map(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// Parses sizes like `512M`, `2G`, `1.5GiB` or a plain byte count (binary multiples).
+pub fn parse_memory_size(raw: &str) -> Option<u64> {
+    let trimmed = raw.trim();
+    let split_at = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split_at);
+    let value = number.parse::<f64>().ok().filter(|v| *v > 0.0)?;
+    let shift = match unit
+        .trim()
+        .trim_end_matches(['b', 'B'])
+        .trim_end_matches('i')
+        .to_ascii_uppercase()
+        .as_str()
+    {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => return None,
+    };
+    Some((value * (1u64 << shift) as f64) as u64)
+}
+
+pub(super) fn apply_process_limits(command: &mut Command) {
+    let limits = current_process_limits();
+    if limits == ProcessLimits::default() {
+        return;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: the closure runs between fork and exec and only calls async-signal-safe libc
+        // functions on values captured by copy.
+        unsafe {
+            command.pre_exec(move || {
+                apply_limits_in_child(limits);
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = command;
+    }
+}
+
+#[cfg(unix)]
+fn apply_limits_in_child(limits: ProcessLimits) {
+    if let Some(bytes) = limits.max_memory_bytes {
+        let bytes = libc::rlim_t::try_from(bytes).unwrap_or(libc::RLIM_INFINITY);
+        let rlimit = libc::rlimit {
+            rlim_cur: bytes,
+            rlim_max: bytes,
+        };
+        unsafe { libc::setrlimit(libc::RLIMIT_DATA, &rlimit) };
+    }
+    if let Some(increment) = limits.nice {
+        unsafe { libc::nice(libc::c_int::try_from(increment.min(19)).unwrap_or(19)) };
+    }
+}
@@ -0,0 +1,310 @@
+use std::io;
+use std::process::{Child, ExitStatus};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// What one runner invocation cost, for `--resource-report`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    /// Peak resident set of the largest single process.
+    pub max_rss_bytes: u64,
+    pub user_cpu_ms: u64,
+    pub system_cpu_ms: u64,
+    /// Runner processes headlamp started and reaped; on Windows also everything they spawned.
+    pub processes: u64,
+}
+
+impl ResourceUsage {
+    fn merge(&mut self, other: ResourceUsage) {
+        self.max_rss_bytes = self.max_rss_bytes.max(other.max_rss_bytes);
+        self.user_cpu_ms += other.user_cpu_ms;
+        self.system_cpu_ms += other.system_cpu_ms;
+        self.processes += other.processes;
+    }
+}
+
+/// One runner invocation's usage, as `--resource-report` and `api::RunOutcome` list it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerResources {
+    pub runner: String,
+    pub root: String,
+    #[serde(flatten)]
+    pub usage: ResourceUsage,
+}
+
+/// Usage of the runner processes reaped through `ChildUsage` since the scope began.
+static REAPED: LazyLock<Mutex<ResourceUsage>> = LazyLock::new(Mutex::default);
+
+fn record_reaped(usage: ResourceUsage) {
+    if let Ok(mut guard) = REAPED.lock() {
+        guard.merge(usage);
+    }
+}
+
+/// Measures one runner invocation. On unix, CPU time comes from `getrusage(RUSAGE_CHILDREN)`, so
+/// it covers every process reaped meanwhile (builds, discovery, test binaries); peaks and process
+/// counts come from the runner processes themselves. Reused jest workers outlive the invocation
+/// and are not counted.
+#[derive(Debug)]
+pub struct UsageScope {
+    #[cfg(unix)]
+    before: ResourceUsage,
+}
+
+impl UsageScope {
+    pub fn begin() -> Self {
+        if let Ok(mut guard) = REAPED.lock() {
+            *guard = ResourceUsage::default();
+        }
+        Self {
+            #[cfg(unix)]
+            before: unix::children_usage(),
+        }
+    }
+
+    pub fn finish(self) -> ResourceUsage {
+        let reaped = REAPED.lock().map(|guard| *guard).unwrap_or_default();
+        #[cfg(unix)]
+        {
+            let after = unix::children_usage();
+            // The children's peak only says something about this scope when it grew during it.
+            let grown_peak = if after.max_rss_bytes > self.before.max_rss_bytes {
+                after.max_rss_bytes
+            } else {
+                0
+            };
+            ResourceUsage {
+                max_rss_bytes: reaped.max_rss_bytes.max(grown_peak),
+                user_cpu_ms: after.user_cpu_ms.saturating_sub(self.before.user_cpu_ms),
+                system_cpu_ms: after
+                    .system_cpu_ms
+                    .saturating_sub(self.before.system_cpu_ms),
+                processes: reaped.processes,
+            }
+        }
+        #[cfg(not(unix))]
+        reaped
+    }
+}
+
+/// Waits on a runner child while collecting what it used: unix reaps it with `wait4`, the only
+/// call that reports a single child's usage; Windows reads the Job Object it was placed in.
+#[derive(Debug)]
+pub struct ChildUsage {
+    status: Option<ExitStatus>,
+    #[cfg(windows)]
+    job: Option<windows::Job>,
+}
+
+impl ChildUsage {
+    pub fn watch(child: &Child) -> Self {
+        #[cfg(not(windows))]
+        let _ = child;
+        Self {
+            status: None,
+            #[cfg(windows)]
+            job: windows::Job::assign(child),
+        }
+    }
+
+    pub fn try_wait(&mut self, child: &mut Child) -> io::Result<Option<ExitStatus>> {
+        if self.status.is_none() {
+            self.status = self.reap(child, false)?;
+        }
+        Ok(self.status)
+    }
+
+    pub fn wait(&mut self, child: &mut Child) -> io::Result<ExitStatus> {
+        loop {
+            if let Some(status) = self.status {
+                return Ok(status);
+            }
+            self.status = self.reap(child, true)?;
+        }
+    }
+
+    /// `try_wait`, polling until `timeout`; `None` when the child is still running.
+    pub fn wait_timeout(
+        &mut self,
+        child: &mut Child,
+        timeout: Duration,
+    ) -> io::Result<Option<ExitStatus>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = self.try_wait(child)? {
+                return Ok(Some(status));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            std::thread::sleep(remaining.min(Duration::from_millis(10)));
+        }
+    }
+
+    #[cfg(unix)]
+    fn reap(&mut self, child: &mut Child, block: bool) -> io::Result<Option<ExitStatus>> {
+        let reaped = unix::wait4(child.id(), block)?;
+        if let Some((_, usage)) = reaped {
+            record_reaped(usage);
+        }
+        Ok(reaped.map(|(status, _)| status))
+    }
+
+    #[cfg(not(unix))]
+    fn reap(&mut self, child: &mut Child, block: bool) -> io::Result<Option<ExitStatus>> {
+        let status = if block {
+            Some(child.wait()?)
+        } else {
+            child.try_wait()?
+        };
+        if status.is_some() {
+            #[cfg(windows)]
+            if let Some(job) = self.job.take() {
+                record_reaped(job.usage());
+            }
+            #[cfg(not(windows))]
+            record_reaped(ResourceUsage {
+                processes: 1,
+                ..ResourceUsage::default()
+            });
+        }
+        Ok(status)
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    use super::ResourceUsage;
+
+    fn from_rusage(rusage: &libc::rusage, processes: u64) -> ResourceUsage {
+        let millis = |time: libc::timeval| {
+            (time.tv_sec.max(0) as u64) * 1000 + (time.tv_usec.max(0) as u64) / 1000
+        };
+        // Linux reports kilobytes, macOS bytes.
+        let rss_unit = if cfg!(target_os = "macos") { 1 } else { 1024 };
+        ResourceUsage {
+            max_rss_bytes: (rusage.ru_maxrss.max(0) as u64) * rss_unit,
+            user_cpu_ms: millis(rusage.ru_utime),
+            system_cpu_ms: millis(rusage.ru_stime),
+            processes,
+        }
+    }
+
+    pub(super) fn children_usage() -> ResourceUsage {
+        let mut rusage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut rusage) } != 0 {
+            return ResourceUsage::default();
+        }
+        from_rusage(&rusage, 0)
+    }
+
+    /// Reaps `pid`; `None` while it is still running and `block` is false.
+    pub(super) fn wait4(pid: u32, block: bool) -> io::Result<Option<(ExitStatus, ResourceUsage)>> {
+        let pid = libc::pid_t::try_from(pid).map_err(io::Error::other)?;
+        let options = if block { 0 } else { libc::WNOHANG };
+        loop {
+            let mut status = 0;
+            let mut rusage = unsafe { std::mem::zeroed::<libc::rusage>() };
+            let reaped = unsafe { libc::wait4(pid, &mut status, options, &mut rusage) };
+            match reaped {
+                0 => return Ok(None),
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err);
+                    }
+                }
+                _ => {
+                    let usage = from_rusage(&rusage, 1);
+                    return Ok(Some((ExitStatus::from_raw(status), usage)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectBasicAccountingInformation,
+        JobObjectExtendedLimitInformation, QueryInformationJobObject,
+    };
+
+    use super::ResourceUsage;
+
+    /// An unnamed job without limits: closing it leaves the processes alone.
+    #[derive(Debug)]
+    pub(super) struct Job(HANDLE);
+
+    // The handle is only used through the owning `ChildUsage`.
+    unsafe impl Send for Job {}
+
+    impl Job {
+        /// Processes the child started before this call stay outside the job.
+        pub(super) fn assign(child: &Child) -> Option<Self> {
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return None;
+            }
+            let job = Job(handle);
+            let process = child.as_raw_handle() as HANDLE;
+            (unsafe { AssignProcessToJobObject(job.0, process) } != 0).then_some(job)
+        }
+
+        pub(super) fn usage(&self) -> ResourceUsage {
+            let mut accounting =
+                unsafe { std::mem::zeroed::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() };
+            let mut limits = unsafe { std::mem::zeroed::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() };
+            let queried = unsafe {
+                QueryInformationJobObject(
+                    self.0,
+                    JobObjectBasicAccountingInformation,
+                    (&mut accounting as *mut JOBOBJECT_BASIC_ACCOUNTING_INFORMATION).cast(),
+                    size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                    std::ptr::null_mut(),
+                ) != 0
+                    && QueryInformationJobObject(
+                        self.0,
+                        JobObjectExtendedLimitInformation,
+                        (&mut limits as *mut JOBOBJECT_EXTENDED_LIMIT_INFORMATION).cast(),
+                        size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                        std::ptr::null_mut(),
+                    ) != 0
+            };
+            if !queried {
+                return ResourceUsage {
+                    processes: 1,
+                    ..ResourceUsage::default()
+                };
+            }
+            // Job times count 100ns intervals.
+            let millis = |time: i64| (time.max(0) as u64) / 10_000;
+            ResourceUsage {
+                max_rss_bytes: limits.PeakProcessMemoryUsed as u64,
+                user_cpu_ms: millis(accounting.TotalUserTime),
+                system_cpu_ms: millis(accounting.TotalKernelTime),
+                processes: u64::from(accounting.TotalProcesses),
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+}
@@ -28,12 +28,8 @@ impl Drop for ProfileSpan {
         if !enabled() {
             return;
         }
-        let elapsed = self.start.elapsed();
-        let pretty_elapsed = format_duration(elapsed);
-        eprintln!(
-            "[headlamp-profile] {name} took {pretty_elapsed}",
-            name = self.name
-        );
+        let elapsed = format_duration(self.start.elapsed());
+        tracing::info!(target: "profile", "{name} took {elapsed}", name = self.name);
     }
 }
 
@@ -13,11 +13,13 @@ pub struct TsJsManifestClassifier {
 }
 
 impl TsJsManifestClassifier {
+    /// Reads jest's test-matching options from the project's jest config file, or from the
+    /// `jest` key of `package.json` when there is no config file.
     pub fn read_from(project_root: PathBuf) -> Option<Self> {
-        let package_json = project_root.join("package.json");
-        let raw = std::fs::read_to_string(&package_json).ok()?;
-        let value = serde_json::from_str::<serde_json::Value>(&raw).ok()?;
-        let jest = value.get("jest")?;
+        let jest = crate::jest_config::list_all_jest_configs(&project_root)
+            .first()
+            .and_then(|config| crate::jest_config::load_jest_config_value(config))
+            .or_else(|| read_package_json_jest(&project_root))?;
 
         let test_match = jest
             .get("testMatch")
@@ -72,6 +74,12 @@ impl TsJsManifestClassifier {
     }
 }
 
+fn read_package_json_jest(project_root: &Path) -> Option<serde_json::Value> {
+    let raw = std::fs::read_to_string(project_root.join("package.json")).ok()?;
+    let mut value = serde_json::from_str::<serde_json::Value>(&raw).ok()?;
+    value.get_mut("jest").map(serde_json::Value::take)
+}
+
 fn as_string_vec(value: &serde_json::Value) -> Option<Vec<String>> {
     value.as_array().map(|arr| {
         arr.iter()
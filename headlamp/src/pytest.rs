@@ -2,24 +2,30 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use headlamp_core::args::ParsedArgs;
-use headlamp_core::format::ctx::make_ctx;
+use headlamp_core::format::ctx::make_ctx_for_args;
 use headlamp_core::format::vitest::render_vitest_from_test_model;
 use headlamp_core::test_model::{TestLocation, TestRunModel};
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::git::changed_files;
 use crate::live_progress;
 use crate::process::run_command_capture_with_timeout;
-use crate::pytest_select::{changed_seeds, discover_pytest_test_files, filter_tests_by_seeds};
-use crate::run::{RunError, run_bootstrap};
+use crate::run::RunError;
 use crate::streaming::StreamAdapter;
 
 const PYTEST_PLUGIN_BYTES: &[u8] = include_bytes!("../assets/pytest/headlamp_pytest_plugin.py");
 
 mod adapter;
+#[cfg(test)]
+mod adapter_test;
 pub(crate) mod coverage;
+pub(crate) mod coveragerc;
+pub mod django;
+mod runner;
+pub(crate) mod selection;
+pub(crate) mod unittest;
 use adapter::PytestAdapter;
+pub(crate) use runner::PythonTestRunner;
 
 pub fn run_pytest(
     repo_root: &Path,
@@ -31,60 +37,93 @@ pub fn run_pytest(
         .duration_since(std::time::UNIX_EPOCH)
         .map(|duration| duration.as_millis() as u64)
         .unwrap_or(0);
-    run_bootstrap_if_configured(repo_root, args)?;
-    let selected = resolve_pytest_selection(repo_root, args)?;
-    let pytest_bin = pytest_bin();
+    crate::run::bootstrap_and_wait(repo_root, args, session.env())?;
+    let interpreter = crate::python_env::resolve_python(repo_root, args.python.as_deref());
+    let runner = PythonTestRunner::detect(interpreter.as_ref());
+    let python_source = interpreter.map(|found| format!("{:?}", found.source));
+    let args = &args_for_runner(args, &runner);
+    let selected = selection::resolve_pytest_selection(repo_root, args, session.env(), &runner)?;
+    tracing::debug!(target: "selection", selected = ?selected, "pytest selection");
+    let pytest_bin = runner.program();
     let (_tmp, pythonpath) = setup_pytest_plugin(repo_root, session)?;
-    let cmd_args = build_pytest_cmd_args(args, session, &selected);
-    if args.collect_coverage {
-        coverage::ensure_cov_report_output_directories(repo_root, &cmd_args)?;
-    }
-    let (exit_code, mut model) =
-        run_pytest_streaming(repo_root, args, session, pytest_bin, cmd_args, pythonpath)?;
+    let django = django::detect_django(repo_root);
+    let mut cmd_args = build_runner_cmd_args(args, session, &runner, django.as_ref(), &selected)?;
+    prepare_coverage(repo_root, args, session, &mut cmd_args)?;
+    let (exit_code, mut model) = run_pytest_streaming(
+        repo_root,
+        args,
+        session,
+        &runner,
+        django.as_ref(),
+        cmd_args,
+        pythonpath,
+    )?;
     apply_run_timing_to_model(
         &mut model,
         started_at_unix_ms,
         started_at.elapsed().as_millis() as u64,
     );
     maybe_print_rendered_pytest_run(repo_root, args, exit_code, &model);
+    let trace_fields = |exit_code: i32, coverage_aborted: bool| {
+        serde_json::json!({
+            "pytest_bin": pytest_bin,
+            "python_source": python_source,
+            "selected_count": selected.len(),
+            "exit_code": exit_code,
+            "coverage_aborted": coverage_aborted,
+        })
+    };
     if args.coverage_abort_on_failure && exit_code != 0 {
         headlamp_core::diagnostics_trace::maybe_write_run_trace(
             repo_root,
             "pytest",
             args,
             Some(started_at),
-            serde_json::json!({
-                "pytest_bin": pytest_bin,
-                "selected_count": selected.len(),
-                "exit_code": exit_code,
-                "coverage_aborted": true,
-            }),
+            trace_fields(exit_code, true),
         );
         return Ok(exit_code);
     }
-    let final_exit = coverage::maybe_collect_pytest_coverage(repo_root, args, session, exit_code)?;
+    let final_exit = coverage::maybe_collect_pytest_coverage(
+        repo_root,
+        args,
+        session,
+        runner.python(),
+        exit_code,
+    )?;
     headlamp_core::diagnostics_trace::maybe_write_run_trace(
         repo_root,
         "pytest",
         args,
         Some(started_at),
-        serde_json::json!({
-            "pytest_bin": pytest_bin,
-            "selected_count": selected.len(),
-            "exit_code": final_exit,
-            "coverage_aborted": false,
-        }),
+        trace_fields(final_exit, false),
     );
     Ok(final_exit)
 }
 
-fn run_bootstrap_if_configured(repo_root: &Path, args: &ParsedArgs) -> Result<(), RunError> {
-    args.bootstrap_command
-        .as_ref()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|cmd| run_bootstrap(repo_root, cmd))
-        .unwrap_or(Ok(()))
+/// pytest-cov's report directories, and a generated `.coveragerc` for repos without one.
+fn prepare_coverage(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    cmd_args: &mut Vec<String>,
+) -> Result<(), RunError> {
+    if args.collect_coverage {
+        coverage::ensure_cov_report_output_directories(repo_root, cmd_args)?;
+        cmd_args.extend(coveragerc::cov_config_args(repo_root, args, session)?);
+    }
+    Ok(())
+}
+
+/// Coverage rides on pytest-cov, so the unittest fallback runs without it.
+fn args_for_runner(args: &ParsedArgs, runner: &PythonTestRunner) -> ParsedArgs {
+    if !(runner.is_unittest() && args.collect_coverage) {
+        return args.clone();
+    }
+    eprintln!("headlamp: pytest not found; running unittest discovery without coverage");
+    ParsedArgs {
+        collect_coverage: false,
+        ..args.clone()
+    }
 }
 
 fn pytest_bin() -> &'static str {
@@ -97,6 +136,7 @@ fn setup_pytest_plugin(
 ) -> Result<(PathBuf, String), RunError> {
     let tmp = session.subdir("pytest");
     let _plugin_path = write_asset(&tmp.join("headlamp_pytest_plugin.py"), PYTEST_PLUGIN_BYTES)?;
+    let _runner_path = unittest::write_unittest_runner(&tmp)?;
     let pythonpath = crate::pythonpath::build_pytest_pythonpath(
         repo_root,
         &[tmp.as_path()],
@@ -105,6 +145,24 @@ fn setup_pytest_plugin(
     Ok((tmp, pythonpath))
 }
 
+fn build_runner_cmd_args(
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    runner: &PythonTestRunner,
+    django: Option<&django::DjangoProject>,
+    selected: &[String],
+) -> Result<Vec<String>, RunError> {
+    let tool_args = match runner {
+        PythonTestRunner::Pytest { .. } => [
+            django.map(|d| d.plugin_args()).unwrap_or_default(),
+            build_pytest_cmd_args(args, session, selected),
+        ]
+        .concat(),
+        PythonTestRunner::Unittest { .. } => unittest::tool_args(args, selected)?,
+    };
+    Ok([runner.module_args(), tool_args].concat())
+}
+
 pub(crate) fn build_pytest_cmd_args(
     args: &ParsedArgs,
     session: &crate::session::RunSession,
@@ -123,11 +181,19 @@ pub(crate) fn build_pytest_cmd_args(
         cmd_args.push("-p".to_string());
         cmd_args.push("pytest_cov".to_string());
     }
-    if !args.keep_artifacts {
+    // `--shuffle` also drops the cache so `--failed-first` / `--stepwise` can't reorder the run.
+    if !args.keep_artifacts || args.shuffle_seed.is_some() {
         cmd_args.push("-p".to_string());
         cmd_args.push("no:cacheprovider".to_string());
     }
     cmd_args.extend(rewrite_pytest_runner_args_for_no_artifacts(args, session));
+    if let Some(seed) = args.shuffle_seed {
+        cmd_args.extend(pytest_randomly_seed_args(&args.runner_args, seed));
+    }
+    let stops_early = |t: &String| t == "-x" || t == "--exitfirst" || t.starts_with("--maxfail");
+    if args.bail && !cmd_args.iter().any(stops_early) {
+        cmd_args.push("-x".to_string());
+    }
     cmd_args.extend(selected.iter().cloned());
     let has_cov = args.runner_args.iter().any(|a| a.starts_with("--cov"));
     if args.collect_coverage {
@@ -136,8 +202,10 @@ pub(crate) fn build_pytest_cmd_args(
             || cmd_args
                 .windows(2)
                 .any(|w| w[0] == "--cov-report" && w[1].starts_with("lcov:"));
-        if !has_cov {
+        if !has_cov && args.coverage_scope.is_empty() {
             cmd_args.push("--cov=.".to_string());
+        } else if !has_cov {
+            cmd_args.extend(args.coverage_scope.iter().map(|dir| format!("--cov={dir}")));
         }
         if !has_cov_branch {
             cmd_args.push("--cov-branch".to_string());
@@ -150,37 +218,81 @@ pub(crate) fn build_pytest_cmd_args(
     cmd_args
 }
 
+/// Plugin autoload is off, so pytest-randomly only runs when the user loads it (`-p randomly`);
+/// then it does the shuffling, with `--shuffle`'s seed unless one was given.
+fn pytest_randomly_seed_args(runner_args: &[String], seed: u64) -> Vec<String> {
+    let loads_randomly = runner_args
+        .windows(2)
+        .any(|w| w[0] == "-p" && matches!(w[1].as_str(), "randomly" | "pytest_randomly"))
+        || runner_args
+            .iter()
+            .any(|t| t == "-prandomly" || t == "-ppytest_randomly");
+    let has_seed = runner_args
+        .iter()
+        .any(|t| t == "--randomly-seed" || t.starts_with("--randomly-seed="));
+    if loads_randomly && !has_seed {
+        vec![format!("--randomly-seed={seed}")]
+    } else {
+        vec![]
+    }
+}
+
+/// Without `--keep-artifacts`, coverage data goes to the session and no `.pyc` files are left
+/// behind in the repo.
+fn apply_artifact_env(
+    command: &mut Command,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+) {
+    if args.keep_artifacts {
+        return;
+    }
+    if args.collect_coverage {
+        let coverage_data_path = coverage::pytest_coverage_data_path(session);
+        command.env("COVERAGE_FILE", coverage_data_path.as_os_str());
+    }
+    command.env("PYTHONDONTWRITEBYTECODE", "1");
+}
+
 fn run_pytest_streaming(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
-    pytest_bin: &str,
+    runner: &PythonTestRunner,
+    django: Option<&django::DjangoProject>,
     cmd_args: Vec<String>,
     pythonpath: String,
 ) -> Result<(i32, TestRunModel), RunError> {
+    let (program, cmd_args) = match args.debug {
+        Some(debugger) => crate::debug::python_invocation(debugger, runner, cmd_args),
+        None => (runner.program(), cmd_args),
+    };
+    let command = pytest_command(
+        repo_root, args, session, django, &program, cmd_args, pythonpath,
+    );
+    if args.debug.is_some() {
+        let exit_code = crate::debug::run_attached(command)?;
+        let adapter = PytestAdapter::new(args.show_logs, args.ci, args.only_failures);
+        return Ok((exit_code, adapter.finalize(exit_code)));
+    }
+    capture_pytest_run(repo_root, args, runner, command)
+}
+
+fn capture_pytest_run(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    runner: &PythonTestRunner,
+    command: Command,
+) -> Result<(i32, TestRunModel), RunError> {
+    let pytest_bin = command.get_program().to_string_lossy().to_string();
     let mode = live_progress::live_progress_mode(
         headlamp_core::format::terminal::is_output_terminal(),
         args.ci,
         args.quiet,
     );
-    let live_progress = live_progress::LiveProgress::start(1, mode);
-    let mut command = Command::new(pytest_bin);
-    command
-        .args(cmd_args)
-        .current_dir(repo_root)
-        .env("CI", "1")
-        // Make pytest runs hermetic and fast by disabling auto-loading of user-installed plugins.
-        // This avoids hangs/slowdowns caused by globally installed plugins (common on dev machines).
-        .env("PYTEST_DISABLE_PLUGIN_AUTOLOAD", "1")
-        .env("PYTHONNOUSERSITE", "1")
-        .env("PYTHONPATH", pythonpath);
-    if args.collect_coverage && !args.keep_artifacts {
-        let coverage_data_path = coverage::pytest_coverage_data_path(session);
-        command.env("COVERAGE_FILE", coverage_data_path.as_os_str());
-    }
-    if !args.keep_artifacts {
-        command.env("PYTHONDONTWRITEBYTECODE", "1");
-    }
+    let live_progress = live_progress::LiveProgress::start_with_args(1, mode, args);
+    live_progress.load_unit_timings(repo_root);
+    live_progress.start_unit(0, runner.label());
     // IMPORTANT: Use capture-with-timeout to prevent hangs. We still parse output lines using the
     // same adapter, but we avoid long-lived pipe reader threads that can deadlock if a pipe never
     // reaches EOF due to unexpected FD inheritance.
@@ -193,6 +305,7 @@ fn run_pytest_streaming(
             .collect::<Vec<_>>()
             .join(" ")
     );
+    tracing::debug!(target: "pytest", command = %display_command, "running pytest");
     let mut adapter = PytestAdapter::new(args.show_logs, args.ci, args.only_failures);
     if let Some(label) = adapter.on_start() {
         live_progress.set_current_label(label);
@@ -217,31 +330,50 @@ fn run_pytest_streaming(
         crate::streaming::OutputStream::Stderr,
         &stderr_text,
     );
-    live_progress.increment_done(1);
+    live_progress.finish_unit(0);
     live_progress.finish();
     Ok((exit_code, adapter.finalize(exit_code)))
 }
 
+fn pytest_command(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    django: Option<&django::DjangoProject>,
+    program: &str,
+    cmd_args: Vec<String>,
+    pythonpath: String,
+) -> Command {
+    let mut command = Command::new(program);
+    command
+        .args(cmd_args)
+        .current_dir(repo_root)
+        .env("CI", "1")
+        // Make pytest runs hermetic and fast by disabling auto-loading of user-installed plugins.
+        // This avoids hangs/slowdowns caused by globally installed plugins (common on dev machines).
+        .env("PYTEST_DISABLE_PLUGIN_AUTOLOAD", "1")
+        .env("PYTHONNOUSERSITE", "1")
+        .env("PYTHONPATH", pythonpath);
+    apply_artifact_env(&mut command, args, session);
+    if let Some(django) = django {
+        django.apply_env(&mut command);
+    }
+    session.env().apply(&mut command);
+    command
+}
+
 fn apply_pytest_output_text(
     adapter: &mut PytestAdapter,
     live_progress: &live_progress::LiveProgress,
     stream: crate::streaming::OutputStream,
     text: &str,
 ) {
-    text.lines()
+    let actions = text
+        .lines()
         .map(|line| line.strip_suffix('\r').unwrap_or(line))
         .flat_map(|line| adapter.on_line(stream, line))
-        .for_each(|action| match action {
-            crate::streaming::StreamAction::SetProgressLabel(label) => {
-                live_progress.set_current_label(label)
-            }
-            crate::streaming::StreamAction::PrintStdout(text) => {
-                live_progress.println_stdout(&text)
-            }
-            crate::streaming::StreamAction::PrintStderr(text) => {
-                live_progress.eprintln_stderr(&text)
-            }
-        });
+        .collect::<Vec<_>>();
+    crate::streaming::apply_actions(live_progress, actions);
 }
 
 fn maybe_print_rendered_pytest_run(
@@ -250,15 +382,15 @@ fn maybe_print_rendered_pytest_run(
     exit_code: i32,
     model: &TestRunModel,
 ) {
-    let ctx = make_ctx(
-        repo_root,
-        None,
-        exit_code != 0,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
+    // A debug session ran on the terminal; there is nothing to render.
+    if args.debug.is_some() {
+        return;
+    }
+    crate::recorded::record_failed_tests(model);
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_test_names(model));
+    let ctx = make_ctx_for_args(repo_root, args, exit_code != 0);
     let rendered = render_vitest_from_test_model(model, &ctx, args.only_failures);
-    (!rendered.trim().is_empty()).then(|| println!("{rendered}"));
+    (!rendered.trim().is_empty()).then(|| crate::format::report_out::println(&rendered));
 }
 
 pub(crate) fn apply_run_timing_to_model(
@@ -342,70 +474,3 @@ fn rewrite_pytest_runner_args_for_no_artifacts(
     }
     rewritten
 }
-
-fn resolve_pytest_selection(repo_root: &Path, args: &ParsedArgs) -> Result<Vec<String>, RunError> {
-    let changed = args
-        .changed
-        .map(|m| changed_files(repo_root, m))
-        .transpose()?
-        .unwrap_or_default();
-
-    let all_tests = discover_pytest_test_files(repo_root, args.no_cache)?;
-    let all_tests_set = all_tests
-        .iter()
-        .map(|p| p.to_string_lossy().to_string())
-        .collect::<std::collections::BTreeSet<_>>();
-
-    let (explicit, seed_inputs) = args
-        .selection_paths
-        .iter()
-        .filter(|token| {
-            token.ends_with(".py")
-                || token.contains('/')
-                || token.contains('\\')
-                || token.contains("::")
-        })
-        .filter_map(|token| {
-            let file_part = token.split("::").next().unwrap_or(token.as_str());
-            let abs = repo_root.join(file_part);
-            abs.exists().then_some((token, abs))
-        })
-        .fold(
-            (Vec::<String>::new(), Vec::<PathBuf>::new()),
-            |(mut explicit_acc, mut seeds_acc), (token, abs)| {
-                let abs_key = abs.to_string_lossy().to_string();
-                if all_tests_set.contains(&abs_key) {
-                    explicit_acc.push((*token).clone());
-                } else {
-                    seeds_acc.push(abs);
-                }
-                (explicit_acc, seeds_acc)
-            },
-        );
-
-    if !explicit.is_empty() {
-        return Ok(explicit);
-    }
-    if !seed_inputs.is_empty() {
-        let seeds = changed_seeds(repo_root, &seed_inputs);
-        let kept = filter_tests_by_seeds(&all_tests, &seeds);
-        return Ok(kept
-            .into_iter()
-            .map(|p| p.to_string_lossy().to_string())
-            .collect());
-    }
-    if changed.is_empty() {
-        return Ok(all_tests
-            .into_iter()
-            .map(|p| p.to_string_lossy().to_string())
-            .collect());
-    }
-
-    let seeds = changed_seeds(repo_root, &changed);
-    let kept = filter_tests_by_seeds(&all_tests, &seeds);
-
-    Ok(kept
-        .into_iter()
-        .map(|p| p.to_string_lossy().to_string())
-        .collect())
-}
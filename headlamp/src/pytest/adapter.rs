@@ -19,6 +19,9 @@ struct PytestCaseEvent {
     stdout: Option<String>,
     stderr: Option<String>,
     longrepr: Option<String>,
+    skip_reason: Option<String>,
+    /// `requests`/`httpx` calls the plugin saw during the test, as jest bridge HTTP events.
+    http: Option<Vec<serde_json::Map<String, serde_json::Value>>>,
 }
 
 #[derive(Debug, Default)]
@@ -73,15 +76,22 @@ impl PytestAdapter {
         let case = headlamp_core::test_model::TestCaseResult {
             title: title.clone(),
             full_name: title.clone(),
-            status: event
-                .outcome
-                .clone()
-                .unwrap_or_else(|| "unknown".to_string()),
+            status: match event.outcome.as_deref() {
+                Some("skipped") => "pending".to_string(),
+                Some(outcome) => outcome.to_string(),
+                None => "unknown".to_string(),
+            },
             timed_out: None,
             duration: duration_ms,
             location,
             failure_messages,
             failure_details: None,
+            repro: Some(crate::repro::pytest(&event.nodeid)),
+            skip_reason: event
+                .skip_reason
+                .clone()
+                .filter(|reason| !reason.is_empty()),
+            attempts: None,
         };
         let suite = self
             .suites
@@ -92,55 +102,21 @@ impl PytestAdapter {
                 console: vec![],
             });
         suite.cases.push(case);
+        suite
+            .console
+            .extend(event.http.unwrap_or_default().into_iter().map(|http| {
+                headlamp_core::format::bridge_console::http_bridge_console_entry(
+                    http, &file, &title,
+                )
+            }));
         if self.show_logs {
-            let stdout = event.stdout.unwrap_or_default();
-            let stderr = event.stderr.unwrap_or_default();
-            if !stdout.trim().is_empty() {
-                suite.console.push(TestConsoleEntry {
-                    message: Some(serde_json::Value::String(stdout)),
-                    type_name: Some("log".to_string()),
-                    origin: Some(title.clone()),
-                });
-            }
-            if !stderr.trim().is_empty() {
-                suite.console.push(TestConsoleEntry {
-                    message: Some(serde_json::Value::String(stderr)),
-                    type_name: Some("error".to_string()),
-                    origin: Some(title),
-                });
-            }
+            push_captured_output(suite, &title, event.stdout, event.stderr);
         }
     }
 
     pub(super) fn finalize(self, exit_code: i32) -> TestRunModel {
-        let mut test_results: Vec<TestSuiteResult> = self
-            .suites
-            .into_values()
-            .map(|suite| {
-                let any_failed = suite
-                    .cases
-                    .iter()
-                    .any(|c| c.status.eq_ignore_ascii_case("failed"));
-                let status = if any_failed { "failed" } else { "passed" }.to_string();
-                let failure_message = suite
-                    .cases
-                    .iter()
-                    .flat_map(|c| c.failure_messages.iter())
-                    .cloned()
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                TestSuiteResult {
-                    test_file_path: suite.test_file_path,
-                    status,
-                    timed_out: None,
-                    failure_message,
-                    failure_details: None,
-                    test_exec_error: None,
-                    console: (!suite.console.is_empty()).then_some(suite.console),
-                    test_results: suite.cases,
-                }
-            })
-            .collect();
+        let mut test_results: Vec<TestSuiteResult> =
+            self.suites.into_values().map(finalize_suite).collect();
         test_results.sort_by(|a, b| a.test_file_path.cmp(&b.test_file_path));
 
         let num_total_test_suites = test_results.len() as u64;
@@ -151,12 +127,18 @@ impl PytestAdapter {
             .iter()
             .map(|s| s.test_results.len() as u64)
             .sum::<u64>();
-        let num_failed_tests = test_results
-            .iter()
-            .flat_map(|s| s.test_results.iter())
-            .filter(|c| c.status.eq_ignore_ascii_case("failed"))
-            .count() as u64;
-        let num_passed_tests = num_total_tests.saturating_sub(num_failed_tests);
+        let count_with_status = |status: &str| {
+            test_results
+                .iter()
+                .flat_map(|s| s.test_results.iter())
+                .filter(|c| c.status.eq_ignore_ascii_case(status))
+                .count() as u64
+        };
+        let num_failed_tests = count_with_status("failed");
+        let num_pending_tests = count_with_status("pending");
+        let num_passed_tests = num_total_tests
+            .saturating_sub(num_failed_tests)
+            .saturating_sub(num_pending_tests);
 
         TestRunModel {
             start_time: 0,
@@ -168,7 +150,7 @@ impl PytestAdapter {
                 num_total_tests,
                 num_passed_tests,
                 num_failed_tests,
-                num_pending_tests: 0,
+                num_pending_tests,
                 num_todo_tests: 0,
                 num_timed_out_tests: None,
                 num_timed_out_test_suites: None,
@@ -176,10 +158,55 @@ impl PytestAdapter {
                 success: exit_code == 0,
                 run_time_ms: None,
             },
+            skipped_units: vec![],
         }
     }
 }
 
+fn push_captured_output(
+    suite: &mut SuiteBuilder,
+    title: &str,
+    stdout: Option<String>,
+    stderr: Option<String>,
+) {
+    [(stdout, "log"), (stderr, "error")]
+        .into_iter()
+        .filter_map(|(text, type_name)| Some((text?, type_name)))
+        .filter(|(text, _)| !text.trim().is_empty())
+        .for_each(|(text, type_name)| {
+            suite.console.push(TestConsoleEntry {
+                message: Some(serde_json::Value::String(text)),
+                type_name: Some(type_name.to_string()),
+                origin: Some(title.to_string()),
+            });
+        });
+}
+
+fn finalize_suite(suite: SuiteBuilder) -> TestSuiteResult {
+    let any_failed = suite
+        .cases
+        .iter()
+        .any(|c| c.status.eq_ignore_ascii_case("failed"));
+    let status = if any_failed { "failed" } else { "passed" }.to_string();
+    let failure_message = suite
+        .cases
+        .iter()
+        .flat_map(|c| c.failure_messages.iter())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    TestSuiteResult {
+        test_file_path: suite.test_file_path,
+        status,
+        timed_out: None,
+        failure_message,
+        failure_details: None,
+        test_exec_error: None,
+        console: (!suite.console.is_empty()).then_some(suite.console),
+        test_results: suite.cases,
+    }
+}
+
 impl StreamAdapter for PytestAdapter {
     fn on_start(&mut self) -> Option<String> {
         Some("pytest".to_string())
@@ -191,6 +218,7 @@ impl StreamAdapter for PytestAdapter {
             match stream {
                 OutputStream::Stdout => actions.push(StreamAction::PrintStdout(line.to_string())),
                 OutputStream::Stderr => actions.push(StreamAction::PrintStderr(line.to_string())),
+                OutputStream::Events => {}
             }
         }
         let Some((_prefix, json)) = line.split_once(PYTEST_EVENT_PREFIX) else {
@@ -225,10 +253,12 @@ impl StreamAdapter for PytestAdapter {
                         .map(|t| t.elapsed())
                 });
             let outcome = evt.outcome.clone().unwrap_or_else(|| "unknown".to_string());
+            let test_outcome = crate::live_progress::outcome_from_status(outcome.as_str());
+            actions.push(StreamAction::RecordOutcome(test_outcome));
             let should_print = !self.only_failures || outcome.eq_ignore_ascii_case("failed");
             if should_print {
                 let line = crate::live_progress::render_finished_test_line(
-                    crate::live_progress::outcome_from_status(outcome.as_str()),
+                    test_outcome,
                     duration,
                     file.as_str(),
                     title.as_str(),
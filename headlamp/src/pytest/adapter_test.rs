@@ -0,0 +1,113 @@
+use std::process::Command;
+
+use crate::format::bridge_console::parse_bridge_console;
+use crate::streaming::{OutputStream, StreamAdapter};
+
+use super::PytestAdapter;
+
+#[test]
+fn pytest_case_http_calls_become_bridge_http_events_of_their_test() {
+    let mut adapter = PytestAdapter::new(false, false, false);
+    let event = serde_json::json!({
+        "type": "case",
+        "nodeid": "tests/test_api.py::test_lists_users",
+        "outcome": "failed",
+        "duration": 0.2,
+        "longrepr": "assert 500 == 200",
+        "http": [{
+            "type": "httpResponse", "timestampMs": 1_700_000_000_000u64, "method": "GET",
+            "url": "http://localhost/api/users", "statusCode": 500, "durationMs": 12,
+        }],
+    });
+    adapter.on_line(
+        OutputStream::Stdout,
+        &format!("HEADLAMP_PYTEST_EVENT {event}"),
+    );
+
+    let model = adapter.finalize(1);
+    let suite = &model.test_results[0];
+    let (http, _, console) = parse_bridge_console(suite.console.as_ref());
+
+    assert!(console.is_empty());
+    assert_eq!(http.len(), 1);
+    assert_eq!(http[0].kind.as_deref(), Some("response"));
+    assert_eq!(http[0].status_code, Some(500));
+    assert_eq!(http[0].timestamp_ms, 1_700_000_000_000);
+    assert_eq!(http[0].test_path.as_deref(), Some("tests/test_api.py"));
+    assert_eq!(
+        http[0].current_test_name.as_deref(),
+        Some("test_lists_users")
+    );
+}
+
+#[test]
+fn pytest_plugin_records_calls_of_already_imported_http_clients() {
+    if which::which("python3").is_err() {
+        return;
+    }
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tmp.path().join("headlamp_pytest_plugin.py"),
+        super::PYTEST_PLUGIN_BYTES,
+    )
+    .unwrap();
+    let script = r#"
+import json, sys, types
+
+class Response:
+    status_code = 404
+    headers = {"content-type": "application/json"}
+    text = '{"error": "missing"}'
+
+class Request:
+    method = "get"
+    url = "http://localhost/api/users/7"
+
+class Session:
+    def send(self, request, **kwargs):
+        if kwargs.get("fail"):
+            raise ConnectionError("refused")
+        return Response()
+
+sys.modules["requests"] = types.SimpleNamespace(Session=Session)
+import headlamp_pytest_plugin as plugin
+plugin._patch_http_clients()
+plugin._patch_http_clients()
+Session().send(Request())
+try:
+    Session().send(Request(), fail=True)
+except ConnectionError:
+    pass
+print(json.dumps(plugin._HTTP_CALLS))
+"#;
+    let out = Command::new("python3")
+        .args(["-c", script])
+        .env("PYTHONPATH", tmp.path())
+        .output()
+        .unwrap();
+    let calls: Vec<serde_json::Value> = serde_json::from_slice(&out.stdout).unwrap();
+
+    let fields = calls
+        .iter()
+        .map(|call| {
+            (
+                call["type"].as_str().unwrap(),
+                call["method"].as_str().unwrap(),
+                call["statusCode"].as_i64(),
+                call["bodyPreview"].as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            (
+                "httpResponse",
+                "GET",
+                Some(404),
+                Some(r#"{"error": "missing"}"#)
+            ),
+            ("httpAbort", "GET", None, None),
+        ]
+    );
+}
@@ -2,36 +2,49 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use headlamp_core::args::ParsedArgs;
-use headlamp_core::coverage::istanbul_pretty::format_istanbul_pretty_from_lcov_report;
+use headlamp_core::coverage::coveragepy_json::read_coveragepy_json_report_from_path;
+use headlamp_core::coverage::istanbul_pretty::{
+    format_istanbul_pretty_from_lcov_report, format_istanbul_text_from_report,
+};
 use headlamp_core::coverage::lcov::read_lcov_filtered_from_path;
-use headlamp_core::coverage::model::apply_statement_totals_to_report;
+use headlamp_core::coverage::lcov::resolve_lcov_paths_to_root;
+use headlamp_core::coverage::model::{CoverageReport, apply_exclusion_pragmas};
 use headlamp_core::coverage::print::PrintOpts;
+use headlamp_core::coverage::print::filter_report;
+use headlamp_core::coverage::scope::{print_scope_label, restrict_report};
 
 use crate::run::RunError;
+use crate::session::artifacts::ArtifactKind;
 
 pub(super) fn maybe_collect_pytest_coverage(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
+    python: Option<&Path>,
     exit_code: i32,
 ) -> Result<i32, RunError> {
     if !args.collect_coverage {
         return Ok(exit_code);
     }
     let coverage_data_file = coverage_data_path_for_args(repo_root, args, session);
-    if should_run_coveragepy_json(&coverage_data_file) {
-        let _ = run_coveragepy_json_report(repo_root, args, session);
-    }
+    combine_coverage_shards(repo_root, session, python, &coverage_data_file);
+    let json_path = should_run_coveragepy_json(&coverage_data_file)
+        .then(|| run_coveragepy_json_report(repo_root, args, session, python).ok())
+        .flatten();
     let lcov_path = lcov_path_for_args(repo_root, args, session);
-    let Some(filtered) = read_lcov_filtered_from_path(
-        repo_root,
-        &lcov_path,
-        &args.include_globs,
-        &args.exclude_globs,
-    ) else {
+    session.register_artifact(ArtifactKind::Coverage, "pytest", &lcov_path);
+    let Some(filtered) = read_pytest_coverage(repo_root, args, json_path.as_deref(), &lcov_path)
+    else {
+        eprintln!(
+            "headlamp: coverage was requested but pytest wrote no coverage data \
+(is pytest-cov installed, and does --coverage-scope name your sources?)"
+        );
         return Ok(exit_code);
     };
-    let filtered = augment_with_coveragepy_statement_totals(repo_root, args, session, filtered);
+    let filtered = apply_exclusion_pragmas(filtered, repo_root);
+    let filtered = restrict_report(filtered, repo_root, &args.coverage_scope);
+    crate::coverage::last_run::save_last_run_coverage(repo_root, "pytest", &filtered);
+    crate::recorded::record_coverage(&filtered);
     let print_opts =
         PrintOpts::for_run(args, headlamp_core::format::terminal::is_output_terminal());
     let threshold_failure_lines = args.coverage_thresholds.as_ref().map(|thresholds| {
@@ -40,18 +53,21 @@ pub(super) fn maybe_collect_pytest_coverage(
             headlamp_core::coverage::thresholds::compute_totals_from_report(&filtered),
         )
     });
-    let pretty = format_istanbul_pretty_from_lcov_report(
-        repo_root,
-        filtered,
-        &print_opts,
-        &[],
-        &args.include_globs,
-        &args.exclude_globs,
-        args.coverage_detail,
-    );
-    if args.coverage_ui != headlamp_core::config::CoverageUi::Jest {
-        println!("{pretty}");
-    }
+    let pretty = if args.coverage_ui == headlamp_core::config::CoverageUi::Jest {
+        format_istanbul_text_from_report(repo_root, filtered, &print_opts)
+    } else {
+        format_istanbul_pretty_from_lcov_report(
+            repo_root,
+            filtered,
+            &print_opts,
+            &[],
+            &args.include_globs,
+            &args.exclude_globs,
+            args.coverage_detail,
+        )
+    };
+    print_scope_label(args);
+    crate::format::report_out::println(headlamp_core::format::deterministic::finalize(pretty));
     let thresholds_failed = threshold_failure_lines.is_some_and(|lines| {
         if lines.is_empty() {
             return false;
@@ -66,33 +82,109 @@ pub(super) fn maybe_collect_pytest_coverage(
     })
 }
 
-fn augment_with_coveragepy_statement_totals(
+/// The `coverage json` report written this run, with branches, functions and statement totals
+/// straight from the (combined) data file; pytest-cov's lcov when there is none.
+fn read_pytest_coverage(
     repo_root: &Path,
     args: &ParsedArgs,
+    json_path: Option<&Path>,
+    lcov_path: &Path,
+) -> Option<CoverageReport> {
+    let from_json = json_path
+        .and_then(|path| read_coveragepy_json_report_from_path(repo_root, path))
+        .map(|report| {
+            filter_report(
+                resolve_lcov_paths_to_root(report, repo_root),
+                repo_root,
+                &args.include_globs,
+                &args.exclude_globs,
+            )
+        });
+    from_json
+        .filter(|report| !report.files.is_empty())
+        .or_else(|| {
+            read_lcov_filtered_from_path(
+                repo_root,
+                lcov_path,
+                &args.include_globs,
+                &args.exclude_globs,
+            )
+        })
+        .filter(|report| !report.files.is_empty())
+}
+
+/// xdist workers that died, and subprocesses measured with `parallel = True`, leave
+/// `.coverage.<suffix>` shards next to the data file that pytest-cov never folded in; they are
+/// combined into it before any report is read.
+fn combine_coverage_shards(
+    repo_root: &Path,
     session: &crate::session::RunSession,
-    filtered: crate::coverage::model::CoverageReport,
-) -> crate::coverage::model::CoverageReport {
-    let totals = if args.keep_artifacts {
-        crate::coverage::coveragepy_json::read_repo_coveragepy_json_statement_totals(repo_root)
-    } else {
-        crate::coverage::coveragepy_json::read_coveragepy_json_statement_totals_from_path(
-            repo_root,
-            &pytest_coverage_json_path(session),
-        )
+    python: Option<&Path>,
+    coverage_data_file: &Path,
+) {
+    if coverage_shards(coverage_data_file).is_empty() {
+        return;
+    }
+    let _ = coverage_command(python, session, coverage_data_file)
+        .args(["combine", "-q", "--append"])
+        .current_dir(repo_root)
+        .status();
+}
+
+pub(crate) fn coverage_shards(coverage_data_file: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (
+        coverage_data_file.parent(),
+        coverage_data_file
+            .file_name()
+            .and_then(|name| name.to_str()),
+    ) else {
+        return vec![];
     };
-    match totals.as_ref() {
-        Some(statement_totals_by_path) => {
-            apply_statement_totals_to_report(filtered, statement_totals_by_path)
-        }
-        None => filtered,
+    let prefix = format!("{name}.");
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .collect()
+}
+
+/// `python -m coverage` against the run's data file, with the generated config if there is one.
+fn coverage_command(
+    python: Option<&Path>,
+    session: &crate::session::RunSession,
+    coverage_data_file: &Path,
+) -> Command {
+    let python_bin = python.map(Path::as_os_str).unwrap_or(if cfg!(windows) {
+        "python.exe".as_ref()
+    } else {
+        "python".as_ref()
+    });
+    let mut command = Command::new(python_bin);
+    command.args(["-m", "coverage"]);
+    command
+        .env("COVERAGE_FILE", coverage_data_file.as_os_str())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    if let Some(rcfile) = super::coveragerc::generated_coveragerc(session) {
+        command.env("COVERAGE_RCFILE", rcfile.as_os_str());
     }
+    command
 }
 
 fn run_coveragepy_json_report(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
-) -> Result<(), RunError> {
+    python: Option<&Path>,
+) -> Result<PathBuf, RunError> {
     let out_path = if args.keep_artifacts {
         repo_root.join("coverage").join("coverage.json")
     } else {
@@ -101,25 +193,16 @@ fn run_coveragepy_json_report(
     if let Some(parent) = out_path.parent() {
         std::fs::create_dir_all(parent).map_err(RunError::Io)?;
     }
-    let python_bin = if cfg!(windows) {
-        "python.exe"
-    } else {
-        "python"
-    };
-    let out_path_string = out_path.to_string_lossy().to_string();
     let coverage_data_path = coverage_data_path_for_args(repo_root, args, session);
-    let status = Command::new(python_bin)
-        .args(["-m", "coverage", "json", "-q", "-o"])
-        .arg(out_path_string)
+    let status = coverage_command(python, session, &coverage_data_path)
+        .args(["json", "-q", "-o"])
+        .arg(&out_path)
         .current_dir(repo_root)
-        .env("COVERAGE_FILE", coverage_data_path.as_os_str())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
         .status()
         .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
     status
         .success()
-        .then_some(())
+        .then_some(out_path)
         .ok_or_else(|| RunError::CommandFailed {
             message: "python -m coverage json failed".to_string(),
         })
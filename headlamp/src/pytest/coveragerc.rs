@@ -0,0 +1,105 @@
+//! A `.coveragerc` for repos that leave coverage.py unconfigured, so pytest-cov measures
+//! branches and reports within headlamp's include/exclude globs instead of coming back empty or
+//! full of site-packages.
+
+use std::path::{Path, PathBuf};
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::run::RunError;
+
+/// `--cov-config=<generated>` unless the repo configures coverage.py or the runner args pick a
+/// config already.
+pub(crate) fn cov_config_args(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+) -> Result<Vec<String>, RunError> {
+    let picks_config = args
+        .runner_args
+        .iter()
+        .any(|arg| arg == "--cov-config" || arg.starts_with("--cov-config="));
+    if picks_config || repo_configures_coverage(repo_root) {
+        return Ok(vec![]);
+    }
+    let path = generated_coveragerc_path(session);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(RunError::Io)?;
+    }
+    std::fs::write(
+        &path,
+        coveragerc_contents(&args.include_globs, &args.exclude_globs),
+    )
+    .map_err(RunError::Io)?;
+    Ok(vec![format!("--cov-config={}", path.to_string_lossy())])
+}
+
+/// The config written for this run, for the `coverage combine` / `coverage json` calls after it.
+pub(crate) fn generated_coveragerc(session: &crate::session::RunSession) -> Option<PathBuf> {
+    Some(generated_coveragerc_path(session)).filter(|path| path.is_file())
+}
+
+fn generated_coveragerc_path(session: &crate::session::RunSession) -> PathBuf {
+    session
+        .subdir("coverage")
+        .join("pytest")
+        .join(".coveragerc")
+}
+
+/// Where coverage.py reads its settings from, besides an explicit `--rcfile`.
+pub(crate) fn repo_configures_coverage(repo_root: &Path) -> bool {
+    let file_has = |name: &str, section: &str| {
+        std::fs::read_to_string(repo_root.join(name)).is_ok_and(|text| text.contains(section))
+    };
+    repo_root.join(".coveragerc").is_file()
+        || file_has("setup.cfg", "[coverage:")
+        || file_has("tox.ini", "[coverage:")
+        || file_has("pyproject.toml", "[tool.coverage")
+}
+
+/// Omits apply to measuring and reporting; includes only to reporting, since coverage.py ignores
+/// `[run] include` once `--cov` names a source.
+pub(crate) fn coveragerc_contents(include_globs: &[String], exclude_globs: &[String]) -> String {
+    let patterns = |globs: &[String]| {
+        globs
+            .iter()
+            .flat_map(|glob| expand_braces(glob))
+            .map(|pattern| format!("    {pattern}\n"))
+            .collect::<String>()
+    };
+    let omit = patterns(exclude_globs);
+    let include = patterns(include_globs);
+    let mut contents = "[run]\nbranch = True\n".to_string();
+    if !omit.is_empty() {
+        contents.push_str(&format!("omit =\n{omit}"));
+    }
+    contents.push_str("\n[report]\n");
+    if !include.is_empty() {
+        contents.push_str(&format!("include =\n{include}"));
+    }
+    if !omit.is_empty() {
+        contents.push_str(&format!("omit =\n{omit}"));
+    }
+    contents
+}
+
+/// coverage.py patterns know `*` and `**` but not `{a,b}`: `**/*.{test,spec}.py` becomes one
+/// pattern per alternative.
+pub(crate) fn expand_braces(glob: &str) -> Vec<String> {
+    let Some(open) = glob.find('{') else {
+        return vec![glob.to_string()];
+    };
+    let Some(close) = glob[open..].find('}').map(|offset| open + offset) else {
+        return vec![glob.to_string()];
+    };
+    glob[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| {
+            expand_braces(&format!(
+                "{}{alternative}{}",
+                &glob[..open],
+                &glob[close + 1..]
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+const SETTINGS_ENV: &str = "DJANGO_SETTINGS_MODULE";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DjangoProject {
+    pub settings_module: String,
+    /// Settings came from pytest config, so the project runs under pytest-django. Plugin
+    /// autoload is disabled for our runs, so the plugin has to be loaded explicitly.
+    pub pytest_django: bool,
+}
+
+/// Finds the Django settings module the way pytest-django would (env var first, then pytest
+/// config), falling back to the `manage.py` default for projects that only use Django's runner.
+pub fn detect_django(repo_root: &Path) -> Option<DjangoProject> {
+    let from_pytest_config = pytest_config_settings(repo_root);
+    let settings_module = std::env::var(SETTINGS_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| from_pytest_config.clone())
+        .or_else(|| manage_py_settings(repo_root))?;
+    Some(DjangoProject {
+        settings_module,
+        pytest_django: from_pytest_config.is_some(),
+    })
+}
+
+impl DjangoProject {
+    pub(super) fn apply_env(&self, command: &mut std::process::Command) {
+        command.env(SETTINGS_ENV, &self.settings_module);
+    }
+
+    pub(super) fn plugin_args(&self) -> Vec<String> {
+        if self.pytest_django {
+            vec!["-p".to_string(), "pytest_django".to_string()]
+        } else {
+            vec![]
+        }
+    }
+}
+
+fn pytest_config_settings(repo_root: &Path) -> Option<String> {
+    [
+        ("pytest.ini", "pytest"),
+        ("setup.cfg", "tool:pytest"),
+        ("tox.ini", "pytest"),
+    ]
+    .into_iter()
+    .find_map(|(file, section)| ini_settings(&repo_root.join(file), section))
+    .or_else(|| pyproject_settings(repo_root))
+}
+
+fn ini_settings(path: &Path, section: &str) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let header = format!("[{section}]");
+    let mut in_section = false;
+    text.lines().map(str::trim).find_map(|line| {
+        if line.starts_with('[') {
+            in_section = line == header;
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        (in_section && key.trim() == SETTINGS_ENV).then(|| value.trim().to_string())
+    })
+}
+
+fn pyproject_settings(repo_root: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(repo_root.join("pyproject.toml")).ok()?;
+    let value = toml::from_str::<toml::Value>(&text).ok()?;
+    value
+        .get("tool")?
+        .get("pytest")?
+        .get("ini_options")?
+        .get(SETTINGS_ENV)?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn manage_py_settings(repo_root: &Path) -> Option<String> {
+    static SETDEFAULT_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"DJANGO_SETTINGS_MODULE['"]\s*,\s*['"]([^'"]+)['"]"#).unwrap()
+    });
+    let text = std::fs::read_to_string(repo_root.join("manage.py")).ok()?;
+    SETDEFAULT_RE
+        .captures(&text)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// The app directory a changed model or migration belongs to. Schema changes ripple to every
+/// test in the app (fixtures, factories, the test database), not just the ones importing the file.
+fn owning_app_dir(changed: &Path) -> Option<PathBuf> {
+    let parent = changed.parent()?;
+    let parent_name = parent.file_name()?.to_string_lossy();
+    let app_dir = match parent_name.as_ref() {
+        "migrations" | "models" => parent.parent()?,
+        _ if changed.file_name()? == "models.py" => parent,
+        _ => return None,
+    };
+    let is_app = app_dir.join("apps.py").is_file() || app_dir.join("migrations").is_dir();
+    is_app.then(|| app_dir.to_path_buf())
+}
+
+pub(crate) fn changed_app_dirs(changed: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = changed
+        .iter()
+        .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("py"))
+        .filter_map(|p| owning_app_dir(p))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Seeds that catch other apps depending on a changed app: model imports and lazy string
+/// references such as `ForeignKey("shop.Order")`.
+pub(crate) fn app_seeds(repo_root: &Path, app_dirs: &[PathBuf]) -> Vec<String> {
+    app_dirs
+        .iter()
+        .filter_map(|dir| dir.strip_prefix(repo_root).ok())
+        .flat_map(|rel| {
+            use path_slash::PathExt;
+            let module = rel.to_slash_lossy().replace('/', ".");
+            let label = module.rsplit('.').next().unwrap_or(&module).to_string();
+            vec![
+                format!("{module}.models"),
+                format!("{label}.models"),
+                format!("\"{label}."),
+                format!("'{label}."),
+            ]
+        })
+        .collect()
+}
+
+pub(crate) fn tests_in_apps(all_tests: &[PathBuf], app_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    all_tests
+        .iter()
+        .filter(|test| app_dirs.iter().any(|dir| test.starts_with(dir)))
+        .cloned()
+        .collect()
+}
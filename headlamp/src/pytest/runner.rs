@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::python_env::PythonInterpreter;
+
+/// Which executable drives the Python run. With a resolved project interpreter, pytest runs as
+/// `python -m pytest`; repos without pytest fall back to stdlib `unittest` discovery via a
+/// wrapper that emits the pytest plugin's event lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PythonTestRunner {
+    Pytest { python: Option<PathBuf> },
+    Unittest { python: PathBuf },
+}
+
+impl PythonTestRunner {
+    pub(crate) fn detect(interpreter: Option<&PythonInterpreter>) -> Self {
+        if let Some(interpreter) = interpreter {
+            let python = interpreter.path.clone();
+            return if can_import_pytest(&python) {
+                Self::Pytest {
+                    python: Some(python),
+                }
+            } else {
+                Self::Unittest { python }
+            };
+        }
+        if which::which(super::pytest_bin()).is_ok() {
+            return Self::Pytest { python: None };
+        }
+        python_bin()
+            .map(|python| Self::Unittest { python })
+            .unwrap_or(Self::Pytest { python: None })
+    }
+
+    pub(crate) fn python(&self) -> Option<&Path> {
+        match self {
+            Self::Pytest { python } => python.as_deref(),
+            Self::Unittest { python } => Some(python),
+        }
+    }
+
+    pub(crate) fn program(&self) -> String {
+        self.python()
+            .map(|python| python.to_string_lossy().to_string())
+            .unwrap_or_else(|| super::pytest_bin().to_string())
+    }
+
+    /// Args that pick the tool when `program()` is an interpreter rather than `pytest` itself.
+    pub(crate) fn module_args(&self) -> Vec<String> {
+        match self {
+            Self::Pytest { python: None } => vec![],
+            Self::Pytest { python: Some(_) } => vec!["-m".to_string(), "pytest".to_string()],
+            Self::Unittest { .. } => super::unittest::module_args(),
+        }
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Self::Pytest { .. } => "pytest",
+            Self::Unittest { .. } => "unittest",
+        }
+    }
+
+    pub(crate) fn is_unittest(&self) -> bool {
+        matches!(self, Self::Unittest { .. })
+    }
+}
+
+fn can_import_pytest(python: &Path) -> bool {
+    Command::new(python)
+        .args(["-c", "import pytest"])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn python_bin() -> Option<PathBuf> {
+    ["python3", "python"]
+        .into_iter()
+        .find_map(|name| which::which(name).ok())
+}
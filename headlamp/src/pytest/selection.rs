@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use headlamp_core::args::ParsedArgs;
+
+use super::{PythonTestRunner, django};
+use crate::git::changed_files;
+use crate::process::RunEnvironment;
+use crate::pytest_select::{
+    changed_seeds, discover_pytest_test_files, filter_tests_by_seeds, list_unittest_test_files,
+};
+use crate::run::RunError;
+
+fn list_python_test_files(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    runner: &PythonTestRunner,
+) -> Result<Vec<PathBuf>, RunError> {
+    match runner {
+        PythonTestRunner::Pytest { python } => {
+            discover_pytest_test_files(repo_root, args.no_cache, python.as_deref(), run_env)
+        }
+        PythonTestRunner::Unittest { .. } => Ok(list_unittest_test_files(repo_root)),
+    }
+}
+
+pub(super) fn resolve_pytest_selection(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    runner: &PythonTestRunner,
+) -> Result<Vec<String>, RunError> {
+    let changed = args
+        .changed
+        .map(|m| changed_files(repo_root, m))
+        .transpose()?
+        .unwrap_or_default();
+
+    let all_tests = list_python_test_files(repo_root, args, run_env, runner)?;
+    let all_tests_set = all_tests
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect::<std::collections::BTreeSet<_>>();
+
+    let (explicit, seed_inputs) = args
+        .selection_paths
+        .iter()
+        .filter(|token| {
+            token.ends_with(".py")
+                || token.contains('/')
+                || token.contains('\\')
+                || token.contains("::")
+        })
+        .filter_map(|token| {
+            let file_part = token.split("::").next().unwrap_or(token.as_str());
+            let abs = repo_root.join(file_part);
+            abs.exists().then_some((token, abs))
+        })
+        .fold(
+            (Vec::<String>::new(), Vec::<PathBuf>::new()),
+            |(mut explicit_acc, mut seeds_acc), (token, abs)| {
+                let abs_key = abs.to_string_lossy().to_string();
+                if all_tests_set.contains(&abs_key) {
+                    explicit_acc.push((*token).clone());
+                } else {
+                    seeds_acc.push(abs);
+                }
+                (explicit_acc, seeds_acc)
+            },
+        );
+
+    if !explicit.is_empty() {
+        return Ok(explicit);
+    }
+    if !seed_inputs.is_empty() {
+        return Ok(select_tests_for_changes(
+            repo_root,
+            &all_tests,
+            &seed_inputs,
+        ));
+    }
+    if changed.is_empty() {
+        return Ok(all_tests
+            .into_iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect());
+    }
+
+    Ok(select_tests_for_changes(repo_root, &all_tests, &changed))
+}
+
+/// Import/name seeds, plus Django app impact: a changed model or migration selects every test
+/// in its app and any test referencing the app's models.
+pub(crate) fn select_tests_for_changes(
+    repo_root: &Path,
+    all_tests: &[PathBuf],
+    changed: &[PathBuf],
+) -> Vec<String> {
+    let app_dirs = django::changed_app_dirs(changed);
+    let seeds = [
+        changed_seeds(repo_root, changed),
+        django::app_seeds(repo_root, &app_dirs),
+    ]
+    .concat();
+    let mut kept = filter_tests_by_seeds(all_tests, &seeds);
+    kept.extend(django::tests_in_apps(all_tests, &app_dirs));
+    kept.sort();
+    kept.dedup();
+    kept.into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use crate::args::ParsedArgs;
+use crate::run::RunError;
+
+const UNITTEST_RUNNER_BYTES: &[u8] =
+    include_bytes!("../../assets/pytest/headlamp_unittest_runner.py");
+
+const UNITTEST_RUNNER_MODULE: &str = "headlamp_unittest_runner";
+
+pub(crate) fn write_unittest_runner(tmp: &Path) -> Result<String, RunError> {
+    super::write_asset(
+        &tmp.join(format!("{UNITTEST_RUNNER_MODULE}.py")),
+        UNITTEST_RUNNER_BYTES,
+    )
+}
+
+/// Leading interpreter args; the wrapper's own flags and the selection follow (see `tool_args`).
+pub(super) fn module_args() -> Vec<String> {
+    vec!["-m".to_string(), UNITTEST_RUNNER_MODULE.to_string()]
+}
+
+/// The wrapper's args: a name filter (already `-k value`, see `translate_runner_args`) stays its
+/// `-k` substring filter and `--bail` becomes `--failfast`, then the selection. Any other runner
+/// arg is a pytest flag the wrapper can't honor, so the run fails rather than ignoring it.
+pub(crate) fn tool_args(args: &ParsedArgs, selected: &[String]) -> Result<Vec<String>, RunError> {
+    let mut out = vec![];
+    let mut unsupported = vec![];
+    let mut tokens = args.runner_args.iter();
+    while let Some(token) = tokens.next() {
+        if token == "-k"
+            && let Some(value) = tokens.next()
+        {
+            out.extend(["-k".to_string(), value.clone()]);
+        } else {
+            unsupported.push(token.as_str());
+        }
+    }
+    if !unsupported.is_empty() {
+        return Err(RunError::CommandFailed {
+            message: format!(
+                "pytest is not installed and the unittest fallback does not support: {}",
+                unsupported.join(" ")
+            ),
+        });
+    }
+    if args.bail {
+        out.push("--failfast".to_string());
+    }
+    out.extend(selected.iter().cloned());
+    Ok(out)
+}
@@ -3,8 +3,10 @@ use std::path::Path;
 use tempfile::tempdir;
 
 use crate::pytest::coverage::{
-    ensure_cov_report_output_directories, extract_lcov_report_paths, should_run_coveragepy_json,
+    coverage_shards, ensure_cov_report_output_directories, extract_lcov_report_paths,
+    should_run_coveragepy_json,
 };
+use crate::pytest::coveragerc::{coveragerc_contents, repo_configures_coverage};
 
 fn write_file(path: &Path, bytes: &[u8]) {
     if let Some(parent) = path.parent() {
@@ -75,3 +77,42 @@ fn pytest_coverage_creates_output_directories_for_absolute_lcov_paths() {
 
     assert!(dir.path().join("abs-coverage").is_dir());
 }
+
+#[test]
+fn pytest_coveragerc_scopes_reports_to_headlamp_globs() {
+    let contents = coveragerc_contents(
+        &["src/**/*.py".to_string()],
+        &["**/*.{test,spec}.py".to_string()],
+    );
+    assert_eq!(
+        contents,
+        "[run]\nbranch = True\nomit =\n    **/*.test.py\n    **/*.spec.py\n\
+\n[report]\ninclude =\n    src/**/*.py\nomit =\n    **/*.test.py\n    **/*.spec.py\n"
+    );
+}
+
+#[test]
+fn pytest_coveragerc_is_left_to_repos_that_configure_coverage() {
+    let dir = tempdir().unwrap();
+    assert!(!repo_configures_coverage(dir.path()));
+    write_file(&dir.path().join("setup.cfg"), b"[metadata]\nname = x\n");
+    assert!(!repo_configures_coverage(dir.path()));
+    write_file(
+        &dir.path().join("pyproject.toml"),
+        b"[tool.coverage.run]\nbranch = true\n",
+    );
+    assert!(repo_configures_coverage(dir.path()));
+}
+
+#[test]
+fn pytest_coverage_finds_worker_shards_next_to_the_data_file() {
+    let dir = tempdir().unwrap();
+    let data_file = dir.path().join(".coverage");
+    write_file(&data_file, b"main");
+    write_file(&dir.path().join(".coverage.host.123.abc"), b"gw0");
+    write_file(&dir.path().join(".coveragerc"), b"[run]\n");
+
+    let shards = coverage_shards(&data_file);
+
+    assert_eq!(shards, vec![dir.path().join(".coverage.host.123.abc")]);
+}
@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use crate::pytest::selection::select_tests_for_changes;
+
+fn write_file(path: &Path, contents: &str) -> PathBuf {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+    path.to_path_buf()
+}
+
+#[test]
+fn changed_migration_selects_owning_app_and_dependent_apps() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = repo.path();
+    write_file(&root.join("shop/apps.py"), "");
+    write_file(&root.join("shop/models.py"), "class Order: pass\n");
+    let migration = write_file(&root.join("shop/migrations/0002_order_total.py"), "");
+    let shop_views = write_file(
+        &root.join("shop/tests/test_views.py"),
+        "def test_x(): pass\n",
+    );
+    let billing = write_file(
+        &root.join("billing/tests/test_invoice.py"),
+        "order = models.ForeignKey(\"shop.Order\")\n",
+    );
+    let unrelated = write_file(
+        &root.join("blog/tests/test_posts.py"),
+        "def test_y(): pass\n",
+    );
+    let all_tests = vec![billing.clone(), unrelated, shop_views.clone()];
+
+    let selected = select_tests_for_changes(root, &all_tests, &[migration]);
+
+    assert_eq!(
+        selected,
+        vec![
+            billing.to_string_lossy().to_string(),
+            shop_views.to_string_lossy().to_string(),
+        ]
+    );
+}
+
+#[test]
+fn plain_module_changes_keep_seed_selection() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = repo.path();
+    let helper = write_file(&root.join("utils/models.py"), "");
+    let user = write_file(
+        &root.join("tests/test_utils.py"),
+        "from utils.models import x\n",
+    );
+    let other = write_file(&root.join("tests/test_other.py"), "");
+
+    // `utils` has no apps.py or migrations/, so it is not treated as a Django app.
+    let selected = select_tests_for_changes(root, &[user.clone(), other], &[helper]);
+
+    assert_eq!(selected, vec![user.to_string_lossy().to_string()]);
+}
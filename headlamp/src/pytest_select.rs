@@ -2,17 +2,26 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Duration;
 
+use crate::process::RunEnvironment;
 use crate::seed_match::SeedMatcher;
 use crate::{fast_related, process, run::RunError};
 
 const PYTEST_COLLECT_TIMEOUT: Duration = Duration::from_secs(25);
 
+/// `python` runs collection as `python -m pytest`; `None` uses `pytest` from PATH.
 pub fn discover_pytest_test_files(
     repo_root: &Path,
     no_cache: bool,
+    python: Option<&Path>,
+    run_env: &RunEnvironment,
 ) -> Result<Vec<PathBuf>, RunError> {
     if no_cache {
-        return discover_pytest_test_files_with_timeout(repo_root, PYTEST_COLLECT_TIMEOUT);
+        return discover_pytest_test_files_with_timeout(
+            repo_root,
+            python,
+            run_env,
+            PYTEST_COLLECT_TIMEOUT,
+        );
     }
 
     let cache_root = fast_related::default_cache_root();
@@ -39,7 +48,12 @@ pub fn discover_pytest_test_files(
         return Ok(paths);
     };
 
-    let discovered = discover_pytest_test_files_with_timeout(repo_root, PYTEST_COLLECT_TIMEOUT)?;
+    let discovered = discover_pytest_test_files_with_timeout(
+        repo_root,
+        python,
+        run_env,
+        PYTEST_COLLECT_TIMEOUT,
+    )?;
     let mut rels = discovered
         .iter()
         .filter_map(|abs| abs.strip_prefix(repo_root).ok())
@@ -61,15 +75,51 @@ pub fn discover_pytest_test_files(
     Ok(discovered)
 }
 
+/// Files matching unittest's default `test*.py` discovery pattern; used when pytest is absent.
+pub fn list_unittest_test_files(repo_root: &Path) -> Vec<PathBuf> {
+    const SKIPPED_DIRS: [&str; 4] = [".venv", "venv", "site-packages", "node_modules"];
+    let mut out = ignore::WalkBuilder::new(repo_root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .filter_entry(|dent| {
+            !SKIPPED_DIRS
+                .iter()
+                .any(|dir| dent.file_name().to_string_lossy() == *dir)
+        })
+        .build()
+        .map_while(Result::ok)
+        .filter(|dent| dent.file_type().is_some_and(|t| t.is_file()))
+        .map(|dent| dent.into_path())
+        .filter(|p| {
+            p.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with("test") && name.ends_with(".py"))
+        })
+        .collect::<Vec<_>>();
+    out.sort();
+    out
+}
+
 fn discover_pytest_test_files_with_timeout(
     repo_root: &Path,
+    python: Option<&Path>,
+    run_env: &RunEnvironment,
     timeout: Duration,
 ) -> Result<Vec<PathBuf>, RunError> {
-    let mut command = Command::new(if cfg!(windows) {
-        "pytest.exe"
-    } else {
-        "pytest"
-    });
+    let mut command = match python {
+        Some(python) => {
+            let mut command = Command::new(python);
+            command.args(["-m", "pytest"]);
+            command
+        }
+        None => Command::new(if cfg!(windows) {
+            "pytest.exe"
+        } else {
+            "pytest"
+        }),
+    };
     let pythonpath = crate::pythonpath::build_pytest_pythonpath(
         repo_root,
         &[],
@@ -80,6 +130,7 @@ fn discover_pytest_test_files_with_timeout(
         .current_dir(repo_root)
         .env("CI", "1")
         .env("PYTHONPATH", pythonpath);
+    run_env.apply(&mut command);
     let display_command = "pytest --collect-only -q".to_string();
     let out = process::run_command_capture_with_timeout(command, display_command, timeout)?;
     if !out.status.success() {
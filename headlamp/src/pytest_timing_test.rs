@@ -22,6 +22,7 @@ fn pytest_timing_applies_elapsed_ms_not_unix_timestamp() {
             success: true,
             run_time_ms: None,
         },
+        skipped_units: vec![],
     };
 
     apply_run_timing_to_model(&mut model, 1_700_000_000_000, 1_500);
@@ -0,0 +1,171 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::pytest::PythonTestRunner;
+use crate::pytest::unittest::{tool_args, write_unittest_runner};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn unittest_runner() -> PythonTestRunner {
+    PythonTestRunner::Unittest {
+        python: "python3".into(),
+    }
+}
+
+fn run_wrapper(repo_root: &Path, selection: &[String]) -> Vec<serde_json::Value> {
+    let tmp = tempfile::tempdir().unwrap();
+    write_unittest_runner(tmp.path()).unwrap();
+    let runner = unittest_runner();
+    let out = Command::new(runner.program())
+        .args(runner.module_args())
+        .args(selection)
+        .current_dir(repo_root)
+        .env("PYTHONPATH", tmp.path())
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("HEADLAMP_PYTEST_EVENT "))
+        .map(|json| serde_json::from_str(json).unwrap())
+        .filter(|event: &serde_json::Value| event["type"] == "case")
+        .collect()
+}
+
+#[test]
+fn runner_module_args_select_the_tool_behind_an_interpreter() {
+    assert_eq!(
+        unittest_runner().module_args(),
+        vec!["-m", "headlamp_unittest_runner"]
+    );
+    let venv_pytest = PythonTestRunner::Pytest {
+        python: Some(".venv/bin/python".into()),
+    };
+    assert_eq!(venv_pytest.program(), ".venv/bin/python");
+    assert_eq!(venv_pytest.module_args(), vec!["-m", "pytest"]);
+    assert!(
+        PythonTestRunner::Pytest { python: None }
+            .module_args()
+            .is_empty()
+    );
+}
+
+#[test]
+fn unittest_wrapper_emits_pytest_style_case_events() {
+    if which::which("python3").is_err() {
+        return;
+    }
+    let repo = tempfile::tempdir().unwrap();
+    write_file(&repo.path().join("tests/__init__.py"), "");
+    write_file(
+        &repo.path().join("tests/test_math.py"),
+        "import unittest\n\n\
+         class MathTest(unittest.TestCase):\n\
+         \x20   def test_ok(self):\n\
+         \x20       print('hello')\n\
+         \x20       self.assertEqual(1 + 1, 2)\n\n\
+         \x20   def test_bad(self):\n\
+         \x20       self.assertEqual(1 + 1, 3)\n\n\
+         \x20   @unittest.skip('later')\n\
+         \x20   def test_skip(self):\n\
+         \x20       pass\n",
+    );
+
+    let events = run_wrapper(repo.path(), &[]);
+    let outcome_of = |nodeid: &str| {
+        events
+            .iter()
+            .find(|e| e["nodeid"] == nodeid)
+            .map(|e| e["outcome"].as_str().unwrap().to_string())
+    };
+    assert_eq!(
+        outcome_of("tests/test_math.py::MathTest::test_ok").as_deref(),
+        Some("passed")
+    );
+    assert_eq!(
+        outcome_of("tests/test_math.py::MathTest::test_bad").as_deref(),
+        Some("failed")
+    );
+    assert_eq!(
+        outcome_of("tests/test_math.py::MathTest::test_skip").as_deref(),
+        Some("skipped")
+    );
+    let skipped = events.iter().find(|e| e["outcome"] == "skipped").unwrap();
+    assert_eq!(skipped["skipReason"], "later");
+    let passed = events.iter().find(|e| e["outcome"] == "passed").unwrap();
+    assert_eq!(passed["stdout"], "hello\n");
+    let failed = events.iter().find(|e| e["outcome"] == "failed").unwrap();
+    assert!(
+        failed["longrepr"]
+            .as_str()
+            .unwrap()
+            .contains("AssertionError")
+    );
+
+    let selected = run_wrapper(
+        repo.path(),
+        &[repo
+            .path()
+            .join("tests/test_math.py")
+            .to_string_lossy()
+            .to_string()],
+    );
+    assert_eq!(selected.len(), 3);
+}
+
+#[test]
+fn unittest_wrapper_applies_name_filters_and_failfast() {
+    if which::which("python3").is_err() {
+        return;
+    }
+    let repo = tempfile::tempdir().unwrap();
+    write_file(&repo.path().join("tests/__init__.py"), "");
+    write_file(
+        &repo.path().join("tests/test_words.py"),
+        "import unittest\n\n\
+         class WordsTest(unittest.TestCase):\n\
+         \x20   def test_a_fails(self):\n\
+         \x20       self.fail('nope')\n\n\
+         \x20   def test_b_upper(self):\n\
+         \x20       self.assertEqual('a'.upper(), 'A')\n\n\
+         \x20   def test_c_lower(self):\n\
+         \x20       self.assertEqual('A'.lower(), 'a')\n",
+    );
+    let nodeids = |argv: &[&str]| {
+        let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        run_wrapper(repo.path(), &argv)
+            .iter()
+            .map(|event| event["nodeid"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        nodeids(&["-k", "upper"]),
+        vec!["tests/test_words.py::WordsTest::test_b_upper"]
+    );
+    assert_eq!(
+        nodeids(&["--failfast"]),
+        vec!["tests/test_words.py::WordsTest::test_a_fails"]
+    );
+    assert_eq!(nodeids(&[]).len(), 3);
+}
+
+#[test]
+fn unittest_tool_args_map_filters_and_bail_and_reject_pytest_flags() {
+    let args = |runner_args: &[&str], bail: bool| crate::args::ParsedArgs {
+        runner_args: runner_args.iter().map(|arg| arg.to_string()).collect(),
+        bail,
+        ..crate::args::ParsedArgs::default()
+    };
+    let selected = vec!["tests/test_a.py".to_string()];
+    assert_eq!(
+        tool_args(&args(&["-k", "upper"], true), &selected).unwrap(),
+        vec!["-k", "upper", "--failfast", "tests/test_a.py"]
+    );
+    let err = tool_args(&args(&["--lf", "-k"], false), &selected).unwrap_err();
+    assert!(
+        err.to_string().contains("does not support: --lf -k"),
+        "{err}"
+    );
+}
@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const POETRY_ENV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the interpreter came from, so verbose output can explain surprising picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PythonSource {
+    /// `--python` or `python` in headlamp config (including `[tool.headlamp]` in pyproject).
+    Explicit,
+    /// `UV_PROJECT_ENVIRONMENT`, the env uv syncs into when it is not `.venv`.
+    UvProjectEnvironment,
+    /// An in-repo `.venv/` or `venv/` (uv and `python -m venv` both default to `.venv`).
+    RepoVenv,
+    /// `poetry env info -p`.
+    Poetry,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonInterpreter {
+    pub path: PathBuf,
+    pub source: PythonSource,
+}
+
+/// Picks the project's interpreter instead of whatever `python`/`pytest` is first on PATH.
+/// Returns `None` when nothing project-specific is found, leaving PATH lookup to the caller.
+pub fn resolve_python(repo_root: &Path, explicit: Option<&str>) -> Option<PythonInterpreter> {
+    if let Some(path) = explicit.map(str::trim).filter(|s| !s.is_empty()) {
+        return Some(PythonInterpreter {
+            path: resolve_explicit_python(repo_root, path),
+            source: PythonSource::Explicit,
+        });
+    }
+    let found = |path: Option<PathBuf>, source: PythonSource| {
+        path.map(|path| PythonInterpreter { path, source })
+    };
+    found(
+        uv_project_environment(repo_root),
+        PythonSource::UvProjectEnvironment,
+    )
+    .or_else(|| found(repo_venv(repo_root), PythonSource::RepoVenv))
+    .or_else(|| found(poetry_env(repo_root), PythonSource::Poetry))
+}
+
+fn resolve_explicit_python(repo_root: &Path, path: &str) -> PathBuf {
+    let candidate = PathBuf::from(path);
+    if candidate.is_absolute() {
+        return candidate;
+    }
+    let repo_relative = repo_root.join(&candidate);
+    if candidate.components().count() > 1 || repo_relative.is_file() {
+        return repo_relative;
+    }
+    // A bare name like `python3.12` means "look it up on PATH".
+    which::which(&candidate).unwrap_or(candidate)
+}
+
+/// The interpreter inside a virtualenv directory, if it has one.
+pub fn venv_python(env_dir: &Path) -> Option<PathBuf> {
+    let candidates: &[&str] = if cfg!(windows) {
+        &["Scripts/python.exe"]
+    } else {
+        &["bin/python", "bin/python3"]
+    };
+    candidates
+        .iter()
+        .map(|rel| env_dir.join(rel))
+        .find(|path| path.is_file())
+}
+
+fn uv_project_environment(repo_root: &Path) -> Option<PathBuf> {
+    let dir = std::env::var_os("UV_PROJECT_ENVIRONMENT").map(PathBuf::from)?;
+    venv_python(&repo_root.join(dir))
+}
+
+fn repo_venv(repo_root: &Path) -> Option<PathBuf> {
+    [".venv", "venv"]
+        .into_iter()
+        .find_map(|dir| venv_python(&repo_root.join(dir)))
+}
+
+fn poetry_env(repo_root: &Path) -> Option<PathBuf> {
+    if !uses_poetry(repo_root) {
+        return None;
+    }
+    let poetry = which::which("poetry").ok()?;
+    let mut command = Command::new(poetry);
+    command
+        .args(["env", "info", "-p"])
+        .current_dir(repo_root)
+        .stdin(std::process::Stdio::null());
+    let out = crate::process::run_command_capture_with_timeout(
+        command,
+        "poetry env info -p".to_string(),
+        POETRY_ENV_TIMEOUT,
+    )
+    .ok()?;
+    let env_dir = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !env_dir.is_empty())
+        .then(|| venv_python(Path::new(&env_dir)))
+        .flatten()
+}
+
+fn uses_poetry(repo_root: &Path) -> bool {
+    repo_root.join("poetry.lock").is_file()
+        || std::fs::read_to_string(repo_root.join("pyproject.toml"))
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Value>(&text).ok())
+            .is_some_and(|value| value.get("tool").and_then(|t| t.get("poetry")).is_some())
+}
@@ -0,0 +1,162 @@
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::run::RunError;
+
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const FIRST_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+/// A service that must accept connections before tests start (`--wait-for`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitTarget {
+    /// `tcp:host:port`, `tcp://host:port` or bare `host:port`: a TCP connect succeeds.
+    Tcp { host: String, port: u16 },
+    /// `http://host[:port][/path]`: a plain-HTTP GET answers with a 2xx or 3xx status.
+    Http {
+        host: String,
+        port: u16,
+        path: String,
+    },
+    /// `unix:/path/to.sock`: the socket accepts a connection.
+    Unix(PathBuf),
+}
+
+pub fn parse_wait_target(spec: &str) -> Option<WaitTarget> {
+    let spec = spec.trim();
+    if let Some(rest) = spec.strip_prefix("http://") {
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let (host, port) = split_host_port(authority).unwrap_or((authority.to_string(), 80));
+        return Some(WaitTarget::Http {
+            host,
+            port,
+            path: if path.is_empty() { "/" } else { path }.to_string(),
+        });
+    }
+    if let Some(path) = spec.strip_prefix("unix:") {
+        let path = path.strip_prefix("//").unwrap_or(path);
+        return (!path.is_empty()).then(|| WaitTarget::Unix(PathBuf::from(path)));
+    }
+    let authority = spec
+        .strip_prefix("tcp://")
+        .or_else(|| spec.strip_prefix("tcp:"))
+        .unwrap_or(spec);
+    let (host, port) = split_host_port(authority)?;
+    Some(WaitTarget::Tcp { host, port })
+}
+
+fn split_host_port(authority: &str) -> Option<(String, u16)> {
+    let (host, port) = authority.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = port.parse().ok()?;
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+/// `500ms`, `90s`, `2m`, `1h`; a bare number is seconds.
+pub fn parse_duration_ms(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (amount, unit) = text.split_at(split);
+    let amount: f64 = amount.parse().ok()?;
+    let scale = match unit.trim() {
+        "ms" => 1.0,
+        "" | "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        _ => return None,
+    };
+    Some((amount * scale).round() as u64)
+}
+
+/// Probes every target in turn with exponential backoff, sharing one deadline.
+pub fn wait_for_targets(specs: &[String], timeout: Duration) -> Result<(), RunError> {
+    let targets = specs
+        .iter()
+        .map(|spec| {
+            parse_wait_target(spec)
+                .map(|target| (spec.trim(), target))
+                .ok_or_else(|| RunError::CommandFailed {
+                    message: format!("invalid --wait-for target: {spec}"),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let deadline = Instant::now() + timeout;
+    for (spec, target) in targets {
+        wait_for_target(spec, &target, deadline, timeout)?;
+    }
+    Ok(())
+}
+
+fn wait_for_target(
+    spec: &str,
+    target: &WaitTarget,
+    deadline: Instant,
+    timeout: Duration,
+) -> Result<(), RunError> {
+    let mut backoff = FIRST_BACKOFF;
+    let mut announced = false;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if probe(
+            target,
+            remaining.clamp(Duration::from_millis(50), MAX_BACKOFF),
+        ) {
+            return Ok(());
+        }
+        if remaining.is_zero() {
+            return Err(RunError::TimedOut {
+                command: format!("waiting for {spec}"),
+                timeout_ms: timeout.as_millis() as u64,
+            });
+        }
+        if !announced {
+            eprintln!("headlamp: waiting for {spec}");
+            announced = true;
+        }
+        std::thread::sleep(backoff.min(remaining));
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+pub fn probe(target: &WaitTarget, attempt_timeout: Duration) -> bool {
+    match target {
+        WaitTarget::Tcp { host, port } => connect_tcp(host, *port, attempt_timeout).is_some(),
+        WaitTarget::Http { host, port, path } => connect_tcp(host, *port, attempt_timeout)
+            .and_then(|stream| http_status(stream, host, path, attempt_timeout))
+            .is_some_and(|status| (200..400).contains(&status)),
+        WaitTarget::Unix(path) => connect_unix(path),
+    }
+}
+
+fn connect_tcp(host: &str, port: u16, timeout: Duration) -> Option<TcpStream> {
+    (host, port)
+        .to_socket_addrs()
+        .ok()?
+        .find_map(|addr| TcpStream::connect_timeout(&addr, timeout).ok())
+}
+
+fn http_status(mut stream: TcpStream, host: &str, path: &str, timeout: Duration) -> Option<u16> {
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    let request = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).ok()?;
+    let mut head = [0u8; 64];
+    let read = stream.read(&mut head).ok()?;
+    let status_line = String::from_utf8_lossy(&head[..read]);
+    status_line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(unix)]
+fn connect_unix(path: &std::path::Path) -> bool {
+    std::os::unix::net::UnixStream::connect(path).is_ok()
+}
+
+#[cfg(not(unix))]
+fn connect_unix(path: &std::path::Path) -> bool {
+    path.exists()
+}
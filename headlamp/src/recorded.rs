@@ -0,0 +1,152 @@
+//! What the runners report over one headlamp run — counts, failing tests, coverage, duplicates,
+//! final models and resource usage — for the end-of-run policies, the footer and notifications,
+//! the `FAILED_TESTS` hook file and `api::run`. Each run records into its own `Recording`.
+
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use crate::coverage::model::CoverageReport;
+use crate::duplicates::Duplicate;
+use crate::process::RunnerResources;
+use crate::test_model::TestRunModel;
+
+/// What the runners reported during the current run, summed over every runner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordedResults {
+    pub passed: u64,
+    pub failed: u64,
+    pub skipped: u64,
+    /// `(file, test name)` per failing test; the name is `None` for a suite that failed outside
+    /// any test.
+    pub failed_tests: Vec<(String, Option<String>)>,
+    /// `(covered, total)` lines over every runner that collected coverage.
+    pub coverage_lines: Option<(u64, u64)>,
+    /// Colliding results, reported at the end of the run.
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// The runners' final models and coverage reports, for callers embedding a run (`api::run`).
+#[derive(Debug, Clone, Default)]
+pub struct RecordedRuns {
+    pub models: Vec<TestRunModel>,
+    pub coverage: Vec<CoverageReport>,
+    /// Per runner invocation, in run order.
+    pub resources: Vec<RunnerResources>,
+}
+
+/// One run's recordings. `begin` makes a fresh one current, and every `record_*` call adds to
+/// the current one; the caller that began the run reads its results from the returned handle,
+/// however many runs begin after it.
+#[derive(Debug, Default)]
+pub struct Recording {
+    results: Mutex<RecordedResults>,
+    runs: Mutex<RecordedRuns>,
+}
+
+impl Recording {
+    pub fn results(&self) -> RecordedResults {
+        self.results
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn runs(&self) -> RecordedRuns {
+        self.runs
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+}
+
+static CURRENT: LazyLock<RwLock<Arc<Recording>>> = LazyLock::new(RwLock::default);
+
+/// Starts a run's recording; `runner::run_plans` calls it as each run starts.
+pub fn begin() -> Arc<Recording> {
+    let recording = Arc::new(Recording::default());
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Arc::clone(&recording);
+    }
+    recording
+}
+
+fn current() -> Arc<Recording> {
+    CURRENT
+        .read()
+        .map(|guard| Arc::clone(&guard))
+        .unwrap_or_default()
+}
+
+/// Keeps `model` for `recorded_runs` without counting it; a runner that failed to start records
+/// its infra-failure model this way.
+pub fn record_model(model: &TestRunModel) {
+    if let Ok(mut guard) = current().runs.lock() {
+        guard.models.push(model.clone());
+    }
+}
+
+/// Each runner invocation's usage, for `--resource-report` and `api::run`.
+pub fn record_resources(resources: RunnerResources) {
+    if let Ok(mut guard) = current().runs.lock() {
+        guard.resources.push(resources);
+    }
+}
+
+/// Called with each runner's final model so `post_run` / `on_failure` hooks can read the
+/// failures from the `FAILED_TESTS` file, and `--notify` can report the totals.
+pub fn record_failed_tests(model: &TestRunModel) {
+    let failed = model.test_results.iter().flat_map(|suite| {
+        let failed_cases = suite
+            .test_results
+            .iter()
+            .filter(|case| case.status == "failed")
+            .map(|case| (suite.test_file_path.clone(), Some(case.full_name.clone())))
+            .collect::<Vec<_>>();
+        if failed_cases.is_empty() && suite.status == "failed" {
+            vec![(suite.test_file_path.clone(), None)]
+        } else {
+            failed_cases
+        }
+    });
+    record_model(model);
+    let totals = &model.aggregated;
+    if let Ok(mut guard) = current().results.lock() {
+        guard.passed += totals.num_passed_tests;
+        guard.failed += totals.num_failed_tests;
+        guard.skipped += totals.num_pending_tests + totals.num_todo_tests;
+        guard.failed_tests.extend(failed);
+    }
+}
+
+/// Called with each runner's final model, and with the per-project models a jest run merges, so
+/// colliding results are reported instead of silently skewing the counts.
+pub fn record_duplicates(duplicates: Vec<Duplicate>) {
+    if let Ok(mut guard) = current().results.lock() {
+        guard.duplicates.extend(duplicates);
+    }
+}
+
+/// Called with each runner's coverage report so the summary line can show line coverage.
+pub fn record_coverage(report: &CoverageReport) {
+    let lines = crate::coverage::thresholds::compute_totals_from_report(report).lines;
+    let recording = current();
+    if let Ok(mut guard) = recording.runs.lock() {
+        guard.coverage.push(report.clone());
+    }
+    if let Ok(mut guard) = recording.results.lock() {
+        let (covered, total) = guard.coverage_lines.unwrap_or_default();
+        guard.coverage_lines = Some((
+            covered + u64::from(lines.covered),
+            total + u64::from(lines.total),
+        ));
+    }
+}
+
+/// What the current (or last) run has recorded so far.
+pub fn recorded_results() -> RecordedResults {
+    current().results()
+}
+
+/// The models and coverage reports the current (or last) run has recorded so far.
+pub fn recorded_runs() -> RecordedRuns {
+    current().runs()
+}
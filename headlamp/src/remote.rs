@@ -0,0 +1,206 @@
+//! `--remote=<executor-cmd>` (experimental): discovery and selection run here, the tests run
+//! wherever the executor sends them. Each run is planned like `--print-commands` into a
+//! `manifest.json`; the executor is run through the shell with `HEADLAMP_REMOTE_MANIFEST` and
+//! `HEADLAMP_REMOTE_OUT` set, and leaves its results in that out dir:
+//!
+//! - `model*.json`: one `TestRunModel` per file, paths absolute or relative to the repo root;
+//! - `lcov.info` (optional): coverage, rendered like a local `--coverage` run.
+//!
+//! The results are rendered and recorded here as if the runners had run locally.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::args::ParsedArgs;
+use crate::print_commands::PlannedCommand;
+use crate::process::RunEnvironment;
+use crate::runner::RunPlan;
+use crate::session::run_dirs::{new_run_dir, prune_run_dirs};
+use crate::test_model::TestRunModel;
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunManifest {
+    pub version: u32,
+    pub repo_root: String,
+    /// Where the executor leaves `model*.json` and `lcov.info`.
+    pub out_dir: String,
+    pub coverage: bool,
+    /// `--env` / `--env-file` values, already applied to each command's own `env`.
+    pub env: BTreeMap<String, String>,
+    pub runs: Vec<ManifestRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestRun {
+    pub runner: String,
+    pub run_root: String,
+    pub selection_paths: Vec<String>,
+    pub commands: Vec<PlannedCommand>,
+}
+
+/// Plans, hands off and renders; the exit code is the executor's when it failed, else the
+/// results'.
+pub fn run_remote(
+    executor: &str,
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    plans: &[RunPlan],
+) -> i32 {
+    let out_dir = new_run_dir(&run_env.cache_root(), repo_root, "remote");
+    let manifest = build_manifest(repo_root, args, run_env, plans, &out_dir);
+    let manifest_path = match write_manifest(&manifest, &out_dir) {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("headlamp: failed to write the remote run manifest: {err}");
+            return 1;
+        }
+    };
+    let executor_code = execute(executor, repo_root, &manifest_path, &out_dir);
+    let models = read_models(&out_dir);
+    if models.is_empty() {
+        eprintln!(
+            "headlamp: remote executor left no model*.json in {}",
+            out_dir.display()
+        );
+        return pruned(
+            args,
+            &out_dir,
+            if executor_code == 0 { 1 } else { executor_code },
+        );
+    }
+    let results_code = render_results(repo_root, args, &models, &out_dir);
+    let code = if executor_code != 0 {
+        executor_code
+    } else {
+        results_code
+    };
+    pruned(args, &out_dir, code)
+}
+
+/// Once the results are out, older runs' dirs go unless `--keep-artifacts`.
+fn pruned(args: &ParsedArgs, out_dir: &Path, code: i32) -> i32 {
+    if !args.keep_artifacts {
+        prune_run_dirs(out_dir);
+    }
+    code
+}
+
+pub fn build_manifest(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    plans: &[RunPlan],
+    out_dir: &Path,
+) -> RunManifest {
+    let runs = plans
+        .iter()
+        .map(|(runner, run_root, run_args)| ManifestRun {
+            runner: runner.label().to_string(),
+            run_root: run_root.to_string_lossy().to_string(),
+            selection_paths: run_args.selection_paths.clone(),
+            commands: crate::print_commands::plan_commands(|| {
+                crate::runner::run_once(*runner, run_root, run_args, run_env);
+            }),
+        })
+        .collect();
+    RunManifest {
+        version: MANIFEST_VERSION,
+        repo_root: repo_root.to_string_lossy().to_string(),
+        out_dir: out_dir.to_string_lossy().to_string(),
+        coverage: args.collect_coverage,
+        env: run_env.pairs().into_iter().collect(),
+        runs,
+    }
+}
+
+pub fn write_manifest(manifest: &RunManifest, out_dir: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(out_dir)?;
+    let path = out_dir.join("manifest.json");
+    let json = serde_json::to_vec_pretty(manifest).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Executor output goes to stderr, like hook output, so it never mixes into the report.
+fn execute(executor: &str, repo_root: &Path, manifest_path: &Path, out_dir: &Path) -> i32 {
+    let result = crate::run::shell_expression(executor)
+        .dir(repo_root)
+        .env("HEADLAMP_REMOTE_MANIFEST", manifest_path)
+        .env("HEADLAMP_REMOTE_OUT", out_dir)
+        .stdout_to_stderr()
+        .unchecked()
+        .run();
+    match result {
+        Ok(output) => output.status.code().unwrap_or(1),
+        Err(err) => {
+            eprintln!("headlamp: failed to start remote executor `{executor}`: {err}");
+            1
+        }
+    }
+}
+
+/// `model*.json` in name order; a file that is not a model only warns.
+pub fn read_models(out_dir: &Path) -> Vec<TestRunModel> {
+    let mut paths = std::fs::read_dir(out_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("model") && name.ends_with(".json"))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| serde_json::from_str(&text).map_err(|err| err.to_string()));
+            parsed
+                .inspect_err(|err| eprintln!("headlamp: skipping {}: {err}", path.display()))
+                .ok()
+        })
+        .collect()
+}
+
+fn render_results(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    models: &[TestRunModel],
+    out_dir: &Path,
+) -> i32 {
+    let failed = models.iter().any(|model| !model.aggregated.success);
+    let ctx = crate::format::ctx::make_ctx_for_args(repo_root, args, failed);
+    for model in models {
+        crate::recorded::record_failed_tests(model);
+        println!(
+            "{}",
+            crate::format::vitest::render_vitest_from_test_model(model, &ctx, args.only_failures)
+        );
+    }
+    let code = i32::from(failed);
+    let lcov_path = out_dir.join("lcov.info");
+    if !args.collect_coverage || !lcov_path.is_file() {
+        return code;
+    }
+    let Some(report) = crate::coverage::lcov::read_lcov_filtered_from_path(
+        repo_root,
+        &lcov_path,
+        &args.include_globs,
+        &args.exclude_globs,
+    ) else {
+        return code;
+    };
+    crate::coverage::last_run::save_last_run_coverage(repo_root, "remote", &report);
+    crate::coverage::print::print_run_coverage(repo_root, args, report, code)
+}
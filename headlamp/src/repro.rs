@@ -0,0 +1,98 @@
+//! Ready-to-paste commands that re-run a single test. Runner adapters store them on
+//! `TestCaseResult::repro` while building the model; the failure block prints them.
+
+/// Leaves plain words alone and single-quotes everything else for POSIX shells.
+pub fn shell_quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        return text.to_string();
+    }
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// `-t` is a regex over the full test name, so the name is escaped and anchored.
+pub fn jest(config: Option<&str>, test_path: &str, full_name: &str) -> String {
+    let config = config
+        .map(|config| format!(" --config {}", shell_quote(config)))
+        .unwrap_or_default();
+    let name_pattern = format!("^{}$", regex::escape(full_name));
+    format!(
+        "npx jest{config} {} -t {}",
+        shell_quote(test_path),
+        shell_quote(&name_pattern)
+    )
+}
+
+pub fn pytest(nodeid: &str) -> String {
+    format!("pytest {}", shell_quote(nodeid))
+}
+
+/// `binary_id` is nextest's `crate::binary` (just `crate` for a library's unit tests).
+pub fn nextest(binary_id: &str, test_name: &str) -> String {
+    let filter = format!("binary_id({binary_id}) & test(={test_name})");
+    format!("cargo nextest run -E {}", shell_quote(&filter))
+}
+
+/// `suite_source_path` is the repo-relative target source (`src/lib.rs`, `crates/api/tests/http.rs`);
+/// a package below the repo root is selected with `--manifest-path`.
+pub fn cargo_test(suite_source_path: &str, test_name: &str) -> String {
+    let (package_dir, target_path) = split_package_dir(suite_source_path);
+    let manifest = package_dir
+        .map(|dir| {
+            format!(
+                " --manifest-path {}",
+                shell_quote(&format!("{dir}/Cargo.toml"))
+            )
+        })
+        .unwrap_or_default();
+    let target = cargo_target_flag(target_path)
+        .map(|flag| format!(" {flag}"))
+        .unwrap_or_default();
+    format!(
+        "cargo test{manifest}{target} -- --exact {}",
+        shell_quote(test_name)
+    )
+}
+
+/// Doctest names look like `src/lib.rs - parse (line 12)`.
+pub fn cargo_doctest(rustdoc_name: &str) -> String {
+    format!("cargo test --doc -- --exact {}", shell_quote(rustdoc_name))
+}
+
+const TARGET_DIRS: [&str; 4] = ["src/", "tests/", "benches/", "examples/"];
+
+fn split_package_dir(path: &str) -> (Option<&str>, &str) {
+    let path = path.trim_start_matches("./");
+    if TARGET_DIRS.iter().any(|dir| path.starts_with(dir)) {
+        return (None, path);
+    }
+    TARGET_DIRS
+        .iter()
+        .filter_map(|dir| path.rfind(&format!("/{dir}")))
+        .max()
+        .map_or((None, path), |slash| {
+            (Some(&path[..slash]), &path[slash + 1..])
+        })
+}
+
+fn cargo_target_flag(target_path: &str) -> Option<String> {
+    let (dir, rest) = target_path.split_once('/')?;
+    let name = rest
+        .strip_suffix("/main.rs")
+        .or_else(|| rest.strip_suffix(".rs"))
+        .unwrap_or(rest);
+    match dir {
+        "src" if rest == "main.rs" => Some("--bins".to_string()),
+        "src" => match name.strip_prefix("bin/") {
+            Some(bin) => Some(format!("--bin {bin}")),
+            None => Some("--lib".to_string()),
+        },
+        "tests" => Some(format!("--test {name}")),
+        "benches" => Some(format!("--bench {name}")),
+        "examples" => Some(format!("--example {name}")),
+        _ => None,
+    }
+}
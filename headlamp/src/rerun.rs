@@ -0,0 +1,228 @@
+//! `--rerun-failed`: replays the failed tests of a saved run. The report is a test model JSON
+//! (e.g. the jest bridge output) or an `--artifacts-dir` `index.json`, whose reports are all read.
+
+use std::path::{Component, Path, PathBuf};
+
+use indexmap::IndexSet;
+use path_slash::PathExt;
+
+use crate::args::ParsedArgs;
+use crate::run::RunError;
+use crate::runner_detect::{DetectedLanguage, detect_languages, language_for_path};
+use crate::session::artifacts::{ArtifactKind, INDEX_FILE, read_index};
+use crate::test_model::TestRunModel;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedTest {
+    /// Repo-relative whenever the report's path can be found below the repo root.
+    pub test_file_path: String,
+    /// `None` when the file failed as a whole, e.g. it did not load.
+    pub full_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RerunRunner {
+    Jest,
+    Pytest,
+    CargoTest,
+    CargoNextest,
+    Headlamp,
+    /// A `plugin:<name>` runner: files plus jest's `-t` name pattern.
+    Plugin,
+}
+
+impl RerunRunner {
+    /// `None` for plugins, which may run any language.
+    fn language(self) -> Option<DetectedLanguage> {
+        match self {
+            Self::Jest => Some(DetectedLanguage::JavaScript),
+            Self::Pytest => Some(DetectedLanguage::Python),
+            Self::CargoTest | Self::CargoNextest | Self::Headlamp => Some(DetectedLanguage::Rust),
+            Self::Plugin => None,
+        }
+    }
+}
+
+/// A directory is read as an artifacts dir, i.e. through its `index.json`.
+pub fn load_failed_tests(repo_root: &Path, report: &Path) -> Result<Vec<FailedTest>, RunError> {
+    Ok(load_report_models(report)?
+        .iter()
+        .flat_map(|model| failed_in_model(repo_root, model))
+        .collect())
+}
+
+/// The models of a test model JSON, or of every report in an artifacts dir (or its `index.json`).
+pub fn load_report_models(report: &Path) -> Result<Vec<TestRunModel>, RunError> {
+    let report = if report.is_dir() {
+        report.join(INDEX_FILE)
+    } else {
+        report.to_path_buf()
+    };
+    if report.file_name().is_some_and(|name| name == INDEX_FILE) {
+        let dir = report.parent().unwrap_or(Path::new("."));
+        read_index(&report)?
+            .into_iter()
+            .filter(|entry| entry.kind == ArtifactKind::Report && entry.path.ends_with(".json"))
+            .map(|entry| read_model(&dir.join(entry.path)))
+            .collect()
+    } else {
+        Ok(vec![read_model(&report)?])
+    }
+}
+
+/// The languages of the failed files, which pick the runners when `--runner` is not given.
+pub fn failed_languages(failed: &[FailedTest]) -> Vec<DetectedLanguage> {
+    let paths = failed
+        .iter()
+        .map(|test| PathBuf::from(&test.test_file_path))
+        .collect::<Vec<_>>();
+    detect_languages(&paths)
+}
+
+/// `args` narrowed to the failed tests `runner` can run; everything else on the command line is
+/// kept. When none of the failed files belong to the runner (say, an explicit `--runner`), all of
+/// them are used.
+pub fn rerun_args(args: &ParsedArgs, failed: &[FailedTest], runner: RerunRunner) -> ParsedArgs {
+    let own = failed
+        .iter()
+        .filter(|test| {
+            runner.language().is_none_or(|language| {
+                language_for_path(Path::new(&test.test_file_path)) == Some(language)
+            })
+        })
+        .collect::<Vec<_>>();
+    let failed = if own.is_empty() {
+        failed.iter().collect()
+    } else {
+        own
+    };
+    let mut out = ParsedArgs {
+        changed: None,
+        ..args.clone()
+    };
+    match runner {
+        RerunRunner::Jest | RerunRunner::Plugin => {
+            out.selection_paths = unique(failed.iter().map(|t| t.test_file_path.clone()));
+            out.selection_specified = true;
+            if let Some(pattern) = jest_name_pattern(&failed) {
+                out.runner_args.extend(["-t".to_string(), pattern]);
+            }
+        }
+        RerunRunner::Pytest => {
+            out.selection_paths = unique(failed.iter().map(|t| match &t.full_name {
+                Some(name) => format!("{}::{name}", t.test_file_path),
+                None => t.test_file_path.clone(),
+            }));
+            out.selection_specified = true;
+        }
+        RerunRunner::CargoTest | RerunRunner::CargoNextest | RerunRunner::Headlamp => {
+            if let Some(names) = exact_names(&failed) {
+                add_rust_name_filter(&mut out.runner_args, runner, &names);
+            }
+        }
+    }
+    out
+}
+
+fn read_model(path: &Path) -> Result<TestRunModel, RunError> {
+    let raw = std::fs::read_to_string(path).map_err(RunError::Io)?;
+    serde_json::from_str::<TestRunModel>(&raw)
+        .map_err(|e| RunError::Io(std::io::Error::other(format!("{}: {e}", path.display()))))
+}
+
+fn failed_in_model(repo_root: &Path, model: &TestRunModel) -> Vec<FailedTest> {
+    model
+        .test_results
+        .iter()
+        .flat_map(|suite| {
+            let test_file_path = localize_path(repo_root, &suite.test_file_path);
+            let mut names = suite
+                .test_results
+                .iter()
+                .filter(|case| case.status == "failed")
+                .map(|case| Some(case.full_name.clone()))
+                .collect::<Vec<_>>();
+            if names.is_empty() && suite.status == "failed" {
+                names.push(None);
+            }
+            names.into_iter().map(move |full_name| FailedTest {
+                test_file_path: test_file_path.clone(),
+                full_name,
+            })
+        })
+        .collect()
+}
+
+/// A report from CI carries that machine's absolute paths, so the longest suffix that exists
+/// below `repo_root` stands in for them.
+pub(crate) fn localize_path(repo_root: &Path, path: &str) -> String {
+    let report_path = Path::new(path);
+    if let Ok(relative) = report_path.strip_prefix(repo_root) {
+        return relative.to_slash_lossy().to_string();
+    }
+    if report_path.is_relative() {
+        return path.to_string();
+    }
+    let parts = report_path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect::<Vec<_>>();
+    (0..parts.len())
+        .map(|skip| parts[skip..].iter().collect::<PathBuf>())
+        .find(|relative| repo_root.join(relative).exists())
+        .map(|relative| relative.to_slash_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn unique(items: impl Iterator<Item = String>) -> Vec<String> {
+    items.collect::<IndexSet<_>>().into_iter().collect()
+}
+
+/// `-t` matches the full test name; a file that failed as a whole still loads under it.
+fn jest_name_pattern(failed: &[&FailedTest]) -> Option<String> {
+    let names = unique(
+        failed
+            .iter()
+            .filter_map(|t| t.full_name.as_deref())
+            .map(regex::escape),
+    );
+    (!names.is_empty()).then(|| format!("^({})$", names.join("|")))
+}
+
+/// A Rust suite that failed as a whole (say, a target that did not build) drops the name filter.
+fn exact_names(failed: &[&FailedTest]) -> Option<Vec<String>> {
+    let names = failed
+        .iter()
+        .map(|t| t.full_name.clone())
+        .collect::<Option<Vec<_>>>()?;
+    let names = unique(names.into_iter());
+    (!names.is_empty()).then_some(names)
+}
+
+/// `cargo test` takes libtest args after `--`, the headlamp runner takes them directly, and
+/// nextest gets a filterset ahead of any `--`.
+fn add_rust_name_filter(runner_args: &mut Vec<String>, runner: RerunRunner, names: &[String]) {
+    match runner {
+        RerunRunner::CargoNextest => {
+            let expr = names
+                .iter()
+                .map(|name| format!("test(={name})"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            let at = runner_args
+                .iter()
+                .position(|t| t == "--")
+                .unwrap_or(runner_args.len());
+            runner_args.splice(at..at, ["-E".to_string(), expr]);
+        }
+        RerunRunner::CargoTest if !runner_args.iter().any(|t| t == "--") => {
+            runner_args.push("--".to_string());
+            runner_args.push("--exact".to_string());
+            runner_args.extend(names.iter().cloned());
+        }
+        _ => {
+            runner_args.push("--exact".to_string());
+            runner_args.extend(names.iter().cloned());
+        }
+    }
+}
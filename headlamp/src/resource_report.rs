@@ -0,0 +1,96 @@
+//! `--resource-report`: what each runner invocation cost (peak memory, CPU time, processes),
+//! printed after the run, as a table or as JSON.
+
+use serde::Serialize;
+
+use crate::args::ParsedArgs;
+use crate::process::RunnerResources;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceReportFormat {
+    Human,
+    Json,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    resources: &'a [RunnerResources],
+}
+
+pub fn print_resource_report_if_requested(args: &ParsedArgs) {
+    let Some(format) = args.resource_report else {
+        return;
+    };
+    let resources = crate::recorded::recorded_runs().resources;
+    crate::format::report_out::println(render_resource_report(&resources, format));
+}
+
+pub fn render_resource_report(
+    resources: &[RunnerResources],
+    format: ResourceReportFormat,
+) -> String {
+    match format {
+        ResourceReportFormat::Json => {
+            serde_json::to_string_pretty(&JsonReport { resources }).unwrap_or_default()
+        }
+        ResourceReportFormat::Human => render_table(resources),
+    }
+}
+
+fn render_table(resources: &[RunnerResources]) -> String {
+    let header = [
+        "runner".to_string(),
+        "max RSS".to_string(),
+        "user CPU".to_string(),
+        "system CPU".to_string(),
+        "processes".to_string(),
+    ];
+    let rows = resources
+        .iter()
+        .map(|entry| {
+            [
+                entry.runner.clone(),
+                format_bytes(entry.usage.max_rss_bytes),
+                format_millis(entry.usage.user_cpu_ms),
+                format_millis(entry.usage.system_cpu_ms),
+                entry.usage.processes.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let widths = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let lines = std::iter::once(&header).chain(&rows).map(|row| {
+        row.iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    });
+    std::iter::once("Resources".to_string())
+        .chain(lines)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    match bytes {
+        0 => "-".to_string(),
+        _ => format!("{:.1} MiB", bytes as f64 / MIB),
+    }
+}
+
+fn format_millis(millis: u64) -> String {
+    format!("{:.2}s", millis as f64 / 1000.0)
+}
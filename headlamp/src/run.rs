@@ -1,6 +1,9 @@
 use std::path::Path;
+use std::time::Duration;
 
+use crate::args::ParsedArgs;
 use crate::format::time::format_duration;
+use crate::process::RunEnvironment;
 use duct::cmd as duct_cmd;
 use thiserror::Error;
 
@@ -26,34 +29,151 @@ pub enum RunError {
 
     #[error("bootstrap failed: {command}")]
     BootstrapFailed { command: String },
+
+    #[error("{hook} hook failed: {command}")]
+    HookFailed { hook: String, command: String },
 }
 
-pub fn run_bootstrap(repo_root: &Path, raw: &str) -> Result<(), RunError> {
-    let raw_cmd = raw.trim();
-    if raw_cmd.is_empty() {
-        return Ok(());
+/// The worst exit code of `runs`, taken in order; with `bail`, none run after the first failure.
+pub fn worst_exit_code(runs: impl Iterator<Item = i32>, bail: bool) -> i32 {
+    let mut worst = 0;
+    for code in runs {
+        worst = worst.max(code);
+        if bail && code != 0 {
+            break;
+        }
     }
+    worst
+}
 
-    let status = if raw_cmd.contains(char::is_whitespace) {
-        if cfg!(windows) {
-            duct_cmd("cmd.exe", ["/d", "/s", "/c", raw_cmd])
-                .dir(repo_root)
-                .unchecked()
-                .run()
-        } else {
-            duct_cmd("bash", ["-lc", raw_cmd])
-                .dir(repo_root)
-                .unchecked()
-                .run()
-        }
+/// `--fail-on-skipped`: a passing run that skipped or left todo any test exits 1 instead.
+pub fn apply_fail_on_skipped(
+    args: &ParsedArgs,
+    results: &crate::recorded::RecordedResults,
+    code: i32,
+) -> i32 {
+    if !args.fail_on_skipped || results.skipped == 0 || code != 0 {
+        return code;
+    }
+    eprintln!(
+        "headlamp: {} skipped test(s) and --fail-on-skipped is set",
+        results.skipped
+    );
+    1
+}
+
+/// The exit code of a `--fail-if-no-tests` run that ran no tests at all.
+pub const NO_TESTS_EXIT_CODE: i32 = 4;
+
+/// `--fail-if-no-tests`: a passing run in which no test ran exits with `NO_TESTS_EXIT_CODE`,
+/// after saying which selection inputs left nothing to run.
+pub fn apply_fail_if_no_tests(
+    args: &ParsedArgs,
+    results: &crate::recorded::RecordedResults,
+    code: i32,
+) -> i32 {
+    let ran_tests = results.passed + results.failed + results.skipped > 0;
+    if !args.fail_if_no_tests || ran_tests || code != 0 {
+        return code;
+    }
+    eprintln!("headlamp: no tests were selected and --fail-if-no-tests is set");
+    no_tests_explanation(args)
+        .iter()
+        .for_each(|line| eprintln!("  {line}"));
+    NO_TESTS_EXIT_CODE
+}
+
+/// The selection inputs of `args`, one per line, for a run that selected nothing.
+pub fn no_tests_explanation(args: &ParsedArgs) -> Vec<String> {
+    let list = |items: &[String]| match items {
+        [] => "(none)".to_string(),
+        _ => items.join(" "),
+    };
+    let mut lines = vec![format!("paths: {}", list(&args.selection_paths))];
+    if let Some(mode) = args.changed {
+        lines.push(format!(
+            "changed: {}",
+            crate::cargo::selection::changed_mode_to_cli_string(mode)
+        ));
+    }
+    let filters = [
+        ("packages", &args.packages),
+        ("excluded packages", &args.exclude_packages),
+        ("runner args", &args.runner_args),
+    ];
+    lines.extend(
+        filters
+            .into_iter()
+            .filter(|(_, values)| !values.is_empty())
+            .map(|(label, values)| format!("{label}: {}", list(values))),
+    );
+    lines
+}
+
+/// A free-form command line run through the platform shell.
+pub fn shell_expression(raw_cmd: &str) -> duct::Expression {
+    let argv = shell_argv(raw_cmd);
+    duct_cmd(argv[0], &argv[1..])
+}
+
+fn shell_argv(raw_cmd: &str) -> Vec<&str> {
+    if cfg!(windows) {
+        vec!["cmd.exe", "/d", "/s", "/c", raw_cmd]
+    } else {
+        vec!["bash", "-lc", raw_cmd]
+    }
+}
+
+/// A bootstrap command with spaces is a shell line; a bare word is an npm script.
+fn bootstrap_argv(raw_cmd: &str) -> Vec<&str> {
+    if raw_cmd.contains(char::is_whitespace) {
+        shell_argv(raw_cmd)
     } else {
         let npm = if cfg!(windows) { "npm.cmd" } else { "npm" };
-        duct_cmd(npm, ["run", "-s", raw_cmd])
-            .dir(repo_root)
-            .unchecked()
-            .run()
+        vec![npm, "run", "-s", raw_cmd]
+    }
+}
+
+/// Everything between argument parsing and the first test: `--bootstrap-command`, then the
+/// `--wait-for` readiness probes.
+pub fn bootstrap_and_wait(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    env: &RunEnvironment,
+) -> Result<(), RunError> {
+    if let Some(command) = args.bootstrap_command.as_deref() {
+        run_bootstrap(repo_root, command, env)?;
+    }
+    if args.wait_for.is_empty() || crate::print_commands::is_active() {
+        return Ok(());
     }
-    .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
+    let timeout = args.wait_timeout_ms.map_or(
+        crate::readiness::DEFAULT_WAIT_TIMEOUT,
+        Duration::from_millis,
+    );
+    crate::readiness::wait_for_targets(&args.wait_for, timeout)
+}
+
+/// The bootstrap command sees the same environment as the runners it prepares for.
+pub fn run_bootstrap(repo_root: &Path, raw: &str, env: &RunEnvironment) -> Result<(), RunError> {
+    let raw_cmd = raw.trim();
+    if raw_cmd.is_empty() {
+        return Ok(());
+    }
+    let argv = bootstrap_argv(raw_cmd);
+    let mut planned = std::process::Command::new(argv[0]);
+    planned.args(&argv[1..]).current_dir(repo_root);
+    env.apply(&mut planned);
+    if crate::print_commands::intercept(&planned) {
+        return Ok(());
+    }
+
+    let status = env
+        .apply_to_expression(duct_cmd(argv[0], &argv[1..]))
+        .dir(repo_root)
+        .unchecked()
+        .run()
+        .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
 
     status
         .status
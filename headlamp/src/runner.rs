@@ -0,0 +1,395 @@
+//! The runners headlamp drives, and one run of one of them: what the CLI does per runner, and
+//! what `api::run` embeds.
+
+use std::path::{Path, PathBuf};
+
+use crate::args::ParsedArgs;
+use crate::process::{RunEnvironment, RunnerResources, UsageScope};
+use crate::recorded::{RecordedResults, RecordedRuns};
+use crate::rerun::RerunRunner;
+use crate::run::RunError;
+use crate::runner_detect::DetectedLanguage;
+use crate::session::RunSession;
+use crate::session::artifacts::ArtifactKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    Jest,
+    Pytest,
+    Headlamp,
+    CargoTest,
+    CargoNextest,
+    /// `plugin:<name>`; the name lives for the whole process.
+    Plugin(&'static str),
+}
+
+impl Runner {
+    /// A `--runner` value.
+    pub fn parse(raw: &str) -> Option<Self> {
+        if let Some(name) = raw
+            .trim()
+            .strip_prefix("plugin:")
+            .filter(|name| !name.is_empty())
+        {
+            return Some(Self::Plugin(name.to_string().leak()));
+        }
+        Some(match raw.trim().to_ascii_lowercase().as_str() {
+            "jest" => Self::Jest,
+            "pytest" => Self::Pytest,
+            "headlamp" => Self::Headlamp,
+            "cargo-nextest" => Self::CargoNextest,
+            "cargo-test" => Self::CargoTest,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Jest => "jest",
+            Self::Pytest => "pytest",
+            Self::Headlamp => "headlamp",
+            Self::CargoTest => "cargo-test",
+            Self::CargoNextest => "cargo-nextest",
+            Self::Plugin(name) => name,
+        }
+    }
+
+    pub fn rerun_runner(self) -> RerunRunner {
+        match self {
+            Self::Jest => RerunRunner::Jest,
+            Self::Pytest => RerunRunner::Pytest,
+            Self::Headlamp => RerunRunner::Headlamp,
+            Self::CargoTest => RerunRunner::CargoTest,
+            Self::CargoNextest => RerunRunner::CargoNextest,
+            Self::Plugin(_) => RerunRunner::Plugin,
+        }
+    }
+
+    pub fn is_rust(self) -> bool {
+        matches!(self, Self::Headlamp | Self::CargoTest | Self::CargoNextest)
+    }
+
+    pub fn for_language(repo_root: &Path, language: DetectedLanguage) -> Self {
+        match language {
+            DetectedLanguage::JavaScript => Self::Jest,
+            DetectedLanguage::Python => Self::Pytest,
+            DetectedLanguage::Rust if has_cargo_nextest(repo_root) => Self::CargoNextest,
+            DetectedLanguage::Rust => Self::CargoTest,
+        }
+    }
+
+    /// `--workspace-root` when given, else the nearest pyproject for pytest and the repo root
+    /// (`--repo-root` or the git toplevel) for everything else.
+    pub fn run_root(self, cwd: &Path, args: &ParsedArgs) -> PathBuf {
+        let workspace_override = args
+            .workspace_root
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .map(|p| if p.is_absolute() { p } else { cwd.join(p) });
+        if let Some(p) = workspace_override {
+            return p;
+        }
+        match self {
+            Self::Pytest => crate::project::markers::find_pyproject_toml_root(cwd)
+                .unwrap_or_else(|| cwd.to_path_buf()),
+            _ => match args
+                .repo_root
+                .as_deref()
+                .filter(|root| !root.trim().is_empty())
+            {
+                Some(root) => crate::config::canonical_or_given(&cwd.join(root)),
+                None => crate::config::find_repo_root(cwd),
+            },
+        }
+    }
+}
+
+fn has_cargo_nextest(repo_root: &Path) -> bool {
+    std::process::Command::new("cargo")
+        .args(["nextest", "--version"])
+        .current_dir(repo_root)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Without `--runner`, `--changed` runs whichever runners the changed files call for; anything
+/// else keeps the jest default.
+pub fn detect_runners(repo_root: &Path, args: &ParsedArgs) -> Vec<Runner> {
+    let Some(mode) = args.changed else {
+        return vec![Runner::Jest];
+    };
+    let changed = crate::git::changed_files(repo_root, mode).unwrap_or_default();
+    let runners = crate::runner_detect::detect_languages(&changed)
+        .into_iter()
+        .map(|language| Runner::for_language(repo_root, language))
+        .collect::<Vec<_>>();
+    if runners.is_empty() {
+        return vec![Runner::Jest];
+    }
+    let labels = runners
+        .iter()
+        .copied()
+        .map(Runner::label)
+        .collect::<Vec<_>>();
+    eprintln!(
+        "headlamp: changed files select runners: {}",
+        labels.join(", ")
+    );
+    runners
+}
+
+/// A runner with its run root and own args (which differ only under `--rerun-failed`).
+pub type RunPlan = (Runner, PathBuf, ParsedArgs);
+
+/// A finished `run_plans`: what the CLI exits with, and what its runners recorded.
+#[derive(Debug, Clone)]
+pub struct FinishedRun {
+    pub exit_code: i32,
+    pub results: RecordedResults,
+    pub runs: RecordedRuns,
+}
+
+/// One headlamp run inside the hooks. Every planned runner runs even when an earlier one fails
+/// (unless `--bail`), then the `--check`s; the worst exit code wins, after the
+/// `--fail-on-skipped` / `--fail-if-no-tests` / untested-changes / duplicate policies.
+pub fn run_plans(
+    config_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    plans: &[RunPlan],
+) -> FinishedRun {
+    let recording = crate::recorded::begin();
+    let code = crate::hooks::run_with_hooks(config_root, args, run_env, || {
+        let runs = plans
+            .iter()
+            .map(|(runner, run_root, run_args)| run_once(*runner, run_root, run_args, run_env))
+            .chain(std::iter::once_with(|| {
+                crate::checks::run_checks(config_root, args, run_env)
+            }));
+        crate::run::worst_exit_code(runs, args.bail)
+    });
+    let results = recording.results();
+    let code = crate::run::apply_fail_on_skipped(args, &results, code);
+    let code = crate::run::apply_fail_if_no_tests(args, &results, code);
+    let code = crate::untested_changes::report_untested_changes(config_root, args, code);
+    FinishedRun {
+        exit_code: crate::duplicates::report_duplicates(args, &results, code),
+        results,
+        runs: recording.runs(),
+    }
+}
+
+/// One run of `runner` in its own session; a runner error is rendered as a failed suite.
+pub fn run_once(
+    runner: Runner,
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+) -> i32 {
+    crate::interrupt::reset_partial_summary();
+    let usage = UsageScope::begin();
+    let exit_code = run_in_session(runner, repo_root, args, run_env);
+    crate::recorded::record_resources(RunnerResources {
+        runner: runner.label().to_string(),
+        root: repo_root.to_string_lossy().to_string(),
+        usage: usage.finish(),
+    });
+    exit_code
+}
+
+fn run_in_session(
+    runner: Runner,
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+) -> i32 {
+    let session = match RunSession::new(args.keep_artifacts) {
+        Ok(session) => session.with_env(run_env.clone()),
+        Err(err) => return render_run_error(repo_root, args, runner, err),
+    };
+    let recorded_before = crate::recorded::recorded_runs().models.len();
+    crate::format::output_filter::start_run();
+    let exit_code = if runner.is_rust() && !args.feature_matrix.is_empty() {
+        crate::cargo::features::run_feature_matrix(args, |entry_args| {
+            run_runner(runner, repo_root, entry_args, &session)
+        })
+    } else {
+        run_runner(runner, repo_root, args, &session)
+    };
+    register_model_reports(runner, &session, recorded_before);
+    register_suite_logs(repo_root, &session, recorded_before);
+    collect_run_artifacts(repo_root, args, &session);
+    report_suppressed_output(args);
+    exit_code
+}
+
+/// `--verbose`: how many lines `--output-filter` and `--output-collapse` kept out of the run.
+fn report_suppressed_output(args: &ParsedArgs) {
+    let suppressed = crate::format::output_filter::suppressed_count();
+    if args.verbose && suppressed > 0 {
+        eprintln!("headlamp: suppressed {suppressed} line(s) matching output filters");
+    }
+}
+
+/// Every runner's results land in the session's reports in full (whatever
+/// `--max-reported-failures` left out of the terminal): runners that do not write a JSON
+/// report of their own get their final models written as one. Timings are zeroed under
+/// `--normalize-times`, in the runner's own reports too.
+fn register_model_reports(runner: Runner, session: &RunSession, recorded_before: usize) {
+    let own_reports = session
+        .artifacts()
+        .into_iter()
+        .filter(|artifact| {
+            artifact.kind == ArtifactKind::Report && artifact.runner == runner.label()
+        })
+        .collect::<Vec<_>>();
+    if !own_reports.is_empty() {
+        if crate::format::deterministic::times_normalized() {
+            own_reports
+                .iter()
+                .for_each(|artifact| normalize_report_times(&artifact.path));
+        }
+        return;
+    }
+    let models = crate::recorded::recorded_runs().models;
+    let dir = session.subdir("reports").join(runner.label());
+    models
+        .iter()
+        .skip(recorded_before)
+        .enumerate()
+        .for_each(|(index, model)| {
+            let mut model = model.clone();
+            crate::format::deterministic::normalize_model_times(&mut model);
+            let path = dir.join(format!("results-{index}.json"));
+            let written = std::fs::create_dir_all(&dir).and_then(|()| {
+                std::fs::write(&path, serde_json::to_vec(&model).unwrap_or_default())
+            });
+            if written.is_ok() {
+                session.register_artifact(ArtifactKind::Report, runner.label(), path);
+            }
+        });
+}
+
+/// Failing suites' console output, one log file each, which `collect_run_artifacts` copies to
+/// `logs/suites/` under `--artifacts-dir`.
+fn register_suite_logs(repo_root: &Path, session: &RunSession, recorded_before: usize) {
+    let models = crate::recorded::recorded_runs().models;
+    let models = models.get(recorded_before..).unwrap_or_default();
+    crate::format::suite_log::write_suite_logs(
+        &crate::format::suite_log::suite_logs_dir(),
+        repo_root,
+        models,
+    )
+    .into_iter()
+    .for_each(|path| session.register_artifact(ArtifactKind::Log, "suites", path));
+}
+
+/// Reports that are not a run model are left as they are.
+fn normalize_report_times(path: &Path) {
+    let Some(mut model) = std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<crate::test_model::TestRunModel>(&bytes).ok())
+    else {
+        return;
+    };
+    crate::format::deterministic::normalize_model_times(&mut model);
+    if let Ok(bytes) = serde_json::to_vec(&model) {
+        let _ = std::fs::write(path, bytes);
+    }
+}
+
+/// Runs before the session (and its temp dir) is dropped; a failure only warns.
+fn collect_run_artifacts(repo_root: &Path, args: &ParsedArgs, session: &RunSession) {
+    let Some(dir) = args.artifacts_dir.as_deref() else {
+        return;
+    };
+    let dest = repo_root.join(dir);
+    let collected = crate::session::artifacts::collect_artifacts(
+        &dest,
+        repo_root,
+        &session.artifacts(),
+        &args.artifact_globs,
+    );
+    if let Err(err) = collected {
+        eprintln!(
+            "headlamp: failed to collect artifacts into {}: {err}",
+            dest.display()
+        );
+    }
+}
+
+/// With `--coverage`, coverage is scoped to the selected packages (see `coverage::scope`), the
+/// repo's native thresholds join headlamp's under `--coverage-native-thresholds` (see
+/// `coverage::native_thresholds`), and an unchanged tree and selection reuse the last report
+/// instead of instrumenting again (see `coverage::cache`).
+fn run_runner(runner: Runner, repo_root: &Path, args: &ParsedArgs, session: &RunSession) -> i32 {
+    let args = &crate::coverage::scope::scoped_args(repo_root, args);
+    let args = &crate::coverage::native_thresholds::with_native_thresholds(repo_root, args);
+    if !crate::coverage::cache::applies(args) {
+        return run_runner_uncached(runner, repo_root, args, session);
+    }
+    let key = crate::coverage::cache::cache_key(runner, repo_root, args);
+    let cache_root = session.env().cache_root();
+    if let Some(report) = crate::coverage::cache::load(&cache_root, repo_root, runner, &key) {
+        let uninstrumented = ParsedArgs {
+            collect_coverage: false,
+            ..args.clone()
+        };
+        let exit_code = run_runner_uncached(runner, repo_root, &uninstrumented, session);
+        return crate::coverage::cache::replay(runner, repo_root, args, report, exit_code);
+    }
+    let recorded_before = crate::recorded::recorded_runs().coverage.len();
+    let exit_code = run_runner_uncached(runner, repo_root, args, session);
+    let reports = crate::recorded::recorded_runs()
+        .coverage
+        .into_iter()
+        .skip(recorded_before)
+        .collect::<Vec<_>>();
+    if !reports.is_empty() {
+        let report = crate::coverage::lcov::merge_reports(&reports, repo_root);
+        crate::coverage::cache::store(&cache_root, repo_root, runner, &key, report);
+    }
+    exit_code
+}
+
+fn run_runner_uncached(
+    runner: Runner,
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &RunSession,
+) -> i32 {
+    match runner {
+        Runner::Jest => crate::jest::run_jest(repo_root, args, session),
+        Runner::Pytest => crate::pytest::run_pytest(repo_root, args, session),
+        Runner::Headlamp => crate::rust_runner::run_headlamp_rust(repo_root, args, session),
+        // A debugger gets the test binaries themselves, whichever cargo runner was picked.
+        Runner::CargoTest | Runner::CargoNextest if args.debug.is_some() => {
+            let args = crate::cargo::libtest_args_for_binaries(args);
+            crate::rust_runner::run_headlamp_rust(repo_root, &args, session)
+        }
+        Runner::CargoTest => crate::cargo::run_cargo_test(repo_root, args, session),
+        Runner::CargoNextest => crate::cargo::run_cargo_nextest(repo_root, args, session),
+        Runner::Plugin(name) => crate::plugin::run_plugin(repo_root, name, args, session),
+    }
+    .unwrap_or_else(|err| render_run_error(repo_root, args, runner, err))
+}
+
+fn render_run_error(repo_root: &Path, args: &ParsedArgs, runner: Runner, err: RunError) -> i32 {
+    let ctx = crate::format::ctx::make_ctx_for_args(repo_root, args, true);
+    let suite_path = format!("headlamp/{}", runner.label());
+    let model = crate::format::infra_failure::build_infra_failure_test_run_model(
+        suite_path.as_str(),
+        "Test suite failed to run",
+        &err.to_string(),
+    );
+    crate::recorded::record_model(&model);
+    let rendered = crate::format::vitest::render_vitest_from_test_model(&model, &ctx, true);
+    if !rendered.trim().is_empty() {
+        crate::format::report_out::println(&rendered);
+    }
+    1
+}
@@ -0,0 +1,55 @@
+use std::path::{Path, PathBuf};
+
+/// The test ecosystems headlamp can pick a runner for from a changed file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DetectedLanguage {
+    JavaScript,
+    Python,
+    Rust,
+}
+
+pub fn language_for_path(path: &Path) -> Option<DetectedLanguage> {
+    let file_name = path.file_name()?.to_str()?;
+    if matches!(file_name, "Cargo.toml" | "Cargo.lock") {
+        return Some(DetectedLanguage::Rust);
+    }
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => DetectedLanguage::JavaScript,
+        "py" => DetectedLanguage::Python,
+        "rs" => DetectedLanguage::Rust,
+        _ => return None,
+    })
+}
+
+/// Test files by name: JS/TS `*.test.*` / `*.spec.*` and files under `__tests__`, Python
+/// `test_*.py` / `*_test.py`, and the `.rs` files of Rust `tests/` dirs.
+pub fn is_test_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let in_dir = |dir: &str| {
+        path.parent()
+            .and_then(|parent| parent.file_name())
+            .is_some_and(|parent| parent == dir)
+    };
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs") => {
+            name.contains(".test.") || name.contains(".spec.") || in_dir("__tests__")
+        }
+        Some("py") => name.starts_with("test_") || name.ends_with("_test.py"),
+        Some("rs") => in_dir("tests"),
+        _ => false,
+    }
+}
+
+/// Languages touched by `paths`, deduplicated, in a stable order (JS, Python, Rust).
+pub fn detect_languages(paths: &[PathBuf]) -> Vec<DetectedLanguage> {
+    let mut languages = paths
+        .iter()
+        .filter_map(|path| language_for_path(path))
+        .collect::<Vec<_>>();
+    languages.sort();
+    languages.dedup();
+    languages
+}
@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use crate::run::RunError;
+
+/// Instrumentation settings `cargo llvm-cov show-env` hands out for builds and test runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LlvmCovEnv {
+    pub(crate) rustflags: Vec<String>,
+    pub(crate) llvm_profile_file: Option<PathBuf>,
+    /// The remaining `CARGO_LLVM_COV*` variables, which `cargo llvm-cov report` expects to
+    /// have been set while the profiles were written.
+    pub(crate) extra: Vec<(String, String)>,
+}
+
+/// Parses `KEY="value"` / `KEY=value` lines as printed by `cargo llvm-cov show-env`.
+pub(crate) fn parse_show_env(output: &str) -> LlvmCovEnv {
+    let mut env = LlvmCovEnv::default();
+    for line in output.lines() {
+        let line = line.trim().trim_start_matches("export ");
+        let Some((key, raw_value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unquote(raw_value.trim());
+        match key.trim() {
+            "RUSTFLAGS" => {
+                env.rustflags = value.split_whitespace().map(str::to_string).collect();
+            }
+            "LLVM_PROFILE_FILE" => env.llvm_profile_file = Some(PathBuf::from(value)),
+            "RUSTDOCFLAGS" | "CARGO_TARGET_DIR" | "" => {}
+            other => env.extra.push((other.to_string(), value)),
+        }
+    }
+    env
+}
+
+fn unquote(raw: &str) -> String {
+    ['"', '\'']
+        .iter()
+        .find_map(|q| raw.strip_prefix(*q).and_then(|rest| rest.strip_suffix(*q)))
+        .unwrap_or(raw)
+        .to_string()
+}
+
+pub(crate) fn has_cargo_llvm_cov(repo_root: &Path) -> bool {
+    std::process::Command::new("cargo")
+        .args(["llvm-cov", "--version"])
+        .current_dir(repo_root)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+fn cargo_llvm_cov_command(repo_root: &Path, cargo_target_dir: &Path) -> std::process::Command {
+    let mut cmd = std::process::Command::new("cargo");
+    cmd.arg("llvm-cov");
+    cmd.current_dir(repo_root);
+    cmd.env("CARGO_TARGET_DIR", cargo_target_dir);
+    cmd
+}
+
+pub(crate) fn load_llvm_cov_env(
+    repo_root: &Path,
+    cargo_target_dir: &Path,
+) -> Result<LlvmCovEnv, RunError> {
+    let mut cmd = cargo_llvm_cov_command(repo_root, cargo_target_dir);
+    cmd.arg("show-env");
+    // Keep the user's RUSTFLAGS out of the output; they are appended separately.
+    cmd.env_remove("RUSTFLAGS");
+    let output = cmd.output().map_err(RunError::SpawnFailed)?;
+    if !output.status.success() {
+        return Err(RunError::CommandFailed {
+            message: format!(
+                "cargo llvm-cov show-env failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(parse_show_env(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Writes lcov and llvm-cov JSON to the same paths the instrument engine uses, so reporting
+/// does not depend on which engine produced them.
+pub(crate) fn export_llvm_cov_reports_via_cargo(
+    repo_root: &Path,
+    cargo_target_dir: &Path,
+    env: &LlvmCovEnv,
+    enable_branch_coverage: bool,
+    lcov_path: &Path,
+    llvm_cov_json_path: &Path,
+) -> Result<(), RunError> {
+    if let Some(parent) = lcov_path.parent() {
+        std::fs::create_dir_all(parent).map_err(RunError::Io)?;
+    }
+    for (format_flag, out_path) in [("--lcov", lcov_path), ("--json", llvm_cov_json_path)] {
+        let mut cmd = cargo_llvm_cov_command(repo_root, cargo_target_dir);
+        cmd.args(["report", format_flag, "--output-path"]);
+        cmd.arg(out_path);
+        if enable_branch_coverage {
+            cmd.arg("--branch");
+        }
+        env.extra.iter().for_each(|(key, value)| {
+            cmd.env(key, value);
+        });
+        let status = cmd.status().map_err(RunError::SpawnFailed)?;
+        if !status.success() {
+            return Err(RunError::CommandFailed {
+                message: format!("cargo llvm-cov report {format_flag} failed"),
+            });
+        }
+    }
+    Ok(())
+}
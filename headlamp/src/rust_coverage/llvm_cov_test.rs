@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use super::llvm_cov::parse_show_env;
+
+#[test]
+fn show_env_output_splits_into_rustflags_profile_file_and_extra_env() {
+    let env = parse_show_env(
+        "RUSTFLAGS=\"-C instrument-coverage --cfg=coverage\"\n\
+         LLVM_PROFILE_FILE=\"/t/llvm-cov-target/app-%p-%16m.profraw\"\n\
+         CARGO_LLVM_COV=1\n\
+         export CARGO_LLVM_COV_TARGET_DIR='/t'\n",
+    );
+    assert_eq!(
+        env.rustflags,
+        vec!["-C", "instrument-coverage", "--cfg=coverage"]
+    );
+    assert_eq!(
+        env.llvm_profile_file,
+        Some(PathBuf::from("/t/llvm-cov-target/app-%p-%16m.profraw"))
+    );
+    assert_eq!(
+        env.extra,
+        vec![
+            ("CARGO_LLVM_COV".to_string(), "1".to_string()),
+            ("CARGO_LLVM_COV_TARGET_DIR".to_string(), "/t".to_string()),
+        ]
+    );
+}
@@ -5,6 +5,13 @@ use headlamp_core::args::ParsedArgs;
 
 use crate::run::RunError;
 
+mod llvm_cov;
+#[cfg(test)]
+mod llvm_cov_test;
+mod orchestrator;
+
+pub(crate) use orchestrator::{RustCoverageRun, print_coverage_and_adjust_exit};
+
 pub(crate) struct RustCoveragePaths {
     pub(crate) lcov_path: PathBuf,
     pub(crate) llvm_cov_json_path: PathBuf,
@@ -56,18 +63,6 @@ pub(crate) fn coverage_rustflags() -> Vec<std::ffi::OsString> {
     ]
 }
 
-pub(crate) fn coverage_rustflags_with_branch_coverage(
-    enable_branch_coverage: bool,
-) -> Vec<std::ffi::OsString> {
-    if !enable_branch_coverage {
-        return coverage_rustflags();
-    }
-    let mut out = coverage_rustflags();
-    out.push(std::ffi::OsString::from("-Z"));
-    out.push(std::ffi::OsString::from("coverage-options=branch"));
-    out
-}
-
 pub(crate) fn append_rustflags(
     existing_rustflags: &str,
     additional: &[std::ffi::OsString],
@@ -371,5 +366,5 @@ pub(crate) fn should_abort_coverage_after_run(
 }
 
 pub(crate) fn should_collect_rust_coverage(args: &ParsedArgs) -> bool {
-    args.collect_coverage && args.coverage_ui != headlamp_core::config::CoverageUi::Jest
+    args.collect_coverage
 }
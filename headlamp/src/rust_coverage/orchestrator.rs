@@ -0,0 +1,235 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use headlamp_core::args::ParsedArgs;
+use headlamp_core::config::CoverageEngine;
+
+use crate::run::RunError;
+use crate::rust_runner::cargo_build::BuiltTestBinary;
+use crate::tool_install::{InstallRecipe, ensure_with_install};
+
+use super::llvm_cov::{
+    LlvmCovEnv, export_llvm_cov_reports_via_cargo, has_cargo_llvm_cov, load_llvm_cov_env,
+};
+use super::{RustCoveragePaths, rust_coverage_paths};
+
+enum ResolvedEngine {
+    Instrument,
+    LlvmCov(LlvmCovEnv),
+}
+
+/// One Rust coverage collection, shared by every Rust runner: it picks the engine, owns the
+/// profraw/profdata locations, instruments builds and test processes, and writes lcov plus
+/// llvm-cov JSON to the same paths whichever engine ran.
+pub(crate) struct RustCoverageRun {
+    engine: ResolvedEngine,
+    toolchain: String,
+    enable_branch_coverage: bool,
+    cargo_target_dir: PathBuf,
+    llvm_profile_prefix: &'static str,
+    pub(crate) paths: RustCoveragePaths,
+}
+
+impl RustCoverageRun {
+    pub(crate) fn start_if_enabled(
+        repo_root: &Path,
+        args: &ParsedArgs,
+        session: &crate::session::RunSession,
+        cargo_target_dir: &Path,
+        llvm_profile_prefix: &'static str,
+    ) -> Result<Option<Self>, RunError> {
+        if !super::should_collect_rust_coverage(args) {
+            return Ok(None);
+        }
+        let (toolchain, enable_branch_coverage) = super::choose_llvm_tools_toolchain(repo_root);
+        let engine = resolve_engine(repo_root, args, &toolchain, cargo_target_dir)?;
+        let run = Self {
+            engine,
+            toolchain,
+            enable_branch_coverage,
+            cargo_target_dir: cargo_target_dir.to_path_buf(),
+            llvm_profile_prefix,
+            paths: rust_coverage_paths(args.keep_artifacts, repo_root, session),
+        };
+        std::fs::create_dir_all(&run.paths.profraw_dir).map_err(RunError::Io)?;
+        run.purge_profiles();
+        super::purge_profile_artifacts(run.paths.profdata_path.parent().unwrap_or(repo_root));
+        Ok(Some(run))
+    }
+
+    pub(crate) fn purge_profiles(&self) {
+        super::purge_profile_artifacts(&self.paths.profraw_dir);
+        if let Some(dir) = self.llvm_cov_profile_dir() {
+            super::purge_profile_artifacts(&dir);
+        }
+    }
+
+    fn llvm_cov_profile_dir(&self) -> Option<PathBuf> {
+        let ResolvedEngine::LlvmCov(env) = &self.engine else {
+            return None;
+        };
+        env.llvm_profile_file
+            .as_deref()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+    }
+
+    pub(crate) fn rustflags(&self) -> Vec<OsString> {
+        let mut flags = match &self.engine {
+            ResolvedEngine::Instrument => super::coverage_rustflags(),
+            ResolvedEngine::LlvmCov(env) => env.rustflags.iter().map(OsString::from).collect(),
+        };
+        if self.enable_branch_coverage {
+            flags.extend(["-Z", "coverage-options=branch"].map(OsString::from));
+        }
+        flags
+    }
+
+    /// `phase` distinguishes build-time profiles (build scripts, proc macros) from test runs.
+    pub(crate) fn llvm_profile_file(&self, phase: &str) -> PathBuf {
+        if let ResolvedEngine::LlvmCov(env) = &self.engine
+            && let Some(pattern) = env.llvm_profile_file.as_ref()
+        {
+            return pattern.clone();
+        }
+        let prefix = format!("{}{phase}", self.llvm_profile_prefix);
+        super::llvm_profile_file_pattern(&self.paths.profraw_dir, &prefix)
+    }
+
+    /// Instruments a `cargo test` / `cargo nextest` invocation that compiles and runs tests.
+    pub(crate) fn apply_to_command(&self, cmd: &mut std::process::Command) {
+        let _ = std::fs::create_dir_all(&self.paths.profraw_dir);
+        cmd.env("LLVM_PROFILE_FILE", self.llvm_profile_file(""));
+        let existing = std::env::var("RUSTFLAGS").unwrap_or_default();
+        cmd.env(
+            "RUSTFLAGS",
+            super::append_rustflags(&existing, &self.rustflags()),
+        );
+        cmd.env("CARGO_INCREMENTAL", "0");
+        if let ResolvedEngine::LlvmCov(env) = &self.engine {
+            env.extra.iter().for_each(|(key, value)| {
+                cmd.env(key, value);
+            });
+        }
+    }
+
+    pub(crate) fn build_instrumented_binaries(
+        &self,
+        repo_root: &Path,
+        args: &ParsedArgs,
+        session: &crate::session::RunSession,
+        extra_cargo_args: &[String],
+    ) -> Result<Vec<BuiltTestBinary>, RunError> {
+        let build_profile_file = self.llvm_profile_file("-build");
+        let built =
+            crate::rust_runner::cargo_build::build_test_binaries_via_cargo_no_run_with_overrides(
+                repo_root,
+                args,
+                session,
+                extra_cargo_args,
+                &self.cargo_target_dir,
+                &self.rustflags(),
+                Some(build_profile_file.as_os_str()),
+            )?;
+        self.purge_profiles();
+        Ok(built)
+    }
+
+    pub(crate) fn export_reports(
+        &self,
+        repo_root: &Path,
+        objects: &[PathBuf],
+    ) -> Result<(), RunError> {
+        match &self.engine {
+            ResolvedEngine::Instrument => {
+                super::merge_profraw_dir_to_profdata(
+                    repo_root,
+                    &self.toolchain,
+                    &self.paths.profraw_dir,
+                    &self.paths.profdata_path,
+                )?;
+                super::export_llvm_cov_reports(
+                    repo_root,
+                    &self.toolchain,
+                    &self.paths.profdata_path,
+                    objects,
+                    &self.paths.lcov_path,
+                    &self.paths.llvm_cov_json_path,
+                )
+            }
+            ResolvedEngine::LlvmCov(env) => export_llvm_cov_reports_via_cargo(
+                repo_root,
+                &self.cargo_target_dir,
+                env,
+                self.enable_branch_coverage,
+                &self.paths.lcov_path,
+                &self.paths.llvm_cov_json_path,
+            ),
+        }
+    }
+}
+
+fn resolve_engine(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    toolchain: &str,
+    cargo_target_dir: &Path,
+) -> Result<ResolvedEngine, RunError> {
+    let llvm_tools_check = || super::ensure_llvm_tools_available(repo_root, toolchain);
+    let use_llvm_cov = match args.coverage_engine {
+        CoverageEngine::Instrument => false,
+        CoverageEngine::LlvmCov => true,
+        CoverageEngine::Auto => llvm_tools_check().is_err() && has_cargo_llvm_cov(repo_root),
+    };
+    if !use_llvm_cov {
+        let recipe = InstallRecipe::llvm_tools(toolchain);
+        ensure_with_install(repo_root, args, &recipe, llvm_tools_check)?;
+        return Ok(ResolvedEngine::Instrument);
+    }
+    let recipe = InstallRecipe::cargo_llvm_cov();
+    ensure_with_install(repo_root, args, &recipe, || {
+        has_cargo_llvm_cov(repo_root)
+            .then_some(())
+            .ok_or_else(|| RunError::MissingRunner {
+                runner: "cargo-llvm-cov".to_string(),
+                hint: format!(
+                    "expected `cargo llvm-cov` for --coverage-engine=llvm-cov; try `{}`",
+                    recipe.command_line()
+                ),
+            })
+    })?;
+    Ok(ResolvedEngine::LlvmCov(load_llvm_cov_env(
+        repo_root,
+        cargo_target_dir,
+    )?))
+}
+
+/// Prints the coverage table and folds threshold failures into the exit code. When coverage was
+/// requested but no lcov was written, the run fails instead of passing silently.
+pub(crate) fn print_coverage_and_adjust_exit(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    exit_code: i32,
+) -> i32 {
+    let normalized_exit_code = if exit_code == 0 { 0 } else { 1 };
+    if !args.collect_coverage {
+        return normalized_exit_code;
+    }
+    let thresholds_failed = crate::cargo::coverage::print_lcov(repo_root, args, session);
+    // `print_lcov` returns false both for "no thresholds failed" and for "could not print".
+    let lcov_path = rust_coverage_paths(args.keep_artifacts, repo_root, session).lcov_path;
+    if !thresholds_failed && !lcov_path.exists() {
+        eprintln!(
+            "headlamp: coverage was requested but Rust lcov was not generated (missing {}). \
+Install `llvm-tools-preview` (rustup) and re-run.",
+            lcov_path.to_string_lossy()
+        );
+        return 1;
+    }
+    if thresholds_failed {
+        1
+    } else {
+        normalized_exit_code
+    }
+}
@@ -94,7 +94,7 @@ fn peek_item_kind(source: &str, token_spans: &[TokenSpan], index: usize) -> Opti
         .then_some(RustItemKind::Other)
 }
 
-fn parse_outer_attribute(
+pub(super) fn parse_outer_attribute(
     source: &str,
     token_spans: &[TokenSpan],
     pound_index: usize,
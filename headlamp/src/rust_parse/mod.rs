@@ -1,10 +1,11 @@
 mod attrs;
 mod imports;
 mod lex;
+mod test_fns;
 mod types;
 mod util;
 
-pub use self::types::RustFileMarkers;
+pub use self::types::{RustFileMarkers, RustTestFn};
 
 pub fn extract_import_specs_from_source(source: &str) -> Vec<String> {
     imports::extract_import_specs_from_source(source)
@@ -13,3 +14,7 @@ pub fn extract_import_specs_from_source(source: &str) -> Vec<String> {
 pub fn classify_rust_file_markers(source: &str) -> RustFileMarkers {
     attrs::classify_rust_file_markers(source)
 }
+
+pub fn find_test_fns(source: &str) -> Vec<RustTestFn> {
+    test_fns::find_test_fns(source)
+}
@@ -0,0 +1,106 @@
+use rustc_lexer::TokenKind;
+
+use super::attrs::parse_outer_attribute;
+use super::lex::{is_trivia, lex_spans};
+use super::types::{RustTestFn, TokenSpan};
+use super::util::{is_ident_text, skip_trivia};
+
+#[derive(Debug, Clone)]
+enum Block {
+    Mod(String),
+    TestFn { name: String, start: usize },
+    Other,
+}
+
+pub(super) fn find_test_fns(source: &str) -> Vec<RustTestFn> {
+    let token_spans = lex_spans(source);
+    let mut found = vec![];
+    let mut blocks: Vec<Block> = vec![];
+    let mut pending_test: Option<usize> = None;
+    let mut next_block = Block::Other;
+    let mut index = 0usize;
+    while index < token_spans.len() {
+        let token = token_spans[index];
+        if is_trivia(token.kind) {
+            index += 1;
+            continue;
+        }
+        match token.kind {
+            TokenKind::Pound => {
+                if let Some((attr, next_index)) = parse_outer_attribute(source, &token_spans, index)
+                {
+                    if attr.is_test_marker {
+                        pending_test.get_or_insert(token.start);
+                    }
+                    index = next_index;
+                    continue;
+                }
+            }
+            TokenKind::OpenBrace => blocks.push(std::mem::replace(&mut next_block, Block::Other)),
+            TokenKind::CloseBrace => {
+                if let Some(Block::TestFn { name, start }) = blocks.pop() {
+                    found.push(test_fn(&blocks, name, start, token.end));
+                }
+            }
+            TokenKind::Semi => next_block = Block::Other,
+            _ => {
+                if let Some((block, next_index)) = item_block(source, &token_spans, index) {
+                    next_block = match block {
+                        Block::TestFn { name, start } => match pending_test.take() {
+                            Some(attr_start) => Block::TestFn {
+                                name,
+                                start: attr_start.min(start),
+                            },
+                            None => Block::Other,
+                        },
+                        block => block,
+                    };
+                    index = next_index;
+                    continue;
+                }
+            }
+        }
+        index += 1;
+    }
+    found
+}
+
+/// `mod <name>` or `fn <name>` at `index`, as the block its `{` would open.
+fn item_block(source: &str, token_spans: &[TokenSpan], index: usize) -> Option<(Block, usize)> {
+    let is_mod = is_ident_text(source, token_spans, index, "mod");
+    if !is_mod && !is_ident_text(source, token_spans, index, "fn") {
+        return None;
+    }
+    let name_index = skip_trivia(token_spans, index + 1);
+    let name_span = token_spans
+        .get(name_index)
+        .filter(|t| matches!(t.kind, TokenKind::Ident | TokenKind::RawIdent))?;
+    let name = source.get(name_span.start..name_span.end)?.to_string();
+    let block = if is_mod {
+        Block::Mod(name)
+    } else {
+        Block::TestFn {
+            name,
+            start: token_spans[index].start,
+        }
+    };
+    Some((block, name_index + 1))
+}
+
+fn test_fn(blocks: &[Block], name: String, start: usize, end: usize) -> RustTestFn {
+    let path = blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Mod(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .chain(std::iter::once(name.as_str()))
+        .collect::<Vec<_>>()
+        .join("::");
+    RustTestFn {
+        name,
+        path,
+        start,
+        end,
+    }
+}
@@ -6,6 +6,17 @@ pub struct RustFileMarkers {
     pub has_cfg_test: bool,
 }
 
+/// A `#[test]` (or `#[tokio::test]`, `#[rstest]`, ...) fn; offsets are bytes into the source,
+/// from its first test attribute to its closing brace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustTestFn {
+    pub name: String,
+    /// `name` behind the file's inline `mod`s, e.g. `tests::adds`.
+    pub path: String,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) struct TokenSpan {
     pub(super) kind: TokenKind,
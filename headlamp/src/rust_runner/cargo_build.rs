@@ -96,10 +96,11 @@ fn build_test_binaries_via_cargo_no_run_with_options(
     overrides: CargoNoRunBuildOverrides<'_>,
 ) -> Result<Vec<BuiltTestBinary>, RunError> {
     let mut cmd = build_cargo_no_run_command(repo_root, args, session, extra_cargo_args, overrides);
+    crate::print_commands::note(&cmd);
     let (mut child, stdout) = spawn_child_with_piped_stdout(&mut cmd)?;
-    let (mut out, debug) = parse_cargo_no_run_json_stdout(repo_root, args, stdout);
+    let (mut out, debug) = parse_cargo_no_run_json_stdout(repo_root, stdout);
     ensure_child_success(&mut child, "cargo test --no-run failed")?;
-    debug.maybe_print_summary(args);
+    debug.log_summary();
     out.sort_by(|a, b| a.executable.cmp(&b.executable));
     out.dedup_by(|a, b| a.executable == b.executable);
     Ok(out)
@@ -142,6 +143,7 @@ fn build_cargo_no_run_command(
             );
         }
     }
+    session.env().apply(&mut cmd);
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::inherit());
     cmd
@@ -169,52 +171,45 @@ struct CargoNoRunJsonDebugCounts {
 }
 
 impl CargoNoRunJsonDebugCounts {
-    fn maybe_print_sample_line(
+    fn log_sample_line(
         &mut self,
-        args: &ParsedArgs,
         executable: &str,
         message: &CargoMessage,
         is_test_profile: bool,
         is_test_kind: bool,
         is_custom_build: bool,
     ) {
-        if !args.verbose || self.seen_artifacts_with_executable >= 5 {
+        if self.seen_artifacts_with_executable >= 5 {
             return;
         }
         self.seen_artifacts_with_executable = self.seen_artifacts_with_executable.saturating_add(1);
-        eprintln!(
-            "headlamp(headlamp-rust): cargo artifact executable={} is_test_profile={} is_test_kind={} is_custom_build={} kind={:?} src_path={:?}",
+        let target = message.target.as_ref();
+        tracing::debug!(
+            target: "cargo",
             executable,
             is_test_profile,
             is_test_kind,
             is_custom_build,
-            message
-                .target
-                .as_ref()
-                .map(|t| t.kind.clone())
-                .unwrap_or_default(),
-            message
-                .target
-                .as_ref()
-                .map(|t| t.src_path.clone())
-                .unwrap_or_default(),
+            kind = ?target.map(|t| t.kind.clone()).unwrap_or_default(),
+            src_path = ?target.map(|t| t.src_path.clone()).unwrap_or_default(),
+            "cargo artifact"
         );
     }
 
-    fn maybe_print_summary(&self, args: &ParsedArgs) {
-        if !args.verbose {
-            return;
-        }
-        eprintln!(
-            "headlamp(headlamp-rust): cargo test --no-run json_lines={} compiler_artifacts={} artifacts_with_executable={} kept={}",
-            self.total_json_lines, self.compiler_artifacts, self.with_executable, self.kept
+    fn log_summary(&self) {
+        tracing::debug!(
+            target: "cargo",
+            json_lines = self.total_json_lines,
+            compiler_artifacts = self.compiler_artifacts,
+            artifacts_with_executable = self.with_executable,
+            kept = self.kept,
+            "cargo test --no-run finished"
         );
     }
 }
 
 fn parse_cargo_no_run_json_stdout(
     repo_root: &Path,
-    args: &ParsedArgs,
     stdout: std::process::ChildStdout,
 ) -> (Vec<BuiltTestBinary>, CargoNoRunJsonDebugCounts) {
     let mut debug = CargoNoRunJsonDebugCounts::default();
@@ -251,8 +246,7 @@ fn parse_cargo_no_run_json_stdout(
                 .iter()
                 .any(|k| k == "custom-build" || k == "build-script-build")
         });
-        debug.maybe_print_sample_line(
-            args,
+        debug.log_sample_line(
             executable,
             &message,
             is_test_profile,
@@ -0,0 +1,75 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::args::ParsedArgs;
+use crate::debug::{Debugger, display_command, run_attached};
+use crate::process::RunEnvironment;
+use crate::run::RunError;
+
+use super::index::TestBinary;
+
+/// `--debug` for Rust: each test binary's exact command, or (with `gdb`/`lldb`) a debugger
+/// session per binary. libtest runs single-threaded and uncaptured so breakpoints and prints
+/// line up with the test being stepped through.
+pub(super) fn debug_test_binaries(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    run_env: &RunEnvironment,
+    debugger: Debugger,
+    binaries: &[TestBinary],
+    filter: Option<&str>,
+) -> Result<i32, RunError> {
+    let binary_args = debug_binary_args(args, filter);
+    let launcher = match debugger {
+        Debugger::Gdb => Some(("rust-gdb", "--args")),
+        Debugger::Lldb => Some(("rust-lldb", "--")),
+        Debugger::Auto | Debugger::Pdb => None,
+    };
+    let Some((program, separator)) = launcher else {
+        for binary in binaries {
+            let mut command = super::test_binary_command(args, &binary.executable);
+            command.args(&binary_args);
+            println!(
+                "{}: {}",
+                binary.suite_source_path,
+                display_command(&command)
+            );
+        }
+        eprintln!("headlamp: debugger: run one of these under yours, or pass --debug=gdb|lldb");
+        return Ok(0);
+    };
+    let mut exit_code = 0;
+    for binary in binaries {
+        let mut command = Command::new(program);
+        command
+            .arg(separator)
+            .arg(&binary.executable)
+            .args(&binary_args)
+            .current_dir(repo_root)
+            .env("RUST_BACKTRACE", "1");
+        run_env.apply(&mut command);
+        exit_code = exit_code.max(run_attached(command)?);
+    }
+    Ok(exit_code)
+}
+
+fn debug_binary_args(args: &ParsedArgs, filter: Option<&str>) -> Vec<String> {
+    let mut out = filter
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(str::to_string)
+        .into_iter()
+        .collect::<Vec<_>>();
+    if !args.runner_args.iter().any(|t| t == "--test-threads") {
+        out.extend(["--test-threads".to_string(), "1".to_string()]);
+    }
+    if !args
+        .runner_args
+        .iter()
+        .any(|t| t == "--nocapture" || t == "--no-capture")
+    {
+        out.push("--nocapture".to_string());
+    }
+    out.extend(args.runner_args.iter().cloned());
+    out
+}
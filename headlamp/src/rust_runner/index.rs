@@ -33,7 +33,7 @@ pub(crate) fn load_or_build_binary_index(
     session: &crate::session::RunSession,
     selection: &CargoSelection,
 ) -> Result<Vec<TestBinary>, RunError> {
-    let cache_file = rust_cache_file(session);
+    let cache_file = rust_cache_file(session, args.target.as_deref());
     if args.no_cache {
         let built = build_and_persist(repo_root, args, session, selection, &cache_file)?;
         return Ok(built);
@@ -112,11 +112,22 @@ fn try_load_cache(
     Some(binaries)
 }
 
-fn rust_cache_file(session: &crate::session::RunSession) -> PathBuf {
-    let base = std::env::var_os("HEADLAMP_CACHE_DIR")
-        .map(PathBuf::from)
+/// Each `--target` keeps its own index so switching between host and cross builds doesn't
+/// evict the other's binaries.
+fn rust_cache_file(session: &crate::session::RunSession, target: Option<&str>) -> PathBuf {
+    let base = session
+        .env()
+        .cache_dir
+        .clone()
         .unwrap_or_else(|| session.subdir("cache"));
-    base.join("rust").join("binary_index.json")
+    let rust_dir = base.join("rust");
+    match target.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(triple) => rust_dir
+            .join("targets")
+            .join(triple)
+            .join("binary_index.json"),
+        None => rust_dir.join("binary_index.json"),
+    }
 }
 
 fn normalize_repo_root_key(repo_root: &Path) -> String {
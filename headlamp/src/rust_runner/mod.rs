@@ -2,11 +2,13 @@ use std::path::Path;
 
 use crate::args::ParsedArgs;
 use crate::live_progress::{LiveProgress, live_progress_mode};
+use crate::process::RunEnvironment;
 use crate::run::RunError;
 use crate::streaming::run_streaming_capture_tail_merged;
 
 pub(crate) mod cargo_build;
 mod coverage;
+mod debug;
 mod index;
 #[cfg(test)]
 mod libtest_parser;
@@ -14,53 +16,129 @@ mod libtest_parser;
 mod libtest_parser_test;
 mod stream_adapter;
 
+pub(crate) use stream_adapter::build_run_model;
+
 pub fn run_headlamp_rust(
     repo_root: &Path,
     args: &ParsedArgs,
     session: &crate::session::RunSession,
 ) -> Result<i32, RunError> {
     let started_at = std::time::Instant::now();
-    if args.collect_coverage {
+    // `--debug` prints (or launches) test binaries, so they must outlive the session's temp dir.
+    let args = &ParsedArgs {
+        keep_artifacts: args.keep_artifacts || args.debug.is_some(),
+        ..args.clone()
+    };
+    if crate::rust_coverage::should_collect_rust_coverage(args) {
         return coverage::run_headlamp_rust_with_coverage(repo_root, args, session);
     }
-    run_optional_bootstrap(repo_root, args)?;
+    crate::run::bootstrap_and_wait(repo_root, args, session.env())?;
 
     let changed_files = changed_files_for_args(repo_root, args)?;
     let selection =
-        crate::cargo::selection::derive_cargo_selection(repo_root, args, &changed_files);
+        crate::cargo::selection::derive_cargo_selection(repo_root, args, session, &changed_files)?;
 
-    let binaries = index::load_or_build_binary_index(repo_root, args, session, &selection)?;
+    let binaries = shuffled(
+        args,
+        index::load_or_build_binary_index(repo_root, args, session, &selection)?,
+    );
     if binaries.is_empty() {
         return Ok(0);
     }
 
     let libtest_filter = derive_libtest_filter(repo_root, args);
-    let live_progress = start_live_progress(args, binaries.len());
-    let (suite_models, exit_code) = run_test_binaries(
+    if let Some(debugger) = args.debug {
+        let run_env = session.env();
+        return debug::debug_test_binaries(
+            repo_root,
+            args,
+            run_env,
+            debugger,
+            &binaries,
+            libtest_filter.as_deref(),
+        );
+    }
+    let live_progress = start_live_progress(repo_root, args, binaries.len());
+    let run_env = crate::cargo::http_log::run_env_with_http_log(session);
+    let (mut suite_models, mut exit_code) = run_test_binaries(
         repo_root,
         args,
+        &run_env,
         live_progress,
         binaries,
         libtest_filter.as_deref(),
     )?;
+    crate::cargo::http_log::attach_http_events(&mut suite_models, &run_env);
+    if let Some(doctests) = run_doctests(
+        repo_root,
+        args,
+        session,
+        &selection.extra_cargo_args,
+        libtest_filter.as_deref(),
+    )? {
+        suite_models.extend(doctests.suites);
+        exit_code = exit_code.max(i32::from(doctests.exit_code != 0));
+    }
 
     let run_time_ms = started_at.elapsed().as_millis() as u64;
-    let _model = render_and_print_run_model(repo_root, args, suite_models, run_time_ms, exit_code);
+    let _model = render_and_print_run_model(
+        repo_root,
+        args,
+        suite_models,
+        selection.skipped_units(),
+        run_time_ms,
+        exit_code,
+    );
     Ok(exit_code)
 }
 
-fn start_live_progress(args: &ParsedArgs, total_units: usize) -> LiveProgress {
+/// Test binaries never contain doctests; rustdoc runs them and they are reported alongside.
+fn run_doctests(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &crate::session::RunSession,
+    extra_cargo_args: &[String],
+    libtest_filter: Option<&str>,
+) -> Result<Option<crate::cargo::doctest::DoctestPhaseOutput>, RunError> {
+    // Runner args are test-binary args here, while `cargo test` expects them after `--`.
+    let doc_args = ParsedArgs {
+        runner_args: std::iter::once("--".to_string())
+            .chain(args.runner_args.iter().cloned())
+            .collect(),
+        ..args.clone()
+    };
+    crate::cargo::doctest::run_doctest_phase(
+        repo_root,
+        &doc_args,
+        session,
+        extra_cargo_args,
+        libtest_filter,
+    )
+}
+
+/// `--shuffle` runs the binaries in the seed's order; libtest shuffles inside each one.
+fn shuffled(args: &ParsedArgs, mut binaries: Vec<index::TestBinary>) -> Vec<index::TestBinary> {
+    if let Some(seed) = args.shuffle_seed {
+        crate::shuffle::shuffle(&mut binaries, seed);
+    }
+    binaries
+}
+
+fn start_live_progress(repo_root: &Path, args: &ParsedArgs, total_units: usize) -> LiveProgress {
     let mode = live_progress_mode(
         crate::format::terminal::is_output_terminal(),
         args.ci,
         args.quiet,
     );
-    LiveProgress::start(total_units, mode)
+    let live_progress = LiveProgress::start_with_args(total_units, mode, args);
+    live_progress.load_unit_timings(repo_root);
+    live_progress
 }
 
 fn run_test_binaries(
     repo_root: &Path,
     args: &ParsedArgs,
+    run_env: &RunEnvironment,
     live_progress: LiveProgress,
     binaries: Vec<index::TestBinary>,
     libtest_filter: Option<&str>,
@@ -68,18 +146,22 @@ fn run_test_binaries(
     let use_libtest_json = crate::cargo::paths::nightly_rustc_exists(repo_root)
         && should_use_libtest_json_output(&args.runner_args);
     let test_binary_args = build_test_binary_args(args, libtest_filter, use_libtest_json);
+    let invocation = BinaryInvocation {
+        test_binary_args: &test_binary_args,
+        llvm_profile_file: None,
+        use_libtest_json,
+        run_env,
+    };
     let mut suite_models: Vec<crate::test_model::TestSuiteResult> = vec![];
     let mut exit_code: i32 = 0;
 
-    for binary in binaries {
+    for (index, binary) in binaries.iter().enumerate() {
         let (model, current_exit_code) = run_single_test_binary(
             repo_root,
             args,
             &live_progress,
-            &binary,
-            &test_binary_args,
-            None,
-            use_libtest_json,
+            (index, binary),
+            &invocation,
         )?;
         if current_exit_code != 0 {
             exit_code = 1;
@@ -93,44 +175,58 @@ fn run_test_binaries(
     Ok((suite_models, exit_code))
 }
 
+/// How every test binary of one run is invoked.
+#[derive(Debug)]
+struct BinaryInvocation<'a> {
+    test_binary_args: &'a [String],
+    llvm_profile_file: Option<&'a std::ffi::OsStr>,
+    use_libtest_json: bool,
+    run_env: &'a RunEnvironment,
+}
+
 fn run_single_test_binary(
     repo_root: &Path,
     args: &ParsedArgs,
     live_progress: &LiveProgress,
-    binary: &index::TestBinary,
-    test_binary_args: &[String],
-    llvm_profile_file: Option<&std::ffi::OsStr>,
-    use_libtest_json: bool,
+    (unit_index, binary): (usize, &index::TestBinary),
+    invocation: &BinaryInvocation<'_>,
 ) -> Result<(Option<crate::test_model::TestRunModel>, i32), RunError> {
-    let mut cmd = std::process::Command::new(&binary.executable);
+    let mut cmd = test_binary_command(args, &binary.executable);
     cmd.current_dir(repo_root);
     cmd.env("RUST_BACKTRACE", "1");
     cmd.env("RUST_LIB_BACKTRACE", "1");
-    if let Some(profile_file) = llvm_profile_file {
+    if let Some(profile_file) = invocation.llvm_profile_file {
         cmd.env("LLVM_PROFILE_FILE", profile_file);
     }
-    cmd.args(test_binary_args);
-
-    if use_libtest_json {
-        let mut adapter = stream_adapter::LibtestJsonAdapter::new(
-            repo_root,
-            args.only_failures,
-            binary.suite_source_path.as_str(),
-        );
-        let (exit_code, _tail) =
-            run_streaming_capture_tail_merged(cmd, live_progress, &mut adapter, 1024 * 1024)?;
-        live_progress.increment_done(1);
-        Ok((adapter.parser.finalize(), exit_code))
+    invocation.run_env.apply(&mut cmd);
+    cmd.args(invocation.test_binary_args);
+    live_progress.start_unit(unit_index, &binary.suite_source_path);
+    let suite_path = binary.suite_source_path.as_str();
+    // The lane closes whether or not the binary could be run.
+    let result = if invocation.use_libtest_json {
+        let mut adapter =
+            stream_adapter::LibtestJsonAdapter::new(repo_root, args.only_failures, suite_path);
+        run_streaming_capture_tail_merged(cmd, live_progress, &mut adapter, 1024 * 1024)
+            .map(|(exit_code, _tail)| (adapter.parser.finalize(), exit_code))
     } else {
-        let mut adapter = stream_adapter::DirectLibtestAdapter::new(
-            repo_root,
-            args.only_failures,
-            binary.suite_source_path.as_str(),
-        );
-        let (exit_code, _tail) =
-            run_streaming_capture_tail_merged(cmd, live_progress, &mut adapter, 1024 * 1024)?;
-        live_progress.increment_done(1);
-        Ok((adapter.parser.finalize(), exit_code))
+        let mut adapter =
+            stream_adapter::DirectLibtestAdapter::new(repo_root, args.only_failures, suite_path);
+        run_streaming_capture_tail_merged(cmd, live_progress, &mut adapter, 1024 * 1024)
+            .map(|(exit_code, _tail)| (adapter.parser.finalize(), exit_code))
+    };
+    live_progress.finish_unit(unit_index);
+    result
+}
+
+/// Cross-compiled binaries run through the target runner (qemu, a device flasher, ...).
+fn test_binary_command(args: &ParsedArgs, executable: &Path) -> std::process::Command {
+    match crate::cargo::target::target_runner_command(args) {
+        Some(runner) => {
+            let mut cmd = std::process::Command::new(&runner[0]);
+            cmd.args(&runner[1..]).arg(executable);
+            cmd
+        }
+        None => std::process::Command::new(executable),
     }
 }
 
@@ -138,32 +234,24 @@ fn render_and_print_run_model(
     repo_root: &Path,
     args: &ParsedArgs,
     suites: Vec<crate::test_model::TestSuiteResult>,
+    skipped_units: Vec<crate::test_model::SkippedUnit>,
     run_time_ms: u64,
     exit_code: i32,
 ) -> crate::test_model::TestRunModel {
-    let model = stream_adapter::build_run_model(suites, run_time_ms);
-    let ctx = crate::format::ctx::make_ctx(
-        repo_root,
-        None,
-        exit_code != 0,
-        args.show_logs,
-        args.editor_cmd.clone(),
-    );
+    let mut model = stream_adapter::build_run_model(suites, run_time_ms);
+    model.skipped_units = skipped_units;
+    crate::cargo::features::label_model_with_feature_set(args, &mut model);
+    crate::recorded::record_failed_tests(&model);
+    crate::recorded::record_duplicates(crate::duplicates::duplicate_test_names(&model));
+    let ctx = crate::format::ctx::make_ctx_for_args(repo_root, args, exit_code != 0);
     let rendered =
         crate::format::vitest::render_vitest_from_test_model(&model, &ctx, args.only_failures);
     if !rendered.trim().is_empty() {
-        println!("{rendered}");
+        crate::format::report_out::println(&rendered);
     }
     model
 }
 
-fn run_optional_bootstrap(repo_root: &Path, args: &ParsedArgs) -> Result<(), RunError> {
-    let Some(command) = args.bootstrap_command.as_deref() else {
-        return Ok(());
-    };
-    crate::run::run_bootstrap(repo_root, command)
-}
-
 fn changed_files_for_args(
     repo_root: &Path,
     args: &ParsedArgs,
@@ -206,6 +294,10 @@ fn build_test_binary_args(
             "json".to_string(),
         ]);
         out.push("--report-time".to_string());
+        // `--shuffle-seed` rides on the `-Z unstable-options` above.
+        if let Some(seed) = args.shuffle_seed {
+            out.extend(crate::shuffle::libtest_seed_args(seed));
+        }
     } else if should_force_pretty_test_output(&args.runner_args) {
         out.extend(["--format".to_string(), "pretty".to_string()]);
     }
@@ -39,16 +39,18 @@ impl LibtestJsonAdapter {
     }
 
     fn actions_for_update(&self, update: LibtestJsonStreamUpdate) -> Vec<StreamAction> {
+        let outcome = outcome_from_status(update.status.as_str());
         if self.only_failures && update.status != "failed" {
-            return vec![];
+            return vec![StreamAction::RecordOutcome(outcome)];
         }
         let line = render_finished_test_line(
-            outcome_from_status(update.status.as_str()),
+            outcome,
             update.duration,
             self.suite_path_display.as_str(),
             update.test_name.as_str(),
         );
         vec![
+            StreamAction::RecordOutcome(outcome),
             StreamAction::SetProgressLabel(format!(
                 "{}::{}",
                 self.suite_path_display, update.test_name
@@ -88,8 +90,9 @@ impl DirectLibtestAdapter {
                 status,
                 duration,
             } => {
+                let outcome = outcome_from_status(status.as_str());
                 if self.only_failures && status != "failed" {
-                    return vec![];
+                    return vec![StreamAction::RecordOutcome(outcome)];
                 }
                 let duration = duration.or_else(|| {
                     self.started_at_by_test
@@ -97,7 +100,7 @@ impl DirectLibtestAdapter {
                         .map(|t| t.elapsed())
                 });
                 let line = render_finished_test_line(
-                    outcome_from_status(status.as_str()),
+                    outcome,
                     duration,
                     suite_path.as_str(),
                     test_name.as_str(),
@@ -106,6 +109,7 @@ impl DirectLibtestAdapter {
                     vec![StreamAction::SetProgressLabel(suite_path)]
                 } else {
                     vec![
+                        StreamAction::RecordOutcome(outcome),
                         StreamAction::SetProgressLabel(format!("{suite_path}::{test_name}")),
                         StreamAction::PrintStdout(line),
                     ]
@@ -129,8 +133,9 @@ impl DirectLibtestAdapter {
             return;
         }
         let status_trimmed = status.trim();
-        let is_terminal_status =
-            status_trimmed == "ok" || status_trimmed == "FAILED" || status_trimmed == "ignored";
+        let is_terminal_status = status_trimmed == "ok"
+            || status_trimmed == "FAILED"
+            || status_trimmed.starts_with("ignored");
         if status_trimmed.is_empty() || !is_terminal_status {
             self.started_at_by_test
                 .entry(name.to_string())
@@ -152,16 +157,18 @@ impl DirectLibtestAdapter {
             .remove(name.as_str())
             .map(|t| t.elapsed());
         let status = if trimmed == "ok" { "passed" } else { "failed" };
+        let outcome = outcome_from_status(status);
         if self.only_failures && status != "failed" {
-            return vec![];
+            return vec![StreamAction::RecordOutcome(outcome)];
         }
         let line = render_finished_test_line(
-            outcome_from_status(status),
+            outcome,
             duration,
             self.suite_path_display.as_str(),
             name.as_str(),
         );
         vec![
+            StreamAction::RecordOutcome(outcome),
             StreamAction::SetProgressLabel(format!("{}::{name}", self.suite_path_display)),
             StreamAction::PrintStdout(line),
         ]
@@ -184,9 +191,11 @@ impl StreamAdapter for DirectLibtestAdapter {
                 .remove(name.as_str())
                 .map(|t| t.elapsed());
             let status = if trimmed == "ok" { "passed" } else { "failed" };
+            let outcome = outcome_from_status(status);
+            actions.push(StreamAction::RecordOutcome(outcome));
             if !(self.only_failures && status != "failed") {
                 let line = render_finished_test_line(
-                    outcome_from_status(status),
+                    outcome,
                     duration,
                     self.suite_path_display.as_str(),
                     name.as_str(),
@@ -224,10 +233,16 @@ impl StreamAdapter for LibtestJsonAdapter {
     }
 
     fn on_line(&mut self, _stream: OutputStream, line: &str) -> Vec<StreamAction> {
-        self.parser
-            .push_line(line)
-            .map(|u| self.actions_for_update(u))
-            .unwrap_or_default()
+        let started_before = self.parser.started_tests();
+        let update = self.parser.push_line(line);
+        let started = self.parser.started_tests() - started_before;
+        std::iter::repeat_n(StreamAction::TestStarted, started)
+            .chain(
+                update
+                    .map(|u| self.actions_for_update(u))
+                    .unwrap_or_default(),
+            )
+            .collect()
     }
 }
 
@@ -285,5 +300,6 @@ pub(crate) fn build_run_model(suites: Vec<TestSuiteResult>, run_time_ms: u64) ->
         start_time: 0,
         test_results: suites,
         aggregated,
+        skipped_units: vec![],
     }
 }
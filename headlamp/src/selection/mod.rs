@@ -2,6 +2,7 @@ pub mod dependency_language;
 pub mod deps;
 pub mod import_extract;
 pub mod import_resolve;
+pub mod related_files;
 pub mod related_tests;
 pub mod relevance;
 pub mod route_index;
@@ -0,0 +1,114 @@
+//! The test files that exercise a source file, whatever its language: the import graph for
+//! JS/TS, import and name seeds for Python and Rust, as `--changed` selection picks them. The
+//! editor server and `headlamp owners` both ask through [`related_test_files`]; the untested
+//! changes report asks about many files at once through [`sources_without_related_tests`].
+
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+
+use crate::args::DEFAULT_EXCLUDE;
+use crate::runner_detect::{DetectedLanguage, language_for_path};
+use crate::selection::dependency_language::DependencyLanguageId;
+
+/// `abs` is an absolute source path; the tests come back absolute. A file of no known language
+/// has none.
+pub fn related_test_files(repo_root: &Path, abs: &Path) -> Vec<PathBuf> {
+    match language_for_path(abs) {
+        Some(DetectedLanguage::JavaScript) => {
+            crate::selection::related_tests::select_related_tests(
+                repo_root,
+                DependencyLanguageId::TsJs,
+                &[abs.to_slash_lossy().to_string()],
+                &js_exclude_globs(),
+            )
+            .selected_test_paths_abs
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+        }
+        Some(DetectedLanguage::Python) => {
+            let tests = crate::pytest_select::list_unittest_test_files(repo_root);
+            crate::pytest::selection::select_tests_for_changes(
+                repo_root,
+                &tests,
+                &[abs.to_path_buf()],
+            )
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+        }
+        Some(DetectedLanguage::Rust) => {
+            let tests = crate::cargo_select::list_rust_test_files(repo_root);
+            let seeds = crate::cargo_select::changed_rust_seeds(repo_root, &[abs.to_path_buf()]);
+            crate::cargo_select::filter_rust_tests_by_seeds(&tests, &seeds)
+        }
+        None => vec![],
+    }
+}
+
+/// The `sources` (absolute) no test exercises, in order, asked as `related_test_files` would
+/// but with each language's tests listed, and the JS/TS import graph built, once for all of them.
+/// Files of no known language are left out.
+pub fn sources_without_related_tests(repo_root: &Path, sources: &[PathBuf]) -> Vec<PathBuf> {
+    let of_language = |language: DetectedLanguage| {
+        sources
+            .iter()
+            .filter(|abs| language_for_path(abs) == Some(language))
+            .collect::<Vec<_>>()
+    };
+    let js_sources = of_language(DetectedLanguage::JavaScript)
+        .into_iter()
+        .map(|abs| abs.to_slash_lossy().to_string())
+        .collect::<Vec<_>>();
+    let untested_js = crate::selection::related_tests::sources_without_related_tests(
+        repo_root,
+        DependencyLanguageId::TsJs,
+        &js_sources,
+        &js_exclude_globs(),
+    );
+    let python_sources = of_language(DetectedLanguage::Python);
+    let python_tests = if python_sources.is_empty() {
+        vec![]
+    } else {
+        crate::pytest_select::list_unittest_test_files(repo_root)
+    };
+    let rust_sources = of_language(DetectedLanguage::Rust);
+    let rust_tests = if rust_sources.is_empty() {
+        vec![]
+    } else {
+        crate::cargo_select::list_rust_test_files(repo_root)
+    };
+    let untested_python = python_sources.into_iter().filter(|abs| {
+        crate::pytest::selection::select_tests_for_changes(
+            repo_root,
+            &python_tests,
+            &[abs.to_path_buf()],
+        )
+        .is_empty()
+    });
+    let untested_rust = rust_sources.into_iter().filter(|abs| {
+        let seeds = crate::cargo_select::changed_rust_seeds(repo_root, &[abs.to_path_buf()]);
+        crate::cargo_select::filter_rust_tests_by_seeds(&rust_tests, &seeds).is_empty()
+    });
+    let untested = untested_js
+        .into_iter()
+        .map(PathBuf::from)
+        .chain(untested_python.cloned())
+        .chain(untested_rust.cloned())
+        .collect::<std::collections::BTreeSet<_>>();
+    sources
+        .iter()
+        .filter(|abs| untested.contains(*abs))
+        .cloned()
+        .collect()
+}
+
+/// `tests/` holds tests here, not fixtures to skip, as in jest's own selection.
+fn js_exclude_globs() -> Vec<String> {
+    DEFAULT_EXCLUDE
+        .iter()
+        .filter(|glob| **glob != "**/tests/**")
+        .map(|glob| glob.to_string())
+        .collect()
+}
@@ -10,7 +10,7 @@ use crate::selection::dependency_language::{
     resolve_import_with_root_cached,
 };
 use crate::selection::relevance::augment_rank_with_priority_paths;
-use crate::selection::route_index::{discover_tests_for_http_paths, get_route_index};
+use crate::selection::route_index::{RouteIndex, discover_tests_for_http_paths, get_route_index};
 
 #[derive(Debug, Clone)]
 pub struct RelatedTestSelection {
@@ -41,8 +41,9 @@ pub fn select_related_tests(
     let (selected_tests, rank_by_abs_path) =
         bfs_related_tests(&graph, &normalized_seeds, &mut classifier);
 
+    let route_index = get_route_index(repo_root);
     let route_augmented_tests =
-        discover_route_augmented_tests(repo_root, &normalized_seeds, exclude_globs);
+        discover_route_augmented_tests(repo_root, &route_index, &normalized_seeds, exclude_globs);
     let augmented_rank =
         augment_rank_with_priority_paths(&rank_by_abs_path, &route_augmented_tests);
 
@@ -62,12 +63,41 @@ pub fn select_related_tests(
     }
 }
 
+/// The `sources_abs` no test reaches through imports or routes, as given. The import graph and
+/// route index are built once for all of them, where `select_related_tests` per file would
+/// rescan the repo each time.
+pub fn sources_without_related_tests(
+    repo_root: &Path,
+    language: DependencyLanguageId,
+    sources_abs: &[String],
+    exclude_globs: &[String],
+) -> Vec<String> {
+    if sources_abs.is_empty() {
+        return vec![];
+    }
+    let graph = build_reverse_import_graph(repo_root, language, exclude_globs);
+    let mut classifier = ProjectClassifier::for_path(language, repo_root);
+    let route_index = get_route_index(repo_root);
+    sources_abs
+        .iter()
+        .filter(|source| {
+            let seed = [normalize_abs_posix(source)];
+            bfs_related_tests(&graph, &seed, &mut classifier)
+                .0
+                .is_empty()
+                && discover_route_augmented_tests(repo_root, &route_index, &seed, exclude_globs)
+                    .is_empty()
+        })
+        .cloned()
+        .collect()
+}
+
 fn discover_route_augmented_tests(
     repo_root: &Path,
+    route_index: &RouteIndex,
     production_selection_paths_abs: &[String],
     exclude_globs: &[String],
 ) -> Vec<String> {
-    let route_index = get_route_index(repo_root);
     let routes = production_selection_paths_abs
         .iter()
         .flat_map(|abs| route_index.http_routes_for_source(abs))
@@ -35,8 +35,8 @@ pub fn discover_tests_for_http_paths(
         "-S".to_string(),
     ];
     for g in [
-        "**/*.{test,spec}.{ts,tsx,js,jsx}",
-        "tests/**/*.{ts,tsx,js,jsx}",
+        "**/*.{test,spec}.{ts,tsx,js,jsx,mjs,cjs,mts,cts}",
+        "tests/**/*.{ts,tsx,js,jsx,mjs,cjs,mts,cts}",
     ] {
         args.push("-g".to_string());
         args.push(g.to_string());
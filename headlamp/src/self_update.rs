@@ -0,0 +1,351 @@
+//! `headlamp self-update` and the `--check-update` notice. Both read the latest GitHub release;
+//! self-update downloads this platform's `.gz` binary, checks it against the release's `.sha256`
+//! asset and swaps it in place of the running executable.
+//!
+//! The `.sha256` comes from the same release as the binary, so the check catches a corrupted or
+//! truncated download, not a tampered release; it is no signature.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::args::ParsedArgs;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/dbpiper/headlamp/releases/latest";
+const API_TIMEOUT_SECS: &str = "10";
+const DOWNLOAD_TIMEOUT_SECS: &str = "300";
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum SelfUpdateError {
+    #[error("{command} failed: {message}")]
+    Command { command: String, message: String },
+    #[error("unexpected release data: {0}")]
+    Release(String),
+    #[error("no release binary for this platform")]
+    UnsupportedPlatform,
+    #[error("{asset} checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        asset: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("io error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> Option<semver::Version> {
+        semver::Version::parse(self.tag_name.trim_start_matches('v')).ok()
+    }
+
+    /// Whether this release is newer than `current` (a `CARGO_PKG_VERSION`).
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        match (self.version(), semver::Version::parse(current)) {
+            (Some(latest), Ok(current)) => latest > current,
+            _ => false,
+        }
+    }
+
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// The release asset built for this platform, named like the release workflow's
+/// `<target label>-<binary>.gz`.
+pub fn platform_asset_name() -> Option<&'static str> {
+    let name = match (
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        cfg!(target_env = "musl"),
+    ) {
+        ("macos", "aarch64", _) => "darwin-arm64-headlamp.gz",
+        ("macos", "x86_64", _) => "darwin-x64-headlamp.gz",
+        ("linux", "x86_64", false) => "linux-x64-gnu-headlamp.gz",
+        ("linux", "aarch64", false) => "linux-arm64-gnu-headlamp.gz",
+        ("linux", "x86_64", true) => "linux-x64-musl-headlamp.gz",
+        ("linux", "aarch64", true) => "linux-arm64-musl-headlamp.gz",
+        ("windows", "x86_64", _) => "win32-x64-headlamp.exe.gz",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// The package manager that owns `exe`, which should update it instead of self-update.
+pub fn managing_package_manager(exe: &Path) -> Option<&'static str> {
+    let path = exe.to_string_lossy().replace('\\', "/");
+    if path.contains("/node_modules/") {
+        Some("npm")
+    } else if path.contains("/site-packages/") || path.contains("/dist-packages/") {
+        Some("pip")
+    } else if path.contains("/.cargo/bin/") {
+        Some("cargo install")
+    } else {
+        None
+    }
+}
+
+/// The first 64-hex-digit word in `text`: what a `.sha256` asset holds, whether written by
+/// `sha256sum`, `shasum` or `certutil`.
+pub fn find_sha256_digest(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .find(|word| word.len() == 64 && word.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_ascii_lowercase)
+}
+
+fn curl(args: &[&str]) -> Result<Vec<u8>, SelfUpdateError> {
+    let output = Command::new("curl")
+        .arg("-fsSL")
+        .args(args)
+        .output()
+        .map_err(|err| SelfUpdateError::Command {
+            command: "curl".to_string(),
+            message: err.to_string(),
+        })?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SelfUpdateError::Command {
+            command: format!("curl {}", args.last().unwrap_or(&"")),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+pub fn fetch_latest_release() -> Result<Release, SelfUpdateError> {
+    let body = curl(&[
+        "-H",
+        "Accept: application/vnd.github+json",
+        "--max-time",
+        API_TIMEOUT_SECS,
+        LATEST_RELEASE_URL,
+    ])?;
+    serde_json::from_slice(&body).map_err(|err| SelfUpdateError::Release(err.to_string()))
+}
+
+/// The lowercase hex sha256 of the file at `path`.
+pub fn sha256_of(path: &Path) -> Result<String, SelfUpdateError> {
+    let mut file = std::fs::File::open(path).map_err(io_error(path))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(io_error(path))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> SelfUpdateError + '_ {
+    move |source| SelfUpdateError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Downloads `asset` and its `.sha256` into `dir`, checks the digest and returns the unpacked
+/// binary.
+fn download_verified(
+    release: &Release,
+    asset: &str,
+    dir: &Path,
+) -> Result<Vec<u8>, SelfUpdateError> {
+    let missing =
+        |name: &str| SelfUpdateError::Release(format!("{} has no {name}", release.tag_name));
+    let binary = release.asset(asset).ok_or_else(|| missing(asset))?;
+    let checksum_name = format!("{asset}.sha256");
+    let checksum = release
+        .asset(&checksum_name)
+        .ok_or_else(|| missing(&checksum_name))?;
+    let expected = find_sha256_digest(&String::from_utf8_lossy(&curl(&[
+        "--max-time",
+        API_TIMEOUT_SECS,
+        &checksum.browser_download_url,
+    ])?))
+    .ok_or_else(|| SelfUpdateError::Release(format!("{checksum_name} holds no sha256")))?;
+    let gz_path = dir.join(asset);
+    let gz_text = gz_path.to_string_lossy().to_string();
+    curl(&[
+        "--max-time",
+        DOWNLOAD_TIMEOUT_SECS,
+        "-o",
+        &gz_text,
+        &binary.browser_download_url,
+    ])?;
+    let actual = sha256_of(&gz_path)?;
+    if actual != expected {
+        return Err(SelfUpdateError::ChecksumMismatch {
+            asset: asset.to_string(),
+            expected,
+            actual,
+        });
+    }
+    let file = std::fs::File::open(&gz_path).map_err(io_error(&gz_path))?;
+    let mut unpacked = vec![];
+    flate2::read::GzDecoder::new(file)
+        .read_to_end(&mut unpacked)
+        .map_err(io_error(&gz_path))?;
+    Ok(unpacked)
+}
+
+/// Writes `binary` next to `exe` and renames it over `exe`. Windows can't replace a running
+/// executable, so there the old one is moved aside to `<exe>.old` first, and moved back if the
+/// new one can't take its place.
+fn swap_in_place(exe: &Path, binary: &[u8]) -> Result<(), SelfUpdateError> {
+    let staged = exe.with_extension("update");
+    std::fs::write(&staged, binary).map_err(io_error(&staged))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))
+            .map_err(io_error(&staged))?;
+    }
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(exe, &old).map_err(io_error(exe))?;
+        std::fs::rename(&staged, exe).or_else(|source| {
+            let _ = std::fs::rename(&old, exe);
+            let _ = std::fs::remove_file(&staged);
+            Err(io_error(exe)(source))
+        })
+    }
+    #[cfg(not(windows))]
+    std::fs::rename(&staged, exe).map_err(io_error(exe))
+}
+
+fn self_update(check_only: bool) -> Result<String, SelfUpdateError> {
+    let current = env!("CARGO_PKG_VERSION");
+    let release = fetch_latest_release()?;
+    if !release.is_newer_than(current) {
+        return Ok(format!("headlamp {current} is up to date"));
+    }
+    let latest = release.tag_name.trim_start_matches('v');
+    if check_only {
+        return Ok(format!(
+            "headlamp {latest} is available (you have {current})"
+        ));
+    }
+    let exe = std::env::current_exe()
+        .and_then(dunce::canonicalize)
+        .map_err(io_error(Path::new("headlamp")))?;
+    if let Some(manager) = managing_package_manager(&exe) {
+        return Ok(format!(
+            "headlamp {latest} is available; {} is managed by {manager}, update it there",
+            exe.display()
+        ));
+    }
+    let asset = platform_asset_name().ok_or(SelfUpdateError::UnsupportedPlatform)?;
+    let dir = tempfile::tempdir().map_err(io_error(&std::env::temp_dir()))?;
+    let binary = download_verified(&release, asset, dir.path())?;
+    swap_in_place(&exe, &binary)?;
+    Ok(format!("headlamp updated {current} -> {latest}"))
+}
+
+/// `headlamp self-update [--check]`; `None` when `argv` is not that command.
+pub fn run_self_update_command(argv: &[String]) -> Option<i32> {
+    let (first, rest) = argv.split_first()?;
+    if first != "self-update" {
+        return None;
+    }
+    let check_only = match rest {
+        [] => false,
+        [flag] if flag == "--check" => true,
+        _ => {
+            eprintln!("headlamp: usage: headlamp self-update [--check]");
+            return Some(2);
+        }
+    };
+    match self_update(check_only) {
+        Ok(message) => {
+            println!("{message}");
+            Some(0)
+        }
+        Err(err) => {
+            eprintln!("headlamp: self-update: {err}");
+            Some(1)
+        }
+    }
+}
+
+/// What the last `--check-update` saw, so the release API is asked at most once a day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UpdateCheck {
+    checked_at_secs: u64,
+    latest: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn latest_version_cached(cache_path: &Path) -> Option<String> {
+    let cached = std::fs::read(cache_path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice::<UpdateCheck>(&raw).ok())
+        .filter(|check| {
+            now_secs().saturating_sub(check.checked_at_secs) < CHECK_INTERVAL.as_secs()
+        });
+    if let Some(check) = cached {
+        return Some(check.latest);
+    }
+    let latest = fetch_latest_release().ok()?.tag_name;
+    let check = UpdateCheck {
+        checked_at_secs: now_secs(),
+        latest: latest.clone(),
+    };
+    let _ = std::fs::create_dir_all(cache_path.parent()?);
+    let _ = std::fs::write(cache_path, serde_json::to_vec(&check).ok()?);
+    Some(latest)
+}
+
+/// `--check-update`: one stderr line when a newer release is out. Network or parse failures
+/// stay silent; the notice must never fail a run.
+pub fn print_update_notice_if_requested(args: &ParsedArgs) {
+    if !args.check_update {
+        return;
+    }
+    let cache_path = crate::fast_related::default_cache_root().join("update-check.json");
+    let Some(latest) = latest_version_cached(&cache_path) else {
+        return;
+    };
+    let release = Release {
+        tag_name: latest,
+        assets: vec![],
+    };
+    let current = env!("CARGO_PKG_VERSION");
+    if !release.is_newer_than(current) {
+        return;
+    }
+    let how = std::env::current_exe()
+        .ok()
+        .and_then(|exe| managing_package_manager(&exe))
+        .map_or("run `headlamp self-update`".to_string(), |manager| {
+            format!("update it with {manager}")
+        });
+    eprintln!(
+        "headlamp: {} is available (you have {current}); {how}",
+        release.tag_name.trim_start_matches('v')
+    );
+}
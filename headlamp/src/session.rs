@@ -1,13 +1,24 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use tempfile::TempDir;
 
+use crate::process::RunEnvironment;
 use crate::run::RunError;
 
+pub mod artifacts;
+pub mod run_dirs;
+
+use artifacts::{Artifact, ArtifactKind};
+
 #[derive(Debug)]
 pub struct RunSession {
     root: PathBuf,
     _temp_dir: Option<TempDir>,
+    memo: Mutex<HashMap<String, Option<String>>>,
+    env: RunEnvironment,
+    artifacts: Mutex<Vec<Artifact>>,
 }
 
 impl RunSession {
@@ -18,6 +29,9 @@ impl RunSession {
             return Ok(Self {
                 root,
                 _temp_dir: None,
+                memo: Mutex::default(),
+                env: RunEnvironment::default(),
+                artifacts: Mutex::default(),
             });
         }
         let temp_dir = tempfile::Builder::new()
@@ -27,9 +41,22 @@ impl RunSession {
         Ok(Self {
             root: temp_dir.path().to_path_buf(),
             _temp_dir: Some(temp_dir),
+            memo: Mutex::default(),
+            env: RunEnvironment::default(),
+            artifacts: Mutex::default(),
         })
     }
 
+    pub fn with_env(mut self, env: RunEnvironment) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// What every runner subprocess started for this run gets layered onto its environment.
+    pub fn env(&self) -> &RunEnvironment {
+        &self.env
+    }
+
     pub fn root(&self) -> &Path {
         &self.root
     }
@@ -37,4 +64,53 @@ impl RunSession {
     pub fn subdir(&self, name: &str) -> PathBuf {
         self.root.join(name)
     }
+
+    /// Computes `key` at most once per run (including failures), for tool queries such as
+    /// `cargo metadata` that several phases need.
+    pub fn memoized(&self, key: &str, compute: impl FnOnce() -> Option<String>) -> Option<String> {
+        let mut memo = self.memo.lock().unwrap_or_else(|e| e.into_inner());
+        memo.entry(key.to_string()).or_insert_with(compute).clone()
+    }
+
+    /// Records an output worth keeping for `--artifacts-dir`; it may not exist yet (or ever).
+    pub fn register_artifact(&self, kind: ArtifactKind, runner: &str, path: impl Into<PathBuf>) {
+        let artifact = Artifact {
+            kind,
+            runner: runner.to_string(),
+            path: path.into(),
+        };
+        let mut artifacts = self.artifacts.lock().unwrap_or_else(|e| e.into_inner());
+        if !artifacts.contains(&artifact) {
+            artifacts.push(artifact);
+        }
+    }
+
+    /// Where the complete output of one runner invocation goes, registered as a log artifact.
+    /// Logs live under the shared `headlamp` temp dir rather than this session's own, so a path
+    /// printed on failure still exists after the run; each invocation label overwrites its log.
+    pub fn runner_log_path(&self, runner: &str, label: &str) -> PathBuf {
+        let file_name = format!("{runner}-{label}.log")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let path = std::env::temp_dir()
+            .join("headlamp")
+            .join("logs")
+            .join(file_name);
+        self.register_artifact(ArtifactKind::Log, runner, &path);
+        path
+    }
+
+    pub fn artifacts(&self) -> Vec<Artifact> {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
 }
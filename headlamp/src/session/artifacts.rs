@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+use serde::{Deserialize, Serialize};
+
+use crate::run::RunError;
+
+pub const INDEX_FILE: &str = "index.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// lcov / istanbul / llvm-cov output.
+    Coverage,
+    /// Machine-readable results a runner produced, e.g. the jest bridge JSON.
+    Report,
+    /// Repo files matched by `--artifact-glob`, e.g. failure screenshots.
+    Attachment,
+    /// The complete output of a runner invocation.
+    Log,
+}
+
+impl ArtifactKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Coverage => "coverage",
+            Self::Report => "reports",
+            Self::Attachment => "attachments",
+            Self::Log => "logs",
+        }
+    }
+}
+
+/// A file or directory a runner produced during the run, registered on the `RunSession`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    pub runner: String,
+    pub path: PathBuf,
+}
+
+/// One line of `index.json`: `path` is relative to the artifacts dir.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactEntry {
+    pub kind: ArtifactKind,
+    pub runner: String,
+    pub source: String,
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactIndex<'a> {
+    schema_version: u32,
+    artifacts: &'a [ArtifactEntry],
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtifactIndexFile {
+    artifacts: Vec<ArtifactEntry>,
+}
+
+/// Copies (hard-links when possible) the registered artifacts and the repo files matching `globs`
+/// into `dest` as `<kind>/<runner>/...`, then writes `index.json`. A `dest` left by a previous
+/// run (it has an index) is cleared first; registered paths that were never written are skipped.
+pub fn collect_artifacts(
+    dest: &Path,
+    repo_root: &Path,
+    registered: &[Artifact],
+    globs: &[String],
+) -> Result<Vec<ArtifactEntry>, RunError> {
+    if dest.join(INDEX_FILE).is_file() {
+        std::fs::remove_dir_all(dest).map_err(RunError::Io)?;
+    }
+    std::fs::create_dir_all(dest).map_err(RunError::Io)?;
+    let mut entries: Vec<ArtifactEntry> = Vec::new();
+    for artifact in registered {
+        let base = Path::new(artifact.kind.dir_name()).join(&artifact.runner);
+        for (source, relative) in files_under(&artifact.path) {
+            entries.push(place(dest, artifact, &source, &base.join(relative))?);
+        }
+    }
+    for source in glob_matches(repo_root, globs) {
+        let relative = source.strip_prefix(repo_root).unwrap_or(&source);
+        let artifact = Artifact {
+            kind: ArtifactKind::Attachment,
+            runner: "repo".to_string(),
+            path: source.clone(),
+        };
+        let target = Path::new(ArtifactKind::Attachment.dir_name()).join(relative);
+        entries.push(place(dest, &artifact, &source, &target)?);
+    }
+    let index = ArtifactIndex {
+        schema_version: 1,
+        artifacts: &entries,
+    };
+    let json = serde_json::to_string_pretty(&index)
+        .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))?;
+    std::fs::write(dest.join(INDEX_FILE), json).map_err(RunError::Io)?;
+    Ok(entries)
+}
+
+/// The entries of an `index.json` written by `collect_artifacts`.
+pub fn read_index(index_path: &Path) -> Result<Vec<ArtifactEntry>, RunError> {
+    let raw = std::fs::read_to_string(index_path).map_err(RunError::Io)?;
+    serde_json::from_str::<ArtifactIndexFile>(&raw)
+        .map(|index| index.artifacts)
+        .map_err(|e| RunError::Io(std::io::Error::other(e.to_string())))
+}
+
+/// A file is its own single entry (named after itself); a directory yields every file below it,
+/// keeping the directory's name as the first component.
+fn files_under(path: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let Some(name) = path.file_name().map(PathBuf::from) else {
+        return vec![];
+    };
+    if path.is_file() {
+        return vec![(path.to_path_buf(), name)];
+    }
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return vec![];
+    };
+    let mut children = read_dir
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    children.sort();
+    children
+        .iter()
+        .flat_map(|child| files_under(child))
+        .map(|(source, relative)| (source, name.join(relative)))
+        .collect()
+}
+
+fn place(
+    dest: &Path,
+    artifact: &Artifact,
+    source: &Path,
+    relative: &Path,
+) -> Result<ArtifactEntry, RunError> {
+    let target = unique_target(&dest.join(relative));
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(RunError::Io)?;
+    }
+    if std::fs::hard_link(source, &target).is_err() {
+        std::fs::copy(source, &target).map_err(RunError::Io)?;
+    }
+    Ok(ArtifactEntry {
+        kind: artifact.kind,
+        runner: artifact.runner.clone(),
+        source: source.to_slash_lossy().to_string(),
+        path: target
+            .strip_prefix(dest)
+            .unwrap_or(&target)
+            .to_slash_lossy()
+            .to_string(),
+        bytes: std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+/// `report.json` becomes `report-2.json`, `report-3.json`, ... when an earlier artifact (say,
+/// another `--feature-matrix` entry) already took the name.
+fn unique_target(target: &Path) -> PathBuf {
+    if !target.exists() {
+        return target.to_path_buf();
+    }
+    let stem = target
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = target
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| target.with_file_name(format!("{stem}-{n}{extension}")))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+/// Walks only below each glob's literal prefix (`test-results/**/*.png` walks `test-results`), and
+/// without gitignore filtering since screenshots and reports are usually ignored files.
+fn glob_matches(repo_root: &Path, globs: &[String]) -> Vec<PathBuf> {
+    let mut matches = globs
+        .iter()
+        .filter_map(|glob| {
+            let matcher = globset::Glob::new(glob).ok()?.compile_matcher();
+            Some((literal_prefix(glob), matcher))
+        })
+        .flat_map(|(prefix, matcher)| {
+            ignore::WalkBuilder::new(repo_root.join(prefix))
+                .standard_filters(false)
+                .build()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+                .map(|entry| entry.into_path())
+                .filter(move |path| {
+                    path.strip_prefix(repo_root)
+                        .is_ok_and(|relative| matcher.is_match(relative))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+fn literal_prefix(glob: &str) -> PathBuf {
+    let parts = glob.split('/').collect::<Vec<_>>();
+    let literal = parts
+        .iter()
+        .take_while(|part| !part.contains(['*', '?', '[', '{']))
+        .count();
+    let dirs = if literal == parts.len() {
+        literal.saturating_sub(1)
+    } else {
+        literal
+    };
+    parts[..dirs].iter().collect()
+}
@@ -0,0 +1,45 @@
+//! Per-run dirs under the repo's cache for work handed to other processes (remote executors,
+//! shard launchers): each run gets a fresh `<millis>-<pid>` dir so concurrent runs never share
+//! results, and once a run has rendered, all but the newest few of its kind are removed.
+
+use std::path::{Path, PathBuf};
+
+/// Run dirs of one kind left in place by `prune_run_dirs`, so the last runs' logs stay readable.
+pub const RUN_DIRS_KEPT: usize = 3;
+
+/// `<cache_root>/<repo key>/<kind>/<millis>-<pid>`; not created.
+pub fn new_run_dir(cache_root: &Path, repo_root: &Path, kind: &str) -> PathBuf {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+    cache_root
+        .join(crate::fast_related::stable_repo_key_hash_12(repo_root))
+        .join(kind)
+        .join(format!("{millis}-{}", std::process::id()))
+}
+
+/// Removes the run dirs next to `run_dir` except the newest `RUN_DIRS_KEPT` (by the millis in
+/// their names); `run_dir` itself is always kept. Best-effort.
+pub fn prune_run_dirs(run_dir: &Path) {
+    let Some(parent) = run_dir.parent() else {
+        return;
+    };
+    let started_millis = |path: &Path| -> Option<u128> {
+        let name = path.file_name()?.to_str()?;
+        name.split_once('-')?.0.parse().ok()
+    };
+    let mut dirs = std::fs::read_dir(parent)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path != run_dir)
+        .filter_map(|path| started_millis(&path).map(|millis| (millis, path)))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    let removed = dirs.len().saturating_sub(RUN_DIRS_KEPT.saturating_sub(1));
+    dirs.into_iter().take(removed).for_each(|(_, path)| {
+        let _ = std::fs::remove_dir_all(path);
+    });
+}
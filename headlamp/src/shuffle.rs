@@ -0,0 +1,47 @@
+//! `--shuffle[=seed]`: run test files in a seeded random order to surface order-dependent
+//! tests. Every runner orders with the same splitmix64-driven Fisher-Yates shuffle (headlamp for
+//! Rust test binaries, the jest sequencer and the pytest plugin for theirs), so a seed printed in
+//! the footer reproduces the order on the next `--shuffle=<seed>` run.
+
+/// How the seed reaches the jest sequencer and the pytest plugin.
+pub const SEED_ENV: &str = "HEADLAMP_SHUFFLE_SEED";
+
+/// A file listing test files, one per line, that the jest sequencer and the pytest plugin run in
+/// that order instead; set by `headlamp bisect-order`.
+pub const ORDER_ENV: &str = "HEADLAMP_TEST_ORDER";
+
+/// A seed for a bare `--shuffle`; it only has to differ between runs.
+pub fn random_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0);
+    SplitMix64(nanos ^ (u64::from(std::process::id()) << 32)).next()
+}
+
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Reorders `items` in place; the same seed and length always give the same permutation.
+pub fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut rng = SplitMix64(seed);
+    for index in (1..items.len()).rev() {
+        let other = (rng.next() % (index as u64 + 1)) as usize;
+        items.swap(index, other);
+    }
+}
+
+/// libtest's own per-binary shuffle; it needs `-Z unstable-options`, so nightly only.
+pub fn libtest_seed_args(seed: u64) -> Vec<String> {
+    vec!["--shuffle-seed".to_string(), seed.to_string()]
+}
@@ -1,16 +1,35 @@
-use std::collections::VecDeque;
 use std::io::{BufRead, BufReader};
 use std::process::Command;
-use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
-use crate::live_progress::LiveProgress;
+use crate::live_progress::{LiveProgress, TestOutcome};
+use crate::process::{ChildGroupGuard, ChildUsage, isolate_process_group};
 use crate::run::RunError;
 
+mod event_pipe;
+mod lines;
+mod ring;
+mod spill;
+#[cfg(test)]
+mod spill_test;
+mod unit_tag;
+#[cfg(test)]
+mod unit_tag_test;
+
+pub use event_pipe::EVENTS_FD_ENV;
+pub use lines::{MAX_LINE_BYTES, RawLines};
+pub use ring::RingBuffer;
+pub(crate) use spill::LineSink;
+pub use spill::SPILL_THRESHOLD_BYTES;
+use spill::SpillReceiver;
+pub(crate) use unit_tag::UnitTagged;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputStream {
     Stdout,
     Stderr,
+    /// NDJSON written by the jest bridge or a runner plugin to the dedicated event pipe.
+    Events,
 }
 
 #[derive(Debug, Clone)]
@@ -18,39 +37,10 @@ pub enum StreamAction {
     PrintStdout(String),
     PrintStderr(String),
     SetProgressLabel(String),
-}
-
-#[derive(Debug, Clone)]
-pub struct RingBuffer {
-    max_bytes: usize,
-    current_bytes: usize,
-    lines: VecDeque<String>,
-}
-
-impl RingBuffer {
-    pub fn new(max_bytes: usize) -> Self {
-        Self {
-            max_bytes: max_bytes.max(1),
-            current_bytes: 0,
-            lines: VecDeque::new(),
-        }
-    }
-
-    pub fn push_line(&mut self, line: String) {
-        let bytes = line.len();
-        self.lines.push_back(line);
-        self.current_bytes = self.current_bytes.saturating_add(bytes);
-        while self.current_bytes > self.max_bytes {
-            let Some(front) = self.lines.pop_front() else {
-                break;
-            };
-            self.current_bytes = self.current_bytes.saturating_sub(front.len());
-        }
-    }
-
-    pub fn lines(&self) -> impl Iterator<Item = &String> {
-        self.lines.iter()
-    }
+    /// A test finished; emitted even when its line is not printed so interrupts can summarize.
+    RecordOutcome(TestOutcome),
+    /// A test began running; counts toward the frame's `running` until its outcome arrives.
+    TestStarted,
 }
 
 pub trait StreamAdapter {
@@ -59,11 +49,18 @@ pub trait StreamAdapter {
     fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction>;
 }
 
-fn apply_actions(progress: &LiveProgress, actions: Vec<StreamAction>) {
+pub(crate) fn apply_actions(progress: &LiveProgress, actions: Vec<StreamAction>) {
     actions.into_iter().for_each(|action| match action {
         StreamAction::SetProgressLabel(label) => progress.set_current_label(label),
+        StreamAction::PrintStdout(line) | StreamAction::PrintStderr(line)
+            if crate::format::output_filter::suppresses(&line) => {}
         StreamAction::PrintStdout(line) => progress.println_stdout(&line),
         StreamAction::PrintStderr(line) => progress.eprintln_stderr(&line),
+        StreamAction::RecordOutcome(outcome) => {
+            progress.record_test_outcome(outcome);
+            crate::interrupt::record_test_outcome(outcome);
+        }
+        StreamAction::TestStarted => progress.record_test_started(),
     });
 }
 
@@ -75,31 +72,29 @@ fn recv_poll_interval() -> Duration {
     Duration::from_millis(50)
 }
 
-fn normalize_crlf_line(line: &str) -> String {
-    line.strip_suffix('\r').unwrap_or(line).to_string()
-}
-
-fn spawn_lines_thread(
+/// Keeps reading until the pipe closes even when nobody listens any more, so the child never
+/// blocks on a full pipe.
+pub(crate) fn spawn_lines_thread(
     reader: impl std::io::Read + Send + 'static,
-    tx: mpsc::Sender<(OutputStream, String)>,
+    tx: impl LineSink,
     stream: OutputStream,
 ) {
     std::thread::spawn(move || {
-        let reader = BufReader::new(reader);
-        reader.lines().map_while(Result::ok).for_each(|line| {
-            let line = normalize_crlf_line(&line);
-            let _ = tx.send((stream, line));
+        let mut listening = true;
+        RawLines::new(BufReader::new(reader)).for_each(|line| {
+            listening = listening && tx.send_line(stream, line);
         });
     });
 }
 
 fn drain_channel_until_exit_then_deadline(
     mut child: std::process::Child,
-    rx: mpsc::Receiver<(OutputStream, String)>,
-    ring_bytes: usize,
+    rx: SpillReceiver,
+    mut ring: RingBuffer,
     mut on_line: impl FnMut(OutputStream, &str, &mut RingBuffer),
 ) -> Result<(i32, RingBuffer), RunError> {
-    let mut ring = RingBuffer::new(ring_bytes);
+    let group = ChildGroupGuard::register(&child);
+    let mut usage = ChildUsage::watch(&child);
     let mut child_exited = false;
     let mut drain_deadline: Option<Instant> = None;
     loop {
@@ -113,7 +108,11 @@ fn drain_channel_until_exit_then_deadline(
                     }
                     continue;
                 }
-                if child.try_wait().map_err(RunError::WaitFailed)?.is_some() {
+                if usage
+                    .try_wait(&mut child)
+                    .map_err(RunError::WaitFailed)?
+                    .is_some()
+                {
                     child_exited = true;
                     drain_deadline = Some(drain_after_child_exit_deadline(now));
                 }
@@ -121,7 +120,19 @@ fn drain_channel_until_exit_then_deadline(
             Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
         }
     }
-    let status = child.wait().map_err(RunError::WaitFailed)?;
+    let status = usage.wait(&mut child).map_err(RunError::WaitFailed)?;
+    crate::interrupt::park_if_interrupted();
+    // Anything still in the group (servers forked by tests, stray workers) would outlive us.
+    group.kill_remaining();
+    let spilled_lines = rx.spilled_lines();
+    if spilled_lines > 0 {
+        tracing::debug!(
+            target: "streaming",
+            spilled_lines,
+            "runner output outpaced rendering; lines spilled to a temp file"
+        );
+    }
+    ring.finish_full_log();
     let exit_code = status.code().unwrap_or(1);
     Ok((exit_code, ring))
 }
@@ -131,27 +142,50 @@ pub fn consume_lines_capture_tail(
     reader: impl BufRead,
     progress: &LiveProgress,
     adapter: &mut dyn StreamAdapter,
-    ring_bytes: usize,
+    ring: impl Into<RingBuffer>,
 ) -> RingBuffer {
-    let mut ring = RingBuffer::new(ring_bytes);
-    reader.lines().map_while(Result::ok).for_each(|line| {
-        // Normalize CRLF -> LF. BufRead::lines strips '\n' but keeps a trailing '\r' if present.
-        let line = normalize_crlf_line(&line);
+    let mut ring = ring.into();
+    RawLines::new(reader).for_each(|line| {
         ring.push_line(line.clone());
         // Once merged, stream distinction is no longer meaningful.
         progress.record_runner_stdout_line(&line);
         let actions = adapter.on_line(OutputStream::Stdout, &line);
         apply_actions(progress, actions);
     });
+    ring.finish_full_log();
     ring
 }
 
 pub fn run_streaming_capture_tail(
+    command: Command,
+    progress: &LiveProgress,
+    adapter: &mut dyn StreamAdapter,
+    ring: impl Into<RingBuffer>,
+) -> Result<(i32, RingBuffer), RunError> {
+    run_streaming_capture_tail_inner(command, progress, adapter, ring.into(), false)
+}
+
+/// Like `run_streaming_capture_tail`, but also hands the child an event pipe (see
+/// `EVENTS_FD_ENV`) whose lines reach the adapter as `OutputStream::Events`.
+pub fn run_streaming_capture_tail_with_events(
+    command: Command,
+    progress: &LiveProgress,
+    adapter: &mut dyn StreamAdapter,
+    ring: impl Into<RingBuffer>,
+) -> Result<(i32, RingBuffer), RunError> {
+    run_streaming_capture_tail_inner(command, progress, adapter, ring.into(), true)
+}
+
+fn run_streaming_capture_tail_inner(
     mut command: Command,
     progress: &LiveProgress,
     adapter: &mut dyn StreamAdapter,
-    ring_bytes: usize,
+    ring: RingBuffer,
+    with_events: bool,
 ) -> Result<(i32, RingBuffer), RunError> {
+    if crate::print_commands::intercept(&command) {
+        return Ok((0, ring));
+    }
     // IMPORTANT: use explicit pipes so we control FD/handle ownership and never retain a write end
     // in the parent. If the parent accidentally keeps a write end open, reader threads can block
     // forever waiting for EOF (especially when the child produces little/no output).
@@ -160,6 +194,10 @@ pub fn run_streaming_capture_tail(
     command
         .stdout(std::process::Stdio::from(stdout_writer))
         .stderr(std::process::Stdio::from(stderr_writer));
+    let events_reader = with_events
+        .then(|| event_pipe::attach_event_pipe(&mut command))
+        .flatten();
+    isolate_process_group(&mut command);
     let child = command.spawn().map_err(RunError::SpawnFailed)?;
     // IMPORTANT: ensure the parent does not retain any pipe write ends via `Command`/`Stdio`
     // ownership. If a write end stays open in the parent, reader threads can block forever and
@@ -170,30 +208,50 @@ pub fn run_streaming_capture_tail(
         progress.set_current_label(label);
     }
 
-    let (tx, rx) = mpsc::channel::<(OutputStream, String)>();
+    let (tx, rx) = spill::spill_channel(SPILL_THRESHOLD_BYTES);
 
     spawn_lines_thread(stdout_reader, tx.clone(), OutputStream::Stdout);
     spawn_lines_thread(stderr_reader, tx.clone(), OutputStream::Stderr);
+    if let Some(reader) = events_reader {
+        spawn_lines_thread(reader, tx.clone(), OutputStream::Events);
+    }
 
     drop(tx);
 
-    drain_channel_until_exit_then_deadline(child, rx, ring_bytes, |stream, line, ring| {
-        ring.push_line(line.to_string());
-        match stream {
-            OutputStream::Stdout => progress.record_runner_stdout_line(line),
-            OutputStream::Stderr => progress.record_runner_stderr_line(line),
+    drain_channel_until_exit_then_deadline(child, rx, ring, |stream, line, ring| {
+        if stream != OutputStream::Events {
+            ring.push_line(line.to_string());
         }
-        let actions = adapter.on_line(stream, line);
-        apply_actions(progress, actions);
+        dispatch_line(progress, adapter, stream, line);
     })
 }
 
+/// Routes one runner line to live progress and the adapter, as a spawned run does; for
+/// long-lived children that outlive a single run (the jest watch worker).
+pub fn dispatch_line(
+    progress: &LiveProgress,
+    adapter: &mut dyn StreamAdapter,
+    stream: OutputStream,
+    line: &str,
+) {
+    match stream {
+        OutputStream::Stdout => progress.record_runner_stdout_line(line),
+        OutputStream::Stderr => progress.record_runner_stderr_line(line),
+        OutputStream::Events => {}
+    }
+    let actions = adapter.on_line(stream, line);
+    apply_actions(progress, actions);
+}
+
 pub fn run_streaming_capture_tail_merged(
     command: Command,
     progress: &LiveProgress,
     adapter: &mut dyn StreamAdapter,
-    ring_bytes: usize,
+    ring: impl Into<RingBuffer>,
 ) -> Result<(i32, RingBuffer), RunError> {
+    if crate::print_commands::intercept(&command) {
+        return Ok((0, ring.into()));
+    }
     struct MergeStreamsAdapter<'a> {
         inner: &'a mut dyn StreamAdapter,
     }
@@ -225,6 +283,7 @@ pub fn run_streaming_capture_tail_merged(
         command
             .stdout(std::process::Stdio::from(merged_writer))
             .stderr(std::process::Stdio::from(merged_writer2));
+        isolate_process_group(&mut command);
 
         let child = command.spawn().map_err(RunError::SpawnFailed)?;
         drop(command);
@@ -233,10 +292,10 @@ pub fn run_streaming_capture_tail_merged(
             progress.set_current_label(label);
         }
 
-        let (tx, rx) = mpsc::channel::<(OutputStream, String)>();
+        let (tx, rx) = spill::spill_channel(SPILL_THRESHOLD_BYTES);
         spawn_lines_thread(merged_reader, tx, OutputStream::Stdout);
 
-        drain_channel_until_exit_then_deadline(child, rx, ring_bytes, |stream, line, ring| {
+        drain_channel_until_exit_then_deadline(child, rx, ring.into(), |stream, line, ring| {
             ring.push_line(line.to_string());
             progress.record_runner_stdout_line(line);
             let actions = merged.on_line(stream, line);
@@ -246,6 +305,6 @@ pub fn run_streaming_capture_tail_merged(
 
     #[cfg(not(unix))]
     {
-        run_streaming_capture_tail(command, progress, &mut merged, ring_bytes)
+        run_streaming_capture_tail(command, progress, &mut merged, ring)
     }
 }
@@ -0,0 +1,43 @@
+use std::process::Command;
+
+/// Environment variable naming the file descriptor the jest bridge reporter and runner plugins
+/// write NDJSON events to.
+pub const EVENTS_FD_ENV: &str = "HEADLAMP_EVENTS_FD";
+
+const EVENTS_FD: i32 = 3;
+
+/// Gives the child a dedicated pipe for structured events on fd 3 (unix only), so the jest bridge
+/// and plugins can stream them without interleaving with the runner's own stdout/stderr. Returns
+/// the read end; `None` means the child sees no `HEADLAMP_EVENTS_FD`: the bridge then falls back
+/// to stderr lines, plugins to stdout lines.
+#[cfg(unix)]
+pub(super) fn attach_event_pipe(command: &mut Command) -> Option<os_pipe::PipeReader> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let (reader, writer) = os_pipe::pipe().ok()?;
+    command.env(EVENTS_FD_ENV, EVENTS_FD.to_string());
+    // SAFETY: only async-signal-safe calls between fork and exec. The writer is moved into the
+    // closure so it is closed in the parent once the `Command` is dropped after spawning.
+    unsafe {
+        command.pre_exec(move || {
+            let raw = writer.as_raw_fd();
+            let ok = if raw == EVENTS_FD {
+                libc::fcntl(raw, libc::F_SETFD, 0) != -1
+            } else {
+                libc::dup2(raw, EVENTS_FD) != -1
+            };
+            if ok {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+    Some(reader)
+}
+
+#[cfg(not(unix))]
+pub(super) fn attach_event_pipe(_command: &mut Command) -> Option<os_pipe::PipeReader> {
+    None
+}
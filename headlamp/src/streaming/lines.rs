@@ -0,0 +1,131 @@
+use std::io::BufRead;
+
+/// Longest line handed on in one piece; longer lines arrive as consecutive chunks so a runner
+/// printing a multi-megabyte line never needs it in memory at once.
+pub const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Runner output split into lines without assuming UTF-8. Invalid bytes are kept as `\xNN`
+/// escapes, CRLF endings are dropped, and a line redrawn with carriage returns (`10%\r20%\r`)
+/// is coalesced to what a terminal would end up showing.
+pub struct RawLines<R> {
+    reader: R,
+    max_line_bytes: usize,
+    /// The start of a UTF-8 sequence cut off by a chunk boundary, carried into the next chunk.
+    carry: Vec<u8>,
+}
+
+impl<R: BufRead> RawLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_max_line_bytes(reader, MAX_LINE_BYTES)
+    }
+
+    pub fn with_max_line_bytes(reader: R, max_line_bytes: usize) -> Self {
+        Self {
+            reader,
+            max_line_bytes: max_line_bytes.max(8),
+            carry: vec![],
+        }
+    }
+
+    /// Appends bytes up to the next newline (consumed, not appended) or until `line` is full.
+    /// Returns whether a newline ended the line; `None` at end of input or on a read error.
+    fn fill_line(&mut self, line: &mut Vec<u8>) -> Option<bool> {
+        loop {
+            let (taken, found_newline) = {
+                let available = match self.reader.fill_buf() {
+                    Ok(available) => available,
+                    Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => return None,
+                };
+                if available.is_empty() {
+                    return None;
+                }
+                let room = self.max_line_bytes.saturating_sub(line.len());
+                // One byte past the room, so a newline right after a full line still ends it.
+                let search = &available[..available.len().min(room + 1)];
+                match search.iter().position(|byte| *byte == b'\n') {
+                    Some(newline) => {
+                        line.extend_from_slice(&search[..newline]);
+                        (newline + 1, true)
+                    }
+                    None => {
+                        let window = &search[..search.len().min(room)];
+                        line.extend_from_slice(window);
+                        (window.len(), false)
+                    }
+                }
+            };
+            self.reader.consume(taken);
+            if found_newline {
+                return Some(true);
+            }
+            if line.len() >= self.max_line_bytes {
+                return Some(false);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for RawLines<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut line = std::mem::take(&mut self.carry);
+        match self.fill_line(&mut line) {
+            Some(true) => Some(render_line(line)),
+            Some(false) => {
+                let keep = incomplete_utf8_suffix_len(&line);
+                self.carry = line.split_off(line.len() - keep);
+                Some(render_line(line))
+            }
+            None if line.is_empty() => None,
+            None => Some(render_line(line)),
+        }
+    }
+}
+
+fn render_line(mut line: Vec<u8>) -> String {
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    if let Some(last_cr) = line.iter().rposition(|byte| *byte == b'\r') {
+        line.drain(..=last_cr);
+    }
+    escape_invalid_utf8(line)
+}
+
+pub fn escape_invalid_utf8(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => error
+            .as_bytes()
+            .utf8_chunks()
+            .fold(String::new(), |mut out, chunk| {
+                out.push_str(chunk.valid());
+                chunk
+                    .invalid()
+                    .iter()
+                    .for_each(|byte| out.push_str(&format!("\\x{byte:02x}")));
+                out
+            }),
+    }
+}
+
+/// How many trailing bytes start a UTF-8 sequence that is not complete yet.
+fn incomplete_utf8_suffix_len(bytes: &[u8]) -> usize {
+    let tail_start = bytes.len().saturating_sub(3);
+    (tail_start..bytes.len())
+        .rev()
+        .find_map(|index| {
+            let width = match bytes[index] {
+                0x80..=0xBF => return None,
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => return Some(0),
+            };
+            let present = bytes.len() - index;
+            Some(if present < width { present } else { 0 })
+        })
+        .unwrap_or(0)
+}
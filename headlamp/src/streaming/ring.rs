@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// What a run keeps of its runner's output: the first `head` bytes, the last `tail` bytes, and
+/// optionally the complete output in a log file. The head matters as much as the tail: a
+/// compile error is printed first and buried by everything after it.
+#[derive(Debug)]
+pub struct RingBuffer {
+    head_max_bytes: usize,
+    head_bytes: usize,
+    head: Vec<String>,
+    head_full: bool,
+    max_bytes: usize,
+    current_bytes: usize,
+    lines: VecDeque<String>,
+    omitted_lines: usize,
+    full_log: Option<FullLog>,
+}
+
+#[derive(Debug)]
+struct FullLog {
+    path: PathBuf,
+    writer: Option<BufWriter<std::fs::File>>,
+}
+
+impl From<usize> for RingBuffer {
+    fn from(max_bytes: usize) -> Self {
+        Self::new(max_bytes)
+    }
+}
+
+impl RingBuffer {
+    /// Tail only: the last `max_bytes` of output.
+    pub fn new(max_bytes: usize) -> Self {
+        Self::with_head(0, max_bytes)
+    }
+
+    pub fn with_head(head_bytes: usize, tail_bytes: usize) -> Self {
+        Self {
+            head_max_bytes: head_bytes,
+            head_bytes: 0,
+            head: vec![],
+            head_full: head_bytes == 0,
+            max_bytes: tail_bytes.max(1),
+            current_bytes: 0,
+            lines: VecDeque::new(),
+            omitted_lines: 0,
+            full_log: None,
+        }
+    }
+
+    /// Also writes every line to `path` (created, or truncated, right away). A log that cannot
+    /// be written is dropped silently; the in-memory head and tail are unaffected.
+    pub fn with_full_log(mut self, path: &Path) -> Self {
+        let writer = path
+            .parent()
+            .is_none_or(|dir| std::fs::create_dir_all(dir).is_ok())
+            .then(|| std::fs::File::create(path).ok())
+            .flatten()
+            .map(BufWriter::new);
+        self.full_log = writer.map(|writer| FullLog {
+            path: path.to_path_buf(),
+            writer: Some(writer),
+        });
+        self
+    }
+
+    pub fn push_line(&mut self, line: String) {
+        if let Some(writer) = self.full_log.as_mut().and_then(|log| log.writer.as_mut()) {
+            let _ = writeln!(writer, "{line}");
+        }
+        let bytes = line.len();
+        if !self.head_full {
+            if self.head_bytes.saturating_add(bytes) <= self.head_max_bytes {
+                self.head_bytes += bytes;
+                self.head.push(line);
+                return;
+            }
+            self.head_full = true;
+        }
+        self.lines.push_back(line);
+        self.current_bytes = self.current_bytes.saturating_add(bytes);
+        while self.current_bytes > self.max_bytes {
+            let Some(front) = self.lines.pop_front() else {
+                break;
+            };
+            self.current_bytes = self.current_bytes.saturating_sub(front.len());
+            self.omitted_lines += 1;
+        }
+    }
+
+    /// Flushes the full log; its path is only worth showing once this has run.
+    pub fn finish_full_log(&mut self) {
+        if let Some(mut writer) = self.full_log.as_mut().and_then(|log| log.writer.take()) {
+            let _ = writer.flush();
+        }
+    }
+
+    /// The retained head followed by the retained tail.
+    pub fn lines(&self) -> impl Iterator<Item = &String> {
+        self.head.iter().chain(self.lines.iter())
+    }
+
+    pub fn head_lines(&self) -> &[String] {
+        &self.head
+    }
+
+    pub fn tail_lines(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter()
+    }
+
+    /// Lines dropped between the head and the tail.
+    pub fn omitted_lines(&self) -> usize {
+        self.omitted_lines
+    }
+
+    pub fn full_log_path(&self) -> Option<&Path> {
+        self.full_log.as_ref().map(|log| log.path.as_path())
+    }
+
+    /// Head and tail as printed on failure, with a marker where lines were dropped.
+    pub fn excerpt(&self) -> Vec<String> {
+        let gap = (self.omitted_lines > 0)
+            .then(|| format!("... {} lines omitted ...", self.omitted_lines));
+        self.head
+            .iter()
+            .cloned()
+            .chain(gap)
+            .chain(self.lines.iter().cloned())
+            .collect()
+    }
+}
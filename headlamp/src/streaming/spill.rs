@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use tempfile::NamedTempFile;
+
+use super::OutputStream;
+
+/// Bytes of runner output allowed to wait in memory for the consumer before lines spill to a
+/// temp file.
+pub const SPILL_THRESHOLD_BYTES: usize = 8 * 1024 * 1024;
+
+/// Where a reader thread hands its lines.
+pub(crate) trait LineSink: Send + 'static {
+    /// `false` once nobody is listening any more.
+    fn send_line(&self, stream: OutputStream, line: String) -> bool;
+}
+
+impl LineSink for Sender<(OutputStream, String)> {
+    fn send_line(&self, stream: OutputStream, line: String) -> bool {
+        self.send((stream, line)).is_ok()
+    }
+}
+
+/// A line queue from the reader threads to the consumer. Up to `threshold` bytes wait in
+/// memory; past that, lines go to a temp file until the consumer has caught up, so a runner
+/// printing faster than headlamp renders cannot grow memory without bound. Lines come out in
+/// the order they went in.
+pub(crate) fn spill_channel(threshold: usize) -> (SpillSender, SpillReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            threshold,
+            memory: VecDeque::new(),
+            memory_bytes: 0,
+            spill: None,
+            spilled_lines: 0,
+            senders: 1,
+        }),
+        ready: Condvar::new(),
+    });
+    (
+        SpillSender {
+            shared: Arc::clone(&shared),
+        },
+        SpillReceiver { shared },
+    )
+}
+
+struct Shared {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+struct State {
+    threshold: usize,
+    memory: VecDeque<(OutputStream, String)>,
+    memory_bytes: usize,
+    spill: Option<SpillFile>,
+    spilled_lines: usize,
+    senders: usize,
+}
+
+struct SpillFile {
+    writer: BufWriter<std::fs::File>,
+    reader: BufReader<std::fs::File>,
+    /// Lines written but not read back yet.
+    pending: usize,
+    unflushed: bool,
+    _file: NamedTempFile,
+}
+
+impl SpillFile {
+    fn create() -> std::io::Result<Self> {
+        let file = NamedTempFile::with_prefix("headlamp-stream-")?;
+        Ok(Self {
+            writer: BufWriter::new(file.as_file().try_clone()?),
+            // A separate open file description, so reads do not move the write offset.
+            reader: BufReader::new(file.reopen()?),
+            pending: 0,
+            unflushed: false,
+            _file: file,
+        })
+    }
+
+    fn write(&mut self, stream: OutputStream, line: &str) -> std::io::Result<()> {
+        let tag = match stream {
+            OutputStream::Stdout => 0u8,
+            OutputStream::Stderr => 1,
+            OutputStream::Events => 2,
+        };
+        self.writer.write_all(&[tag])?;
+        self.writer.write_all(&(line.len() as u64).to_le_bytes())?;
+        self.writer.write_all(line.as_bytes())?;
+        self.pending += 1;
+        self.unflushed = true;
+        Ok(())
+    }
+
+    fn read(&mut self) -> std::io::Result<(OutputStream, String)> {
+        if std::mem::take(&mut self.unflushed) {
+            self.writer.flush()?;
+        }
+        let mut tag = [0u8; 1];
+        self.reader.read_exact(&mut tag)?;
+        let mut len = [0u8; 8];
+        self.reader.read_exact(&mut len)?;
+        let mut bytes = vec![0u8; u64::from_le_bytes(len) as usize];
+        self.reader.read_exact(&mut bytes)?;
+        self.pending -= 1;
+        let stream = match tag[0] {
+            0 => OutputStream::Stdout,
+            1 => OutputStream::Stderr,
+            _ => OutputStream::Events,
+        };
+        Ok((stream, super::lines::escape_invalid_utf8(bytes)))
+    }
+}
+
+impl State {
+    fn push(&mut self, stream: OutputStream, line: String) {
+        let fits = self.memory.is_empty() || self.memory_bytes + line.len() <= self.threshold;
+        if self.spill.is_none() && fits {
+            self.memory_bytes += line.len();
+            self.memory.push_back((stream, line));
+            return;
+        }
+        if self.spill.is_none() {
+            self.spill = SpillFile::create().ok();
+        }
+        let spilled = self
+            .spill
+            .as_mut()
+            .is_some_and(|spill| spill.write(stream, &line).is_ok());
+        if spilled {
+            self.spilled_lines += 1;
+        } else {
+            // No temp file to spill to: keeping the line beats dropping it. What the file still
+            // holds comes back first, so lines keep their order.
+            self.unspill();
+            self.memory_bytes += line.len();
+            self.memory.push_back((stream, line));
+        }
+    }
+
+    /// Stops spilling, moving the lines still in the file to the back of memory.
+    fn unspill(&mut self) {
+        let Some(mut spill) = self.spill.take() else {
+            return;
+        };
+        while spill.pending > 0 {
+            let Ok((stream, line)) = spill.read() else {
+                return;
+            };
+            self.memory_bytes += line.len();
+            self.memory.push_back((stream, line));
+        }
+    }
+
+    fn pop(&mut self) -> Option<(OutputStream, String)> {
+        if let Some((stream, line)) = self.memory.pop_front() {
+            self.memory_bytes -= line.len();
+            return Some((stream, line));
+        }
+        let spill = self.spill.as_mut()?;
+        let line = spill.read().ok();
+        if line.is_none() || spill.pending == 0 {
+            // Caught up (or the file broke): back to memory until the next burst.
+            self.spill = None;
+        }
+        line
+    }
+}
+
+pub(crate) struct SpillSender {
+    shared: Arc<Shared>,
+}
+
+impl Clone for SpillSender {
+    fn clone(&self) -> Self {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.senders += 1;
+        }
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Drop for SpillSender {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.senders -= 1;
+        }
+        self.shared.ready.notify_all();
+    }
+}
+
+impl LineSink for SpillSender {
+    fn send_line(&self, stream: OutputStream, line: String) -> bool {
+        let Ok(mut state) = self.shared.state.lock() else {
+            return false;
+        };
+        state.push(stream, line);
+        drop(state);
+        self.shared.ready.notify_one();
+        true
+    }
+}
+
+#[cfg(test)]
+impl SpillSender {
+    /// Makes further writes to the current spill file fail, as a full disk would.
+    pub(super) fn break_spill_file(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        let spill = state.spill.as_mut().expect("spilling");
+        let read_only = std::fs::File::open(spill._file.path()).unwrap();
+        spill.writer = BufWriter::with_capacity(0, read_only);
+        spill.unflushed = false;
+    }
+}
+
+pub(crate) struct SpillReceiver {
+    shared: Arc<Shared>,
+}
+
+impl SpillReceiver {
+    /// Like `mpsc::Receiver::recv_timeout`: disconnected once every sender is gone and the
+    /// queue is drained.
+    pub(crate) fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<(OutputStream, String), RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .map_err(|_| RecvTimeoutError::Disconnected)?;
+        loop {
+            if let Some(item) = state.pop() {
+                return Ok(item);
+            }
+            if state.senders == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self
+                .shared
+                .ready
+                .wait_timeout(state, deadline - now)
+                .map_err(|_| RecvTimeoutError::Disconnected)?
+                .0;
+        }
+    }
+
+    /// Lines that went through the temp file rather than memory so far.
+    pub(crate) fn spilled_lines(&self) -> usize {
+        self.shared
+            .state
+            .lock()
+            .map(|state| state.spilled_lines)
+            .unwrap_or(0)
+    }
+}
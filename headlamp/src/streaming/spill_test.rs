@@ -0,0 +1,80 @@
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use super::OutputStream;
+use super::spill::{LineSink, spill_channel};
+
+fn drain(rx: &super::spill::SpillReceiver) -> Vec<(OutputStream, String)> {
+    std::iter::from_fn(|| rx.recv_timeout(Duration::from_millis(10)).ok()).collect()
+}
+
+#[test]
+fn lines_past_the_threshold_spill_and_come_back_in_order() {
+    let (tx, rx) = spill_channel(8);
+    (0..5).for_each(|index| {
+        let stream = if index % 2 == 0 {
+            OutputStream::Stdout
+        } else {
+            OutputStream::Stderr
+        };
+        assert!(tx.send_line(stream, format!("line-{index}")));
+    });
+    assert_eq!(rx.spilled_lines(), 4);
+
+    let received = drain(&rx);
+    assert_eq!(
+        received,
+        vec![
+            (OutputStream::Stdout, "line-0".to_string()),
+            (OutputStream::Stderr, "line-1".to_string()),
+            (OutputStream::Stdout, "line-2".to_string()),
+            (OutputStream::Stderr, "line-3".to_string()),
+            (OutputStream::Stdout, "line-4".to_string()),
+        ]
+    );
+
+    // Caught up: the next line waits in memory again.
+    tx.send_line(OutputStream::Stdout, "late".to_string());
+    assert_eq!(rx.spilled_lines(), 4);
+    assert_eq!(drain(&rx), vec![(OutputStream::Stdout, "late".to_string())]);
+}
+
+#[test]
+fn a_failed_spill_write_keeps_the_lines_in_order() {
+    let (tx, rx) = spill_channel(8);
+    (0..3).for_each(|index| {
+        tx.send_line(OutputStream::Stdout, format!("line-{index}"));
+    });
+    tx.break_spill_file();
+    (3..5).for_each(|index| {
+        tx.send_line(OutputStream::Stdout, format!("line-{index}"));
+    });
+    assert_eq!(
+        drain(&rx)
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>(),
+        vec!["line-0", "line-1", "line-2", "line-3", "line-4"]
+    );
+}
+
+#[test]
+fn receiver_disconnects_once_senders_are_gone_and_queue_is_drained() {
+    let (tx, rx) = spill_channel(1024);
+    let other = tx.clone();
+    tx.send_line(OutputStream::Events, "{}".to_string());
+    drop(tx);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Ok((OutputStream::Events, "{}".to_string()))
+    );
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    drop(other);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
@@ -0,0 +1,52 @@
+//! `--show-logs` while several units run at once: every line a unit's adapter prints gets the
+//! unit's colored tag (`[web]`, `[api]`), so interleaved output can be attributed.
+
+use super::{OutputStream, StreamAction, StreamAdapter};
+use crate::format::ansi;
+
+/// Wraps a unit's adapter; with no tag the lines pass through untouched.
+pub(crate) struct UnitTagged<'a> {
+    inner: &'a mut dyn StreamAdapter,
+    prefix: Option<String>,
+}
+
+impl<'a> UnitTagged<'a> {
+    pub(crate) fn new(inner: &'a mut dyn StreamAdapter, tag: Option<&str>) -> Self {
+        Self {
+            inner,
+            prefix: tag.map(tag_prefix),
+        }
+    }
+
+    fn tagged(&self, line: String) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix} {line}"),
+            None => line,
+        }
+    }
+}
+
+impl StreamAdapter for UnitTagged<'_> {
+    fn on_start(&mut self) -> Option<String> {
+        self.inner.on_start()
+    }
+
+    fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        self.inner
+            .on_line(stream, line)
+            .into_iter()
+            .map(|action| match action {
+                StreamAction::PrintStdout(line) => StreamAction::PrintStdout(self.tagged(line)),
+                StreamAction::PrintStderr(line) => StreamAction::PrintStderr(self.tagged(line)),
+                other => other,
+            })
+            .collect()
+    }
+}
+
+/// `[tag]` in a color picked from the tag's bytes, so a unit keeps its color from run to run.
+pub(crate) fn tag_prefix(tag: &str) -> String {
+    const PALETTE: [fn(&str) -> String; 4] = [ansi::cyan, ansi::magenta, ansi::yellow, ansi::green];
+    let index = tag.bytes().map(usize::from).sum::<usize>() % PALETTE.len();
+    PALETTE[index](&format!("[{tag}]"))
+}
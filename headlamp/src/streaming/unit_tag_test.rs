@@ -0,0 +1,61 @@
+use super::unit_tag::{UnitTagged, tag_prefix};
+use super::{OutputStream, StreamAction, StreamAdapter};
+use crate::format::stacks::strip_ansi_simple;
+
+/// Prints stdout lines as they come and labels progress with stderr ones.
+struct Echo;
+
+impl StreamAdapter for Echo {
+    fn on_start(&mut self) -> Option<String> {
+        Some("start".to_string())
+    }
+
+    fn on_line(&mut self, stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        match stream {
+            OutputStream::Stderr => vec![
+                StreamAction::SetProgressLabel(line.to_string()),
+                StreamAction::PrintStderr(line.to_string()),
+            ],
+            _ => vec![StreamAction::PrintStdout(line.to_string())],
+        }
+    }
+}
+
+fn printed(actions: Vec<StreamAction>) -> Vec<String> {
+    actions
+        .into_iter()
+        .map(|action| match action {
+            StreamAction::PrintStdout(line) => format!("out {}", strip_ansi_simple(&line)),
+            StreamAction::PrintStderr(line) => format!("err {}", strip_ansi_simple(&line)),
+            StreamAction::SetProgressLabel(label) => format!("label {label}"),
+            other => format!("{other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn printed_lines_carry_the_unit_tag() {
+    let mut echo = Echo;
+    let mut tagged = UnitTagged::new(&mut echo, Some("web"));
+    assert_eq!(tagged.on_start().as_deref(), Some("start"));
+    assert_eq!(
+        printed(tagged.on_line(OutputStream::Stdout, "console.log hi")),
+        vec!["out [web] console.log hi"]
+    );
+    assert_eq!(
+        printed(tagged.on_line(OutputStream::Stderr, "warn")),
+        vec!["label warn", "err [web] warn"]
+    );
+}
+
+#[test]
+fn untagged_lines_pass_through_and_tags_keep_their_color() {
+    let mut echo = Echo;
+    let mut untagged = UnitTagged::new(&mut echo, None);
+    assert_eq!(
+        printed(untagged.on_line(OutputStream::Stdout, "plain")),
+        vec!["out plain"]
+    );
+    assert_eq!(tag_prefix("api"), tag_prefix("api"));
+    assert_eq!(strip_ansi_simple(&tag_prefix("crate:core")), "[crate:core]");
+}
@@ -7,6 +7,33 @@ pub struct TestRunModel {
     pub start_time: u64,
     pub test_results: Vec<TestSuiteResult>,
     pub aggregated: TestRunAggregated,
+    /// Jest projects and cargo packages the selection matched no tests in, so they never ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_units: Vec<SkippedUnit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedUnit {
+    pub kind: SkippedUnitKind,
+    /// A jest project's label or a cargo package name.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkippedUnitKind {
+    JestProject,
+    CargoPackage,
+}
+
+impl SkippedUnitKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::JestProject => "jest project",
+            Self::CargoPackage => "cargo package",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -41,6 +68,17 @@ pub struct TestCaseResult {
     pub location: Option<TestLocation>,
     pub failure_messages: Vec<String>,
     pub failure_details: Option<Vec<serde_json::Value>>,
+    /// A shell command that re-runs just this test, when the runner adapter can build one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repro: Option<String>,
+    /// Why a skipped test was skipped (`#[ignore = "..."]`, `pytest.skip("...")`), when the
+    /// runner says.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    /// How many times the runner ran the test when it retried it (nextest `retries`); a test
+    /// that passed after more than one attempt is flaky.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
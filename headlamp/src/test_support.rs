@@ -0,0 +1,57 @@
+//! Helpers for the tests headlamp runs, behind the `test-support` feature.
+//!
+//! `record_http` logs an HTTP call a Rust test made, so a failing test's output shows it the way
+//! jest failures show theirs. It is a no-op outside headlamp, which sets `HEADLAMP_HTTP_LOG`.
+//!
+//! `model` builds run models for tests of what headlamp renders and merges.
+
+pub mod model;
+
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub use crate::cargo::http_log::HTTP_LOG_ENV;
+
+/// One call, as an HTTP client wrapper or mock server in the test saw it.
+#[derive(Debug, Clone, Default)]
+pub struct HttpCall<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    /// `None` when no response arrived (refused, reset, timed out).
+    pub status_code: Option<u16>,
+    pub duration: Duration,
+    pub content_type: Option<&'a str>,
+    pub body_preview: Option<&'a str>,
+}
+
+/// Appends `call` to the run's HTTP log, attributed to the test running on this thread.
+pub fn record_http(call: &HttpCall<'_>) {
+    let Some(path) = std::env::var_os(HTTP_LOG_ENV) else {
+        return;
+    };
+    let line = format!("{}\n", http_event(call, std::thread::current().name()));
+    // One write per line keeps lines whole when tests on several threads append at once.
+    let _ = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+}
+
+fn http_event(call: &HttpCall<'_>, test_name: Option<&str>) -> serde_json::Value {
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0);
+    serde_json::json!({
+        "type": if call.status_code.is_some() { "httpResponse" } else { "httpAbort" },
+        "timestampMs": timestamp_ms,
+        "testName": test_name.unwrap_or_default(),
+        "method": call.method.to_ascii_uppercase(),
+        "url": call.url,
+        "statusCode": call.status_code,
+        "durationMs": call.duration.as_millis() as u64,
+        "contentType": call.content_type,
+        "bodyPreview": call.body_preview,
+    })
+}
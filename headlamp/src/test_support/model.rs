@@ -0,0 +1,76 @@
+//! Builders for the run model the renderers, reports and merges consume, so tests state only
+//! the paths, names and outcomes they care about.
+
+use crate::test_model::{TestCaseResult, TestRunAggregated, TestRunModel, TestSuiteResult};
+
+/// A test named `name` with `status` ("passed", "failed", "pending", ...) that took 1ms.
+pub fn case(name: &str, status: &str) -> TestCaseResult {
+    TestCaseResult {
+        title: name.to_string(),
+        full_name: name.to_string(),
+        status: status.to_string(),
+        timed_out: None,
+        duration: 1,
+        location: None,
+        failure_messages: vec![],
+        failure_details: None,
+        repro: None,
+        skip_reason: None,
+        attempts: None,
+    }
+}
+
+/// A failed test whose runner reported `message`.
+pub fn failed_case(name: &str, message: &str) -> TestCaseResult {
+    TestCaseResult {
+        failure_messages: vec![message.to_string()],
+        ..case(name, "failed")
+    }
+}
+
+/// The suite of `path`; it failed when any of its tests did.
+pub fn suite(path: &str, cases: Vec<TestCaseResult>) -> TestSuiteResult {
+    let failed = cases.iter().any(|case| case.status == "failed");
+    TestSuiteResult {
+        test_file_path: path.to_string(),
+        status: if failed { "failed" } else { "passed" }.to_string(),
+        timed_out: None,
+        failure_message: String::new(),
+        failure_details: None,
+        test_exec_error: None,
+        console: None,
+        test_results: cases,
+    }
+}
+
+/// A run of `suites` starting at 0, with totals counted from them and a run time that adds up
+/// their tests' durations.
+pub fn run_model(suites: Vec<TestSuiteResult>) -> TestRunModel {
+    let tests = || suites.iter().flat_map(|suite| suite.test_results.iter());
+    let count_tests = |status: &str| tests().filter(|case| case.status == status).count() as u64;
+    let failed_suites = suites
+        .iter()
+        .filter(|suite| suite.status == "failed")
+        .count() as u64;
+    let aggregated = TestRunAggregated {
+        num_total_test_suites: suites.len() as u64,
+        num_passed_test_suites: suites.len() as u64 - failed_suites,
+        num_failed_test_suites: failed_suites,
+        num_total_tests: tests().count() as u64,
+        num_passed_tests: count_tests("passed"),
+        num_failed_tests: count_tests("failed"),
+        num_pending_tests: count_tests("pending"),
+        num_todo_tests: count_tests("todo"),
+        num_timed_out_tests: None,
+        num_timed_out_test_suites: None,
+        start_time: 0,
+        success: failed_suites == 0,
+        run_time_ms: Some(tests().map(|case| case.duration).sum()),
+    };
+    TestRunModel {
+        start_time: 0,
+        test_results: suites,
+        aggregated,
+        skipped_units: vec![],
+    }
+}
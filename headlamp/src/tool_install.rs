@@ -0,0 +1,130 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use headlamp_core::args::ParsedArgs;
+
+use crate::run::RunError;
+
+/// The documented install command for a tool headlamp shells out to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallRecipe {
+    pub tool: String,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl InstallRecipe {
+    pub fn cargo_nextest() -> Self {
+        Self {
+            tool: "cargo-nextest".to_string(),
+            program: "cargo".to_string(),
+            args: ["install", "cargo-nextest", "--locked"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+
+    pub fn cargo_llvm_cov() -> Self {
+        Self {
+            tool: "cargo-llvm-cov".to_string(),
+            program: "cargo".to_string(),
+            args: ["install", "cargo-llvm-cov", "--locked"]
+                .map(str::to_string)
+                .to_vec(),
+        }
+    }
+
+    pub fn llvm_tools(toolchain: &str) -> Self {
+        Self {
+            tool: "llvm-tools".to_string(),
+            program: "rustup".to_string(),
+            args: [
+                "component",
+                "add",
+                "llvm-tools-preview",
+                "--toolchain",
+                toolchain,
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        }
+    }
+
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Runs `check`; when it reports a missing tool, installs it via `recipe` (with `--auto-install`,
+/// or after a yes at an interactive prompt) and checks once more.
+pub(crate) fn ensure_with_install(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    recipe: &InstallRecipe,
+    check: impl Fn() -> Result<(), RunError>,
+) -> Result<(), RunError> {
+    let (runner, hint) = match check() {
+        Err(RunError::MissingRunner { runner, hint }) => (runner, hint),
+        other => return other,
+    };
+    if !(args.auto_install || confirm_install(args, recipe)) {
+        return Err(RunError::MissingRunner {
+            runner,
+            hint: format!("{hint}; rerun with --auto-install to install it automatically"),
+        });
+    }
+    run_install(repo_root, recipe)?;
+    check()
+}
+
+fn confirm_install(args: &ParsedArgs, recipe: &InstallRecipe) -> bool {
+    let interactive = !args.ci && std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    if !interactive {
+        return false;
+    }
+    eprint!(
+        "headlamp: {} is missing. Run `{}` now? [y/N] ",
+        recipe.tool,
+        recipe.command_line()
+    );
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    let read = std::io::stdin().lock().read_line(&mut answer);
+    read.is_ok() && is_yes(&answer)
+}
+
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+fn run_install(repo_root: &Path, recipe: &InstallRecipe) -> Result<(), RunError> {
+    eprintln!(
+        "headlamp: installing {}: {}",
+        recipe.tool,
+        recipe.command_line()
+    );
+    let status = std::process::Command::new(&recipe.program)
+        .args(&recipe.args)
+        .current_dir(repo_root)
+        .stdout(if crate::format::report_out::is_quiet() {
+            std::process::Stdio::from(std::io::stderr())
+        } else {
+            std::process::Stdio::inherit()
+        })
+        .stderr(std::process::Stdio::inherit())
+        .status()
+        .map_err(RunError::SpawnFailed)?;
+    status
+        .success()
+        .then_some(())
+        .ok_or_else(|| RunError::CommandFailed {
+            message: format!(
+                "`{}` failed while installing {}",
+                recipe.command_line(),
+                recipe.tool
+            ),
+        })
+}
@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use indexmap::IndexSet;
+use path_slash::PathExt;
+use regex::Regex;
+
+use headlamp_core::args::ParsedArgs;
+use headlamp_core::config::Typecheck;
+use headlamp_core::format::ctx::make_ctx_for_args;
+use headlamp_core::format::vitest::render_vitest_from_test_model;
+
+use crate::checks::absolute_slash;
+use crate::run::RunError;
+use crate::session::RunSession;
+use crate::test_model::TestRunModel;
+
+/// One `tsc --pretty false` diagnostic, with `file` made absolute.
+pub type TscDiagnostic = crate::checks::Finding;
+
+static TSC_LOCATED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)\((\d+),(\d+)\): error (TS\d+): (.*)$").unwrap());
+static TSC_GLOBAL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^error (TS\d+): (.*)$").unwrap());
+
+/// Parses tsc's plain output. Paths are relative to `cwd`; errors without a location (a bad
+/// tsconfig, say) are pinned to `fallback_file`. Indented lines continue the previous message.
+pub fn parse_tsc_output(output: &str, cwd: &Path, fallback_file: &Path) -> Vec<TscDiagnostic> {
+    let mut diagnostics: Vec<TscDiagnostic> = vec![];
+    for line in output.lines().map(|line| line.trim_end()) {
+        if let Some(caps) = TSC_LOCATED_RE.captures(line) {
+            diagnostics.push(TscDiagnostic {
+                file: absolute_slash(cwd, &caps[1]),
+                line: caps[2].parse().unwrap_or(1),
+                column: caps[3].parse().unwrap_or(1),
+                code: caps[4].to_string(),
+                message: caps[5].to_string(),
+            });
+        } else if let Some(caps) = TSC_GLOBAL_RE.captures(line) {
+            diagnostics.push(TscDiagnostic {
+                file: fallback_file.to_slash_lossy().to_string(),
+                line: 1,
+                column: 1,
+                code: caps[1].to_string(),
+                message: caps[2].to_string(),
+            });
+        } else if line.starts_with(char::is_whitespace)
+            && let Some(last) = diagnostics.last_mut()
+        {
+            last.message.push('\n');
+            last.message.push_str(line.trim_start());
+        }
+    }
+    diagnostics
+}
+
+/// The nearest `tsconfig.json` above each path, searching no higher than `repo_root`.
+pub fn tsconfigs_for_paths(repo_root: &Path, paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let start = if path.is_dir() {
+                path.as_path()
+            } else {
+                path.parent()?
+            };
+            start
+                .ancestors()
+                .take_while(|dir| dir.starts_with(repo_root))
+                .map(|dir| dir.join("tsconfig.json"))
+                .find(|candidate| candidate.is_file())
+        })
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Type errors as a run model, one failed suite per file (see `checks::build_findings_model`).
+pub fn build_typecheck_model(diagnostics: &[TscDiagnostic]) -> TestRunModel {
+    crate::checks::build_findings_model("typecheck", diagnostics)
+}
+
+/// What to typecheck for a jest run: the selected files and the tests they pulled in, or every
+/// jest project when nothing was selected.
+pub(crate) fn jest_typecheck_scope(
+    selection_paths_abs: &[String],
+    selected_test_paths_abs: &[String],
+    project_configs: &[crate::jest_projects::JestProject],
+) -> Vec<PathBuf> {
+    let selected = selection_paths_abs
+        .iter()
+        .chain(selected_test_paths_abs)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    if !selected.is_empty() {
+        return selected;
+    }
+    project_configs
+        .iter()
+        .map(|project| project.config.clone())
+        .collect()
+}
+
+/// Runs the `--typecheck` phase for `paths` and prints any type errors. Returns whether the
+/// tests should be skipped: only in `gate` mode, and only when there were errors.
+pub(crate) fn run_typecheck_phase(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &RunSession,
+    paths: &[PathBuf],
+) -> Result<bool, RunError> {
+    if args.typecheck == Typecheck::Off {
+        return Ok(false);
+    }
+    let tsconfigs = tsconfigs_for_paths(repo_root, paths);
+    if tsconfigs.is_empty() {
+        eprintln!("headlamp: --typecheck found no tsconfig.json for the selection; skipping it");
+        return Ok(false);
+    }
+    let started_at = std::time::Instant::now();
+    let mut diagnostics = vec![];
+    for tsconfig in &tsconfigs {
+        diagnostics.extend(run_tsc(repo_root, args, session, tsconfig)?);
+    }
+    if diagnostics.is_empty() {
+        return Ok(false);
+    }
+    let mut model = build_typecheck_model(&diagnostics);
+    model.aggregated.run_time_ms = Some(started_at.elapsed().as_millis() as u64);
+    let ctx = make_ctx_for_args(repo_root, args, true);
+    let rendered = render_vitest_from_test_model(&model, &ctx, false);
+    crate::format::report_out::println(&rendered);
+    let gate = args.typecheck == Typecheck::Gate;
+    eprintln!(
+        "headlamp: typecheck found {} error(s) in {} file(s); {}",
+        diagnostics.len(),
+        model.test_results.len(),
+        if gate {
+            "skipping tests (--typecheck=warn runs them anyway)"
+        } else {
+            "running tests anyway"
+        }
+    );
+    Ok(gate)
+}
+
+fn run_tsc(
+    repo_root: &Path,
+    args: &ParsedArgs,
+    session: &RunSession,
+    tsconfig: &Path,
+) -> Result<Vec<TscDiagnostic>, RunError> {
+    let dir = tsconfig.parent().unwrap_or(repo_root);
+    let expression = match args.typecheck_command.as_deref() {
+        Some(command) => {
+            crate::run::shell_expression(&format!("{command} -p tsconfig.json --pretty false"))
+        }
+        None => duct::cmd(
+            tsc_bin(repo_root, dir)?,
+            ["--noEmit", "-p", "tsconfig.json", "--pretty", "false"],
+        ),
+    };
+    let output = session
+        .env()
+        .apply_to_expression(expression)
+        .dir(dir)
+        .stderr_to_stdout()
+        .stdout_capture()
+        .unchecked()
+        .run()
+        .map_err(RunError::Io)?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let diagnostics = parse_tsc_output(&text, dir, tsconfig);
+    if diagnostics.is_empty() && !output.status.success() {
+        return Err(RunError::CommandFailed {
+            message: format!(
+                "typecheck failed for {} without reporting errors:\n{}",
+                tsconfig.display(),
+                text.trim_end()
+            ),
+        });
+    }
+    Ok(diagnostics)
+}
+
+/// The project's own `tsc` from the nearest `node_modules/.bin`, else the one on PATH.
+fn tsc_bin(repo_root: &Path, dir: &Path) -> Result<PathBuf, RunError> {
+    let bin_name = if cfg!(windows) { "tsc.cmd" } else { "tsc" };
+    dir.ancestors()
+        .take_while(|ancestor| ancestor.starts_with(repo_root))
+        .map(|ancestor| ancestor.join("node_modules").join(".bin").join(bin_name))
+        .find(|candidate| candidate.is_file())
+        .or_else(|| which::which("tsc").ok())
+        .ok_or_else(|| RunError::MissingRunner {
+            runner: "tsc".to_string(),
+            hint: "install typescript, or set --typecheck-command".to_string(),
+        })
+}
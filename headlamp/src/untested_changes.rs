@@ -0,0 +1,83 @@
+//! After a `--changed` run: the changed source files no test exercises — no test imports them,
+//! directly or through other files, and no test hits a route they serve — so the selection had
+//! nothing to run for them. `--fail-on-untested-changes` fails a passing run that has any.
+
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+
+use crate::args::ParsedArgs;
+use crate::format::ansi;
+
+/// The repo-relative paths in `changed` (absolute, as `git::changed_files` lists them) that are
+/// source files, not tests, and that no test file exercises. Deleted files and files of no known
+/// language are left out.
+pub fn untested_changed_files(repo_root: &Path, changed: &[PathBuf]) -> Vec<String> {
+    let sources = changed
+        .iter()
+        .filter(|abs| abs.is_file())
+        .filter(|abs| !is_test_or_test_support(abs))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut untested =
+        crate::selection::related_files::sources_without_related_tests(repo_root, &sources)
+            .into_iter()
+            .map(|abs| {
+                abs.strip_prefix(repo_root)
+                    .unwrap_or(&abs)
+                    .to_slash_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+    untested.sort();
+    untested.dedup();
+    untested
+}
+
+/// Test files, and what only tests use: fixtures under a tests dir, pytest's `conftest.py`.
+fn is_test_or_test_support(path: &Path) -> bool {
+    crate::runner_detect::is_test_file(path)
+        || path.file_name().is_some_and(|name| name == "conftest.py")
+        || path
+            .components()
+            .any(|part| matches!(part.as_os_str().to_str(), Some("tests" | "__tests__")))
+}
+
+/// The "changed but untested" section; empty when every changed file is covered.
+pub fn render_untested_changes(files: &[String]) -> Vec<String> {
+    if files.is_empty() {
+        return vec![];
+    }
+    std::iter::once(format!(
+        "{} {}",
+        ansi::yellow("Changed but untested"),
+        ansi::dim("(no test imports these files or hits their routes)")
+    ))
+    .chain(
+        files
+            .iter()
+            .map(|file| format!("  {} {file}", ansi::dim("•"))),
+    )
+    .collect()
+}
+
+/// Warns about the changed files of a `--changed` run that no test exercises; under
+/// `--fail-on-untested-changes` a passing run that has any exits 1 instead.
+pub fn report_untested_changes(repo_root: &Path, args: &ParsedArgs, code: i32) -> i32 {
+    let Some(mode) = args.changed else {
+        return code;
+    };
+    let changed = crate::git::changed_files(repo_root, mode).unwrap_or_default();
+    let untested = untested_changed_files(repo_root, &changed);
+    render_untested_changes(&untested)
+        .iter()
+        .for_each(|line| eprintln!("{line}"));
+    if !args.fail_on_untested_changes || untested.is_empty() || code != 0 {
+        return code;
+    }
+    eprintln!(
+        "headlamp: {} changed file(s) without tests and --fail-on-untested-changes is set",
+        untested.len()
+    );
+    1
+}
@@ -13,7 +13,6 @@ pub enum WatchDecision {
 pub fn run_polling_watch_loop(
     repo_root: &Path,
     poll_interval: Duration,
-    verbose: bool,
     mut run_once: impl FnMut() -> i32,
 ) -> i32 {
     let _initial_exit_code = run_once();
@@ -23,9 +22,7 @@ pub fn run_polling_watch_loop(
         match watch_decision(repo_root, &mut last_fingerprint) {
             WatchDecision::Continue => {}
             WatchDecision::Rerun => {
-                if verbose {
-                    eprintln!("headlamp: watch detected changes, re-running");
-                }
+                tracing::debug!(target: "watch", "detected changes, re-running");
                 let _ = run_once();
             }
         }
@@ -63,7 +60,7 @@ fn compute_repo_fingerprint(repo_root: &Path) -> u64 {
     hasher.finish()
 }
 
-fn walk_watch_paths(repo_root: &Path) -> impl Iterator<Item = PathBuf> {
+pub(crate) fn walk_watch_paths(repo_root: &Path) -> impl Iterator<Item = PathBuf> {
     WalkBuilder::new(repo_root)
         .standard_filters(true)
         .hidden(false)
@@ -57,6 +57,7 @@ pub struct ArtifactPaths {
     pub sides: Vec<SideArtifactPaths>,
     pub diffs: Vec<String>,
     pub report: String,
+    pub report_html: String,
     pub meta: String,
     pub analysis: String,
     pub reruns_dir: String,
@@ -225,6 +226,11 @@ fn allowlisted_env(
         "PYTHONPATH",
         "HEADLAMP_DIAGNOSTICS_DIR",
         "HEADLAMP_PARITY_DUMP_ROOT",
+        "HEADLAMP_PARITY_BLESS",
+        "HEADLAMP_PARITY_GOLDEN_DIR",
+        "HEADLAMP_PARITY_RETRIES",
+        "HEADLAMP_PARITY_WORKTREE_POOL_SIZE",
+        "HEADLAMP_PARITY_WORKTREE_LEASE_TIMEOUT_SECS",
     ];
     KEYS.iter()
         .filter_map(|k| env.get(*k).map(|v| ((*k).to_string(), v.clone())))
@@ -324,7 +330,11 @@ fn normalize_rerun_side(
     }
 }
 
-fn normalize_text(repo: &Path, normalizer: parity_meta::NormalizerKind, raw: String) -> String {
+pub(crate) fn normalize_text(
+    repo: &Path,
+    normalizer: parity_meta::NormalizerKind,
+    raw: String,
+) -> String {
     match normalizer {
         parity_meta::NormalizerKind::NonTty => normalize::normalize(raw, repo),
         parity_meta::NormalizerKind::TtyUi => normalize::normalize_tty_ui(raw, repo),
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use crate::parity_meta::ParityCompareInput;
+
+#[derive(Debug, Clone)]
+pub(super) struct SideDumpPaths {
+    pub(super) normalized: std::path::PathBuf,
+    pub(super) raw: std::path::PathBuf,
+    pub(super) tokens: std::path::PathBuf,
+    pub(super) ast: std::path::PathBuf,
+}
+
+pub(super) fn dump_root_dir() -> std::path::PathBuf {
+    std::env::var("HEADLAMP_PARITY_DUMP_ROOT")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+pub(super) fn dump_dir_for_repo_case_run(repo: &Path, safe_case: &str) -> std::path::PathBuf {
+    // Use a stable key so CI artifacts are grouped deterministically even if the worktree folder
+    // name changes run-to-run.
+    let repo_key = headlamp::fast_related::stable_repo_key_hash_12(repo);
+    let run_id = format!(
+        "run-{}-{}",
+        std::process::id(),
+        crate::hashing::next_capture_id()
+    );
+    dump_root_dir()
+        .join("parity")
+        .join(repo_key)
+        .join(safe_case)
+        .join(run_id)
+}
+
+pub(super) fn build_side_dump_paths(
+    dump_dir: &Path,
+    compare: &ParityCompareInput,
+) -> Vec<SideDumpPaths> {
+    compare
+        .sides
+        .iter()
+        .map(|side| {
+            let side_key = side.label.file_safe_label();
+            SideDumpPaths {
+                normalized: dump_dir.join(format!("{side_key}--normalized.txt")),
+                raw: dump_dir.join(format!("{side_key}--raw.txt")),
+                tokens: dump_dir.join(format!("{side_key}--tokens.json")),
+                ast: dump_dir.join(format!("{side_key}--ast.json")),
+            }
+        })
+        .collect::<Vec<_>>()
+}
+
+pub(super) fn write_side_dumps(compare: &ParityCompareInput, side_dump_paths: &[SideDumpPaths]) {
+    compare
+        .sides
+        .iter()
+        .zip(side_dump_paths.iter())
+        .for_each(|(side, paths)| write_side_dump(side, paths));
+}
+
+fn write_side_dump(side: &crate::parity_meta::ParityCompareSideInput, paths: &SideDumpPaths) {
+    let _ = std::fs::write(&paths.normalized, &side.normalized);
+    let _ = std::fs::write(&paths.raw, &side.raw);
+    let raw_tokens = crate::token_ast::build_token_stream(&side.raw);
+    let norm_tokens = crate::token_ast::build_token_stream(&side.normalized);
+    let doc_ast = crate::token_ast::build_document_ast(&side.normalized);
+    let _ = std::fs::File::create(&paths.tokens)
+        .ok()
+        .and_then(|mut file| {
+            serde_json::to_writer_pretty(&mut file, &(raw_tokens, norm_tokens)).ok()
+        });
+    let _ = std::fs::File::create(&paths.ast)
+        .ok()
+        .and_then(|mut file| serde_json::to_writer_pretty(&mut file, &doc_ast).ok());
+}
+
+pub(super) fn write_diffs(
+    compare: &ParityCompareInput,
+    dump_dir: &Path,
+    safe: &str,
+) -> Vec<String> {
+    let pivot_index = crate::cluster::pick_pivot_index(compare);
+    crate::cluster::cluster_indices_by_normalized(compare)
+        .iter()
+        .filter(|cluster| !cluster.member_indices.contains(&pivot_index))
+        .filter_map(|cluster| pick_min_label_index(compare, cluster))
+        .map(|other_index| write_diff(compare, dump_dir, safe, pivot_index, other_index))
+        .collect::<Vec<_>>()
+}
+
+/// One diff against the golden per distinct drifted output, named after its first side.
+pub(super) fn write_golden_diffs(
+    compare: &ParityCompareInput,
+    golden: &crate::golden::Golden,
+    drifted: &[usize],
+    dump_dir: &Path,
+) -> Vec<String> {
+    let mut seen = std::collections::BTreeSet::<&str>::new();
+    drifted
+        .iter()
+        .copied()
+        .filter(|&index| seen.insert(compare.sides[index].normalized.as_str()))
+        .map(|index| {
+            let side = &compare.sides[index];
+            let side_key = side.label.file_safe_label();
+            let diff_path = dump_dir.join(format!("golden--diff--golden--vs--{side_key}.txt"));
+            let diff = similar_asserts::SimpleDiff::from_str(
+                &golden.render(),
+                &crate::golden::Golden {
+                    exit: side.exit,
+                    normalized: side.normalized.clone(),
+                }
+                .render(),
+                "golden",
+                &side.label.display_label(),
+            )
+            .to_string();
+            let _ = std::fs::write(&diff_path, &diff);
+            diff_path.to_string_lossy().to_string()
+        })
+        .collect()
+}
+
+fn pick_min_label_index(
+    compare: &ParityCompareInput,
+    cluster: &crate::cluster::OutputCluster,
+) -> Option<usize> {
+    cluster.member_indices.iter().copied().min_by(|&a, &b| {
+        compare.sides[a]
+            .label
+            .display_label()
+            .cmp(&compare.sides[b].label.display_label())
+    })
+}
+
+fn write_diff(
+    compare: &ParityCompareInput,
+    dump_dir: &Path,
+    safe: &str,
+    pivot_index: usize,
+    other_index: usize,
+) -> String {
+    let pivot_key = compare.sides[pivot_index].label.file_safe_label();
+    let other_key = compare.sides[other_index].label.file_safe_label();
+    let diff_path = dump_dir.join(format!("{safe}--diff--{pivot_key}--vs--{other_key}.txt"));
+    let diff = similar_asserts::SimpleDiff::from_str(
+        &compare.sides[pivot_index].normalized,
+        &compare.sides[other_index].normalized,
+        &compare.sides[pivot_index].label.display_label(),
+        &compare.sides[other_index].label.display_label(),
+    )
+    .to_string();
+    let _ = std::fs::write(&diff_path, &diff);
+    diff_path.to_string_lossy().to_string()
+}
@@ -5,6 +5,18 @@ use serde::Serialize;
 use crate::parity_meta::{ParityCompareInput, ParitySideLabel};
 use crate::types::ParityRunGroup;
 
+use dumps::{
+    SideDumpPaths, build_side_dump_paths, dump_dir_for_repo_case_run, dump_root_dir, write_diffs,
+    write_golden_diffs, write_side_dumps,
+};
+
+mod dumps;
+mod retry;
+#[cfg(all(test, unix))]
+mod retry_test;
+
+pub use retry::RETRIES_ENV;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct RerunMeta {
     pub variant: String,
@@ -138,30 +150,124 @@ pub fn assert_parity_with_diagnostics(
     compare: &ParityCompareInput,
     run_group: Option<&ParityRunGroup>,
 ) {
-    if compare.sides.len() < 2 || parity_matches(compare) {
+    if compare.sides.len() < 2 {
         return;
     }
-
     let safe = safe_case(case);
+    let golden_path = crate::golden::golden_path(&crate::golden::golden_dir(), &safe, compare);
+    bless_golden(case, &golden_path, compare);
+    let golden = crate::golden::read_golden(&golden_path);
+    let drifted = golden
+        .as_ref()
+        .map(|golden| crate::golden::sides_drifting_from(golden, compare))
+        .unwrap_or_default();
+    if parity_matches(compare) && drifted.is_empty() {
+        return;
+    }
+
     let dump_dir = dump_dir_for_repo_case_run(repo, &safe);
     let _ = std::fs::create_dir_all(&dump_dir);
+    let mismatch = Mismatch {
+        repo,
+        case,
+        compare,
+        run_group,
+        golden: golden.as_ref(),
+        drifted: &drifted,
+    };
+    let reruns_dir = dump_dir.join("reruns");
+    let retry = retry::retry_until_match(
+        repo,
+        compare,
+        run_group,
+        golden.as_ref(),
+        retry::retries_from_env(),
+        &reruns_dir,
+    );
+    let written = write_mismatch_artifacts(&mismatch, &retry.reruns, &dump_dir, &reruns_dir);
+    if let Some(variant) = &retry.passed_on {
+        eprintln!(
+            "[headlamp_parity_support] flaky parity case={case}: sides agreed on {variant}\nanalysis_path={}",
+            written.analysis_path.display()
+        );
+        return;
+    }
+
+    let dump_dir_display = dump_dir.to_string_lossy();
+    let summary = build_one_screen_summary(compare, run_group, &dump_dir_display);
+    panic!(
+        "parity mismatch case={case}{}\ndump_root={}\ndump_dir={}\nreport_path={}\nreport_html={}\nanalysis_path={}\n{}\n{}\n\n{}",
+        stable_suffix(&retry.reruns),
+        dump_root_dir().to_string_lossy(),
+        dump_dir_display,
+        written.report_path.display(),
+        written.report_html_path.display(),
+        written.analysis_path.display(),
+        golden_summary(compare, &golden_path, golden.is_some(), &drifted),
+        summary,
+        truncate_report_for_panic(&written.report),
+    );
+}
 
+struct Mismatch<'a> {
+    repo: &'a Path,
+    case: &'a str,
+    compare: &'a ParityCompareInput,
+    run_group: Option<&'a ParityRunGroup>,
+    golden: Option<&'a crate::golden::Golden>,
+    drifted: &'a [usize],
+}
+
+struct WrittenReports {
+    report: String,
+    report_path: std::path::PathBuf,
+    report_html_path: std::path::PathBuf,
+    analysis_path: std::path::PathBuf,
+}
+
+fn write_mismatch_artifacts(
+    mismatch: &Mismatch,
+    reruns: &[RerunMeta],
+    dump_dir: &Path,
+    reruns_dir: &Path,
+) -> WrittenReports {
+    let compare = mismatch.compare;
     let report_path = dump_dir.join("report.txt");
+    let report_html_path = dump_dir.join("report.html");
     let analysis_path = dump_dir.join("analysis.json");
     let meta_path = dump_dir.join("meta.json");
 
-    let side_dump_paths = build_side_dump_paths(&dump_dir, compare);
+    let side_dump_paths = build_side_dump_paths(dump_dir, compare);
     write_side_dumps(compare, &side_dump_paths);
-    let diff_paths = write_diffs(compare, &dump_dir, "diff");
-    let artifacts = build_artifacts(
+    let mut diff_paths = write_diffs(compare, dump_dir, "diff");
+    if let Some(golden) = mismatch.golden {
+        diff_paths.extend(write_golden_diffs(
+            compare,
+            golden,
+            mismatch.drifted,
+            dump_dir,
+        ));
+    }
+    let mut artifacts = build_artifacts(
         compare,
         &side_dump_paths,
         diff_paths,
         &report_path,
+        &report_html_path,
         &meta_path,
         &analysis_path,
     );
-    let bundle = crate::diagnostics::build_bundle(repo, case, artifacts, compare, &[], run_group);
+    if !reruns.is_empty() {
+        artifacts.reruns_dir = reruns_dir.to_string_lossy().to_string();
+    }
+    let bundle = crate::diagnostics::build_bundle(
+        mismatch.repo,
+        mismatch.case,
+        artifacts,
+        compare,
+        reruns,
+        mismatch.run_group,
+    );
     if let Ok(mut file) = std::fs::File::create(&analysis_path) {
         let _ = serde_json::to_writer_pretty(&mut file, &bundle);
     }
@@ -171,19 +277,61 @@ pub fn assert_parity_with_diagnostics(
 
     let report = crate::diff_report::build_parity_report_with_meta(compare);
     let _ = std::fs::write(&report_path, &report);
+    let report_html = crate::diff_report::build_parity_html_report(mismatch.case, compare);
+    let _ = std::fs::write(&report_html_path, report_html);
+    WrittenReports {
+        report,
+        report_path,
+        report_html_path,
+        analysis_path,
+    }
+}
 
-    let dump_root = dump_root_dir();
-    let dump_dir_display = dump_dir.to_string_lossy();
-    let summary = build_one_screen_summary(compare, run_group, &dump_dir_display);
-    panic!(
-        "parity mismatch case={case}\ndump_root={}\ndump_dir={}\nreport_path={}\nanalysis_path={}\n{}\n\n{}",
-        dump_root.to_string_lossy(),
-        dump_dir_display,
-        report_path.display(),
-        analysis_path.display(),
-        summary,
-        truncate_report_for_panic(&report),
-    );
+fn stable_suffix(reruns: &[RerunMeta]) -> String {
+    match reruns.len() {
+        0 => String::new(),
+        retries => format!(" (stable across {retries} retries)"),
+    }
+}
+
+fn bless_golden(case: &str, golden_path: &Path, compare: &ParityCompareInput) {
+    match crate::golden::bless(
+        golden_path,
+        compare,
+        &crate::golden::BlessRequest::from_env(),
+    ) {
+        Ok(Some(index)) => eprintln!(
+            "[headlamp_parity_support] blessed case={case} from {} -> {}",
+            compare.sides[index].label.display_label(),
+            golden_path.display()
+        ),
+        Ok(None) => {}
+        Err(err) => panic!(
+            "failed to bless case={case} golden={}: {err}",
+            golden_path.display()
+        ),
+    }
+}
+
+fn golden_summary(
+    compare: &ParityCompareInput,
+    golden_path: &Path,
+    has_golden: bool,
+    drifted: &[usize],
+) -> String {
+    if !has_golden {
+        return format!("golden=none (bless with {}=1)", crate::golden::BLESS_ENV);
+    }
+    let labels = drifted
+        .iter()
+        .map(|&index| compare.sides[index].label.display_label())
+        .collect::<Vec<_>>();
+    let drift = if labels.is_empty() {
+        "all sides match".to_string()
+    } else {
+        format!("drifted: {}", labels.join(", "))
+    };
+    format!("golden={} ({drift})", golden_path.display())
 }
 
 fn build_one_screen_summary(
@@ -228,14 +376,6 @@ fn build_one_screen_summary(
     out.join("\n")
 }
 
-#[derive(Debug, Clone)]
-struct SideDumpPaths {
-    normalized: std::path::PathBuf,
-    raw: std::path::PathBuf,
-    tokens: std::path::PathBuf,
-    ast: std::path::PathBuf,
-}
-
 fn parity_matches(compare: &ParityCompareInput) -> bool {
     let Some(first) = compare.sides.first() else {
         return true;
@@ -248,119 +388,12 @@ fn parity_matches(compare: &ParityCompareInput) -> bool {
     all_exits_equal && all_normalized_equal
 }
 
-fn dump_root_dir() -> std::path::PathBuf {
-    std::env::var("HEADLAMP_PARITY_DUMP_ROOT")
-        .ok()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(std::env::temp_dir)
-}
-
-fn dump_dir_for_repo_case_run(repo: &Path, safe_case: &str) -> std::path::PathBuf {
-    // Use a stable key so CI artifacts are grouped deterministically even if the worktree folder
-    // name changes run-to-run.
-    let repo_key = headlamp::fast_related::stable_repo_key_hash_12(repo);
-    let run_id = format!(
-        "run-{}-{}",
-        std::process::id(),
-        crate::hashing::next_capture_id()
-    );
-    dump_root_dir()
-        .join("parity")
-        .join(repo_key)
-        .join(safe_case)
-        .join(run_id)
-}
-
-fn build_side_dump_paths(dump_dir: &Path, compare: &ParityCompareInput) -> Vec<SideDumpPaths> {
-    compare
-        .sides
-        .iter()
-        .map(|side| {
-            let side_key = side.label.file_safe_label();
-            SideDumpPaths {
-                normalized: dump_dir.join(format!("{side_key}--normalized.txt")),
-                raw: dump_dir.join(format!("{side_key}--raw.txt")),
-                tokens: dump_dir.join(format!("{side_key}--tokens.json")),
-                ast: dump_dir.join(format!("{side_key}--ast.json")),
-            }
-        })
-        .collect::<Vec<_>>()
-}
-
-fn write_side_dumps(compare: &ParityCompareInput, side_dump_paths: &[SideDumpPaths]) {
-    compare
-        .sides
-        .iter()
-        .zip(side_dump_paths.iter())
-        .for_each(|(side, paths)| write_side_dump(side, paths));
-}
-
-fn write_side_dump(side: &crate::parity_meta::ParityCompareSideInput, paths: &SideDumpPaths) {
-    let _ = std::fs::write(&paths.normalized, &side.normalized);
-    let _ = std::fs::write(&paths.raw, &side.raw);
-    let raw_tokens = crate::token_ast::build_token_stream(&side.raw);
-    let norm_tokens = crate::token_ast::build_token_stream(&side.normalized);
-    let doc_ast = crate::token_ast::build_document_ast(&side.normalized);
-    let _ = std::fs::File::create(&paths.tokens)
-        .ok()
-        .and_then(|mut file| {
-            serde_json::to_writer_pretty(&mut file, &(raw_tokens, norm_tokens)).ok()
-        });
-    let _ = std::fs::File::create(&paths.ast)
-        .ok()
-        .and_then(|mut file| serde_json::to_writer_pretty(&mut file, &doc_ast).ok());
-}
-
-fn write_diffs(compare: &ParityCompareInput, dump_dir: &Path, safe: &str) -> Vec<String> {
-    let pivot_index = crate::cluster::pick_pivot_index(compare);
-    crate::cluster::cluster_indices_by_normalized(compare)
-        .iter()
-        .filter(|cluster| !cluster.member_indices.contains(&pivot_index))
-        .filter_map(|cluster| pick_min_label_index(compare, cluster))
-        .map(|other_index| write_diff(compare, dump_dir, safe, pivot_index, other_index))
-        .collect::<Vec<_>>()
-}
-
-fn pick_min_label_index(
-    compare: &ParityCompareInput,
-    cluster: &crate::cluster::OutputCluster,
-) -> Option<usize> {
-    cluster.member_indices.iter().copied().min_by(|&a, &b| {
-        compare.sides[a]
-            .label
-            .display_label()
-            .cmp(&compare.sides[b].label.display_label())
-    })
-}
-
-fn write_diff(
-    compare: &ParityCompareInput,
-    dump_dir: &Path,
-    safe: &str,
-    pivot_index: usize,
-    other_index: usize,
-) -> String {
-    let pivot_key = compare.sides[pivot_index].label.file_safe_label();
-    let other_key = compare.sides[other_index].label.file_safe_label();
-    let diff_path = dump_dir.join(format!("{safe}--diff--{pivot_key}--vs--{other_key}.txt"));
-    let diff = similar_asserts::SimpleDiff::from_str(
-        &compare.sides[pivot_index].normalized,
-        &compare.sides[other_index].normalized,
-        &compare.sides[pivot_index].label.display_label(),
-        &compare.sides[other_index].label.display_label(),
-    )
-    .to_string();
-    let _ = std::fs::write(&diff_path, &diff);
-    diff_path.to_string_lossy().to_string()
-}
-
 fn build_artifacts(
     compare: &ParityCompareInput,
     side_dump_paths: &[SideDumpPaths],
     diff_paths: Vec<String>,
     report_path: &Path,
+    report_html_path: &Path,
     meta_path: &Path,
     analysis_path: &Path,
 ) -> crate::diagnostics::ArtifactPaths {
@@ -379,6 +412,7 @@ fn build_artifacts(
             .collect(),
         diffs: diff_paths,
         report: report_path.to_string_lossy().to_string(),
+        report_html: report_html_path.to_string_lossy().to_string(),
         meta: meta_path.to_string_lossy().to_string(),
         analysis: analysis_path.to_string_lossy().to_string(),
         reruns_dir: String::new(),
@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use crate::golden::Golden;
+use crate::parity_meta::{ParityCompareInput, ParityCompareSideInput};
+use crate::types::ParityRunGroup;
+
+use super::{RerunMeta, RerunSideMeta};
+
+/// How many times a mismatching case re-runs every side before it fails (default 0, at most 5).
+pub const RETRIES_ENV: &str = "HEADLAMP_PARITY_RETRIES";
+
+const MAX_RETRIES: usize = 5;
+
+pub fn retries_from_env() -> usize {
+    std::env::var(RETRIES_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_RETRIES)
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RetryOutcome {
+    pub(super) reruns: Vec<RerunMeta>,
+    /// The variant (`retry-N`) whose sides all agreed, when the mismatch turned out to be flaky.
+    pub(super) passed_on: Option<String>,
+}
+
+/// Re-runs every side of `run_group` up to `retries` times, stopping at the first
+/// attempt whose exits and normalized outputs all agree (and match `golden`, if any). Raw
+/// outputs land in `reruns_dir/retry-N/`. Without a run group there is nothing to re-run.
+pub(super) fn retry_until_match(
+    repo: &Path,
+    compare: &ParityCompareInput,
+    run_group: Option<&ParityRunGroup>,
+    golden: Option<&Golden>,
+    retries: usize,
+    reruns_dir: &Path,
+) -> RetryOutcome {
+    let Some(group) = run_group.filter(|group| group.sides.len() == compare.sides.len()) else {
+        return RetryOutcome::default();
+    };
+    let mut outcome = RetryOutcome::default();
+    for attempt in 1..=retries {
+        let variant = format!("retry-{attempt}");
+        let (meta, attempt_compare) =
+            rerun_sides(repo, compare, group, &variant, &reruns_dir.join(&variant));
+        outcome.reruns.push(meta);
+        let golden_ok = golden.is_none_or(|golden| {
+            crate::golden::sides_drifting_from(golden, &attempt_compare).is_empty()
+        });
+        if super::parity_matches(&attempt_compare) && golden_ok {
+            outcome.passed_on = Some(variant);
+            break;
+        }
+    }
+    outcome
+}
+
+fn rerun_sides(
+    repo: &Path,
+    compare: &ParityCompareInput,
+    group: &ParityRunGroup,
+    variant: &str,
+    attempt_dir: &Path,
+) -> (RerunMeta, ParityCompareInput) {
+    let _ = std::fs::create_dir_all(attempt_dir);
+    let (sides, compare_sides): (Vec<_>, Vec<_>) = compare
+        .sides
+        .iter()
+        .zip(group.sides.iter())
+        .map(|(side, spec)| {
+            let (code, raw) = crate::parity_run::run_parity_spec(spec);
+            let path = attempt_dir.join(format!("{}--raw.txt", side.label.file_safe_label()));
+            let _ = std::fs::write(&path, &raw);
+            let normalized = crate::diagnostics::normalize_text(
+                repo,
+                side.meta.normalization.normalizer,
+                raw.clone(),
+            );
+            let rerun_side = RerunSideMeta {
+                label: side.label.clone(),
+                code,
+                path: path.to_string_lossy().to_string(),
+                bytes: raw.len(),
+                tokens: crate::token_ast::build_token_stream(&normalized).stats,
+                blocks: crate::token_ast::build_document_ast(&normalized)
+                    .blocks
+                    .len(),
+            };
+            let compare_side = ParityCompareSideInput {
+                label: side.label.clone(),
+                exit: code,
+                raw,
+                normalized,
+                meta: side.meta.clone(),
+            };
+            (rerun_side, compare_side)
+        })
+        .unzip();
+    let meta = RerunMeta {
+        variant: variant.to_string(),
+        sides,
+    };
+    (
+        meta,
+        ParityCompareInput {
+            sides: compare_sides,
+        },
+    )
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use crate::parity_meta::{
+    NormalizationMeta, NormalizerKind, ParityCompareInput, ParityCompareSideInput, ParitySideLabel,
+    ParitySideMeta,
+};
+use crate::types::{ParityRunGroup, ParityRunSpec};
+
+use super::retry::retry_until_match;
+
+fn label(binary: &str) -> ParitySideLabel {
+    ParitySideLabel {
+        binary: binary.to_string(),
+        runner_stack: "non_tty".to_string(),
+    }
+}
+
+fn side(binary: &str, normalized: &str) -> ParityCompareSideInput {
+    ParityCompareSideInput {
+        label: label(binary),
+        exit: 0,
+        raw: normalized.to_string(),
+        normalized: normalized.to_string(),
+        meta: ParitySideMeta {
+            raw_bytes: normalized.len(),
+            raw_lines: normalized.lines().count(),
+            normalized_bytes: normalized.len(),
+            normalized_lines: normalized.lines().count(),
+            normalization: NormalizationMeta {
+                normalizer: NormalizerKind::NonTty,
+                used_fallback: false,
+                last_failed_tests_line: None,
+                last_test_files_line: None,
+                last_box_table_top_line: None,
+                stages: vec![],
+            },
+        },
+    }
+}
+
+fn printing(binary: &str, cwd: &std::path::Path, text: &str) -> ParityRunSpec {
+    ParityRunSpec {
+        cwd: cwd.to_path_buf(),
+        program: "sh".into(),
+        side_label: label(binary),
+        args: vec!["-c".to_string(), format!("printf '{text}\\n'")],
+        env: BTreeMap::new(),
+        tty_columns: None,
+        stdout_piped: false,
+        exec_backend: None,
+    }
+}
+
+fn mismatch() -> ParityCompareInput {
+    ParityCompareInput {
+        sides: vec![side("side_0", "PASS a\n"), side("side_1", "PASS b\n")],
+    }
+}
+
+#[test]
+fn flaky_mismatch_passes_on_the_first_agreeing_retry() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let group = ParityRunGroup {
+        sides: vec![
+            printing("side_0", temp_dir.path(), "PASS a"),
+            printing("side_1", temp_dir.path(), "PASS a"),
+        ],
+    };
+    let reruns_dir = temp_dir.path().join("reruns");
+    let outcome = retry_until_match(
+        temp_dir.path(),
+        &mismatch(),
+        Some(&group),
+        None,
+        3,
+        &reruns_dir,
+    );
+    assert_eq!(outcome.passed_on.as_deref(), Some("retry-1"));
+    assert_eq!(outcome.reruns.len(), 1);
+    assert_eq!(outcome.reruns[0].sides[1].code, 0);
+    assert!(
+        reruns_dir
+            .join("retry-1")
+            .join("side_1-non_tty--raw.txt")
+            .exists()
+    );
+}
+
+#[test]
+fn stable_mismatch_records_every_retry_and_fails() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let group = ParityRunGroup {
+        sides: vec![
+            printing("side_0", temp_dir.path(), "PASS a"),
+            printing("side_1", temp_dir.path(), "PASS b"),
+        ],
+    };
+    let reruns_dir = temp_dir.path().join("reruns");
+    let outcome = retry_until_match(
+        temp_dir.path(),
+        &mismatch(),
+        Some(&group),
+        None,
+        2,
+        &reruns_dir,
+    );
+    assert_eq!(outcome.passed_on, None);
+    assert_eq!(
+        outcome
+            .reruns
+            .iter()
+            .map(|rerun| rerun.variant.as_str())
+            .collect::<Vec<_>>(),
+        ["retry-1", "retry-2"]
+    );
+
+    let without_group = retry_until_match(temp_dir.path(), &mismatch(), None, None, 2, &reruns_dir);
+    assert!(without_group.reruns.is_empty());
+}
@@ -0,0 +1,249 @@
+use similar::{DiffTag, TextDiff};
+
+use crate::parity_meta::ParityCompareInput;
+use crate::token_ast::BlockNode;
+
+use super::utils::strip_ansi;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:1.5em}\
+table{border-collapse:collapse}\
+td,th{padding:0 .6em;vertical-align:top}\
+.sides td,.sides th{border:1px solid #ccc}\
+.diff{width:100%;table-layout:fixed;font-family:monospace;font-size:12px}\
+.diff td{white-space:pre-wrap;word-break:break-all}\
+.diff td.no{width:3.5em;color:#888;text-align:right}\
+.del{background:#ffecec}.ins{background:#eaffea}\
+del{background:#f8b9b9;text-decoration:none}ins{background:#a6f3a6;text-decoration:none}\
+details{margin:.3em 0}summary{cursor:pointer;font-family:monospace}\
+summary.changed{font-weight:bold;color:#b00}\
+pre{background:#f6f6f6;padding:.5em;overflow-x:auto}";
+
+/// A self-contained HTML page for a parity mismatch: each comparison the text report makes
+/// (pivot vs. one side per other cluster) as a side-by-side diff of the ANSI-stripped
+/// normalized output, with one collapsible section per pivot block (changed blocks open) and
+/// character-level highlighting on replaced lines, followed by every side's full output.
+pub fn build_parity_html_report(case: &str, compare: &ParityCompareInput) -> String {
+    let clusters = crate::cluster::cluster_indices_by_normalized(compare);
+    let pivot_index = crate::cluster::pick_pivot_index(compare);
+    let comparisons = super::other_indices_for_comparisons(compare, pivot_index, &clusters)
+        .into_iter()
+        .map(|other_index| comparison_section(compare, pivot_index, other_index))
+        .collect::<String>();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>parity {title}</title>\
+         <style>{STYLE}</style></head><body>\n<h1>Parity mismatch: {title}</h1>\n{sides}\
+         {comparisons}<h2>Sides</h2>\n{outputs}</body></html>\n",
+        title = escape(case),
+        sides = sides_table(compare, pivot_index, &clusters),
+        outputs = side_outputs(compare),
+    )
+}
+
+fn sides_table(
+    compare: &ParityCompareInput,
+    pivot_index: usize,
+    clusters: &[crate::cluster::OutputCluster],
+) -> String {
+    let rows = compare
+        .sides
+        .iter()
+        .enumerate()
+        .map(|(index, side)| {
+            let cluster = clusters
+                .iter()
+                .position(|cluster| cluster.member_indices.contains(&index))
+                .map(|position| position.to_string())
+                .unwrap_or_default();
+            format!(
+                "<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{}B / {}L</td></tr>\n",
+                escape(&side.label.display_label()),
+                if index == pivot_index { " (pivot)" } else { "" },
+                side.exit,
+                cluster,
+                side.meta.normalized_bytes,
+                side.meta.normalized_lines,
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<table class=\"sides\"><tr><th>side</th><th>exit</th><th>cluster</th>\
+         <th>normalized</th></tr>\n{rows}</table>\n"
+    )
+}
+
+fn side_outputs(compare: &ParityCompareInput) -> String {
+    compare
+        .sides
+        .iter()
+        .map(|side| {
+            format!(
+                "<details><summary>{} (exit={})</summary><pre>{}</pre></details>\n",
+                escape(&side.label.display_label()),
+                side.exit,
+                escape(&strip_ansi(&side.normalized)),
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DiffRow<'a> {
+    tag: DiffTag,
+    left: Option<(usize, &'a str)>,
+    right: Option<(usize, &'a str)>,
+}
+
+fn comparison_section(
+    compare: &ParityCompareInput,
+    pivot_index: usize,
+    other_index: usize,
+) -> String {
+    let pivot = &compare.sides[pivot_index];
+    let other = &compare.sides[other_index];
+    let left_text = strip_ansi(&pivot.normalized);
+    let right_text = strip_ansi(&other.normalized);
+    let left_lines = left_text.lines().collect::<Vec<_>>();
+    let right_lines = right_text.lines().collect::<Vec<_>>();
+    let rows = diff_rows(&left_lines, &right_lines);
+    let blocks = crate::token_ast::build_document_ast(&pivot.normalized).blocks;
+    let sections = group_rows_by_block(&rows, &blocks, left_lines.len())
+        .into_iter()
+        .map(|(block, rows)| block_section(block, rows))
+        .collect::<String>();
+    format!(
+        "<h2>{} vs {}</h2>\n{sections}",
+        escape(&pivot.label.display_label()),
+        escape(&other.label.display_label()),
+    )
+}
+
+fn diff_rows<'a>(left: &[&'a str], right: &[&'a str]) -> Vec<DiffRow<'a>> {
+    similar::capture_diff_slices(similar::Algorithm::Myers, left, right)
+        .into_iter()
+        .flat_map(|op| {
+            let (tag, left_range, right_range) = op.as_tag_tuple();
+            let count = left_range.len().max(right_range.len());
+            (0..count).map(move |offset| {
+                let left_index = left_range.start + offset;
+                let right_index = right_range.start + offset;
+                DiffRow {
+                    tag,
+                    left: (offset < left_range.len()).then(|| (left_index, left[left_index])),
+                    right: (offset < right_range.len()).then(|| (right_index, right[right_index])),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Each row lands in the pivot block holding its left line; rows that only exist on the right
+/// join the block of the next left line (or the last block at the end of the output).
+fn group_rows_by_block<'r, 'a>(
+    rows: &'r [DiffRow<'a>],
+    blocks: &'r [BlockNode],
+    left_len: usize,
+) -> Vec<(Option<&'r BlockNode>, &'r [DiffRow<'a>])> {
+    let block_of_line = |line: usize| {
+        blocks
+            .iter()
+            .position(|block| block.line_range.contains(&line))
+            .or_else(|| blocks.len().checked_sub(1))
+    };
+    let mut next_left = 0usize;
+    let row_blocks = rows
+        .iter()
+        .map(|row| {
+            let line = row.left.map(|(index, _)| index).unwrap_or(next_left);
+            next_left = row.left.map(|(index, _)| index + 1).unwrap_or(next_left);
+            block_of_line(line.min(left_len.saturating_sub(1)))
+        })
+        .collect::<Vec<_>>();
+    let mut groups: Vec<(Option<&BlockNode>, &[DiffRow])> = vec![];
+    let mut start = 0usize;
+    while start < rows.len() {
+        let end = (start..rows.len())
+            .find(|&index| row_blocks[index] != row_blocks[start])
+            .unwrap_or(rows.len());
+        groups.push((row_blocks[start].map(|b| &blocks[b]), &rows[start..end]));
+        start = end;
+    }
+    groups
+}
+
+fn block_section(block: Option<&BlockNode>, rows: &[DiffRow]) -> String {
+    let changed = rows.iter().any(|row| row.tag != DiffTag::Equal);
+    let title = block
+        .map(|block| {
+            format!(
+                "{} lines {}-{}",
+                block.role.label(),
+                block.line_range.start + 1,
+                block.line_range.end
+            )
+        })
+        .unwrap_or_else(|| "(added output)".to_string());
+    format!(
+        "<details{}><summary{}>{} {title}</summary><table class=\"diff\">\n{}</table></details>\n",
+        if changed { " open" } else { "" },
+        if changed { " class=\"changed\"" } else { "" },
+        if changed { "changed" } else { "equal" },
+        rows.iter().map(|row| row_html(*row)).collect::<String>(),
+    )
+}
+
+fn row_html(row: DiffRow) -> String {
+    let (left_html, right_html) = match (row.tag, row.left, row.right) {
+        (DiffTag::Replace, Some((_, left)), Some((_, right))) => inline_highlight(left, right),
+        _ => (
+            row.left.map(|(_, text)| escape(text)).unwrap_or_default(),
+            row.right.map(|(_, text)| escape(text)).unwrap_or_default(),
+        ),
+    };
+    let cell_class = |present: bool, class: &str| match (row.tag, present) {
+        (DiffTag::Equal, _) | (_, false) => String::new(),
+        _ => format!(" class=\"{class}\""),
+    };
+    format!(
+        "<tr><td class=\"no\">{}</td><td{}>{left_html}</td><td class=\"no\">{}</td><td{}>{right_html}</td></tr>\n",
+        line_number(row.left),
+        cell_class(row.left.is_some(), "del"),
+        line_number(row.right),
+        cell_class(row.right.is_some(), "ins"),
+    )
+}
+
+fn line_number(side: Option<(usize, &str)>) -> String {
+    side.map(|(index, _)| (index + 1).to_string())
+        .unwrap_or_default()
+}
+
+/// Escaped left/right line with the runs only on that side wrapped in `<del>`/`<ins>`.
+fn inline_highlight(left: &str, right: &str) -> (String, String) {
+    let diff = TextDiff::from_chars(left, right);
+    let (old, new) = (diff.old_slices(), diff.new_slices());
+    let mut out = (String::new(), String::new());
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let old_text = escape(&old[old_range].concat());
+        let new_text = escape(&new[new_range].concat());
+        if tag == DiffTag::Equal {
+            out.0.push_str(&old_text);
+            out.1.push_str(&new_text);
+            continue;
+        }
+        if !old_text.is_empty() {
+            out.0.push_str(&format!("<del>{old_text}</del>"));
+        }
+        if !new_text.is_empty() {
+            out.1.push_str(&format!("<ins>{new_text}</ins>"));
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
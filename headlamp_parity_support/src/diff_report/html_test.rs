@@ -0,0 +1,59 @@
+use crate::parity_meta::{
+    NormalizationMeta, NormalizerKind, ParityCompareInput, ParityCompareSideInput, ParitySideLabel,
+    ParitySideMeta,
+};
+
+use super::build_parity_html_report;
+
+fn side(binary: &str, normalized: &str) -> ParityCompareSideInput {
+    ParityCompareSideInput {
+        label: ParitySideLabel {
+            binary: binary.to_string(),
+            runner_stack: "tty_ui".to_string(),
+        },
+        exit: 1,
+        raw: normalized.to_string(),
+        normalized: normalized.to_string(),
+        meta: ParitySideMeta {
+            raw_bytes: normalized.len(),
+            raw_lines: normalized.lines().count(),
+            normalized_bytes: normalized.len(),
+            normalized_lines: normalized.lines().count(),
+            normalization: NormalizationMeta {
+                normalizer: NormalizerKind::TtyUi,
+                used_fallback: false,
+                last_failed_tests_line: None,
+                last_test_files_line: None,
+                last_box_table_top_line: None,
+                stages: vec![],
+            },
+        },
+    }
+}
+
+#[test]
+fn html_report_opens_changed_blocks_and_highlights_changed_characters() {
+    let compare = ParityCompareInput {
+        sides: vec![
+            side(
+                "side_0",
+                "FAIL <a.test.js>\n\n| File | % Lines |\n| a.js | 80 |\n",
+            ),
+            side(
+                "side_1",
+                "FAIL <a.test.js>\n\n| File | % Lines |\n| a.js | 85 |\n",
+            ),
+        ],
+    };
+    let html = build_parity_html_report("jest <coverage>", &compare);
+
+    assert!(html.contains("<h1>Parity mismatch: jest &lt;coverage&gt;</h1>"));
+    assert!(html.contains("<h2>side_0[tty_ui] vs side_1[tty_ui]</h2>"));
+    assert!(html.contains("<details><summary>equal file overview lines 1-1</summary>"));
+    assert!(html.contains(
+        "<details open><summary class=\"changed\">changed coverage table lines 3-4</summary>"
+    ));
+    assert!(html.contains("| a.js | 8<del>0</del> |"));
+    assert!(html.contains("| a.js | 8<ins>5</ins> |"));
+    assert!(html.contains("FAIL &lt;a.test.js&gt;"));
+}
@@ -1,10 +1,18 @@
 use crate::parity_meta::ParityCompareInput;
 
 mod compare;
+mod html;
+#[cfg(test)]
+mod html_test;
+mod semantic;
+#[cfg(test)]
+mod semantic_test;
 mod summary;
 mod tables;
 mod utils;
 
+pub use html::build_parity_html_report;
+
 pub fn build_parity_report_with_meta(compare: &ParityCompareInput) -> String {
     if compare.sides.len() < 2 {
         return "Parity report: only one side".to_string();
@@ -77,6 +85,12 @@ fn push_comparison_sections(
     sections.push(compare::build_classification_section(
         pivot_norm, other_norm,
     ));
+    sections.push(semantic::build_semantic_section(
+        pivot_label,
+        other_label,
+        pivot_norm,
+        other_norm,
+    ));
     sections.push(compare::build_first_mismatch_section(
         pivot_label,
         other_label,
@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+use crate::token_ast::BlockRole;
+
+use super::utils::strip_ansi;
+
+const MAX_MISMATCHES: usize = 12;
+const MAX_LINE_CHARS: usize = 160;
+
+/// A maximal run of same-role blocks (blank blocks in between ignored), as ANSI-stripped lines.
+#[derive(Debug)]
+struct Section {
+    role: BlockRole,
+    lines: Vec<String>,
+}
+
+/// Names which semantic sections diverge ("coverage table row 3 differs") by pairing the
+/// n-th section of each role on both sides and comparing them row by row.
+pub(super) fn build_semantic_section(
+    label_0: &str,
+    label_1: &str,
+    out_0: &str,
+    out_1: &str,
+) -> String {
+    let sections_0 = sections(out_0);
+    let sections_1 = sections(out_1);
+    let roles = sections_0
+        .iter()
+        .chain(sections_1.iter())
+        .map(|section| section.role)
+        .collect::<BTreeSet<_>>();
+    let mismatches = roles
+        .into_iter()
+        .flat_map(|role| {
+            let of_role = |all: &[Section]| {
+                all.iter()
+                    .filter(|section| section.role == role)
+                    .map(|section| section.lines.clone())
+                    .collect::<Vec<_>>()
+            };
+            role_mismatches(
+                role,
+                label_0,
+                label_1,
+                &of_role(&sections_0),
+                &of_role(&sections_1),
+            )
+        })
+        .take(MAX_MISMATCHES)
+        .collect::<Vec<_>>();
+    if mismatches.is_empty() {
+        return String::new();
+    }
+    std::iter::once("Semantic block mismatches (ANSI-stripped)".to_string())
+        .chain(mismatches)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn sections(text: &str) -> Vec<Section> {
+    let stripped = strip_ansi(text);
+    let lines = stripped.lines().collect::<Vec<_>>();
+    let mut out: Vec<Section> = vec![];
+    crate::token_ast::build_document_ast(text)
+        .blocks
+        .into_iter()
+        .filter(|block| block.kind != crate::token_ast::BlockKind::Blank)
+        .for_each(|block| {
+            let block_lines = lines
+                .get(block.line_range.clone())
+                .unwrap_or_default()
+                .iter()
+                .map(|line| line.to_string());
+            match out.last_mut() {
+                Some(last) if last.role == block.role => last.lines.extend(block_lines),
+                _ => out.push(Section {
+                    role: block.role,
+                    lines: block_lines.collect(),
+                }),
+            }
+        });
+    out
+}
+
+fn role_mismatches(
+    role: BlockRole,
+    label_0: &str,
+    label_1: &str,
+    sections_0: &[Vec<String>],
+    sections_1: &[Vec<String>],
+) -> Vec<String> {
+    let count = sections_0.len().max(sections_1.len());
+    (0..count)
+        .filter_map(|ordinal| {
+            let name = if count > 1 {
+                format!("{} #{}", role.label(), ordinal + 1)
+            } else {
+                role.label().to_string()
+            };
+            match (sections_0.get(ordinal), sections_1.get(ordinal)) {
+                (Some(lines_0), Some(lines_1)) => {
+                    first_row_mismatch(role, &name, label_0, label_1, lines_0, lines_1)
+                }
+                (Some(_), None) => Some(format!("- {name} missing in {label_1}")),
+                (None, Some(_)) => Some(format!("- {name} missing in {label_0}")),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+fn first_row_mismatch(
+    role: BlockRole,
+    name: &str,
+    label_0: &str,
+    label_1: &str,
+    lines_0: &[String],
+    lines_1: &[String],
+) -> Option<String> {
+    let rows_0 = rows(role, lines_0);
+    let rows_1 = rows(role, lines_1);
+    let index = (0..rows_0.len().max(rows_1.len())).find(|&i| rows_0.get(i) != rows_1.get(i))?;
+    let unit = if role == BlockRole::CoverageTable {
+        "row"
+    } else {
+        "line"
+    };
+    let show = |row: Option<&&String>| {
+        row.map(|row| {
+            row.trim_end()
+                .chars()
+                .take(MAX_LINE_CHARS)
+                .collect::<String>()
+        })
+        .unwrap_or_else(|| "<missing>".to_string())
+    };
+    Some(format!(
+        "- {name} {unit} {} differs\n    {label_0}: {}\n    {label_1}: {}",
+        index + 1,
+        show(rows_0.get(index)),
+        show(rows_1.get(index)),
+    ))
+}
+
+/// Table rows skip borders and rules so "row 3" counts the rows a reader sees.
+fn rows(role: BlockRole, lines: &[String]) -> Vec<&String> {
+    lines
+        .iter()
+        .filter(|line| {
+            role != BlockRole::CoverageTable
+                || !line.trim().chars().all(|c| "─┌┐└┘├┤┬┴┼|-=: ".contains(c))
+        })
+        .collect()
+}
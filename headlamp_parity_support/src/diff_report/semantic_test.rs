@@ -0,0 +1,34 @@
+use super::semantic::build_semantic_section;
+
+const COVERAGE: &str = "Test Files 1 passed (1)
+┌──────┬──────┐
+│File  │Lines%│
+├──────┼──────┤
+│a.rs  │ 100% │
+│b.rs  │  80% │
+└──────┴──────┘
+";
+
+#[test]
+fn semantic_section_names_the_differing_coverage_row() {
+    let other = COVERAGE.replace("│  80% │", "│  85% │");
+    let section = build_semantic_section("side_0", "side_1", COVERAGE, &other);
+    assert_eq!(
+        section,
+        "Semantic block mismatches (ANSI-stripped)\n\
+         - coverage table row 3 differs\n    \
+         side_0: │b.rs  │  80% │\n    \
+         side_1: │b.rs  │  85% │"
+    );
+}
+
+#[test]
+fn semantic_section_reports_missing_sections_and_is_empty_when_equal() {
+    let without_table = "Test Files 1 passed (1)\n";
+    let section = build_semantic_section("side_0", "side_1", COVERAGE, without_table);
+    assert!(
+        section.contains("- coverage table missing in side_1"),
+        "{section}"
+    );
+    assert_eq!(build_semantic_section("a", "b", COVERAGE, COVERAGE), "");
+}
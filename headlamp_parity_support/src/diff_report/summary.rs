@@ -134,7 +134,7 @@ pub(super) fn build_block_order_summary(
             crate::token_ast::build_document_ast(&side.normalized)
                 .blocks
                 .into_iter()
-                .map(|block| block.hash)
+                .map(|block| format!("{}:{}", block.role.label().replace(' ', "_"), block.hash))
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
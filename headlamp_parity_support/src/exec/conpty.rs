@@ -0,0 +1,50 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+// ConPTY paints a blank screen before the child writes anything (clear, reset, home), sets the
+// window title, and toggles cursor visibility as it re-renders. None of that comes from the
+// runner, so drop it along with the CRLF line endings to match the Unix PTY captures.
+static SCREEN_SETUP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\A(?:\x1b\[(?:2J|m|H))+").unwrap());
+static TITLE_AND_CURSOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\]0;[^\x07]*\x07|\x1b\[\?25[hl]").unwrap());
+
+pub(crate) fn sanitize_conpty_output(raw: &str) -> String {
+    let without_title = TITLE_AND_CURSOR.replace_all(raw, "");
+    SCREEN_SETUP
+        .replace(&without_title, "")
+        .replace("\r\n", "\n")
+}
+
+/// The Windows counterpart of `exec {exe} {args} > {stdout_capture}` under `script(1)`: runs
+/// `cmd` through `cmd.exe` so only stderr reaches the pseudo console.
+///
+/// `call` keeps the command line from starting with a quote, which `cmd.exe /c` would strip
+/// when the program path contains spaces.
+pub(crate) fn build_conpty_stdout_redirect_command(
+    cmd: &Command,
+    stdout_capture: &Path,
+) -> Command {
+    let mut shell = Command::new("cmd.exe");
+    shell
+        .args(["/d", "/s", "/c", "call"])
+        .arg(cmd.get_program())
+        .args(cmd.get_args())
+        .arg(">")
+        .arg(stdout_capture);
+    if let Some(cwd) = cmd.get_current_dir() {
+        shell.current_dir(cwd);
+    }
+    cmd.get_envs().for_each(|(key, value)| match value {
+        Some(v) => {
+            shell.env(key, v);
+        }
+        None => {
+            shell.env_remove(key);
+        }
+    });
+    shell
+}
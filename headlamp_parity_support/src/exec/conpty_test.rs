@@ -0,0 +1,44 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::conpty::{build_conpty_stdout_redirect_command, sanitize_conpty_output};
+
+#[test]
+fn conpty_screen_setup_title_and_crlf_are_stripped() {
+    let raw = "\u{1b}[?25l\u{1b}[2J\u{1b}[m\u{1b}[H\u{1b}]0;C:\\headlamp.exe\u{7}\u{1b}[?25h\
+               \u{1b}[32mPASS\u{1b}[m tests/a.test.js\r\nTests: 1 passed\r\n";
+    assert_eq!(
+        sanitize_conpty_output(raw),
+        "\u{1b}[32mPASS\u{1b}[m tests/a.test.js\nTests: 1 passed\n"
+    );
+}
+
+#[test]
+fn conpty_stdout_redirect_keeps_program_args_cwd_and_env() {
+    let mut cmd = Command::new(r"C:\Program Files\headlamp\headlamp.exe");
+    cmd.args(["--runner=jest", "tests/a b.test.js"])
+        .current_dir(r"C:\repo")
+        .env("FORCE_COLOR", "1")
+        .env_remove("NO_COLOR");
+    let shell = build_conpty_stdout_redirect_command(&cmd, Path::new(r"C:\tmp\out.txt"));
+    assert_eq!(shell.get_program(), "cmd.exe");
+    assert_eq!(
+        shell.get_args().collect::<Vec<_>>(),
+        [
+            "/d",
+            "/s",
+            "/c",
+            "call",
+            r"C:\Program Files\headlamp\headlamp.exe",
+            "--runner=jest",
+            "tests/a b.test.js",
+            ">",
+            r"C:\tmp\out.txt",
+        ]
+    );
+    assert_eq!(shell.get_current_dir(), Some(Path::new(r"C:\repo")));
+    assert_eq!(
+        shell.get_envs().collect::<Vec<_>>(),
+        cmd.get_envs().collect::<Vec<_>>()
+    );
+}
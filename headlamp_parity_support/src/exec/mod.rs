@@ -1,3 +1,6 @@
+mod conpty;
+#[cfg(test)]
+mod conpty_test;
 mod non_tty;
 mod portable_pty;
 mod shell;
@@ -5,6 +8,6 @@ mod tty;
 
 pub use non_tty::{run_cmd, run_cmd_with_timeout};
 pub use tty::{
-    TtyBackend, run_cmd_tty, run_cmd_tty_stdout_piped, run_cmd_tty_with_backend,
-    run_cmd_tty_with_backend_timeout,
+    TtyBackend, native_pty_backend, run_cmd_tty, run_cmd_tty_stdout_piped,
+    run_cmd_tty_with_backend, run_cmd_tty_with_backend_timeout,
 };
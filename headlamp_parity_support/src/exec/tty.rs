@@ -4,12 +4,15 @@ use std::time::Duration;
 
 use crate::hashing::next_capture_id;
 
+use super::conpty::{build_conpty_stdout_redirect_command, sanitize_conpty_output};
 use super::portable_pty::run_cmd_tty_portable_pty;
 use super::shell::{build_tty_shell_command, build_tty_shell_command_stdout_redirect};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TtyBackend {
     PortablePty,
+    /// `portable_pty` on Windows, where it drives the ConPTY pseudo console.
+    ConPty,
     Script,
 }
 
@@ -17,11 +20,21 @@ impl std::fmt::Display for TtyBackend {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TtyBackend::PortablePty => write!(f, "portable_pty"),
+            TtyBackend::ConPty => write!(f, "conpty"),
             TtyBackend::Script => write!(f, "script"),
         }
     }
 }
 
+/// The backend `portable_pty` runs on this platform.
+pub fn native_pty_backend() -> TtyBackend {
+    if cfg!(windows) {
+        TtyBackend::ConPty
+    } else {
+        TtyBackend::PortablePty
+    }
+}
+
 pub fn run_cmd_tty_with_backend(mut cmd: Command, columns: usize) -> (i32, String, TtyBackend) {
     let _timing = crate::timing::TimingGuard::start("tty_run");
     apply_tty_env(&mut cmd, true);
@@ -30,7 +43,11 @@ pub fn run_cmd_tty_with_backend(mut cmd: Command, columns: usize) -> (i32, Strin
     // (Different `script(1)` implementations and PTY behaviors can subtly change runner output.)
     let portable = run_cmd_tty_portable_pty(&cmd, columns, tty_timeout());
     if let Some((code, out)) = portable {
-        return (code, sanitize_tty_output(out), TtyBackend::PortablePty);
+        return (code, sanitize_tty_output(out), native_pty_backend());
+    }
+    if cfg!(windows) {
+        // There is no `script(1)` to fall back to.
+        return (1, String::new(), TtyBackend::ConPty);
     }
 
     let tty_capture_path = capture_path("tty-capture");
@@ -56,7 +73,11 @@ pub fn run_cmd_tty_with_backend_timeout(
 
     let portable = run_cmd_tty_portable_pty(&cmd, columns, timeout);
     if let Some((code, out)) = portable {
-        return (code, sanitize_tty_output(out), TtyBackend::PortablePty);
+        return (code, sanitize_tty_output(out), native_pty_backend());
+    }
+    if cfg!(windows) {
+        // There is no `script(1)` to fall back to.
+        return (1, String::new(), TtyBackend::ConPty);
     }
 
     let tty_capture_path = capture_path("tty-capture");
@@ -78,6 +99,9 @@ pub fn run_cmd_tty(cmd: Command, columns: usize) -> (i32, String) {
 pub fn run_cmd_tty_stdout_piped(mut cmd: Command, columns: usize) -> (i32, String) {
     let _timing = crate::timing::TimingGuard::start("tty_stdout_piped_run");
     apply_tty_env(&mut cmd, false);
+    if cfg!(windows) {
+        return run_cmd_tty_stdout_piped_conpty(&cmd, columns);
+    }
 
     let stdout_capture_path = capture_path("tty-stdout-capture");
     let tty_capture_path = capture_path("tty-capture");
@@ -105,6 +129,16 @@ pub fn run_cmd_tty_stdout_piped(mut cmd: Command, columns: usize) -> (i32, Strin
     (code, combined)
 }
 
+fn run_cmd_tty_stdout_piped_conpty(cmd: &Command, columns: usize) -> (i32, String) {
+    let stdout_capture_path = capture_path("tty-stdout-capture");
+    let shell = build_conpty_stdout_redirect_command(cmd, &stdout_capture_path);
+    let (code, tty_text) =
+        run_cmd_tty_portable_pty(&shell, columns, tty_timeout()).unwrap_or((1, String::new()));
+    let combined = sanitize_tty_output(format!("{}{}", read_lossy(&stdout_capture_path), tty_text));
+    let _ = std::fs::remove_file(&stdout_capture_path);
+    (code, combined)
+}
+
 fn apply_tty_env(cmd: &mut Command, force_color: bool) {
     cmd.env("TERM", "xterm-256color");
     cmd.env("CI", "1");
@@ -213,5 +247,10 @@ fn read_lossy(path: &PathBuf) -> String {
 }
 
 fn sanitize_tty_output(raw: String) -> String {
+    let raw = if cfg!(windows) {
+        sanitize_conpty_output(&raw)
+    } else {
+        raw
+    };
     raw.replace(['\u{0008}', '\u{0004}'], "").replace("^D", "")
 }
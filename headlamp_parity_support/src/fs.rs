@@ -50,3 +50,184 @@ pub fn mk_repo(name: &str, node_modules: &Path) -> PathBuf {
     symlink_dir(node_modules, &repo.join("node_modules"));
     repo
 }
+
+#[derive(Debug, Clone)]
+enum FixtureOp {
+    Write { rel_path: String, contents: String },
+    NodeModules(PathBuf),
+    Commit(String),
+}
+
+/// Fluent setup for a fixture repo: each call queues a step and [`FixtureRepoBuilder::build`]
+/// applies them in order, so files queued before a `commit` land in that commit.
+///
+/// ```no_run
+/// use headlamp_parity_support::fs::FixtureRepoBuilder;
+///
+/// let repo = FixtureRepoBuilder::in_temp_dir("sum-js")
+///     .jest_config("**/tests/**/*.test.js")
+///     .file("src/sum.js", "exports.sum = (a, b) => a + b;\n")
+///     .file("tests/sum.test.js", "test('sums', () => {});\n")
+///     .commit("init")
+///     .history(3)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixtureRepoBuilder {
+    root: PathBuf,
+    fresh: bool,
+    ops: Vec<FixtureOp>,
+}
+
+impl FixtureRepoBuilder {
+    /// Builds into `root` as is, keeping whatever is already there.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            fresh: false,
+            ops: vec![],
+        }
+    }
+
+    /// Builds into an emptied `target/parity-fixtures/<name>`, like [`mk_temp_dir`].
+    pub fn in_temp_dir(name: &str) -> Self {
+        Self {
+            root: PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("target")
+                .join("parity-fixtures")
+                .join(name),
+            fresh: true,
+            ops: vec![],
+        }
+    }
+
+    pub fn file(mut self, rel_path: &str, contents: &str) -> Self {
+        self.ops.push(FixtureOp::Write {
+            rel_path: rel_path.to_string(),
+            contents: contents.to_string(),
+        });
+        self
+    }
+
+    pub fn files(self, files: &[(&str, &str)]) -> Self {
+        files.iter().fold(self, |builder, (rel_path, contents)| {
+            builder.file(rel_path, contents)
+        })
+    }
+
+    /// `jest.config.js` with a single `testMatch`, as written by [`write_jest_config`].
+    pub fn jest_config(self, test_match: &str) -> Self {
+        self.file(
+            "jest.config.js",
+            &format!("module.exports = {{ testMatch: ['{test_match}'] }};\n"),
+        )
+    }
+
+    /// `node_modules` as a symlink to a shared install.
+    pub fn node_modules(mut self, node_modules: &Path) -> Self {
+        self.ops
+            .push(FixtureOp::NodeModules(node_modules.to_path_buf()));
+        self
+    }
+
+    /// A `pyproject.toml` that points pytest at `tests/`.
+    pub fn pyproject(self, name: &str) -> Self {
+        self.file(
+            "pyproject.toml",
+            &format!(
+                "[project]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n\
+                 [tool.pytest.ini_options]\ntestpaths = [\"tests\"]\n"
+            ),
+        )
+    }
+
+    /// A root `Cargo.toml` workspace plus one library crate per member path, named after the
+    /// member's last path component.
+    pub fn cargo_workspace(self, members: &[&str]) -> Self {
+        let member_list = members
+            .iter()
+            .map(|member| format!("\"{member}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let root = self.file(
+            "Cargo.toml",
+            &format!("[workspace]\nresolver = \"2\"\nmembers = [{member_list}]\n"),
+        );
+        members.iter().fold(root, |builder, member| {
+            let name = member.rsplit('/').next().unwrap_or(member);
+            builder
+                .file(
+                    &format!("{member}/Cargo.toml"),
+                    &format!(
+                        "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n"
+                    ),
+                )
+                .file(&format!("{member}/src/lib.rs"), "")
+        })
+    }
+
+    /// A root `package.json` with npm workspaces and a `package.json` per package directory.
+    pub fn js_monorepo(self, packages: &[&str]) -> Self {
+        let workspaces = packages
+            .iter()
+            .map(|package| format!("\"{package}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let root = self.file(
+            "package.json",
+            &format!("{{\n  \"name\": \"root\",\n  \"private\": true,\n  \"workspaces\": [{workspaces}]\n}}\n"),
+        );
+        packages.iter().fold(root, |builder, package| {
+            let name = package.rsplit('/').next().unwrap_or(package);
+            builder.file(
+                &format!("{package}/package.json"),
+                &format!("{{\n  \"name\": \"{name}\",\n  \"version\": \"0.1.0\"\n}}\n"),
+            )
+        })
+    }
+
+    /// Commits everything written so far (initializing git on first use).
+    pub fn commit(mut self, message: &str) -> Self {
+        self.ops.push(FixtureOp::Commit(message.to_string()));
+        self
+    }
+
+    /// `count` more commits, each appending a line to `HISTORY.txt`, for changed-mode and
+    /// depth scenarios that need a history to walk.
+    pub fn history(self, count: usize) -> Self {
+        (1..=count).fold(self, |builder, index| {
+            let contents = (1..=index)
+                .map(|line| format!("commit {line}\n"))
+                .collect::<String>();
+            builder
+                .file("HISTORY.txt", &contents)
+                .commit(&format!("history {index}"))
+        })
+    }
+
+    pub fn build(self) -> PathBuf {
+        if self.fresh {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+        std::fs::create_dir_all(&self.root).unwrap();
+        let mut git_ready = false;
+        for op in self.ops {
+            match op {
+                FixtureOp::Write { rel_path, contents } => {
+                    write_file(&self.root.join(rel_path), &contents)
+                }
+                FixtureOp::NodeModules(node_modules) => {
+                    symlink_dir(&node_modules, &self.root.join("node_modules"))
+                }
+                FixtureOp::Commit(message) => {
+                    if !git_ready {
+                        crate::git::git_init(&self.root);
+                        git_ready = true;
+                    }
+                    crate::git::git_commit_all(&self.root, &message);
+                }
+            }
+        }
+        self.root
+    }
+}
@@ -0,0 +1,54 @@
+use std::process::Command;
+
+use crate::fs::FixtureRepoBuilder;
+
+fn git_log_subjects(repo: &std::path::Path) -> Vec<String> {
+    let out = Command::new("git")
+        .current_dir(repo)
+        .args(["log", "--format=%s"])
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn fixture_builder_writes_layouts_and_history_in_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let repo = FixtureRepoBuilder::new(temp_dir.path().join("repo"))
+        .jest_config("**/tests/**/*.test.js")
+        .js_monorepo(&["packages/app"])
+        .cargo_workspace(&["crates/core"])
+        .pyproject("fixture")
+        .files(&[("tests/a.test.js", "test('a', () => {});\n")])
+        .commit("init")
+        .history(2)
+        .file("tests/b.test.js", "test('b', () => {});\n")
+        .build();
+
+    let read = |rel: &str| std::fs::read_to_string(repo.join(rel)).unwrap();
+    assert_eq!(
+        read("jest.config.js"),
+        "module.exports = { testMatch: ['**/tests/**/*.test.js'] };\n"
+    );
+    assert!(read("package.json").contains("\"workspaces\": [\"packages/app\"]"));
+    assert!(read("packages/app/package.json").contains("\"name\": \"app\""));
+    assert!(read("Cargo.toml").contains("members = [\"crates/core\"]"));
+    assert!(read("crates/core/Cargo.toml").contains("name = \"core\""));
+    assert!(repo.join("crates/core/src/lib.rs").exists());
+    assert!(read("pyproject.toml").contains("testpaths = [\"tests\"]"));
+    assert_eq!(read("HISTORY.txt"), "commit 1\ncommit 2\n");
+    assert_eq!(git_log_subjects(&repo), ["history 2", "history 1", "init"]);
+
+    let status = Command::new("git")
+        .current_dir(&repo)
+        .args(["status", "--porcelain"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&status.stdout).trim(),
+        "?? tests/b.test.js"
+    );
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use crate::parity_meta::{NormalizerKind, ParityCompareInput};
+
+pub const BLESS_ENV: &str = "HEADLAMP_PARITY_BLESS";
+pub const GOLDEN_DIR_ENV: &str = "HEADLAMP_PARITY_GOLDEN_DIR";
+
+/// A case's accepted normalized output, checked in so output formats can change on purpose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Golden {
+    pub exit: i32,
+    pub normalized: String,
+}
+
+impl Golden {
+    pub fn render(&self) -> String {
+        format!("exit={}\n{}", self.exit, self.normalized)
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let (header, normalized) = text.split_once('\n')?;
+        let exit = header.strip_prefix("exit=")?.trim().parse::<i32>().ok()?;
+        Some(Self {
+            exit,
+            normalized: normalized.to_string(),
+        })
+    }
+}
+
+/// Which side `HEADLAMP_PARITY_BLESS` promotes: `1`/`true` takes the pivot (the side closest to
+/// all others), anything else names a side by its display label, e.g. `side_1[tty_ui]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlessRequest {
+    Off,
+    Pivot,
+    Side(String),
+}
+
+impl BlessRequest {
+    pub fn from_env_value(value: Option<&str>) -> Self {
+        match value.map(str::trim).filter(|v| !v.is_empty()) {
+            None | Some("0" | "false") => Self::Off,
+            Some("1" | "true") => Self::Pivot,
+            Some(label) => Self::Side(label.to_string()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::from_env_value(std::env::var(BLESS_ENV).ok().as_deref())
+    }
+
+    pub fn side_index(&self, compare: &ParityCompareInput) -> Option<usize> {
+        match self {
+            Self::Off => None,
+            Self::Pivot => {
+                (!compare.sides.is_empty()).then(|| crate::cluster::pick_pivot_index(compare))
+            }
+            Self::Side(label) => compare
+                .sides
+                .iter()
+                .position(|side| side.label.display_label() == *label),
+        }
+    }
+}
+
+pub fn golden_dir() -> PathBuf {
+    std::env::var(GOLDEN_DIR_ENV)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("headlamp_parity_tests")
+                .join("tests")
+                .join("golden")
+        })
+}
+
+/// One golden per case and normalizer, shared by every side of the comparison.
+pub fn golden_path(dir: &Path, safe_case: &str, compare: &ParityCompareInput) -> PathBuf {
+    let normalizer = compare
+        .sides
+        .first()
+        .map(|side| side.meta.normalization.normalizer);
+    let kind = match normalizer {
+        Some(NormalizerKind::TtyUi) => "tty_ui",
+        _ => "non_tty",
+    };
+    dir.join(format!("{safe_case}--{kind}.txt"))
+}
+
+pub fn read_golden(path: &Path) -> Option<Golden> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| Golden::parse(&text))
+}
+
+/// Writes the requested side's output to `path`; returns that side's index, or `None` when
+/// blessing is off or names a side that is not in this comparison.
+pub fn bless(
+    path: &Path,
+    compare: &ParityCompareInput,
+    request: &BlessRequest,
+) -> std::io::Result<Option<usize>> {
+    let Some(index) = request.side_index(compare) else {
+        return Ok(None);
+    };
+    let side = &compare.sides[index];
+    let golden = Golden {
+        exit: side.exit,
+        normalized: side.normalized.clone(),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, golden.render())?;
+    Ok(Some(index))
+}
+
+/// Indices of the sides whose exit code or normalized output differ from `golden`.
+pub fn sides_drifting_from(golden: &Golden, compare: &ParityCompareInput) -> Vec<usize> {
+    compare
+        .sides
+        .iter()
+        .enumerate()
+        .filter(|(_, side)| side.exit != golden.exit || side.normalized != golden.normalized)
+        .map(|(index, _)| index)
+        .collect()
+}
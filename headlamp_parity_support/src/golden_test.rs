@@ -0,0 +1,94 @@
+use crate::golden::{BlessRequest, Golden, bless, golden_path, read_golden, sides_drifting_from};
+use crate::parity_meta::{
+    NormalizationMeta, NormalizerKind, ParityCompareInput, ParityCompareSideInput, ParitySideLabel,
+    ParitySideMeta,
+};
+
+fn side(binary: &str, exit: i32, normalized: &str) -> ParityCompareSideInput {
+    ParityCompareSideInput {
+        label: ParitySideLabel {
+            binary: binary.to_string(),
+            runner_stack: "tty_ui".to_string(),
+        },
+        exit,
+        raw: normalized.to_string(),
+        normalized: normalized.to_string(),
+        meta: ParitySideMeta {
+            raw_bytes: normalized.len(),
+            raw_lines: normalized.lines().count(),
+            normalized_bytes: normalized.len(),
+            normalized_lines: normalized.lines().count(),
+            normalization: NormalizationMeta {
+                normalizer: NormalizerKind::TtyUi,
+                used_fallback: false,
+                last_failed_tests_line: None,
+                last_test_files_line: None,
+                last_box_table_top_line: None,
+                stages: vec![],
+            },
+        },
+    }
+}
+
+fn three_way(outputs: [(i32, &str); 3]) -> ParityCompareInput {
+    ParityCompareInput {
+        sides: ["side_0", "side_1", "side_2"]
+            .into_iter()
+            .zip(outputs)
+            .map(|(binary, (exit, normalized))| side(binary, exit, normalized))
+            .collect(),
+    }
+}
+
+#[test]
+fn bless_env_value_picks_pivot_or_named_side() {
+    assert_eq!(BlessRequest::from_env_value(None), BlessRequest::Off);
+    assert_eq!(BlessRequest::from_env_value(Some("0")), BlessRequest::Off);
+    assert_eq!(
+        BlessRequest::from_env_value(Some(" 1 ")),
+        BlessRequest::Pivot
+    );
+    assert_eq!(
+        BlessRequest::from_env_value(Some("side_2[tty_ui]")),
+        BlessRequest::Side("side_2[tty_ui]".to_string())
+    );
+
+    let compare = three_way([(1, "PASS a\n"), (1, "PASS a\n"), (0, "PASS b\n")]);
+    assert_eq!(BlessRequest::Pivot.side_index(&compare), Some(0));
+    assert_eq!(
+        BlessRequest::Side("side_2[tty_ui]".to_string()).side_index(&compare),
+        Some(2)
+    );
+    assert_eq!(
+        BlessRequest::Side("side_9[tty_ui]".to_string()).side_index(&compare),
+        None
+    );
+}
+
+#[test]
+fn blessed_golden_round_trips_and_flags_drifting_sides() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let compare = three_way([(1, "FAIL a\n"), (1, "FAIL a\n"), (1, "FAIL a\n")]);
+    let path = golden_path(temp_dir.path(), "jest_fail", &compare);
+    assert!(path.ends_with("jest_fail--tty_ui.txt"));
+    assert_eq!(read_golden(&path), None);
+
+    assert_eq!(bless(&path, &compare, &BlessRequest::Off).unwrap(), None);
+    assert!(!path.exists());
+    assert_eq!(
+        bless(&path, &compare, &BlessRequest::Pivot).unwrap(),
+        Some(0)
+    );
+    let golden = read_golden(&path).unwrap();
+    assert_eq!(
+        golden,
+        Golden {
+            exit: 1,
+            normalized: "FAIL a\n".to_string()
+        }
+    );
+    assert!(sides_drifting_from(&golden, &compare).is_empty());
+
+    let evolved = three_way([(1, "FAIL a\n"), (0, "FAIL a\n"), (1, "FAIL  a\n")]);
+    assert_eq!(sides_drifting_from(&golden, &evolved), vec![1, 2]);
+}
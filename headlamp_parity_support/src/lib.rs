@@ -15,12 +15,19 @@ pub mod exec;
 mod extract;
 pub mod fs;
 pub mod git;
+pub mod golden;
 pub mod hashing;
 pub mod parity_run;
 pub mod types;
 
 #[cfg(test)]
 mod env_test;
+#[cfg(test)]
+mod fs_test;
+#[cfg(test)]
+mod golden_test;
+#[cfg(test)]
+mod token_ast_test;
 
 pub use binaries::{ParityBinaries, RunnerParityBinaries, parity_binaries, runner_parity_binaries};
 pub use diagnostics_assert::{
@@ -36,8 +43,9 @@ pub use normalize::{normalize, normalize_tty_ui};
 pub use parity_meta::ParitySideLabel;
 pub use parity_run::{
     assert_parity, assert_parity_tty_ui_with_args, assert_parity_with_args,
-    run_headlamp_with_args_tty, run_headlamp_with_args_tty_env, run_parity_fixture_with_args,
-    run_parity_fixture_with_args_tty, run_parity_fixture_with_args_tty_stdout_piped,
-    run_parity_headlamp_vs_headlamp_with_args_tty, run_rust_fixture_with_args_tty_stdout_piped,
+    run_headlamp_with_args_tty, run_headlamp_with_args_tty_capturing_frames,
+    run_headlamp_with_args_tty_env, run_parity_fixture_with_args, run_parity_fixture_with_args_tty,
+    run_parity_fixture_with_args_tty_stdout_piped, run_parity_headlamp_vs_headlamp_with_args_tty,
+    run_rust_fixture_with_args_tty_stdout_piped,
 };
 pub use types::{ParityRunGroup, ParityRunSpec};
@@ -57,11 +57,20 @@ pub(super) fn stage_stats(stage: &'static str, text: &str) -> NormalizationStage
     NormalizationStageStats {
         stage,
         bytes: text.len(),
+        bytes_delta: 0,
         lines: text.lines().count(),
         markers,
     }
 }
 
+pub(super) fn fill_byte_deltas(input_bytes: usize, stages: &mut [NormalizationStageStats]) {
+    let mut previous = input_bytes;
+    for stage in stages {
+        stage.bytes_delta = stage.bytes as i64 - previous as i64;
+        previous = stage.bytes;
+    }
+}
+
 pub(super) fn compute_render_indices(text: &str) -> (Option<usize>, Option<usize>, Option<usize>) {
     let stripped = headlamp::format::stacks::strip_ansi_simple(text);
     let stripped_lines = stripped.lines().collect::<Vec<_>>();
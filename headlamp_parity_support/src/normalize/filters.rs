@@ -67,6 +67,10 @@ fn should_keep_line(line: &str) -> bool {
     if trimmed.starts_with("idle ") {
         return false;
     }
+    // Reproduction commands are runner-specific by design.
+    if trimmed.starts_with("still running: ") || line.contains("Reproduce: ") {
+        return false;
+    }
     // Worktree paths can wrap in a narrow TTY, producing a continuation line like:
     // `s/057093c2092a/wt-187-0)` (from `repos/<hash>/wt-...` split after `repo`).
     if is_wrapped_worktree_path_continuation(trimmed) {
@@ -6,9 +6,14 @@ mod blocks;
 mod common;
 mod filters;
 mod paths;
+mod pipeline;
+#[cfg(test)]
+mod pipeline_test;
 mod runner_parity;
 mod tty_blocks;
 
+pub use pipeline::{NormalizeStage, Normalizer, NormalizerBuilder};
+
 use regex::Regex;
 use std::sync::LazyLock;
 
@@ -31,11 +36,15 @@ pub fn normalize_tty_ui_runner_parity_with_meta(
     text: String,
     root: &Path,
 ) -> (String, NormalizationMeta) {
-    let (normalized, meta) = normalize_tty_ui_with_meta(text, root);
-    (runner_parity::strip_failure_details(&normalized), meta)
+    Normalizer::builder()
+        .base(crate::parity_meta::NormalizerKind::TtyUi)
+        .stage(NormalizeStage::StripFailureDetails)
+        .build()
+        .normalize_with_meta(text, root)
 }
 
 pub fn normalize_with_meta(text: String, root: &Path) -> (String, NormalizationMeta) {
+    let input_bytes = text.len();
     let normalized_paths = paths::normalize_paths(text, root);
     let filtered = filters::drop_nondeterministic_lines(&normalized_paths);
     let stripped = filters::strip_terminal_sequences(&filtered);
@@ -45,13 +54,14 @@ pub fn normalize_with_meta(text: String, root: &Path) -> (String, NormalizationM
 
     let (last_failed_tests_line, last_test_files_line, last_box_table_top_line) =
         common::compute_render_indices(&stripped);
-    let stages = vec![
+    let mut stages = vec![
         common::stage_stats("normalized_paths", &normalized_paths),
         common::stage_stats("filtered", &filtered),
         common::stage_stats("stripped", &stripped),
         common::stage_stats("final_block", &final_block),
         common::stage_stats("normalized", &normalized),
     ];
+    common::fill_byte_deltas(input_bytes, &mut stages);
     let meta = NormalizationMeta {
         normalizer: crate::parity_meta::NormalizerKind::NonTty,
         used_fallback: false,
@@ -104,6 +114,7 @@ fn normalize_tty_line(raw_line: &str, state: &mut DropLiveProgressState) -> Opti
 }
 
 pub fn normalize_tty_ui_with_meta(text: String, root: &Path) -> (String, NormalizationMeta) {
+    let input_bytes = text.len();
     let normalized_paths = paths::normalize_paths(text, root);
     let no_osc8 = filters::strip_osc8_sequences(&normalized_paths);
     let normalized_cr = normalize_crlf_and_carriage_returns(&no_osc8);
@@ -148,13 +159,14 @@ pub fn normalize_tty_ui_with_meta(text: String, root: &Path) -> (String, Normali
 
     let (last_failed_tests_line, last_test_files_line, last_box_table_top_line) =
         common::compute_render_indices(&normalized);
-    let stages = vec![
+    let mut stages = vec![
         common::stage_stats("normalized_paths", &normalized_paths),
         common::stage_stats("no_osc8", &no_osc8),
         common::stage_stats("normalized_cr", &normalized_cr),
         common::stage_stats("filtered", &filtered),
         common::stage_stats("normalized", &normalized),
     ];
+    common::fill_byte_deltas(input_bytes, &mut stages);
     let meta = NormalizationMeta {
         normalizer: crate::parity_meta::NormalizerKind::TtyUi,
         used_fallback,
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+
+use crate::parity_meta::{
+    NormalizationMeta, NormalizerKind, ParityCompareSideInput, ParitySideLabel, ParitySideMeta,
+};
+
+use super::{common, filters, paths, runner_parity};
+
+static DURATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:\b\d+(?:\.\d+)?|<N>)\s?(?:ms|s)\b").unwrap());
+
+type StageFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// One step of a [`Normalizer`]; the named stages are the building blocks of the fixed
+/// pipelines, `Custom` lets a single parity test add its own.
+#[derive(Clone)]
+pub enum NormalizeStage {
+    StripAnsi,
+    NormalizePaths,
+    DropTimings,
+    CollapseTables,
+    StripFailureDetails,
+    Custom { name: &'static str, apply: StageFn },
+}
+
+impl fmt::Debug for NormalizeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl NormalizeStage {
+    pub const NAMES: [&'static str; 5] = [
+        "strip_ansi",
+        "normalize_paths",
+        "drop_timings",
+        "collapse_tables",
+        "strip_failure_details",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "strip_ansi" => Some(Self::StripAnsi),
+            "normalize_paths" => Some(Self::NormalizePaths),
+            "drop_timings" => Some(Self::DropTimings),
+            "collapse_tables" => Some(Self::CollapseTables),
+            "strip_failure_details" => Some(Self::StripFailureDetails),
+            _ => None,
+        }
+    }
+
+    pub fn custom(
+        name: &'static str,
+        apply: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom {
+            name,
+            apply: Arc::new(apply),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StripAnsi => "strip_ansi",
+            Self::NormalizePaths => "normalize_paths",
+            Self::DropTimings => "drop_timings",
+            Self::CollapseTables => "collapse_tables",
+            Self::StripFailureDetails => "strip_failure_details",
+            Self::Custom { name, .. } => name,
+        }
+    }
+
+    fn apply(&self, text: String, root: &Path) -> String {
+        match self {
+            Self::StripAnsi => filters::strip_terminal_sequences(&text),
+            Self::NormalizePaths => paths::normalize_paths(text, root),
+            Self::DropTimings => drop_timings(&text),
+            Self::CollapseTables => filters::drop_box_table_interior_blank_lines(&text),
+            Self::StripFailureDetails => runner_parity::strip_failure_details(&text),
+            Self::Custom { apply, .. } => apply(&text),
+        }
+    }
+}
+
+/// Profile suffixes dropped, `Time` footers and inline durations replaced by `<DURATION>`.
+fn drop_timings(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = super::normalize_time_line_tty(filters::strip_headlamp_profile_suffix(line));
+            DURATION_RE.replace_all(&line, "<DURATION>").into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A normalization pipeline assembled from [`NormalizeStage`]s, optionally on top of one of
+/// the fixed pipelines. Retries re-normalize with the base pipeline only, so custom stages
+/// should not be needed for sides to agree.
+///
+/// ```no_run
+/// use headlamp_parity_support::normalize::{NormalizeStage, Normalizer};
+/// use headlamp_parity_support::parity_meta::NormalizerKind;
+///
+/// let normalizer = Normalizer::builder()
+///     .base(NormalizerKind::TtyUi)
+///     .stage(NormalizeStage::StripFailureDetails)
+///     .custom_stage("drop_seed", |text| text.replace("seed=42", "seed=<N>"))
+///     .build();
+/// let normalized = normalizer.normalize("raw output".to_string(), std::path::Path::new("/repo"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    base: Option<NormalizerKind>,
+    stages: Vec<NormalizeStage>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NormalizerBuilder {
+    normalizer: Normalizer,
+}
+
+impl NormalizerBuilder {
+    pub fn base(mut self, kind: NormalizerKind) -> Self {
+        self.normalizer.base = Some(kind);
+        self
+    }
+
+    pub fn stage(mut self, stage: NormalizeStage) -> Self {
+        self.normalizer.stages.push(stage);
+        self
+    }
+
+    pub fn custom_stage(
+        self,
+        name: &'static str,
+        apply: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.stage(NormalizeStage::custom(name, apply))
+    }
+
+    pub fn build(self) -> Normalizer {
+        self.normalizer
+    }
+}
+
+impl Normalizer {
+    pub fn builder() -> NormalizerBuilder {
+        NormalizerBuilder::default()
+    }
+
+    /// A pipeline of named stages only, e.g. `["normalize_paths", "strip_ansi"]`.
+    pub fn from_stage_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let stages = names
+            .into_iter()
+            .map(|name| {
+                NormalizeStage::from_name(name).ok_or_else(|| {
+                    format!(
+                        "unknown normalize stage {name:?} (expected one of {})",
+                        NormalizeStage::NAMES.join(", ")
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { base: None, stages })
+    }
+
+    pub fn normalize(&self, text: String, root: &Path) -> String {
+        self.normalize_with_meta(text, root).0
+    }
+
+    pub fn normalize_with_meta(&self, text: String, root: &Path) -> (String, NormalizationMeta) {
+        let input_bytes = text.len();
+        let (mut out, mut meta) = match self.base {
+            Some(NormalizerKind::NonTty) => super::normalize_with_meta(text, root),
+            Some(NormalizerKind::TtyUi) => super::normalize_tty_ui_with_meta(text, root),
+            None => (text, unstaged_meta()),
+        };
+        for stage in &self.stages {
+            out = stage.apply(out, root);
+            meta.stages.push(common::stage_stats(stage.name(), &out));
+        }
+        common::fill_byte_deltas(input_bytes, &mut meta.stages);
+        if self.base.is_some() && self.stages.is_empty() {
+            return (out, meta);
+        }
+        let (last_failed_tests_line, last_test_files_line, last_box_table_top_line) =
+            common::compute_render_indices(&out);
+        meta.last_failed_tests_line = last_failed_tests_line;
+        meta.last_test_files_line = last_test_files_line;
+        meta.last_box_table_top_line = last_box_table_top_line;
+        (out, meta)
+    }
+
+    /// A comparison side for `assert_parity_with_diagnostics`, normalized by this pipeline.
+    pub fn side(
+        &self,
+        label: ParitySideLabel,
+        exit: i32,
+        raw: String,
+        root: &Path,
+    ) -> ParityCompareSideInput {
+        let (normalized, normalization) = self.normalize_with_meta(raw.clone(), root);
+        ParityCompareSideInput {
+            label,
+            exit,
+            meta: ParitySideMeta {
+                raw_bytes: raw.len(),
+                raw_lines: raw.lines().count(),
+                normalized_bytes: normalized.len(),
+                normalized_lines: normalized.lines().count(),
+                normalization,
+            },
+            raw,
+            normalized,
+        }
+    }
+}
+
+fn unstaged_meta() -> NormalizationMeta {
+    NormalizationMeta {
+        normalizer: NormalizerKind::NonTty,
+        used_fallback: false,
+        last_failed_tests_line: None,
+        last_test_files_line: None,
+        last_box_table_top_line: None,
+        stages: vec![],
+    }
+}
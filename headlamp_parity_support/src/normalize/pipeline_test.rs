@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use crate::parity_meta::NormalizerKind;
+
+use super::{NormalizeStage, Normalizer};
+
+#[test]
+fn named_stages_run_in_order_and_record_byte_deltas() {
+    let normalizer =
+        Normalizer::from_stage_names(["normalize_paths", "strip_ansi", "drop_timings"]).unwrap();
+    let raw = "\u{1b}[1mFAIL\u{1b}[22m /repo/tests/a_test.js (12 ms)\nTime 1.5 s\n".to_string();
+    let (normalized, meta) = normalizer.normalize_with_meta(raw.clone(), Path::new("/repo"));
+
+    assert_eq!(
+        normalized,
+        "FAIL <ROOT>/tests/a_test.<EXT> (<DURATION>)\nTime      <DURATION>"
+    );
+    let names = meta.stages.iter().map(|s| s.stage).collect::<Vec<_>>();
+    assert_eq!(names, ["normalize_paths", "strip_ansi", "drop_timings"]);
+    let total_delta = meta.stages.iter().map(|s| s.bytes_delta).sum::<i64>();
+    assert_eq!(total_delta, normalized.len() as i64 - raw.len() as i64);
+    assert!(meta.stages[1].bytes_delta < 0, "{:?}", meta.stages);
+}
+
+#[test]
+fn unknown_stage_names_are_rejected_with_the_known_names() {
+    let error = Normalizer::from_stage_names(["strip_ansi", "strip_colours"]).unwrap_err();
+    assert!(error.contains("\"strip_colours\""), "{error}");
+    assert!(error.contains("collapse_tables"), "{error}");
+}
+
+#[test]
+fn custom_stages_run_after_the_base_pipeline() {
+    let normalizer = Normalizer::builder()
+        .base(NormalizerKind::TtyUi)
+        .custom_stage("redact_seed", |text| text.replace("seed=42", "seed=<N>"))
+        .build();
+    let (normalized, meta) =
+        normalizer.normalize_with_meta("RUN  /repo\nseed=42\n".to_string(), Path::new("/repo"));
+
+    assert!(normalized.contains("seed=<N>"), "{normalized}");
+    assert!(matches!(meta.normalizer, NormalizerKind::TtyUi));
+    assert_eq!(meta.stages.last().map(|s| s.stage), Some("redact_seed"));
+    assert!(
+        meta.stages.len() > 1,
+        "base stages should precede custom ones"
+    );
+    assert_eq!(
+        NormalizeStage::custom("redact_seed", |t| t.to_string()).name(),
+        "redact_seed"
+    );
+}
@@ -45,6 +45,8 @@ pub enum NormalizerKind {
 pub struct NormalizationStageStats {
     pub stage: &'static str,
     pub bytes: usize,
+    /// Byte change this stage made relative to the previous stage (or the raw input).
+    pub bytes_delta: i64,
     pub lines: usize,
     pub markers: BTreeMap<&'static str, usize>,
 }
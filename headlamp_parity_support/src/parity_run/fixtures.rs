@@ -333,7 +333,7 @@ fn rs_fixture_spec(
     }
 }
 
-fn run_parity_spec(spec: &ParityRunSpec) -> (i32, String) {
+pub(crate) fn run_parity_spec(spec: &ParityRunSpec) -> (i32, String) {
     let cmd = build_command_from_spec(spec);
     match (spec.tty_columns, spec.stdout_piped) {
         (None, _) => crate::exec::run_cmd(cmd),
@@ -1,6 +1,8 @@
 use std::path::Path;
 use std::process::Command;
 
+use headlamp::live_progress::{CapturedFrame, FRAMES_FILE_ENV, read_captured_frames};
+
 use crate::env::{build_env_map, headlamp_runner_stack, program_display_name};
 use crate::parity_meta::ParitySideLabel;
 use crate::types::ParityRunSpec;
@@ -39,6 +41,37 @@ pub fn run_headlamp_with_args_tty_env(
     (spec, code, out)
 }
 
+/// Like [`run_headlamp_with_args_tty_env`], also returning every live progress frame headlamp
+/// drew during the run (via `HEADLAMP_PROGRESS_FRAMES_FILE`).
+pub fn run_headlamp_with_args_tty_capturing_frames(
+    repo: &Path,
+    headlamp_bin: &Path,
+    columns: usize,
+    runner: &str,
+    args: &[&str],
+    extra_env: &[(&str, String)],
+) -> (ParityRunSpec, i32, String, Vec<CapturedFrame>) {
+    let frames_dir = crate::fs::mk_temp_dir(&format!(
+        "progress-frames-{}-{}",
+        std::process::id(),
+        crate::hashing::next_capture_id()
+    ));
+    let frames_path = frames_dir.join("frames.jsonl");
+    let env = extra_env
+        .iter()
+        .cloned()
+        .chain(std::iter::once((
+            FRAMES_FILE_ENV,
+            frames_path.to_string_lossy().to_string(),
+        )))
+        .collect::<Vec<_>>();
+    let (spec, code, out) =
+        run_headlamp_with_args_tty_env(repo, headlamp_bin, columns, runner, args, &env, None);
+    let frames = read_captured_frames(&frames_path);
+    let _ = std::fs::remove_dir_all(&frames_dir);
+    (spec, code, out, frames)
+}
+
 fn mk_headlamp_tty_run_spec(
     repo: &Path,
     headlamp_bin: &Path,
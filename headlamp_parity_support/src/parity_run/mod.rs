@@ -1,10 +1,14 @@
 mod fixtures;
 mod headlamp;
 
+pub(crate) use fixtures::run_parity_spec;
 pub use fixtures::{
     assert_parity, assert_parity_tty_ui_with_args, assert_parity_with_args,
     run_parity_fixture_with_args, run_parity_fixture_with_args_tty,
     run_parity_fixture_with_args_tty_stdout_piped, run_parity_headlamp_vs_headlamp_with_args_tty,
     run_rust_fixture_with_args_tty_stdout_piped,
 };
-pub use headlamp::{run_headlamp_with_args_tty, run_headlamp_with_args_tty_env};
+pub use headlamp::{
+    run_headlamp_with_args_tty, run_headlamp_with_args_tty_capturing_frames,
+    run_headlamp_with_args_tty_env,
+};
@@ -2,7 +2,9 @@ use std::path::Path;
 use std::process::Command;
 use std::time::Duration;
 
-use crate::exec::{TtyBackend, run_cmd_tty_with_backend_timeout, run_cmd_with_timeout};
+use crate::exec::{
+    TtyBackend, native_pty_backend, run_cmd_tty_with_backend_timeout, run_cmd_with_timeout,
+};
 use crate::parity_meta::ParitySideLabel;
 use crate::types::ParityRunSpec;
 
@@ -95,7 +97,7 @@ pub fn run_env_matrix_no_hang(
     for env in environments {
         for (runner_id, args) in runners {
             let (tty_columns, tty_backend) = match env {
-                ParityExecEnv::Tty { columns } => (Some(*columns), Some(TtyBackend::PortablePty)),
+                ParityExecEnv::Tty { columns } => (Some(*columns), Some(native_pty_backend())),
                 ParityExecEnv::NonTty => (None, None),
             };
             let mut spec =
@@ -4,6 +4,10 @@ mod fixture_repo;
 mod git_utils;
 mod headlamp_bin;
 mod jest_bin;
+mod stale_worktrees;
+mod worktree_metrics;
+#[cfg(test)]
+mod worktree_metrics_test;
 mod worktrees;
 
 pub use env_matrix::*;
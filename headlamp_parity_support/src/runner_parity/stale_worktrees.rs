@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::process::Command;
+
+/// The owning process id of a pool worktree directory named `wt-{pid}-{index}`.
+pub(super) fn pool_worktree_owner_pid(dir_name: &str) -> Option<u32> {
+    let (pid, index) = dir_name.strip_prefix("wt-")?.split_once('-')?;
+    index.parse::<usize>().ok()?;
+    pid.parse::<u32>().ok()
+}
+
+#[cfg(unix)]
+fn process_is_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", pid.to_string().as_str()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn process_is_alive(_pid: u32) -> bool {
+    // No cheap liveness probe; keep other processes' worktrees rather than risk removing live ones.
+    true
+}
+
+/// Removes pool worktrees owned by processes that are gone (tests killed or interrupted before
+/// their leases dropped); returns how many were removed.
+pub(super) fn prune_worktrees_of_dead_processes(base_repo: &Path, pool_root: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(pool_root) else {
+        return 0;
+    };
+    let current_pid = std::process::id();
+    let stale = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(pool_worktree_owner_pid)
+                .is_some_and(|pid| pid != current_pid && !process_is_alive(pid))
+        })
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    for worktree_dir in &stale {
+        let _ = Command::new("git")
+            .current_dir(base_repo)
+            .args(["worktree", "remove", "--force"])
+            .arg(worktree_dir)
+            .status();
+        let _ = std::fs::remove_dir_all(worktree_dir);
+    }
+    stale.len()
+}
@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Prints a worktree pool summary whenever a pool goes idle; the last line is the session total.
+pub const METRICS_ENV: &str = "HEADLAMP_PARITY_WORKTREE_METRICS";
+
+pub fn worktree_metrics_enabled() -> bool {
+    std::env::var(METRICS_ENV).ok().is_some_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "y" | "on"
+        )
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct WorktreePoolMetrics {
+    leases: AtomicU64,
+    reused: AtomicU64,
+    immediate: AtomicU64,
+    waited: AtomicU64,
+    wait_total_ms: AtomicU64,
+    timeouts: AtomicU64,
+    pruned_stale: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WorktreePoolMetricsSnapshot {
+    pub leases: u64,
+    /// Leases served by a worktree an earlier lease already used (no checkout from scratch).
+    pub reused: u64,
+    /// Leases served without waiting for another lease to be released.
+    pub immediate: u64,
+    pub waited: u64,
+    pub wait_total_ms: u64,
+    pub timeouts: u64,
+    /// Worktrees left behind by crashed or interrupted runs and removed at pool start.
+    pub pruned_stale: u64,
+}
+
+impl WorktreePoolMetrics {
+    pub fn record_lease(&self, reused: bool, waited: Duration) {
+        self.leases.fetch_add(1, Ordering::Relaxed);
+        if reused {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+        }
+        if waited.is_zero() {
+            self.immediate.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.waited.fetch_add(1, Ordering::Relaxed);
+            self.wait_total_ms
+                .fetch_add(waited.as_millis() as u64, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_timeout(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_pruned_stale(&self, count: usize) {
+        self.pruned_stale.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> WorktreePoolMetricsSnapshot {
+        WorktreePoolMetricsSnapshot {
+            leases: self.leases.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            immediate: self.immediate.load(Ordering::Relaxed),
+            waited: self.waited.load(Ordering::Relaxed),
+            wait_total_ms: self.wait_total_ms.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            pruned_stale: self.pruned_stale.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn percent(part: u64, total: u64) -> u64 {
+    (part * 100).checked_div(total).unwrap_or(0)
+}
+
+impl WorktreePoolMetricsSnapshot {
+    pub fn summary_line(&self, repo_key: &str, pool_size: usize) -> String {
+        format!(
+            "[headlamp_parity_tests] worktree pool repo={repo_key} size={pool_size} leases={} \
+             reused={} ({}%) immediate={} ({}%) waited={} wait_total={}ms timeouts={} pruned_stale={}",
+            self.leases,
+            self.reused,
+            percent(self.reused, self.leases),
+            self.immediate,
+            percent(self.immediate, self.leases),
+            self.waited,
+            self.wait_total_ms,
+            self.timeouts,
+            self.pruned_stale,
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use super::stale_worktrees::pool_worktree_owner_pid;
+use super::worktree_metrics::{WorktreePoolMetrics, WorktreePoolMetricsSnapshot};
+
+#[test]
+fn metrics_count_reuse_and_waits_per_lease() {
+    let metrics = WorktreePoolMetrics::default();
+    metrics.record_lease(false, Duration::ZERO);
+    metrics.record_lease(true, Duration::ZERO);
+    metrics.record_lease(true, Duration::from_millis(40));
+    metrics.record_pruned_stale(2);
+    assert_eq!(
+        metrics.snapshot(),
+        WorktreePoolMetricsSnapshot {
+            leases: 3,
+            reused: 2,
+            immediate: 2,
+            waited: 1,
+            wait_total_ms: 40,
+            timeouts: 0,
+            pruned_stale: 2,
+        }
+    );
+}
+
+#[test]
+fn summary_line_reports_rates_and_handles_no_leases() {
+    let snapshot = WorktreePoolMetricsSnapshot {
+        leases: 4,
+        reused: 3,
+        immediate: 1,
+        waited: 3,
+        wait_total_ms: 120,
+        ..WorktreePoolMetricsSnapshot::default()
+    };
+    let line = snapshot.summary_line("abc123", 5);
+    assert!(line.contains("repo=abc123 size=5 leases=4"), "{line}");
+    assert!(line.contains("reused=3 (75%) immediate=1 (25%)"), "{line}");
+    let empty = WorktreePoolMetricsSnapshot::default().summary_line("abc123", 5);
+    assert!(empty.contains("reused=0 (0%)"), "{empty}");
+}
+
+#[test]
+fn pool_worktree_owner_pid_parses_only_pool_directory_names() {
+    assert_eq!(pool_worktree_owner_pid("wt-4242-0"), Some(4242));
+    assert_eq!(pool_worktree_owner_pid("wt-4242-12"), Some(4242));
+    assert_eq!(pool_worktree_owner_pid("wt-4242"), None);
+    assert_eq!(pool_worktree_owner_pid("wt-abc-0"), None);
+    assert_eq!(pool_worktree_owner_pid("worktree_pool_smoke"), None);
+}
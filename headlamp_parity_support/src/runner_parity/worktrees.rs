@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::{Arc, Condvar, Mutex, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use super::fixture_repo::shared_real_runner_repo_for_worktrees;
 use super::git_utils::{git_rev_parse_head, run_git_expect_success};
 use super::jest_bin::ensure_repo_local_jest_bin;
+use super::stale_worktrees::prune_worktrees_of_dead_processes;
+use super::worktree_metrics::{WorktreePoolMetrics, worktree_metrics_enabled};
 
 fn ensure_git_info_exclude_has(repo: &Path, pattern: &str) {
     let out = Command::new("git")
@@ -71,11 +73,23 @@ impl Drop for RealRunnerWorktreeLease {
     }
 }
 
+#[derive(Debug, Default)]
+struct WorktreePoolState {
+    available: Vec<PathBuf>,
+    /// Worktrees some earlier lease already used; leasing one again counts as a reuse.
+    used: HashSet<PathBuf>,
+    /// Lease name per leased worktree, for the timeout message.
+    holders: HashMap<PathBuf, String>,
+}
+
 #[derive(Debug)]
 struct RealRunnerWorktreePool {
     base_repo: PathBuf,
-    available_worktrees: Mutex<Vec<PathBuf>>,
+    repo_key: String,
+    pool_size: usize,
+    state: Mutex<WorktreePoolState>,
     available_worktrees_cv: Condvar,
+    metrics: WorktreePoolMetrics,
 }
 
 fn parse_usize_env(var_name: &str) -> Option<usize> {
@@ -91,6 +105,11 @@ fn default_worktree_pool_size() -> usize {
     parse_usize_env("HEADLAMP_PARITY_WORKTREE_POOL_SIZE").unwrap_or(5)
 }
 
+fn worktree_lease_timeout() -> Duration {
+    let secs = parse_usize_env("HEADLAMP_PARITY_WORKTREE_LEASE_TIMEOUT_SECS").unwrap_or(10 * 60);
+    Duration::from_secs(secs as u64)
+}
+
 impl RealRunnerWorktreePool {
     fn new(base_repo: PathBuf) -> Self {
         let _git_lock = acquire_worktree_git_lock();
@@ -98,6 +117,8 @@ impl RealRunnerWorktreePool {
         let pool_root = ensure_worktree_pool_root_exists(&base_repo);
         let pool_size = default_worktree_pool_size();
         remove_stale_git_lock_files(&base_repo);
+        let metrics = WorktreePoolMetrics::default();
+        metrics.record_pruned_stale(prune_worktrees_of_dead_processes(&base_repo, &pool_root));
         git_worktree_prune_best_effort(&base_repo);
 
         let mut worktrees = desired_worktree_paths(&pool_root, pool_size);
@@ -107,20 +128,54 @@ impl RealRunnerWorktreePool {
 
         worktrees.reverse();
         Self {
+            repo_key: crate::hashing::sha1_12(base_repo.to_string_lossy().as_ref()),
             base_repo,
-            available_worktrees: Mutex::new(worktrees),
+            pool_size,
+            state: Mutex::new(WorktreePoolState {
+                available: worktrees,
+                ..WorktreePoolState::default()
+            }),
             available_worktrees_cv: Condvar::new(),
+            metrics,
         }
     }
 
-    fn acquire(&self, lease_name: &str) -> PathBuf {
-        let mut available = self.available_worktrees.lock().unwrap();
+    fn wait_for_worktree(&self, lease_name: &str) -> PathBuf {
+        let timeout = worktree_lease_timeout();
+        let started = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let mut waited = Duration::ZERO;
         let worktree_path = loop {
-            if let Some(path) = available.pop() {
+            if let Some(path) = state.available.pop() {
                 break path;
             }
-            available = self.available_worktrees_cv.wait(available).unwrap();
+            let Some(remaining) = timeout.checked_sub(started.elapsed()) else {
+                self.metrics.record_timeout();
+                let holders = state.holders.values().cloned().collect::<Vec<_>>();
+                panic!(
+                    "timed out after {timeout:?} waiting for a worktree lease name={lease_name} \
+                     pool_size={} held_by={holders:?} (raise HEADLAMP_PARITY_WORKTREE_POOL_SIZE \
+                     or HEADLAMP_PARITY_WORKTREE_LEASE_TIMEOUT_SECS)",
+                    self.pool_size
+                );
+            };
+            state = self
+                .available_worktrees_cv
+                .wait_timeout(state, remaining)
+                .unwrap()
+                .0;
+            waited = started.elapsed();
         };
+        let reused = !state.used.insert(worktree_path.clone());
+        state
+            .holders
+            .insert(worktree_path.clone(), lease_name.to_string());
+        self.metrics.record_lease(reused, waited);
+        worktree_path
+    }
+
+    fn acquire(&self, lease_name: &str) -> PathBuf {
+        let worktree_path = self.wait_for_worktree(lease_name);
 
         let _timing = crate::timing::TimingGuard::start(format!(
             "lease acquire reset+clean name={lease_name}"
@@ -138,9 +193,20 @@ impl RealRunnerWorktreePool {
     }
 
     fn release(&self, worktree_path: PathBuf) {
-        let mut available = self.available_worktrees.lock().unwrap();
-        available.push(worktree_path);
+        let mut state = self.state.lock().unwrap();
+        state.holders.remove(&worktree_path);
+        state.available.push(worktree_path);
+        let idle = state.available.len() == self.pool_size;
         self.available_worktrees_cv.notify_one();
+        drop(state);
+        if idle && worktree_metrics_enabled() {
+            eprintln!(
+                "{}",
+                self.metrics
+                    .snapshot()
+                    .summary_line(&self.repo_key, self.pool_size)
+            );
+        }
     }
 }
 
@@ -42,6 +42,30 @@ pub enum BlockKind {
     Text,
 }
 
+/// What a block is in headlamp's output, as opposed to its shape ([`BlockKind`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum BlockRole {
+    RunHeader,
+    FileOverview,
+    FailureBlock,
+    CoverageTable,
+    Footer,
+    Other,
+}
+
+impl BlockRole {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RunHeader => "run header",
+            Self::FileOverview => "file overview",
+            Self::FailureBlock => "failure block",
+            Self::CoverageTable => "coverage table",
+            Self::Footer => "footer",
+            Self::Other => "other",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LineNode {
     pub token_range: Range<usize>,
@@ -52,6 +76,7 @@ pub struct LineNode {
 #[derive(Debug, Clone, Serialize)]
 pub struct BlockNode {
     pub kind: BlockKind,
+    pub role: BlockRole,
     pub line_range: Range<usize>,
     pub hash: String,
     pub line_count: usize,
@@ -216,16 +241,19 @@ fn build_lines(text: &str) -> Vec<LineNode> {
 }
 
 fn build_blocks(lines: &[LineNode]) -> Vec<BlockNode> {
+    let roles = assign_line_roles(lines);
     let mut blocks: Vec<BlockNode> = vec![];
     let mut start = 0usize;
     while start < lines.len() {
-        let next = next_block_end(lines, start);
+        let next = next_block_end(lines, &roles, start);
         let slice = &lines[start..next];
         let kind = classify_block(slice);
+        let role = roles[start];
         blocks.push(BlockNode {
             kind,
+            role,
             line_range: start..next,
-            hash: hash_block(kind, slice),
+            hash: hash_block(kind, role, slice),
             line_count: slice.len(),
         });
         start = next;
@@ -233,16 +261,96 @@ fn build_blocks(lines: &[LineNode]) -> Vec<BlockNode> {
     blocks
 }
 
-fn next_block_end(lines: &[LineNode], start: usize) -> usize {
+/// Blocks break at blank/non-blank transitions and wherever the line role changes.
+fn next_block_end(lines: &[LineNode], roles: &[BlockRole], start: usize) -> usize {
     let is_blank = |line: &LineNode| line.stripped_preview.trim().is_empty();
     let start_is_blank = lines.get(start).is_some_and(is_blank);
     let end = (start..lines.len()).find(|&i| {
         let blank = lines.get(i).is_some_and(is_blank);
-        blank != start_is_blank
+        blank != start_is_blank || roles[i] != roles[start]
     });
     end.unwrap_or(lines.len())
 }
 
+/// Lines without a marker of their own continue the role of the line before them (blank
+/// lines included); details right after a `FAIL` line belong to that failure.
+fn assign_line_roles(lines: &[LineNode]) -> Vec<BlockRole> {
+    let mut current = BlockRole::Other;
+    let mut after_fail_line = false;
+    lines
+        .iter()
+        .map(|line| {
+            let text = line.stripped_preview.trim();
+            if text.is_empty() {
+                return current;
+            }
+            let continuation = if after_fail_line {
+                BlockRole::FailureBlock
+            } else {
+                current
+            };
+            current = explicit_line_role(text, current).unwrap_or(continuation);
+            after_fail_line = text.starts_with("FAIL ");
+            current
+        })
+        .collect()
+}
+
+fn explicit_line_role(text: &str, current: BlockRole) -> Option<BlockRole> {
+    const FOOTER_PREFIXES: [&str; 7] = [
+        "Test Files ",
+        "Tests ",
+        "Time ",
+        "Snapshots ",
+        "Test Suites:",
+        "Tests:",
+        "Snapshots:",
+    ];
+    const COVERAGE_PREFIXES: [&str; 4] = [
+        "Coverage summary",
+        "Hotspots:",
+        "Uncovered functions:",
+        "Uncovered Line",
+    ];
+    let is_test_line = text.starts_with(['✓', '×', '✕', '↓', '○']);
+    if text.starts_with("RUN ") {
+        Some(BlockRole::RunHeader)
+    } else if FOOTER_PREFIXES.iter().any(|p| text.starts_with(p)) {
+        Some(BlockRole::Footer)
+    } else if text.contains("Failed Tests")
+        || text.starts_with('●')
+        || (is_test_line && text.contains(" > "))
+    {
+        Some(BlockRole::FailureBlock)
+    } else if is_box_table_line(text)
+        || is_pipe_table_line(text)
+        || (text.starts_with('|') && text.ends_with('|'))
+        || COVERAGE_PREFIXES.iter().any(|p| text.starts_with(p))
+    {
+        Some(BlockRole::CoverageTable)
+    } else if text.starts_with("PASS ")
+        || text.starts_with("FAIL ")
+        || is_file_header_line(text)
+        || (is_test_line && current != BlockRole::FailureBlock)
+    {
+        Some(BlockRole::FileOverview)
+    } else {
+        None
+    }
+}
+
+/// `tests/sum_test.rs (3)`: a test file followed by its test count.
+fn is_file_header_line(text: &str) -> bool {
+    let Some((path, count)) = text
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+    else {
+        return false;
+    };
+    let count_is_numeric = !count.is_empty() && count.chars().all(|c| c.is_ascii_digit());
+    !path.contains(' ') && (count == "<N>" || count_is_numeric)
+}
+
 fn classify_block(lines: &[LineNode]) -> BlockKind {
     if lines.iter().all(|l| l.stripped_preview.trim().is_empty()) {
         return BlockKind::Blank;
@@ -281,9 +389,9 @@ fn is_rule_line(line: &str) -> bool {
     t.len() >= 20 && t.chars().all(|c| c == '─' || c == '=' || c == '-')
 }
 
-fn hash_block(kind: BlockKind, lines: &[LineNode]) -> String {
+fn hash_block(kind: BlockKind, role: BlockRole, lines: &[LineNode]) -> String {
     let mut h = Sha1::new();
-    h.update(format!("{kind:?}\n").as_bytes());
+    h.update(format!("{kind:?} {role:?}\n").as_bytes());
     lines
         .iter()
         .for_each(|line| h.update(format!("{}\n", line.stripped_preview).as_bytes()));
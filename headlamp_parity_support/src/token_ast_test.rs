@@ -0,0 +1,56 @@
+use crate::token_ast::{BlockRole, build_document_ast};
+
+const INLINE_FAILURE_RUN: &str = " RUN  /repo
+
+tests/sum_test.rs (1)
+  × test_sum_fails
+
+ FAIL  tests/sum_test.rs
+    Expected
+      2
+    Received
+      1
+
+────────────────────────────────────────────────────────────────────────────────
+× tests/sum_test.rs > test_sum_fails
+    Message:
+    assertion failed
+───────────────────────────────────────────────────────────────  Failed Tests 1
+
+Test Files 1 failed (1)
+Tests     1 failed (1)
+Time      1ms
+┌──────┬──────┐
+│File  │Lines%│
+└──────┴──────┘
+";
+
+#[test]
+fn document_ast_classifies_blocks_by_their_role_in_the_run() {
+    let roles = build_document_ast(INLINE_FAILURE_RUN)
+        .blocks
+        .iter()
+        .filter(|block| block.kind != crate::token_ast::BlockKind::Blank)
+        .map(|block| (block.role, block.line_count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        roles,
+        vec![
+            (BlockRole::RunHeader, 1),
+            (BlockRole::FileOverview, 2),
+            (BlockRole::FileOverview, 1),
+            (BlockRole::FailureBlock, 4),
+            (BlockRole::FailureBlock, 5),
+            (BlockRole::Footer, 3),
+            (BlockRole::CoverageTable, 3),
+        ]
+    );
+}
+
+#[test]
+fn document_ast_only_treats_counted_paths_as_file_headers() {
+    let overview = build_document_ast("tests/a_test.rs (1)\n").blocks;
+    let other = build_document_ast("tests/a_test.rs (one)\n").blocks;
+    assert_eq!(overview[0].role, BlockRole::FileOverview);
+    assert_eq!(other[0].role, BlockRole::Other);
+}
@@ -1,3 +1,4 @@
+use crate::parity_support::fs::FixtureRepoBuilder;
 use crate::parity_support::runner_parity::{
     RunnerId, assert_runner_parity_tty_snapshot_all_runners_env, lease_real_runner_worktree,
     shared_threshold_real_runner_repo,
@@ -350,30 +351,22 @@ fn parity_runner_failure_message_identical_all_runners() {
     let repo = lease.path();
 
     let shared_message = "assertion `left == right` failed\n  left: 1\n right: 2\n";
-    std::fs::write(
-        repo.join("tests/sum_fail_test.js"),
-        format!(
-            "test('test_sum_fails', () => {{ throw new Error({:?}); }});\n",
-            shared_message
-        ),
-    )
-    .unwrap();
-    std::fs::write(
-        repo.join("tests/sum_fail_test.rs"),
-        format!(
-            "#[test]\nfn test_sum_fails() {{\n    panic!({:?});\n}}\n",
-            shared_message
-        ),
-    )
-    .unwrap();
-    std::fs::write(
-        repo.join("tests/sum_fail_test.py"),
-        format!(
-            "def test_sum_fails() -> None:\n    raise AssertionError({:?})\n",
-            shared_message
-        ),
-    )
-    .unwrap();
+    FixtureRepoBuilder::new(repo)
+        .file(
+            "tests/sum_fail_test.js",
+            &format!("test('test_sum_fails', () => {{ throw new Error({shared_message:?}); }});\n"),
+        )
+        .file(
+            "tests/sum_fail_test.rs",
+            &format!("#[test]\nfn test_sum_fails() {{\n    panic!({shared_message:?});\n}}\n"),
+        )
+        .file(
+            "tests/sum_fail_test.py",
+            &format!(
+                "def test_sum_fails() -> None:\n    raise AssertionError({shared_message:?})\n"
+            ),
+        )
+        .build();
 
     let headlamp_bin = crate::parity_support::runner_parity::runner_parity_headlamp_bin();
     let runners = [
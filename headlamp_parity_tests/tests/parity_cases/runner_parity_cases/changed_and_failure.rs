@@ -30,21 +30,22 @@ fn parity_runner_failure_output_has_standard_sections_all_runners() {
     let lease = lease_repo_for_case("failure-output-sections");
     let repo = lease.path();
 
-    std::fs::write(
-        repo.join("tests/sum_fail_test.js"),
-        "test('test_sum_fails', () => { expect(1).toBe(2); });\n",
-    )
-    .unwrap();
-    std::fs::write(
-        repo.join("tests/sum_fail_test.rs"),
-        "#[test]\nfn test_sum_fails() {\n    assert_eq!(1, 2);\n}\n",
-    )
-    .unwrap();
-    std::fs::write(
-        repo.join("tests/sum_fail_test.py"),
-        "def test_sum_fails() -> None:\n    assert 1 == 2\n",
-    )
-    .unwrap();
+    FixtureRepoBuilder::new(repo)
+        .files(&[
+            (
+                "tests/sum_fail_test.js",
+                "test('test_sum_fails', () => { expect(1).toBe(2); });\n",
+            ),
+            (
+                "tests/sum_fail_test.rs",
+                "#[test]\nfn test_sum_fails() {\n    assert_eq!(1, 2);\n}\n",
+            ),
+            (
+                "tests/sum_fail_test.py",
+                "def test_sum_fails() -> None:\n    assert 1 == 2\n",
+            ),
+        ])
+        .build();
 
     let headlamp_bin = crate::parity_support::runner_parity::runner_parity_headlamp_bin();
     let runners = [
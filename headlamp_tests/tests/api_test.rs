@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::api::{ApiError, RunRequest, parse_args, run};
+use headlamp::runner::Runner;
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join("headlamp-api-tests").join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn requests_collect_runners_and_args() {
+    let request = RunRequest::new("/repo")
+        .runner(Runner::Pytest)
+        .args(["--bail", "tests/test_sum.py"]);
+    assert_eq!(request.repo_root, PathBuf::from("/repo"));
+    assert_eq!(request.runners, vec![Runner::Pytest]);
+    assert_eq!(request.args, vec!["--bail", "tests/test_sum.py"]);
+    assert!(!request.quiet);
+    assert!(request.quiet().quiet);
+}
+
+#[test]
+fn runner_names_parse_like_the_cli_flag() {
+    assert_eq!(Runner::parse("cargo-nextest"), Some(Runner::CargoNextest));
+    assert_eq!(Runner::parse("Pytest"), Some(Runner::Pytest));
+    assert_eq!(
+        Runner::parse("plugin:gradle"),
+        Some(Runner::Plugin("gradle"))
+    );
+    assert_eq!(Runner::parse("plugin:"), None);
+    assert_eq!(Runner::parse("mocha"), None);
+}
+
+#[test]
+fn cli_only_flags_and_bad_args_are_errors() {
+    let repo_root = mk_temp_dir("cli_only_flags");
+    let watch = run(&RunRequest::new(&repo_root).args(["--watch"])).unwrap_err();
+    assert!(
+        matches!(&watch, ApiError::InvalidArgs(message) if message.contains("--watch")),
+        "{watch}"
+    );
+    let unknown_preset = parse_args(&repo_root, &repo_root, &["--preset=nope".to_string()]);
+    assert!(unknown_preset.is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn a_run_returns_the_model_and_its_rendering() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo_root = mk_temp_dir("plugin_run");
+    let script = repo_root.join(".headlamp/plugins/run.sh");
+    let case = serde_json::json!({
+        "event": "testCase",
+        "testPath": "checks/spelling",
+        "result": {
+            "title": "no typos",
+            "fullName": "no typos",
+            "status": "failed",
+            "timedOut": null,
+            "duration": 1,
+            "location": null,
+            "failureMessages": ["teh"],
+            "failureDetails": null,
+        },
+    });
+    write_file(
+        &script,
+        &format!(
+            "#!/bin/sh\necho '{{\"event\":\"hello\",\"protocol\":1}}' >&${{HEADLAMP_EVENTS_FD:-1}}\n\
+             echo '{case}' >&${{HEADLAMP_EVENTS_FD:-1}}\n"
+        ),
+    );
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    write_file(
+        &repo_root.join(".headlamp/plugins/spelling.json"),
+        r#"{"name":"spelling","protocol":1,"command":["./run.sh"]}"#,
+    );
+
+    let request = RunRequest::new(&repo_root)
+        .runner(Runner::Plugin("spelling"))
+        .args(["--ci"]);
+    let outcome = run(&request).unwrap();
+    assert_eq!(outcome.exit_code, 1);
+    assert_eq!(outcome.models.len(), 1);
+    assert_eq!(outcome.results.failed, 1);
+    assert_eq!(
+        outcome.results.failed_tests,
+        vec![("checks/spelling".to_string(), Some("no typos".to_string()))]
+    );
+    assert!(outcome.coverage.is_empty());
+    assert!(
+        outcome.rendered.contains("no typos"),
+        "{}",
+        outcome.rendered
+    );
+    assert!(!headlamp::format::report_out::is_quiet());
+
+    let quiet = run(&request.clone().quiet()).unwrap();
+    assert!(headlamp::format::report_out::is_quiet());
+    assert_eq!(quiet.results, outcome.results);
+    assert!(quiet.rendered.contains("no typos"), "{}", quiet.rendered);
+    run(&request).unwrap();
+    assert!(!headlamp::format::report_out::is_quiet());
+}
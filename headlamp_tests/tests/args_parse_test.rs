@@ -1,5 +1,5 @@
 use headlamp::args::{CoverageDetail, config_tokens, derive_args};
-use headlamp::config::{CoverageUi, HeadlampConfig};
+use headlamp::config::{CoverageEngine, CoverageProvider, CoverageUi, HeadlampConfig};
 
 #[test]
 fn derives_basic_flags_and_selection() {
@@ -316,3 +316,179 @@ fn double_dash_separator_forces_passthrough_even_for_headlamp_named_flags() {
     assert!(parsed.runner_args.iter().any(|t| t == "--verbose"));
     assert!(!parsed.runner_args.iter().any(|t| t == "--"));
 }
+
+#[test]
+fn full_stacks_flag_and_config_are_not_forwarded() {
+    let argv = vec!["--full-stacks".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        true,
+    );
+    assert!(parsed.full_stacks);
+    assert!(parsed.runner_args.is_empty());
+
+    let cfg = HeadlampConfig {
+        full_stacks: Some(true),
+        ..Default::default()
+    };
+    let parsed_from_cfg = derive_args(&config_tokens(&cfg, &[]), &[], true);
+    assert!(parsed_from_cfg.full_stacks);
+}
+
+#[test]
+fn progress_heartbeat_interval_parses_from_cli_and_config() {
+    let argv = vec!["--progress-heartbeat=5".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.progress_heartbeat, Some(5));
+    assert!(parsed.runner_args.is_empty());
+
+    let cfg = HeadlampConfig {
+        progress_heartbeat: Some(0),
+        ..Default::default()
+    };
+    let parsed_from_cfg = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed_from_cfg.progress_heartbeat, Some(0));
+}
+
+#[test]
+fn process_limits_parse_from_cli_and_config() {
+    let argv = vec![
+        "--max-memory=1.5G".to_string(),
+        "--nice".to_string(),
+        "10".to_string(),
+    ];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.max_memory_bytes, Some(3 * 512 * 1024 * 1024));
+    assert_eq!(parsed.nice, Some(10));
+    assert!(parsed.runner_args.is_empty());
+
+    let cfg = HeadlampConfig {
+        max_memory: Some("512MiB".to_string()),
+        nice: Some(5),
+        ..Default::default()
+    };
+    let parsed_from_cfg = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed_from_cfg.max_memory_bytes, Some(512 * 1024 * 1024));
+    assert_eq!(parsed_from_cfg.nice, Some(5));
+}
+
+#[test]
+fn invalid_max_memory_is_ignored() {
+    let argv = vec!["--max-memory=lots".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.max_memory_bytes, None);
+}
+
+#[test]
+fn python_interpreter_parses_from_cli_and_config() {
+    let argv = vec!["--python".to_string(), ".venv/bin/python".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.python.as_deref(), Some(".venv/bin/python"));
+    assert!(parsed.selection_paths.is_empty());
+
+    let cfg = HeadlampConfig {
+        python: Some("python3.12".to_string()),
+        ..Default::default()
+    };
+    let parsed_from_cfg = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed_from_cfg.python.as_deref(), Some("python3.12"));
+}
+
+#[test]
+fn cargo_package_flags_parse_including_short_form() {
+    let argv = headlamp::args::expand_short_package_flag(
+        &["-p", "core", "-pcli", "--exclude", "bench", "--", "-p"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>(),
+    );
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.packages, vec!["core", "cli"]);
+    assert_eq!(parsed.exclude_packages, vec!["bench"]);
+    assert!(parsed.selection_paths.is_empty());
+    assert_eq!(parsed.runner_args, vec!["-p"]);
+
+    let cfg = HeadlampConfig {
+        packages: Some(vec!["core".to_string()]),
+        exclude_packages: Some(vec!["bench".to_string()]),
+        ..Default::default()
+    };
+    let parsed_from_cfg = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed_from_cfg.packages, vec!["core"]);
+    assert_eq!(parsed_from_cfg.exclude_packages, vec!["bench"]);
+}
+
+#[test]
+fn coverage_engine_defaults_to_auto_and_reads_cli_and_config() {
+    let parsed = derive_args(&[], &[], false);
+    assert_eq!(parsed.coverage_engine, CoverageEngine::Auto);
+
+    let argv = vec!["--coverage-engine=llvm-cov".to_string()];
+    let parsed = derive_args(&[], &argv, false);
+    assert_eq!(parsed.coverage_engine, CoverageEngine::LlvmCov);
+
+    let cfg: HeadlampConfig =
+        serde_json::from_str(r#"{ "coverageEngine": "instrument" }"#).unwrap();
+    let argv = vec!["--coverage".to_string()];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.coverage_engine, CoverageEngine::Instrument);
+}
+
+#[test]
+fn coverage_thresholds_spec_sets_several_metrics() {
+    let cfg = HeadlampConfig::default();
+    let argv = ["--coverage-thresholds", "branches=80,lines=90%"].map(str::to_string);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    let thresholds = parsed.coverage_thresholds.expect("thresholds");
+    assert_eq!(thresholds.branches, Some(80.0));
+    assert_eq!(thresholds.lines, Some(90.0));
+    assert_eq!(thresholds.functions, None);
+    assert!(parsed.runner_args.is_empty());
+}
+
+#[test]
+fn coverage_provider_defaults_to_babel_and_reads_cli_and_config() {
+    let cfg = HeadlampConfig::default();
+    let argv = vec!["--coverage-provider=v8".to_string()];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.coverage_provider, CoverageProvider::V8);
+
+    let argv = vec!["--coverage".to_string()];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.coverage_provider, CoverageProvider::Babel);
+
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "coverageProvider": "v8" }"#).unwrap();
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.coverage_provider, CoverageProvider::V8);
+}
+
+#[test]
+fn project_flags_are_repeatable_and_come_from_config() {
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "ignoreProjects": ["legacy"] }"#).unwrap();
+    let argv = ["--project", "web", "--project=api,docs"].map(str::to_string);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.projects, vec!["web", "api", "docs"]);
+    assert_eq!(parsed.ignore_projects, vec!["legacy"]);
+    assert!(parsed.selection_paths.is_empty());
+}
@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::session::RunSession;
+use headlamp::session::artifacts::{ArtifactKind, INDEX_FILE, collect_artifacts};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-artifacts-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn artifact_flags_come_from_cli_and_config() {
+    let cfg: HeadlampConfig = serde_json::from_str(
+        r#"{ "artifactsDir": "ci-artifacts", "artifactGlobs": ["test-results/**/*.png"] }"#,
+    )
+    .unwrap();
+    let argv = ["--artifact-glob", "target/nextest/**/junit.xml"].map(str::to_string);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.artifacts_dir.as_deref(), Some("ci-artifacts"));
+    assert_eq!(
+        parsed.artifact_globs,
+        vec!["test-results/**/*.png", "target/nextest/**/junit.xml"]
+    );
+    assert!(parsed.runner_args.is_empty());
+}
+
+#[test]
+fn registered_artifacts_and_glob_matches_are_collected_with_an_index() {
+    let repo_root = mk_temp_dir("collected_with_an_index");
+    let session = RunSession::new(false).unwrap();
+    let coverage = session.subdir("coverage");
+    write_file(&coverage.join("lcov.info"), "TN:\n");
+    write_file(&coverage.join("jest/coverage-final.json"), "{}");
+    let report = session.subdir("jest").join("bridge.0.json");
+    write_file(&report, "{\"ok\":true}");
+    session.register_artifact(ArtifactKind::Coverage, "jest", &coverage);
+    session.register_artifact(ArtifactKind::Report, "jest", &report);
+    session.register_artifact(
+        ArtifactKind::Report,
+        "jest",
+        session.subdir("never-written.json"),
+    );
+    write_file(&repo_root.join("test-results/login/failed.png"), "png");
+    write_file(&repo_root.join("test-results/login/trace.txt"), "trace");
+
+    let dest = repo_root.join("out");
+    let globs = vec!["test-results/**/*.png".to_string()];
+    let entries = collect_artifacts(&dest, &repo_root, &session.artifacts(), &globs).unwrap();
+
+    let paths = entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            "coverage/jest/coverage/jest/coverage-final.json",
+            "coverage/jest/coverage/lcov.info",
+            "reports/jest/bridge.0.json",
+            "attachments/test-results/login/failed.png",
+        ]
+    );
+    let report_copy = std::fs::read_to_string(dest.join("reports/jest/bridge.0.json")).unwrap();
+    assert_eq!(report_copy, "{\"ok\":true}");
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dest.join(INDEX_FILE)).unwrap()).unwrap();
+    assert_eq!(index["schema_version"], 1);
+    assert_eq!(index["artifacts"][2]["kind"], "report");
+    assert_eq!(index["artifacts"][2]["bytes"], 11);
+    assert_eq!(index["artifacts"][3]["runner"], "repo");
+}
+
+#[test]
+fn collecting_again_replaces_the_previous_run() {
+    let repo_root = mk_temp_dir("replaces_the_previous_run");
+    let dest = repo_root.join("out");
+    let session = RunSession::new(false).unwrap();
+    let log = session.subdir("first.json");
+    write_file(&log, "1");
+    session.register_artifact(ArtifactKind::Report, "pytest", &log);
+    collect_artifacts(&dest, &repo_root, &session.artifacts(), &[]).unwrap();
+    assert!(dest.join("reports/pytest/first.json").is_file());
+
+    let entries = collect_artifacts(&dest, &repo_root, &[], &[]).unwrap();
+    assert!(entries.is_empty());
+    assert!(!dest.join("reports").exists());
+    assert!(dest.join(INDEX_FILE).is_file());
+}
@@ -0,0 +1,56 @@
+use headlamp::args::derive_args;
+use headlamp::badges::{coverage_badge, render_badge, tests_badge};
+use headlamp::recorded::RecordedResults;
+
+#[test]
+fn badge_out_is_parsed_from_the_flag() {
+    let parse = |argv: &[&str]| {
+        let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        derive_args(&[], &argv, false).badge_out
+    };
+    assert_eq!(parse(&[]), None);
+    assert_eq!(parse(&["--badge-out=badges"]).as_deref(), Some("badges"));
+    assert_eq!(parse(&["--badgeOut", "out"]).as_deref(), Some("out"));
+}
+
+#[test]
+fn tests_badge_shows_counts_and_turns_red_on_failures() {
+    let passing = RecordedResults {
+        passed: 40,
+        skipped: 2,
+        ..RecordedResults::default()
+    };
+    let badge = tests_badge(&passing);
+    assert!(badge.contains("<title>tests: 40 passed, 2 skipped</title>"));
+    assert!(badge.contains(r##"fill="#4c1""##));
+
+    let failing = RecordedResults {
+        failed: 3,
+        ..passing
+    };
+    let badge = tests_badge(&failing);
+    assert!(badge.contains("<title>tests: 3 failed, 40 passed, 2 skipped</title>"));
+    assert!(badge.contains(r##"fill="#e05d44""##));
+
+    assert!(tests_badge(&RecordedResults::default()).contains("tests: no tests"));
+}
+
+#[test]
+fn coverage_badge_needs_recorded_coverage() {
+    assert_eq!(coverage_badge(&RecordedResults::default()), None);
+    let results = RecordedResults {
+        coverage_lines: Some((842, 1000)),
+        ..RecordedResults::default()
+    };
+    let badge = coverage_badge(&results).expect("badge");
+    assert!(badge.contains("<title>coverage: 84.2%</title>"));
+    assert!(badge.contains(r##"fill="#97ca00""##));
+}
+
+#[test]
+fn badge_widths_follow_the_text_and_text_is_escaped() {
+    let badge = render_badge("a<b", "ok", "#4c1");
+    assert!(badge.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="55""#));
+    assert!(badge.contains("<title>a&lt;b: ok</title>"));
+    assert!(badge.contains(r#"<text x="15" y="14">a&lt;b</text><text x="43" y="14">ok</text>"#));
+}
@@ -0,0 +1,106 @@
+use headlamp::bisect_order::{BisectOptions, bisect, matches_target, run_order};
+use headlamp::rerun::FailedTest;
+use headlamp::runner::Runner;
+
+fn test(file: &str, name: &str) -> FailedTest {
+    FailedTest {
+        test_file_path: file.to_string(),
+        full_name: Some(name.to_string()),
+    }
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn bisection_narrows_to_the_single_polluter() {
+    let candidates = (0..13).collect::<Vec<_>>();
+    let mut runs = 0;
+    let found = bisect(&candidates, |subset| {
+        runs += 1;
+        subset.contains(&9)
+    });
+    assert_eq!(found, vec![9]);
+    assert!(runs <= 8, "{runs} runs");
+}
+
+#[test]
+fn bisection_stops_when_the_failure_needs_tests_from_both_halves() {
+    let candidates = (0..8).collect::<Vec<_>>();
+    let found = bisect(&candidates, |subset| {
+        subset.contains(&1) && subset.contains(&6)
+    });
+    assert_eq!(found, candidates);
+
+    let found = bisect(&candidates, |subset| {
+        subset.contains(&4) && subset.contains(&5)
+    });
+    assert_eq!(found, vec![4, 5]);
+}
+
+#[test]
+fn the_runner_and_target_are_taken_out_of_the_headlamp_args() {
+    let options = BisectOptions::parse(&strings(&[
+        "--runner",
+        "pytest",
+        "--test=tests/test_b.py::test_clean",
+        "--shuffle=7",
+        "tests",
+    ]))
+    .unwrap();
+    assert_eq!(options.runner, Some(Runner::Pytest));
+    assert_eq!(
+        options.target.as_deref(),
+        Some("tests/test_b.py::test_clean")
+    );
+    assert_eq!(options.args, strings(&["--shuffle=7", "tests"]));
+
+    assert!(BisectOptions::parse(&strings(&["--test"])).is_err());
+    assert!(BisectOptions::parse(&strings(&["--runner=mocha"])).is_err());
+}
+
+#[test]
+fn a_target_matches_by_file_suffix_and_optional_name() {
+    let case = test("tests/test_b.py", "test_clean");
+    assert!(matches_target(&case, "test_b.py"));
+    assert!(matches_target(&case, "./tests/test_b.py::test_clean"));
+    assert!(!matches_target(&case, "tests/test_b.py::test_other"));
+    assert!(!matches_target(&case, "test_a.py"));
+}
+
+#[test]
+fn the_run_order_follows_the_shuffle_seed_by_file_and_libtest_by_name() {
+    let tests = vec![
+        test("b.test.js", "b one"),
+        test("a.test.js", "a one"),
+        test("c.test.js", "c one"),
+        test("a.test.js", "a two"),
+    ];
+    assert_eq!(
+        run_order(Runner::Jest, &tests, None),
+        vec![
+            test("a.test.js", "a one"),
+            test("a.test.js", "a two"),
+            test("b.test.js", "b one"),
+            test("c.test.js", "c one"),
+        ]
+    );
+
+    let mut files = vec!["a.test.js", "b.test.js", "c.test.js"];
+    headlamp::shuffle::shuffle(&mut files, 3);
+    let mut shuffled = run_order(Runner::Jest, &tests, Some(3))
+        .into_iter()
+        .map(|test| test.test_file_path)
+        .collect::<Vec<_>>();
+    shuffled.dedup();
+    assert_eq!(shuffled, strings(&files));
+
+    assert_eq!(
+        run_order(Runner::CargoTest, &tests, Some(3))
+            .iter()
+            .filter_map(|test| test.full_name.as_deref())
+            .collect::<Vec<_>>(),
+        vec!["a one", "a two", "b one", "c one"]
+    );
+}
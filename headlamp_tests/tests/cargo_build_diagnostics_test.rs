@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use headlamp::format::cargo_build::{CargoBuildDiagnostics, render_build_failed};
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+
+fn compiler_message(level: &str, code: Option<&str>, message: &str, rendered: &str) -> String {
+    serde_json::json!({
+        "reason": "compiler-message",
+        "package_id": "path+file:///repo#demo@0.1.0",
+        "target": { "name": "demo" },
+        "message": {
+            "message": message,
+            "level": level,
+            "code": code.map(|code| serde_json::json!({ "code": code })),
+            "spans": [{
+                "file_name": "src/lib.rs",
+                "line_start": 3,
+                "column_start": 18,
+                "is_primary": true,
+            }],
+            "rendered": rendered,
+        }
+    })
+    .to_string()
+}
+
+const MISMATCHED_TYPES: &str = "\u{1b}[1m\u{1b}[38;5;9merror[E0308]\u{1b}[0m: mismatched types\n  --> src/lib.rs:3:18\n   |\n3  |     let x: u32 = \"nope\";\n   |                  ^^^^^^ expected `u32`, found `&str`\n\n";
+
+#[test]
+fn cargo_messages_are_taken_and_other_lines_are_left_alone() {
+    let mut build = CargoBuildDiagnostics::default();
+    let artifact = r#"{"reason":"compiler-artifact","package_id":"demo","target":{"name":"demo"}}"#;
+    assert!(build.push_line(artifact));
+    assert!(!build.push_line("test it_works ... ok"));
+    assert!(!build.push_line(r#"{"type":"test","event":"ok","name":"it_works"}"#));
+    assert!(!build.has_errors());
+}
+
+#[test]
+fn errors_are_deduplicated_across_targets_and_warnings_counted() {
+    let mut build = CargoBuildDiagnostics::default();
+    let error = compiler_message("error", Some("E0308"), "mismatched types", MISMATCHED_TYPES);
+    build.push_line(&error);
+    build.push_line(&error);
+    build.push_line(&compiler_message(
+        "warning",
+        None,
+        "unused variable: `y`",
+        "",
+    ));
+    build.push_line(&compiler_message("warning", None, "1 warning emitted", ""));
+    build.push_line(&compiler_message(
+        "error",
+        None,
+        "aborting due to 1 previous error",
+        "",
+    ));
+
+    assert_eq!(build.errors().len(), 1);
+    assert_eq!(build.warning_count(), 1);
+    let error = &build.errors()[0];
+    assert_eq!(error.code.as_deref(), Some("E0308"));
+    assert_eq!(error.file.as_deref(), Some("src/lib.rs"));
+    assert_eq!((error.line, error.column), (Some(3), Some(18)));
+}
+
+#[test]
+fn build_failed_section_links_the_location_and_keeps_the_snippet() {
+    let mut build = CargoBuildDiagnostics::default();
+    build.push_line(&compiler_message(
+        "error",
+        Some("E0308"),
+        "mismatched types",
+        MISMATCHED_TYPES,
+    ));
+    let ctx = make_ctx(Path::new("/repo"), Some(80), false, false, None);
+    let rendered = render_build_failed(&build, &ctx).join("\n");
+    let plain = strip_ansi_simple(&rendered);
+
+    assert!(plain.contains("Build failed"), "{plain}");
+    assert!(
+        plain.contains("✕ error[E0308]: mismatched types"),
+        "{plain}"
+    );
+    assert!(plain.contains("at src/lib.rs:3:18"), "{plain}");
+    assert!(plain.contains("expected `u32`, found `&str`"), "{plain}");
+    assert!(!plain.contains("-->"), "{plain}");
+    assert!(
+        plain.contains("Build failed 1 error, 0 warnings"),
+        "{plain}"
+    );
+    assert!(rendered.contains("/repo/src/lib.rs"), "{rendered}");
+}
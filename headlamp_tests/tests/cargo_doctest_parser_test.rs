@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use headlamp::format::cargo_doctest::{parse_doctest_name, parse_doctest_output};
+use headlamp::format::cargo_test::parse_cargo_test_output;
+
+const DOCTEST_OUTPUT: &str = "   Doc-tests pkg
+
+running 4 tests
+test src/lib.rs - Foo::bar (line 10) ... FAILED
+test src/lib.rs - Foo::qux (line 18) - compile fail ... ok
+test src/util.rs - (line 1) ... ignored
+test src/lib.rs - add (line 3) ... ok
+
+failures:
+
+---- src/lib.rs - Foo::bar (line 10) stdout ----
+Test executable failed (exit status: 101).
+
+stderr:
+
+thread 'main' panicked at src/lib.rs:5:1:
+assertion `left == right` failed
+  left: 3
+ right: 4
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+   1: rust_out::main
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+failures:
+    src/lib.rs - Foo::bar (line 10)
+
+test result: FAILED. 2 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.29s";
+
+#[test]
+fn doctest_names_carry_source_file_and_doc_comment_line() {
+    let name = parse_doctest_name("src/lib.rs - Foo::qux (line 18) - compile fail").unwrap();
+    assert_eq!(name.source_path, "src/lib.rs");
+    assert_eq!(name.item, "Foo::qux");
+    assert_eq!(name.line, 18);
+    assert_eq!(name.title(), "Foo::qux (line 18)");
+    assert_eq!(
+        parse_doctest_name("src/util.rs - (line 1)")
+            .unwrap()
+            .title(),
+        "(line 1)"
+    );
+    assert!(parse_doctest_name("tests::unit").is_none());
+}
+
+#[test]
+fn doctest_failures_are_attributed_to_the_doc_comment() {
+    let suites = parse_doctest_output(Path::new("/repo"), DOCTEST_OUTPUT);
+    let paths = suites
+        .iter()
+        .map(|s| s.test_file_path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["/repo/src/lib.rs", "/repo/src/util.rs"]);
+
+    let lib = &suites[0];
+    assert_eq!(lib.status, "failed");
+    let failed = lib
+        .test_results
+        .iter()
+        .find(|t| t.title == "Foo::bar (line 10)")
+        .unwrap();
+    assert_eq!(failed.status, "failed");
+    assert_eq!(failed.location.as_ref().map(|l| l.line), Some(10));
+    let message = failed.failure_messages.join("\n");
+    assert!(message.contains("assertion `left == right` failed"));
+    assert!(!message.contains("rust_begin_unwind"));
+    assert!(!message.contains("Test executable failed"));
+
+    assert_eq!(suites[1].test_results[0].status, "pending");
+}
+
+#[test]
+fn cargo_test_output_keeps_doctests_out_of_the_last_test_binary_suite() {
+    let combined = format!(
+        "Running tests/sum_test.rs (target/debug/deps/sum_test-0000000000000000)\n\
+         test adds ... ok\n\
+         \n\
+         test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out\n\
+         {DOCTEST_OUTPUT}"
+    );
+    let model = parse_cargo_test_output(Path::new("/repo"), &combined).unwrap();
+    let sum_suite = model
+        .test_results
+        .iter()
+        .find(|s| s.test_file_path.ends_with("tests/sum_test.rs"))
+        .unwrap();
+    assert_eq!(sum_suite.test_results.len(), 1);
+    assert_eq!(model.test_results.len(), 3);
+    assert_eq!(model.aggregated.num_failed_tests, 1);
+    assert_eq!(model.aggregated.num_total_tests, 5);
+}
@@ -0,0 +1,74 @@
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::cargo::features::{
+    args_for_feature_set, feature_set_label, parse_feature_list, parse_feature_matrix,
+    run_feature_matrix,
+};
+use headlamp::config::HeadlampConfig;
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    )
+}
+
+#[test]
+fn feature_lists_accept_commas_and_spaces() {
+    assert_eq!(
+        parse_feature_list("serde, std tokio"),
+        vec!["serde", "std", "tokio"]
+    );
+    assert_eq!(
+        parse_feature_matrix("serde,std;;tokio"),
+        vec![
+            vec!["serde".to_string(), "std".to_string()],
+            vec![],
+            vec!["tokio".to_string()]
+        ]
+    );
+    assert!(parse_feature_matrix(" ").is_empty());
+}
+
+#[test]
+fn feature_flags_parse_and_label_the_run() {
+    let parsed = parse(&["--features=serde", "--no-default-features"]);
+    assert_eq!(parsed.features, vec!["serde"]);
+    assert!(parsed.no_default_features);
+    assert!(!parsed.all_features);
+    assert!(parsed.runner_args.is_empty());
+    assert_eq!(
+        feature_set_label(&parsed),
+        "--features=serde --no-default-features"
+    );
+    assert_eq!(feature_set_label(&parse(&[])), "default features");
+}
+
+#[test]
+fn matrix_entries_replace_features_and_carry_a_label() {
+    let parsed = parse(&["--features=std", "--feature-matrix=serde;"]);
+    assert_eq!(parsed.feature_matrix.len(), 2);
+    let entry = args_for_feature_set(&parsed, &parsed.feature_matrix[0]);
+    assert_eq!(entry.features, vec!["serde"]);
+    assert!(entry.feature_matrix.is_empty());
+    assert_eq!(entry.feature_set_label.as_deref(), Some("--features=serde"));
+    let default_entry = args_for_feature_set(&parsed, &parsed.feature_matrix[1]);
+    assert_eq!(
+        default_entry.feature_set_label.as_deref(),
+        Some("default features")
+    );
+}
+
+#[test]
+fn matrix_fails_when_any_entry_fails() {
+    let parsed = parse(&["--feature-matrix=a;b;c"]);
+    let mut seen = vec![];
+    let exit_code = run_feature_matrix(&parsed, |entry| {
+        seen.push(entry.features.join(","));
+        i32::from(entry.features == ["b"])
+    });
+    assert_eq!(seen, vec!["a", "b", "c"]);
+    assert_eq!(exit_code, 1);
+    assert_eq!(run_feature_matrix(&parsed, |_| 0), 0);
+}
@@ -1,4 +1,6 @@
-use headlamp::cargo_select::{changed_rust_seeds, filter_rust_tests_by_seeds};
+use headlamp::cargo_select::{
+    changed_rust_seeds, filter_rust_tests_by_seeds, parse_cargo_metadata,
+};
 
 fn mk_temp_dir(name: &str) -> std::path::PathBuf {
     let base = std::env::temp_dir()
@@ -28,3 +30,61 @@ fn selects_rust_tests_by_seed() {
     assert_eq!(kept.len(), 1);
     assert_eq!(kept[0], test_file);
 }
+
+fn workspace_metadata() -> String {
+    serde_json::json!({
+        "packages": [
+            { "name": "core", "manifest_path": "/ws/crates/core/Cargo.toml", "dependencies": [
+                { "name": "serde", "kind": null }
+            ], "targets": [{ "kind": ["lib"], "doctest": true }] },
+            { "name": "cli", "manifest_path": "/ws/crates/cli/Cargo.toml", "dependencies": [
+                { "name": "core", "path": "/ws/crates/core" }
+            ] },
+            { "name": "e2e", "manifest_path": "/ws/tests/e2e/Cargo.toml", "dependencies": [
+                { "name": "cli", "kind": "dev", "path": "/ws/crates/cli" }
+            ] },
+            { "name": "docs", "manifest_path": "/ws/crates/docs/Cargo.toml", "dependencies": [] }
+        ]
+    })
+    .to_string()
+}
+
+#[test]
+fn changed_files_select_owning_packages_and_reverse_dependencies() {
+    let workspace = parse_cargo_metadata(&workspace_metadata()).unwrap();
+    assert!(workspace.is_multi_package());
+    let core = workspace
+        .packages
+        .iter()
+        .find(|p| p.name == "core")
+        .unwrap();
+    assert!(core.workspace_deps.is_empty());
+    assert!(core.has_doctests);
+    assert!(
+        !workspace
+            .packages
+            .iter()
+            .any(|p| p.name == "e2e" && p.has_doctests)
+    );
+
+    let changed = vec![std::path::PathBuf::from("/ws/crates/core/src/lib.rs")];
+    assert_eq!(
+        workspace.affected_packages(&changed),
+        vec!["cli", "core", "e2e"]
+    );
+    let changed = vec![std::path::PathBuf::from("/ws/crates/docs/src/lib.rs")];
+    assert_eq!(workspace.affected_packages(&changed), vec!["docs"]);
+    let changed = vec![std::path::PathBuf::from("/ws/README.md")];
+    assert!(workspace.affected_packages(&changed).is_empty());
+    let changed = vec![std::path::PathBuf::from("/ws/Cargo.lock")];
+    assert_eq!(workspace.affected_packages(&changed).len(), 4);
+}
+
+#[test]
+fn package_specs_match_names_globs_and_versions() {
+    let workspace = parse_cargo_metadata(&workspace_metadata()).unwrap();
+    assert_eq!(workspace.matching_packages("cli"), vec!["cli"]);
+    assert_eq!(workspace.matching_packages("c*"), vec!["core", "cli"]);
+    assert_eq!(workspace.matching_packages("core@0.1.0"), vec!["core"]);
+    assert!(workspace.matching_packages("nope").is_empty());
+}
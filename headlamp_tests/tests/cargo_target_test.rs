@@ -0,0 +1,46 @@
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::cargo::target::{target_runner_command, target_runner_env_key};
+use headlamp::config::HeadlampConfig;
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    )
+}
+
+#[test]
+fn target_runner_env_key_matches_cargo_naming() {
+    assert_eq!(
+        target_runner_env_key("thumbv7em-none-eabihf"),
+        "CARGO_TARGET_THUMBV7EM_NONE_EABIHF_RUNNER"
+    );
+    assert_eq!(
+        target_runner_env_key("aarch64-unknown-linux-gnu"),
+        "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER"
+    );
+}
+
+#[test]
+fn target_flags_parse_and_runner_wraps_only_cross_runs() {
+    let parsed = parse(&[
+        "--target",
+        "aarch64-unknown-linux-gnu",
+        "--target-runner=qemu-aarch64 -L /usr/aarch64-linux-gnu",
+    ]);
+    assert_eq!(parsed.target.as_deref(), Some("aarch64-unknown-linux-gnu"));
+    assert!(parsed.selection_paths.is_empty());
+    assert_eq!(
+        target_runner_command(&parsed),
+        Some(vec![
+            "qemu-aarch64".to_string(),
+            "-L".to_string(),
+            "/usr/aarch64-linux-gnu".to_string()
+        ])
+    );
+
+    let host_only = parse(&["--target-runner=qemu-aarch64"]);
+    assert_eq!(target_runner_command(&host_only), None);
+}
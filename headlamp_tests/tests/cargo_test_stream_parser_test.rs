@@ -238,3 +238,43 @@ fn cargo_test_parser_resolves_suite_paths_in_workspace_members_and_sets_location
     assert_eq!(loc.line, 12);
     assert_eq!(loc.column, 3);
 }
+
+#[test]
+fn cargo_test_stream_parser_keeps_ignore_reasons() {
+    let repo_root = Path::new("/repo");
+    let mut parser = CargoTestStreamParser::new(repo_root);
+
+    let combined = [
+        "Running tests/slow.rs (target/debug/deps/slow-0000000000000000)",
+        "running 2 tests",
+        "test needs_network ... ignored, needs network access",
+        "test bare_ignore ... ignored",
+        "",
+        "test result: ok. 0 passed; 0 failed; 2 ignored; 0 measured; 0 filtered out; finished in 0.00s",
+    ]
+    .join("\n");
+
+    combined.lines().for_each(|line| {
+        let _ = parser.push_line(line);
+    });
+
+    let model = parser.finalize().expect("model");
+    let cases = &model.test_results.first().expect("suite").test_results;
+    let reason_of = |name: &str| {
+        cases
+            .iter()
+            .find(|case| case.full_name == name)
+            .map(|case| (case.status.clone(), case.skip_reason.clone()))
+    };
+    assert_eq!(
+        reason_of("needs_network"),
+        Some((
+            "pending".to_string(),
+            Some("needs network access".to_string())
+        ))
+    );
+    assert_eq!(
+        reason_of("bare_ignore"),
+        Some(("pending".to_string(), None))
+    );
+}
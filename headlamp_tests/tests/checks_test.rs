@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::checks::{
+    build_findings_model, parse_check_spec, parse_clippy_messages, parse_eslint_json,
+    parse_ruff_json,
+};
+use headlamp::config::{CheckFormat, HeadlampConfig};
+
+#[test]
+fn checks_come_from_the_cli_and_config() {
+    let cfg: HeadlampConfig = serde_json::from_str(
+        r#"{ "checks": [{ "format": "ruff", "command": "ruff check --output-format json {files}" }] }"#,
+    )
+    .unwrap();
+    let argv = ["--check=eslint:npx eslint --format json {files}"].map(String::from);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+
+    assert_eq!(
+        parsed.checks,
+        vec![
+            "ruff:ruff check --output-format json {files}",
+            "eslint:npx eslint --format json {files}"
+        ]
+    );
+    let check = parse_check_spec(&parsed.checks[0]).unwrap();
+    assert_eq!(check.format, CheckFormat::Ruff);
+    assert_eq!(check.command, "ruff check --output-format json {files}");
+    assert!(parse_check_spec("pylint:pylint .").is_none());
+    assert!(parse_check_spec("eslint:").is_none());
+}
+
+#[test]
+fn eslint_errors_are_findings_and_warnings_are_not() {
+    let output = r#"[
+        {"filePath": "/repo/src/a.ts", "messages": [
+            {"ruleId": "no-unused-vars", "severity": 2, "message": "'x' is unused.", "line": 3, "column": 7},
+            {"ruleId": "eqeqeq", "severity": 1, "message": "Expected '==='.", "line": 4, "column": 9}
+        ]},
+        {"filePath": "/repo/src/b.ts", "messages": [
+            {"ruleId": null, "fatal": true, "severity": 2, "message": "Parsing error: ';' expected.", "line": 1, "column": 5}
+        ]}
+    ]"#;
+    let findings = parse_eslint_json(output, Path::new("/repo")).unwrap();
+
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].code, "no-unused-vars");
+    assert_eq!((findings[0].line, findings[0].column), (3, 7));
+    assert_eq!(findings[1].file, "/repo/src/b.ts");
+    assert_eq!(findings[1].code, "eslint");
+    assert!(parse_eslint_json("Oops! Something went wrong!", Path::new("/repo")).is_none());
+}
+
+#[test]
+fn ruff_and_clippy_findings_are_made_absolute() {
+    let ruff = r#"[{"code": "F401", "message": "`os` imported but unused", "filename": "pkg/mod.py",
+        "location": {"row": 1, "column": 8}}]"#;
+    let findings = parse_ruff_json(ruff, Path::new("/repo")).unwrap();
+    assert_eq!(findings[0].file, "/repo/pkg/mod.py");
+    assert_eq!(findings[0].code, "F401");
+
+    let lint = serde_json::json!({
+        "reason": "compiler-message",
+        "message": {
+            "message": "unneeded `return` statement",
+            "level": "warning",
+            "code": { "code": "clippy::needless_return" },
+            "spans": [{ "file_name": "src/lib.rs", "line_start": 4, "column_start": 5, "is_primary": true }],
+            "rendered": "",
+        }
+    })
+    .to_string();
+    let summary = r#"{"reason":"compiler-message","message":{"message":"1 warning emitted","level":"warning","code":null,"spans":[]}}"#;
+    let output = [
+        lint.as_str(),
+        lint.as_str(),
+        summary,
+        "    Checking demo v0.1.0",
+    ]
+    .join("\n");
+    let findings = parse_clippy_messages(&output, Path::new("/repo"));
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].file, "/repo/src/lib.rs");
+    assert_eq!(findings[0].code, "clippy::needless_return");
+}
+
+#[test]
+fn findings_become_failed_cases_named_after_the_tool() {
+    let output = r#"[{"filePath": "/repo/src/a.ts", "messages": [
+        {"ruleId": "no-console", "severity": 2, "message": "Unexpected console statement.", "line": 2, "column": 1}
+    ]}]"#;
+    let findings = parse_eslint_json(output, Path::new("/repo")).unwrap();
+    let model = build_findings_model("eslint", &findings);
+
+    assert_eq!(model.aggregated.num_failed_test_suites, 1);
+    assert_eq!(model.aggregated.num_failed_tests, 1);
+    let case = &model.test_results[0].test_results[0];
+    assert_eq!(case.full_name, "eslint no-console (2:1)");
+    assert_eq!(
+        case.failure_messages[0],
+        "Unexpected console statement.\n    at /repo/src/a.ts:2:1"
+    );
+}
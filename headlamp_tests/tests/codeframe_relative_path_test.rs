@@ -67,6 +67,9 @@ fn renders_code_frame_when_stack_location_is_relative_to_repo_root() {
                 location: None,
                 failure_messages: vec![failure_message],
                 failure_details: None,
+                repro: None,
+                skip_reason: None,
+                attempts: None,
             }],
         }],
         aggregated: TestRunAggregated {
@@ -84,6 +87,7 @@ fn renders_code_frame_when_stack_location_is_relative_to_repo_root() {
             success: false,
             run_time_ms: Some(1),
         },
+        skipped_units: vec![],
     };
 
     let ctx = make_ctx(repo_root.as_path(), Some(120), true, false, None);
@@ -205,6 +205,45 @@ export default defineConfig({
     }
 }
 
+#[test]
+fn config_loads_ts_templates_ternaries_env_reads_and_json_parse() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("headlamp.config.ts");
+    write_file(
+        &path,
+        r#"
+const base = ['--no-watchman'];
+const task = process.env.HEADLAMP_TEST_NEVER_SET ?? 'jest';
+
+export default defineConfig({
+  bootstrapCommand: `test:${task}:bootstrap`,
+  sequential: process.env.PATH ? true : false,
+  quiet: process.env['HEADLAMP_TEST_NEVER_SET'] === 'yes',
+  jestArgs: [...base, ...JSON.parse('["--ci"]')],
+  coverage: JSON.parse(`{"abortOnFailure": ${!false}}`),
+});
+"#,
+    );
+
+    let cfg = load_headlamp_config_from_path(&path).unwrap();
+    assert_eq!(
+        cfg.bootstrap_command.as_deref(),
+        Some("test:jest:bootstrap")
+    );
+    assert_eq!(cfg.sequential, Some(true));
+    assert_eq!(cfg.quiet, Some(false));
+    assert_eq!(
+        cfg.jest_args.clone().unwrap_or_default(),
+        vec!["--no-watchman".to_string(), "--ci".to_string()]
+    );
+    match cfg.coverage {
+        Some(headlamp::config::CoverageConfig::Obj(section)) => {
+            assert_eq!(section.abort_on_failure, Some(true));
+        }
+        other => panic!("expected coverage obj, got {other:?}"),
+    }
+}
+
 #[test]
 fn config_load_ts_rejects_dynamic_expressions_with_clear_error() {
     let temp = TempDir::new().unwrap();
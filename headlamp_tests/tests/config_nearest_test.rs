@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use serde_json::json;
+use tempfile::TempDir;
+
+use headlamp::config::CoverageConfig;
+use headlamp::config::nearest::{merge_config_values, nested_config_paths, selection_anchor};
+use headlamp::config::validate::load_headlamp_config_for;
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+}
+
+fn monorepo() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("headlamp.config.json"),
+        r#"{ "sequential": true, "coverage": { "pageFit": true, "thresholds": { "lines": 60, "branches": 50 } } }"#,
+    );
+    write_file(
+        &root.join("packages/core/headlamp.config.json"),
+        r#"{ "coverage": { "thresholds": { "lines": 90 } } }"#,
+    );
+    write_file(&root.join("packages/core/src/a.test.ts"), "");
+    write_file(&root.join("packages/core/src/b.test.ts"), "");
+    write_file(&root.join("packages/web/src/c.test.ts"), "");
+    temp
+}
+
+#[test]
+fn selection_anchor_is_the_common_directory_of_existing_selection_paths() {
+    let temp = monorepo();
+    let root = temp.path();
+    let selection = [
+        "packages/core/src/a.test.ts".to_string(),
+        "packages/core/src/b.test.ts".to_string(),
+        "someTestName".to_string(),
+    ];
+    assert_eq!(
+        selection_anchor(root, &selection),
+        root.join("packages/core/src")
+    );
+    let spread = [
+        "packages/core/src/a.test.ts".to_string(),
+        "packages/web".to_string(),
+    ];
+    assert_eq!(selection_anchor(root, &spread), root.join("packages"));
+    assert_eq!(selection_anchor(root, &[]), root);
+}
+
+#[test]
+fn nested_config_paths_skip_the_root_and_order_outermost_first() {
+    let temp = monorepo();
+    let root = temp.path();
+    write_file(&root.join("packages/headlamp.config.json"), "{}");
+    let paths = nested_config_paths(root, &root.join("packages/core/src"));
+    assert_eq!(
+        paths,
+        vec![
+            root.join("packages/headlamp.config.json"),
+            root.join("packages/core/headlamp.config.json"),
+        ]
+    );
+    assert!(nested_config_paths(root, root).is_empty());
+}
+
+#[test]
+fn merge_config_values_merges_objects_and_replaces_everything_else() {
+    let mut base = json!({ "jestArgs": ["--a"], "coverage": { "pageFit": true, "mode": "full" } });
+    merge_config_values(
+        &mut base,
+        json!({ "jestArgs": ["--b"], "coverage": { "mode": "compact" } }),
+    );
+    assert_eq!(
+        base,
+        json!({ "jestArgs": ["--b"], "coverage": { "pageFit": true, "mode": "compact" } })
+    );
+}
+
+#[test]
+fn nearest_config_overrides_the_root_config_under_its_directory() {
+    let temp = monorepo();
+    let root = temp.path();
+    let thresholds = |anchor: &Path| match load_headlamp_config_for(root, anchor).coverage {
+        Some(CoverageConfig::Obj(section)) => section.thresholds.unwrap(),
+        other => panic!("expected coverage obj, got {other:?}"),
+    };
+    let core = thresholds(&root.join("packages/core/src"));
+    assert_eq!((core.lines, core.branches), (Some(90.0), Some(50.0)));
+    let web = thresholds(&root.join("packages/web"));
+    assert_eq!((web.lines, web.branches), (Some(60.0), Some(50.0)));
+    assert_eq!(
+        load_headlamp_config_for(root, &root.join("packages/core")).sequential,
+        Some(true)
+    );
+}
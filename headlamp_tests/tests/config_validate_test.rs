@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use serde_json::json;
+use tempfile::TempDir;
+
+use headlamp::config::schema::{did_you_mean, locate_line, validate_config_value};
+use headlamp::config::validate::{render_issue, validate_config_file};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn unknown_keys_suggest_the_closest_known_key() {
+    let (issues, cleaned) = validate_config_value(&json!({ "showLog": true, "sequential": true }));
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "showLog");
+    assert!(issues[0].message.contains("did you mean `showLogs`?"));
+    assert_eq!(cleaned, json!({ "sequential": true }));
+}
+
+#[test]
+fn type_mismatches_name_the_nested_path_and_are_dropped() {
+    let value = json!({ "coverage": { "thresholds": { "lines": "80", "branches": 70 } } });
+    let (issues, cleaned) = validate_config_value(&value);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "coverage.thresholds.lines");
+    assert!(issues[0].message.contains("found the string \"80\""));
+    assert_eq!(
+        cleaned,
+        json!({ "coverage": { "thresholds": { "branches": 70 } } })
+    );
+}
+
+#[test]
+fn misspelled_options_suggest_the_closest_option() {
+    let (issues, _) = validate_config_value(&json!({ "checks": [{ "format": "eslnt" }] }));
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "checks[0].format");
+    assert!(issues[0].message.ends_with("did you mean \"eslint\"?"));
+}
+
+#[test]
+fn did_you_mean_ignores_case_and_separators_but_not_unrelated_keys() {
+    let keys = ["bootstrapCommand", "sequential"];
+    assert_eq!(
+        did_you_mean("bootstrap_comand", keys.into_iter()),
+        Some("bootstrapCommand")
+    );
+    assert_eq!(did_you_mean("watch", keys.into_iter()), None);
+}
+
+#[test]
+fn locate_line_finds_snake_case_keys_in_toml_tables() {
+    let source =
+        "[tool.headlamp]\nsequential = true\n\n[tool.headlamp.coverage]\nabort_on_failure = 1\n";
+    assert_eq!(locate_line(source, "coverage.abortOnFailure"), Some(5));
+}
+
+#[test]
+fn validate_config_file_reports_lines_from_the_source() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("headlamp.config.json");
+    write_file(
+        &path,
+        "{\n  \"sequential\": true,\n  \"coverage\": {\n    \"thresholds\": { \"lines\": \"80\" }\n  }\n}\n",
+    );
+    let (issues, _) = validate_config_file(&path).unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(4));
+    assert!(
+        render_issue("headlamp.config.json", &issues[0])
+            .starts_with("headlamp.config.json:4: coverage.thresholds.lines: ")
+    );
+}
+
+#[test]
+fn presets_must_map_names_to_lists_of_flags() {
+    let value = json!({ "presets": { "ci": ["--ci"], "fast": "--quiet" } });
+    let (issues, cleaned) = validate_config_value(&value);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "presets.fast");
+    assert_eq!(cleaned, json!({ "presets": { "ci": ["--ci"] } }));
+}
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use headlamp::args::derive_args;
+use headlamp::coverage::cache::{applies, cache_key, load, store};
+use headlamp::coverage::model::{CoverageReport, FileCoverage};
+use headlamp::runner::Runner;
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+fn report() -> CoverageReport {
+    CoverageReport {
+        files: vec![FileCoverage {
+            path: "src/lib.rs".to_string(),
+            lines_total: 2,
+            lines_covered: 1,
+            statements_total: None,
+            statements_covered: None,
+            statement_hits: None,
+            uncovered_lines: vec![2],
+            line_hits: BTreeMap::from([(1, 3), (2, 0)]),
+            function_hits: BTreeMap::new(),
+            function_map: BTreeMap::new(),
+            branch_hits: BTreeMap::new(),
+            branch_map: BTreeMap::new(),
+        }],
+    }
+}
+
+#[test]
+fn only_coverage_runs_that_print_headlamps_table_are_cached() {
+    assert!(applies(&parse(&["--coverage"])));
+    assert!(!applies(&parse(&[])));
+    assert!(!applies(&parse(&["--coverage", "--no-cache"])));
+    assert!(!applies(&parse(&["--coverage", "--coverage-ui=jest"])));
+}
+
+#[test]
+fn the_key_follows_sources_and_selection_but_not_coverage_output() {
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(repo.path().join("src")).unwrap();
+    std::fs::write(repo.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+    let args = parse(&["--coverage"]);
+    let key = cache_key(Runner::CargoTest, repo.path(), &args);
+
+    std::fs::write(repo.path().join(".coverage"), "sqlite").unwrap();
+    std::fs::create_dir_all(repo.path().join("coverage")).unwrap();
+    std::fs::write(repo.path().join("coverage/lcov.info"), "SF:x\n").unwrap();
+    assert_eq!(cache_key(Runner::CargoTest, repo.path(), &args), key);
+
+    let output_only = parse(&["--coverage", "--summary-line", "--log-level=debug"]);
+    assert_eq!(cache_key(Runner::CargoTest, repo.path(), &output_only), key);
+
+    let selected = parse(&["--coverage", "tests/api.rs"]);
+    assert_ne!(cache_key(Runner::CargoTest, repo.path(), &selected), key);
+    assert_ne!(cache_key(Runner::CargoNextest, repo.path(), &args), key);
+
+    std::fs::write(repo.path().join("src/lib.rs"), "fn a() { b() }\n").unwrap();
+    assert_ne!(cache_key(Runner::CargoTest, repo.path(), &args), key);
+}
+
+#[test]
+fn a_stored_report_is_only_loaded_for_the_same_key() {
+    let cache = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    let (cache, repo) = (cache.path(), repo.path());
+    store(cache, repo, Runner::Jest, "abc", report());
+    assert_eq!(load(cache, repo, Runner::Jest, "abc"), Some(report()));
+    assert_eq!(load(cache, repo, Runner::Jest, "def"), None);
+    assert_eq!(load(cache, repo, Runner::Pytest, "abc"), None);
+    let other_cache = tempfile::tempdir().unwrap();
+    assert_eq!(load(other_cache.path(), repo, Runner::Jest, "abc"), None);
+}
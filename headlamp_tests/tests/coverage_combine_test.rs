@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use headlamp::coverage::combine::{combine_reports, resolve_relative_sources};
+use headlamp::coverage::lcov::parse_lcov_text;
+use headlamp::coverage::thresholds::compute_totals_from_report;
+use path_slash::PathExt;
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn combines_frontend_and_backend_lcov_under_repo_paths() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo_root = temp.path();
+    write_file(&repo_root.join("frontend/src/app.ts"), "");
+    write_file(&repo_root.join("backend/src/lib.rs"), "");
+    let lib_abs = repo_root
+        .join("backend/src/lib.rs")
+        .to_slash_lossy()
+        .to_string();
+
+    let frontend = resolve_relative_sources(
+        parse_lcov_text("TN:\nSF:src/app.ts\nDA:1,1\nDA:2,0\nend_of_record\n"),
+        &repo_root.join("frontend"),
+    );
+    let backend = parse_lcov_text(&format!(
+        "TN:\nSF:{lib_abs}\nDA:1,1\nDA:2,1\nDA:3,1\nDA:4,0\nend_of_record\n"
+    ));
+
+    let combined = combine_reports(repo_root, &[frontend, backend], &[], &[]);
+    let paths = combined
+        .files
+        .iter()
+        .map(|f| {
+            Path::new(&f.path)
+                .strip_prefix(repo_root)
+                .unwrap()
+                .to_slash_lossy()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["backend/src/lib.rs", "frontend/src/app.ts"]);
+    let totals = combined.totals();
+    assert_eq!((totals.lines_covered, totals.lines_total), (4, 6));
+    assert_eq!(compute_totals_from_report(&combined).lines.total, 6);
+}
+
+#[test]
+fn same_file_from_two_runs_sums_hits() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo_root = temp.path();
+    write_file(&repo_root.join("src/a.py"), "");
+    let a_abs = repo_root.join("src/a.py").to_slash_lossy().to_string();
+    let first = parse_lcov_text(&format!("TN:\nSF:{a_abs}\nDA:1,1\nDA:2,0\nend_of_record\n"));
+    let second = parse_lcov_text(&format!("TN:\nSF:{a_abs}\nDA:1,0\nDA:2,3\nend_of_record\n"));
+
+    let combined = combine_reports(repo_root, &[first, second], &[], &[]);
+    assert_eq!(combined.files.len(), 1);
+    assert_eq!(combined.files[0].lines_covered, 2);
+}
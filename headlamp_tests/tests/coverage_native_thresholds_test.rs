@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::{CoverageThresholds, HeadlampConfig};
+use headlamp::coverage::native_thresholds::{native_thresholds, with_native_thresholds};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn native_thresholds_come_from_jest_coveragepy_and_cargo_configs() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = repo.path();
+    assert_eq!(native_thresholds(root), None);
+
+    write(
+        root,
+        "package.json",
+        r#"{ "jest": { "coverageThreshold": { "global": { "branches": 70, "lines": 80, "functions": -10 } } } }"#,
+    );
+    write(
+        root,
+        "pyproject.toml",
+        "[tool.coverage.report]\nfail_under = 85\n",
+    );
+    write(
+        root,
+        "Cargo.toml",
+        "[package]\nname = \"x\"\n\n[package.metadata.headlamp.coverage.thresholds]\nlines = 90\nbranches = 60\n",
+    );
+
+    assert_eq!(
+        native_thresholds(root),
+        Some(CoverageThresholds {
+            lines: Some(90.0),
+            functions: None,
+            branches: Some(70.0),
+            statements: Some(85.0),
+        })
+    );
+}
+
+#[test]
+fn own_thresholds_win_and_native_ones_fill_the_rest_only_under_the_flag() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = repo.path();
+    write(
+        root,
+        "jest.config.js",
+        "module.exports = { coverageThreshold: { global: { lines: 80, statements: 75 } } };\n",
+    );
+    let argv = vec![
+        "--coverage".to_string(),
+        "--coverage-thresholds-lines=50".to_string(),
+    ];
+
+    let without_flag = derive_args(&[], &argv, false);
+    assert_eq!(
+        with_native_thresholds(root, &without_flag).coverage_thresholds,
+        without_flag.coverage_thresholds
+    );
+
+    let argv = [argv, vec!["--coverage-native-thresholds".to_string()]].concat();
+    let merged = with_native_thresholds(root, &derive_args(&[], &argv, false));
+    assert_eq!(
+        merged.coverage_thresholds,
+        Some(CoverageThresholds {
+            lines: Some(50.0),
+            functions: None,
+            branches: None,
+            statements: Some(75.0),
+        })
+    );
+}
+
+#[test]
+fn native_thresholds_flag_comes_from_the_coverage_config_section() {
+    let cfg: HeadlampConfig =
+        serde_json::from_str(r#"{ "coverage": { "nativeThresholds": true } }"#).unwrap();
+    let argv = vec!["--coverage".to_string()];
+    assert!(derive_args(&config_tokens(&cfg, &argv), &argv, false).coverage_native_thresholds);
+    assert!(!derive_args(&[], &argv, false).coverage_native_thresholds);
+}
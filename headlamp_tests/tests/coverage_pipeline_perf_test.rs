@@ -106,6 +106,7 @@ fn default_print_opts_for_perf() -> PrintOpts {
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     }
 }
 
@@ -83,6 +83,7 @@ fn coverage_pretty_demangles_rust_function_symbols_in_functions_rows() {
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     };
 
     let pretty = format_istanbul_pretty_from_lcov_report(
@@ -123,6 +124,7 @@ fn coverage_pretty_from_lcov_large_report_completes_under_one_second() {
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     };
 
     let mut best = Duration::MAX;
@@ -167,6 +169,7 @@ fn coverage_pretty_runtime_scales_approximately_linearly_in_file_count() {
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     };
 
     let small_report = mk_large_report_at_path(&repo_root, 500);
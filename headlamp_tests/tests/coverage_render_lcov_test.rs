@@ -47,6 +47,7 @@ end_of_record
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     };
 
     let without_hotspots = render_report_text(&report, &opts, repo_root, false);
@@ -60,3 +61,42 @@ end_of_record
     assert!(with_hotspots.contains("10"));
     assert!(with_hotspots.contains("11"));
 }
+
+#[test]
+fn renders_branch_column_summary_and_uncovered_branch_locations() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo_root = temp.path();
+
+    write_file(&repo_root.join("src/a.ts"), "");
+    let a_abs = repo_root.join("src/a.ts").to_slash_lossy().to_string();
+    let lcov_text = format!(
+        r#"TN:
+SF:{a_abs}
+DA:3,1
+DA:4,1
+BRDA:3,0,0,1
+BRDA:3,0,1,0
+BRDA:4,1,0,2
+BRDA:4,1,1,1
+end_of_record
+"#
+    );
+    write_file(&repo_root.join("coverage/lcov.info"), &lcov_text);
+
+    let report = read_repo_lcov_filtered(repo_root, &["**/*.ts".to_string()], &[]).unwrap();
+    let opts = PrintOpts {
+        max_files: None,
+        max_hotspots: Some(5),
+        page_fit: true,
+        tty: false,
+        editor_cmd: None,
+        path_display: Default::default(),
+    };
+
+    let text = render_report_text(&report, &opts, repo_root, true);
+    assert!(text.contains("%Branch"), "{text}");
+    assert!(text.contains("75.0%"), "{text}");
+    assert!(text.contains("Branches: 75.0% (3/4)"), "{text}");
+    assert!(text.contains("uncovered branches: src/a.ts:3"), "{text}");
+    assert!(!text.contains("src/a.ts:4"), "{text}");
+}
@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::args::derive_args;
+use headlamp::coverage::model::{CoverageReport, FileCoverage};
+use headlamp::coverage::scope::{coverage_scope, restrict_report, scoped_args};
+
+fn monorepo() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    let write = |rel: &str, text: &str| {
+        let path = root.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    };
+    write("package.json", r#"{"name": "root"}"#);
+    write("packages/api/package.json", r#"{"name": "api"}"#);
+    write("packages/api/src/a.test.ts", "");
+    write("packages/web/package.json", r#"{"name": "web"}"#);
+    write("packages/web/src/b.ts", "");
+    write("py/svc/pyproject.toml", "[project]\nname = \"svc\"\n");
+    write("py/svc/svc/c.py", "");
+    write("scripts/d.ts", "");
+    root
+}
+
+fn file(path: &Path) -> FileCoverage {
+    FileCoverage {
+        path: path.to_string_lossy().to_string(),
+        lines_total: 1,
+        lines_covered: 1,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines: vec![],
+        line_hits: Default::default(),
+        function_hits: Default::default(),
+        function_map: Default::default(),
+        branch_hits: Default::default(),
+        branch_map: Default::default(),
+    }
+}
+
+#[test]
+fn scope_is_the_packages_owning_the_selected_files() {
+    let root = monorepo();
+    let files = [
+        "packages/web/src/b.ts",
+        "packages/api/src/a.test.ts",
+        "py/svc/svc/c.py",
+        "packages/web",
+    ]
+    .map(|rel| root.path().join(rel));
+    assert_eq!(
+        coverage_scope(root.path(), &files),
+        vec!["packages/api", "packages/web", "py/svc"]
+    );
+}
+
+#[test]
+fn a_file_in_the_root_package_leaves_coverage_unscoped() {
+    let root = monorepo();
+    let files: Vec<PathBuf> = vec![
+        root.path().join("packages/api/src/a.test.ts"),
+        root.path().join("scripts/d.ts"),
+    ];
+    assert!(coverage_scope(root.path(), &files).is_empty());
+}
+
+#[test]
+fn scoped_args_follow_explicit_selection_only_with_coverage() {
+    let root = monorepo();
+    let argv = ["--coverage", "packages/api/src/a.test.ts"].map(String::from);
+    let args = derive_args(&[], &argv, false);
+    assert_eq!(
+        scoped_args(root.path(), &args).coverage_scope,
+        vec!["packages/api"]
+    );
+    let argv = ["packages/api/src/a.test.ts".to_string()];
+    let args = derive_args(&[], &argv, false);
+    assert!(scoped_args(root.path(), &args).coverage_scope.is_empty());
+}
+
+#[test]
+fn restrict_report_keeps_only_files_in_scope() {
+    let root = monorepo();
+    let report = CoverageReport {
+        files: vec![
+            file(&root.path().join("packages/api/src/a.ts")),
+            file(Path::new("packages/web/src/b.ts")),
+            file(&root.path().join("packages/apiary/x.ts")),
+        ],
+    };
+    let kept = restrict_report(report.clone(), root.path(), &["packages/api".to_string()]);
+    assert_eq!(kept.files.len(), 1);
+    assert!(kept.files[0].path.ends_with("packages/api/src/a.ts"));
+    assert_eq!(restrict_report(report.clone(), root.path(), &[]), report);
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use headlamp::coverage::last_run::report_to_lcov;
+use headlamp::coverage::lcov::parse_lcov_text;
+use headlamp::coverage::print::PrintOpts;
+use headlamp::coverage::show::{render_file_show, uncovered_ranges};
+use path_slash::PathExt;
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+}
+
+fn plain_opts(editor_cmd: Option<&str>) -> PrintOpts {
+    PrintOpts {
+        max_files: None,
+        max_hotspots: None,
+        page_fit: false,
+        tty: false,
+        editor_cmd: editor_cmd.map(str::to_string),
+        path_display: Default::default(),
+    }
+}
+
+#[test]
+fn uncovered_ranges_collapse_consecutive_lines() {
+    assert_eq!(
+        uncovered_ranges(&[2, 3, 4, 7, 9, 10]),
+        vec![(2, 4), (7, 7), (9, 10)]
+    );
+}
+
+#[test]
+fn cached_lcov_round_trips_line_and_branch_hits() {
+    let report = parse_lcov_text(
+        "TN:\nSF:/repo/src/a.ts\nDA:1,2\nDA:2,0\nBRDA:1,0,0,1\nBRDA:1,0,1,0\nend_of_record\n",
+    );
+    assert_eq!(parse_lcov_text(&report_to_lcov(&report)), report);
+}
+
+#[test]
+fn show_annotates_source_with_hits_and_links_uncovered_ranges() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let repo_root = temp.path();
+    write_file(
+        &repo_root.join("src/a.ts"),
+        "const a = 1;\nif (a) {\n  b();\n  c();\n}\n",
+    );
+    let a_abs = repo_root.join("src/a.ts").to_slash_lossy().to_string();
+    let report = parse_lcov_text(&format!(
+        "TN:\nSF:{a_abs}\nDA:1,3\nDA:2,3\nDA:3,0\nDA:4,0\nend_of_record\n"
+    ));
+
+    let text = render_file_show(&report, "src/a.ts", repo_root, &plain_opts(None)).unwrap();
+    assert!(text.contains("Lines: 50.0% (2/4)"), "{text}");
+    assert!(text.contains("Uncovered: 3-4"), "{text}");
+    assert!(text.contains("1     3x | const a = 1;"), "{text}");
+    assert!(text.contains("3     0x |   b();  <- uncovered"), "{text}");
+    assert!(text.contains("5        | }"), "{text}");
+
+    let linked = render_file_show(
+        &report,
+        "a.ts",
+        repo_root,
+        &plain_opts(Some("vscode://file/{file}:{line}")),
+    )
+    .unwrap();
+    assert!(
+        linked.contains(&format!("Uncovered: 3<vscode://file/{a_abs}:3>-4")),
+        "{linked}"
+    );
+
+    let missing = render_file_show(&report, "src/b.ts", repo_root, &plain_opts(None));
+    assert!(
+        missing
+            .unwrap_err()
+            .contains("not in the last run's coverage")
+    );
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use headlamp::coverage::history::{
+    CoverageSummary, PackageTotals, load_history, record_summary, summarize,
+};
+use headlamp::coverage::model::{CoverageReport, FileCoverage};
+use headlamp::coverage::trend::{
+    TrendOptions, export_trend, parse_duration_seconds, parse_trend_options, render_trend,
+    sparkline,
+};
+use headlamp::format::stacks::strip_ansi_simple;
+
+fn totals(lines_covered: u32, branches_covered: u32) -> PackageTotals {
+    PackageTotals {
+        lines_covered,
+        lines_total: 100,
+        branches_covered,
+        branches_total: 10,
+    }
+}
+
+fn summary(timestamp: u64, packages: &[(&str, PackageTotals)]) -> CoverageSummary {
+    CoverageSummary {
+        timestamp,
+        source: "jest".to_string(),
+        commit: Some("abc1234".to_string()),
+        packages: packages
+            .iter()
+            .map(|(name, totals)| (name.to_string(), totals.clone()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+fn file(path: &str, lines_covered: u32) -> FileCoverage {
+    FileCoverage {
+        path: path.to_string(),
+        lines_total: 10,
+        lines_covered,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines: vec![],
+        line_hits: Default::default(),
+        function_hits: Default::default(),
+        function_map: Default::default(),
+        branch_hits: BTreeMap::from([("0".to_string(), vec![1, 0])]),
+        branch_map: Default::default(),
+    }
+}
+
+#[test]
+fn trend_options_parse_since_and_export() {
+    let tokens = ["--since", "30d", "--export=trend.csv"].map(String::from);
+    assert_eq!(
+        parse_trend_options(&tokens),
+        Ok(TrendOptions {
+            since_seconds: Some(30 * 86_400),
+            export: Some("trend.csv".to_string()),
+        })
+    );
+    assert_eq!(parse_duration_seconds("2w"), Some(14 * 86_400));
+    assert_eq!(parse_duration_seconds("12h"), Some(12 * 3_600));
+    assert_eq!(parse_duration_seconds("7"), Some(7 * 86_400));
+    assert_eq!(parse_duration_seconds("soon"), None);
+    assert!(parse_trend_options(&["--bogus".to_string()]).is_err());
+}
+
+#[test]
+fn sparkline_scales_between_the_series_low_and_high() {
+    assert_eq!(sparkline(&[70.0, 75.0, 80.0]), "▁▅█");
+    assert_eq!(sparkline(&[50.0, 50.0]), "▅▅");
+}
+
+#[test]
+fn trend_renders_one_row_per_package_with_first_and_last_percentages() {
+    let summaries = [
+        summary(1, &[("packages/api", totals(70, 5)), (".", totals(40, 0))]),
+        summary(2, &[("packages/api", totals(75, 6))]),
+        summary(3, &[("packages/api", totals(80, 4))]),
+    ];
+    let rendered = strip_ansi_simple(&render_trend(&summaries));
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Coverage trend: 3 runs");
+    assert_eq!(lines[1], ".             Lines ▅ 40.0%  Branches ▅ 0.0%");
+    assert_eq!(
+        lines[2],
+        "packages/api  Lines ▁▅█ 70.0% → 80.0% (+10.0)  Branches ▅█▁ 50.0% → 40.0% (-10.0)"
+    );
+}
+
+#[test]
+fn trend_exports_one_csv_row_per_package_per_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("trend.csv");
+    let summaries = [summary(
+        1,
+        &[("packages/api", totals(70, 5)), ("web", totals(1, 0))],
+    )];
+    assert_eq!(export_trend(&path, &summaries), 0);
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "timestamp,source,commit,package,lines_covered,lines_total,branches_covered,branches_total\n\
+         1,jest,abc1234,packages/api,70,100,5,10\n\
+         1,jest,abc1234,web,1,100,0,10\n"
+    );
+    let json_path = dir.path().join("trend.json");
+    assert_eq!(export_trend(&json_path, &summaries), 0);
+    let rows: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    assert_eq!(rows[1]["package"], "web");
+}
+
+#[test]
+fn summaries_group_files_by_package_and_round_trip_through_the_history() {
+    let cache_root = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(repo.path().join("pkg/src")).unwrap();
+    std::fs::write(repo.path().join("pkg/Cargo.toml"), "[package]\n").unwrap();
+    let report = CoverageReport {
+        files: vec![
+            file(&repo.path().join("pkg/src/a.rs").to_string_lossy(), 5),
+            file(&repo.path().join("pkg/src/b.rs").to_string_lossy(), 7),
+            file(&repo.path().join("main.rs").to_string_lossy(), 1),
+        ],
+    };
+    let summary = summarize(repo.path(), "rust", &report, 42);
+    assert_eq!(
+        summary.packages["pkg"],
+        PackageTotals {
+            lines_covered: 12,
+            lines_total: 20,
+            branches_covered: 2,
+            branches_total: 4,
+        }
+    );
+    assert_eq!(summary.packages["."].lines_covered, 1);
+    record_summary(cache_root.path(), repo.path(), &summary);
+    record_summary(cache_root.path(), repo.path(), &summary);
+    assert_eq!(
+        load_history(cache_root.path(), repo.path()),
+        vec![summary.clone(), summary]
+    );
+}
@@ -0,0 +1,73 @@
+use headlamp::coverage::istanbul_pretty::format_istanbul_text_from_report;
+use headlamp::coverage::model::{CoverageReport, FileCoverage};
+use headlamp::coverage::print::PrintOpts;
+use headlamp::format::path_display::PathDisplay;
+use headlamp::format::stacks::strip_ansi_simple;
+
+fn file(path: String, line_hits: &[(u32, u32)]) -> FileCoverage {
+    FileCoverage {
+        path,
+        lines_total: line_hits.len() as u32,
+        lines_covered: line_hits.iter().filter(|(_, hits)| *hits > 0).count() as u32,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines: vec![],
+        line_hits: line_hits.iter().copied().collect(),
+        function_hits: Default::default(),
+        function_map: Default::default(),
+        branch_hits: Default::default(),
+        branch_map: Default::default(),
+    }
+}
+
+fn opts() -> PrintOpts {
+    PrintOpts {
+        max_files: None,
+        max_hotspots: None,
+        page_fit: false,
+        tty: false,
+        editor_cmd: None,
+        path_display: PathDisplay::default(),
+    }
+}
+
+#[test]
+fn jest_coverage_ui_renders_istanbul_text_and_text_summary() {
+    let repo = tempfile::tempdir().unwrap();
+    let report = CoverageReport {
+        files: vec![
+            file(
+                repo.path().join("src/lib.rs").to_string_lossy().to_string(),
+                &[(1, 1), (2, 0), (3, 1), (4, 1)],
+            ),
+            file("/elsewhere/registry/dep.rs".to_string(), &[(1, 0)]),
+        ],
+    };
+
+    let out = strip_ansi_simple(&format_istanbul_text_from_report(
+        repo.path(),
+        report,
+        &opts(),
+    ));
+
+    let header = out
+        .lines()
+        .find(|line| line.contains("% Stmts"))
+        .expect("istanbul text header");
+    assert!(header.contains("% Lines") && header.contains("Uncovered Line #s"));
+    assert!(out.lines().any(|line| line.contains("All files")));
+    assert!(
+        out.lines()
+            .any(|line| line.contains("lib.rs") && line.contains("75") && line.contains('2')),
+        "{out}"
+    );
+    assert!(!out.contains("dep.rs"), "{out}");
+    assert!(out.contains("Coverage summary"));
+    assert!(
+        out.lines()
+            .any(|line| line.starts_with("Lines") && line.ends_with("75% ( 3/4 )")),
+        "{out}"
+    );
+    assert!(!out.contains("─"), "no headlamp pretty tables: {out}");
+}
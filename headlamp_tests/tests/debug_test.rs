@@ -0,0 +1,62 @@
+use std::process::Command;
+
+use headlamp::api::{ApiError, RunRequest, run};
+use headlamp::args::derive_args;
+use headlamp::debug::{Debugger, NODE_INSPECT_ADDRESS, display_command, node_options_with_inspect};
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+#[test]
+fn debug_takes_an_optional_debugger_and_runs_sequentially() {
+    let bare = parse(&["--debug"]);
+    assert_eq!(bare.debug, Some(Debugger::Auto));
+    assert!(bare.sequential);
+    assert_eq!(parse(&["--debug=gdb"]).debug, Some(Debugger::Gdb));
+    assert_eq!(parse(&["--debug=lldb"]).debug, Some(Debugger::Lldb));
+    assert_eq!(parse(&["--debug=pdb"]).debug, Some(Debugger::Pdb));
+    let off = parse(&["--debug=false"]);
+    assert_eq!(off.debug, None);
+    assert!(!off.sequential);
+}
+
+#[test]
+fn inspect_is_an_alias_that_never_takes_the_next_token() {
+    let parsed = parse(&["--inspect", "src/a.test.ts"]);
+    assert_eq!(parsed.debug, Some(Debugger::Auto));
+    assert_eq!(parsed.selection_paths, vec!["src/a.test.ts".to_string()]);
+}
+
+#[test]
+fn node_options_keep_the_users_flags() {
+    let inspect = format!("--inspect-brk={NODE_INSPECT_ADDRESS}");
+    assert_eq!(node_options_with_inspect(None), inspect);
+    assert_eq!(node_options_with_inspect(Some("  ")), inspect);
+    assert_eq!(
+        node_options_with_inspect(Some("--max-old-space-size=4096")),
+        format!("--max-old-space-size=4096 {inspect}")
+    );
+}
+
+#[test]
+fn debug_commands_print_as_pasteable_shell_lines() {
+    let mut command = Command::new("target/debug/deps/api-1234");
+    command.args(["--test-threads", "1", "tests::it works"]);
+    assert_eq!(
+        display_command(&command),
+        "target/debug/deps/api-1234 --test-threads 1 'tests::it works'"
+    );
+}
+
+#[test]
+fn debug_is_only_supported_by_the_cli() {
+    let repo_root = std::env::temp_dir().join("headlamp-debug-tests");
+    std::fs::create_dir_all(&repo_root).unwrap();
+    let err = run(&RunRequest::new(&repo_root).args(["--debug"])).unwrap_err();
+    assert!(
+        matches!(&err, ApiError::InvalidArgs(message) if message.contains("--debug")),
+        "{err}"
+    );
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::deterministic;
+use headlamp::format::time::format_duration;
+
+#[test]
+fn deterministic_flag_comes_from_cli_and_config_and_implies_quiet() {
+    let argv = vec!["--deterministic".to_string()];
+    let parsed = derive_args(&[], &argv, true);
+    assert!(parsed.deterministic);
+    assert!(parsed.quiet);
+
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "deterministic": true }"#).unwrap();
+    let parsed = derive_args(&config_tokens(&cfg, &[]), &[], true);
+    assert!(parsed.deterministic);
+
+    let parsed = derive_args(&[], &[], true);
+    assert!(!parsed.deterministic);
+    assert!(!parsed.quiet);
+}
+
+#[test]
+fn deterministic_mode_replaces_times_and_relativizes_paths() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(tmp.path()).unwrap();
+    let root_text = root.to_string_lossy().replace('\\', "/");
+
+    deterministic::configure(true, &root);
+    assert_eq!(format_duration(Duration::from_millis(1234)), "<time>");
+    let rendered = deterministic::finalize(format!(
+        "RUN {root_text}\n FAIL {root_text}/src/a.test.ts\nTime 1.2s"
+    ));
+    assert_eq!(rendered, "RUN .\n FAIL src/a.test.ts\nTime 1.2s");
+
+    deterministic::configure(false, &root);
+    assert_eq!(format_duration(Duration::from_millis(1234)), "1s 234ms");
+    let untouched = format!("RUN {root_text}");
+    assert_eq!(deterministic::finalize(untouched.clone()), untouched);
+}
@@ -0,0 +1,72 @@
+use headlamp::args::derive_args;
+use headlamp::duplicates::{
+    Duplicate, duplicate_suite_paths, duplicate_test_names, report_duplicates,
+};
+use headlamp::recorded::RecordedResults;
+use headlamp::test_model::TestSuiteResult;
+use headlamp::test_support::model::{self, case, run_model};
+
+fn suite(path: &str, names: &[&str]) -> TestSuiteResult {
+    model::suite(
+        path,
+        names.iter().map(|name| case(name, "passed")).collect(),
+    )
+}
+
+#[test]
+fn repeated_full_names_within_a_suite_are_duplicates() {
+    let run = run_model(vec![
+        suite("/repo/a.test.ts", &["adds", "adds", "adds", "subtracts"]),
+        suite("/repo/b.test.ts", &["adds"]),
+    ]);
+    let found = duplicate_test_names(&run);
+    assert_eq!(
+        found,
+        vec![Duplicate::TestName {
+            suite_path: "/repo/a.test.ts".to_string(),
+            full_name: "adds".to_string(),
+            count: 3,
+        }]
+    );
+    assert_eq!(
+        found[0].to_string(),
+        "3 tests named \"adds\" in /repo/a.test.ts"
+    );
+}
+
+#[test]
+fn suite_paths_reported_by_several_projects_are_duplicates() {
+    let projects = [
+        run_model(vec![suite("/repo/shared.test.ts", &["a"])]),
+        run_model(vec![
+            suite("/repo/shared.test.ts", &["a"]),
+            suite("/repo/own.test.ts", &["b"]),
+        ]),
+    ];
+    assert_eq!(
+        duplicate_suite_paths(&projects),
+        vec![Duplicate::SuitePath {
+            suite_path: "/repo/shared.test.ts".to_string(),
+            count: 2,
+        }]
+    );
+}
+
+#[test]
+fn duplicates_fail_the_run_only_under_strict_duplicates() {
+    let found = RecordedResults {
+        duplicates: vec![Duplicate::SuitePath {
+            suite_path: "/repo/shared.test.ts".to_string(),
+            count: 2,
+        }],
+        ..RecordedResults::default()
+    };
+    let strict = derive_args(&[], &["--strict-duplicates".to_string()], false);
+    let lenient = derive_args(&[], &[], false);
+    assert_eq!(report_duplicates(&strict, &found, 0), 1);
+    assert_eq!(report_duplicates(&lenient, &found, 0), 0);
+    assert_eq!(
+        report_duplicates(&strict, &RecordedResults::default(), 0),
+        0
+    );
+}
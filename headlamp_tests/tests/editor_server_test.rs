@@ -0,0 +1,249 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use headlamp::editor_server::{
+    EditorServer, SymbolKind, read_message, symbol_at_line, test_symbols, tests_in_file,
+    write_message,
+};
+use serde_json::{Value, json};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-editor-server-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn framed(messages: &[Value]) -> Vec<u8> {
+    let mut input = vec![];
+    for message in messages {
+        write_message(&mut input, message).unwrap();
+    }
+    input
+}
+
+fn serve(repo_root: &Path, messages: &[Value]) -> (i32, Vec<Value>) {
+    let mut input = Cursor::new(framed(messages));
+    let mut output = vec![];
+    let code = EditorServer::new(repo_root.to_path_buf())
+        .serve(&mut input, &mut output)
+        .unwrap();
+    let mut output = Cursor::new(output);
+    let replies = std::iter::from_fn(|| read_message(&mut output).unwrap()).collect();
+    (code, replies)
+}
+
+#[test]
+fn js_symbols_nest_describe_titles() {
+    let source = "describe('math', () => {\n  it('adds', () => {\n    expect(1 + 1).toBe(2);\n  });\n  test.skip(`subtracts`, () => {});\n});\n";
+    let symbols = test_symbols(Path::new("src/math.test.ts"), source);
+    let names = symbols
+        .iter()
+        .map(|symbol| (symbol.kind, symbol.full_name.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            (SymbolKind::Suite, "math"),
+            (SymbolKind::Test, "math adds"),
+            (SymbolKind::Test, "math subtracts"),
+        ]
+    );
+    assert_eq!((symbols[1].start_line, symbols[1].end_line), (1, 3));
+    assert_eq!(symbol_at_line(&symbols, 2).unwrap().name, "adds");
+    assert_eq!(symbol_at_line(&symbols, 5).unwrap().name, "math");
+}
+
+#[test]
+fn python_symbols_follow_pytest_collection() {
+    let source = "import pytest\n\ndef helper():\n    pass\n\ndef test_sum():\n    assert helper() is None\n\nclass TestMath:\n    def test_add(self):\n        assert 1 + 1 == 2\n\n    def setup_method(self):\n        pass\n";
+    let symbols = test_symbols(Path::new("tests/test_math.py"), source);
+    let names = symbols
+        .iter()
+        .map(|symbol| {
+            (
+                symbol.full_name.as_str(),
+                symbol.start_line,
+                symbol.end_line,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            ("test_sum", 5, 6),
+            ("TestMath", 8, 13),
+            ("TestMath::test_add", 9, 10)
+        ]
+    );
+    assert!(symbol_at_line(&symbols, 2).is_none());
+}
+
+#[test]
+fn rust_symbols_use_module_paths() {
+    let source = "fn helper() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn adds() {\n        assert_eq!(2, 2);\n    }\n}\n";
+    let symbols = test_symbols(Path::new("crates/math/src/ops/add.rs"), source);
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].full_name, "ops::add::tests::adds");
+    assert_eq!((symbols[0].start_line, symbols[0].end_line), (4, 7));
+
+    let integration = test_symbols(Path::new("tests/it.rs"), "#[test]\nfn works() {}\n");
+    assert_eq!(integration[0].full_name, "works");
+}
+
+#[test]
+fn framing_round_trips_messages() {
+    let message = json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"});
+    let bytes = framed(std::slice::from_ref(&message));
+    assert!(String::from_utf8_lossy(&bytes).starts_with("Content-Length: "));
+    let mut input = Cursor::new(bytes);
+    assert_eq!(read_message(&mut input).unwrap(), Some(message));
+    assert_eq!(read_message(&mut input).unwrap(), None);
+}
+
+#[test]
+fn requests_list_and_resolve_tests() {
+    let repo_root = mk_temp_dir("list_and_resolve");
+    write_file(
+        &repo_root.join("tests/test_math.py"),
+        "def test_sum():\n    assert True\n",
+    );
+    let (code, replies) = serve(
+        &repo_root,
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "headlamp/listTests",
+                   "params": {"file": "tests/test_math.py"}}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "headlamp/resolveTest",
+                   "params": {"file": "tests/test_math.py", "line": 1}}),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "headlamp/nope"}),
+            json!({"jsonrpc": "2.0", "id": 5, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ],
+    );
+    assert_eq!(code, 0);
+    assert_eq!(replies.len(), 5);
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "headlamp");
+    assert_eq!(replies[1]["result"]["tests"][0]["fullName"], "test_sum");
+    assert_eq!(replies[2]["result"]["kind"], "test");
+    assert_eq!(replies[2]["result"]["startLine"], 0);
+    assert_eq!(replies[3]["error"]["code"], -32601);
+    assert_eq!(replies[4]["result"], Value::Null);
+}
+
+#[test]
+fn exit_without_shutdown_is_an_error_exit() {
+    let repo_root = mk_temp_dir("exit_without_shutdown");
+    let (code, replies) = serve(&repo_root, &[json!({"jsonrpc": "2.0", "method": "exit"})]);
+    assert_eq!(code, 1);
+    assert!(replies.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_stream_results_as_notifications() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo_root = mk_temp_dir("run_at_cursor");
+    let script = repo_root.join(".headlamp/plugins/run.sh");
+    let case = json!({
+        "event": "testCase",
+        "testPath": "checks/spelling.txt",
+        "result": {
+            "title": "no typos",
+            "fullName": "no typos",
+            "status": "passed",
+            "timedOut": null,
+            "duration": 1,
+            "location": {"line": 3, "column": 1},
+            "failureMessages": [],
+            "failureDetails": null,
+        },
+    });
+    write_file(
+        &script,
+        &format!(
+            "#!/bin/sh\necho '{{\"event\":\"hello\",\"protocol\":1}}' >&${{HEADLAMP_EVENTS_FD:-1}}\n\
+             echo '{case}' >&${{HEADLAMP_EVENTS_FD:-1}}\n"
+        ),
+    );
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    write_file(
+        &repo_root.join(".headlamp/plugins/spelling.json"),
+        r#"{"name":"spelling","protocol":1,"command":["./run.sh"]}"#,
+    );
+    write_file(&repo_root.join("checks/spelling.txt"), "words\n");
+
+    let (_, replies) = serve(
+        &repo_root,
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                   "params": {"initializationOptions": {"runner": "plugin:spelling"}}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "headlamp/runTestAtCursor",
+                   "params": {"file": "checks/spelling.txt", "line": 0}}),
+        ],
+    );
+    let methods = replies
+        .iter()
+        .map(|reply| reply["method"].as_str().unwrap_or("<response>"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        methods,
+        vec![
+            "<response>",
+            "headlamp/runStarted",
+            "headlamp/testResults",
+            "<response>"
+        ]
+    );
+    let results = &replies[2]["params"];
+    assert_eq!(results["file"], "checks/spelling.txt");
+    assert_eq!(results["tests"][0]["status"], "passed");
+    assert_eq!(results["tests"][0]["line"], 2);
+    assert_eq!(replies[3]["result"]["passed"], 1);
+    assert_eq!(replies[3]["result"]["exitCode"], 0);
+}
+
+#[test]
+fn tests_in_file_lists_cases_and_covering_tests() {
+    let repo_root = mk_temp_dir("tests_in_file");
+    write_file(
+        &repo_root.join("src/sum.js"),
+        "export function sum(a, b) {\n  return a + b;\n}\n",
+    );
+    let test_source = "import { sum } from '../src/sum';\n\ntest('adds', () => {\n  expect(sum(1, 2)).toBe(3);\n});\n";
+    write_file(&repo_root.join("tests/sum.test.js"), test_source);
+    write_file(
+        &repo_root.join("calc.py"),
+        "def add(a, b):\n    return a + b\n",
+    );
+    write_file(
+        &repo_root.join("tests/test_calc.py"),
+        "from calc import add\n\ndef test_add():\n    assert add(1, 2) == 3\n",
+    );
+
+    let source = tests_in_file(&repo_root, Path::new("src/sum.js"), "export const x = 1;\n");
+    assert_eq!(source.file, "src/sum.js");
+    assert!(source.tests.is_empty());
+    assert_eq!(source.related_tests, vec!["tests/sum.test.js"]);
+
+    let test_file = tests_in_file(
+        &repo_root,
+        &repo_root.join("tests/sum.test.js"),
+        test_source,
+    );
+    assert_eq!(test_file.file, "tests/sum.test.js");
+    assert_eq!(test_file.tests[0].full_name, "adds");
+    assert_eq!(test_file.tests[0].start_line, 2);
+    assert!(test_file.related_tests.is_empty());
+
+    let python = tests_in_file(&repo_root, Path::new("calc.py"), "");
+    assert_eq!(python.related_tests, vec!["tests/test_calc.py"]);
+}
@@ -25,6 +25,9 @@ fn render_expected_received_from_rust_left_right_snapshot() {
                     "assertion `left == right` failed\n  left: 1\n right: 2\n",
                 )],
                 failure_details: None,
+                repro: None,
+                skip_reason: None,
+                attempts: None,
             }],
         }],
         aggregated: TestRunAggregated {
@@ -42,6 +45,7 @@ fn render_expected_received_from_rust_left_right_snapshot() {
             success: false,
             run_time_ms: Some(1),
         },
+        skipped_units: vec![],
     };
     let ctx = make_ctx(std::path::Path::new("/repo"), Some(80), true, false, None);
     let out = render_vitest_from_test_model(&model, &ctx, false);
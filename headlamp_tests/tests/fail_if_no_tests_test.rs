@@ -0,0 +1,45 @@
+use headlamp::args::derive_args;
+use headlamp::recorded::RecordedResults;
+use headlamp::run::{NO_TESTS_EXIT_CODE, apply_fail_if_no_tests, no_tests_explanation};
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+#[test]
+fn a_run_without_tests_fails_only_when_asked() {
+    let strict = derive_args(&[], &strings(&["--fail-if-no-tests"]), false);
+    let lenient = derive_args(&[], &[], false);
+    let none_ran = RecordedResults::default();
+    let one_skipped = RecordedResults {
+        skipped: 1,
+        ..RecordedResults::default()
+    };
+    assert_eq!(
+        apply_fail_if_no_tests(&strict, &none_ran, 0),
+        NO_TESTS_EXIT_CODE
+    );
+    assert_eq!(apply_fail_if_no_tests(&strict, &none_ran, 1), 1);
+    assert_eq!(apply_fail_if_no_tests(&strict, &one_skipped, 0), 0);
+    assert_eq!(apply_fail_if_no_tests(&lenient, &none_ran, 0), 0);
+}
+
+#[test]
+fn the_explanation_names_the_selection_inputs() {
+    let args = derive_args(
+        &[],
+        &strings(&["--changed=staged", "src/widgets", "--exclude=slow-crate"]),
+        false,
+    );
+    let explanation = no_tests_explanation(&args);
+    assert_eq!(explanation[0], "paths: src/widgets");
+    assert!(explanation.contains(&"changed: staged".to_string()));
+    assert!(
+        explanation
+            .iter()
+            .any(|line| line == "excluded packages: slow-crate")
+    );
+
+    let bare = no_tests_explanation(&derive_args(&[], &[], false));
+    assert_eq!(bare, vec!["paths: (none)".to_string()]);
+}
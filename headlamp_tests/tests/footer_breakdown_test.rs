@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::{TestCaseResult, TestSuiteResult};
+use headlamp::test_support::model::{self, failed_case, run_model};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn case(status: &str, duration: u64) -> TestCaseResult {
+    let case = match status {
+        "failed" => failed_case("t", "Error: nope"),
+        _ => model::case("t", status),
+    };
+    TestCaseResult { duration, ..case }
+}
+
+fn suite(path: &Path, cases: Vec<TestCaseResult>) -> TestSuiteResult {
+    model::suite(&path.to_string_lossy(), cases)
+}
+
+#[test]
+fn footer_breaks_totals_down_by_owning_unit() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(repo.path()).unwrap();
+    write(
+        &root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/core\"]\n",
+    );
+    write(
+        &root,
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core\"\n",
+    );
+    write(&root, "web/jest.config.js", "module.exports = {};\n");
+    write(&root, "svc/pytest.ini", "[pytest]\n");
+    let data = run_model(vec![
+        suite(
+            &root.join("crates/core/tests/api.rs"),
+            vec![case("passed", 1_000), case("failed", 500)],
+        ),
+        suite(&root.join("web/src/a.test.js"), vec![case("passed", 200)]),
+        suite(&root.join("web/src/b.test.js"), vec![case("pending", 0)]),
+        suite(
+            &root.join("svc/tests/test_x.py"),
+            vec![case("passed", 3_000)],
+        ),
+    ]);
+
+    let mut ctx = make_ctx(&root, Some(100), true, false, None);
+    let plain = strip_ansi_simple(&render_vitest_from_test_model(&data, &ctx, false));
+    assert!(!plain.contains("By unit"));
+
+    ctx.footer_breakdown = true;
+    let out = strip_ansi_simple(&render_vitest_from_test_model(&data, &ctx, false));
+    let breakdown = out
+        .lines()
+        .skip_while(|line| *line != "By unit")
+        .take(4)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        breakdown,
+        vec![
+            "By unit",
+            "  cargo package core               1 failed | 1 passed (2)  1s 500ms",
+            "  jest project web/jest.config.js  1 passed | 1 skipped (2)  200ms",
+            "  pytest rootdir svc               1 passed (1)  3s",
+        ]
+    );
+}
+
+#[test]
+fn footer_breakdown_comes_from_the_flag_or_config() {
+    let argv = vec!["--footerBreakdown".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        true,
+    );
+    assert!(parsed.footer_breakdown);
+
+    let cfg = HeadlampConfig {
+        footer_breakdown: Some(true),
+        ..HeadlampConfig::default()
+    };
+    assert!(derive_args(&config_tokens(&cfg, &[]), &[], true).footer_breakdown);
+}
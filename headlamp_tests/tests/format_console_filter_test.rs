@@ -69,6 +69,9 @@ fn build_file_result_for_two_tests(
                 location: None,
                 failure_messages: vec![],
                 failure_details: None,
+                repro: None,
+                skip_reason: None,
+                attempts: None,
             },
             BridgeAssertion {
                 title: "fail".to_string(),
@@ -79,6 +82,9 @@ fn build_file_result_for_two_tests(
                 location: None,
                 failure_messages: vec!["Error: boom".to_string()],
                 failure_details: None,
+                repro: None,
+                skip_reason: None,
+                attempts: None,
             },
         ],
     }
@@ -107,6 +113,7 @@ fn build_bridge_with_single_file(file: BridgeFileResult) -> BridgeJson {
         start_time: 0,
         test_results: vec![file],
         aggregated: aggregated_for_one_failed_suite_with_two_tests(),
+        skipped_units: vec![],
     }
 }
 
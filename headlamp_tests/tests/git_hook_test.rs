@@ -0,0 +1,119 @@
+use headlamp::args::derive_args;
+use headlamp::git_hook::{GitHookKind, hook_script, install_hook, parse_install_args};
+use headlamp::interrupt::{PartialSummary, render_stopped_summary};
+use headlamp::run::worst_exit_code;
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|t| t.to_string()).collect()
+}
+
+fn git_init(dir: &std::path::Path) {
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn bail_and_max_duration_are_parsed() {
+    let parsed = derive_args(&[], &strings(&["--bail", "--max-duration=90s"]), false);
+    assert!(parsed.bail);
+    assert_eq!(parsed.max_duration_ms, Some(90_000));
+    let parsed = derive_args(&[], &strings(&["--maxDuration", "2m"]), false);
+    assert!(!parsed.bail);
+    assert_eq!(parsed.max_duration_ms, Some(120_000));
+}
+
+#[test]
+fn bail_skips_the_runs_after_the_first_failure() {
+    let mut ran = vec![];
+    let mut run = |code: i32| {
+        ran.push(code);
+        code
+    };
+    assert_eq!(
+        worst_exit_code([0, 1, 2].into_iter().map(&mut run), true),
+        1
+    );
+    assert_eq!(ran, vec![0, 1]);
+    assert_eq!(worst_exit_code([0, 1, 2].into_iter(), false), 2);
+}
+
+#[test]
+fn stopped_summary_names_the_reason() {
+    let summary = PartialSummary {
+        passed: 3,
+        done_units: 1,
+        total_units: 4,
+        ..PartialSummary::default()
+    };
+    assert_eq!(
+        render_stopped_summary("exceeded --max-duration of 2m", &summary),
+        "headlamp: exceeded --max-duration of 2m after 1/4 units (3 passed, 0 failed, 0 skipped)"
+    );
+}
+
+#[test]
+fn install_args_pick_the_hook_and_keep_extra_flags() {
+    let install = parse_install_args(&strings(&["pre-push", "--runner=jest", "--force"])).unwrap();
+    assert_eq!(install.kind, GitHookKind::PrePush);
+    assert!(install.force);
+    assert_eq!(install.extra_args, vec!["--runner=jest"]);
+    assert_eq!(
+        parse_install_args(&[]).unwrap().kind,
+        GitHookKind::PreCommit
+    );
+    assert!(parse_install_args(&strings(&["post-merge"])).is_err());
+}
+
+#[test]
+fn hook_script_runs_headlamp_on_what_changed_with_extras_last() {
+    let install = parse_install_args(&strings(&["--max-duration=30s"])).unwrap();
+    let script = hook_script(&install, "/opt/headlamp bin/headlamp");
+    assert!(script.starts_with("#!/bin/sh\n"), "{script}");
+    assert!(
+        script.contains("HEADLAMP='/opt/headlamp bin/headlamp'"),
+        "{script}"
+    );
+    assert!(
+        script.contains(
+            "exec \"$HEADLAMP\" --changed=staged --bail --quiet --max-duration=2m --max-duration=30s\n"
+        ),
+        "{script}"
+    );
+    let pre_push = parse_install_args(&strings(&["pre-push"])).unwrap();
+    assert!(hook_script(&pre_push, "headlamp").contains("--changed=branch"));
+}
+
+#[test]
+fn install_writes_an_executable_hook_and_keeps_foreign_ones() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    git_init(root);
+    let install = parse_install_args(&[]).unwrap();
+
+    let path = install_hook(root, &install).unwrap();
+    assert_eq!(path, root.join(".git/hooks/pre-commit"));
+    let first = std::fs::read_to_string(&path).unwrap();
+    assert!(first.contains("--changed=staged"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+    install_hook(root, &install).expect("reinstalling our own hook");
+
+    std::fs::write(&path, "#!/bin/sh\nnpm test\n").unwrap();
+    let err = install_hook(root, &install).unwrap_err().to_string();
+    assert!(err.contains("--force"), "{err}");
+    let forced = parse_install_args(&strings(&["--force"])).unwrap();
+    install_hook(root, &forced).unwrap();
+    assert!(
+        std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("headlamp hook install")
+    );
+}
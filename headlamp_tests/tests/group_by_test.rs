@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::config::GroupBy;
+use headlamp::format::ctx::{Ctx, make_ctx};
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::{TestCaseResult, TestRunModel};
+use headlamp::test_support::model::{run_model, suite};
+
+fn case(title: &str, full_name: &str, status: &str) -> TestCaseResult {
+    TestCaseResult {
+        title: title.to_string(),
+        ..headlamp::test_support::model::case(full_name, status)
+    }
+}
+
+fn render(data: &TestRunModel, ctx: &Ctx) -> Vec<String> {
+    render_vitest_from_test_model(data, ctx, false)
+        .lines()
+        .map(strip_ansi_simple)
+        .collect()
+}
+
+fn ctx(root: &Path, group_by: GroupBy) -> Ctx {
+    Ctx {
+        group_by,
+        ..make_ctx(root, Some(120), false, false, None)
+    }
+}
+
+#[test]
+fn group_by_is_parsed_from_the_flag() {
+    let parse = |argv: &[&str]| {
+        let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        derive_args(&[], &argv, false).group_by
+    };
+    assert_eq!(parse(&[]), GroupBy::File);
+    assert_eq!(parse(&["--group-by=suite"]), GroupBy::Suite);
+    assert_eq!(parse(&["--groupBy", "package"]), GroupBy::Package);
+}
+
+#[test]
+fn suite_grouping_splits_files_by_describe_block_and_module_path() {
+    let root = tempfile::tempdir().unwrap();
+    let data = run_model(vec![
+        suite(
+            "src/a.test.ts",
+            vec![
+                case("adds", "Math adds", "passed"),
+                case("logs in", "Auth logs in", "failed"),
+            ],
+        ),
+        suite(
+            "src/b.test.ts",
+            vec![case("subtracts", "Math subtracts", "passed")],
+        ),
+        suite(
+            "tests/api.rs",
+            vec![case("auth::rejects", "auth::rejects", "passed")],
+        ),
+    ]);
+    let lines = render(&data, &ctx(root.path(), GroupBy::Suite));
+    let headers = lines
+        .iter()
+        .filter(|line| line.starts_with('▸'))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        headers,
+        vec![
+            "▸ Auth 1 failed (1)",
+            "▸ Math 2 passed (2)",
+            "▸ auth 1 passed (1)",
+        ]
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("Groups    1 failed | 2 passed (3)"))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("Test Files 1 failed | 2 passed (3)"))
+    );
+}
+
+#[test]
+fn package_grouping_uses_the_nearest_manifest_name() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("packages/api/src")).unwrap();
+    std::fs::write(
+        root.path().join("packages/api/package.json"),
+        r#"{"name": "@acme/api"}"#,
+    )
+    .unwrap();
+    let data = run_model(vec![
+        suite(
+            &root
+                .path()
+                .join("packages/api/src/a.test.ts")
+                .to_string_lossy(),
+            vec![case("works", "works", "passed")],
+        ),
+        suite("top.test.ts", vec![case("works", "works", "failed")]),
+    ]);
+    let lines = render(&data, &ctx(root.path(), GroupBy::Package));
+    let headers = lines
+        .iter()
+        .filter(|line| line.starts_with('▸'))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        headers,
+        vec!["▸ . 1 failed (1)", "▸ @acme/api 1 passed (1)"]
+    );
+}
+
+#[test]
+fn file_grouping_renders_no_group_headers() {
+    let root = tempfile::tempdir().unwrap();
+    let data = run_model(vec![suite(
+        "src/a.test.ts",
+        vec![case("adds", "Math adds", "passed")],
+    )]);
+    let lines = render(&data, &ctx(root.path(), GroupBy::File));
+    assert!(!lines.iter().any(|line| line.starts_with('▸')));
+    assert!(!lines.iter().any(|line| line.starts_with("Groups")));
+}
@@ -0,0 +1,73 @@
+use headlamp::args::{FlagKind, HelpTopic, all_flags, derive_args, split_headlamp_tokens};
+use headlamp::help::{help_text, run_help_command, topic_help_text};
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+#[test]
+fn every_flag_is_listed_on_its_topic_page_once() {
+    for topic in HelpTopic::ALL {
+        let page = topic_help_text(topic);
+        for spec in topic.flags() {
+            assert!(
+                page.contains(&format!("{}{}", spec.name, spec.value)),
+                "{} missing from help {}",
+                spec.name,
+                topic.name()
+            );
+        }
+    }
+    let mut names = all_flags().map(|spec| spec.name).collect::<Vec<_>>();
+    let count = names.len();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), count, "a flag is listed under two topics");
+}
+
+#[test]
+fn every_spelling_splits_the_way_its_kind_says() {
+    for spec in all_flags() {
+        for spelling in spec.spellings() {
+            let (next, consumed) = match spec.kind {
+                FlagKind::Bool => ("false", true),
+                // `KEY=VALUE` keeps `--env` headlamp's rather than jest's.
+                FlagKind::Value => ("KEY=value", true),
+                FlagKind::Switch => ("src/a.test.ts", false),
+            };
+            let (headlamp, passthrough) = split_headlamp_tokens(&strings(&[spelling, next]));
+            assert_eq!(headlamp.len(), 1 + usize::from(consumed), "{spelling}");
+            assert_eq!(passthrough.is_empty(), consumed, "{spelling}");
+        }
+    }
+}
+
+#[test]
+fn legacy_spellings_parse_as_the_canonical_flag() {
+    let dotted = derive_args(&[], &strings(&["--coverage.maxFiles=3"]), false);
+    let canonical = derive_args(&[], &strings(&["--coverage-max-files=3"]), false);
+    assert_eq!(dotted.coverage_max_files, Some(3));
+    assert_eq!(dotted.coverage_max_files, canonical.coverage_max_files);
+
+    let compact = derive_args(&[], &strings(&["--coverage.compact"]), false);
+    let mode = derive_args(&[], &strings(&["--coverage-mode=compact"]), false);
+    assert_eq!(compact.coverage_mode, mode.coverage_mode);
+}
+
+#[test]
+fn overview_points_at_every_topic() {
+    let overview = help_text();
+    for topic in HelpTopic::ALL {
+        assert!(overview.contains(topic.summary()), "{}", topic.name());
+        assert_eq!(HelpTopic::parse(topic.name()), Some(topic));
+    }
+    assert!(overview.contains("--runner=<runner>"));
+    assert!(!overview.contains("--coverage-max-hotspots"));
+}
+
+#[test]
+fn help_command_is_only_the_help_subcommand() {
+    assert_eq!(run_help_command(&strings(&["src/help.test.ts"])), None);
+    assert_eq!(run_help_command(&strings(&["help", "nope"])), Some(2));
+    assert_eq!(run_help_command(&strings(&["help", "coverage"])), Some(0));
+}
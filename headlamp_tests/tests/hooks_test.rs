@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use headlamp::args::{ParsedArgs, config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::infra_failure::build_infra_failure_test_run_model;
+use headlamp::hooks::run_with_hooks;
+use headlamp::process::RunEnvironment;
+use headlamp::recorded::record_failed_tests;
+
+/// Failures are recorded process-wide, so runs must not overlap.
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join("headlamp-hooks-tests").join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn parse_with_config(config_json: &str, argv: &[&str]) -> ParsedArgs {
+    let cfg: HeadlampConfig = serde_json::from_str(config_json).unwrap();
+    let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    derive_args(&config_tokens(&cfg, &argv), &argv, false)
+}
+
+#[test]
+fn hooks_come_from_config_and_repeatable_flags() {
+    let parsed = parse_with_config(
+        r#"{ "hooks": { "pre_run": ["docker compose up -d"], "postRun": ["docker compose down"] } }"#,
+        &["--hook-pre-run", "echo a,b", "--hook-on-failure=notify"],
+    );
+    assert_eq!(
+        parsed.hooks.pre_run,
+        vec!["docker compose up -d", "echo a,b"]
+    );
+    assert_eq!(parsed.hooks.post_run, vec!["docker compose down"]);
+    assert_eq!(parsed.hooks.on_failure, vec!["notify"]);
+    assert!(parsed.hooks.pre_coverage.is_empty());
+    assert!(parsed.runner_args.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_run_runs_on_failure_and_post_run_with_env() {
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let repo_root = mk_temp_dir("failed_run_runs_on_failure_and_post_run_with_env");
+    let parsed = parse_with_config(
+        r#"{ "hooks": {
+            "preRun": ["echo \"$RUN_ID\" > pre.txt", "echo \"$CI $GREETING\" > env.txt"],
+            "preCoverage": ["touch coverage-hook.txt"],
+            "onFailure": ["echo \"$EXIT_CODE\" > failure.txt"],
+            "postRun": ["echo \"$RUN_ID $EXIT_CODE\" > post.txt", "cp \"$FAILED_TESTS\" failed.txt"]
+        } }"#,
+        &[],
+    );
+    let run_env = RunEnvironment {
+        ci: true,
+        vars: vec![("GREETING".to_string(), "hi".to_string())],
+        ..RunEnvironment::default()
+    };
+    headlamp::recorded::begin();
+    let exit_code = run_with_hooks(&repo_root, &parsed, &run_env, || {
+        record_failed_tests(&build_infra_failure_test_run_model(
+            "tests/a.test.js",
+            "adds",
+            "boom",
+        ));
+        1
+    });
+    assert_eq!(exit_code, 1);
+    let read = |name: &str| std::fs::read_to_string(repo_root.join(name)).unwrap();
+    let run_id = read("pre.txt").trim().to_string();
+    assert!(!run_id.is_empty());
+    assert_eq!(read("failure.txt").trim(), "1");
+    assert_eq!(read("post.txt").trim(), format!("{run_id} 1"));
+    assert_eq!(read("env.txt").trim(), "1 hi");
+    assert_eq!(read("failed.txt"), "tests/a.test.js\tadds\n");
+    assert!(!repo_root.join("coverage-hook.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_pre_run_skips_the_run_but_still_tears_down() {
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let repo_root = mk_temp_dir("failed_pre_run_skips_the_run_but_still_tears_down");
+    let parsed = parse_with_config(
+        r#"{ "hooks": { "preRun": ["exit 3"], "postRun": ["echo \"$EXIT_CODE\" > post.txt"] } }"#,
+        &[],
+    );
+    let mut ran = false;
+    let exit_code = run_with_hooks(&repo_root, &parsed, &RunEnvironment::default(), || {
+        ran = true;
+        0
+    });
+    assert_eq!(exit_code, 1);
+    assert!(!ran);
+    let post = std::fs::read_to_string(repo_root.join("post.txt")).unwrap();
+    assert_eq!(post.trim(), "1");
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use headlamp::format::bridge_console::http_bridge_console_entry;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::TestRunModel;
+use headlamp::test_support::model::{failed_case, run_model, suite};
+use headlamp::test_support::{HTTP_LOG_ENV, HttpCall, record_http};
+
+fn failed_model(test_file: &str, name: &str, message: &str) -> TestRunModel {
+    let http = serde_json::json!({
+        "type": "httpResponse", "timestampMs": 10, "method": "GET",
+        "url": "http://localhost/api/users", "statusCode": 500, "durationMs": 12,
+    });
+    let serde_json::Value::Object(http) = http else {
+        unreachable!()
+    };
+    let mut model = run_model(vec![suite(test_file, vec![failed_case(name, message)])]);
+    model.test_results[0].console = Some(vec![http_bridge_console_entry(http, test_file, name)]);
+    model
+}
+
+fn render(model: &TestRunModel) -> String {
+    let root = tempfile::tempdir().unwrap();
+    let ctx = make_ctx(root.path(), Some(120), false, false, None);
+    strip_ansi_simple(&render_vitest_from_test_model(model, &ctx, true))
+}
+
+#[test]
+fn http_cards_render_for_pytest_and_rust_status_assertions() {
+    let pytest = render(&failed_model(
+        "tests/test_users.py",
+        "test_lists_users",
+        "def test_lists_users():\n>       assert response.status_code == 200\nE       assert 500 == 200",
+    ));
+    let rust = render(&failed_model(
+        "tests/users.rs",
+        "lists_users",
+        "thread 'lists_users' panicked at tests/users.rs:9:5:\nassertion `left == right` failed\n  left: 500\n right: 200",
+    ));
+
+    for out in [pytest, rust] {
+        assert!(out.contains("HTTP:"), "{out}");
+        assert!(out.contains("-> 500"), "{out}");
+        assert!(out.contains("Expected: 200   Received: 500"), "{out}");
+    }
+}
+
+#[test]
+fn record_http_appends_the_call_under_the_test_thread_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("http.jsonl");
+    let call = HttpCall {
+        method: "post",
+        url: "http://localhost/api/users",
+        status_code: Some(201),
+        duration: Duration::from_millis(7),
+        ..HttpCall::default()
+    };
+
+    record_http(&call);
+    assert!(!log.exists());
+
+    // SAFETY: the only test in this binary that touches the environment.
+    unsafe { std::env::set_var(HTTP_LOG_ENV, &log) };
+    record_http(&call);
+    record_http(&HttpCall {
+        status_code: None,
+        ..call
+    });
+    unsafe { std::env::remove_var(HTTP_LOG_ENV) };
+
+    let events = std::fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["type"], "httpResponse");
+    assert_eq!(events[0]["method"], "POST");
+    assert_eq!(events[0]["statusCode"], 201);
+    assert_eq!(events[0]["durationMs"], 7);
+    assert_eq!(
+        events[0]["testName"],
+        "record_http_appends_the_call_under_the_test_thread_name"
+    );
+    assert_eq!(events[1]["type"], "httpAbort");
+}
@@ -0,0 +1,86 @@
+use headlamp::interrupt::{PartialSummary, render_partial_summary};
+use headlamp::live_progress::{LiveProgress, LiveProgressMode};
+use headlamp::streaming::{OutputStream, StreamAction, StreamAdapter};
+
+/// Teardown signals every registered group, so runs here must not overlap it.
+static GROUPS_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[test]
+fn partial_summary_reports_units_and_test_counts() {
+    let summary = PartialSummary {
+        passed: 12,
+        failed: 1,
+        skipped: 2,
+        done_units: 3,
+        total_units: 7,
+    };
+    assert_eq!(
+        render_partial_summary(&summary),
+        "headlamp: interrupted after 3/7 units (12 passed, 1 failed, 2 skipped)"
+    );
+}
+
+#[test]
+fn partial_summary_without_progress_omits_units() {
+    let summary = PartialSummary {
+        passed: 4,
+        ..PartialSummary::default()
+    };
+    assert_eq!(
+        render_partial_summary(&summary),
+        "headlamp: interrupted (4 passed, 0 failed, 0 skipped)"
+    );
+}
+
+#[derive(Default)]
+struct CaptureAdapter {
+    lines: Vec<String>,
+}
+
+impl StreamAdapter for CaptureAdapter {
+    fn on_start(&mut self) -> Option<String> {
+        None
+    }
+
+    fn on_line(&mut self, _stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        self.lines.push(line.to_string());
+        vec![]
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn streamed_runner_leads_its_own_process_group() {
+    let _groups = GROUPS_LOCK.lock().unwrap();
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args(["-c", "echo \"$$ $(cut -d' ' -f5 /proc/$$/stat)\""]);
+    let progress = LiveProgress::start(1, LiveProgressMode::Off);
+    let mut adapter = CaptureAdapter::default();
+    let (code, _ring) =
+        headlamp::streaming::run_streaming_capture_tail(cmd, &progress, &mut adapter, 1024)
+            .expect("run_streaming_capture_tail");
+    progress.finish();
+    assert_eq!(code, 0);
+    let line = adapter.lines.first().expect("pid line");
+    let (pid, pgid) = line.split_once(' ').expect("pid and pgid");
+    assert_eq!(pid, pgid, "{line}");
+}
+
+#[cfg(unix)]
+#[test]
+fn teardown_does_not_wait_out_the_grace_for_an_exited_runner() {
+    let _groups = GROUPS_LOCK.lock().unwrap();
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args(["-c", "exit 3"]);
+    headlamp::process::isolate_process_group(&mut cmd);
+    let mut child = cmd.spawn().unwrap();
+    let guard = headlamp::process::ChildGroupGuard::register(&child);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let started = std::time::Instant::now();
+    headlamp::process::group::terminate_registered_groups(std::time::Duration::from_secs(5));
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    // The exit status is still there for the runner's owner to collect.
+    assert_eq!(child.wait().unwrap().code(), Some(3));
+    drop(guard);
+}
@@ -0,0 +1,34 @@
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::{HeadlampConfig, JestEsm};
+
+#[test]
+fn jest_esm_defaults_to_auto_and_reads_cli_and_config() {
+    let cfg = HeadlampConfig::default();
+    let argv = vec!["--jest-esm=off".to_string()];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.jest_esm, JestEsm::Off);
+
+    let argv: Vec<String> = vec![];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.jest_esm, JestEsm::Auto);
+
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "jestEsm": "on" }"#).unwrap();
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.jest_esm, JestEsm::On);
+}
+
+#[test]
+fn node_override_reads_cli_and_config() {
+    let argv = vec!["--node=/opt/node/bin/node".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    assert_eq!(parsed.node.as_deref(), Some("/opt/node/bin/node"));
+
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "node": "/usr/bin/node" }"#).unwrap();
+    let argv: Vec<String> = vec![];
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.node.as_deref(), Some("/usr/bin/node"));
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use headlamp::jest_config::{jest_display_name, load_jest_config_value};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn evaluates_ts_config_exported_through_define_config() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.config.ts"),
+        "import type { Config } from 'jest';\n\
+         import { defineConfig } from 'jest-config-helpers';\n\
+         const roots: string[] = ['<rootDir>/src'];\n\
+         export default defineConfig({ displayName: 'api', roots } as Config);\n",
+    );
+    let config = load_jest_config_value(&root.join("jest.config.ts")).unwrap();
+    assert_eq!(
+        config,
+        serde_json::json!({ "displayName": "api", "roots": ["<rootDir>/src"] })
+    );
+}
+
+#[test]
+fn evaluates_async_config_function_with_dynamic_import() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.base.mjs"),
+        "export default { testEnvironment: 'node' };\nexport const shared = ['<rootDir>/setup.js'];\n",
+    );
+    write_file(
+        &root.join("jest.config.mjs"),
+        "export default async () => {\n\
+         \x20 const base = (await import('./jest.base.mjs')).default;\n\
+         \x20 return { ...base, displayName: `web`, setupFiles: (await import('./jest.base.mjs')).shared };\n\
+         };\n",
+    );
+    let config = load_jest_config_value(&root.join("jest.config.mjs")).unwrap();
+    assert_eq!(
+        config,
+        serde_json::json!({
+            "testEnvironment": "node",
+            "displayName": "web",
+            "setupFiles": ["<rootDir>/setup.js"],
+        })
+    );
+}
+
+#[test]
+fn evaluates_common_js_config_with_require() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.base.cjs"),
+        "module.exports = { testEnvironment: 'jsdom' };\n",
+    );
+    write_file(
+        &root.join("jest.config.cjs"),
+        "const base = require('./jest.base.cjs');\n\
+         function config() {\n\
+         \x20 return { ...base, displayName: { name: 'ui', color: 'cyan' } };\n\
+         }\n\
+         module.exports = config;\n",
+    );
+    assert_eq!(
+        jest_display_name(&root.join("jest.config.cjs")).as_deref(),
+        Some("ui")
+    );
+    let config = load_jest_config_value(&root.join("jest.config.cjs")).unwrap();
+    assert_eq!(config["testEnvironment"], "jsdom");
+}
+
+#[test]
+fn config_that_reads_a_package_import_is_not_evaluated() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.config.ts"),
+        "import preset from 'ts-jest/presets';\n\
+         export default { ...preset, displayName: 'api' };\n",
+    );
+    assert_eq!(load_jest_config_value(&root.join("jest.config.ts")), None);
+    assert_eq!(
+        jest_display_name(&root.join("jest.config.ts")).as_deref(),
+        Some("api")
+    );
+}
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use headlamp::jest_discovery::discover_jest_list_tests_cached_with_timeout;
+use headlamp::process::RunEnvironment;
 
 fn mk_temp_dir(name: &str) -> PathBuf {
     let unique_suffix = std::time::SystemTime::now()
@@ -65,6 +66,7 @@ exit 0\n",
     let first = discover_jest_list_tests_cached_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &args,
         false,
         Duration::from_secs(10),
@@ -73,6 +75,7 @@ exit 0\n",
     let second = discover_jest_list_tests_cached_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &args,
         false,
         Duration::from_secs(10),
@@ -118,6 +121,7 @@ exit 0\n",
     let _ = discover_jest_list_tests_cached_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &args,
         true,
         Duration::from_secs(10),
@@ -126,6 +130,7 @@ exit 0\n",
     let _ = discover_jest_list_tests_cached_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &args,
         true,
         Duration::from_secs(10),
@@ -4,8 +4,10 @@ use std::time::Duration;
 use path_slash::PathExt;
 
 use headlamp::jest_discovery::{
-    discover_jest_list_tests_resilient_with_timeout, discover_jest_list_tests_with_timeout,
+    RelatedTestsFallback, discover_jest_list_tests_resilient_with_timeout,
+    discover_jest_list_tests_with_timeout,
 };
+use headlamp::process::RunEnvironment;
 use headlamp::run::RunError;
 
 fn mk_temp_dir(name: &str) -> PathBuf {
@@ -53,6 +55,7 @@ fn jest_list_tests_times_out() {
     let err = discover_jest_list_tests_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &[],
         Duration::from_millis(50),
     )
@@ -104,10 +107,13 @@ fn jest_list_tests_timeout_falls_back_to_rg_when_related_paths_exist() {
     let discovered = discover_jest_list_tests_resilient_with_timeout(
         &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &[],
-        &related_production_paths_abs,
-        &[],
-        false,
+        RelatedTestsFallback {
+            production_paths_abs: &related_production_paths_abs,
+            exclude_globs: &[],
+            no_cache: false,
+        },
         Duration::from_millis(50),
     )
     .unwrap();
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use headlamp::config::JestEsm;
+use headlamp::jest_esm::{is_esm_project, needs_vm_modules, node_options_with_vm_modules};
+use headlamp::jest_projects::JestProject;
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn type_module_package_is_esm() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(&root.join("package.json"), r#"{ "type": "module" }"#);
+    write_file(&root.join("jest.config.js"), "export default {};\n");
+    let project = JestProject::from(root.join("jest.config.js"));
+    assert!(is_esm_project(root, &project, &[]));
+    assert!(!needs_vm_modules(JestEsm::Off, root, &project, &[]));
+}
+
+#[test]
+fn esm_config_files_options_and_tests_are_detected() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(&root.join("package.json"), r#"{ "name": "demo" }"#);
+    write_file(&root.join("jest.config.cjs"), "module.exports = {};\n");
+    write_file(
+        &root.join("packages/ts/jest.config.ts"),
+        "export default { extensionsToTreatAsEsm: ['.ts'] };\n",
+    );
+    write_file(
+        &root.join("packages/m/jest.config.mjs"),
+        "export default {};\n",
+    );
+
+    let cjs = JestProject::from(root.join("jest.config.cjs"));
+    assert!(!is_esm_project(root, &cjs, &[]));
+    assert!(is_esm_project(
+        root,
+        &cjs,
+        &["/repo/a.test.mjs".to_string()]
+    ));
+    assert!(needs_vm_modules(JestEsm::On, root, &cjs, &[]));
+
+    let ts = JestProject::from(root.join("packages/ts/jest.config.ts"));
+    assert!(is_esm_project(root, &ts, &[]));
+    let mjs = JestProject::from(root.join("packages/m/jest.config.mjs"));
+    assert!(needs_vm_modules(JestEsm::Auto, root, &mjs, &[]));
+}
+
+#[test]
+fn node_options_keep_existing_flags_without_repeating_the_vm_modules_flag() {
+    assert_eq!(
+        node_options_with_vm_modules(None),
+        "--experimental-vm-modules"
+    );
+    assert_eq!(
+        node_options_with_vm_modules(Some("--max-old-space-size=4096")),
+        "--max-old-space-size=4096 --experimental-vm-modules"
+    );
+    assert_eq!(
+        node_options_with_vm_modules(Some("--experimental-vm-modules --inspect")),
+        "--experimental-vm-modules --inspect"
+    );
+}
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use headlamp::jest_discovery::discover_jest_list_tests_for_project_with_patterns_with_timeout;
+use headlamp::process::RunEnvironment;
 
 fn mk_temp_dir(name: &str) -> PathBuf {
     let base = std::env::temp_dir()
@@ -57,8 +58,8 @@ exit 0\n",
     let discovery_args: Vec<String> = vec!["--no-watchman".to_string()];
     let patterns: Vec<String> = vec!["tests/a.test.js".to_string()];
     let _listed = discover_jest_list_tests_for_project_with_patterns_with_timeout(
-        &repo_root,
         &jest_bin,
+        &RunEnvironment::default(),
         &discovery_args,
         "jest.config.js",
         &repo_root,
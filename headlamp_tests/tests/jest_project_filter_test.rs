@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use headlamp::jest_config::{filter_jest_projects, jest_display_name, list_all_jest_configs};
+use headlamp::jest_projects::{JestProject, expand_jest_projects};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+}
+
+fn setup() -> tempfile::TempDir {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.config.js"),
+        "module.exports = { displayName: 'web', testMatch: ['**/*.test.js'] };\n",
+    );
+    write_file(
+        &root.join("jest.config.ts"),
+        "export default { displayName: { name: \"api\", color: 'blue' } };\n",
+    );
+    temp
+}
+
+#[test]
+fn reads_display_name_in_string_and_object_form() {
+    let temp = setup();
+    let root = temp.path();
+    assert_eq!(
+        jest_display_name(&root.join("jest.config.js")).as_deref(),
+        Some("web")
+    );
+    assert_eq!(
+        jest_display_name(&root.join("jest.config.ts")).as_deref(),
+        Some("api")
+    );
+}
+
+#[test]
+fn project_and_ignore_project_select_by_display_name_or_path() {
+    let temp = setup();
+    let root = temp.path();
+    let configs = expand_jest_projects(list_all_jest_configs(root));
+    assert_eq!(configs.len(), 2);
+
+    let only_api = filter_jest_projects(configs.clone(), root, &["api".to_string()], &[]).unwrap();
+    assert_eq!(
+        only_api,
+        vec![JestProject::from(root.join("jest.config.ts"))]
+    );
+
+    let by_path = filter_jest_projects(
+        configs.clone(),
+        root,
+        &["./jest.config.js".to_string()],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        by_path,
+        vec![JestProject::from(root.join("jest.config.js"))]
+    );
+
+    let ignored = filter_jest_projects(configs.clone(), root, &[], &["web".to_string()]).unwrap();
+    assert_eq!(
+        ignored,
+        vec![JestProject::from(root.join("jest.config.ts"))]
+    );
+
+    let err = filter_jest_projects(configs, root, &["mobile".to_string()], &[]).unwrap_err();
+    assert!(
+        err.contains("no jest project matches --project mobile"),
+        "{err}"
+    );
+    assert!(err.contains("web") && err.contains("api"), "{err}");
+}
+
+#[test]
+fn projects_array_expands_into_directory_configs_and_selected_inline_projects() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    for package in ["a", "b"] {
+        std::fs::create_dir_all(root.join("packages").join(package)).unwrap();
+        write_file(
+            &root.join("packages").join(package).join("jest.config.js"),
+            &format!("module.exports = {{ displayName: '{package}' }};\n"),
+        );
+    }
+    std::fs::create_dir_all(root.join("packages/node_modules/c")).unwrap();
+    write_file(
+        &root.join("jest.config.ts"),
+        "export default {\n\
+         \x20 projects: [\n\
+         \x20   '<rootDir>/packages/*',\n\
+         \x20   { displayName: 'lint', runner: 'jest-runner-eslint' },\n\
+         \x20 ],\n\
+         };\n",
+    );
+
+    let projects = expand_jest_projects(list_all_jest_configs(root));
+    assert_eq!(
+        projects
+            .iter()
+            .map(|project| project.label(root))
+            .collect::<Vec<_>>(),
+        vec![
+            "packages/a/jest.config.js",
+            "packages/b/jest.config.js",
+            "jest.config.ts#lint",
+        ]
+    );
+    assert_eq!(projects[2].select_args(), vec!["--selectProjects", "lint"]);
+
+    let only_lint =
+        filter_jest_projects(projects.clone(), root, &["lint".to_string()], &[]).unwrap();
+    assert_eq!(
+        only_lint,
+        vec![JestProject::selected(
+            root.join("jest.config.ts"),
+            "lint".to_string()
+        )]
+    );
+    let without_a = filter_jest_projects(projects, root, &[], &["a".to_string()]).unwrap();
+    assert_eq!(without_a.len(), 2);
+}
+
+#[test]
+fn projects_array_with_an_unnamed_inline_project_stays_one_unit() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path();
+    write_file(
+        &root.join("jest.config.js"),
+        "module.exports = { projects: [{ displayName: 'a' }, { testEnvironment: 'node' }] };\n",
+    );
+    assert_eq!(
+        expand_jest_projects(list_all_jest_configs(root)),
+        vec![JestProject::from(root.join("jest.config.js"))]
+    );
+}
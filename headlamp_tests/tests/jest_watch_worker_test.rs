@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-jest-watch-worker-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(path, contents).unwrap();
+}
+
+#[cfg(unix)]
+fn write_executable(path: &Path, contents: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    write_file(path, contents);
+    let mut perms = std::fs::metadata(path).unwrap().permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(path, perms).unwrap();
+}
+
+/// A jest install whose programmatic API logs the pid of the process running it; the CLI entry
+/// only answers `--listTests` and fails any real run, so a cold spawn would show up as exit 3.
+#[cfg(unix)]
+fn write_fake_jest(repo_root: &Path) {
+    let test_file = repo_root.join("tests").join("a.test.js");
+    write_file(&test_file, "test('a', () => {});\n");
+    write_executable(
+        &repo_root.join("node_modules/.bin/jest"),
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in *--listTests*) echo '{}'; exit 0;; esac\nexit 3\n",
+            test_file.display()
+        ),
+    );
+    write_file(
+        &repo_root.join("node_modules/jest/package.json"),
+        r#"{ "name": "jest" }"#,
+    );
+    write_file(
+        &repo_root.join("node_modules/jest-cli/package.json"),
+        r#"{ "name": "jest-cli", "main": "index.js" }"#,
+    );
+    write_file(
+        &repo_root.join("node_modules/jest-cli/index.js"),
+        "exports.buildArgv = async (args) => ({ _: args, projects: [] });\n",
+    );
+    write_file(
+        &repo_root.join("node_modules/@jest/core/package.json"),
+        r#"{ "name": "@jest/core", "main": "index.js" }"#,
+    );
+    write_file(
+        &repo_root.join("node_modules/@jest/core/index.js"),
+        "const fs = require('fs');\n\
+         exports.runCLI = async () => {\n\
+           fs.appendFileSync('worker-pids.log', `${process.pid}\\n`);\n\
+           return { results: { success: true } };\n\
+         };\n",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_reruns_reuse_one_jest_worker() {
+    if which::which("node").is_err() {
+        return;
+    }
+    let repo_root = mk_temp_dir("watch_reruns_reuse_one_jest_worker");
+    write_fake_jest(&repo_root);
+    let argv = vec!["--watch".to_string(), "--no-cache".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        false,
+    );
+    for _ in 0..2 {
+        let session = headlamp::session::RunSession::new(false).unwrap();
+        let exit_code = headlamp::jest::run_jest(&repo_root, &parsed, &session).unwrap();
+        assert_eq!(exit_code, 0);
+    }
+    let pids = std::fs::read_to_string(repo_root.join("worker-pids.log")).unwrap();
+    let pids = pids.lines().collect::<Vec<_>>();
+    assert_eq!(pids.len(), 2);
+    assert_eq!(pids[0], pids[1]);
+}
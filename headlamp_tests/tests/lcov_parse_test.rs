@@ -36,6 +36,7 @@ end_of_record
         page_fit: true,
         tty: false,
         editor_cmd: None,
+        path_display: Default::default(),
     };
     let root = std::path::Path::new("/repo");
     let filtered = filter_report(report, root, &["**/*.ts".to_string()], &[]);
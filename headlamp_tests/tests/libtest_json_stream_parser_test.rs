@@ -81,3 +81,31 @@ fn libtest_json_stream_parser_captures_failure_stdout() {
         "expected panic text, got: {msg}"
     );
 }
+
+#[test]
+fn libtest_json_stream_parser_keeps_ignore_reasons() {
+    let mut parser = LibtestJsonStreamParser::new(Path::new("/repo"), "tests/slow.rs");
+    let lines = [
+        r#"{"type":"suite","event":"started","test_count":2}"#,
+        r#"{"type":"test","event":"ignored","name":"needs_network","message":"needs network access"}"#,
+        r#"{"type":"test","event":"ignored","name":"bare_ignore"}"#,
+        r#"{"type":"suite","event":"ok","passed":0,"failed":0,"ignored":2,"measured":0,"filtered_out":0,"exec_time":0.0}"#,
+    ];
+    lines.iter().for_each(|line| {
+        let _ = parser.push_line(line);
+    });
+
+    let model = parser.finalize().expect("model");
+    let cases = &model.test_results.first().expect("suite").test_results;
+    let reason_of = |name: &str| {
+        cases
+            .iter()
+            .find(|case| case.full_name == name)
+            .and_then(|case| case.skip_reason.clone())
+    };
+    assert_eq!(
+        reason_of("needs_network").as_deref(),
+        Some("needs network access")
+    );
+    assert_eq!(reason_of("bare_ignore"), None);
+}
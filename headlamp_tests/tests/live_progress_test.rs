@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use headlamp::live_progress::{
-    LiveProgressMode, RenderRunFrameArgs, frame_physical_line_count,
-    live_progress_mode_with_env_ci, render_run_frame, render_run_frame_with_columns,
+    LaneView, LiveProgressMode, ProgressHarness, RenderRunFrameArgs, TestCounts, TestOutcome,
+    frame_physical_line_count, live_progress_mode_with_env_ci, read_captured_frames,
+    render_heartbeat_line, render_run_frame, render_run_frame_with_columns,
 };
 
 #[test]
@@ -37,6 +40,8 @@ fn live_progress_long_details_wraps_to_multiple_physical_lines() {
         elapsed_seconds: 199,
         idle_seconds: 0,
         recent: long_recent,
+        counts: "",
+        lanes: &[],
         columns: 64,
     });
     assert!(frame_a.starts_with("RUN ["));
@@ -79,3 +84,184 @@ fn live_progress_classifies_nextest_suite_json_lines() {
     assert!(hint.contains("suite ok:"));
     assert!(hint.contains("headlamp_tests::vitest_render_snapshot_test"));
 }
+
+#[test]
+fn live_progress_renders_one_line_per_in_flight_lane_with_eta() {
+    let lanes = vec![
+        LaneView {
+            label: "packages/a/jest.config.js".to_string(),
+            elapsed_seconds: 45,
+            eta_seconds: Some(15),
+        },
+        LaneView {
+            label: "packages/b/jest.config.js".to_string(),
+            elapsed_seconds: 200,
+            eta_seconds: Some(-80),
+        },
+        LaneView {
+            label: "packages/c/jest.config.js".to_string(),
+            elapsed_seconds: 3,
+            eta_seconds: None,
+        },
+    ];
+    let frame = render_run_frame_with_columns(RenderRunFrameArgs {
+        current_label: "packages/c/jest.config.js",
+        done_units: 2,
+        total_units: 6,
+        spinner_index: 0,
+        elapsed_seconds: 210,
+        idle_seconds: 0,
+        recent: "",
+        counts: "",
+        lanes: &lanes,
+        columns: 200,
+    });
+    let lines = frame.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "RUN [⠋ +3m 30s] (2/6)");
+    assert_eq!(lines[1], "  ⠋ packages/a/jest.config.js (+45s, eta ~15s)");
+    assert_eq!(
+        lines[2],
+        "  ⠋ packages/b/jest.config.js (+3m 20s, overdue +1m 20s)"
+    );
+    assert_eq!(lines[3], "  ⠋ packages/c/jest.config.js (+3s)");
+    assert_eq!(lines.len(), 5);
+}
+
+#[test]
+fn live_progress_single_lane_keeps_label_on_header() {
+    let lanes = vec![LaneView {
+        label: "target/debug/deps/foo".to_string(),
+        elapsed_seconds: 5,
+        eta_seconds: Some(2),
+    }];
+    let frame = render_run_frame_with_columns(RenderRunFrameArgs {
+        current_label: "tests/foo.rs",
+        done_units: 0,
+        total_units: 1,
+        spinner_index: 0,
+        elapsed_seconds: 5,
+        idle_seconds: 1,
+        recent: "",
+        counts: "",
+        lanes: &lanes,
+        columns: 200,
+    });
+    assert!(frame.starts_with("RUN [⠋ +5s] (0/1) tests/foo.rs (+5s, eta ~2s)\n"));
+}
+
+#[test]
+fn live_progress_test_counts_track_running_tests_until_they_finish() {
+    let mut counts = TestCounts::default();
+    assert_eq!(counts.summary(), "");
+    (0..4).for_each(|_| counts.started());
+    counts.finished(TestOutcome::Pass);
+    counts.finished(TestOutcome::Pass);
+    counts.finished(TestOutcome::Fail);
+    assert_eq!(counts.summary(), "2 passed, 1 failed, 1 running");
+    counts.finished(TestOutcome::Skip);
+    counts.finished(TestOutcome::Skip);
+    assert_eq!(counts.summary(), "2 passed, 1 failed, 2 skipped");
+}
+
+#[test]
+fn live_progress_header_shows_test_counts_after_the_label() {
+    let frame = render_run_frame_with_columns(RenderRunFrameArgs {
+        current_label: "tests/foo.rs",
+        done_units: 0,
+        total_units: 1,
+        spinner_index: 0,
+        elapsed_seconds: 5,
+        idle_seconds: 1,
+        recent: "",
+        counts: "3 passed, 1 running",
+        lanes: &[],
+        columns: 200,
+    });
+    assert!(
+        frame.starts_with("RUN [⠋ +5s] (0/1) tests/foo.rs · 3 passed, 1 running"),
+        "{frame}"
+    );
+}
+
+#[test]
+fn live_progress_heartbeat_line_reports_label_elapsed_and_counts() {
+    assert_eq!(
+        render_heartbeat_line("tests/parity_suite_test.rs", 192, 42, 97),
+        "still running: tests/parity_suite_test.rs (3m 12s elapsed, 42/97 done)"
+    );
+}
+
+#[test]
+fn live_progress_harness_drives_frames_on_a_fake_clock() {
+    let mut harness = ProgressHarness::new(2);
+    harness.progress().start_unit(0, "tests/a.rs");
+    harness.progress().start_unit(1, "tests/b.rs");
+    harness.capture(80);
+    harness.advance(Duration::from_secs(65));
+    harness
+        .progress()
+        .record_runner_stdout_line("Compiling headlamp v0.1.0");
+    harness.capture(80);
+    harness.progress().finish_unit(0);
+    harness.advance(Duration::from_secs(7));
+    harness.capture(80);
+
+    assert_eq!(
+        harness.frames()[0].text,
+        "RUN [⠋ +<1s] (0/2)\n  ⠋ tests/a.rs (+<1s)\n  ⠋ tests/b.rs (+<1s)\nno activity yet"
+    );
+    assert_eq!(harness.frames()[1].elapsed_ms, 65_000);
+    assert!(
+        harness.frames()[1]
+            .text
+            .starts_with("RUN [⠙ +1m 5s] (0/2)\n")
+    );
+    harness.assert_transitions(&["no activity yet", "Compiling", "(1/2) tests/b.rs (+1m 12s)"]);
+}
+
+#[test]
+fn live_progress_lanes_of_units_sharing_a_label_stay_apart() {
+    let mut harness = ProgressHarness::new(3);
+    harness.progress().start_unit(0, "web");
+    harness.progress().start_unit(1, "web");
+    harness.progress().start_unit(2, "api");
+    harness.progress().finish_unit(0);
+    let frame = harness.capture(80);
+    assert_eq!(frame.matches("⠋ web").count(), 1, "{frame}");
+    assert!(frame.contains("⠋ api"), "{frame}");
+}
+
+#[test]
+fn live_progress_harness_captures_at_the_given_width() {
+    let mut harness = ProgressHarness::new(1);
+    harness
+        .progress()
+        .set_current_label("tests/some/deeply/nested/parity_suite_test.rs".to_string());
+    let narrow = harness.capture(24);
+    let wide = harness.capture(120);
+    assert!(narrow.lines().all(|line| line.chars().count() <= 24));
+    assert_eq!(
+        frame_physical_line_count(&narrow, 24),
+        narrow.lines().count()
+    );
+    assert_eq!(wide.lines().count(), 2);
+    assert_eq!(
+        harness.capture_plain(120),
+        "RUN (+<1s) (0/1) tests/some/deeply/nested/parity_suite_test.rs\nidle <1s | no activity yet"
+    );
+}
+
+#[test]
+fn live_progress_captured_frames_file_skips_unreadable_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("frames.jsonl");
+    std::fs::write(
+        &path,
+        "{\"elapsed_ms\":120,\"columns\":80,\"text\":\"RUN (0/1)\"}\n{\"elapsed_ms\":2",
+    )
+    .unwrap();
+    let frames = read_captured_frames(&path);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].text, "RUN (0/1)");
+    assert!(read_captured_frames(&dir.path().join("missing.jsonl")).is_empty());
+}
@@ -0,0 +1,53 @@
+use headlamp::args::{ParsedArgs, config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::log::{filter_directives, init};
+
+fn parse(argv: &[&str]) -> ParsedArgs {
+    let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+#[test]
+fn log_flags_come_from_cli_and_config() {
+    let cfg: HeadlampConfig =
+        serde_json::from_str(r#"{ "logLevel": "info", "logJson": true }"#).unwrap();
+    let parsed = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed.log_level.as_deref(), Some("info"));
+    assert!(parsed.log_json);
+
+    let parsed = parse(&["--log-level", "trace", "--log-json=false", "-t", "adds"]);
+    assert_eq!(parsed.log_level.as_deref(), Some("trace"));
+    assert!(!parsed.log_json);
+    assert_eq!(parsed.runner_args, vec!["-t", "adds"]);
+}
+
+#[test]
+fn headlamp_log_wins_over_log_level_which_wins_over_verbose() {
+    assert_eq!(filter_directives(&parse(&[]), None, false), "warn");
+    assert_eq!(
+        filter_directives(&parse(&["--verbose"]), None, false),
+        "debug"
+    );
+    assert_eq!(
+        filter_directives(&parse(&["--verbose", "--log-level=ERROR"]), None, false),
+        "error"
+    );
+    assert_eq!(
+        filter_directives(
+            &parse(&["--log-level=error"]),
+            Some("selection=debug,jest=trace"),
+            false
+        ),
+        "selection=debug,jest=trace"
+    );
+    assert_eq!(
+        filter_directives(&parse(&[]), Some("  "), true),
+        "warn,profile=info"
+    );
+}
+
+#[test]
+fn unknown_log_level_is_rejected() {
+    let err = init(&parse(&["--log-level=loud"])).unwrap_err();
+    assert!(err.to_string().contains("invalid --log-level: loud"));
+}
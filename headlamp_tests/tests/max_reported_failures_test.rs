@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::format::ctx::{Ctx, make_ctx};
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::TestRunModel;
+use headlamp::test_support::model::{failed_case, run_model, suite};
+
+fn model() -> TestRunModel {
+    let failed = |name: &str| failed_case(name, &format!("Error: {name} broke"));
+    run_model(vec![
+        suite("src/a.test.ts", vec![failed("alpha"), failed("beta")]),
+        suite("src/b.test.ts", vec![failed("gamma")]),
+    ])
+}
+
+fn render(root: &Path, max_reported_failures: Option<usize>) -> String {
+    let ctx = Ctx {
+        max_reported_failures,
+        ..make_ctx(root, Some(120), false, false, None)
+    };
+    strip_ansi_simple(&render_vitest_from_test_model(&model(), &ctx, true))
+}
+
+#[test]
+fn max_reported_failures_is_parsed_from_the_flag() {
+    let parse = |argv: &[&str]| {
+        let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        derive_args(&[], &argv, false).max_reported_failures
+    };
+    assert_eq!(parse(&[]), None);
+    assert_eq!(parse(&["--max-reported-failures=20"]), Some(20));
+    assert_eq!(parse(&["--maxReportedFailures", "3"]), Some(3));
+}
+
+#[test]
+fn failures_past_the_cap_are_listed_one_line_each() {
+    let root = tempfile::tempdir().unwrap();
+    let out = render(root.path(), Some(1));
+    assert!(out.contains("× src/a.test.ts > alpha"));
+    assert!(!out.contains("× src/a.test.ts > beta"));
+    assert!(!out.contains("× src/b.test.ts > gamma"));
+    let index = out
+        .lines()
+        .skip_while(|line| !line.starts_with("… 2 more failures (--max-reported-failures=1):"))
+        .skip(1)
+        .take(2)
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        index,
+        vec!["× src/a.test.ts › beta", "× src/b.test.ts › gamma"]
+    );
+    assert!(out.contains("Tests     3 failed (3)"));
+}
+
+#[test]
+fn without_a_cap_every_failure_is_detailed() {
+    let root = tempfile::tempdir().unwrap();
+    let out = render(root.path(), None);
+    assert!(out.contains("× src/a.test.ts > alpha"));
+    assert!(out.contains("× src/a.test.ts > beta"));
+    assert!(out.contains("× src/b.test.ts > gamma"));
+    assert!(!out.contains("more failure"));
+}
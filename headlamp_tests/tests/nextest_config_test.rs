@@ -0,0 +1,69 @@
+use headlamp::cargo::nextest_config::{SlowTimeout, parse_profile};
+
+const CONFIG: &str = r#"
+[profile.default]
+retries = 1
+slow-timeout = "60s"
+
+[[profile.default.overrides]]
+filter = "test(/^network::/)"
+retries = { backoff = "exponential", count = 4, delay = "1s" }
+
+[profile.ci]
+slow-timeout = { period = "30s", terminate-after = 4 }
+
+[[profile.ci.overrides]]
+filter = "package(db)"
+slow-timeout = { period = "2m", terminate-after = 2 }
+"#;
+
+#[test]
+fn a_named_profile_layers_over_the_default_profile() {
+    let ci = parse_profile(CONFIG, "ci");
+    assert_eq!(ci.retries, 1);
+    assert_eq!(
+        ci.slow_timeout,
+        Some(SlowTimeout {
+            period: "30s".to_string(),
+            terminate_after: Some(4),
+        })
+    );
+    let filters = ci
+        .overrides
+        .iter()
+        .map(|o| o.filter.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(filters, vec!["package(db)", "test(/^network::/)"]);
+    assert_eq!(ci.overrides[1].retries, Some(4));
+    assert!(ci.may_retry() && ci.may_time_out());
+
+    let default = parse_profile(CONFIG, "default");
+    assert_eq!(default.overrides.len(), 1);
+    assert!(!default.may_time_out());
+}
+
+#[test]
+fn the_status_level_asks_for_the_lines_the_profile_can_produce() {
+    assert_eq!(parse_profile("", "default").status_level(&[]), "none");
+    assert_eq!(
+        parse_profile("", "default").status_level(&["--retries=2".to_string()]),
+        "retry"
+    );
+    assert_eq!(parse_profile(CONFIG, "ci").status_level(&[]), "retry");
+    let timeouts_only = r#"
+[profile.default]
+slow-timeout = { period = "10s", terminate-after = 3 }
+"#;
+    assert_eq!(
+        parse_profile(timeouts_only, "default").status_level(&[]),
+        "fail"
+    );
+}
+
+#[test]
+fn nextest_profile_is_a_flag_and_a_config_key() {
+    let argv = vec!["--nextest-profile=ci".to_string()];
+    let parsed = headlamp::args::derive_args(&[], &argv, false);
+    assert_eq!(parsed.nextest_profile.as_deref(), Some("ci"));
+    assert_eq!(headlamp::cargo::nextest_config::profile_name(&parsed), "ci");
+}
@@ -33,6 +33,7 @@ fn nextest_stream_parser_emits_updates_and_finalizes() {
         "expected a non-zero duration for at least one test update"
     );
 
+    assert_eq!(parser.started_tests(), 2);
     let model = parser.finalize().expect("model");
     assert_eq!(model.test_results.len(), 1);
     assert_eq!(model.test_results[0].test_results.len(), 2);
@@ -99,3 +100,82 @@ fn nextest_parser_sets_location_when_panic_location_matches_suite_file() {
     assert_eq!(loc.line, 7);
     assert_eq!(loc.column, 2);
 }
+
+#[test]
+fn nextest_status_lines_mark_retried_tests_flaky_and_killed_tests_timed_out() {
+    let repo_root = Path::new("/repo");
+    let mut parser = NextestStreamParser::new(repo_root);
+    let lines = [
+        r#"{"type":"suite","event":"started","test_count":3,"nextest":{"crate":"parity_sum","test_binary":"sum_test","kind":"test"}}"#,
+        "   TRY 1 FAIL [   0.004s] parity_sum::sum_test sum_flaky",
+        "  RETRY 2/3 [         ] parity_sum::sum_test sum_flaky",
+        "   TRY 2 PASS [   0.003s] parity_sum::sum_test sum_flaky",
+        r#"{"type":"test","event":"ok","name":"parity_sum::sum_test$sum_flaky","exec_time":0.003}"#,
+        r#"{"type":"test","event":"ok","name":"parity_sum::sum_test$sum_passes","exec_time":0.001}"#,
+        "     TIMEOUT [  60.002s] parity_sum::sum_test sum_hangs",
+        r#"{"type":"test","event":"failed","name":"parity_sum::sum_test$sum_hangs","exec_time":60.002}"#,
+    ];
+    lines.iter().for_each(|line| {
+        let _ = parser.push_line(line);
+    });
+    let model = parser.finalize().expect("model");
+    let suite = &model.test_results[0];
+    assert!(
+        suite.console.is_none(),
+        "status lines are not console output"
+    );
+    let test = |name: &str| {
+        suite
+            .test_results
+            .iter()
+            .find(|t| t.full_name == name)
+            .expect(name)
+    };
+    assert_eq!(test("sum_flaky").attempts, Some(2));
+    assert_eq!(test("sum_flaky").status, "passed");
+    assert_eq!(test("sum_passes").attempts, None);
+    assert_eq!(test("sum_hangs").timed_out, Some(true));
+
+    let ctx = headlamp::format::ctx::make_ctx(repo_root, Some(100), false, false, None);
+    let rendered = headlamp::format::stacks::strip_ansi_simple(
+        &headlamp::format::vitest::render_vitest_from_test_model(&model, &ctx, false),
+    );
+    assert!(rendered.contains("1 flaky"), "{rendered}");
+    assert!(
+        rendered.contains("flaky sum_flaky (passed on try 2)"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn nextest_parser_anchors_failures_without_a_panic_location_at_the_test_fn() {
+    let repo_root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(repo_root.path().join("tests")).unwrap();
+    std::fs::write(
+        repo_root.path().join("tests").join("sum_test.rs"),
+        "use std::thread;\n\n#[test]\nfn sum_fails() {\n    thread::spawn(|| panic!()).join().unwrap();\n}\n\nmod nested {\n    #[test]\n    #[ignore = \"slow\"]\n    fn deep_fails() {}\n}\n",
+    )
+    .unwrap();
+    let mut parser = NextestStreamParser::new(repo_root.path());
+    let lines = [
+        r#"{"type":"suite","event":"started","test_count":3,"nextest":{"crate":"parity_sum","test_binary":"sum_test","kind":"test"}}"#,
+        r#"{"type":"test","event":"failed","name":"parity_sum::sum_test$sum_fails","exec_time":0.01,"stdout":"called `Result::unwrap()` on an `Err` value: Any { .. }"}"#,
+        r#"{"type":"test","event":"failed","name":"parity_sum::sum_test$nested::deep_fails","exec_time":0.01}"#,
+        r#"{"type":"test","event":"failed","name":"parity_sum::sum_test$missing","exec_time":0.01}"#,
+    ];
+    lines.iter().for_each(|line| {
+        parser.push_line(line);
+    });
+    let model = parser.finalize().expect("model");
+    let location_of = |name: &str| {
+        model.test_results[0]
+            .test_results
+            .iter()
+            .find(|test| test.full_name == name)
+            .and_then(|test| test.location.as_ref())
+            .map(|loc| (loc.line, loc.column))
+    };
+    assert_eq!(location_of("sum_fails"), Some((4, 1)));
+    assert_eq!(location_of("nested::deep_fails"), Some((11, 5)));
+    assert_eq!(location_of("missing"), None);
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use headlamp::jest_discovery::jest_bin;
+use headlamp::node_toolchain::{
+    jest_command, nvm_node_bin_dir, resolve_jest_bin, volta_pinned_node,
+};
+
+fn touch(path: &Path) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, "").unwrap();
+}
+
+#[test]
+fn jest_bin_walks_up_to_the_workspace_root() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let bin = root
+        .join("node_modules")
+        .join(".bin")
+        .join(if cfg!(windows) { "jest.cmd" } else { "jest" });
+    touch(&bin);
+    let package = root.join("packages").join("web");
+    std::fs::create_dir_all(&package).unwrap();
+    assert_eq!(jest_bin(&package), bin);
+}
+
+#[test]
+fn jest_entry_script_runs_under_node() {
+    let temp = tempfile::tempdir().unwrap();
+    let entry = temp
+        .path()
+        .join("node_modules")
+        .join("jest")
+        .join("bin")
+        .join("jest.js");
+    touch(&entry);
+    assert_eq!(resolve_jest_bin(temp.path()), Some(entry.clone()));
+
+    let command = jest_command(&entry);
+    assert_eq!(command.get_program(), "node");
+    let args = command.get_args().collect::<Vec<_>>();
+    assert_eq!(args, vec![entry.as_os_str()]);
+}
+
+#[test]
+fn yarn_pnp_runs_jest_through_yarn() {
+    let temp = tempfile::tempdir().unwrap();
+    touch(&temp.path().join(".pnp.cjs"));
+    let entry = resolve_jest_bin(temp.path()).unwrap();
+    let command = jest_command(&entry);
+    let args = command
+        .get_args()
+        .map(|a| a.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(args.last().map(String::as_str), Some("jest"));
+}
+
+#[test]
+fn nvm_picks_the_newest_installed_match() {
+    let temp = tempfile::tempdir().unwrap();
+    let versions = temp.path().join("versions").join("node");
+    for version in ["v18.19.0", "v20.9.0", "v20.11.1", "v21.0.0"] {
+        std::fs::create_dir_all(versions.join(version).join("bin")).unwrap();
+    }
+    let bin = |version: &str| Some(versions.join(version).join("bin"));
+    assert_eq!(nvm_node_bin_dir(temp.path(), "v20\n"), bin("v20.11.1"));
+    assert_eq!(nvm_node_bin_dir(temp.path(), "20.9"), bin("v20.9.0"));
+    assert_eq!(nvm_node_bin_dir(temp.path(), "18.19.0"), bin("v18.19.0"));
+    assert_eq!(nvm_node_bin_dir(temp.path(), "22"), None);
+    assert_eq!(nvm_node_bin_dir(temp.path(), "lts/*"), None);
+}
+
+#[test]
+fn volta_pin_is_inherited_from_the_workspace_root() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp.path().join("package.json"),
+        r#"{ "volta": { "node": "20.11.0" } }"#,
+    )
+    .unwrap();
+    let package = temp.path().join("packages").join("web");
+    std::fs::create_dir_all(&package).unwrap();
+    std::fs::write(package.join("package.json"), r#"{ "name": "web" }"#).unwrap();
+    assert_eq!(volta_pinned_node(&package).as_deref(), Some("20.11.0"));
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::deterministic;
+use headlamp::format::time::format_duration;
+use headlamp::test_model::{TestCaseResult, TestRunModel};
+use headlamp::test_support::model::{case, run_model, suite};
+
+fn timed_model() -> TestRunModel {
+    let works = TestCaseResult {
+        duration: 42,
+        ..case("works", "passed")
+    };
+    let mut model = run_model(vec![suite("/repo/src/a.test.ts", vec![works])]);
+    model.start_time = 1_700_000_000_000;
+    model.aggregated.start_time = 1_700_000_000_000;
+    model.aggregated.run_time_ms = Some(1234);
+    model
+}
+
+#[test]
+fn normalize_times_comes_from_cli_and_config_without_implying_deterministic() {
+    let argv = vec!["--normalizeTimes".to_string()];
+    let parsed = derive_args(&[], &argv, true);
+    assert!(parsed.normalize_times);
+    assert!(!parsed.deterministic);
+    assert!(!parsed.quiet);
+
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "normalizeTimes": true }"#).unwrap();
+    assert!(derive_args(&config_tokens(&cfg, &[]), &[], true).normalize_times);
+    assert!(!derive_args(&[], &[], true).normalize_times);
+}
+
+#[test]
+fn normalized_times_render_as_placeholders_and_zero_model_timings() {
+    let mut untouched = timed_model();
+    deterministic::normalize_model_times(&mut untouched);
+    assert_eq!(untouched.aggregated.run_time_ms, Some(1234));
+
+    deterministic::configure_times(true);
+    assert_eq!(format_duration(Duration::from_millis(1234)), "<time>");
+    let mut model = timed_model();
+    deterministic::normalize_model_times(&mut model);
+    deterministic::configure_times(false);
+
+    assert_eq!(format_duration(Duration::from_millis(1234)), "1s 234ms");
+    assert_eq!(model.start_time, 0);
+    assert_eq!(model.aggregated.start_time, 0);
+    assert_eq!(model.aggregated.run_time_ms, Some(0));
+    assert_eq!(model.test_results[0].test_results[0].duration, 0);
+    assert_eq!(model.test_results[0].test_results[0].title, "works");
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::infra_failure::build_infra_failure_test_run_model;
+use headlamp::hooks::run_with_hooks;
+use headlamp::notify::{
+    NotifyPayload, NotifyTarget, notify_run_completed, parse_notify_target, slack_curl_config,
+    validate_notify_targets,
+};
+use headlamp::process::RunEnvironment;
+use headlamp::recorded::{RecordedResults, record_failed_tests};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-notify-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn results_with_failures(count: usize) -> RecordedResults {
+    RecordedResults {
+        passed: 40,
+        failed: count as u64,
+        skipped: 1,
+        failed_tests: (0..count)
+            .map(|i| (format!("tests/t{i}.test.js"), Some(format!("case {i}"))))
+            .collect(),
+        coverage_lines: None,
+        duplicates: vec![],
+    }
+}
+
+#[test]
+fn notify_targets_parse_and_bad_ones_are_rejected_up_front() {
+    assert_eq!(parse_notify_target("desktop"), Some(NotifyTarget::Desktop));
+    assert_eq!(
+        parse_notify_target("slack:https://hooks.slack.com/services/T/B/X"),
+        Some(NotifyTarget::Slack(
+            "https://hooks.slack.com/services/T/B/X".to_string()
+        ))
+    );
+    assert_eq!(
+        parse_notify_target("cmd:./scripts/notify.sh --team qa"),
+        Some(NotifyTarget::Command(
+            "./scripts/notify.sh --team qa".to_string()
+        ))
+    );
+    assert_eq!(parse_notify_target("slack:http://insecure"), None);
+    assert_eq!(parse_notify_target("cmd:"), None);
+    assert!(validate_notify_targets(&["desktop".to_string()]).is_ok());
+    let err = validate_notify_targets(&["email".to_string()]).unwrap_err();
+    assert!(err.to_string().contains("invalid --notify target: email"));
+}
+
+#[test]
+fn slack_webhooks_reach_curl_through_its_stdin_config() {
+    let config = slack_curl_config(
+        "https://hooks.slack.com/services/T/B/X",
+        r#"{"text":"2 failed: \"adds\""}"#,
+    );
+    assert_eq!(
+        config.lines().collect::<Vec<_>>(),
+        vec![
+            r#"url = "https://hooks.slack.com/services/T/B/X""#,
+            r#"data-binary = "{\"text\":\"2 failed: \\\"adds\\\"\"}""#,
+        ]
+    );
+}
+
+#[test]
+fn notify_comes_from_config_and_repeatable_flags() {
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "notify": ["desktop"] }"#).unwrap();
+    let argv = ["--notify", "cmd:echo done"].map(str::to_string);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.notify, vec!["desktop", "cmd:echo done"]);
+    assert!(parsed.runner_args.is_empty());
+}
+
+#[test]
+fn payload_message_lists_a_bounded_number_of_failures() {
+    let payload = NotifyPayload::new(&results_with_failures(12), 1, Duration::from_secs(63));
+    assert_eq!(
+        payload.headline(),
+        "headlamp failed: 12 failed, 40 passed, 1 skipped in 1m 3s"
+    );
+    let message = payload.message();
+    assert_eq!(message.lines().count(), 12);
+    assert!(message.contains("\n✗ tests/t0.test.js > case 0\n"));
+    assert!(message.ends_with("…and 2 more"));
+
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(json["status"], "failed");
+    assert_eq!(json["duration_ms"], 63_000);
+    assert_eq!(json["failed_tests"].as_array().unwrap().len(), 12);
+    assert_eq!(json["failed_tests"][11]["name"], "case 11");
+}
+
+#[cfg(unix)]
+#[test]
+fn cmd_target_receives_the_run_payload_on_stdin() {
+    let repo_root = mk_temp_dir("cmd_target_receives_the_run_payload_on_stdin");
+    let argv = ["--notify=cmd:cat > payload.json"].map(str::to_string);
+    let parsed = derive_args(&[], &argv, false);
+    let run_env = RunEnvironment::default();
+    headlamp::recorded::begin();
+    let exit_code = run_with_hooks(&repo_root, &parsed, &run_env, || {
+        record_failed_tests(&build_infra_failure_test_run_model(
+            "tests/a.test.js",
+            "adds",
+            "boom",
+        ));
+        1
+    });
+    notify_run_completed(
+        &repo_root,
+        &parsed,
+        &run_env,
+        exit_code,
+        Duration::from_millis(1500),
+    );
+    let payload: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(repo_root.join("payload.json")).unwrap())
+            .unwrap();
+    assert_eq!(payload["exit_code"], 1);
+    assert_eq!(payload["failed"], 1);
+    assert_eq!(payload["duration_ms"], 1500);
+    assert_eq!(payload["failed_tests"][0]["file"], "tests/a.test.js");
+    assert_eq!(payload["failed_tests"][0]["name"], "adds");
+}
@@ -23,6 +23,7 @@ fn empty_success_model() -> TestRunModel {
             success: true,
             run_time_ms: Some(0),
         },
+        skipped_units: vec![],
     }
 }
 
@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use headlamp::orchestrate::{
+    FileTimings, OrchestrateOptions, child_args, discover_test_files, merge_models, orchestrate,
+    split_into_shards,
+};
+use headlamp::runner::Runner;
+use headlamp::test_model::{TestCaseResult, TestRunModel};
+use headlamp::test_support::model::{case, failed_case, run_model, suite};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+fn model(test_file: &str, duration: u64, failed: bool) -> TestRunModel {
+    let case = if failed {
+        failed_case("works", "Error: nope")
+    } else {
+        case("works", "passed")
+    };
+    run_model(vec![suite(
+        test_file,
+        vec![TestCaseResult { duration, ..case }],
+    )])
+}
+
+#[test]
+fn options_split_the_selection_from_the_forwarded_flags() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "src/a.test.ts", "");
+    let parse = |argv: &[&str]| OrchestrateOptions::parse(dir.path(), &strings(argv));
+    let options = parse(&[
+        "--shards=3",
+        "--launcher",
+        "./ci/shard.sh",
+        "--only-failures",
+        "src",
+        "--",
+        "--ci",
+    ])
+    .unwrap();
+    assert_eq!(options.shards, 3);
+    assert_eq!(options.launcher.as_deref(), Some("./ci/shard.sh"));
+    assert_eq!(options.paths, vec![dir.path().join("src")]);
+    assert_eq!(options.args, strings(&["--only-failures"]));
+    assert_eq!(options.passthrough, strings(&["--ci"]));
+    assert!(parse(&["src"]).is_err());
+    assert!(parse(&["--shards=0"]).is_err());
+    assert!(parse(&["--shards=2", "--runner=nope"]).is_err());
+
+    let args = child_args(
+        &options,
+        Runner::Jest,
+        &strings(&["src/a.test.ts"]),
+        Path::new("/tmp/out"),
+    );
+    assert_eq!(
+        args,
+        strings(&[
+            "--runner=jest",
+            "--only-failures",
+            "--artifacts-dir=/tmp/out",
+            "src/a.test.ts",
+            "--",
+            "--ci",
+        ])
+    );
+}
+
+#[test]
+fn test_files_are_found_by_name_for_each_language() {
+    let dir = tempfile::tempdir().unwrap();
+    for rel in [
+        "src/a.test.ts",
+        "src/__tests__/b.tsx",
+        "src/c.ts",
+        "tests/test_d.py",
+        "pkg/e_test.py",
+        "pkg/helpers.py",
+        "crates/x/tests/f.rs",
+        "crates/x/src/lib.rs",
+        "node_modules/dep/g.test.js",
+    ] {
+        write(dir.path(), rel, "");
+    }
+    assert_eq!(
+        discover_test_files(dir.path(), &[]),
+        strings(&[
+            "crates/x/tests/f.rs",
+            "pkg/e_test.py",
+            "src/__tests__/b.tsx",
+            "src/a.test.ts",
+            "tests/test_d.py",
+        ])
+    );
+    assert_eq!(
+        discover_test_files(dir.path(), &[dir.path().join("src")]),
+        strings(&["src/__tests__/b.tsx", "src/a.test.ts"])
+    );
+}
+
+#[test]
+fn shards_are_balanced_by_recorded_timings() {
+    let timings = FileTimings {
+        ms_by_file: BTreeMap::from([
+            ("slow.test.ts".to_string(), 9_000),
+            ("mid.test.ts".to_string(), 5_000),
+            ("quick.test.ts".to_string(), 4_000),
+        ]),
+    };
+    let files = strings(&[
+        "mid.test.ts",
+        "new.test.ts",
+        "quick.test.ts",
+        "slow.test.ts",
+    ]);
+    let shards = split_into_shards(&files, &timings, 2);
+    // Files without a timing count as the mean of the recorded ones: 6s for new.test.ts.
+    assert_eq!(shards[0].files, strings(&["quick.test.ts", "slow.test.ts"]));
+    assert_eq!(shards[1].files, strings(&["mid.test.ts", "new.test.ts"]));
+    assert_eq!(
+        (shards[0].estimated_ms, shards[1].estimated_ms),
+        (13_000, 11_000)
+    );
+    assert_eq!(split_into_shards(&files, &timings, 2), shards);
+
+    let empty = split_into_shards(&strings(&["a.test.ts"]), &FileTimings::default(), 3);
+    assert_eq!(empty.len(), 3);
+    assert!(empty[1].files.is_empty() && empty[2].files.is_empty());
+}
+
+#[test]
+fn shard_reports_merge_into_one_model() {
+    let merged = merge_models(&[
+        model("/repo/b.test.ts", 30, true),
+        model("/repo/a.test.ts", 50, false),
+    ])
+    .unwrap();
+    assert_eq!(
+        merged
+            .test_results
+            .iter()
+            .map(|suite| suite.test_file_path.as_str())
+            .collect::<Vec<_>>(),
+        vec!["/repo/a.test.ts", "/repo/b.test.ts"]
+    );
+    assert_eq!(merged.aggregated.num_total_tests, 2);
+    assert_eq!(merged.aggregated.num_failed_test_suites, 1);
+    assert!(!merged.aggregated.success);
+    assert_eq!(merged.aggregated.run_time_ms, Some(50));
+    assert!(merge_models(&[]).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn a_launcher_runs_every_shard_and_its_reports_decide_the_exit_code() {
+    let cache = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    write(repo.path(), "src/a.test.ts", "");
+    write(repo.path(), "src/b.test.ts", "");
+    let fixture = |name: &str, failed| {
+        let json = serde_json::to_string(&model(&format!("src/{name}.test.ts"), 20, failed));
+        write(
+            repo.path(),
+            &format!("fixtures/{name}.json"),
+            &json.unwrap(),
+        );
+    };
+    fixture("a", false);
+    fixture("b", true);
+    let index = r#"{"schema_version":1,"artifacts":[{"kind":"report","runner":"jest","source":"x","path":"reports/jest/results.json","bytes":0}]}"#;
+    write(repo.path(), "fixtures/index.json", index);
+    // Shard 1 gets a.test.ts, shard 2 b.test.ts; each launcher copies its shard's report.
+    let launcher = r#"mkdir -p "$HEADLAMP_SHARD_OUT/reports/jest" && \
+        cp fixtures/index.json "$HEADLAMP_SHARD_OUT/" && \
+        if [ "$HEADLAMP_SHARD_INDEX" = 1 ]; then f=a; else f=b; fi && \
+        echo "$HEADLAMP_SHARD_ARGS" | grep -q "src/$f.test.ts" && \
+        cp "fixtures/$f.json" "$HEADLAMP_SHARD_OUT/reports/jest/results.json""#;
+    let options = OrchestrateOptions {
+        shards: 2,
+        launcher: Some(launcher.to_string()),
+        ..OrchestrateOptions::default()
+    };
+    assert_eq!(orchestrate(repo.path(), cache.path(), &options), 1);
+    let recorded = headlamp::recorded::recorded_results();
+    assert!(
+        recorded
+            .failed_tests
+            .iter()
+            .any(|(file, name)| file.ends_with("src/b.test.ts") && name.as_deref() == Some("works"))
+    );
+    assert_eq!(
+        FileTimings::load(cache.path(), repo.path()).ms_by_file,
+        BTreeMap::from([
+            ("src/a.test.ts".to_string(), 20),
+            ("src/b.test.ts".to_string(), 20),
+        ])
+    );
+
+    let failing_launcher = OrchestrateOptions {
+        launcher: Some("exit 3".to_string()),
+        ..options
+    };
+    assert_eq!(orchestrate(repo.path(), cache.path(), &failing_launcher), 3);
+}
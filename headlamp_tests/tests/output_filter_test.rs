@@ -0,0 +1,81 @@
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::config::schema::validate_config_value;
+use headlamp::format::console::ConsoleEntry;
+use headlamp::format::output_filter;
+
+fn console_entry(message: &str) -> ConsoleEntry {
+    ConsoleEntry {
+        type_name: Some("warn".to_string()),
+        message: Some(message.to_string()),
+        origin: None,
+        test_path: None,
+        current_test_name: None,
+    }
+}
+
+#[test]
+fn output_filters_come_from_cli_and_config() {
+    let argv = vec![
+        "--output-filter=^Error: Not implemented: navigation".to_string(),
+        "--outputCollapse=future-incompat".to_string(),
+    ];
+    let parsed = derive_args(&[], &argv, false);
+    assert_eq!(
+        parsed.output_filters,
+        vec!["^Error: Not implemented: navigation".to_string()]
+    );
+    assert_eq!(parsed.output_collapse, vec!["future-incompat".to_string()]);
+
+    let raw = serde_json::json!({
+        "outputFilters": ["jsdom", { "pattern": "future-incompat", "collapse": true }],
+    });
+    let (issues, _) = validate_config_value(&raw);
+    assert!(issues.is_empty(), "{issues:?}");
+    let cfg: HeadlampConfig = serde_json::from_value(raw).unwrap();
+    let parsed = derive_args(&config_tokens(&cfg, &[]), &[], false);
+    assert_eq!(parsed.output_filters, vec!["jsdom".to_string()]);
+    assert_eq!(parsed.output_collapse, vec!["future-incompat".to_string()]);
+}
+
+#[test]
+fn filtered_lines_are_dropped_or_collapsed_and_counted() {
+    assert!(output_filter::configure(&["(".to_string()], &[]).is_err());
+    output_filter::configure(
+        &["^Error: Not implemented: navigation".to_string()],
+        &["future-incompat".to_string()],
+    )
+    .unwrap();
+
+    assert!(output_filter::suppresses(
+        "\u{1b}[31mError: Not implemented: navigation (except hash changes)\u{1b}[0m"
+    ));
+    assert!(!output_filter::suppresses(
+        "warning: the following packages contain code that will be rejected (future-incompat)"
+    ));
+    assert!(output_filter::suppresses("note: future-incompat report"));
+    assert!(!output_filter::suppresses("test result: ok"));
+    assert_eq!(output_filter::suppressed_count(), 2);
+
+    let kept = output_filter::filter_console(vec![
+        console_entry("Error: Not implemented: navigation"),
+        console_entry("future-incompat #1"),
+        console_entry("future-incompat #2"),
+        console_entry("user log"),
+    ]);
+    let messages = kept
+        .iter()
+        .filter_map(|entry| entry.message.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(messages, vec!["future-incompat #1", "user log"]);
+    assert_eq!(output_filter::suppressed_count(), 4);
+
+    output_filter::start_run();
+    assert_eq!(output_filter::suppressed_count(), 0);
+    assert!(!output_filter::suppresses("future-incompat again"));
+
+    output_filter::configure(&[], &[]).unwrap();
+    assert!(!output_filter::suppresses(
+        "Error: Not implemented: navigation"
+    ));
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use headlamp::owners::{OwnerKind, TestOwner, owners, render_owners};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn owner(kind: OwnerKind, name: &str) -> Option<TestOwner> {
+    Some(TestOwner {
+        kind,
+        name: name.to_string(),
+    })
+}
+
+#[test]
+fn tests_of_a_source_file_are_listed_with_their_jest_project() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(repo.path()).unwrap();
+    write(
+        &root,
+        "jest.config.js",
+        "module.exports = { projects: ['<rootDir>/packages/*'] };\n",
+    );
+    write(
+        &root,
+        "packages/web/jest.config.js",
+        "module.exports = { displayName: 'web' };\n",
+    );
+    write(
+        &root,
+        "packages/web/src/sum.js",
+        "export function sum(a, b) {\n  return a + b;\n}\n",
+    );
+    write(
+        &root,
+        "packages/web/src/sum.test.js",
+        "import { sum } from './sum';\n\ntest('adds', () => {\n  expect(sum(1, 2)).toBe(3);\n});\n",
+    );
+    write(
+        &root,
+        "packages/web/src/other.test.js",
+        "test('x', () => {});\n",
+    );
+
+    let found = owners(&root, Path::new("packages/web/src/sum.js"));
+    assert_eq!(found.file, "packages/web/src/sum.js");
+    assert_eq!(
+        found
+            .tests
+            .iter()
+            .map(|test| (test.file.as_str(), test.owner.clone()))
+            .collect::<Vec<_>>(),
+        vec![(
+            "packages/web/src/sum.test.js",
+            owner(OwnerKind::JestProject, "packages/web/jest.config.js")
+        )]
+    );
+    assert_eq!(
+        render_owners(&found),
+        "packages/web/src/sum.js: 1 test file(s)\n  packages/web/src/sum.test.js  jest project: \
+         packages/web/jest.config.js"
+    );
+}
+
+#[test]
+fn python_tests_are_owned_by_their_package() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(repo.path()).unwrap();
+    write(&root, "svc/pyproject.toml", "[project]\nname = \"svc\"\n");
+    write(&root, "svc/calc.py", "def add(a, b):\n    return a + b\n");
+    write(
+        &root,
+        "svc/tests/test_calc.py",
+        "from calc import add\n\ndef test_add():\n    assert add(1, 2) == 3\n",
+    );
+
+    let found = owners(&root, &root.join("svc/calc.py"));
+    assert_eq!(found.tests.len(), 1);
+    assert_eq!(found.tests[0].file, "svc/tests/test_calc.py");
+    assert_eq!(found.tests[0].owner, owner(OwnerKind::PythonPackage, "svc"));
+
+    let untested = owners(&root, Path::new("svc/pyproject.toml"));
+    assert!(untested.tests.is_empty());
+    assert_eq!(
+        render_owners(&untested),
+        "svc/pyproject.toml: no test files exercise it"
+    );
+}
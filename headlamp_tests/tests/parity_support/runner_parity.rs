@@ -300,6 +300,7 @@ fn build_model(repo: &Path, scenario: &RunnerParityScenario) -> TestRunModel {
         start_time: 0,
         test_results: vec![suite],
         aggregated: aggregated_for_should_fail(scenario.should_fail),
+        skipped_units: vec![],
     }
 }
 
@@ -321,6 +322,8 @@ fn passing_case(passing_test_name: &str) -> TestCaseResult {
         location: None,
         failure_messages: vec![],
         failure_details: None,
+        repro: None,
+        skip_reason: None,
     }
 }
 
@@ -340,6 +343,8 @@ fn failing_case(scenario: &RunnerParityScenario) -> TestCaseResult {
         location: None,
         failure_messages,
         failure_details: None,
+        repro: None,
+        skip_reason: None,
     }
 }
 
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::coverage::model::{CoverageReport, FileCoverage};
+use headlamp::coverage::print::{PrintOpts, format_compact};
+use headlamp::format::path_display::{PathDisplay, PathStyle};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn monorepo() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), "packages/api/package.json", "{}");
+    write(root.path(), "packages/api/src/routes/users.test.ts", "");
+    write(root.path(), "tools/check.test.ts", "");
+    root
+}
+
+#[test]
+fn path_style_and_strip_prefixes_are_parsed_from_flags() {
+    let parse = |argv: &[&str]| {
+        let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let args = derive_args(&[], &argv, false);
+        (args.path_style, args.strip_path_prefixes)
+    };
+    assert_eq!(parse(&[]), (PathStyle::Relative, vec![]));
+    assert_eq!(
+        parse(&[
+            "--path-style=package-relative",
+            "--strip-path-prefix=packages/*/src/",
+            "--stripPathPrefix",
+            "lib/",
+        ]),
+        (
+            PathStyle::PackageRelative,
+            vec!["packages/*/src/".to_string(), "lib/".to_string()]
+        )
+    );
+    assert_eq!(parse(&["--pathStyle", "absolute"]).0, PathStyle::Absolute);
+}
+
+#[test]
+fn each_style_shows_paths_relative_to_its_base() {
+    let repo = monorepo();
+    let root = repo.path().to_string_lossy().to_string();
+    let abs = format!("{root}/packages/api/src/routes/users.test.ts");
+    let show = |style| PathDisplay::new(style, &[]).display(&abs, &root);
+    assert_eq!(
+        show(PathStyle::Relative),
+        "packages/api/src/routes/users.test.ts"
+    );
+    assert_eq!(show(PathStyle::Absolute), abs);
+    assert_eq!(show(PathStyle::PackageRelative), "src/routes/users.test.ts");
+    assert_eq!(
+        PathDisplay::new(PathStyle::PackageRelative, &[]).display("tools/check.test.ts", &root),
+        "tools/check.test.ts"
+    );
+    assert_eq!(
+        PathDisplay::new(PathStyle::Absolute, &[]).display("tools/check.test.ts", &root),
+        format!("{root}/tools/check.test.ts")
+    );
+}
+
+#[test]
+fn strip_prefixes_match_globs_at_the_start_and_keep_the_file_name() {
+    let display = PathDisplay::new(
+        PathStyle::Relative,
+        &["packages/*/src/".to_string(), "tools/**/".to_string()],
+    );
+    assert_eq!(
+        display.display("packages/api/src/routes/users.test.ts", "/repo"),
+        "routes/users.test.ts"
+    );
+    assert_eq!(
+        display.display("/repo/tools/a/b/check.test.ts", "/repo"),
+        "check.test.ts"
+    );
+    assert_eq!(
+        display.display("src/packages/x.ts", "/repo"),
+        "src/packages/x.ts"
+    );
+}
+
+#[test]
+fn coverage_tables_use_the_path_display() {
+    let repo = monorepo();
+    let file = FileCoverage {
+        path: repo
+            .path()
+            .join("packages/api/src/routes/users.ts")
+            .to_string_lossy()
+            .to_string(),
+        lines_total: 4,
+        lines_covered: 3,
+        statements_total: None,
+        statements_covered: None,
+        statement_hits: None,
+        uncovered_lines: vec![2],
+        line_hits: Default::default(),
+        function_hits: Default::default(),
+        function_map: Default::default(),
+        branch_hits: Default::default(),
+        branch_map: Default::default(),
+    };
+    let opts = PrintOpts {
+        max_files: None,
+        max_hotspots: None,
+        page_fit: false,
+        tty: false,
+        editor_cmd: None,
+        path_display: PathDisplay::new(PathStyle::Relative, &["packages/*/src/".to_string()]),
+    };
+    let report = CoverageReport { files: vec![file] };
+    let table = format_compact(&report, &opts, repo.path());
+    assert!(
+        table
+            .lines()
+            .any(|line| line.ends_with("  routes/users.ts")),
+        "{table}"
+    );
+}
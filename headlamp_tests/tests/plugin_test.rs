@@ -0,0 +1,217 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::args::derive_args;
+use headlamp::plugin::{PluginAdapter, discover_plugin, parse_event};
+use headlamp::rerun::{FailedTest, RerunRunner, rerun_args};
+use headlamp::run::RunError;
+use headlamp::streaming::{OutputStream, StreamAction, StreamAdapter};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-plugin-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+const HELLO: &str = r#"{"event":"hello","protocol":1}"#;
+
+fn test_case(path: &str, name: &str, status: &str) -> String {
+    serde_json::json!({
+        "event": "testCase",
+        "testPath": path,
+        "result": {
+            "title": name,
+            "fullName": name,
+            "status": status,
+            "timedOut": null,
+            "duration": 3,
+            "location": null,
+            "failureMessages": if status == "failed" { vec!["boom"] } else { vec![] },
+            "failureDetails": null,
+        },
+    })
+    .to_string()
+}
+
+fn feed(adapter: &mut PluginAdapter, stream: OutputStream, lines: &[&str]) -> Vec<StreamAction> {
+    lines
+        .iter()
+        .flat_map(|line| adapter.on_line(stream, line))
+        .collect()
+}
+
+#[test]
+fn manifests_are_found_in_the_repo_and_resolve_relative_programs() {
+    let repo_root = mk_temp_dir("manifest_discovery");
+    write_file(
+        &repo_root.join(".headlamp/plugins/gradle.json"),
+        r#"{"name":"gradle","protocol":1,"command":["./bin/run","--fast"]}"#,
+    );
+    let manifest = discover_plugin(&repo_root, "gradle").unwrap();
+    assert_eq!(
+        manifest.program(),
+        repo_root.join(".headlamp/plugins/./bin/run")
+    );
+    assert_eq!(manifest.args(), ["--fast".to_string()]);
+
+    let missing = discover_plugin(&repo_root, "no-such-plugin").unwrap_err();
+    assert!(
+        matches!(missing, RunError::MissingRunner { runner, .. } if runner == "plugin:no-such-plugin")
+    );
+}
+
+#[test]
+fn manifests_for_another_protocol_or_without_a_command_are_rejected() {
+    let repo_root = mk_temp_dir("manifest_rejected");
+    write_file(
+        &repo_root.join(".headlamp/plugins/future.json"),
+        r#"{"name":"future","protocol":2,"command":["run"]}"#,
+    );
+    write_file(
+        &repo_root.join(".headlamp/plugins/empty.json"),
+        r#"{"name":"empty","protocol":1,"command":[]}"#,
+    );
+    let future = discover_plugin(&repo_root, "future")
+        .unwrap_err()
+        .to_string();
+    assert!(future.contains("protocol 2"), "{future}");
+    let empty = discover_plugin(&repo_root, "empty")
+        .unwrap_err()
+        .to_string();
+    assert!(empty.contains("`command` is empty"), "{empty}");
+}
+
+#[test]
+fn events_drive_live_output_and_build_the_model() {
+    let mut adapter = PluginAdapter::new("demo", false);
+    let passed = test_case("tests/a_test.kt", "adds", "passed");
+    let failed = test_case("tests/a_test.kt", "subtracts", "failed");
+    let actions = feed(
+        &mut adapter,
+        OutputStream::Events,
+        &[
+            HELLO,
+            r#"{"event":"progress","label":"compiling"}"#,
+            r#"{"event":"print","text":"warming up","stderr":true}"#,
+            &passed,
+            &failed,
+        ],
+    );
+    assert!(matches!(&actions[0], StreamAction::SetProgressLabel(label) if label == "compiling"));
+    assert!(matches!(&actions[1], StreamAction::PrintStderr(text) if text == "warming up"));
+    assert_eq!(
+        actions
+            .iter()
+            .filter(|action| matches!(action, StreamAction::RecordOutcome(_)))
+            .count(),
+        2
+    );
+
+    let model = adapter.finish(1).unwrap();
+    assert_eq!(model.test_results.len(), 1);
+    assert_eq!(model.test_results[0].status, "failed");
+    assert_eq!(model.aggregated.num_failed_tests, 1);
+    assert_eq!(model.aggregated.num_passed_tests, 1);
+}
+
+#[test]
+fn stdout_carries_events_when_there_is_no_event_pipe() {
+    let mut adapter = PluginAdapter::new("demo", false);
+    let passed = test_case("spec/a.rb", "works", "passed");
+    feed(
+        &mut adapter,
+        OutputStream::Stdout,
+        &[HELLO, "plain output", "{not json", &passed],
+    );
+    let model = adapter.finish(0).unwrap();
+    assert_eq!(model.aggregated.num_passed_tests, 1);
+    assert!(parse_event("plain output").is_none());
+}
+
+#[test]
+fn the_handshake_must_come_first_and_match() {
+    let mut mismatched = PluginAdapter::new("demo", false);
+    feed(
+        &mut mismatched,
+        OutputStream::Events,
+        &[r#"{"event":"hello","protocol":99}"#],
+    );
+    let message = mismatched.finish(0).unwrap_err().to_string();
+    assert!(message.contains("protocol 99"), "{message}");
+
+    let mut early = PluginAdapter::new("demo", false);
+    feed(
+        &mut early,
+        OutputStream::Events,
+        &[r#"{"event":"progress","label":"x"}"#, HELLO],
+    );
+    let message = early.finish(0).unwrap_err().to_string();
+    assert!(message.contains("before its hello"), "{message}");
+
+    let mut silent = PluginAdapter::new("demo", false);
+    feed(&mut silent, OutputStream::Stderr, &["command not found"]);
+    let message = silent.finish(127).unwrap_err().to_string();
+    assert!(message.contains("exited with 127"), "{message}");
+    assert!(message.contains("command not found"), "{message}");
+}
+
+#[test]
+fn reruns_give_plugins_the_failed_files_and_a_name_pattern() {
+    let parsed = derive_args(&[], &[], false);
+    let failed = [
+        FailedTest {
+            test_file_path: "src/test/AddTest.kt".to_string(),
+            full_name: Some("adds".to_string()),
+        },
+        FailedTest {
+            test_file_path: "tests/sum.test.ts".to_string(),
+            full_name: Some("sums".to_string()),
+        },
+    ];
+    let rerun = rerun_args(&parsed, &failed, RerunRunner::Plugin);
+    assert_eq!(
+        rerun.selection_paths,
+        vec!["src/test/AddTest.kt", "tests/sum.test.ts"]
+    );
+    assert_eq!(rerun.runner_args[0], "-t");
+}
+
+#[cfg(unix)]
+#[test]
+fn a_script_plugin_runs_end_to_end() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo_root = mk_temp_dir("script_plugin");
+    let script = repo_root.join(".headlamp/plugins/run.sh");
+    let case = test_case("checks/lint", "no warnings", "failed").replace('\'', "");
+    write_file(
+        &script,
+        &format!(
+            "#!/bin/sh\necho '{HELLO}' >&${{HEADLAMP_EVENTS_FD:-1}}\n\
+             echo \"$HEADLAMP_PLUGIN_REQUEST\" > request.json\n\
+             echo '{case}' >&${{HEADLAMP_EVENTS_FD:-1}}\n"
+        ),
+    );
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    write_file(
+        &repo_root.join(".headlamp/plugins/lint.json"),
+        r#"{"name":"lint","protocol":1,"command":["./run.sh"]}"#,
+    );
+    let parsed = derive_args(&[], &["--ci".to_string()], false);
+    let session = headlamp::session::RunSession::new(false).unwrap();
+
+    let exit_code = headlamp::plugin::run_plugin(&repo_root, "lint", &parsed, &session).unwrap();
+    assert_eq!(exit_code, 1, "a zero exit with a failed test still fails");
+    let request: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(repo_root.join("request.json")).unwrap())
+            .unwrap();
+    assert_eq!(request["protocol"], 1);
+    assert_eq!(request["ci"], true);
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use headlamp::args::{derive_args, expand_presets};
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+fn presets() -> BTreeMap<String, Vec<String>> {
+    BTreeMap::from([
+        ("base".to_string(), strings(&["--sequential", "--bail"])),
+        (
+            "ci".to_string(),
+            strings(&["--preset=base", "--ci", "--coverage-ui=jest"]),
+        ),
+        ("local-fast".to_string(), strings(&["--quiet"])),
+        ("loop-a".to_string(), strings(&["--preset", "loop-b"])),
+        ("loop-b".to_string(), strings(&["--preset=loop-a"])),
+    ])
+}
+
+#[test]
+fn presets_expand_in_order_ahead_of_the_command_line() {
+    let argv = strings(&["--preset", "ci", "--preset=local-fast", "src/a.test.ts"]);
+    let expanded = expand_presets(Some(&presets()), &argv).unwrap();
+    assert_eq!(
+        expanded,
+        strings(&[
+            "--sequential",
+            "--bail",
+            "--ci",
+            "--coverage-ui=jest",
+            "--quiet",
+            "--preset",
+            "ci",
+            "--preset=local-fast",
+            "src/a.test.ts",
+        ])
+    );
+}
+
+#[test]
+fn command_line_flags_override_preset_flags() {
+    let argv = strings(&["--preset=ci", "--sequential=false", "--coverage-ui=both"]);
+    let expanded = expand_presets(Some(&presets()), &argv).unwrap();
+    let parsed = derive_args(&[], &expanded, false);
+    assert!(parsed.ci);
+    assert!(parsed.bail);
+    assert!(!parsed.sequential);
+    assert_eq!(format!("{:?}", parsed.coverage_ui), "Both");
+    assert_eq!(parsed.presets, strings(&["ci"]));
+    assert!(parsed.selection_paths.is_empty());
+}
+
+#[test]
+fn preset_names_after_double_dash_belong_to_the_runner() {
+    let argv = strings(&["--", "--preset=ci"]);
+    assert_eq!(expand_presets(None, &argv).unwrap(), argv);
+}
+
+#[test]
+fn unknown_presets_and_cycles_are_errors() {
+    let unknown = expand_presets(Some(&presets()), &strings(&["--preset=cl"])).unwrap_err();
+    assert_eq!(unknown, "unknown preset `cl`; did you mean `ci`?");
+    let none = expand_presets(None, &strings(&["--preset=ci"])).unwrap_err();
+    assert!(none.contains("defines no presets"));
+    let cycle = expand_presets(Some(&presets()), &strings(&["--preset=loop-a"])).unwrap_err();
+    assert_eq!(cycle, "preset cycle: loop-a -> loop-b -> loop-a");
+}
@@ -0,0 +1,58 @@
+use std::process::Command;
+
+use headlamp::args::derive_args;
+use headlamp::print_commands::{
+    PrintCommandsFormat, intercept, is_active, plan_and_print, planned_command, render_planned,
+};
+
+fn sample_command() -> Command {
+    let mut command = Command::new("cargo");
+    command
+        .args(["test", "--", "needs quoting"])
+        .current_dir("/repo")
+        .env("CI", "1")
+        .env_remove("RUSTFLAGS");
+    command
+}
+
+#[test]
+fn print_commands_takes_an_optional_format_but_never_the_next_token() {
+    let human = derive_args(&[], &["--print-commands".to_string()], false);
+    assert_eq!(human.print_commands, Some(PrintCommandsFormat::Human));
+    let json = derive_args(&[], &["--print-commands=json".to_string()], false);
+    assert_eq!(json.print_commands, Some(PrintCommandsFormat::Json));
+    let with_path = derive_args(
+        &[],
+        &["--print-commands".to_string(), "src/a.test.ts".to_string()],
+        false,
+    );
+    assert_eq!(with_path.print_commands, Some(PrintCommandsFormat::Human));
+    assert_eq!(with_path.selection_paths, vec!["src/a.test.ts".to_string()]);
+}
+
+#[test]
+fn planned_commands_render_as_shell_lines_and_as_json() {
+    let planned = vec![planned_command(&sample_command())];
+    assert_eq!(
+        render_planned(&planned, PrintCommandsFormat::Human),
+        "$ cargo test -- 'needs quoting'\n    cwd: /repo\n    env: CI=1 -RUSTFLAGS"
+    );
+    let json: serde_json::Value =
+        serde_json::from_str(&render_planned(&planned, PrintCommandsFormat::Json)).unwrap();
+    assert_eq!(json["commands"][0]["program"], "cargo");
+    assert_eq!(json["commands"][0]["args"][2], "needs quoting");
+    assert_eq!(json["commands"][0]["env"]["CI"], "1");
+    assert!(json["commands"][0]["env"]["RUSTFLAGS"].is_null());
+}
+
+#[test]
+fn commands_are_only_intercepted_while_planning() {
+    assert!(!intercept(&sample_command()));
+    let mut intercepted_while_planning = false;
+    let code = plan_and_print(PrintCommandsFormat::Human, || {
+        intercepted_while_planning = is_active() && intercept(&sample_command());
+    });
+    assert_eq!(code, 0);
+    assert!(intercepted_while_planning);
+    assert!(!is_active());
+}
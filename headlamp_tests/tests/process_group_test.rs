@@ -0,0 +1,101 @@
+#![cfg(target_os = "linux")]
+
+use std::process::Command;
+use std::time::Duration;
+
+use headlamp::live_progress::{LiveProgress, LiveProgressMode};
+use headlamp::process::{ProcessLimits, run_command_capture_with_timeout, set_process_limits};
+use headlamp::streaming::{OutputStream, StreamAction, StreamAdapter};
+
+#[derive(Default)]
+struct CaptureAdapter {
+    lines: Vec<String>,
+}
+
+impl StreamAdapter for CaptureAdapter {
+    fn on_start(&mut self) -> Option<String> {
+        None
+    }
+
+    fn on_line(&mut self, _stream: OutputStream, line: &str) -> Vec<StreamAction> {
+        self.lines.push(line.to_string());
+        vec![]
+    }
+}
+
+fn is_running(pid: &str) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|stat| stat.rsplit_once(") ").map(|(_, rest)| rest.to_string()))
+        .is_some_and(|rest| !rest.starts_with('Z') && !rest.starts_with('X'))
+}
+
+fn wait_until_stopped(pid: &str) -> bool {
+    (0..40).any(|_| {
+        std::thread::sleep(Duration::from_millis(50));
+        !is_running(pid)
+    })
+}
+
+fn niceness_of_self() -> i64 {
+    let stat = std::fs::read_to_string("/proc/self/stat").unwrap();
+    let (_, rest) = stat.rsplit_once(") ").unwrap();
+    rest.split(' ').nth(16).unwrap().parse().unwrap()
+}
+
+#[test]
+fn streaming_kills_processes_forked_by_the_runner_after_it_exits() {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", "sleep 30 >/dev/null 2>&1 & echo $!"]);
+    let progress = LiveProgress::start(1, LiveProgressMode::Off);
+    let mut adapter = CaptureAdapter::default();
+    let (code, _ring) =
+        headlamp::streaming::run_streaming_capture_tail(cmd, &progress, &mut adapter, 1024)
+            .expect("run_streaming_capture_tail");
+    progress.finish();
+    assert_eq!(code, 0);
+    let orphan = adapter
+        .lines
+        .first()
+        .expect("orphan pid")
+        .trim()
+        .to_string();
+    assert!(wait_until_stopped(&orphan), "orphan {orphan} still running");
+}
+
+#[test]
+fn capture_timeout_kills_the_whole_process_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        &format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+    ]);
+    let result =
+        run_command_capture_with_timeout(cmd, "sh".to_string(), Duration::from_millis(500));
+    assert!(result.is_err());
+    let orphan = std::fs::read_to_string(&pid_file)
+        .unwrap()
+        .trim()
+        .to_string();
+    assert!(
+        wait_until_stopped(&orphan),
+        "grandchild {orphan} still running"
+    );
+}
+
+#[test]
+fn nice_limit_lowers_runner_priority() {
+    set_process_limits(ProcessLimits {
+        max_memory_bytes: None,
+        nice: Some(3),
+    });
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", "cut -d' ' -f19 /proc/$$/stat"]);
+    let out = run_command_capture_with_timeout(cmd, "sh".to_string(), Duration::from_secs(5))
+        .expect("capture");
+    set_process_limits(ProcessLimits::default());
+    let child_nice: i64 = String::from_utf8_lossy(&out.stdout).trim().parse().unwrap();
+    assert_eq!(child_nice, (niceness_of_self() + 3).min(19));
+}
@@ -118,3 +118,33 @@ fn marker_inference_finds_nearest_project_root() {
         repo.to_string_lossy().replace('\\', "/")
     );
 }
+
+#[test]
+fn ts_js_jest_config_ts_test_match_marks_test_without_test_calls() {
+    let repo = mk_temp_dir("project-classifier-tsjs-jest-config-ts");
+    write_file(&repo.join("package.json"), "{\"name\":\"demo\"}\n");
+    write_file(
+        &repo.join("jest.config.ts"),
+        "import type { Config } from 'jest';\n\
+         import { defineConfig } from './define';\n\
+         export default defineConfig({ testMatch: ['qa/**/*_case.js'] } satisfies Config);\n",
+    );
+    write_file(
+        &repo.join("define.ts"),
+        "export const defineConfig = (c: unknown) => c;\n",
+    );
+    write_file(
+        &repo.join("qa/specimens/alpha_case.js"),
+        "export const x = 1;\n",
+    );
+
+    let mut classifier = headlamp::project::classify::ProjectClassifier::for_path(
+        headlamp::selection::dependency_language::DependencyLanguageId::TsJs,
+        &repo,
+    );
+    let kind = classifier.classify_abs_path(&repo.join("qa/specimens/alpha_case.js"));
+    assert!(matches!(
+        kind,
+        headlamp::project::classify::FileKind::Test | headlamp::project::classify::FileKind::Mixed
+    ));
+}
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use headlamp::pytest::django::{DjangoProject, detect_django};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn settings_from_pytest_ini_enable_pytest_django() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(
+        &repo.path().join("pytest.ini"),
+        "[pytest]\nDJANGO_SETTINGS_MODULE = mysite.settings.test\naddopts = -q\n",
+    );
+    assert_eq!(
+        detect_django(repo.path()),
+        Some(DjangoProject {
+            settings_module: "mysite.settings.test".to_string(),
+            pytest_django: true,
+        })
+    );
+}
+
+#[test]
+fn settings_from_pyproject_ini_options() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(
+        &repo.path().join("pyproject.toml"),
+        "[tool.pytest.ini_options]\nDJANGO_SETTINGS_MODULE = \"mysite.settings\"\n",
+    );
+    let django = detect_django(repo.path()).expect("django project");
+    assert_eq!(django.settings_module, "mysite.settings");
+    assert!(django.pytest_django);
+}
+
+#[test]
+fn manage_py_default_sets_env_without_plugin() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(
+        &repo.path().join("manage.py"),
+        "import os\nos.environ.setdefault('DJANGO_SETTINGS_MODULE', 'mysite.settings')\n",
+    );
+    assert_eq!(
+        detect_django(repo.path()),
+        Some(DjangoProject {
+            settings_module: "mysite.settings".to_string(),
+            pytest_django: false,
+        })
+    );
+}
+
+#[test]
+fn non_django_repo_is_not_detected() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(
+        &repo.path().join("setup.cfg"),
+        "[tool:pytest]\naddopts = -q\n",
+    );
+    assert_eq!(detect_django(repo.path()), None);
+}
@@ -28,3 +28,24 @@ fn selects_tests_by_import_seed() {
     assert_eq!(kept.len(), 1);
     assert_eq!(kept[0], test_file);
 }
+
+#[test]
+fn unittest_listing_matches_default_discovery_pattern() {
+    let root = mk_temp_dir("unittest_listing");
+    for rel in [
+        "tests/test_a.py",
+        "pkg/helpers.py",
+        "pkg/tests.py",
+        ".venv/lib/test_vendored.py",
+    ] {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "").unwrap();
+    }
+
+    let listed = headlamp::pytest_select::list_unittest_test_files(&root);
+    assert_eq!(
+        listed,
+        vec![root.join("pkg/tests.py"), root.join("tests/test_a.py")]
+    );
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use headlamp::python_env::{PythonInterpreter, PythonSource, resolve_python};
+
+fn write_file(path: &Path) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, "").unwrap();
+}
+
+fn venv_python_rel() -> &'static str {
+    if cfg!(windows) {
+        ".venv/Scripts/python.exe"
+    } else {
+        ".venv/bin/python"
+    }
+}
+
+#[test]
+fn repo_venv_is_preferred_over_path() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(&repo.path().join(venv_python_rel()));
+    assert_eq!(
+        resolve_python(repo.path(), None),
+        Some(PythonInterpreter {
+            path: repo.path().join(venv_python_rel()),
+            source: PythonSource::RepoVenv,
+        })
+    );
+}
+
+#[test]
+fn explicit_python_wins_and_resolves_relative_to_repo() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(&repo.path().join(venv_python_rel()));
+    write_file(&repo.path().join("envs/py312/bin/python"));
+    assert_eq!(
+        resolve_python(repo.path(), Some("envs/py312/bin/python")),
+        Some(PythonInterpreter {
+            path: repo.path().join("envs/py312/bin/python"),
+            source: PythonSource::Explicit,
+        })
+    );
+}
+
+#[test]
+fn no_project_interpreter_leaves_path_lookup_to_caller() {
+    let repo = tempfile::tempdir().unwrap();
+    write_file(&repo.path().join("venv-notes/readme.txt"));
+    assert_eq!(resolve_python(repo.path(), None), None);
+}
@@ -0,0 +1,113 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::readiness::{WaitTarget, parse_duration_ms, parse_wait_target, wait_for_targets};
+use headlamp::run::RunError;
+
+#[test]
+fn wait_targets_parse_tcp_http_and_unix() {
+    let tcp = |host: &str, port| WaitTarget::Tcp {
+        host: host.to_string(),
+        port,
+    };
+    assert_eq!(
+        parse_wait_target("tcp:localhost:5432"),
+        Some(tcp("localhost", 5432))
+    );
+    assert_eq!(parse_wait_target("tcp://db:5432"), Some(tcp("db", 5432)));
+    assert_eq!(parse_wait_target("[::1]:6379"), Some(tcp("::1", 6379)));
+    assert_eq!(
+        parse_wait_target("http://localhost:8080/healthz"),
+        Some(WaitTarget::Http {
+            host: "localhost".to_string(),
+            port: 8080,
+            path: "/healthz".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_wait_target("http://api"),
+        Some(WaitTarget::Http {
+            host: "api".to_string(),
+            port: 80,
+            path: "/".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_wait_target("unix:/tmp/pg.sock"),
+        Some(WaitTarget::Unix(PathBuf::from("/tmp/pg.sock")))
+    );
+    assert_eq!(parse_wait_target("localhost"), None);
+}
+
+#[test]
+fn durations_accept_units_and_bare_seconds() {
+    assert_eq!(parse_duration_ms("60s"), Some(60_000));
+    assert_eq!(parse_duration_ms("2m"), Some(120_000));
+    assert_eq!(parse_duration_ms("500ms"), Some(500));
+    assert_eq!(parse_duration_ms("1.5"), Some(1_500));
+    assert_eq!(parse_duration_ms("soon"), None);
+}
+
+#[test]
+fn wait_flags_come_from_cli_and_config() {
+    let cfg: HeadlampConfig =
+        serde_json::from_str(r#"{ "waitFor": ["tcp:db:5432"], "waitTimeout": "2m" }"#).unwrap();
+    let argv = ["--wait-for", "http://api/healthz"].map(str::to_string);
+    let parsed = derive_args(&config_tokens(&cfg, &argv), &argv, false);
+    assert_eq!(parsed.wait_for, vec!["tcp:db:5432", "http://api/healthz"]);
+    assert_eq!(parsed.wait_timeout_ms, Some(120_000));
+    assert!(parsed.runner_args.is_empty());
+}
+
+#[test]
+fn waits_for_listening_tcp_and_http_services() {
+    let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+    let http = TcpListener::bind("127.0.0.1:0").unwrap();
+    let http_port = http.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for mut stream in http.incoming().map_while(Result::ok) {
+            let mut request = [0u8; 512];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n");
+        }
+    });
+    let specs = vec![
+        format!("tcp:127.0.0.1:{}", tcp.local_addr().unwrap().port()),
+        format!("http://127.0.0.1:{http_port}/ready"),
+    ];
+    wait_for_targets(&specs, Duration::from_secs(5)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn waits_for_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("svc.sock");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+    let specs = vec![format!("unix:{}", socket.display())];
+    wait_for_targets(&specs, Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn unreachable_service_times_out() {
+    let port = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let specs = vec![format!("tcp:127.0.0.1:{port}")];
+    let err = wait_for_targets(&specs, Duration::from_millis(300)).unwrap_err();
+    assert!(matches!(err, RunError::TimedOut { .. }));
+}
+
+#[test]
+fn invalid_target_is_an_error() {
+    let err = wait_for_targets(&["nowhere".to_string()], Duration::from_secs(1)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid --wait-for target: nowhere")
+    );
+}
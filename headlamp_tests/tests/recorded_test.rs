@@ -0,0 +1,29 @@
+use headlamp::format::infra_failure::build_infra_failure_test_run_model;
+use headlamp::recorded::{begin, record_failed_tests, recorded_results};
+
+#[test]
+fn each_run_keeps_its_own_recording() {
+    let first = begin();
+    record_failed_tests(&build_infra_failure_test_run_model(
+        "tests/a.test.js",
+        "adds",
+        "boom",
+    ));
+    let second = begin();
+    assert!(recorded_results().failed_tests.is_empty());
+    record_failed_tests(&build_infra_failure_test_run_model(
+        "tests/b.test.js",
+        "subtracts",
+        "boom",
+    ));
+    assert_eq!(
+        first.results().failed_tests,
+        vec![("tests/a.test.js".to_string(), Some("adds".to_string()))]
+    );
+    assert_eq!(
+        second.results().failed_tests,
+        vec![("tests/b.test.js".to_string(), Some("subtracts".to_string()))]
+    );
+    assert_eq!(first.runs().models.len(), 1);
+    assert_eq!(second.runs().models.len(), 1);
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::process::RunEnvironment;
+use headlamp::remote::{RunManifest, build_manifest, read_models, run_remote, write_manifest};
+use headlamp::test_model::TestRunModel;
+use headlamp::test_support::model::{case, failed_case, run_model, suite};
+
+fn model(test_file: &str, failed: bool) -> TestRunModel {
+    let case = if failed {
+        failed_case("adds", "Error: nope")
+    } else {
+        case("adds", "passed")
+    };
+    run_model(vec![suite(test_file, vec![case])])
+}
+
+fn args(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+fn write_fixture(dir: &Path, name: &str, failed: bool) {
+    std::fs::write(
+        dir.join(name),
+        serde_json::to_vec(&model("src/a.test.ts", failed)).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn remote_flag_is_parsed() {
+    assert_eq!(args(&[]).remote, None);
+    assert_eq!(
+        args(&["--remote=./ci/run-remote.sh"]).remote.as_deref(),
+        Some("./ci/run-remote.sh")
+    );
+}
+
+#[test]
+fn manifests_round_trip_and_models_are_read_in_name_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let run_env = RunEnvironment {
+        vars: vec![("API_URL".to_string(), "http://localhost".to_string())],
+        ..RunEnvironment::default()
+    };
+    let manifest = build_manifest(
+        dir.path(),
+        &args(&["--coverage"]),
+        &run_env,
+        &[],
+        &dir.path().join("out"),
+    );
+    assert!(manifest.coverage);
+    assert_eq!(manifest.env["API_URL"], "http://localhost");
+    let path = write_manifest(&manifest, &dir.path().join("out")).unwrap();
+    let read: RunManifest = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+    assert_eq!(read, manifest);
+
+    write_fixture(dir.path(), "model-2.json", true);
+    write_fixture(dir.path(), "model-1.json", false);
+    std::fs::write(dir.path().join("model-3.json"), "not json").unwrap();
+    let models = read_models(dir.path());
+    assert_eq!(
+        models
+            .iter()
+            .map(|model| model.aggregated.success)
+            .collect::<Vec<_>>(),
+        vec![true, false]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn the_executor_gets_the_manifest_and_its_results_decide_the_exit_code() {
+    let cache = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    write_fixture(repo.path(), "failing.json", true);
+    let executor = r#"test -f "$HEADLAMP_REMOTE_MANIFEST" && cp failing.json "$HEADLAMP_REMOTE_OUT/model.json""#;
+    let run_env = RunEnvironment {
+        cache_dir: Some(cache.path().to_path_buf()),
+        ..RunEnvironment::default()
+    };
+    assert_eq!(
+        run_remote(executor, repo.path(), &args(&[]), &run_env, &[]),
+        1
+    );
+    let recorded = headlamp::recorded::recorded_results();
+    assert!(
+        recorded
+            .failed_tests
+            .contains(&("src/a.test.ts".to_string(), Some("adds".to_string())))
+    );
+
+    assert_eq!(
+        run_remote("exit 7", repo.path(), &args(&[]), &run_env, &[]),
+        7
+    );
+    assert_eq!(
+        run_remote("true", repo.path(), &args(&[]), &run_env, &[]),
+        1
+    );
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::config::{find_repo_root, resolve_repo_root};
+use headlamp::format::path_display::PathDisplay;
+use headlamp::format::paths::strip_root;
+
+fn argv(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+fn git_init(dir: &Path) {
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(dir)
+        .status()
+        .expect("git");
+    assert!(status.success());
+}
+
+#[test]
+fn repo_root_flag_is_parsed_and_overrides_the_git_toplevel() {
+    assert_eq!(
+        derive_args(&[], &argv(&["--repo-root=../mono"]), false)
+            .repo_root
+            .as_deref(),
+        Some("../mono")
+    );
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("nested/app")).unwrap();
+    let canonical = dunce::canonicalize(dir.path()).unwrap();
+    let resolve = |tokens: &[&str]| resolve_repo_root(dir.path(), &argv(tokens));
+    assert_eq!(
+        resolve(&["--repo-root=nested/app"]),
+        canonical.join("nested/app")
+    );
+    assert_eq!(
+        resolve(&["--repoRoot", "nested", "src/a.test.ts"]),
+        canonical.join("nested")
+    );
+    assert_eq!(resolve(&["--", "--repo-root=nested"]), canonical);
+}
+
+#[cfg(unix)]
+#[test]
+fn a_checkout_reached_through_a_symlink_resolves_to_the_same_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let real = dir.path().join("real");
+    std::fs::create_dir_all(real.join("src")).unwrap();
+    git_init(&real);
+    let link = dir.path().join("link");
+    std::os::unix::fs::symlink(&real, &link).unwrap();
+
+    let root = find_repo_root(&link.join("src"));
+    assert_eq!(root, dunce::canonicalize(&real).unwrap());
+    assert_eq!(find_repo_root(&real), root);
+
+    let through_link = link.join("src/a.test.ts");
+    std::fs::write(&through_link, "").unwrap();
+    assert_eq!(
+        strip_root(&through_link, &root).as_deref(),
+        Some(Path::new("src/a.test.ts"))
+    );
+    assert_eq!(
+        PathDisplay::default().display(&through_link.to_string_lossy(), &root.to_string_lossy()),
+        "src/a.test.ts"
+    );
+    assert_eq!(strip_root(Path::new("src/a.test.ts"), &root), None);
+}
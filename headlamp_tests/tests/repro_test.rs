@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use headlamp::format::cargo_test::parse_cargo_test_output;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::infra_failure::build_infra_failure_test_run_model;
+use headlamp::format::nextest::parse_nextest_libtest_json_output;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::repro;
+
+#[test]
+fn jest_repro_anchors_and_quotes_the_test_name() {
+    assert_eq!(
+        repro::jest(None, "tests/sum.test.ts", "sum adds"),
+        "npx jest tests/sum.test.ts -t '^sum adds$'"
+    );
+    assert_eq!(
+        repro::jest(
+            Some("packages/api/jest.config.js"),
+            "packages/api/a.test.js",
+            "it's (maybe) 1+1"
+        ),
+        r#"npx jest --config packages/api/jest.config.js packages/api/a.test.js -t '^it'\''s \(maybe\) 1\+1$'"#
+    );
+}
+
+#[test]
+fn pytest_and_doctest_repros_use_the_runner_ids() {
+    assert_eq!(
+        repro::pytest("tests/test_api.py::TestUsers::test_create"),
+        "pytest tests/test_api.py::TestUsers::test_create"
+    );
+    assert_eq!(
+        repro::pytest("tests/test_math.py::test_add[1-2]"),
+        "pytest 'tests/test_math.py::test_add[1-2]'"
+    );
+    assert_eq!(
+        repro::cargo_doctest("src/lib.rs - parse (line 12)"),
+        "cargo test --doc -- --exact 'src/lib.rs - parse (line 12)'"
+    );
+}
+
+#[test]
+fn cargo_test_repro_selects_the_target_and_package() {
+    assert_eq!(
+        repro::cargo_test("src/lib.rs", "parser::tests::empty"),
+        "cargo test --lib -- --exact parser::tests::empty"
+    );
+    assert_eq!(
+        repro::cargo_test("tests/api.rs", "creates_user"),
+        "cargo test --test api -- --exact creates_user"
+    );
+    assert_eq!(
+        repro::cargo_test("crates/cli/src/bin/tool.rs", "flags"),
+        "cargo test --manifest-path crates/cli/Cargo.toml --bin tool -- --exact flags"
+    );
+}
+
+#[test]
+fn runner_parsers_record_repro_commands() {
+    let nextest = parse_nextest_libtest_json_output(
+        Path::new("/repo"),
+        r#"{"type":"suite","event":"started","test_count":1,"nextest":{"crate":"parity_sum","test_binary":"sum_test","kind":"test"}}
+{"type":"test","event":"failed","name":"parity_sum::sum_test$math::sum_fails","exec_time":0.01,"stdout":"fail"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        nextest.test_results[0].test_results[0].repro.as_deref(),
+        Some("cargo nextest run -E 'binary_id(parity_sum::sum_test) & test(=math::sum_fails)'")
+    );
+
+    let cargo = parse_cargo_test_output(
+        Path::new("/repo"),
+        "Running tests/api.rs (target/debug/deps/api-abc123)\ntest creates_user ... FAILED\n",
+    )
+    .unwrap();
+    assert_eq!(
+        cargo.test_results[0].test_results[0].repro.as_deref(),
+        Some("cargo test --test api -- --exact creates_user")
+    );
+}
+
+#[test]
+fn failure_block_prints_the_repro_command() {
+    let mut model =
+        build_infra_failure_test_run_model("/repo/tests/api.rs", "creates_user", "boom");
+    model.test_results[0].test_results[0].repro =
+        Some("cargo test --test api -- --exact creates_user".to_string());
+    let ctx = make_ctx(Path::new("/repo"), Some(100), true, false, None);
+    let rendered = render_vitest_from_test_model(&model, &ctx, true);
+    let plain = strip_ansi_simple(&rendered);
+    assert!(plain.contains("    Reproduce: cargo test --test api -- --exact creates_user\n"));
+}
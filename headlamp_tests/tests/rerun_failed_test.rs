@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::args::derive_args;
+use headlamp::rerun::{FailedTest, RerunRunner, failed_languages, load_failed_tests, rerun_args};
+use headlamp::runner_detect::DetectedLanguage;
+use headlamp::session::RunSession;
+use headlamp::session::artifacts::{ArtifactKind, collect_artifacts};
+use headlamp::test_model::TestRunModel;
+use headlamp::test_support::model::{case, failed_case, run_model, suite};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-rerun-failed-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+/// One passing and one failing test in `path`, as the runner on another machine reported them.
+fn report_with_failure(path: &str, failed_name: &str) -> TestRunModel {
+    let cases = vec![
+        case("still passes", "passed"),
+        failed_case(failed_name, "boom"),
+    ];
+    run_model(vec![suite(path, cases)])
+}
+
+fn failed(path: &str, name: Option<&str>) -> FailedTest {
+    FailedTest {
+        test_file_path: path.to_string(),
+        full_name: name.map(str::to_string),
+    }
+}
+
+#[test]
+fn ci_report_paths_are_mapped_into_the_local_checkout() {
+    let repo_root = mk_temp_dir("ci_report_paths");
+    write_file(&repo_root.join("tests/sum.test.ts"), "");
+    let report = repo_root.join("report.json");
+    let model = report_with_failure("/home/runner/work/app/app/tests/sum.test.ts", "sum adds");
+    write_file(&report, &serde_json::to_string(&model).unwrap());
+
+    let failed_tests = load_failed_tests(&repo_root, &report).unwrap();
+    assert_eq!(
+        failed_tests,
+        vec![failed("tests/sum.test.ts", Some("sum adds"))]
+    );
+    assert_eq!(
+        failed_languages(&failed_tests),
+        vec![DetectedLanguage::JavaScript]
+    );
+}
+
+#[test]
+fn an_artifacts_dir_is_read_through_its_index() {
+    let repo_root = mk_temp_dir("artifacts_dir_index");
+    let session = RunSession::new(false).unwrap();
+    let report = session.subdir("jest").join("bridge.0.json");
+    let model = report_with_failure(&repo_root.join("a.test.js").to_string_lossy(), "a (fails)");
+    write_file(&report, &serde_json::to_string(&model).unwrap());
+    session.register_artifact(ArtifactKind::Report, "jest", &report);
+    let dest = repo_root.join("ci-artifacts");
+    collect_artifacts(&dest, &repo_root, &session.artifacts(), &[]).unwrap();
+
+    let failed_tests = load_failed_tests(&repo_root, &dest).unwrap();
+    assert_eq!(failed_tests, vec![failed("a.test.js", Some("a (fails)"))]);
+}
+
+#[test]
+fn jest_and_pytest_reruns_select_the_failed_files_and_names() {
+    let argv = ["--coverage", "--", "--ci"].map(str::to_string);
+    let parsed = derive_args(&[], &argv, false);
+    let failed_tests = vec![
+        failed("tests/a.test.ts", Some("a (fails)")),
+        failed("tests/a.test.ts", Some("a also fails")),
+        failed("tests/test_api.py", Some("test_create[1-2]")),
+        failed("tests/test_db.py", None),
+    ];
+
+    let jest = rerun_args(&parsed, &failed_tests, RerunRunner::Jest);
+    assert!(jest.collect_coverage);
+    assert_eq!(jest.selection_paths, vec!["tests/a.test.ts"]);
+    assert!(jest.selection_specified);
+    assert_eq!(
+        jest.runner_args[jest.runner_args.len() - 2..],
+        ["-t", r"^(a \(fails\)|a also fails)$"]
+    );
+
+    let pytest = rerun_args(&parsed, &failed_tests, RerunRunner::Pytest);
+    assert_eq!(
+        pytest.selection_paths,
+        vec!["tests/test_api.py::test_create[1-2]", "tests/test_db.py"]
+    );
+}
+
+#[test]
+fn rust_reruns_filter_by_exact_test_name() {
+    let parsed = derive_args(&[], &[], false);
+    let failed_tests = vec![
+        failed("tests/api.rs", Some("creates_user")),
+        failed("src/lib.rs", Some("parser::tests::empty")),
+    ];
+
+    let cargo = rerun_args(&parsed, &failed_tests, RerunRunner::CargoTest);
+    assert_eq!(
+        cargo.runner_args,
+        vec!["--", "--exact", "creates_user", "parser::tests::empty"]
+    );
+    let nextest = rerun_args(&parsed, &failed_tests, RerunRunner::CargoNextest);
+    assert_eq!(
+        nextest.runner_args,
+        vec!["-E", "test(=creates_user) | test(=parser::tests::empty)"]
+    );
+
+    let with_build_failure = [failed_tests, vec![failed("tests/http.rs", None)]].concat();
+    let cargo = rerun_args(&parsed, &with_build_failure, RerunRunner::CargoTest);
+    assert!(cargo.runner_args.is_empty());
+}
@@ -0,0 +1,95 @@
+use headlamp::args::derive_args;
+use headlamp::process::{ResourceUsage, RunnerResources};
+use headlamp::resource_report::{ResourceReportFormat, render_resource_report};
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+fn sample() -> Vec<RunnerResources> {
+    vec![RunnerResources {
+        runner: "pytest".to_string(),
+        root: "/repo".to_string(),
+        usage: ResourceUsage {
+            max_rss_bytes: 64 * 1024 * 1024,
+            user_cpu_ms: 1250,
+            system_cpu_ms: 40,
+            processes: 3,
+        },
+    }]
+}
+
+#[test]
+fn resource_report_is_a_switch_that_never_takes_the_next_token() {
+    let parsed = parse(&["--resource-report", "tests/test_sum.py"]);
+    assert_eq!(parsed.resource_report, Some(ResourceReportFormat::Human));
+    assert_eq!(
+        parsed.selection_paths,
+        vec!["tests/test_sum.py".to_string()]
+    );
+    assert_eq!(
+        parse(&["--resource-report=json"]).resource_report,
+        Some(ResourceReportFormat::Json)
+    );
+    assert_eq!(parse(&["--resource-report=false"]).resource_report, None);
+}
+
+#[test]
+fn human_report_is_an_aligned_table() {
+    let rendered = render_resource_report(&sample(), ResourceReportFormat::Human);
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Resources");
+    assert_eq!(
+        lines[1],
+        "runner   max RSS  user CPU  system CPU  processes"
+    );
+    assert_eq!(
+        lines[2],
+        "pytest  64.0 MiB     1.25s       0.04s          3"
+    );
+}
+
+#[test]
+fn json_report_lists_each_runner_with_flat_usage_fields() {
+    let rendered = render_resource_report(&sample(), ResourceReportFormat::Json);
+    let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "resources": [{
+                "runner": "pytest",
+                "root": "/repo",
+                "maxRssBytes": 67108864,
+                "userCpuMs": 1250,
+                "systemCpuMs": 40,
+                "processes": 3,
+            }]
+        })
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn usage_scope_measures_the_runner_processes_it_reaps() {
+    use std::process::Command;
+    use std::time::Duration;
+
+    use headlamp::process::{UsageScope, run_command_capture_with_timeout};
+
+    let scope = UsageScope::begin();
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        "i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done; echo done",
+    ]);
+    let output =
+        run_command_capture_with_timeout(command, "sh".to_string(), Duration::from_secs(60))
+            .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+    let usage = scope.finish();
+    assert_eq!(usage.processes, 1);
+    assert!(usage.max_rss_bytes > 0, "{usage:?}");
+    assert!(usage.user_cpu_ms + usage.system_cpu_ms > 0, "{usage:?}");
+}
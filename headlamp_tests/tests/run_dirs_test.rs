@@ -0,0 +1,35 @@
+use headlamp::session::run_dirs::{RUN_DIRS_KEPT, new_run_dir, prune_run_dirs};
+
+#[test]
+fn run_dirs_are_fresh_per_run_and_pruned_to_the_newest() {
+    let cache = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    let current = new_run_dir(cache.path(), repo.path(), "remote");
+    let kind_dir = current.parent().unwrap();
+    assert_eq!(kind_dir.file_name().unwrap(), "remote");
+    assert!(current.starts_with(cache.path()));
+
+    let old = (1..=5)
+        .map(|millis| kind_dir.join(format!("{millis}-42")))
+        .collect::<Vec<_>>();
+    old.iter()
+        .chain([&current])
+        .for_each(|dir| std::fs::create_dir_all(dir).unwrap());
+    std::fs::write(kind_dir.join("notes.txt"), "").unwrap();
+    prune_run_dirs(&current);
+
+    let mut left = std::fs::read_dir(kind_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    left.sort();
+    let mut expected = vec![
+        "4-42".to_string(),
+        "5-42".to_string(),
+        current.file_name().unwrap().to_string_lossy().to_string(),
+        "notes.txt".to_string(),
+    ];
+    expected.sort();
+    assert_eq!(RUN_DIRS_KEPT, 3);
+    assert_eq!(left, expected);
+}
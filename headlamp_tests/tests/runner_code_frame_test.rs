@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use headlamp::format::codeframe::failure_message_location;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::TestRunModel;
+use headlamp::test_support::model::{failed_case, run_model, suite};
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn model(test_file: &str, name: &str, message: &str) -> TestRunModel {
+    run_model(vec![suite(test_file, vec![failed_case(name, message)])])
+}
+
+fn render(root: &Path, model: &TestRunModel) -> String {
+    let ctx = make_ctx(root, Some(120), true, false, None);
+    strip_ansi_simple(&render_vitest_from_test_model(model, &ctx, true))
+}
+
+const HELPER_RS: &str = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub fn check(sum: i32) {\n    assert_eq!(sum, 5);\n}\n";
+
+#[test]
+fn rust_panics_render_a_frame_at_the_panic_location() {
+    let root = tempfile::tempdir().unwrap();
+    write(root.path(), "src/helpers.rs", HELPER_RS);
+    write(root.path(), "tests/math.rs", "#[test]\nfn adds() {}\n");
+    let message = "thread 'adds' panicked at src/helpers.rs:6:5:\nassertion `left == right` failed\n  left: 4\n right: 5";
+    let out = render(root.path(), &model("tests/math.rs", "adds", message));
+    assert!(out.contains("> 6 |     assert_eq!(sum, 5);"), "{out}");
+    assert!(out.contains("3 | }"), "{out}");
+    assert!(!out.contains("2 |     a + b"), "{out}");
+}
+
+#[test]
+fn pytest_failures_render_a_frame_at_the_deepest_project_frame() {
+    let root = tempfile::tempdir().unwrap();
+    write(
+        root.path(),
+        "app/calc.py",
+        "def div(a, b):\n    return a / b\n",
+    );
+    write(
+        root.path(),
+        "tests/test_calc.py",
+        "from app.calc import div\n\n\ndef test_div():\n    assert div(1, 0) == 0\n",
+    );
+    let longrepr = [
+        "def test_div():",
+        ">       assert div(1, 0) == 0",
+        "",
+        "tests/test_calc.py:5: ",
+        "_ _ _ _ _ _ _ _",
+        "",
+        ">       return a / b",
+        "E       ZeroDivisionError: division by zero",
+        "",
+        "app/calc.py:2: ZeroDivisionError",
+    ]
+    .join("\n");
+    let out = render(
+        root.path(),
+        &model("tests/test_calc.py", "test_div", &longrepr),
+    );
+    assert!(out.contains("> 2 |     return a / b"), "{out}");
+}
+
+#[test]
+fn frames_outside_the_project_are_ignored() {
+    let root = tempfile::tempdir().unwrap();
+    write(
+        root.path(),
+        "tests/test_a.py",
+        "def test_a():\n    fail()\n",
+    );
+    let cwd = make_ctx(root.path(), None, true, false, None).cwd;
+    let lines = |text: &str| vec![text.to_string()];
+    let loc = failure_message_location(
+        &lines("tests/test_a.py:2: in test_a\n/usr/lib/python3/site-packages/lib.py:9: ValueError"),
+        &cwd,
+    )
+    .expect("project frame");
+    assert!(loc.file.ends_with("tests/test_a.py"));
+    assert_eq!(loc.line, 2);
+    assert!(
+        failure_message_location(&lines("File \"/nowhere/x.py\", line 3, in f"), &cwd).is_none()
+    );
+}
@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+
+use headlamp::runner_detect::{DetectedLanguage, detect_languages, language_for_path};
+
+#[test]
+fn maps_extensions_and_cargo_manifests_to_languages() {
+    assert_eq!(
+        language_for_path(Path::new("web/src/App.tsx")),
+        Some(DetectedLanguage::JavaScript)
+    );
+    assert_eq!(
+        language_for_path(Path::new("lib/util.mjs")),
+        Some(DetectedLanguage::JavaScript)
+    );
+    assert_eq!(
+        language_for_path(Path::new("api/models.py")),
+        Some(DetectedLanguage::Python)
+    );
+    assert_eq!(
+        language_for_path(Path::new("crates/core/src/lib.rs")),
+        Some(DetectedLanguage::Rust)
+    );
+    assert_eq!(
+        language_for_path(Path::new("crates/core/Cargo.toml")),
+        Some(DetectedLanguage::Rust)
+    );
+    assert_eq!(language_for_path(Path::new("README.md")), None);
+}
+
+#[test]
+fn detect_languages_is_deduplicated_and_ordered() {
+    let paths = [
+        "src/b.rs",
+        "docs/x.md",
+        "web/a.ts",
+        "src/c.rs",
+        "tools/gen.py",
+    ]
+    .map(PathBuf::from)
+    .to_vec();
+    assert_eq!(
+        detect_languages(&paths),
+        vec![
+            DetectedLanguage::JavaScript,
+            DetectedLanguage::Python,
+            DetectedLanguage::Rust
+        ]
+    );
+    assert!(detect_languages(&[PathBuf::from("notes.txt")]).is_empty());
+}
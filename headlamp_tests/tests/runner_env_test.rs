@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use headlamp::args::{ParsedArgs, config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::process::RunEnvironment;
+use headlamp::process::env::{parse_dotenv, run_var};
+
+fn mk_temp_dir(name: &str) -> PathBuf {
+    let base = std::env::temp_dir()
+        .join("headlamp-runner-env-tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&base);
+    std::fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn parse_with_config(config_json: &str, argv: &[&str]) -> ParsedArgs {
+    let cfg: HeadlampConfig = serde_json::from_str(config_json).unwrap();
+    let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    derive_args(&config_tokens(&cfg, &argv), &argv, false)
+}
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn dotenv_handles_comments_export_and_quotes() {
+    let text = "# db\nexport DATABASE_URL=postgres://localhost/test\n\nPLAIN=a b # note\n\
+                SINGLE='raw \\n $X'\nDOUBLE=\"line\\nnext \\\"q\\\"\"\nEMPTY=\n";
+    assert_eq!(
+        parse_dotenv(text).unwrap(),
+        pairs(&[
+            ("DATABASE_URL", "postgres://localhost/test"),
+            ("PLAIN", "a b"),
+            ("SINGLE", "raw \\n $X"),
+            ("DOUBLE", "line\nnext \"q\""),
+            ("EMPTY", ""),
+        ])
+    );
+    assert_eq!(parse_dotenv("NOT A LINE").unwrap_err(), "NOT A LINE");
+    assert_eq!(
+        parse_dotenv("OPEN=\"unterminated").unwrap_err(),
+        "OPEN=\"unterminated"
+    );
+}
+
+#[test]
+fn env_flags_come_from_cli_and_config_but_jest_env_passes_through() {
+    let parsed = parse_with_config(
+        r#"{ "env": { "A": "config" }, "envFile": ".env.test" }"#,
+        &[
+            "--env",
+            "B=1=2",
+            "--env=jsdom",
+            "--env",
+            "node",
+            "--envFile",
+            "other.env",
+        ],
+    );
+    assert_eq!(parsed.env_vars, vec!["A=config", "B=1=2"]);
+    assert_eq!(parsed.env_files, vec![".env.test", "other.env"]);
+    assert_eq!(parsed.runner_args, vec!["--env=jsdom", "--env", "node"]);
+}
+
+#[test]
+fn env_vars_override_env_files_in_order() {
+    let repo_root = mk_temp_dir("env_vars_override_env_files_in_order");
+    std::fs::write(repo_root.join(".env.test"), "A=file1\nB=file1\n").unwrap();
+    std::fs::write(repo_root.join(".env.local"), "B=file2\nC=file2\n").unwrap();
+    let parsed = parse_with_config(
+        r#"{ "envFile": ".env.test" }"#,
+        &["--env-file=.env.local", "--env", "C=flag"],
+    );
+    assert_eq!(
+        RunEnvironment::from_args(&repo_root, &parsed).unwrap().vars,
+        pairs(&[("A", "file1"), ("B", "file2"), ("C", "flag")])
+    );
+}
+
+#[test]
+fn bad_env_entries_and_missing_files_are_errors() {
+    let repo_root = mk_temp_dir("bad_env_entries_and_missing_files_are_errors");
+    let missing = parse_with_config("{}", &["--env-file=.env.nope"]);
+    let err = RunEnvironment::from_args(&repo_root, &missing).unwrap_err();
+    assert!(err.to_string().contains("failed to read --env-file"));
+    let bad_key = parse_with_config("{}", &["--env", "1BAD=x"]);
+    let err = RunEnvironment::from_args(&repo_root, &bad_key).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid --env (expected KEY=VALUE): 1BAD=x")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn run_environment_layers_ci_cache_dir_and_user_vars_onto_the_command() {
+    let run_env = RunEnvironment {
+        ci: true,
+        cache_dir: Some(PathBuf::from("/tmp/headlamp-cache-test")),
+        shuffle_seed: None,
+        vars: pairs(&[
+            ("HEADLAMP_CACHE_DIR", "/tmp/user-cache"),
+            ("APP_MODE", "test"),
+        ]),
+    };
+    let mut command = std::process::Command::new("sh");
+    command.args([
+        "-c",
+        "printf '%s|%s|%s' \"$CI\" \"$HEADLAMP_CACHE_DIR\" \"$APP_MODE\"",
+    ]);
+    run_env.apply(&mut command);
+    let output = command.output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1|/tmp/user-cache|test"
+    );
+    assert!(std::env::var_os("APP_MODE").is_none());
+}
+
+#[test]
+fn each_published_run_environment_replaces_the_last() {
+    let published = |value: &str| RunEnvironment {
+        vars: pairs(&[("HEADLAMP_PUBLISH_TEST", value)]),
+        ..RunEnvironment::default()
+    };
+    published("first").publish();
+    assert_eq!(run_var("HEADLAMP_PUBLISH_TEST").as_deref(), Some("first"));
+    published("second").publish();
+    assert_eq!(run_var("HEADLAMP_PUBLISH_TEST").as_deref(), Some("second"));
+    RunEnvironment::default().publish();
+    assert_eq!(run_var("HEADLAMP_PUBLISH_TEST"), None);
+}
@@ -0,0 +1,100 @@
+use headlamp::args::{derive_args, translate_runner_args, validate_runner_passthrough};
+use headlamp::rerun::RerunRunner;
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+#[test]
+fn headlamp_flags_after_the_separator_are_rejected() {
+    let err = validate_runner_passthrough(&strings(&["--", "-t", "sum", "--coverage"]))
+        .expect_err("--coverage after -- must be rejected");
+    assert!(err.contains("`--coverage`"), "{err}");
+    assert!(err.contains("move it before `--`"), "{err}");
+
+    let err = validate_runner_passthrough(&strings(&["--", "--changed=staged"]))
+        .expect_err("--changed=staged after -- must be rejected");
+    assert!(err.contains("`--changed=staged`"), "{err}");
+}
+
+#[test]
+fn runner_flags_and_the_test_binary_part_pass_validation() {
+    for argv in [
+        strings(&["--coverage", "src/a.test.ts"]),
+        strings(&["--coverage", "--", "-t", "sum", "--env", "jsdom"]),
+        strings(&["--", "--env=node"]),
+        strings(&["--", "--release", "--", "--coverage"]),
+    ] {
+        assert_eq!(validate_runner_passthrough(&argv), Ok(()), "{argv:?}");
+    }
+}
+
+#[test]
+fn name_filter_aliases_take_each_runners_spelling() {
+    let args = strings(&["--grep", "sum", "--verbose"]);
+    assert_eq!(
+        translate_runner_args(RerunRunner::Jest, &args),
+        strings(&["-t", "sum", "--verbose"])
+    );
+    assert_eq!(
+        translate_runner_args(RerunRunner::Pytest, &strings(&["-t", "sum"])),
+        strings(&["-k", "sum"])
+    );
+    assert_eq!(
+        translate_runner_args(RerunRunner::Jest, &strings(&["--testNamePattern=sum"])),
+        strings(&["-t", "sum"])
+    );
+    assert_eq!(
+        translate_runner_args(RerunRunner::Headlamp, &strings(&["-k", "sum"])),
+        strings(&["sum"])
+    );
+}
+
+#[test]
+fn cargo_runners_move_filters_and_libtest_flags_behind_the_separator() {
+    for runner in [RerunRunner::CargoTest, RerunRunner::CargoNextest] {
+        assert_eq!(
+            translate_runner_args(
+                runner,
+                &strings(&[
+                    "--release",
+                    "-k",
+                    "sum",
+                    "--nocapture",
+                    "--test-threads",
+                    "2"
+                ])
+            ),
+            strings(&[
+                "--release",
+                "--",
+                "sum",
+                "--nocapture",
+                "--test-threads",
+                "2"
+            ])
+        );
+        assert_eq!(
+            translate_runner_args(runner, &strings(&["-t", "sum", "--", "--exact"])),
+            strings(&["--", "sum", "--exact"])
+        );
+        assert_eq!(
+            translate_runner_args(runner, &strings(&["--release"])),
+            strings(&["--release"])
+        );
+    }
+}
+
+#[test]
+fn tokens_after_the_separator_stay_runner_args_verbatim() {
+    let parsed = derive_args(
+        &[],
+        &strings(&["src/a.test.ts", "--", "src/b.test.ts", "--", "--exact"]),
+        false,
+    );
+    assert_eq!(parsed.selection_paths, strings(&["src/a.test.ts"]));
+    assert_eq!(
+        parsed.runner_args,
+        strings(&["src/b.test.ts", "--", "--exact"])
+    );
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::self_update::{
+    Release, ReleaseAsset, find_sha256_digest, managing_package_manager, platform_asset_name,
+    run_self_update_command, sha256_of,
+};
+
+const DIGEST: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+fn release(tag: &str) -> Release {
+    Release {
+        tag_name: tag.to_string(),
+        assets: vec![ReleaseAsset {
+            name: "linux-x64-gnu-headlamp.gz".to_string(),
+            browser_download_url: "https://example.test/linux-x64-gnu-headlamp.gz".to_string(),
+        }],
+    }
+}
+
+#[test]
+fn only_a_higher_semver_tag_counts_as_newer() {
+    assert!(release("v0.2.0").is_newer_than("0.1.60"));
+    assert!(release("0.1.61").is_newer_than("0.1.60"));
+    assert!(!release("v0.1.60").is_newer_than("0.1.60"));
+    assert!(!release("v0.1.9").is_newer_than("0.1.60"));
+    assert!(!release("nightly").is_newer_than("0.1.60"));
+}
+
+#[test]
+fn release_json_deserializes_and_finds_assets_by_name() {
+    let json = r#"{"tag_name":"v0.1.61","assets":[{"name":"linux-x64-gnu-headlamp.gz",
+        "browser_download_url":"https://example.test/a.gz","size":1}],"draft":false}"#;
+    let parsed: Release = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        parsed.version().map(|v| v.to_string()).as_deref(),
+        Some("0.1.61")
+    );
+    assert!(parsed.asset("linux-x64-gnu-headlamp.gz").is_some());
+    assert!(parsed.asset("linux-x64-gnu-headlamp.gz.sha256").is_none());
+}
+
+#[test]
+fn this_platform_has_a_release_asset_named_like_the_workflow() {
+    let name = platform_asset_name().expect("CI platforms all have release binaries");
+    assert!(name.ends_with("-headlamp.gz") || name.ends_with("-headlamp.exe.gz"));
+}
+
+#[test]
+fn digests_are_read_from_checksum_files_and_tool_output() {
+    let sha256sum = format!("{DIGEST}  linux-x64-gnu-headlamp.gz\n");
+    assert_eq!(find_sha256_digest(&sha256sum).as_deref(), Some(DIGEST));
+    let certutil = format!(
+        "SHA256 hash of a.gz:\r\n{}\r\nCertUtil: -hashfile command completed successfully.\r\n",
+        DIGEST.to_ascii_uppercase()
+    );
+    assert_eq!(find_sha256_digest(&certutil).as_deref(), Some(DIGEST));
+    assert_eq!(find_sha256_digest("not a digest"), None);
+}
+
+#[test]
+fn files_are_hashed_in_process() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("headlamp.gz");
+    std::fs::write(&path, "test").unwrap();
+    assert_eq!(sha256_of(&path).unwrap(), DIGEST);
+    assert!(sha256_of(&dir.path().join("missing.gz")).is_err());
+}
+
+#[test]
+fn package_manager_installs_are_left_to_their_manager() {
+    let npm = Path::new("/repo/node_modules/headlamp/bin/linux-x64-gnu/headlamp");
+    assert_eq!(managing_package_manager(npm), Some("npm"));
+    let pip = Path::new("/venv/lib/python3.12/site-packages/headlamp/bin/headlamp");
+    assert_eq!(managing_package_manager(pip), Some("pip"));
+    assert_eq!(
+        managing_package_manager(Path::new("/usr/local/bin/headlamp")),
+        None
+    );
+}
+
+#[test]
+fn self_update_is_its_own_subcommand() {
+    let strings = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    assert_eq!(run_self_update_command(&strings(&["src/a.test.ts"])), None);
+    assert_eq!(
+        run_self_update_command(&strings(&["self-update", "--force"])),
+        Some(2)
+    );
+}
+
+#[test]
+fn check_update_comes_from_the_flag_or_config() {
+    let parsed = derive_args(&[], &["--check-update".to_string()], false);
+    assert!(parsed.check_update);
+    let from_config = derive_args(&["--checkUpdate=true".to_string()], &[], false);
+    assert!(from_config.check_update);
+    assert!(!derive_args(&[], &[], false).check_update);
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::process::RunEnvironment;
+use headlamp::shuffle::{SEED_ENV, shuffle};
+use headlamp::test_support::model::run_model;
+
+fn parse(argv: &[&str]) -> headlamp::args::ParsedArgs {
+    let argv = argv.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    derive_args(&[], &argv, false)
+}
+
+#[test]
+fn shuffle_takes_an_optional_seed_and_never_the_next_token() {
+    let parsed = parse(&["--shuffle", "tests/test_sum.py"]);
+    assert!(parsed.shuffle_seed.is_some());
+    assert_eq!(
+        parsed.selection_paths,
+        vec!["tests/test_sum.py".to_string()]
+    );
+    assert_eq!(parse(&["--shuffle=42"]).shuffle_seed, Some(42));
+    assert_eq!(parse(&["--shuffle=0"]).shuffle_seed, Some(0));
+    assert_eq!(parse(&["--shuffle=false"]).shuffle_seed, None);
+    assert_eq!(parse(&[]).shuffle_seed, None);
+}
+
+#[test]
+fn a_seed_always_gives_the_same_permutation() {
+    let original = (0..20).collect::<Vec<_>>();
+    let mut first = original.clone();
+    let mut second = original.clone();
+    shuffle(&mut first, 7);
+    shuffle(&mut second, 7);
+    assert_eq!(first, second);
+    assert_ne!(first, original);
+    let mut sorted = first.clone();
+    sorted.sort();
+    assert_eq!(sorted, original);
+
+    let mut other_seed = original.clone();
+    shuffle(&mut other_seed, 8);
+    assert_ne!(other_seed, first);
+}
+
+#[test]
+fn the_footer_prints_the_seed_to_reproduce_with() {
+    let ctx = make_ctx(Path::new("/repo"), Some(80), false, false, None);
+    let plain = render_vitest_from_test_model(&run_model(vec![]), &ctx, false);
+    assert!(!plain.contains("Seed"));
+
+    let ctx = headlamp::format::ctx::Ctx {
+        shuffle_seed: Some(1234),
+        ..ctx
+    };
+    let rendered = render_vitest_from_test_model(&run_model(vec![]), &ctx, false);
+    let seed_line = rendered
+        .lines()
+        .map(strip_ansi_simple)
+        .find(|line| line.starts_with("Seed"))
+        .expect("seed line");
+    assert_eq!(seed_line, "Seed      1234 (--shuffle=1234 to reproduce)");
+}
+
+#[test]
+fn the_seed_reaches_every_runner_through_the_run_environment() {
+    let run_env = RunEnvironment {
+        shuffle_seed: Some(99),
+        ..RunEnvironment::default()
+    };
+    assert!(
+        run_env
+            .pairs()
+            .contains(&(SEED_ENV.to_string(), "99".to_string()))
+    );
+    assert!(
+        !RunEnvironment::default()
+            .pairs()
+            .iter()
+            .any(|(key, _)| key == SEED_ENV)
+    );
+}
@@ -0,0 +1,84 @@
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::{SkippedUnit, SkippedUnitKind, TestRunModel};
+use headlamp::test_support::model::run_model;
+
+fn web_and_core() -> Vec<SkippedUnit> {
+    vec![
+        SkippedUnit {
+            kind: SkippedUnitKind::JestProject,
+            name: "packages/web/jest.config.js".to_string(),
+        },
+        SkippedUnit {
+            kind: SkippedUnitKind::CargoPackage,
+            name: "core".to_string(),
+        },
+    ]
+}
+
+fn empty_model(skipped_units: Vec<SkippedUnit>) -> TestRunModel {
+    TestRunModel {
+        skipped_units,
+        ..run_model(vec![])
+    }
+}
+
+#[test]
+fn skipped_units_round_trip_and_are_omitted_when_empty() {
+    let json = serde_json::to_value(empty_model(web_and_core())).unwrap();
+    assert_eq!(json["skippedUnits"][0]["kind"], "jest-project");
+    assert_eq!(json["skippedUnits"][1]["kind"], "cargo-package");
+    let read: TestRunModel = serde_json::from_value(json).unwrap();
+    assert_eq!(read.skipped_units, web_and_core());
+
+    let none = serde_json::to_value(empty_model(vec![])).unwrap();
+    assert!(none.get("skippedUnits").is_none());
+    let read: TestRunModel = serde_json::from_value(none).unwrap();
+    assert!(read.skipped_units.is_empty());
+}
+
+#[test]
+fn skipped_projects_section_is_rendered_only_when_asked_for() {
+    let repo = std::path::PathBuf::from("/repo");
+    let model = empty_model(web_and_core());
+    let mut ctx = make_ctx(&repo, Some(80), false, false, None);
+    let hidden = strip_ansi_simple(&render_vitest_from_test_model(&model, &ctx, false));
+    assert!(!hidden.contains("Skipped Projects"));
+
+    ctx.show_skipped_projects = true;
+    let shown = strip_ansi_simple(&render_vitest_from_test_model(&model, &ctx, false));
+    assert!(shown.contains(" Skipped Projects (no matching tests) 2 "));
+    assert!(shown.contains("↓ packages/web/jest.config.js [jest project]"));
+    assert!(shown.contains("↓ core [cargo package]"));
+
+    let nothing_skipped = empty_model(vec![]);
+    let plain = strip_ansi_simple(&render_vitest_from_test_model(
+        &nothing_skipped,
+        &ctx,
+        false,
+    ));
+    assert!(!plain.contains("Skipped Projects"));
+}
+
+#[test]
+fn show_skipped_projects_comes_from_the_flag_or_config() {
+    let argv = vec!["--showSkippedProjects".to_string()];
+    let parsed = derive_args(
+        &config_tokens(&HeadlampConfig::default(), &argv),
+        &argv,
+        true,
+    );
+    assert!(parsed.show_skipped_projects);
+
+    let cfg = HeadlampConfig {
+        show_skipped_projects: Some(true),
+        ..HeadlampConfig::default()
+    };
+    let parsed = derive_args(&config_tokens(&cfg, &[]), &[], true);
+    assert!(parsed.show_skipped_projects);
+    let parsed = derive_args(&config_tokens(&HeadlampConfig::default(), &[]), &[], true);
+    assert!(!parsed.show_skipped_projects);
+}
@@ -0,0 +1,99 @@
+use headlamp::format::source_map::{SourceMap, remap_stack_line};
+use headlamp::format::stacks::{clean_stacks, strip_ansi_simple};
+
+fn write_generated_with_sibling_map(dir: &std::path::Path) -> std::path::PathBuf {
+    let dist = dir.join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(dir.join("src").join("a.ts"), "a\nb\nc\n").unwrap();
+    let generated = dist.join("a.js");
+    std::fs::write(&generated, "x;\ny;\n").unwrap();
+    std::fs::write(
+        dist.join("a.js.map"),
+        r#"{"version":3,"sources":["../src/a.ts"],"names":[],"mappings":"AAAA;AAEE"}"#,
+    )
+    .unwrap();
+    generated
+}
+
+#[test]
+fn source_map_lookup_maps_generated_position_to_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let map = SourceMap::parse(
+        r#"{"version":3,"sources":["a.ts"],"mappings":"AAAA;AAEE"}"#,
+        dir.path(),
+    )
+    .unwrap();
+    let loc = map.lookup(2, 1).unwrap();
+    assert!(loc.source.ends_with("a.ts"));
+    assert_eq!((loc.line, loc.column), (3, 3));
+}
+
+#[test]
+fn remap_stack_line_uses_sibling_map_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let generated = write_generated_with_sibling_map(dir.path());
+    let frame = format!("    at run ({}:2:1)", generated.to_string_lossy());
+    let remapped = remap_stack_line(&frame);
+    assert!(remapped.starts_with("    at run ("), "{remapped}");
+    assert!(remapped.contains("a.ts:3:3)"), "{remapped}");
+}
+
+#[test]
+fn remap_stack_line_decodes_inline_data_url() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("b.ts"), "a\n").unwrap();
+    let generated = dir.path().join("b.js");
+    // {"version":3,"sources":["b.ts"],"mappings":"AAAA"}
+    let inline = "eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImIudHMiXSwibWFwcGluZ3MiOiJBQUFBIn0=";
+    std::fs::write(
+        &generated,
+        format!("x;\n//# sourceMappingURL=data:application/json;base64,{inline}\n"),
+    )
+    .unwrap();
+    let frame = format!("    at {}:1:1", generated.to_string_lossy());
+    assert!(remap_stack_line(&frame).contains("b.ts:1:1"));
+}
+
+#[test]
+fn clean_stacks_collapses_vendor_frames_with_count() {
+    let lines = vec![
+        "Error: boom".to_string(),
+        "    at myFn (/repo/src/main.js:10:2)".to_string(),
+        "    at __webpack_require__ (webpack/bootstrap:19:1)".to_string(),
+        "    at step (/repo/node_modules/regenerator-runtime/runtime.js:1:2)".to_string(),
+        "    at processTicksAndRejections (node:internal/process/task_queues:95:5)".to_string(),
+    ];
+    let plain = clean_stacks(&lines, false)
+        .iter()
+        .map(|line| strip_ansi_simple(line))
+        .collect::<Vec<_>>();
+    assert_eq!(plain.len(), 3);
+    assert!(plain[2].contains("3 stack frames hidden"));
+}
+
+#[test]
+fn clean_stacks_full_stacks_keeps_every_frame() {
+    let lines = vec![
+        "Error: boom".to_string(),
+        "    at Object.<anonymous> (/repo/node_modules/pkg/index.js:1:2)".to_string(),
+        "    at node:internal/process/task_queues:105:5".to_string(),
+    ];
+    assert_eq!(clean_stacks(&lines, true), lines);
+}
+
+#[test]
+fn clean_stacks_keeps_the_styling_of_remapped_frames() {
+    let dir = tempfile::tempdir().unwrap();
+    let generated = write_generated_with_sibling_map(dir.path());
+    let styled = format!(
+        "\u{1b}[2m    at run ({}:2:1)\u{1b}[22m",
+        generated.to_string_lossy()
+    );
+    let cleaned = clean_stacks(&[styled], false);
+    assert!(
+        cleaned[0].starts_with("\u{1b}[2m    at run ("),
+        "{cleaned:?}"
+    );
+    assert!(cleaned[0].ends_with("a.ts:3:3)\u{1b}[22m"), "{cleaned:?}");
+}
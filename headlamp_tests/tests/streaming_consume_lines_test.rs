@@ -73,3 +73,70 @@ fn consume_lines_capture_tail_does_not_hang_on_empty_input() {
         .expect("timed out waiting for consume_lines_capture_tail to finish");
     assert_eq!(line_count, 0);
 }
+
+#[test]
+fn consume_lines_capture_tail_keeps_invalid_utf8_as_escapes_and_coalesces_carriage_returns() {
+    let input = b"ok \xff\xfe bytes\nprogress 10%\rprogress 55%\rprogress 100%\r\nafter\n";
+    let reader = Cursor::new(input.as_slice());
+    let progress = LiveProgress::start(1, LiveProgressMode::Off);
+    let mut adapter = CaptureAdapter::default();
+
+    headlamp::streaming::consume_lines_capture_tail(reader, &progress, &mut adapter, 1024);
+    progress.finish();
+
+    assert_eq!(
+        adapter.lines,
+        vec!["ok \\xff\\xfe bytes", "progress 100%", "after"]
+    );
+}
+
+#[test]
+fn raw_lines_split_very_long_lines_without_breaking_utf8() {
+    let long = format!("{}é{}\nnext\n", "a".repeat(9), "b".repeat(6));
+    let lines =
+        headlamp::streaming::RawLines::with_max_line_bytes(Cursor::new(long.into_bytes()), 10)
+            .collect::<Vec<_>>();
+    assert_eq!(lines, vec!["aaaaaaaaa", "ébbbbbb", "next"]);
+
+    let exact = headlamp::streaming::RawLines::with_max_line_bytes(
+        Cursor::new(b"0123456789\nz".to_vec()),
+        10,
+    )
+    .collect::<Vec<_>>();
+    assert_eq!(exact, vec!["0123456789", "z"]);
+}
+
+#[test]
+fn consume_lines_capture_tail_keeps_head_and_tail_and_writes_the_full_log() {
+    let input = (1..=20)
+        .map(|index| format!("line {index:02}\n"))
+        .collect::<String>();
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("logs").join("rust-cargo-test.log");
+    let progress = LiveProgress::start(1, LiveProgressMode::Off);
+    let mut adapter = CaptureAdapter::default();
+
+    let ring = headlamp::streaming::consume_lines_capture_tail(
+        Cursor::new(input.clone().into_bytes()),
+        &progress,
+        &mut adapter,
+        headlamp::streaming::RingBuffer::with_head(14, 21).with_full_log(&log_path),
+    );
+    progress.finish();
+
+    assert_eq!(ring.head_lines(), ["line 01", "line 02"]);
+    assert_eq!(ring.omitted_lines(), 15);
+    assert_eq!(
+        ring.excerpt(),
+        vec![
+            "line 01",
+            "line 02",
+            "... 15 lines omitted ...",
+            "line 18",
+            "line 19",
+            "line 20",
+        ]
+    );
+    assert_eq!(ring.full_log_path(), Some(log_path.as_path()));
+    assert_eq!(std::fs::read_to_string(&log_path).unwrap(), input);
+}
@@ -101,3 +101,30 @@ exit 0
     assert!(texts.iter().any(|l| l == "FIRST"));
     assert!(texts.iter().any(|l| l == "LATE"));
 }
+
+#[cfg(unix)]
+#[test]
+fn event_pipe_lines_reach_adapter_as_events_stream() {
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        "echo out; echo \"{\\\"fd\\\":$HEADLAMP_EVENTS_FD}\" >&\"$HEADLAMP_EVENTS_FD\"",
+    ]);
+    let progress = LiveProgress::start(1, LiveProgressMode::Off);
+    let mut adapter = CaptureAdapter::default();
+    let (code, ring) = headlamp::streaming::run_streaming_capture_tail_with_events(
+        cmd,
+        &progress,
+        &mut adapter,
+        1024 * 1024,
+    )
+    .expect("run_streaming_capture_tail_with_events");
+    progress.finish();
+    assert_eq!(code, 0);
+    assert!(
+        adapter
+            .lines
+            .contains(&(OutputStream::Events, "{\"fd\":3}".to_string()))
+    );
+    assert!(ring.lines().all(|line| line == "out"));
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use headlamp::args::derive_args;
+use headlamp::format::ctx::make_ctx;
+use headlamp::format::stacks::strip_ansi_simple;
+use headlamp::format::suite_log;
+use headlamp::format::vitest::render_vitest_from_test_model;
+use headlamp::test_model::{TestConsoleEntry, TestSuiteResult};
+use headlamp::test_support::model::{self, case, failed_case, run_model};
+
+fn suite(path: &Path, status: &str, console: Option<Vec<TestConsoleEntry>>) -> TestSuiteResult {
+    let case = match status {
+        "failed" => failed_case("t", "Error: nope"),
+        _ => case("t", status),
+    };
+    TestSuiteResult {
+        console,
+        ..model::suite(&path.to_string_lossy(), vec![case])
+    }
+}
+
+fn console() -> Option<Vec<TestConsoleEntry>> {
+    let entry = |message: &str, type_name: &str, origin: Option<&str>| TestConsoleEntry {
+        message: Some(serde_json::Value::String(message.to_string())),
+        type_name: Some(type_name.to_string()),
+        origin: origin.map(str::to_string),
+    };
+    Some(vec![
+        entry("loading user 7", "log", Some("src/api/user.ts:12")),
+        entry("db timeout", "error", None),
+    ])
+}
+
+#[test]
+fn failing_suites_with_console_output_get_a_log_file() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(repo.path()).unwrap();
+    let data = run_model(vec![
+        suite(&root.join("src/api/user.test.ts"), "failed", console()),
+        suite(&root.join("src/api/ok.test.ts"), "passed", console()),
+        suite(&root.join("src/quiet.test.ts"), "failed", None),
+    ]);
+    let out_dir = root.join("out");
+
+    let written = suite_log::write_suite_logs(&out_dir, &root, std::slice::from_ref(&data));
+
+    assert_eq!(written, vec![out_dir.join("src__api__user.test.ts.log")]);
+    let contents = std::fs::read_to_string(&written[0]).unwrap();
+    assert!(contents.contains("log: loading user 7 (src/api/user.ts:12)"));
+    assert!(contents.contains("error: db timeout"));
+}
+
+#[test]
+fn failure_block_points_at_the_suite_log() {
+    let repo = tempfile::tempdir().unwrap();
+    let root = dunce::canonicalize(repo.path()).unwrap();
+    let data = run_model(vec![
+        suite(&root.join("src/api/user.test.ts"), "failed", console()),
+        suite(&root.join("src/quiet.test.ts"), "failed", None),
+    ]);
+
+    let mut ctx = make_ctx(&root, Some(100), true, false, None);
+    let plain = strip_ansi_simple(&render_vitest_from_test_model(&data, &ctx, false));
+    assert!(!plain.contains("logs:"));
+
+    ctx.suite_log_dir = Some(".headlamp/run-123/logs/suites".to_string());
+    let out = strip_ansi_simple(&render_vitest_from_test_model(&data, &ctx, false));
+    let log_lines = out
+        .lines()
+        .filter(|line| line.trim_start().starts_with("logs:"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        log_lines,
+        vec!["    logs: .headlamp/run-123/logs/suites/src__api__user.test.ts.log"]
+    );
+}
+
+#[test]
+fn shown_log_dir_follows_artifacts_dir() {
+    let argv = vec!["--artifacts-dir=.headlamp/run-123".to_string()];
+    let parsed = derive_args(&[], &argv, true);
+    assert_eq!(
+        suite_log::shown_suite_logs_dir(&parsed),
+        ".headlamp/run-123/logs/suites"
+    );
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use headlamp::format::vitest::summary_line;
+use headlamp::recorded::RecordedResults;
+use headlamp::run::apply_fail_on_skipped;
+
+fn results(coverage_lines: Option<(u64, u64)>) -> RecordedResults {
+    RecordedResults {
+        passed: 179,
+        failed: 3,
+        skipped: 2,
+        failed_tests: vec![],
+        coverage_lines,
+        duplicates: vec![],
+    }
+}
+
+#[test]
+fn summary_line_reports_counts_duration_and_line_coverage() {
+    let line = summary_line(
+        &results(Some((812, 1000))),
+        1,
+        Duration::from_millis(93_420),
+    );
+    assert_eq!(
+        line,
+        "headlamp: result=fail tests=184 failed=3 skipped=2 duration=93.4s coverage_lines=81.2%"
+    );
+}
+
+#[test]
+fn summary_line_omits_coverage_when_none_was_collected() {
+    let line = summary_line(&results(None), 0, Duration::from_millis(1_250));
+    assert_eq!(
+        line,
+        "headlamp: result=pass tests=184 failed=3 skipped=2 duration=1.2s"
+    );
+}
+
+#[test]
+fn fail_on_skipped_fails_only_passing_runs_that_skipped_tests() {
+    let enabled = headlamp::args::derive_args(&[], &["--fail-on-skipped".to_string()], false);
+    let disabled = headlamp::args::ParsedArgs::default();
+    let none_skipped = RecordedResults {
+        skipped: 0,
+        ..results(None)
+    };
+    assert_eq!(apply_fail_on_skipped(&enabled, &results(None), 0), 1);
+    assert_eq!(apply_fail_on_skipped(&enabled, &results(None), 2), 2);
+    assert_eq!(apply_fail_on_skipped(&enabled, &none_skipped, 0), 0);
+    assert_eq!(apply_fail_on_skipped(&disabled, &results(None), 0), 0);
+}
@@ -0,0 +1,21 @@
+use headlamp::tool_install::{InstallRecipe, is_yes};
+
+#[test]
+fn install_recipes_use_the_documented_commands() {
+    assert_eq!(
+        InstallRecipe::cargo_nextest().command_line(),
+        "cargo install cargo-nextest --locked"
+    );
+    assert_eq!(
+        InstallRecipe::llvm_tools("nightly").command_line(),
+        "rustup component add llvm-tools-preview --toolchain nightly"
+    );
+}
+
+#[test]
+fn install_prompt_only_accepts_an_explicit_yes() {
+    assert!(is_yes("y\n"));
+    assert!(is_yes(" YES "));
+    assert!(!is_yes("\n"));
+    assert!(!is_yes("no"));
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::{HeadlampConfig, Typecheck};
+use headlamp::typecheck::{build_typecheck_model, parse_tsc_output, tsconfigs_for_paths};
+
+#[test]
+fn typecheck_is_off_by_default_and_reads_cli_and_config() {
+    let cfg = HeadlampConfig::default();
+    let parse = |argv: &[&str], cfg: &HeadlampConfig| {
+        let argv = argv.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        derive_args(&config_tokens(cfg, &argv), &argv, false)
+    };
+    assert_eq!(parse(&[], &cfg).typecheck, Typecheck::Off);
+
+    let parsed = parse(&["--typecheck", "src/a.test.ts"], &cfg);
+    assert_eq!(parsed.typecheck, Typecheck::Gate);
+    assert_eq!(parsed.selection_paths, vec!["src/a.test.ts"]);
+    assert_eq!(
+        parse(&["--typecheck=warn"], &cfg).typecheck,
+        Typecheck::Warn
+    );
+
+    let cfg: HeadlampConfig = serde_json::from_str(
+        r#"{ "typecheck": "warn", "typecheckCommand": "pnpm exec vue-tsc --noEmit" }"#,
+    )
+    .unwrap();
+    let parsed = parse(&[], &cfg);
+    assert_eq!(parsed.typecheck, Typecheck::Warn);
+    assert_eq!(
+        parsed.typecheck_command.as_deref(),
+        Some("pnpm exec vue-tsc --noEmit")
+    );
+}
+
+#[test]
+fn tsc_output_is_parsed_with_continuations_and_global_errors() {
+    let output = [
+        "src/math.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+        "src/user.ts(10,1): error TS2345: Argument of type '{ id: string; }' is not assignable.",
+        "  Types of property 'id' are incompatible.",
+        "error TS5058: The specified path does not exist: 'missing.json'.",
+        "Found 3 errors in 2 files.",
+    ]
+    .join("\n");
+    let diagnostics = parse_tsc_output(
+        &output,
+        Path::new("/repo/web"),
+        Path::new("/repo/web/tsconfig.json"),
+    );
+
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].file, "/repo/web/src/math.ts");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 7));
+    assert_eq!(diagnostics[0].code, "TS2322");
+    assert_eq!(
+        diagnostics[1].message,
+        "Argument of type '{ id: string; }' is not assignable.\nTypes of property 'id' are incompatible."
+    );
+    assert_eq!(diagnostics[2].file, "/repo/web/tsconfig.json");
+    assert_eq!(diagnostics[2].code, "TS5058");
+}
+
+#[test]
+fn each_path_maps_to_its_nearest_tsconfig_within_the_repo() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    std::fs::create_dir_all(root.join("packages/api/src")).unwrap();
+    std::fs::create_dir_all(root.join("packages/web/src")).unwrap();
+    std::fs::write(root.join("tsconfig.json"), "{}").unwrap();
+    std::fs::write(root.join("packages/api/tsconfig.json"), "{}").unwrap();
+    let paths = [
+        "packages/api/src/a.test.ts",
+        "packages/api/src/b.ts",
+        "packages/web/src/c.test.ts",
+    ]
+    .map(|path| root.join(path));
+
+    assert_eq!(
+        tsconfigs_for_paths(root, &paths),
+        vec![
+            root.join("packages/api/tsconfig.json"),
+            root.join("tsconfig.json")
+        ]
+    );
+    assert!(
+        tsconfigs_for_paths(&root.join("packages/web"), std::slice::from_ref(&paths[2])).is_empty()
+    );
+}
+
+#[test]
+fn type_errors_become_one_failed_suite_per_file() {
+    let output = [
+        "src/a.ts(1,1): error TS1005: ';' expected.",
+        "src/b.ts(2,3): error TS2304: Cannot find name 'foo'.",
+        "src/a.ts(4,2): error TS2304: Cannot find name 'bar'.",
+    ]
+    .join("\n");
+    let diagnostics = parse_tsc_output(
+        &output,
+        Path::new("/repo"),
+        Path::new("/repo/tsconfig.json"),
+    );
+    let model = build_typecheck_model(&diagnostics);
+
+    assert_eq!(model.aggregated.num_failed_test_suites, 2);
+    assert_eq!(model.aggregated.num_failed_tests, 3);
+    assert!(!model.aggregated.success);
+    let suite = &model.test_results[0];
+    assert_eq!(suite.test_file_path, "/repo/src/a.ts");
+    assert_eq!(suite.test_results[1].title, "TS2304 (4:2)");
+    assert_eq!(
+        suite.test_results[1].failure_messages[0],
+        "Cannot find name 'bar'.\n    at /repo/src/a.ts:4:2"
+    );
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+use std::process::Command;
+
+use headlamp::args::{config_tokens, derive_args};
+use headlamp::config::HeadlampConfig;
+use headlamp::untested_changes::{
+    render_untested_changes, report_untested_changes, untested_changed_files,
+};
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn strings(tokens: &[&str]) -> Vec<String> {
+    tokens.iter().map(|token| token.to_string()).collect()
+}
+
+fn js_repo() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write_file(&root.join("package.json"), r#"{ "name": "untested" }"#);
+    write_file(&root.join("src/a.ts"), "export const a = 1;\n");
+    write_file(&root.join("src/b.ts"), "export const b = 2;\n");
+    write_file(
+        &root.join("src/a.test.ts"),
+        "import { a } from './a';\ntest('a', () => expect(a).toBe(1));\n",
+    );
+    write_file(&root.join("README.md"), "# untested\n");
+    dir
+}
+
+#[test]
+fn only_changed_source_files_without_related_tests_are_reported() {
+    let dir = js_repo();
+    let root = dir.path().canonicalize().unwrap();
+    let changed = [
+        "src/a.ts",
+        "src/b.ts",
+        "src/a.test.ts",
+        "README.md",
+        "src/gone.ts",
+    ]
+    .map(|rel| root.join(rel));
+
+    assert_eq!(untested_changed_files(&root, &changed), vec!["src/b.ts"]);
+}
+
+#[test]
+fn the_section_lists_each_untested_file() {
+    assert!(render_untested_changes(&[]).is_empty());
+    let lines = render_untested_changes(&strings(&["src/b.ts", "src/c.ts"]))
+        .iter()
+        .map(|line| headlamp::format::stacks::strip_ansi_simple(line))
+        .collect::<Vec<_>>();
+    assert!(lines[0].starts_with("Changed but untested"), "{lines:?}");
+    assert_eq!(lines[1..], ["  • src/b.ts", "  • src/c.ts"]);
+}
+
+#[test]
+fn untested_changes_fail_the_run_only_when_asked() {
+    if which::which("git").is_err() {
+        return;
+    }
+    let dir = js_repo();
+    let root = dir.path().canonicalize().unwrap();
+    let init = Command::new("git")
+        .current_dir(&root)
+        .args(["init", "-q"])
+        .status();
+    assert!(init.is_ok_and(|status| status.success()));
+
+    let strict = derive_args(
+        &[],
+        &strings(&["--changed=all", "--fail-on-untested-changes"]),
+        false,
+    );
+    let lenient = derive_args(&[], &strings(&["--changed=all"]), false);
+    let not_changed = derive_args(&[], &strings(&["--fail-on-untested-changes"]), false);
+    assert_eq!(report_untested_changes(&root, &strict, 0), 1);
+    assert_eq!(report_untested_changes(&root, &strict, 3), 3);
+    assert_eq!(report_untested_changes(&root, &lenient, 0), 0);
+    assert_eq!(report_untested_changes(&root, &not_changed, 0), 0);
+}
+
+#[test]
+fn fail_on_untested_changes_comes_from_cli_and_config() {
+    let cfg: HeadlampConfig = serde_json::from_str(r#"{ "failOnUntestedChanges": true }"#).unwrap();
+    assert!(derive_args(&config_tokens(&cfg, &[]), &[], false).fail_on_untested_changes);
+    let argv = strings(&["--failOnUntestedChanges"]);
+    assert!(derive_args(&[], &argv, false).fail_on_untested_changes);
+    assert!(!derive_args(&[], &[], false).fail_on_untested_changes);
+}
@@ -20,6 +20,9 @@ fn mk_assertion(
         location: None,
         failure_messages,
         failure_details: None,
+        repro: None,
+        skip_reason: None,
+        attempts: None,
     }
 }
 
@@ -92,6 +95,7 @@ fn sample_bridge() -> BridgeJson {
             success: false,
             run_time_ms: Some(1500),
         },
+        skipped_units: vec![],
     }
 }
 
@@ -130,3 +134,26 @@ fn render_vitest_ignores_empty_test_suites() {
         .expect("missing Test Files footer line");
     assert!(test_files_line.contains("(2)"));
 }
+
+#[test]
+fn render_vitest_lists_skipped_tests_with_reasons_when_asked() {
+    let repo = std::path::PathBuf::from("/repo");
+    let mut bridge = sample_bridge();
+    let mut skipped = mk_assertion("slow", "suite slow", "pending", 0, vec![]);
+    skipped.skip_reason = Some("needs network access".to_string());
+    bridge.test_results[0].test_results.extend([
+        skipped,
+        mk_assertion("later", "suite later", "todo", 0, vec![]),
+    ]);
+
+    let mut ctx = make_ctx(&repo, Some(80), true, false, None);
+    let hidden = render_vitest_from_test_model(&bridge, &ctx, false);
+    assert!(!headlamp::format::stacks::strip_ansi_simple(&hidden).contains("Skipped Tests"));
+
+    ctx.show_skipped = true;
+    let out = render_vitest_from_test_model(&bridge, &ctx, false);
+    let simple = headlamp::format::stacks::strip_ansi_simple(&out);
+    assert!(simple.contains(" Skipped Tests 2 "));
+    assert!(simple.contains("↓ tests/pass.test.js > suite slow [skipped] — needs network access"));
+    assert!(simple.contains("☐ tests/pass.test.js > suite later [todo]"));
+}
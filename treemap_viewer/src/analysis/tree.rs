@@ -196,9 +196,11 @@ fn finalize_root_children(root_children: &mut [TreemapNode]) {
             file_node.bytes = file_node.children.iter().map(|n| n.bytes).sum();
         }
         crate_node.bytes = crate_node.children.iter().map(|n| n.bytes).sum();
-        crate_node.children.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+        crate_node
+            .children
+            .sort_by_key(|node| std::cmp::Reverse(node.bytes));
     }
-    root_children.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    root_children.sort_by_key(|node| std::cmp::Reverse(node.bytes));
 }
 
 fn flush_function_into_file(
@@ -237,7 +239,9 @@ fn flush_file_into_crate(
     let Some(mut file_node) = current_file.take() else {
         return;
     };
-    file_node.children.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    file_node
+        .children
+        .sort_by_key(|node| std::cmp::Reverse(node.bytes));
     file_node.bytes = file_node.children.iter().map(|n| n.bytes).sum();
     if file_node.bytes == 0 {
         return;
@@ -252,7 +256,9 @@ fn flush_crate_into_root(
     let Some(mut crate_node) = current_crate.take() else {
         return;
     };
-    crate_node.children.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    crate_node
+        .children
+        .sort_by_key(|node| std::cmp::Reverse(node.bytes));
     crate_node.bytes = crate_node.children.iter().map(|n| n.bytes).sum();
     if crate_node.bytes == 0 {
         return;
@@ -43,7 +43,7 @@ pub fn parse_map_file(map_path: &Path) -> anyhow::Result<SizeReport> {
         .into_iter()
         .map(|(crate_name, bytes)| CrateSizeRow { crate_name, bytes })
         .collect::<Vec<_>>();
-    crate_sizes.sort_by(|left, right| right.bytes.cmp(&left.bytes));
+    crate_sizes.sort_by_key(|node| std::cmp::Reverse(node.bytes));
 
     Ok(SizeReport { crate_sizes })
 }
@@ -31,7 +31,7 @@ pub fn build_treemap(symbols: &[SymbolWithLocation]) -> TreemapNode {
         .into_iter()
         .map(|(crate_name, entries)| build_crate_node(&crate_name, &entries))
         .collect::<Vec<_>>();
-    crate_nodes.sort_by(|left, right| right.bytes.cmp(&left.bytes));
+    crate_nodes.sort_by_key(|node| std::cmp::Reverse(node.bytes));
 
     let total_bytes = crate_nodes.iter().map(|node| node.bytes).sum::<u64>();
     TreemapNode {
@@ -56,7 +56,7 @@ fn build_crate_node(crate_name: &str, entries: &[SymbolWithLocation]) -> Treemap
         .into_iter()
         .map(|(file_path, file_entries)| build_file_node(&file_path, &file_entries))
         .collect::<Vec<_>>();
-    file_nodes.sort_by(|left, right| right.bytes.cmp(&left.bytes));
+    file_nodes.sort_by_key(|node| std::cmp::Reverse(node.bytes));
 
     let total_bytes = file_nodes.iter().map(|node| node.bytes).sum::<u64>();
     TreemapNode {
@@ -84,7 +84,7 @@ fn build_file_node(file_path: &str, entries: &[&SymbolWithLocation]) -> TreemapN
             children: Vec::new(),
         })
         .collect::<Vec<_>>();
-    function_nodes.sort_by(|left, right| right.bytes.cmp(&left.bytes));
+    function_nodes.sort_by_key(|node| std::cmp::Reverse(node.bytes));
 
     let total_bytes = function_nodes.iter().map(|node| node.bytes).sum::<u64>();
     TreemapNode {